@@ -0,0 +1,221 @@
+//! Count the allocations in the phases of every frame, to catch the
+//! allocations that a frame shouldn't make, see
+//! [`assert_steady_state_allocs!`](crate::assert_steady_state_allocs).
+//!
+//! With the `ribir_alloc_track` feature, the counts of the last frame of a
+//! window are in its [`FrameStats::allocs`](crate::window::FrameStats::allocs),
+//! if [`TrackingAllocator`] is the global allocator of the test or the app:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static GLOBAL: ribir_core::alloc_track::TrackingAllocator =
+//!   ribir_core::alloc_track::TrackingAllocator;
+//! ```
+//!
+//! Nothing is counted without the feature.
+
+/// The allocations made in a period.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocCount {
+  /// How many times the memory is allocated or reallocated.
+  pub count: usize,
+  /// The bytes allocated or reallocated.
+  pub bytes: usize,
+}
+
+/// Run `f` and count the allocations it made in the current thread, the count
+/// is always zero without the `ribir_alloc_track` feature.
+pub fn count_allocs<R>(f: impl FnOnce() -> R) -> (R, AllocCount) {
+  #[cfg(feature = "ribir_alloc_track")]
+  {
+    let start = tracking::current();
+    let r = f();
+    (r, tracking::current().since(start))
+  }
+  #[cfg(not(feature = "ribir_alloc_track"))]
+  (f(), AllocCount::default())
+}
+
+/// The allocations made in the phases of a frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FrameAllocs {
+  /// Running the tasks and the ticks of the frame before the layout, all the
+  /// frame if it's not painted.
+  pub tasks: AllocCount,
+  /// Laying out the widgets.
+  pub layout: AllocCount,
+  /// Painting the widgets.
+  pub paint: AllocCount,
+  /// Presenting the frame and finishing it.
+  pub present: AllocCount,
+}
+
+#[cfg(feature = "ribir_alloc_track")]
+impl AllocCount {
+  fn since(self, start: AllocCount) -> AllocCount {
+    AllocCount { count: self.count - start.count, bytes: self.bytes - start.bytes }
+  }
+}
+
+impl FrameAllocs {
+  /// The allocations of the whole frame.
+  pub fn total(&self) -> AllocCount {
+    [self.tasks, self.layout, self.paint, self.present]
+      .into_iter()
+      .fold(AllocCount::default(), |sum, c| AllocCount {
+        count: sum.count + c.count,
+        bytes: sum.bytes + c.bytes,
+      })
+  }
+}
+
+/// The phases of a frame that [`FrameAllocs`] counts.
+#[derive(Clone, Copy)]
+pub(crate) enum FramePhase {
+  Tasks,
+  Layout,
+  Paint,
+  Present,
+}
+
+/// Record the allocations of a frame phase by phase, it's empty without the
+/// `ribir_alloc_track` feature.
+pub(crate) struct FrameAllocRecorder {
+  #[cfg(feature = "ribir_alloc_track")]
+  last: AllocCount,
+  #[cfg(feature = "ribir_alloc_track")]
+  allocs: FrameAllocs,
+}
+
+impl FrameAllocRecorder {
+  #[inline]
+  pub(crate) fn start() -> Self {
+    #[cfg(feature = "ribir_alloc_track")]
+    return Self { last: tracking::current(), allocs: <_>::default() };
+    #[cfg(not(feature = "ribir_alloc_track"))]
+    Self {}
+  }
+
+  /// End the `phase`, the allocations since the last phase are counted in it.
+  #[inline]
+  #[allow(unused_variables)]
+  pub(crate) fn end(&mut self, phase: FramePhase) {
+    #[cfg(feature = "ribir_alloc_track")]
+    {
+      let now = tracking::current();
+      let count = match phase {
+        FramePhase::Tasks => &mut self.allocs.tasks,
+        FramePhase::Layout => &mut self.allocs.layout,
+        FramePhase::Paint => &mut self.allocs.paint,
+        FramePhase::Present => &mut self.allocs.present,
+      };
+      let delta = now.since(self.last);
+      count.count += delta.count;
+      count.bytes += delta.bytes;
+      self.last = now;
+    }
+  }
+
+  #[cfg(feature = "ribir_alloc_track")]
+  pub(crate) fn finish(self) -> FrameAllocs { self.allocs }
+}
+
+#[cfg(feature = "ribir_alloc_track")]
+pub use tracking::TrackingAllocator;
+
+#[cfg(feature = "ribir_alloc_track")]
+mod tracking {
+  use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+  };
+
+  use super::AllocCount;
+
+  thread_local! {
+    /// Every thread counts its own allocations, so the frames of a thread are
+    /// not disturbed by the others.
+    static ALLOCATED: Cell<AllocCount> = const { Cell::new(AllocCount { count: 0, bytes: 0 }) };
+  }
+
+  /// The allocator that counts the allocations of every thread, and allocates
+  /// by the system allocator.
+  pub struct TrackingAllocator;
+
+  pub(super) fn current() -> AllocCount { ALLOCATED.try_with(Cell::get).unwrap_or_default() }
+
+  fn count(bytes: usize) {
+    let _ = ALLOCATED.try_with(|c| {
+      let AllocCount { count, bytes: total } = c.get();
+      c.set(AllocCount { count: count + 1, bytes: total + bytes });
+    });
+  }
+
+  unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+      count(layout.size());
+      unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+      count(layout.size());
+      unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+      unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+      count(new_size);
+      unsafe { System.realloc(ptr, layout, new_size) }
+    }
+  }
+}
+
+#[cfg(all(test, feature = "ribir_alloc_track"))]
+mod tests {
+  use super::TrackingAllocator;
+  use crate::{assert_steady_state_allocs, prelude::*, reset_test_env, test_helper::*};
+
+  #[global_allocator]
+  static GLOBAL: TrackingAllocator = TrackingAllocator;
+
+  #[test]
+  fn static_layout() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @ {
+          (0..10).map(|i| @MockBox { size: Size::new(10. * i as f32, 10.) })
+        }
+      }
+    });
+    // The idle frames are skipped, they don't allocate.
+    assert_steady_state_allocs!(wnd, 0);
+  }
+
+  #[test]
+  fn animate_opacity() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      let mut mock_box = @MockBox { size: Size::new(100., 100.) };
+      let animate = @Animate {
+        transition: EasingTransition {
+          duration: Duration::from_millis(100),
+          easing: easing::LINEAR,
+        }
+        .repeat(f32::INFINITY)
+        .box_it(),
+        state: part_writer!(&mut mock_box.opacity),
+        from: 0.,
+      };
+      animate.run();
+      mock_box
+    });
+    // A frame of the animation allocates for its commands and the layout.
+    assert_steady_state_allocs!(wnd, 32);
+  }
+}
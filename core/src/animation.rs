@@ -14,6 +14,8 @@ mod stagger;
 pub use stagger::Stagger;
 mod keyframes;
 pub use keyframes::*;
+mod flip;
+pub use flip::FlipAnimation;
 
 ///  Trait to describe how to control the animation.
 pub trait Animation {
@@ -13,6 +13,10 @@ where
   #[declare(strict)]
   pub state: S,
   pub from: S::Value,
+  /// If the animation jumps to its final value when the application prefers
+  /// reduced motion, see [`AppCtx::reduced_motion`].
+  #[declare(default = true)]
+  pub respect_reduced_motion: bool,
   #[declare(skip)]
   running_info: Option<AnimateInfo<S::Value>>,
   #[declare(skip, default = BuildCtx::get().window().id())]
@@ -45,6 +49,17 @@ where
       return;
     }
 
+    if this.respect_reduced_motion && AppCtx::reduced_motion() {
+      // The state already holds the final value, unless the animation is
+      // running.
+      if let Some(info) = this.running_info.as_ref() {
+        this.state.revert_value(info.to.clone());
+      }
+      drop(animate_ref);
+      self.stop();
+      return;
+    }
+
     let new_to = this.state.get();
 
     if let Some(AnimateInfo { from, to, last_progress, start_at, .. }) = &mut this.running_info {
@@ -53,7 +68,7 @@ where
         .calc_lerp_value(from, to, last_progress.value());
       *to = new_to;
       *last_progress = AnimateProgress::Between(0.);
-      *start_at = Instant::now();
+      *start_at = clock::now();
     } else {
       drop(animate_ref);
 
@@ -109,7 +124,7 @@ where
       this.running_info = Some(AnimateInfo {
         from: this.from.clone(),
         to: new_to,
-        start_at: Instant::now(),
+        start_at: clock::now(),
         last_progress: AnimateProgress::Dismissed,
         _tick_msg_guard: Some(Box::new((tick_handle, state_handle))),
         already_lerp: false,
@@ -235,4 +250,97 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(*c_state.read(), 1);
   }
+
+  fn linear_animate(
+    state: Stateful<f32>, respect_reduced_motion: bool,
+  ) -> (TestWindow, State<Animate<Stateful<f32>>>) {
+    let animate = Stateful::new(None);
+    let c_animate = animate.clone_writer();
+    let w = fn_widget! {
+      let a = @Animate {
+        transition: EasingTransition {
+          easing: easing::LINEAR,
+          duration: Duration::from_millis(500),
+        }.box_it(),
+        state: state.clone_writer(),
+        from: 0.,
+        respect_reduced_motion,
+      };
+      *$c_animate.write() = Some(a);
+      @Void {}
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.set_flags(wnd.flags() | WindowFlags::ANIMATIONS);
+    wnd.draw_frame();
+    let animate = animate.write().take().unwrap();
+    (wnd, animate)
+  }
+
+  /// Run the animation by the frame interval, return the value of every frame
+  /// and the time the animation reached its final value.
+  fn run_at_rate(frame: Duration) -> (Vec<(Duration, f32)>, Duration) {
+    let state = Stateful::new(100.);
+    let (mut wnd, animate) = linear_animate(state.clone_writer(), true);
+
+    let start = clock::now();
+    let values = Stateful::new(vec![]);
+    let c_values = values.clone_writer();
+    let _h = wnd
+      .frame_tick_stream()
+      .filter(|msg| matches!(msg, FrameMsg::LayoutReady(_)))
+      .subscribe(move |_| {
+        c_values
+          .write()
+          .push((clock::now() - start, *state.read()))
+      });
+
+    animate.run();
+    while animate.is_running() {
+      wnd.advance_time(frame);
+    }
+    let values = values.read().clone();
+    let finished_at = values
+      .iter()
+      .find(|(_, v)| *v == 100.)
+      .map(|(at, _)| *at)
+      .unwrap();
+    (values, finished_at)
+  }
+
+  #[test]
+  fn same_duration_at_any_refresh_rate() {
+    reset_test_env!();
+
+    let duration = Duration::from_millis(500);
+    for hz in [144., 30.] {
+      let frame = Duration::from_secs_f64(1. / hz);
+      let (values, total) = run_at_rate(frame);
+      assert!(values.len() >= (duration.as_secs_f64() * hz) as usize);
+      for (at, v) in values.iter().filter(|(at, _)| *at <= duration) {
+        let expected = at.as_secs_f32() / duration.as_secs_f32() * 100.;
+        assert!((v - expected).abs() < 0.01, "{hz}Hz at {at:?}: {v} != {expected}");
+      }
+      assert!(total.abs_diff(duration) < frame, "{hz}Hz finished at {total:?}");
+    }
+  }
+
+  #[test]
+  fn jump_to_final_value_with_reduced_motion() {
+    reset_test_env!();
+
+    AppCtx::set_reduced_motion(Some(true));
+    let state = Stateful::new(100.);
+    let (mut wnd, animate) = linear_animate(state.clone_writer(), true);
+    animate.run();
+    assert!(!animate.is_running());
+    wnd.advance_time(Duration::from_millis(16));
+    assert_eq!(*state.read(), 100.);
+
+    let (_wnd, animate) = linear_animate(state.clone_writer(), false);
+    animate.run();
+    assert!(animate.is_running());
+
+    AppCtx::set_reduced_motion(None);
+    assert!(!AppCtx::reduced_motion());
+  }
 }
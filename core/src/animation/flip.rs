@@ -0,0 +1,323 @@
+//! A FLIP ("first, last, invert, play") animation moves the items of a
+//! container smoothly from their old positions to the new ones, such as when a
+//! list is reordered.
+//!
+//! Every item is tracked by a key. When an item with the same key paints at a
+//! new position, it starts painting at its old position and slides to the new
+//! one over the transition. The layout is not changed by the animation, the
+//! items are only translated when they paint.
+//!
+//! # Example
+//!
+//! ```rust
+//! use ribir::prelude::*;
+//!
+//! let list = Stateful::new(vec![1, 2, 3]);
+//! let _ = fn_widget! {
+//!   let flip = FlipAnimation::new(transitions::EASE_IN.of(BuildCtx::get()));
+//!   @Column {
+//!     on_tap: move |_| $list.write().reverse(),
+//!     @ {
+//!       pipe!($list.clone()).map(move |list| {
+//!         let flip = flip.clone();
+//!         list.into_iter().map(move |i| {
+//!           flip.item(i, @Text { text: i.to_string() }.into_widget())
+//!         })
+//!       })
+//!     }
+//!   }
+//! };
+//! ```
+
+use std::{
+  any::Any,
+  cell::{Cell, RefCell},
+  collections::hash_map::Entry,
+  hash::Hash,
+  rc::Rc,
+};
+
+use crate::{prelude::*, ticker::FrameMsg, window::WindowFlags, wrap_render::*};
+
+/// The controller of a FLIP animation, its clones share the same items. See
+/// the [module-level documentation](self) for more.
+pub struct FlipAnimation<K> {
+  inner: Rc<RefCell<FlipInner<K>>>,
+}
+
+struct FlipInner<K> {
+  transition: Box<dyn Transition>,
+  items: ahash::HashMap<K, FlipRecord>,
+  _tick_guard: Option<Box<dyn Any>>,
+}
+
+struct FlipRecord {
+  /// The global position that the item painted at last time.
+  pos: Point,
+  motion: Option<Motion>,
+  /// If the item is painted in the current frame.
+  painted: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Motion {
+  from: Vector,
+  start_at: Instant,
+}
+
+impl<K: Hash + Eq + Clone + 'static> FlipAnimation<K> {
+  /// Create a FLIP animation that moves the items over the `transition`.
+  pub fn new(transition: impl Transition + 'static) -> Self {
+    let inner = Rc::new(RefCell::new(FlipInner {
+      transition: transition.box_it(),
+      items: <_>::default(),
+      _tick_guard: None,
+    }));
+
+    // Forget the items that are not painted in a painted frame, they're
+    // removed or hidden.
+    let weak = Rc::downgrade(&inner);
+    let guard = BuildCtx::get()
+      .window()
+      .frame_tick_stream()
+      .subscribe(move |msg| {
+        if let (FrameMsg::Finish(_), Some(inner)) = (msg, weak.upgrade()) {
+          let items = &mut inner.borrow_mut().items;
+          if items.values().any(|r| r.painted) {
+            items.retain(|_, r| std::mem::take(&mut r.painted));
+          }
+        }
+      })
+      .unsubscribe_when_dropped();
+    inner.borrow_mut()._tick_guard = Some(Box::new(guard));
+
+    Self { inner }
+  }
+
+  /// Wrap the `child` as the item of the `key`, it moves smoothly when it
+  /// paints at a different position from the last item of the same key.
+  pub fn item<'c>(&self, key: K, child: Widget<'c>) -> Widget<'c> {
+    let running = Rc::new(Cell::new(false));
+    let item = FlipItem { key, flip: self.clone(), running: running.clone() };
+    let child = WrapRender::combine_child(State::value(item), child);
+    FatObj::new(child)
+      .on_disposed(move |e| {
+        if running.get() {
+          e.window().dec_running_animate();
+        }
+      })
+      .into_widget()
+  }
+
+  /// Return if any item is moving.
+  pub fn is_running(&self) -> bool {
+    self
+      .inner
+      .borrow()
+      .items
+      .values()
+      .any(|r| r.motion.is_some())
+  }
+
+  /// Record that the item of the `key` paints at `pos`, and return the offset
+  /// to paint it if it's moving.
+  fn paint_offset(&self, key: K, pos: Point, animate: bool) -> Option<Vector> {
+    let mut inner = self.inner.borrow_mut();
+    let FlipInner { transition, items, .. } = &mut *inner;
+    let now = clock::now();
+    let record = match items.entry(key) {
+      Entry::Occupied(e) => e.into_mut(),
+      Entry::Vacant(e) => {
+        e.insert(FlipRecord { pos, motion: None, painted: true });
+        return None;
+      }
+    };
+    record.painted = true;
+
+    if record.pos != pos {
+      // Start from where the item is seen now, even if it's moving.
+      let seen = record
+        .motion
+        .and_then(|m| m.offset(&**transition, now))
+        .unwrap_or_default();
+      let from = seen + (record.pos - pos);
+      record.pos = pos;
+      record.motion = animate.then_some(Motion { from, start_at: now });
+    }
+
+    let offset = record
+      .motion
+      .and_then(|m| m.offset(&**transition, now));
+    if offset.is_none() {
+      record.motion = None;
+    }
+    offset
+  }
+}
+
+impl<K> Clone for FlipAnimation<K> {
+  fn clone(&self) -> Self { Self { inner: self.inner.clone() } }
+}
+
+impl Motion {
+  fn offset(&self, transition: &dyn Transition, now: Instant) -> Option<Vector> {
+    let dur = now - self.start_at;
+    if dur >= transition.duration() {
+      return None;
+    }
+    match transition.rate_of_change(dur) {
+      AnimateProgress::Dismissed => Some(self.from),
+      AnimateProgress::Between(rate) => Some(self.from * (1. - rate)),
+      AnimateProgress::Finish => None,
+    }
+  }
+}
+
+struct FlipItem<K> {
+  key: K,
+  flip: FlipAnimation<K>,
+  running: Rc<Cell<bool>>,
+}
+
+impl<K: Hash + Eq + Clone + 'static> WrapRender for FlipItem<K> {
+  fn perform_layout(&self, clamp: BoxClamp, host: &dyn Render, ctx: &mut LayoutCtx) -> Size {
+    host.perform_layout(clamp, ctx)
+  }
+
+  fn paint(&self, host: &dyn Render, ctx: &mut PaintingCtx) {
+    let wnd = ctx.window();
+    let animate = wnd.flags().contains(WindowFlags::ANIMATIONS) && !AppCtx::reduced_motion();
+    let pos = ctx.map_to_global(Point::zero());
+    let offset = self
+      .flip
+      .paint_offset(self.key.clone(), pos, animate);
+
+    // Keep the window drawing while the item moves.
+    if self.running.replace(offset.is_some()) != offset.is_some() {
+      if offset.is_some() {
+        wnd.inc_running_animate();
+      } else {
+        wnd.dec_running_animate();
+      }
+    }
+    if let Some(offset) = offset {
+      ctx.painter().translate(offset.x, offset.y);
+    }
+    host.paint(ctx)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn item_rects(wnd: &mut TestWindow, colors: &[Color]) -> Vec<Rect> {
+    let commands = wnd.take_last_frame().unwrap().commands;
+    colors
+      .iter()
+      .map(|c| {
+        commands
+          .iter()
+          .find_map(|cmd| match cmd {
+            PaintCommand::Path(PathCommand {
+              paint_bounds,
+              action: PaintPathAction::Paint { brush: CommandBrush::Color(color), .. },
+              ..
+            }) if color == c => Some(*paint_bounds),
+            _ => None,
+          })
+          .unwrap()
+      })
+      .collect()
+  }
+
+  #[test]
+  fn reorder_moves_from_old_offsets() {
+    reset_test_env!();
+
+    let colors = [Color::RED, Color::GREEN, Color::BLUE];
+    let order = Stateful::new(vec![0, 1, 2]);
+    let c_order = order.clone_writer();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let flip = FlipAnimation::new(EasingTransition {
+          duration: Duration::from_millis(100),
+          easing: easing::LinearEasing,
+        });
+        @MockMulti {
+          @ {
+            pipe!($c_order.clone()).map(move |order| {
+              let flip = flip.clone();
+              order.into_iter().map(move |i| {
+                let item = @Container {
+                  size: Size::new(20., 10.),
+                  background: colors[i],
+                };
+                flip.item(i, item.into_widget())
+              })
+            })
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+
+    wnd.set_flags(wnd.flags() | WindowFlags::ANIMATIONS);
+    let x = |rects: Vec<Rect>| {
+      rects
+        .iter()
+        .map(|r| r.origin.x)
+        .collect::<Vec<_>>()
+    };
+    wnd.draw_frame();
+    assert_eq!(x(item_rects(&mut wnd, &colors)), [0., 20., 40.]);
+
+    order.write().reverse();
+    wnd.draw_frame();
+    // The items start painting at their old offsets.
+    assert_eq!(x(item_rects(&mut wnd, &colors)), [0., 20., 40.]);
+    // The middle one stays.
+    assert_eq!(wnd.running_animates.get(), 2);
+
+    wnd.advance_time(Duration::from_millis(50));
+    assert_eq!(x(item_rects(&mut wnd, &colors)), [20., 20., 20.]);
+
+    wnd.advance_time(Duration::from_millis(50));
+    assert_eq!(x(item_rects(&mut wnd, &colors)), [40., 20., 0.]);
+    assert_eq!(wnd.running_animates.get(), 0);
+  }
+
+  #[test]
+  fn prev_layout_rect_during_layout() {
+    reset_test_env!();
+
+    let width = Stateful::new(10.);
+    let c_width = width.clone_writer();
+    let rects: Stateful<Vec<(Option<Rect>, Option<Rect>)>> = Stateful::new(vec![]);
+    let c_rects = rects.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: pipe!(Size::new(*$c_width, 10.)),
+        on_performed_layout: move |e| {
+          $c_rects.write().push((e.prev_layout_rect(), e.box_rect()));
+        },
+      }
+    });
+    wnd.draw_frame();
+    assert_eq!(
+      rects.read().last().copied(),
+      Some((None, Some(Rect::new(Point::zero(), Size::new(10., 10.)))))
+    );
+
+    *width.write() = 30.;
+    wnd.draw_frame();
+    assert_eq!(
+      rects.read().last().copied(),
+      Some((
+        Some(Rect::new(Point::zero(), Size::new(10., 10.))),
+        Some(Rect::new(Point::zero(), Size::new(30., 10.)))
+      ))
+    );
+  }
+}
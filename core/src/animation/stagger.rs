@@ -138,7 +138,7 @@ impl<T: Transition + 'static> Animation for Stateful<Stagger<T>> {
     }
     {
       let mut this = self.write();
-      this.next_to_run = Some(AnimationCursor { prev_at: Instant::now(), index: 0 });
+      this.next_to_run = Some(AnimationCursor { prev_at: clock::now(), index: 0 });
       this.run_times += 1;
     }
 
@@ -182,7 +182,10 @@ impl<T: Transition + 'static> Stateful<Stagger<T>> {
         drop(this);
 
         let this = self.clone_writer();
-        let h = observable::timer_at((), at, AppCtx::scheduler()).subscribe(move |_| {
+        // Don't use `timer_at`, it measures the delay by the system time rather
+        // than the clock of the framework.
+        let delay = at.saturating_duration_since(clock::now());
+        let h = observable::timer((), delay, AppCtx::scheduler()).subscribe(move |_| {
           next.run();
           this.trigger_next();
         });
@@ -0,0 +1,135 @@
+//! Speak the status changes to the users of the assistive technologies, such
+//! as "3 new messages", even if the focus is elsewhere. See
+//! [`Semantics::announce`](crate::prelude::Semantics::announce) and
+//! [`LiveRegion`](crate::prelude::LiveRegion).
+
+use std::{cell::RefCell, rc::Rc};
+
+use ribir_algo::CowArc;
+
+use crate::{
+  builtin_widgets::text_content_of,
+  prelude::{Duration, Instant},
+  widget_tree::{WidgetId, WidgetTree},
+  window::WindowId,
+};
+
+/// How urgent an announcement is.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Politeness {
+  /// Wait until the user is idle, such as a new message arrives.
+  #[default]
+  Polite,
+  /// Interrupt the user at once, such as an error. The polite announcements
+  /// queued before it are dropped.
+  Assertive,
+}
+
+/// Speak the announcements of the windows, it's implemented by the platform
+/// accessibility integration, see [`AppCtx::set_announce_sink`].
+///
+/// [`AppCtx::set_announce_sink`]: crate::prelude::AppCtx::set_announce_sink
+pub trait AnnounceSink {
+  /// Speak the `message` of the window `wnd` and return at once.
+  fn announce(&mut self, wnd: WindowId, message: &str, politeness: Politeness);
+}
+
+/// A sink that speaks nothing, it records the announcements for the tests.
+/// The clones share the records.
+#[derive(Default, Clone)]
+pub struct MockAnnounceSink {
+  announced: Rc<RefCell<Vec<(String, Politeness)>>>,
+}
+
+impl MockAnnounceSink {
+  /// The announcements that it's asked to speak, in order.
+  pub fn announced(&self) -> Vec<(String, Politeness)> { self.announced.borrow().clone() }
+}
+
+impl AnnounceSink for MockAnnounceSink {
+  fn announce(&mut self, _: WindowId, message: &str, politeness: Politeness) {
+    self
+      .announced
+      .borrow_mut()
+      .push((message.to_string(), politeness));
+  }
+}
+
+/// A message is announced only once in this duration.
+const COALESCE_DURATION: Duration = Duration::from_millis(500);
+
+struct LiveRegionRecord {
+  id: WidgetId,
+  politeness: Politeness,
+  text: CowArc<str>,
+}
+
+/// The announcements of a window, they're sent to the sink at the end of a
+/// frame.
+#[derive(Default)]
+pub(crate) struct Announcer {
+  queue: Vec<(CowArc<str>, Politeness)>,
+  recent: Vec<(CowArc<str>, Instant)>,
+  live_regions: Vec<LiveRegionRecord>,
+}
+
+impl Announcer {
+  pub(crate) fn push(&mut self, message: CowArc<str>, politeness: Politeness, now: Instant) {
+    if politeness == Politeness::Assertive {
+      self
+        .queue
+        .retain(|(_, p)| *p == Politeness::Assertive);
+    }
+    let queued = self.queue.iter().any(|(m, _)| *m == message);
+    let recent = self
+      .recent
+      .iter()
+      .any(|(m, at)| *m == message && now - *at < COALESCE_DURATION);
+    if !queued && !recent {
+      self.queue.push((message, politeness));
+    }
+  }
+
+  pub(crate) fn add_live_region(
+    &mut self, id: WidgetId, politeness: Politeness, tree: &WidgetTree,
+  ) {
+    let text = text_content_of(id, tree).unwrap_or_default();
+    self
+      .live_regions
+      .push(LiveRegionRecord { id, politeness, text });
+  }
+
+  pub(crate) fn remove_live_region(&mut self, id: WidgetId) {
+    self.live_regions.retain(|r| r.id != id);
+  }
+
+  /// Queue the changed texts of the live regions and take the announcements
+  /// to speak.
+  pub(crate) fn take(&mut self, tree: &WidgetTree, now: Instant) -> Vec<(CowArc<str>, Politeness)> {
+    let mut changed = vec![];
+    for r in self.live_regions.iter_mut() {
+      if r.id.is_dropped(tree) {
+        continue;
+      }
+      let text = text_content_of(r.id, tree).unwrap_or_default();
+      if text != r.text {
+        r.text = text.clone();
+        if !text.is_empty() {
+          changed.push((text, r.politeness));
+        }
+      }
+    }
+    for (text, politeness) in changed {
+      self.push(text, politeness, now);
+    }
+
+    self
+      .recent
+      .retain(|(_, at)| now - *at < COALESCE_DURATION);
+    let queue = std::mem::take(&mut self.queue);
+    self
+      .recent
+      .extend(queue.iter().map(|(m, _)| (m.clone(), now)));
+    queue
+  }
+}
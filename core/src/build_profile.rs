@@ -0,0 +1,303 @@
+//! Time the builds of the compose widgets and the regenerations of the pipes,
+//! to tell which of them make the build of a frame slow. See
+//! [`AppCtx::set_build_profiling`].
+//!
+//! The profiler is only compiled in debug builds, or with the `build-profile`
+//! feature, and it costs nothing until it's turned on.
+
+use std::{collections::HashMap, fmt, panic::Location, ptr::NonNull};
+
+use crate::{
+  clock,
+  context::{AppCtx, BuildCtx},
+  prelude::{Duration, Instant},
+  widget_tree::WidgetTree,
+};
+
+/// If the profiler is compiled.
+pub(crate) const BUILD_PROFILE_ENABLED: bool =
+  cfg!(any(debug_assertions, feature = "build-profile"));
+
+/// What is built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildKind {
+  /// A compose widget composes itself.
+  Compose,
+  /// A pipe regenerates its widgets.
+  Pipe,
+}
+
+/// The builds of a compose widget or the regenerations of a pipe declared at
+/// the same place in a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildCost {
+  pub kind: BuildKind,
+  /// The type of the compose widget, or the type of the value of the pipe.
+  pub name: &'static str,
+  /// Where the widget or the pipe was declared, if it's known.
+  pub location: Option<&'static Location<'static>>,
+  /// The whole time of the builds, including the builds nested in them, e.g.
+  /// a compose widget that composes another one. The children are built after
+  /// their parent, so they're not nested in it.
+  pub inclusive: Duration,
+  /// The time of the builds without the builds nested in them.
+  pub exclusive: Duration,
+  /// How many times it was built.
+  pub count: usize,
+}
+
+/// The builds of a frame, see [`Window::build_profile`].
+///
+/// [`Window::build_profile`]: crate::window::Window::build_profile
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildProfile {
+  /// The builds, the largest exclusive time first.
+  pub builds: Vec<BuildCost>,
+}
+
+type BuildKey = (BuildKind, &'static str, Option<&'static Location<'static>>);
+
+/// The builds of a window, only recorded while the profiler is on.
+#[derive(Default)]
+pub(crate) struct BuildProfiler {
+  builds: HashMap<BuildKey, BuildCost, ahash::RandomState>,
+  /// The inclusive time of the builds nested in every running build.
+  nested: Vec<Duration>,
+  last: BuildProfile,
+}
+
+/// A running build, ended by [`BuildSpan::end`].
+pub(crate) struct BuildSpan {
+  tree: NonNull<WidgetTree>,
+  start: Instant,
+}
+
+impl BuildProfile {
+  /// The `count` builds of the largest exclusive time.
+  pub fn top(&self, count: usize) -> &[BuildCost] { &self.builds[..count.min(self.builds.len())] }
+
+  /// The whole time of the builds in the frame.
+  pub fn total(&self) -> Duration { self.builds.iter().map(|b| b.exclusive).sum() }
+}
+
+impl fmt::Display for BuildProfile {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "build {:?} in total", self.total())?;
+    for b in &self.builds {
+      write!(
+        f,
+        "  {:?} exclusive, {:?} inclusive, {} times: {:?} `{}`",
+        b.exclusive, b.inclusive, b.count, b.kind, b.name
+      )?;
+      if let Some(loc) = b.location {
+        write!(f, " declared at {loc}")?;
+      }
+      writeln!(f)?;
+    }
+    Ok(())
+  }
+}
+
+impl BuildSpan {
+  /// Start to time a build of the current build context, `None` if the
+  /// profiler is off or it's not in a build.
+  #[inline]
+  pub(crate) fn start() -> Option<Self> {
+    if !BUILD_PROFILE_ENABLED || !AppCtx::is_build_profiling() {
+      return None;
+    }
+    let tree = BuildCtx::try_get()?.tree;
+    // Safety: the tree lives longer than the builds of its widgets.
+    let profiler = unsafe { tree.as_ref() };
+    profiler
+      .build_profiler
+      .borrow_mut()
+      .nested
+      .push(Duration::ZERO);
+    Some(Self { tree, start: clock::now() })
+  }
+
+  /// End the build, it's counted as a build if `counted`, otherwise only its
+  /// time is added.
+  pub(crate) fn end(
+    self, kind: BuildKind, name: &'static str, location: Option<&'static Location<'static>>,
+    counted: bool,
+  ) {
+    let inclusive = clock::now() - self.start;
+    // Safety: the tree lives longer than the builds of its widgets.
+    let tree = unsafe { self.tree.as_ref() };
+    let mut profiler = tree.build_profiler.borrow_mut();
+    let nested = profiler.nested.pop().unwrap_or_default();
+    if let Some(parent) = profiler.nested.last_mut() {
+      *parent += inclusive;
+    }
+    let cost = profiler
+      .builds
+      .entry((kind, name, location))
+      .or_insert_with(|| BuildCost {
+        kind,
+        name,
+        location,
+        inclusive: Duration::ZERO,
+        exclusive: Duration::ZERO,
+        count: 0,
+      });
+    cost.inclusive += inclusive;
+    cost.exclusive += inclusive.saturating_sub(nested);
+    cost.count += counted as usize;
+  }
+}
+
+/// Time `f` as a build.
+pub(crate) fn profile_build<R>(
+  kind: BuildKind, name: &'static str, location: Option<&'static Location<'static>>,
+  f: impl FnOnce() -> R,
+) -> R {
+  let span = BuildSpan::start();
+  let r = f();
+  if let Some(span) = span {
+    span.end(kind, name, location, true);
+  }
+  r
+}
+
+impl BuildProfiler {
+  /// End a frame, its builds replace the profile of the last frame if it
+  /// built anything.
+  pub(crate) fn end_frame(&mut self) {
+    if self.builds.is_empty() {
+      return;
+    }
+    let mut builds: Vec<_> = self.builds.drain().map(|(_, b)| b).collect();
+    builds.sort_by_key(|b| std::cmp::Reverse(b.exclusive));
+    self.last = BuildProfile { builds };
+  }
+
+  pub(crate) fn last(&self) -> BuildProfile { self.last.clone() }
+}
+
+impl AppCtx {
+  /// Turn on or off the build profiler. While it's on, every window times the
+  /// builds of the compose widgets and the regenerations of the pipes, see
+  /// [`Window::build_profile`].
+  ///
+  /// It does nothing in release builds without the `build-profile` feature.
+  ///
+  /// [`Window::build_profile`]: crate::window::Window::build_profile
+  pub fn set_build_profiling(on: bool) { Self::shared().build_profiling.set(on) }
+
+  /// Whether the build profiler is on.
+  pub fn is_build_profiling() -> bool {
+    BUILD_PROFILE_ENABLED && Self::shared().build_profiling.get()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  struct SlowInner;
+
+  struct SlowOuter;
+
+  impl Compose for SlowInner {
+    fn compose(_: impl StateWriter<Value = Self>) -> Widget<'static> {
+      fn_widget! {
+        TEST_CLOCK.advance(Duration::from_millis(20));
+        @MockBox { size: Size::new(10., 10.) }
+      }
+      .into_widget()
+    }
+  }
+
+  impl Compose for SlowOuter {
+    fn compose(_: impl StateWriter<Value = Self>) -> Widget<'static> {
+      fn_widget! {
+        TEST_CLOCK.advance(Duration::from_millis(10));
+        @ { SlowInner }
+      }
+      .into_widget()
+    }
+  }
+
+  fn find<'a>(profile: &'a BuildProfile, name: &str) -> &'a BuildCost {
+    profile
+      .builds
+      .iter()
+      .find(|b| b.name.ends_with(name))
+      .unwrap()
+  }
+
+  fn slow_outer() -> Widget<'static> { SlowOuter.into_widget() }
+
+  #[test]
+  fn rank_compose_widgets() {
+    reset_test_env!();
+    AppCtx::set_build_profiling(true);
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @ { slow_outer() }
+        @ { slow_outer() }
+      }
+    });
+    wnd.draw_frame();
+
+    let profile = wnd.build_profile();
+    let outer = find(&profile, "SlowOuter");
+    let inner = find(&profile, "SlowInner");
+    assert_eq!((outer.count, inner.count), (2, 2));
+    assert_eq!(inner.exclusive, Duration::from_millis(40));
+    assert_eq!(outer.exclusive, Duration::from_millis(20));
+    assert_eq!(outer.inclusive, Duration::from_millis(60));
+    assert_eq!(outer.kind, BuildKind::Compose);
+    assert_eq!(outer.location.map(|l| l.file()), Some(file!()));
+    assert_eq!(profile.top(2), [inner.clone(), outer.clone()]);
+
+    AppCtx::set_build_profiling(false);
+  }
+
+  #[test]
+  fn pipe_regeneration() {
+    reset_test_env!();
+    AppCtx::set_build_profiling(true);
+
+    let slow = Stateful::new(false);
+    let c_slow = slow.clone_watcher();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @ {
+        pipe!(*$c_slow).map(|slow| fn_widget! {
+          if slow {
+            SlowInner.into_widget()
+          } else {
+            MockBox { size: Size::zero() }.into_widget()
+          }
+        })
+      }
+    });
+    wnd.draw_frame();
+    let profile = wnd.build_profile();
+    assert!(
+      !profile
+        .builds
+        .iter()
+        .any(|b| b.name.ends_with("SlowInner"))
+    );
+
+    *slow.write() = true;
+    wnd.draw_frame();
+    let profile = wnd.build_profile();
+    let pipe = profile
+      .builds
+      .iter()
+      .find(|b| b.kind == BuildKind::Pipe)
+      .unwrap();
+    assert_eq!(pipe.count, 1);
+    assert_eq!(pipe.inclusive, Duration::from_millis(20));
+    assert_eq!(pipe.exclusive, Duration::ZERO);
+    assert_eq!(find(&profile, "SlowInner").count, 1);
+
+    AppCtx::set_build_profiling(false);
+  }
+}
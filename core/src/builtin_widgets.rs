@@ -12,6 +12,7 @@ use std::ops::DerefMut;
 pub use key::{Key, KeyWidget};
 pub use painting_style::*;
 pub mod image_widget;
+pub use image_widget::Image;
 pub mod keep_alive;
 pub use keep_alive::*;
 mod theme;
@@ -30,18 +31,28 @@ mod box_decoration;
 pub use box_decoration::*;
 mod scrollable;
 pub use scrollable::*;
+mod scroll_physics;
+pub use scroll_physics::*;
+mod scroll_restoration;
+pub use scroll_restoration::*;
 mod transform_widget;
 pub use transform_widget::*;
 mod visibility;
 pub use visibility::*;
 mod ignore_pointer;
 pub use ignore_pointer::*;
+mod hit_slop;
+pub use hit_slop::*;
+mod drag_drop;
+pub use drag_drop::*;
 mod void;
 pub use void::Void;
 mod unconstrained_box;
 pub use unconstrained_box::*;
 mod opacity;
 pub use opacity::*;
+mod z_index;
+pub use z_index::*;
 mod anchor;
 pub use anchor::*;
 mod layout_box;
@@ -65,6 +76,8 @@ mod mix_builtin;
 pub use mix_builtin::*;
 pub mod container;
 pub use container::*;
+mod layout_boundary;
+pub use layout_boundary::*;
 mod provider;
 pub use provider::*;
 mod class;
@@ -82,8 +95,15 @@ mod text;
 pub use text::*;
 mod tooltips;
 pub use tooltips::*;
+mod global_key;
+pub use global_key::*;
+mod semantics;
+pub use semantics::*;
 
-use crate::prelude::*;
+use crate::{
+  prelude::*,
+  widget_tree::{PROVENANCE_ENABLED, Provenance},
+};
 
 /// A fat object that extend the `T` object with all builtin widgets ability.
 ///
@@ -130,6 +150,7 @@ pub struct FatObj<T> {
   margin: Option<State<Margin>>,
   transform: Option<State<TransformWidget>>,
   opacity: Option<State<Opacity>>,
+  z_index: Option<State<ZIndex>>,
   visibility: Option<State<Visibility>>,
   h_align: Option<State<HAlignWidget>>,
   v_align: Option<State<VAlignWidget>>,
@@ -139,7 +160,10 @@ pub struct FatObj<T> {
   text_style: Option<State<TextStyleWidget>>,
   keep_alive: Option<State<KeepAlive>>,
   tooltips: Option<State<Tooltips>>,
+  semantics_label: Option<State<SemanticsLabel>>,
+  global_key: Option<State<GlobalKeyWidget>>,
   keep_alive_unsubscribe_handle: Option<Box<dyn Any>>,
+  provenance: Provenance,
 }
 
 /// Create a function widget that uses an empty `FatObj` as the host object.
@@ -184,9 +208,13 @@ impl<T> FatObj<T> {
       text_style: self.text_style,
       visibility: self.visibility,
       opacity: self.opacity,
+      z_index: self.z_index,
       tooltips: self.tooltips,
+      semantics_label: self.semantics_label,
+      global_key: self.global_key,
       keep_alive: self.keep_alive,
       keep_alive_unsubscribe_handle: self.keep_alive_unsubscribe_handle,
+      provenance: self.provenance,
     }
   }
 
@@ -214,8 +242,11 @@ impl<T> FatObj<T> {
       && self.text_style.is_none()
       && self.visibility.is_none()
       && self.opacity.is_none()
+      && self.z_index.is_none()
       && self.keep_alive.is_none()
       && self.tooltips.is_none()
+      && self.semantics_label.is_none()
+      && self.global_key.is_none()
   }
 
   /// Return the host object of the FatObj.
@@ -429,6 +460,14 @@ impl<T> FatObj<T> {
       .get_or_insert_with(|| State::value(<_>::default()))
   }
 
+  /// Returns the `State<ZIndex>` widget from the FatObj. If it doesn't exist,
+  /// a new one will be created.
+  pub fn get_z_index_widget(&mut self) -> &State<ZIndex> {
+    self
+      .z_index
+      .get_or_insert_with(|| State::value(<_>::default()))
+  }
+
   /// Returns the `State<KeepAlive>` widget from the FatObj. If it doesn't
   /// exist, a new one will be created.
   pub fn get_keep_alive_widget(&mut self) -> &State<KeepAlive> {
@@ -444,6 +483,22 @@ impl<T> FatObj<T> {
       .tooltips
       .get_or_insert_with(|| State::value(<_>::default()))
   }
+
+  /// Returns the `State<SemanticsLabel>` widget from the FatObj. If it
+  /// doesn't exist, a new one is created.
+  pub fn get_semantics_label_widget(&mut self) -> &State<SemanticsLabel> {
+    self
+      .semantics_label
+      .get_or_insert_with(|| State::value(<_>::default()))
+  }
+
+  /// Returns the `State<GlobalKeyWidget>` widget from the FatObj. If it
+  /// doesn't exist, a new one is created.
+  pub fn get_global_key_widget(&mut self) -> &State<GlobalKeyWidget> {
+    self
+      .global_key
+      .get_or_insert_with(|| State::value(<_>::default()))
+  }
 }
 
 macro_rules! on_mixin {
@@ -472,6 +527,20 @@ impl<T> FatObj<T> {
     on_mixin!(self, on_performed_layout, f)
   }
 
+  /// Attaches an event handler that runs at the end of a frame if the layout
+  /// size of the widget differs from the last frame. It runs at most once a
+  /// frame and doesn't run for the first layout of the widget.
+  pub fn on_size_changed(mut self, f: impl FnMut(&SizeChangedEvent) + 'static) -> Self {
+    on_mixin!(self, on_size_changed, f)
+  }
+
+  /// Attaches an event handler that runs at the end of a frame if the position
+  /// of the widget relative to its parent differs from the last frame. It runs
+  /// at most once a frame and doesn't run for the first layout of the widget.
+  pub fn on_position_changed(mut self, f: impl FnMut(&PositionChangedEvent) + 'static) -> Self {
+    on_mixin!(self, on_position_changed, f)
+  }
+
   /// Attaches an event handler that runs when the widget is disposed.
   pub fn on_disposed(mut self, f: impl FnOnce(&mut LifecycleEvent) + 'static) -> Self {
     on_mixin!(self, on_disposed, f)
@@ -553,6 +622,60 @@ impl<T> FatObj<T> {
     on_mixin!(self, on_tap_capture, f)
   }
 
+  /// Attaches a handler to the widget that is triggered when a pointer is held
+  /// down on it without moving for the [`GestureSettings::long_press`]
+  /// duration. Once the long press fires, the release of the pointer is not a
+  /// tap anymore.
+  pub fn on_long_press(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_long_press, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered during the capture
+  /// phase of a long press event. This is similar to `on_long_press`, but it's
+  /// triggered earlier in the event flow. For more information on event
+  /// capturing, see [Event capture](https://www.w3.org/TR/DOM-Level-2-Events/events.html#Events-flow-capture).
+  pub fn on_long_press_capture(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_long_press_capture, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when a pointer pressed
+  /// on it moves farther than the slop of [`GestureSettings`], so a tap never
+  /// starts a drag. The release of the pointer is not a tap anymore.
+  pub fn on_drag_start(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_start, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when the dragging
+  /// pointer moves, even if it leaves the widget. The event carries the
+  /// movement since the last drag event and since the pointer was pressed.
+  pub fn on_drag_update(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_update, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when the dragging
+  /// pointer is released or canceled, wherever the pointer is.
+  pub fn on_drag_end(mut self, f: impl FnMut(&mut DragEvent) + 'static) -> Self {
+    on_mixin!(self, on_drag_end, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when the middle button
+  /// taps it.
+  pub fn on_middle_tap(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_middle_tap, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when the "Browser
+  /// Back" button of the mouse taps it.
+  pub fn on_back(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_back, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when the "Browser
+  /// Forward" button of the mouse taps it.
+  pub fn on_forward(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_forward, f)
+  }
+
   /// Attaches a handler to the widget that is triggered when a double tap
   /// occurs.
   pub fn on_double_tap(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
@@ -581,6 +704,54 @@ impl<T> FatObj<T> {
     on_mixin!(self, on_triple_tap_capture, f)
   }
 
+  /// Attaches a handler to the widget that is triggered when a double tap
+  /// occurs, and the second tap follows the first one within `interval`
+  /// rather than the [`Theme::multi_tap_interval`].
+  pub fn on_double_tap_within(
+    mut self, (interval, f): (Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .on_double_tap_within((interval, f));
+    self
+  }
+
+  /// Attaches a handler to the widget that is triggered during the capture
+  /// phase of a double tap event. This is similar to `on_double_tap_within`,
+  /// but it's triggered earlier in the event flow.
+  pub fn on_double_tap_within_capture(
+    mut self, (interval, f): (Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .on_double_tap_within_capture((interval, f));
+    self
+  }
+
+  /// Attaches a handler to the widget that is triggered when a triple tap
+  /// occurs, and every tap follows the previous one within `interval` rather
+  /// than the [`Theme::multi_tap_interval`].
+  pub fn on_triple_tap_within(
+    mut self, (interval, f): (Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .on_triple_tap_within((interval, f));
+    self
+  }
+
+  /// Attaches a handler to the widget that is triggered during the capture
+  /// phase of a triple tap event. This is similar to `on_triple_tap_within`,
+  /// but it's triggered earlier in the event flow.
+  pub fn on_triple_tap_within_capture(
+    mut self, (interval, f): (Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .on_triple_tap_within_capture((interval, f));
+    self
+  }
+
   /// Attaches a handler to the widget that is triggered when a x-times tap
   /// occurs.
   pub fn on_x_times_tap(
@@ -605,6 +776,30 @@ impl<T> FatObj<T> {
     self
   }
 
+  /// Attaches a handler to the widget that is triggered when a x-times tap
+  /// occurs, and every tap follows the previous one within `interval` rather
+  /// than the [`Theme::multi_tap_interval`].
+  pub fn on_x_times_tap_within(
+    mut self, (times, interval, f): (usize, Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .on_x_times_tap_within((times, interval, f));
+    self
+  }
+
+  /// Attaches a handler to the widget that is triggered during the capture
+  /// phase of a x-times tap event. This is similar to `on_x_times_tap_within`,
+  /// but it's triggered earlier in the event flow.
+  pub fn on_x_times_tap_within_capture(
+    mut self, (times, interval, f): (usize, Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .on_x_times_tap_within_capture((times, interval, f));
+    self
+  }
+
   /// Attaches a handler to the widget that is triggered when the user rotates a
   /// wheel button on a pointing device (typically a mouse).
   pub fn on_wheel(mut self, f: impl FnMut(&mut WheelEvent) + 'static) -> Self {
@@ -619,6 +814,31 @@ impl<T> FatObj<T> {
     on_mixin!(self, on_wheel_capture, f)
   }
 
+  /// Attaches a handler to the widget that is triggered when the nearest
+  /// scrollable widget inside it scrolls, or its scrollable extent changes.
+  pub fn on_scroll(mut self, f: impl FnMut(&mut ScrollEvent) + 'static) -> Self {
+    on_mixin!(self, on_scroll, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when it locks the
+  /// pointer or loses the lock, see [`PointerEvent::request_pointer_lock`].
+  pub fn on_pointer_lock_change(mut self, f: impl FnMut(&mut PointerLockEvent) + 'static) -> Self {
+    on_mixin!(self, on_pointer_lock_change, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when it captures a
+  /// pointer, see [`CommonEvent::set_pointer_capture`].
+  pub fn on_got_pointer_capture(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_got_pointer_capture, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered when it loses the
+  /// capture of a pointer, the pointer is released, canceled, or captured by
+  /// another widget.
+  pub fn on_lost_pointer_capture(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_lost_pointer_capture, f)
+  }
+
   /// Attaches a handler to the widget that is triggered when the input method
   /// pre-edit area is changed.
   pub fn on_ime_pre_edit(mut self, f: impl FnMut(&mut ImePreEditEvent) + 'static) -> Self {
@@ -743,6 +963,18 @@ impl<T> FatObj<T> {
     )
   }
 
+  /// Initializes the mouse buttons that the pointer down, up and tap handlers
+  /// of the widget respond to. They're skipped unless the buttons of the press
+  /// intersect the mask, see [`PointerEvent::chord_buttons`]. They respond to
+  /// all the buttons if it's not set.
+  pub fn buttons<const M: usize>(self, buttons: impl DeclareInto<MouseButtons, M>) -> Self {
+    self.declare_builtin_init(
+      buttons,
+      |this| this.get_mix_builtin_widget().mix_flags(),
+      |m, v| m.set_mouse_buttons_filter(v),
+    )
+  }
+
   /// Initializes the `Class` that should be applied to the widget.
   pub fn class<const M: usize>(self, cls: impl DeclareInto<ClassName, M>) -> Self {
     self.declare_builtin_init(cls, Self::get_class_widget, |c, cls| c.class = Some(cls))
@@ -801,6 +1033,22 @@ impl<T> FatObj<T> {
     self.declare_builtin_init(v, Self::get_text_style_widget, |m, v| m.text_style.overflow = v)
   }
 
+  /// Initializes the shadows of the text of this widget.
+  pub fn text_shadows<const M: usize>(self, v: impl DeclareInto<Vec<TextShadow>, M>) -> Self {
+    self.declare_builtin_init(v, Self::get_text_style_widget, |m, v| m.text_style.shadows = v)
+  }
+
+  /// Initializes the outline of the text of this widget.
+  pub fn text_stroke<const M: usize>(self, v: impl DeclareInto<Option<TextStroke>, M>) -> Self {
+    self.declare_builtin_init(v, Self::get_text_style_widget, |m, v| m.text_style.stroke = v)
+  }
+
+  /// Initializes the hyphenator to break the overflowed words of the text of
+  /// this widget.
+  pub fn text_hyphenator<const M: usize>(self, v: impl DeclareInto<Option<Hyphenator>, M>) -> Self {
+    self.declare_builtin_init(v, Self::get_text_style_widget, |m, v| m.text_style.hyphenator = v)
+  }
+
   /// Initializes the background of the widget.
   pub fn background<const M: usize>(self, v: impl DeclareInto<Option<Brush>, M>) -> Self {
     self.declare_builtin_init(v, Self::get_box_decoration_widget, |m, v| m.background = v)
@@ -827,7 +1075,7 @@ impl<T> FatObj<T> {
   }
 
   /// Initializes the cursor of the widget.
-  pub fn cursor<const M: usize>(self, v: impl DeclareInto<CursorIcon, M>) -> Self {
+  pub fn cursor<const M: usize>(self, v: impl DeclareInto<PointerCursor, M>) -> Self {
     self.declare_builtin_init(v, Self::get_cursor_widget, |m, v| m.cursor = v)
   }
 
@@ -846,6 +1094,13 @@ impl<T> FatObj<T> {
     self.declare_builtin_init(v, Self::get_scrollable_widget, |m, v| m.scrollable = v)
   }
 
+  /// Initializes the id that the scrollable widget saves its scroll position
+  /// by, it's restored when a widget of the same id is built again, see
+  /// [`ScrollRestoration`].
+  pub fn restoration_id<const M: usize>(self, v: impl DeclareInto<CowArc<str>, M>) -> Self {
+    self.declare_builtin_init(v, Self::get_scrollable_widget, |m, v| m.restoration_id = Some(v))
+  }
+
   /// Initializes the transformation of the widget.
   pub fn transform<const M: usize>(self, v: impl DeclareInto<Transform, M>) -> Self {
     self.declare_builtin_init(v, Self::get_transform_widget, |m, v| m.transform = v)
@@ -886,11 +1141,25 @@ impl<T> FatObj<T> {
     self.declare_builtin_init(v, Self::get_opacity_widget, |m, v| m.opacity = v)
   }
 
+  /// Initializes the order that the widget is painted and hit among its
+  /// siblings. The siblings of a higher `z_index` are painted above and hit
+  /// first, and the siblings of the same `z_index` keep their order in the
+  /// tree. It's `0` by default and doesn't change the layout order.
+  pub fn z_index<const M: usize>(self, v: impl DeclareInto<i32, M>) -> Self {
+    self.declare_builtin_init(v, Self::get_z_index_widget, |m, v| m.z_index = v)
+  }
+
   /// Initializes the tooltips of the widget.
   pub fn tooltips<const M: usize>(self, v: impl DeclareInto<CowArc<str>, M>) -> Self {
     self.declare_builtin_init(v, Self::get_tooltips_widget, |m, v| m.tooltips = v)
   }
 
+  /// Initializes the label of the widget for the assistive technologies, see
+  /// [`SemanticsLabel`].
+  pub fn semantics_label<const M: usize>(self, v: impl DeclareInto<CowArc<str>, M>) -> Self {
+    self.declare_builtin_init(v, Self::get_semantics_label_widget, |m, v| m.semantics_label = v)
+  }
+
   /// Initializes the `keep_alive` value of the `KeepAlive` widget.
   pub fn keep_alive<const M: usize>(mut self, v: impl DeclareInto<bool, M>) -> Self {
     let (v, o) = v.declare_into().unzip();
@@ -913,12 +1182,28 @@ impl<T> FatObj<T> {
     self
   }
 
+  /// Initializes the global key of the widget, see [`GlobalKey`].
+  pub fn global_key(mut self, key: GlobalKey) -> Self {
+    self.get_global_key_widget().silent().global_key = key;
+    self
+  }
+
   /// Initializes the track_id of the widget.
   pub fn track_id(mut self) -> Self {
     self.get_track_id_widget();
     self
   }
 
+  /// Initializes the name of the widget to tell it apart in the panic messages
+  /// and the tree dump. It's ignored in release builds, unless the
+  /// `widget-provenance` feature is enabled.
+  pub fn debug_name(mut self, name: impl Into<CowArc<str>>) -> Self {
+    if PROVENANCE_ENABLED {
+      self.provenance.debug_name = Some(name.into());
+    }
+    self
+  }
+
   fn declare_builtin_init<V: 'static, B: 'static, const M: usize>(
     mut self, init: impl DeclareInto<V, M>, get_builtin: impl FnOnce(&mut Self) -> &State<B>,
     set_value: fn(&mut B, V),
@@ -944,7 +1229,13 @@ pub trait FatDeclarerExtend: Sized {
 impl<T: FatDeclarerExtend> ObjDeclarer for FatObj<T> {
   type Target = FatObj<T::Target>;
 
-  fn finish(self) -> Self::Target { T::finish(self) }
+  #[track_caller]
+  fn finish(mut self) -> Self::Target {
+    if PROVENANCE_ENABLED {
+      self.provenance.location = Some(std::panic::Location::caller());
+    }
+    T::finish(self)
+  }
 }
 
 impl FatDeclarerExtend for () {
@@ -974,6 +1265,7 @@ impl<'a> FatObj<Widget<'a>> {
     compose_builtin_widgets!(
       host
         + [
+          global_key,
           track_id,
           padding,
           fitted_box,
@@ -986,12 +1278,14 @@ impl<'a> FatObj<Widget<'a>> {
           class,
           constrained_box,
           tooltips,
+          semantics_label,
           margin,
           cursor,
           mix_builtin,
           request_focus,
           transform,
           opacity,
+          z_index,
           visibility,
           h_align,
           v_align,
@@ -1004,6 +1298,15 @@ impl<'a> FatObj<Widget<'a>> {
     if let Some(h) = self.keep_alive_unsubscribe_handle {
       host = host.attach_anonymous_data(h);
     }
+    let provenance = self.provenance;
+    if !provenance.is_empty() {
+      host = host.on_build(move |id| {
+        BuildCtx::get_mut()
+          .tree_mut()
+          .provenance
+          .record(id, provenance)
+      });
+    }
     host
   }
 }
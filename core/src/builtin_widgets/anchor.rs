@@ -10,6 +10,15 @@ pub enum HAnchor {
   /// positions the widget's right edge x pixels to the left of the target's
   /// right edge.
   Right(Measure),
+
+  /// positions the widget's start edge x pixels to the end of the target's
+  /// start edge. The start is the left in a left-to-right layout and the
+  /// right in a right-to-left layout.
+  Start(Measure),
+
+  /// positions the widget's end edge x pixels to the start of the target's
+  /// end edge.
+  End(Measure),
 }
 
 /// Specifies the vertical position you want to anchor the widget.
@@ -28,6 +37,24 @@ impl HAnchor {
     match self {
       HAnchor::Left(x) => HAnchor::Left(f(x)),
       HAnchor::Right(x) => HAnchor::Right(f(x)),
+      HAnchor::Start(x) => HAnchor::Start(f(x)),
+      HAnchor::End(x) => HAnchor::End(f(x)),
+    }
+  }
+
+  /// Whether the anchor is relative to the logical start or end.
+  pub fn is_logical(&self) -> bool { matches!(self, HAnchor::Start(_) | HAnchor::End(_)) }
+
+  /// Resolve the logical `Start` and `End` to the physical `Left` and `Right`
+  /// by the text direction.
+  pub fn resolve(self, dir: TextDirection) -> Self {
+    let rtl = dir == TextDirection::RightToLeft;
+    match self {
+      HAnchor::Start(x) if rtl => HAnchor::Right(x),
+      HAnchor::Start(x) => HAnchor::Left(x),
+      HAnchor::End(x) if rtl => HAnchor::Left(x),
+      HAnchor::End(x) => HAnchor::Right(x),
+      physical => physical,
     }
   }
 }
@@ -57,6 +84,8 @@ impl Lerp for HAnchor {
     match (self, other) {
       (HAnchor::Left(x1), HAnchor::Left(x2)) => HAnchor::Left(x1.lerp(x2, t)),
       (HAnchor::Right(x1), HAnchor::Right(x2)) => HAnchor::Right(x1.lerp(x2, t)),
+      (HAnchor::Start(x1), HAnchor::Start(x2)) => HAnchor::Start(x1.lerp(x2, t)),
+      (HAnchor::End(x1), HAnchor::End(x2)) => HAnchor::End(x1.lerp(x2, t)),
       _ => *other,
     }
   }
@@ -107,6 +136,16 @@ impl Anchor {
     Self { x: Some(HAnchor::Right(x.into())), y: None }
   }
 
+  /// Return Anchor that positions the widget's start edge x pixels to the end
+  /// of the target's start edge, it follows the inherited [`TextDirection`].
+  pub fn start(x: impl Into<Measure>) -> Self {
+    Self { x: Some(HAnchor::Start(x.into())), y: None }
+  }
+
+  /// Return Anchor that positions the widget's end edge x pixels to the start
+  /// of the target's end edge, it follows the inherited [`TextDirection`].
+  pub fn end(x: impl Into<Measure>) -> Self { Self { x: Some(HAnchor::End(x.into())), y: None } }
+
   /// Return Anchor that positions the widget's top edge x pixels bellow the
   /// target's top edge.
   pub fn top(y: impl Into<Measure>) -> Self { Self { x: None, y: Some(VAnchor::Top(y.into())) } }
@@ -170,7 +209,11 @@ impl WrapRender for RelativeAnchor {
     ctx.update_position(ctx.widget_id(), Point::zero());
     let child_size = host.perform_layout(clamp, ctx);
 
-    let offset = self.anchor.into_pixel(child_size, clamp.max);
+    let mut anchor = self.anchor;
+    if let Some(x) = anchor.x.filter(HAnchor::is_logical) {
+      anchor.x = Some(x.resolve(ctx.text_direction()));
+    }
+    let offset = anchor.into_pixel(child_size, clamp.max);
 
     let pos = ctx.box_pos().unwrap_or_default();
     ctx.update_position(ctx.widget_id(), pos + Size::new(offset.x, offset.y));
@@ -179,10 +222,12 @@ impl WrapRender for RelativeAnchor {
 }
 
 impl HAnchor {
+  /// The offset of the widget, the logical anchors are taken as left to
+  /// right, [`resolve`](HAnchor::resolve) them first for other directions.
   pub fn into_pixel(self, width: f32, max_clamp: f32) -> f32 {
     match self {
-      HAnchor::Left(x) => x.into_pixel(max_clamp),
-      HAnchor::Right(x) => max_clamp - width - x.into_pixel(max_clamp),
+      HAnchor::Left(x) | HAnchor::Start(x) => x.into_pixel(max_clamp),
+      HAnchor::Right(x) | HAnchor::End(x) => max_clamp - width - x.into_pixel(max_clamp),
     }
   }
 }
@@ -293,4 +338,31 @@ mod test {
     .with_wnd_size(Size::new(500., 500.)),
     LayoutCase::new(&[0]).with_rect(ribir_geom::rect(40., 30., 100., 100.))
   );
+
+  fn logical_tester(anchor: Anchor, dir: TextDirection) -> WidgetTester {
+    WidgetTester::new(fn_widget! {
+      Provider::new(Box::new(Queryable(dir))).with_child(fn_widget! {
+        @MockBox { size: CHILD_SIZE, anchor }
+      })
+    })
+    .with_wnd_size(WND_SIZE)
+  }
+
+  widget_layout_test!(
+    start_ltr,
+    logical_tester(Anchor::start(1.), TextDirection::LeftToRight),
+    LayoutCase::default().with_x(1.)
+  );
+
+  widget_layout_test!(
+    start_rtl,
+    logical_tester(Anchor::start(1.), TextDirection::RightToLeft),
+    LayoutCase::default().with_x(49.)
+  );
+
+  widget_layout_test!(
+    end_rtl,
+    logical_tester(Anchor::end(1.), TextDirection::RightToLeft),
+    LayoutCase::default().with_x(1.)
+  );
 }
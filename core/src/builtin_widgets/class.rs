@@ -336,6 +336,7 @@ impl ClassNode {
     // it needs to be updated.
     new.update_track_id(orig_id);
     std::mem::swap(new, old);
+    tree.refresh_bloom(orig_id);
     if new_id == n_orig {
       // If applying the class does not generate additional widgets, the original
       // widget ID will include all new elements after the swap.
@@ -369,7 +370,10 @@ impl ClassNode {
             .borrow_mut()
             .single_range_replace(&old_rg, &new_rg)
         });
-      cls_holder.replace(new_id, tree);
+      match cls_holder {
+        Some(holder) => holder.replace(new_id, tree),
+        None => tree.recover(CoreError::OrphanWidget(child_id)),
+      }
     }
 
     if orig_id != child_id {
@@ -457,6 +461,11 @@ impl Query for ClassNode {
   fn query_write(&self, type_id: &QueryId) -> Option<QueryHandle> {
     self.inner().render.query_write(type_id)
   }
+
+  fn type_bloom(&self, bloom: &mut QueryBloom) {
+    self.inner().render.type_bloom(bloom);
+    bloom.insert(&QueryId::of::<DynInfo>());
+  }
 }
 
 impl PartialEq for OverrideClass {
@@ -1,10 +1,40 @@
-use crate::prelude::*;
+use crate::{prelude::*, window::WindowId};
 
 /// `Cursor` is an attribute to assign an `cursor` to a widget.
 
 #[derive(Default, Debug)]
 pub struct Cursor {
-  pub cursor: CursorIcon,
+  pub cursor: PointerCursor,
+}
+
+/// The cursor of the pointer, a standard icon or an image, see
+/// [`Cursor::from_image`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum PointerCursor {
+  Icon(CursorIcon),
+  Image(ImageCursor),
+}
+
+/// A cursor of an image, the `hotspot` is the point in the image that the
+/// pointer points to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageCursor {
+  pub image: Resource<PixelImage>,
+  pub hotspot: Point,
+}
+
+impl Default for PointerCursor {
+  fn default() -> Self { Self::Icon(CursorIcon::Default) }
+}
+
+impl From<CursorIcon> for PointerCursor {
+  #[inline]
+  fn from(icon: CursorIcon) -> Self { Self::Icon(icon) }
+}
+
+impl From<ImageCursor> for PointerCursor {
+  #[inline]
+  fn from(cursor: ImageCursor) -> Self { Self::Image(cursor) }
 }
 
 impl Declare for Cursor {
@@ -17,7 +47,7 @@ impl<'c> ComposeChild<'c> for Cursor {
   type Child = Widget<'c>;
   fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
     fn_widget! {
-      let save_cursor: Stateful<Option<CursorIcon>> = Stateful::new(None);
+      let save_cursor: Stateful<Option<PointerCursor>> = Stateful::new(None);
       let child = FatObj::new(child);
       @ $child {
         on_pointer_enter: move |e: &mut PointerEvent| {
@@ -25,18 +55,18 @@ impl<'c> ComposeChild<'c> for Cursor {
             && e.mouse_buttons() == MouseButtons::empty()
           {
             let wnd = e.window();
-            *$save_cursor.write() = Some(wnd.get_cursor());
-            wnd.set_cursor($this.get_cursor());
+            *$save_cursor.write() = Some(wnd.pointer_cursor());
+            wnd.set_pointer_cursor($this.get_cursor());
           }
         },
         on_pointer_leave: move |e: &mut PointerEvent| {
           if let Some(cursor) = $save_cursor.write().take() {
-            e.window().set_cursor(cursor);
+            e.window().set_pointer_cursor(cursor);
           }
         },
         on_disposed: move |e| {
           if let Some(cursor) = $save_cursor.write().take() {
-            e.window().set_cursor(cursor);
+            e.window().set_pointer_cursor(cursor);
           }
         },
       }
@@ -46,12 +76,74 @@ impl<'c> ComposeChild<'c> for Cursor {
 }
 
 impl Cursor {
-  fn get_cursor(&self) -> CursorIcon { self.cursor }
+  fn get_cursor(&self) -> PointerCursor { self.cursor.clone() }
+
+  /// A cursor of the `image`, the `hotspot` is the point in the image that the
+  /// pointer points to.
+  ///
+  /// The platform draws it if it can, otherwise the window hides the cursor of
+  /// the platform and paints the image over the widgets following the pointer.
+  pub fn from_image(image: PixelImage, hotspot: Point) -> PointerCursor {
+    ImageCursor { image: Resource::new(image), hotspot }.into()
+  }
+}
+
+/// Paints the image cursor over the widgets following the pointer, for the
+/// platforms that can't use an image as the cursor.
+#[derive(Default)]
+pub(crate) struct CursorSprite {
+  cursor: Option<ImageCursor>,
+  pos: Stateful<Point>,
+  overlay: Option<Overlay>,
+}
+
+impl CursorSprite {
+  /// Paint the `cursor` at `pos`, or stop painting if it's `None`.
+  pub(crate) fn set(&mut self, cursor: Option<ImageCursor>, pos: Point, wnd_id: WindowId) {
+    self.hide();
+    self.cursor = cursor;
+    self.move_to(pos, wnd_id);
+  }
+
+  /// Follow the pointer moved to `pos`.
+  pub(crate) fn move_to(&mut self, pos: Point, wnd_id: WindowId) {
+    let Some(ImageCursor { image, hotspot }) = self.cursor.clone() else { return };
+    *self.pos.write() = pos - hotspot.to_vector();
+    if self.overlay.is_some() {
+      return;
+    }
+    let Some(wnd) = AppCtx::get_window(wnd_id) else { return };
+    let pos = self.pos.clone_watcher();
+    let overlay = Overlay::new(
+      move || {
+        let image = image.clone();
+        let pos = pos.clone_watcher();
+        let w = fn_widget! {
+          // The cursor is under the pointer, it mustn't hide the widgets.
+          @IgnorePointer {
+            anchor: pipe!(Anchor::from_point(*$pos)),
+            @ { image }
+          }
+        };
+        w.into_widget()
+      },
+      OverlayStyle { auto_close_policy: AutoClosePolicy::NOT_AUTO_CLOSE, mask: None },
+    );
+    overlay.show(wnd);
+    self.overlay = Some(overlay);
+  }
+
+  /// Stop painting until the pointer moves in the window again.
+  pub(crate) fn hide(&mut self) {
+    if let Some(overlay) = self.overlay.take() {
+      overlay.close();
+    }
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use winit::event::{DeviceId, WindowEvent};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 
   use super::*;
   use crate::{reset_test_env, test_helper::*};
@@ -120,4 +212,76 @@ mod tests {
     wnd.run_frame_tasks();
     assert_eq!(wnd.get_cursor(), CursorIcon::Help);
   }
+
+  #[test]
+  fn image_cursor_fallback() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let image = PixelImage::new(vec![0; 16 * 16 * 4].into(), 16, 16, image::ColorFormat::Rgba8);
+      @MockBox {
+        size: Size::new(100., 100.),
+        cursor: Cursor::from_image(image, Point::new(4., 6.)),
+        on_tap: move |_| *$w_taps.write() += 1,
+      }
+    });
+    wnd.draw_frame();
+
+    let move_to = |wnd: &mut TestWindow, x: f64, y: f64| {
+      let device_id = unsafe { DeviceId::dummy() };
+      wnd
+        .dispatcher
+        .borrow_mut()
+        .dispatch(WindowEvent::CursorMoved { device_id, position: (x, y).into() }, 1.);
+      wnd.draw_frame();
+    };
+    let shell_cursor_visible = |wnd: &TestWindow| {
+      let shell = wnd.shell_wnd().borrow();
+      shell
+        .as_any()
+        .downcast_ref::<TestShellWindow>()
+        .unwrap()
+        .cursor_visible
+    };
+    let sprite_pos = |wnd: &TestWindow| {
+      let tree = wnd.tree();
+      let overlays = tree.root().children(tree).count() - 1;
+      (overlays > 0).then(|| wnd.layout_info_by_path(&[1]).unwrap().pos)
+    };
+
+    move_to(&mut wnd, 30., 40.);
+    assert!(matches!(wnd.pointer_cursor(), PointerCursor::Image(_)));
+    assert!(!shell_cursor_visible(&wnd));
+    assert_eq!(sprite_pos(&wnd), Some(Point::new(26., 34.)));
+
+    move_to(&mut wnd, 50., 60.);
+    assert_eq!(sprite_pos(&wnd), Some(Point::new(46., 54.)));
+
+    // The sprite doesn't take the hits.
+    wnd.process_mouse_input(unsafe { DeviceId::dummy() }, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(
+      unsafe { DeviceId::dummy() },
+      ElementState::Released,
+      MouseButton::Left,
+    );
+    wnd.draw_frame();
+    assert_eq!(*taps.read(), 1);
+
+    wnd
+      .dispatcher
+      .borrow_mut()
+      .dispatch(WindowEvent::CursorLeft { device_id: unsafe { DeviceId::dummy() } }, 1.);
+    wnd.draw_frame();
+    assert_eq!(sprite_pos(&wnd), None);
+
+    move_to(&mut wnd, 10., 10.);
+    assert_eq!(sprite_pos(&wnd), Some(Point::new(6., 4.)));
+
+    // Back to the icon when the pointer leaves the widget.
+    move_to(&mut wnd, 200., 200.);
+    assert_eq!(wnd.pointer_cursor(), PointerCursor::Icon(CursorIcon::Default));
+    assert!(shell_cursor_visible(&wnd));
+    assert_eq!(sprite_pos(&wnd), None);
+  }
 }
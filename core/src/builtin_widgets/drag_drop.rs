@@ -0,0 +1,447 @@
+use std::{any::Any, rc::Rc};
+
+use crate::prelude::*;
+
+/// A widget that starts to drag its `payload` after its child is long
+/// pressed, the drag is dropped on a [`DropTarget`] of the same payload type.
+///
+/// The drag starts only if the pointer holds still for the long press of the
+/// [`GestureSettings`], a pointer that moves farther than the slop before it
+/// is a scroll or a swipe of the widgets around, not a drag. Once the drag
+/// starts, it takes all the moves of the pointer until the pointer is
+/// released, and the `feedback` widget follows the pointer on the overlay.
+///
+/// # Example
+///
+/// ```
+/// use ribir::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct FileItem(&'static str);
+///
+/// let _w = fn_widget! {
+///   @Row {
+///     @Draggable {
+///       payload: FileItem("notes.txt"),
+///       feedback: fn_widget! { @Text { text: "notes.txt" } },
+///       @Text { text: "notes.txt" }
+///     }
+///     @DropTarget::<FileItem> {
+///       on_drop: |file: &FileItem| println!("{} is deleted", file.0),
+///       @Text { text: "Trash" }
+///     }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct Draggable<T>
+where
+  T: 'static,
+{
+  /// The data the drag carries to the drop target.
+  #[declare(strict)]
+  pub payload: T,
+  /// The widget that follows the pointer while dragging.
+  #[declare(default = GenWidget::new(|| Void.into_widget()))]
+  pub feedback: GenWidget,
+  #[declare(skip)]
+  dragging: bool,
+  #[declare(skip)]
+  long_press: Option<TaskHandle<NormalReturn<()>>>,
+}
+
+/// A widget that receives the drags whose payload is of type `T`, the drags
+/// of other payloads pass it over as if it's not there.
+#[derive(Declare)]
+pub struct DropTarget<T>
+where
+  T: 'static,
+{
+  /// Called when a drag of the payload moves into the widget.
+  #[declare(default)]
+  pub on_drag_enter: DragHandler<T>,
+  /// Called when a drag of the payload moves out of the widget.
+  #[declare(default)]
+  pub on_drag_leave: DragHandler<T>,
+  /// Called every time a drag of the payload moves over the widget.
+  #[declare(default)]
+  pub on_drag_over: DragHandler<T>,
+  /// Called when a drag of the payload is dropped on the widget.
+  #[declare(default)]
+  pub on_drop: DragHandler<T>,
+  #[declare(skip)]
+  accepting: bool,
+}
+
+/// The callback of the drag events of a [`DropTarget`], it receives the
+/// payload of the drag.
+pub struct DragHandler<T>(Box<dyn FnMut(&T)>);
+
+impl<T> Default for DragHandler<T> {
+  fn default() -> Self { Self(Box::new(|_| {})) }
+}
+
+impl<T, F: FnMut(&T) + 'static> From<F> for DragHandler<T> {
+  fn from(f: F) -> Self { Self(Box::new(f)) }
+}
+
+impl<T> Draggable<T> {
+  /// Whether the widget is being dragged.
+  pub fn is_dragging(&self) -> bool { self.dragging }
+}
+
+impl<T> DropTarget<T> {
+  /// Whether a drag that the widget accepts is over it, use it to highlight
+  /// the widget.
+  pub fn is_accepting(&self) -> bool { self.accepting }
+}
+
+impl<'c, T: Clone + 'static> ComposeChild<'c> for Draggable<T> {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    let source = this.clone_writer();
+    let w = fn_widget! {
+      let child = FatObj::new(child);
+      @ $child {
+        on_pointer_down: move |e| {
+          let wnd = e.window();
+          if wnd.is_dragging() {
+            return;
+          }
+          let press = e.global_pos();
+          let pointer = e.point_type.clone();
+          let settings = GestureSettings::of(e);
+          let this = source.clone_writer();
+          let h = observable::timer((), settings.long_press, AppCtx::scheduler())
+            .subscribe(move |_| {
+              this.silent().long_press = None;
+              // The pointer may be grabbed by others, read its state from the window.
+              let held = {
+                let dispatcher = wnd.dispatcher.borrow();
+                let info = &dispatcher.info;
+                !info.mouse_buttons().is_empty()
+                  && !settings.is_drag(&pointer, info.global_pos() - press)
+              };
+              if held && !wnd.is_dragging() {
+                start_drag(&this, &wnd, press);
+              }
+            });
+          if let Some(old) = source.silent().long_press.replace(h) {
+            old.unsubscribe();
+          }
+        },
+        on_pointer_up: move |_| cancel_long_press(&mut $this.silent().long_press),
+        on_disposed: move |_| cancel_long_press(&mut $this.silent().long_press),
+      }
+    };
+    w.into_widget()
+  }
+}
+
+fn cancel_long_press(h: &mut Option<TaskHandle<NormalReturn<()>>>) {
+  if let Some(h) = h.take() {
+    h.unsubscribe();
+  }
+}
+
+fn start_drag<T: Clone + 'static>(
+  this: &impl StateWriter<Value = Draggable<T>>, wnd: &Sc<Window>, pos: Point,
+) {
+  let (payload, feedback) = {
+    let this = this.read();
+    (Rc::new(this.payload.clone()) as Rc<dyn Any>, this.feedback.clone())
+  };
+  let source = this.clone_writer();
+  let on_end = Box::new(move || source.write().dragging = false);
+  wnd.start_drag(DragSession::new(payload, feedback, pos, on_end), wnd.clone());
+  this.write().dragging = true;
+}
+
+impl<'c, T: 'static> ComposeChild<'c> for DropTarget<T> {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    let handle = DropTargetHandle(Rc::new(this.clone_writer()));
+    child.attach_data(Box::new(Queryable(handle)))
+  }
+}
+
+#[derive(Clone, Copy)]
+enum DragEvent {
+  Enter,
+  Leave,
+  Over,
+  Drop,
+}
+
+trait AnyDropTarget {
+  fn accept(&self, payload: &dyn Any) -> bool;
+
+  fn notify(&self, event: DragEvent, payload: &dyn Any);
+}
+
+impl<T: 'static, W: StateWriter<Value = DropTarget<T>>> AnyDropTarget for W {
+  fn accept(&self, payload: &dyn Any) -> bool { payload.is::<T>() }
+
+  fn notify(&self, event: DragEvent, payload: &dyn Any) {
+    let Some(payload) = payload.downcast_ref::<T>() else { return };
+    let accepting = matches!(event, DragEvent::Enter | DragEvent::Over);
+    if self.read().accepting != accepting {
+      self.write().accepting = accepting;
+    }
+    let mut this = self.silent();
+    let handler = match event {
+      DragEvent::Enter => &mut this.on_drag_enter,
+      DragEvent::Leave => &mut this.on_drag_leave,
+      DragEvent::Over => &mut this.on_drag_over,
+      DragEvent::Drop => &mut this.on_drop,
+    };
+    (handler.0)(payload);
+  }
+}
+
+#[derive(Clone)]
+struct DropTargetHandle(Rc<dyn AnyDropTarget>);
+
+/// The drag in progress of a window.
+pub(crate) struct DragSession {
+  payload: Rc<dyn Any>,
+  feedback: GenWidget,
+  pos: Stateful<Point>,
+  overlay: Option<Overlay>,
+  /// The drop target under the pointer that accepts the payload.
+  target: Option<(WidgetId, DropTargetHandle)>,
+  on_end: Box<dyn FnOnce()>,
+}
+
+impl DragSession {
+  fn new(payload: Rc<dyn Any>, feedback: GenWidget, pos: Point, on_end: Box<dyn FnOnce()>) -> Self {
+    Self { payload, feedback, pos: Stateful::new(pos), overlay: None, target: None, on_end }
+  }
+}
+
+impl Window {
+  /// Whether a [`Draggable`] is being dragged in the window.
+  pub fn is_dragging(&self) -> bool { self.drag.borrow().is_some() }
+
+  fn start_drag(&self, mut session: DragSession, wnd: Sc<Window>) {
+    let (feedback, pos) = (session.feedback.clone(), session.pos.clone_watcher());
+    let overlay = Overlay::new(
+      move || {
+        let feedback = feedback.clone();
+        let pos = pos.clone_watcher();
+        let w = fn_widget! {
+          // The feedback is under the pointer, it mustn't hide the targets.
+          @IgnorePointer {
+            anchor: pipe!(Anchor::from_point(*$pos)),
+            @ { feedback.gen_widget() }
+          }
+        };
+        w.into_widget()
+      },
+      OverlayStyle { auto_close_policy: AutoClosePolicy::NOT_AUTO_CLOSE, mask: None },
+    );
+    overlay.show(wnd);
+    session.overlay = Some(overlay);
+    *self.drag.borrow_mut() = Some(session);
+  }
+
+  /// Move the drag to `pos`, the drop targets it leaves and enters are
+  /// notified.
+  pub(crate) fn drag_to(&self, pos: Point) {
+    let Some((payload, old)) = self.drag.borrow().as_ref().map(|s| {
+      *s.pos.write() = pos;
+      (s.payload.clone(), s.target.clone())
+    }) else {
+      return;
+    };
+
+    let hit = self.dispatcher.borrow().hit_widget();
+    let tree = self.tree();
+    let new = hit.and_then(|hit| {
+      hit.ancestors(tree).find_map(|id| {
+        id.query_all_iter::<DropTargetHandle>(tree)
+          .find(|t| t.0.accept(&*payload))
+          .map(|t| (id, t.clone()))
+      })
+    });
+
+    if old.as_ref().map(|t| t.0) != new.as_ref().map(|t| t.0) {
+      if let Some((_, old)) = old {
+        old.0.notify(DragEvent::Leave, &*payload);
+      }
+      if let Some((_, new)) = &new {
+        new.0.notify(DragEvent::Enter, &*payload);
+      }
+    }
+    if let Some((_, new)) = &new {
+      new.0.notify(DragEvent::Over, &*payload);
+    }
+    if let Some(session) = self.drag.borrow_mut().as_mut() {
+      session.target = new;
+    }
+  }
+
+  /// Drop the drag at `pos` to the drop target under it.
+  pub(crate) fn drop_drag(&self, pos: Point) {
+    self.drag_to(pos);
+    let Some(session) = self.drag.borrow_mut().take() else { return };
+    if let Some((_, target)) = session.target {
+      target
+        .0
+        .notify(DragEvent::Drop, &*session.payload);
+    }
+    if let Some(overlay) = session.overlay {
+      overlay.close();
+    }
+    (session.on_end)();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, ElementState, MouseButton, WindowEvent},
+  };
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[derive(Clone, Debug, PartialEq)]
+  struct FileItem(&'static str);
+
+  #[derive(Clone)]
+  struct Folder;
+
+  thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+  }
+
+  fn log<T>(name: &'static str, event: &'static str) -> impl FnMut(&T) {
+    move |_| LOG.with_borrow_mut(|log| log.push(format!("{name} {event}")))
+  }
+
+  fn take_log() -> Vec<String> { LOG.with_borrow_mut(std::mem::take) }
+
+  #[allow(deprecated)]
+  fn move_to(wnd: &mut TestWindow, x: f32) {
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id: unsafe { DeviceId::dummy() },
+      position: LogicalPosition::new(x, 25.).to_physical(1.),
+    });
+    wnd.draw_frame();
+  }
+
+  #[allow(deprecated)]
+  fn press(wnd: &mut TestWindow, state: ElementState) {
+    wnd.process_mouse_input(unsafe { DeviceId::dummy() }, state, MouseButton::Left);
+    wnd.draw_frame();
+  }
+
+  /// A draggable file at `0..50`, a file target at `50..100` and a folder
+  /// target at `100..150`.
+  type Record<T> = Watcher<Reader<T>>;
+
+  fn drag_wnd() -> (TestWindow, Record<Vec<FileItem>>, Record<bool>) {
+    let (dropped, w_dropped) = split_value(vec![]);
+    let (accepting, w_accepting) = split_value(false);
+    let wnd = TestWindow::new(fn_widget! {
+      let file_target = @DropTarget::<FileItem> {
+        on_drag_enter: log("file", "enter"),
+        on_drag_leave: log("file", "leave"),
+        on_drag_over: log("file", "over"),
+        on_drop: move |f: &FileItem| $w_dropped.write().push(f.clone()),
+      };
+      watch!($file_target.is_accepting())
+        .subscribe(move |a| *$w_accepting.write() = a);
+      @MockMulti {
+        @Draggable {
+          payload: FileItem("notes.txt"),
+          @MockBox { size: Size::new(50., 50.) }
+        }
+        @ $file_target { @MockBox { size: Size::new(50., 50.) } }
+        @DropTarget::<Folder> {
+          on_drag_enter: log("folder", "enter"),
+          on_drag_leave: log("folder", "leave"),
+          on_drag_over: log("folder", "over"),
+          on_drop: log("folder", "drop"),
+          @MockBox { size: Size::new(50., 50.) }
+        }
+      }
+    });
+    (wnd, dropped, accepting)
+  }
+
+  fn start_drag(wnd: &mut TestWindow) {
+    wnd.draw_frame();
+    move_to(wnd, 25.);
+    press(wnd, ElementState::Pressed);
+    wnd.advance_time(GestureSettings::default().long_press);
+    assert!(wnd.is_dragging());
+  }
+
+  #[test]
+  fn drop_on_matching_target() {
+    reset_test_env!();
+
+    let (mut wnd, dropped, accepting) = drag_wnd();
+    start_drag(&mut wnd);
+
+    move_to(&mut wnd, 75.);
+    assert!(*accepting.read());
+    move_to(&mut wnd, 80.);
+    move_to(&mut wnd, 125.);
+    assert!(!*accepting.read());
+    move_to(&mut wnd, 75.);
+    press(&mut wnd, ElementState::Released);
+
+    assert!(!wnd.is_dragging());
+    assert!(!*accepting.read());
+    assert_eq!(*dropped.read(), [FileItem("notes.txt")]);
+    assert_eq!(
+      take_log(),
+      [
+        "file enter",
+        "file over",
+        "file over",
+        "file leave",
+        "file enter",
+        "file over",
+        "file over"
+      ]
+    );
+  }
+
+  #[test]
+  fn ignored_by_other_payload_target() {
+    reset_test_env!();
+
+    let (mut wnd, dropped, _) = drag_wnd();
+    start_drag(&mut wnd);
+
+    move_to(&mut wnd, 125.);
+    press(&mut wnd, ElementState::Released);
+
+    assert!(!wnd.is_dragging());
+    assert!(dropped.read().is_empty());
+    assert!(take_log().is_empty());
+  }
+
+  #[test]
+  fn moved_press_is_not_drag() {
+    reset_test_env!();
+
+    let (mut wnd, ..) = drag_wnd();
+    wnd.draw_frame();
+    move_to(&mut wnd, 25.);
+    press(&mut wnd, ElementState::Pressed);
+    // The pointer scrolls away before the long press.
+    move_to(&mut wnd, 40.);
+    wnd.advance_time(GestureSettings::default().long_press);
+    assert!(!wnd.is_dragging());
+  }
+}
@@ -12,6 +12,59 @@ pub struct FocusScope {
   /// skip the whole subtree.
   #[declare(default)]
   pub can_focus: bool,
+
+  /// If true, the scope is a single stop of the `Tab` key, `Tab` moves the
+  /// focus into the scope, and from inside the scope out to the next one. Use
+  /// the arrow keys to move the focus in the scope, see `arrow_navigation`.
+  #[declare(default)]
+  pub skip_descendants_on_tab: bool,
+
+  /// How the arrow keys move the focus between the descendants. The innermost
+  /// scope that navigates by the arrow keys handles them first.
+  #[declare(default)]
+  pub arrow_navigation: ArrowNav,
+
+  /// If true, the arrow key moves the focus to the other side of the scope if
+  /// there is nothing in its direction, e.g. from the end of a row to its
+  /// start.
+  #[declare(default)]
+  pub arrow_wrap: bool,
+}
+
+/// How the arrow keys move the focus in a [`FocusScope`], to the focusable
+/// widget nearest to the focusing one in the direction of the key, by their
+/// layout rects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrowNav {
+  /// The arrow keys don't move the focus.
+  #[default]
+  None,
+  /// All the arrow keys move the focus, for the widgets in a grid.
+  Grid,
+  /// Only the arrow keys along the axis move the focus, for the widgets in a
+  /// row or a column.
+  Linear(NavAxis),
+}
+
+/// The axis of the arrow keys that [`ArrowNav::Linear`] uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavAxis {
+  /// The left and right arrow keys.
+  Horizontal,
+  /// The up and down arrow keys.
+  Vertical,
+}
+
+impl ArrowNav {
+  /// Whether the arrow key moving by `dir` moves the focus.
+  pub(crate) fn accept(self, dir: Vector) -> bool {
+    match self {
+      ArrowNav::None => false,
+      ArrowNav::Grid => true,
+      ArrowNav::Linear(NavAxis::Horizontal) => dir.y == 0.,
+      ArrowNav::Linear(NavAxis::Vertical) => dir.x == 0.,
+    }
+  }
 }
 
 impl<'c> ComposeChild<'c> for FocusScope {
@@ -35,6 +88,7 @@ mod tests {
   use winit::{
     dpi::LogicalPosition,
     event::{DeviceId, ElementState, MouseButton, WindowEvent},
+    keyboard::NativeKeyCode,
   };
 
   use super::*;
@@ -200,4 +254,70 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(*result.read(), 2);
   }
+
+  #[test]
+  fn arrow_navigation_grid() {
+    reset_test_env!();
+
+    let grid = |wrap: bool| {
+      fn_widget! {
+        @MockMulti {
+          @FocusScope {
+            arrow_navigation: ArrowNav::Grid,
+            arrow_wrap: wrap,
+            skip_descendants_on_tab: true,
+            @MockStack {
+              @ {
+                (0..9).map(|i| @MockBox {
+                  size: Size::new(10., 10.),
+                  tab_index: 0i16,
+                  anchor: Anchor::left_top((i % 3) as f32 * 20., (i / 3) as f32 * 20.),
+                })
+              }
+            }
+          }
+          @MockBox { size: Size::new(10., 10.), tab_index: 0i16 }
+        }
+      }
+    };
+    let press = |wnd: &TestWindow, key: NamedKey| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+        VirtualKey::Named(key),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.run_frame_tasks();
+      wnd.focus_mgr.borrow().focusing()
+    };
+
+    for wrap in [false, true] {
+      let mut wnd = TestWindow::new(grid(wrap));
+      wnd.draw_frame();
+      let tree = wnd.tree();
+      let scope = tree.content_root().first_child(tree).unwrap();
+      let cells: Vec<_> = scope.children(tree).collect();
+      let after = scope.next_sibling(tree).unwrap();
+
+      wnd.focus_mgr.borrow_mut().focus(cells[0], tree);
+      assert_eq!(press(&wnd, NamedKey::ArrowRight), Some(cells[1]));
+      assert_eq!(press(&wnd, NamedKey::ArrowDown), Some(cells[4]));
+      assert_eq!(press(&wnd, NamedKey::ArrowDown), Some(cells[7]));
+      assert_eq!(press(&wnd, NamedKey::ArrowRight), Some(cells[8]));
+      assert_eq!(press(&wnd, NamedKey::ArrowUp), Some(cells[5]));
+      assert_eq!(press(&wnd, NamedKey::ArrowLeft), Some(cells[4]));
+      assert_eq!(press(&wnd, NamedKey::ArrowLeft), Some(cells[3]));
+
+      // At the edge of the grid.
+      let expected = if wrap { cells[5] } else { cells[3] };
+      assert_eq!(press(&wnd, NamedKey::ArrowLeft), Some(expected));
+      let expected = if wrap { cells[2] } else { cells[6] };
+      press(&wnd, NamedKey::ArrowDown);
+      assert_eq!(press(&wnd, NamedKey::ArrowDown), Some(expected));
+
+      // `Tab` exits the scope.
+      assert_eq!(press(&wnd, NamedKey::Tab), Some(after));
+    }
+  }
 }
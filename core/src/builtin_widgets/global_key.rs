@@ -0,0 +1,196 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::prelude::*;
+
+/// A key to find a widget in its window.
+///
+/// Create a key, attach it to a widget by the `global_key` builtin field, and
+/// find the widget by [`Window::find_by_key`] later. The key follows the
+/// widget when it's regenerated by a pipe, as long as the new widget has the
+/// same key, and it resolves to `None` after the widget is disposed.
+///
+/// A key should be attached to only one widget that is in a window at a time.
+///
+/// ```rust
+/// use ribir_core::prelude::*;
+///
+/// let row_42 = GlobalKey::new();
+/// let c_row_42 = row_42.clone();
+/// let _w = fn_widget! {
+///   @Text {
+///     text: "row 42",
+///     global_key: c_row_42.clone(),
+///     on_tap: move |e| {
+///       let id = e.window().find_by_key(&row_42);
+///       assert_eq!(id, Some(e.current_target()));
+///     }
+///   }
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlobalKey(usize);
+
+/// The builtin widget that registers its global key to the window.
+#[derive(Default)]
+pub struct GlobalKeyWidget {
+  pub global_key: GlobalKey,
+}
+
+impl GlobalKey {
+  /// Create a new key that differs from all the others.
+  pub fn new() -> Self {
+    static ID: AtomicUsize = AtomicUsize::new(0);
+    Self(ID.fetch_add(1, Ordering::Relaxed))
+  }
+}
+
+impl Default for GlobalKey {
+  fn default() -> Self { Self::new() }
+}
+
+impl Declare for GlobalKeyWidget {
+  type Builder = FatObj<()>;
+  #[inline]
+  fn declarer() -> Self::Builder { FatObj::new(()) }
+}
+
+impl<'c> ComposeChild<'c> for GlobalKeyWidget {
+  type Child = Widget<'c>;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    // The key is fixed after the widget is declared.
+    let key = this.read().global_key.clone();
+    let c_key = key.clone();
+    let mut w = FatObj::new(child);
+    let track_id = w.get_track_id_widget().read().track_id();
+    w.on_mounted(move |e| e.window().register_global_key(key, track_id))
+      .on_disposed(move |e| {
+        e.window()
+          .unregister_global_key(&c_key, e.current_target())
+      })
+      .into_widget()
+  }
+}
+
+impl Window {
+  /// Return the id of the widget attached with the `key`, or `None` if no
+  /// widget in this window has the key.
+  pub fn find_by_key(&self, key: &GlobalKey) -> Option<WidgetId> {
+    let tree = self.tree();
+    self
+      .global_keys
+      .borrow()
+      .get(key)
+      .and_then(TrackId::get)
+      .filter(|id| !id.is_dropped(tree))
+  }
+
+  pub(crate) fn register_global_key(&self, key: GlobalKey, track_id: TrackId) {
+    let mut keys = self.global_keys.borrow_mut();
+    if cfg!(debug_assertions) {
+      let tree = self.tree();
+      let exist = keys
+        .get(&key)
+        .and_then(TrackId::get)
+        .filter(|id| *id != track_id.get().unwrap())
+        .filter(|id| !id.is_dropped(tree) && tree.root().ancestor_of(*id, tree));
+      if exist.is_some() {
+        panic!("The global key `{key:?}` is attached to more than one widget in a window.");
+      }
+    }
+    keys.insert(key, track_id);
+  }
+
+  pub(crate) fn unregister_global_key(&self, key: &GlobalKey, id: WidgetId) {
+    let mut keys = self.global_keys.borrow_mut();
+    if keys
+      .get(key)
+      .is_some_and(|track_id| track_id.get() == Some(id))
+    {
+      keys.remove(key);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn find_across_pipe_rebuild() {
+    reset_test_env!();
+
+    let key = GlobalKey::new();
+    let c_key = key.clone();
+    let (trigger, w_trigger) = split_value(0);
+    let w = fn_widget! {
+      let key = c_key.clone();
+      @MockMulti {
+        @ {
+          pipe!(*$trigger).map(move |_| @MockBox {
+            size: Size::new(10., 10.),
+            global_key: key.clone(),
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let first = wnd.find_by_key(&key);
+    assert!(first.is_some());
+
+    *w_trigger.write() += 1;
+    wnd.draw_frame();
+    let second = wnd.find_by_key(&key);
+    assert!(second.is_some());
+    assert_ne!(first, second);
+    assert_eq!(wnd.widget_size(second.unwrap()), Some(Size::new(10., 10.)));
+  }
+
+  #[test]
+  fn none_after_removed() {
+    reset_test_env!();
+
+    let key = GlobalKey::new();
+    let c_key = key.clone();
+    let (show, w_show) = split_value(true);
+    let w = fn_widget! {
+      let key = c_key.clone();
+      @MockMulti {
+        @ {
+          pipe!(*$show).map(move |show| show.then(|| @MockBox {
+            size: Size::zero(),
+            global_key: key.clone(),
+          }))
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    assert!(wnd.find_by_key(&key).is_some());
+
+    *w_show.write() = false;
+    wnd.draw_frame();
+    assert_eq!(wnd.find_by_key(&key), None);
+  }
+
+  #[test]
+  #[cfg(debug_assertions)]
+  #[should_panic(expected = "is attached to more than one widget")]
+  fn panic_on_duplicate_key() {
+    reset_test_env!();
+
+    let key = GlobalKey::new();
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::zero(), global_key: key.clone() }
+        @MockBox { size: Size::zero(), global_key: key.clone() }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+  }
+}
@@ -0,0 +1,163 @@
+use crate::{prelude::*, wrap_render::*};
+
+/// A widget that enlarges the area where its child receives pointer hits,
+/// without changing the layout or painting of the child.
+///
+/// The hit area is the box of the child outset by `slop`, and then expanded
+/// around its center to at least `min_size`.
+#[derive(Declare, Clone, Default)]
+pub struct HitSlop {
+  /// The extra space around the child that still receives hits.
+  #[declare(default)]
+  pub slop: EdgeInsets,
+  /// The minimum size of the hit area.
+  #[declare(default)]
+  pub min_size: Size,
+}
+
+impl_compose_child_for_wrap_render!(HitSlop);
+
+impl WrapRender for HitSlop {
+  #[inline]
+  fn perform_layout(&self, clamp: BoxClamp, host: &dyn Render, ctx: &mut LayoutCtx) -> Size {
+    host.perform_layout(clamp, ctx)
+  }
+
+  fn hit_test(&self, host: &dyn Render, ctx: &HitTestCtx, pos: Point) -> HitTest {
+    let mut hit = host.hit_test(ctx, pos);
+    if !hit.hit {
+      hit.hit = ctx
+        .box_rect()
+        .is_some_and(|rect| self.hit_rect(rect).contains(pos));
+    }
+    hit
+  }
+
+  fn hit_bounds(&self, host: &dyn Render, ctx: &HitTestCtx) -> Option<Rect> {
+    let bounds = host.hit_bounds(ctx)?;
+    Some(
+      ctx
+        .box_rect()
+        .map_or(bounds, |rect| bounds.union(&self.hit_rect(rect))),
+    )
+  }
+}
+
+impl HitSlop {
+  /// The hit area of a widget that has the box of `rect`.
+  pub fn hit_rect(&self, rect: Rect) -> Rect {
+    let EdgeInsets { left, right, bottom, top, .. } = self.slop;
+    let mut rect = Rect::new(
+      Point::new(rect.min_x() - left, rect.min_y() - top),
+      Size::new(rect.width() + left + right, rect.height() + top + bottom),
+    );
+    let dw = (self.min_size.width - rect.width()).max(0.);
+    let dh = (self.min_size.height - rect.height()).max(0.);
+    rect.origin -= Vector::new(dw / 2., dh / 2.);
+    rect.size += Size::new(dw, dh);
+    rect
+  }
+}
+
+/// A widget that enlarges the hit area of its child to the
+/// [`TouchTargetSize`] of the theme in a touch-first window, see
+/// [`Window::set_touch_first`].
+///
+/// The built-in controls use it, so they are easy to tap with a finger
+/// without looking bigger.
+#[derive(Declare)]
+pub struct TouchTarget;
+
+impl<'c> ComposeChild<'c> for TouchTarget {
+  type Child = Widget<'c>;
+
+  fn compose_child(_: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    rdl! {
+      let target = Theme::of(BuildCtx::get()).touch_target_size.0;
+      let media = BuildCtx::get().media();
+      @HitSlop {
+        min_size: pipe!(if $media.touch { target } else { Size::zero() }),
+        @ { child }
+      }
+    }
+    .into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, ElementState, MouseButton, WindowEvent},
+  };
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn tap_at(wnd: &mut TestWindow, x: f32, y: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: LogicalPosition::new(x, y).to_physical(1.),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn slop_hit_outside_box() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(20., 20.) }
+        @HitSlop {
+          slop: EdgeInsets::all(10.),
+          @MockBox {
+            size: Size::new(10., 10.),
+            on_tap: move |_| *$w_taps.write() += 1,
+          }
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    tap_at(&mut wnd, 25., 15.);
+    assert_eq!(*taps.read(), 1);
+    tap_at(&mut wnd, 25., 25.);
+    assert_eq!(*taps.read(), 1);
+  }
+
+  #[test]
+  fn touch_target_only_for_touch() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(40., 40.) }
+        @TouchTarget {
+          @MockBox {
+            size: Size::new(20., 20.),
+            on_tap: move |_| *$w_taps.write() += 1,
+          }
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    // Outside the painted bounds, but inside the default 48x48 target.
+    tap_at(&mut wnd, 50., 30.);
+    assert_eq!(*taps.read(), 0);
+
+    wnd.set_touch_first(true);
+    wnd.draw_frame();
+    tap_at(&mut wnd, 50., 30.);
+    assert_eq!(*taps.read(), 1);
+    // The layout is not changed.
+    wnd.assert_root_size(Size::new(60., 40.));
+  }
+}
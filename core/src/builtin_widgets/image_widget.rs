@@ -1,3 +1,7 @@
+use std::{cell::Cell, future::Future, rc::Rc};
+
+use futures::{FutureExt, future::LocalBoxFuture};
+
 use crate::prelude::*;
 
 impl Render for Resource<PixelImage> {
@@ -16,3 +20,201 @@ impl Render for Resource<PixelImage> {
     }
   }
 }
+
+/// An image shared through the [`ImageCache`] of the application. The images
+/// with the same key share one load and one decoded image, and it shows
+/// nothing until the image is loaded.
+///
+/// The image is decoded in the [`DecodeSize`] of its laid out size, so a large
+/// image shown small is downscaled, and it's decoded again if it grows larger
+/// than that size. The loader gets the decode size as a hint, it may decode a
+/// smaller image by itself, otherwise the decoded image is downscaled.
+///
+/// # Example
+///
+/// ```no_run
+/// use ribir_core::prelude::*;
+///
+/// let _w = fn_widget! {
+///   Image::from_cache("logo.png", |_| async {
+///     let data = std::fs::read("logo.png").ok()?;
+///     Some(PixelImage::from_png(&data))
+///   })
+/// };
+/// ```
+pub struct Image {
+  key: ImageKey,
+  loader: Rc<ImageLoader>,
+  cache_original: bool,
+  prefetch: bool,
+}
+
+type ImageLoader = dyn Fn(DecodeSize) -> LocalBoxFuture<'static, Option<PixelImage>>;
+
+/// The image source shared by the renders that an [`Image`] builds.
+struct ImageSource {
+  key: ImageKey,
+  loader: Rc<ImageLoader>,
+  cache_original: bool,
+  /// The largest size that the image is requested to load in.
+  requested: Cell<Option<DecodeSize>>,
+  loaded: Stateful<Option<(DecodedImage, DecodeSize)>>,
+}
+
+/// The render of [`Image`], it requests to decode the image in the size it's
+/// laid out in, and marks the image visible in the cache.
+struct ImageRender {
+  src: Rc<ImageSource>,
+  loaded: Option<(DecodedImage, DecodeSize)>,
+}
+
+impl Image {
+  /// Show the image of the `key` in the cache, or load it by the `loader` in
+  /// a [`DecodeSize`]. The `loader` isn't polled if the image is cached or
+  /// being loaded in that size.
+  pub fn from_cache<F>(key: impl Into<ImageKey>, loader: impl Fn(DecodeSize) -> F + 'static) -> Self
+  where
+    F: Future<Output = Option<PixelImage>> + 'static,
+  {
+    let loader: Rc<ImageLoader> = Rc::new(move |size| loader(size).boxed_local());
+    Self { key: key.into(), loader, cache_original: false, prefetch: false }
+  }
+
+  /// Show an image asset embedded by [`include_asset!`]. The image is loaded
+  /// once, an overridden asset isn't loaded again after it's cached.
+  #[cfg(feature = "png")]
+  pub fn from_asset(asset: AssetId) -> Self {
+    Self::from_cache(ImageKey::Content(asset.hash()), move |_| {
+      let img = PixelImage::from_png(&asset.bytes());
+      std::future::ready(Some(img))
+    })
+  }
+
+  /// Decode the image in its original size rather than downscale it to the
+  /// size it's shown in, e.g. to zoom in it later.
+  pub fn cache_original(mut self, cache_original: bool) -> Self {
+    self.cache_original = cache_original;
+    self
+  }
+
+  /// Load the image in the idle time once it's mounted, even if it's not
+  /// shown yet, such as the image of the next page hidden behind the current
+  /// one, so it shows at once. It's loaded in the size of the window, and the
+  /// load is dropped if the image is disposed before the idle time.
+  pub fn prefetch(mut self, prefetch: bool) -> Self {
+    self.prefetch = prefetch;
+    self
+  }
+}
+
+impl ImageSource {
+  fn request(&self, size: DecodeSize) {
+    let size = if self.cache_original { DecodeSize::Original } else { size };
+    if self
+      .requested
+      .get()
+      .is_some_and(|r| r.covers(size))
+    {
+      return;
+    }
+    self.requested.set(Some(size));
+
+    let load = AppCtx::image_cache()
+      .borrow_mut()
+      .load(self.key.clone(), size, (self.loader)(size));
+    let loaded = self.loaded.clone_writer();
+    AppCtx::spawn_local(async move {
+      let Some(img) = load.await else { return };
+      let outdated = loaded
+        .read()
+        .as_ref()
+        .is_some_and(|(_, s)| s.covers(size));
+      if !outdated {
+        *loaded.write() = Some((img, size));
+      }
+    })
+    .expect("spawn the image load failed");
+  }
+}
+
+impl Compose for Image {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    let src = {
+      let this = this.read();
+      Rc::new(ImageSource {
+        key: this.key.clone(),
+        loader: this.loader.clone(),
+        cache_original: this.cache_original,
+        requested: Cell::new(None),
+        loaded: Stateful::new(None),
+      })
+    };
+    if src.cache_original {
+      src.request(DecodeSize::Original);
+    }
+    let loaded = src.loaded.clone_watcher();
+    let prefetch = this.read().prefetch;
+    let f = fn_widget! {
+      let c_src = src.clone();
+      let w = pipe!($loaded.clone()).map(move |loaded| ImageRender { src: c_src.clone(), loaded });
+      let mut w = FatObj::new(w);
+      if prefetch {
+        let src = src.clone();
+        w = w.on_mounted(move |e| {
+          let wnd = e.window();
+          let size = DecodeSize::bucket_of(wnd.size() * wnd.device_pixel_ratio());
+          let task = IdleTask::new(move || {
+            src.request(size);
+            IdleStep::Done
+          });
+          AppCtx::spawn_idle(task.owned_by(&*e));
+        });
+      }
+      w
+    };
+    f.into_widget()
+  }
+}
+
+impl Render for ImageRender {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let (size, shown) = match &self.loaded {
+      Some((img, _)) => {
+        let size = clamp.clamp(img.original_size.to_f32().cast_unit());
+        (size, size)
+      }
+      // The size of the image is unknown, it may be shown as large as allowed.
+      None => (clamp.min, clamp.max),
+    };
+    let dpr = ctx.window().device_pixel_ratio();
+    self
+      .src
+      .request(DecodeSize::bucket_of(shown * dpr));
+    size
+  }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let Some((DecodedImage { img, original_size }, decoded)) = &self.loaded else { return };
+    let box_rect = Rect::from_size(ctx.box_size().unwrap());
+    let original = Rect::from_size(original_size.to_f32().cast_unit());
+    let Some(rc) = original.intersection(&box_rect) else { return };
+
+    if ctx.painter().intersect_paint_bounds(&rc) {
+      let wnd = ctx.window();
+      let frame = wnd.frame_stats().painted;
+      AppCtx::image_cache()
+        .borrow_mut()
+        .mark_painted(&self.src.key, *decoded, wnd.id(), frame);
+    }
+
+    // The part of the decoded image in the visible rect of the original image.
+    let img_rect = Rect::from_size(Size::new(img.width() as f32, img.height() as f32));
+    let src = rc
+      .scale(img_rect.width() / original.width(), img_rect.height() / original.height())
+      .intersection(&img_rect)
+      .unwrap_or(img_rect);
+    ctx
+      .painter()
+      .draw_img(img.clone(), &rc, &Some(src));
+  }
+}
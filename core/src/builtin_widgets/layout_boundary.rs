@@ -0,0 +1,66 @@
+use crate::prelude::*;
+
+/// A widget of a fixed size that stops the relayout of its descendants from
+/// spreading to its ancestors.
+///
+/// Its size is the `size` clamped by its parent and never follows its child,
+/// the child is laid out within it, so a change of the descendants only
+/// relayouts the subtree of the boundary. A change of the `size` relayouts its
+/// ancestors as usual.
+#[derive(Declare, SingleChild)]
+pub struct LayoutBoundary {
+  pub size: Size,
+}
+
+impl Render for LayoutBoundary {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let size = clamp.clamp(self.size);
+    ctx.perform_single_child_layout(BoxClamp { min: Size::zero(), max: size });
+    size
+  }
+
+  #[inline]
+  fn only_sized_by_parent(&self) -> bool { true }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn sized_by_its_size_not_child() {
+    reset_test_env!();
+
+    let (child, w_child) = split_value(Size::new(10., 10.));
+    let (size, w_size) = split_value(Size::new(20., 20.));
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @LayoutBoundary {
+          size: pipe!(*$size),
+          @MockBox { size: pipe!(*$child) }
+        }
+        @MockBox { size: Size::new(5., 5.) }
+      }
+    });
+    let rect = |wnd: &TestWindow, path: &[usize]| {
+      let info = wnd.layout_info_by_path(path).unwrap();
+      Rect::new(info.pos, info.size.unwrap())
+    };
+    wnd.draw_frame();
+    assert_eq!(rect(&wnd, &[0, 0]).size, Size::new(20., 20.));
+    assert_eq!(rect(&wnd, &[0, 1]).origin, Point::new(20., 0.));
+
+    // The child is kept in the boundary.
+    *w_child.write() = Size::new(30., 30.);
+    wnd.draw_frame();
+    assert_eq!(rect(&wnd, &[0, 0]).size, Size::new(20., 20.));
+    assert_eq!(rect(&wnd, &[0, 0, 0]).size, Size::new(20., 20.));
+
+    // A change of the size relayouts the siblings.
+    *w_size.write() = Size::new(40., 40.);
+    wnd.draw_frame();
+    assert_eq!(rect(&wnd, &[0, 0]).size, Size::new(40., 40.));
+    assert_eq!(rect(&wnd, &[0, 1]).origin, Point::new(40., 0.));
+  }
+}
@@ -6,6 +6,11 @@ pub struct EdgeInsets {
   pub right: f32,
   pub bottom: f32,
   pub top: f32,
+  /// The space at the logical start, the left in a left-to-right layout and
+  /// the right in a right-to-left layout. See [`EdgeInsets::resolve`].
+  pub start: f32,
+  /// The space at the logical end, the opposite of `start`.
+  pub end: f32,
 }
 
 /// The widget utilizes empty space to surround the child widget.
@@ -53,14 +58,14 @@ impl Margin {
 }
 
 impl EdgeInsets {
-  pub const ZERO: Self = Self { top: 0., right: 0., bottom: 0., left: 0. };
+  pub const ZERO: Self = Self { top: 0., right: 0., bottom: 0., left: 0., start: 0., end: 0. };
 
   #[inline]
   pub const fn all(value: f32) -> Self { Self::new(value, value, value, value) }
 
   #[inline]
   pub const fn new(top: f32, right: f32, bottom: f32, left: f32) -> Self {
-    Self { top, right, bottom, left }
+    Self { top, right, bottom, left, start: 0., end: 0. }
   }
 
   #[inline]
@@ -74,9 +79,19 @@ impl EdgeInsets {
   #[inline]
   pub const fn only_top(top: f32) -> Self { Self { top, ..Self::ZERO } }
 
+  /// The space at the logical start, it follows the inherited
+  /// [`TextDirection`].
+  #[inline]
+  pub const fn only_start(start: f32) -> Self { Self { start, ..Self::ZERO } }
+
+  /// The space at the logical end, it follows the inherited
+  /// [`TextDirection`].
+  #[inline]
+  pub const fn only_end(end: f32) -> Self { Self { end, ..Self::ZERO } }
+
   #[inline]
   pub const fn symmetrical(vertical: f32, horizontal: f32) -> Self {
-    Self { top: vertical, bottom: vertical, left: horizontal, right: horizontal }
+    Self { top: vertical, bottom: vertical, left: horizontal, right: horizontal, ..Self::ZERO }
   }
 
   #[inline]
@@ -90,10 +105,37 @@ impl EdgeInsets {
   }
 
   pub const fn thickness(&self) -> Size {
-    Size::new(self.right + self.left, self.bottom + self.top)
+    Size::new(self.right + self.left + self.start + self.end, self.bottom + self.top)
+  }
+
+  /// Whether the insets use the logical `start` or `end`.
+  pub fn is_logical(&self) -> bool { self.start != 0. || self.end != 0. }
+
+  /// Resolve the logical `start` and `end` to the physical `left` and `right`
+  /// by the text direction.
+  ///
+  /// # Panics
+  ///
+  /// Mixing the physical and the logical horizontal insets is ambiguous, it
+  /// panics in debug mode.
+  pub fn resolve(&self, dir: TextDirection) -> Self {
+    if !self.is_logical() {
+      return *self;
+    }
+    debug_assert!(
+      self.left == 0. && self.right == 0.,
+      "`EdgeInsets` can't mix the physical `left`/`right` with the logical `start`/`end`."
+    );
+    let (left, right) = if dir == TextDirection::RightToLeft {
+      (self.end, self.start)
+    } else {
+      (self.start, self.end)
+    };
+    Self { left, right, start: 0., end: 0., ..*self }
   }
 
-  /// Convert to an array by the top, right, bottom, left order.
+  /// Convert to an array by the top, right, bottom, left order, the logical
+  /// `start` and `end` are not included.
   #[inline]
   pub const fn to_array(&self) -> [f32; 4] { [self.top, self.right, self.bottom, self.left] }
 }
@@ -114,6 +156,8 @@ impl std::ops::AddAssign for EdgeInsets {
     self.right += rhs.right;
     self.bottom += rhs.bottom;
     self.top += rhs.top;
+    self.start += rhs.start;
+    self.end += rhs.end;
   }
 }
 
@@ -125,6 +169,8 @@ pub(crate) fn space_around_layout(
     None => return Size::zero(),
   };
 
+  let edges = &if edges.is_logical() { edges.resolve(ctx.text_direction()) } else { *edges };
+
   // Reset children position before layout
   ctx.update_position(child, Point::zero());
 
@@ -151,7 +197,7 @@ mod tests {
   use ribir_dev_helper::*;
 
   use super::*;
-  use crate::test_helper::*;
+  use crate::{reset_test_env, test_helper::*};
 
   widget_layout_test!(
     smoke,
@@ -165,4 +211,47 @@ mod tests {
     LayoutCase::default().with_size(Size::new(102., 102.)),
     LayoutCase::new(&[0, 0]).with_rect(ribir_geom::rect(1., 1.0, 100., 100.))
   );
+
+  fn start_margin(dir: TextDirection) -> WidgetTester {
+    WidgetTester::new(fn_widget! {
+      Provider::new(Box::new(Queryable(dir))).with_child(fn_widget! {
+        @MockMulti {
+          @MockBox {
+            margin: EdgeInsets::only_start(10.),
+            size: Size::new(20., 20.)
+          }
+        }
+      })
+    })
+    .with_wnd_size(Size::new(100., 100.))
+  }
+
+  widget_layout_test!(
+    start_margin_ltr,
+    start_margin(TextDirection::LeftToRight),
+    LayoutCase::new(&[0, 0]).with_size(Size::new(30., 20.)),
+    LayoutCase::new(&[0, 0, 0]).with_x(10.)
+  );
+
+  widget_layout_test!(
+    start_margin_rtl,
+    start_margin(TextDirection::RightToLeft),
+    LayoutCase::new(&[0, 0]).with_size(Size::new(30., 20.)),
+    LayoutCase::new(&[0, 0, 0]).with_x(0.)
+  );
+
+  #[cfg(debug_assertions)]
+  #[test]
+  #[should_panic(expected = "can't mix")]
+  fn mix_physical_and_logical() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        margin: EdgeInsets::only_left(1.) + EdgeInsets::only_start(1.),
+        size: Size::new(20., 20.)
+      }
+    });
+    wnd.draw_frame();
+  }
 }
@@ -1,11 +1,9 @@
-use std::{cell::RefCell, convert::Infallible};
+use std::{cell::RefCell, convert::Infallible, rc::Rc};
 
 use rxrust::prelude::*;
 
 use self::focus_mgr::FocusType;
-use crate::prelude::*;
-
-const MULTI_TAP_DURATION: Duration = Duration::from_millis(250);
+use crate::{prelude::*, ticker::FrameMsg};
 
 bitflags! {
   #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
@@ -25,13 +23,24 @@ bitflags! {
     #[doc="Bubble focus event listener flag, hint the widget is listening to \
      FocusIn/FocusOut and their capture events"]
     const FocusInOut = 1 << 5;
+    #[doc="Scroll listener flag, hint the widget is listening to scroll events"]
+    const Scroll = 1 << 6;
+    #[doc="Long press listener flag, hint the widget is listening to long press \
+    events, the pointer down on it starts to recognize a long press"]
+    const LongPress = 1 << 7 | Self::Pointer.bits();
+    #[doc="Drag listener flag, hint the widget is listening to drag events, the \
+    pointer down on it starts to recognize a drag"]
+    const Drag = 1 << 8 | Self::Pointer.bits();
 
     const AllListeners = Self::Lifecycle.bits()
       | Self::Pointer.bits()
       | Self::Wheel.bits()
       | Self::KeyBoard.bits()
       | Self::Focus.bits()
-      | Self::FocusInOut.bits();
+      | Self::FocusInOut.bits()
+      | Self::Scroll.bits()
+      | Self::LongPress.bits()
+      | Self::Drag.bits();
     // listener end
 
     #[doc="Indicates whether this widget is tracing its focus status."]
@@ -46,6 +55,9 @@ bitflags! {
     const TracePointerPressed = 1 << 20;
     #[doc="Indicates whether the pointer is pressed on this widget."]
     const PointerPressed = 1 << 21;
+    #[doc="The mouse buttons that the pointer down, up and tap listeners accept, \
+    they accept all the buttons if none is set."]
+    const MouseButtonsFilter = 0b1_1111 << 24;
 
     #[doc="Indicates whether this widget has auto-focus functionality."]
     const AutoFocus = 1 << 47;
@@ -75,6 +87,23 @@ macro_rules! event_map_filter {
   };
 }
 
+/// Same as `impl_event_callback!` for the pointer events that respect the
+/// mouse buttons filter of the widget.
+macro_rules! impl_buttons_event_callback {
+  ($this:ident, $event_name:ident, $handler:ident) => {{
+    $this.silent_mark(MixFlags::Pointer);
+    let _ = $this
+      .subject()
+      .filter_map(buttons_map_filter(
+        $this.flags.clone_reader(),
+        event_map_filter!($event_name, PointerEvent),
+      ))
+      .subscribe($handler);
+
+    $this
+  }};
+}
+
 macro_rules! impl_event_callback {
   ($this:ident, $listen_type:ident, $event_name:ident, $event_ty:ident, $handler:ident) => {{
     $this.silent_mark(MixFlags::$listen_type);
@@ -105,6 +134,10 @@ impl MixFlags {
   /// this widget. If you do not call `MixBuiltin::trace_hover` when this
   /// widget is created, this method will always return false, even if the mouse
   /// is hovering over it.
+  ///
+  /// In the `fn_widget!`, `$host.is_hover()` traces the hover status of the
+  /// `host` and can be bound with a `pipe!`. The hover status is cleared when
+  /// the pointer leaves the window or the widget is disposed.
   pub fn is_hover(&self) -> bool { self.contains(MixFlags::Hovered) }
 
   /// Indicates whether the the pointer is pressed on this widget.
@@ -132,6 +165,24 @@ impl MixFlags {
       .then(|| (self.bits() >> 48) as i16)
   }
 
+  /// The mouse buttons that the pointer down, up and tap listeners of the
+  /// widget accept, empty if they accept all the buttons.
+  pub fn mouse_buttons_filter(&self) -> MouseButtons {
+    MouseButtons::from_bits_truncate((self.bits() >> 24) as u8)
+  }
+
+  pub fn set_mouse_buttons_filter(&mut self, buttons: MouseButtons) {
+    self.remove(MixFlags::MouseButtonsFilter);
+    let flags = self.bits() | ((buttons.bits() as u64) << 24);
+    *self = MixFlags::from_bits_retain(flags);
+  }
+
+  /// Whether the pointer listeners accept the press of the `buttons`.
+  pub fn accept_buttons(&self, buttons: MouseButtons) -> bool {
+    let filter = self.mouse_buttons_filter();
+    filter.is_empty() || filter.intersects(buttons)
+  }
+
   pub fn set_tab_index(&mut self, tab_idx: i16) {
     self.insert(MixFlags::Focus);
     let flags = self.bits() | ((tab_idx as u64) << 48);
@@ -166,6 +217,48 @@ impl MixBuiltin {
     impl_event_callback!(self, Lifecycle, PerformedLayout, LifecycleEvent, handler)
   }
 
+  pub fn on_size_changed(&self, handler: impl FnMut(&SizeChangedEvent) + 'static) -> &Self {
+    self.on_layout_changed(|tree, id| tree.store.layout_box_size(id), handler)
+  }
+
+  pub fn on_position_changed(&self, handler: impl FnMut(&PositionChangedEvent) + 'static) -> &Self {
+    self.on_layout_changed(|tree, id| tree.store.layout_info(id).map(|info| info.pos), handler)
+  }
+
+  /// Call the `handler` at the end of every frame that the `value` of the
+  /// layout differs from the last frame. The first layout only records the
+  /// value. The handler runs out of the layout, so a state it modifies starts a
+  /// new frame.
+  fn on_layout_changed<T: PartialEq + Copy + 'static>(
+    &self, value: fn(&WidgetTree, WidgetId) -> Option<T>,
+    mut handler: impl FnMut(&LayoutChangedEvent<T>) + 'static,
+  ) -> &Self {
+    let ticks: Rc<RefCell<Option<BoxSubscription<'static>>>> = <_>::default();
+    let c_ticks = ticks.clone();
+    self.on_mounted(move |e| {
+      let id = e.current_target();
+      let wnd = e.window();
+      let mut last = None;
+      let sub = wnd
+        .frame_tick_stream()
+        .filter(|msg| matches!(msg, FrameMsg::Finish(_)))
+        .subscribe(move |_| {
+          let Some(new) = value(wnd.tree(), id) else { return };
+          if let Some(old) = last.replace(new) {
+            if old != new {
+              handler(&LayoutChangedEvent { old, new });
+            }
+          }
+        });
+      *c_ticks.borrow_mut() = Some(BoxSubscription::new(sub));
+    });
+    self.on_disposed(move |_| {
+      if let Some(ticks) = ticks.borrow_mut().take() {
+        ticks.unsubscribe();
+      }
+    })
+  }
+
   pub fn on_disposed(&self, handler: impl FnOnce(&mut LifecycleEvent) + 'static) -> &Self {
     self.silent_mark(MixFlags::Lifecycle);
     let _ = self
@@ -178,19 +271,19 @@ impl MixBuiltin {
   }
 
   pub fn on_pointer_down(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
-    impl_event_callback!(self, Pointer, PointerDown, PointerEvent, handler)
+    impl_buttons_event_callback!(self, PointerDown, handler)
   }
 
   pub fn on_pointer_down_capture(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
-    impl_event_callback!(self, Pointer, PointerDownCapture, PointerEvent, handler)
+    impl_buttons_event_callback!(self, PointerDownCapture, handler)
   }
 
   pub fn on_pointer_up(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
-    impl_event_callback!(self, Pointer, PointerUp, PointerEvent, handler)
+    impl_buttons_event_callback!(self, PointerUp, handler)
   }
 
   pub fn on_pointer_up_capture(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
-    impl_event_callback!(self, Pointer, PointerUpCapture, PointerEvent, handler)
+    impl_buttons_event_callback!(self, PointerUpCapture, handler)
   }
 
   pub fn on_pointer_move(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
@@ -214,11 +307,31 @@ impl MixBuiltin {
   }
 
   pub fn on_tap(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
-    impl_event_callback!(self, Pointer, Tap, PointerEvent, handler)
+    impl_buttons_event_callback!(self, Tap, handler)
   }
 
   pub fn on_tap_capture(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
-    impl_event_callback!(self, Pointer, TapCapture, PointerEvent, handler)
+    impl_buttons_event_callback!(self, TapCapture, handler)
+  }
+
+  pub fn on_long_press(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    impl_event_callback!(self, LongPress, LongPress, PointerEvent, handler)
+  }
+
+  pub fn on_long_press_capture(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    impl_event_callback!(self, LongPress, LongPressCapture, PointerEvent, handler)
+  }
+
+  pub fn on_drag_start(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragStart, DragEvent, handler)
+  }
+
+  pub fn on_drag_update(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragUpdate, DragEvent, handler)
+  }
+
+  pub fn on_drag_end(&self, handler: impl FnMut(&mut DragEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Drag, DragEnd, DragEvent, handler)
   }
 
   pub fn on_double_tap(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
@@ -237,16 +350,78 @@ impl MixBuiltin {
     self.on_x_times_tap_capture((3, handler))
   }
 
+  /// Listen to the double taps, the second tap follows the first one within
+  /// `interval` rather than the interval of the theme.
+  pub fn on_double_tap_within(
+    &self, (interval, handler): (Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> &Self {
+    self.on_x_times_tap_within((2, interval, handler))
+  }
+
+  pub fn on_double_tap_within_capture(
+    &self, (interval, handler): (Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> &Self {
+    self.on_x_times_tap_within_capture((2, interval, handler))
+  }
+
+  /// Listen to the triple taps, every tap follows the previous one within
+  /// `interval` rather than the interval of the theme.
+  pub fn on_triple_tap_within(
+    &self, (interval, handler): (Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> &Self {
+    self.on_x_times_tap_within((3, interval, handler))
+  }
+
+  pub fn on_triple_tap_within_capture(
+    &self, (interval, handler): (Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> &Self {
+    self.on_x_times_tap_within_capture((3, interval, handler))
+  }
+
+  /// Listen to `times` taps in a row, every tap follows the previous one
+  /// within the [`Theme::multi_tap_interval`].
   pub fn on_x_times_tap(
     &self, (times, handler): (usize, impl FnMut(&mut PointerEvent) + 'static),
   ) -> &Self {
-    self.on_x_times_tap_impl(times, MULTI_TAP_DURATION, false, handler)
+    self.on_x_times_tap_impl(times, None, false, handler)
   }
 
   pub fn on_x_times_tap_capture(
     &self, (times, handler): (usize, impl FnMut(&mut PointerEvent) + 'static),
   ) -> &Self {
-    self.on_x_times_tap_impl(times, MULTI_TAP_DURATION, true, handler)
+    self.on_x_times_tap_impl(times, None, true, handler)
+  }
+
+  /// Listen to `times` taps in a row, every tap follows the previous one
+  /// within `interval` rather than the interval of the theme.
+  pub fn on_x_times_tap_within(
+    &self, (times, interval, handler): (usize, Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> &Self {
+    self.on_x_times_tap_impl(times, Some(interval), false, handler)
+  }
+
+  pub fn on_x_times_tap_within_capture(
+    &self, (times, interval, handler): (usize, Duration, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> &Self {
+    self.on_x_times_tap_impl(times, Some(interval), true, handler)
+  }
+
+  /// Listen to the taps of the middle button, they ignore the mouse buttons
+  /// filter of the widget.
+  pub fn on_middle_tap(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    self.on_button_tap(MouseButtons::AUXILIARY, handler)
+  }
+
+  /// Listen to the taps of the "Browser Back" button, they ignore the mouse
+  /// buttons filter of the widget.
+  pub fn on_back(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    self.on_button_tap(MouseButtons::FOURTH, handler)
+  }
+
+  /// Listen to the taps of the "Browser Forward" button, they ignore the mouse
+  /// buttons filter of the widget.
+  pub fn on_forward(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    self.on_button_tap(MouseButtons::FIFTH, handler)
   }
 
   pub fn on_wheel(&self, handler: impl FnMut(&mut WheelEvent) + 'static) -> &Self {
@@ -257,14 +432,46 @@ impl MixBuiltin {
     impl_event_callback!(self, Wheel, WheelCapture, WheelEvent, handler)
   }
 
+  pub fn on_scroll(&self, handler: impl FnMut(&mut ScrollEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Scroll, Scroll, ScrollEvent, handler)
+  }
+
+  pub fn on_pointer_lock_change(
+    &self, handler: impl FnMut(&mut PointerLockEvent) + 'static,
+  ) -> &Self {
+    impl_event_callback!(self, Pointer, PointerLockChange, PointerLockEvent, handler)
+  }
+
+  pub fn on_got_pointer_capture(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Pointer, GotPointerCapture, PointerEvent, handler)
+  }
+
+  pub fn on_lost_pointer_capture(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    impl_event_callback!(self, Pointer, LostPointerCapture, PointerEvent, handler)
+  }
+
+  fn on_button_tap(
+    &self, button: MouseButtons, handler: impl FnMut(&mut PointerEvent) + 'static,
+  ) -> &Self {
+    self.silent_mark(MixFlags::Pointer);
+    let _ = self
+      .subject()
+      .filter_map(button_tap_map_filter(button))
+      .subscribe(handler);
+    self
+  }
+
   fn on_x_times_tap_impl(
-    &self, times: usize, dur: Duration, capture: bool,
+    &self, times: usize, interval: Option<Duration>, capture: bool,
     handler: impl FnMut(&mut PointerEvent) + 'static,
   ) -> &Self {
     self.silent_mark(MixFlags::Pointer);
     self
       .subject()
-      .filter_map(x_times_tap_map_filter(times, dur, capture))
+      .filter_map(buttons_map_filter(
+        self.flags.clone_reader(),
+        x_times_tap_map_filter(times, interval, capture),
+      ))
       .subscribe(handler);
     self
   }
@@ -344,6 +551,13 @@ impl MixBuiltin {
       self.on_pointer_enter(move |_| flags.write().insert(MixFlags::Hovered));
       let flags = self.flags.clone_writer();
       self.on_pointer_leave(move |_| flags.write().remove(MixFlags::Hovered));
+      // The widget disposed under the pointer receives no leave event.
+      let flags = self.flags.clone_writer();
+      self.on_disposed(move |_| {
+        if flags.read().is_hover() {
+          flags.write().remove(MixFlags::Hovered);
+        }
+      });
     }
   }
 
@@ -355,6 +569,8 @@ impl MixBuiltin {
       self.on_pointer_down(move |_| flags.write().insert(MixFlags::PointerPressed));
       let flags = self.flags.clone_writer();
       self.on_pointer_up(move |_| flags.write().remove(MixFlags::PointerPressed));
+      let flags = self.flags.clone_writer();
+      self.on_pointer_cancel(move |_| flags.write().remove(MixFlags::PointerPressed));
     }
   }
 
@@ -436,45 +652,55 @@ impl<'c> ComposeChild<'c> for MixBuiltin {
   }
 }
 
+fn buttons_map_filter(
+  flags: impl StateReader<Value = MixFlags>,
+  mut f: impl FnMut(&mut Event) -> Option<&mut PointerEvent>,
+) -> impl FnMut(&mut Event) -> Option<&mut PointerEvent> {
+  move |e| f(e).filter(|e| flags.read().accept_buttons(e.chord_buttons()))
+}
+
+fn button_tap_map_filter(
+  button: MouseButtons,
+) -> impl FnMut(&mut Event) -> Option<&mut PointerEvent> {
+  move |e| match e {
+    Event::Tap(e) if e.chord_buttons().contains(button) => Some(e),
+    _ => None,
+  }
+}
+
 fn x_times_tap_map_filter(
-  x: usize, dur: Duration, capture: bool,
+  x: usize, interval: Option<Duration>, capture: bool,
 ) -> impl FnMut(&mut Event) -> Option<&mut PointerEvent> {
   assert!(x > 0);
   struct TapInfo {
     pointer_id: PointerId,
-    stamps: Vec<Instant>,
+    count: usize,
+    last: Instant,
   }
 
-  let mut type_info: Option<TapInfo> = None;
+  let mut tap_info: Option<TapInfo> = None;
   move |e: &mut Event| {
     let e = match e {
       Event::Tap(e) if !capture => e,
       Event::TapCapture(e) if capture => e,
       _ => return None,
     };
-    let now = Instant::now();
-    match &mut type_info {
+    let now = clock::now();
+    // A tap continues the sequence only if it follows the previous tap of the
+    // same pointer in time, so a sequence of slow but steady taps still counts.
+    let count = match &tap_info {
       Some(info) if info.pointer_id == e.id => {
-        if info.stamps.len() + 1 == x {
-          if now.duration_since(info.stamps[0]) <= dur {
-            // emit x-tap event and reset the tap info
-            type_info = None;
-            Some(e)
-          } else {
-            // remove the expired tap
-            info.stamps.remove(0);
-            info.stamps.push(now);
-            None
-          }
-        } else {
-          info.stamps.push(now);
-          None
-        }
-      }
-      _ => {
-        type_info = Some(TapInfo { pointer_id: e.id, stamps: vec![now] });
-        None
+        let interval = interval.unwrap_or_else(|| Theme::of(&*e).multi_tap_interval);
+        if now.duration_since(info.last) <= interval { info.count + 1 } else { 1 }
       }
+      _ => 1,
+    };
+    if count == x {
+      tap_info = None;
+      Some(e)
+    } else {
+      tap_info = Some(TapInfo { pointer_id: e.id, count, last: now });
+      None
     }
   }
 }
@@ -494,6 +720,8 @@ impl Clone for MixBuiltin {
 
 #[cfg(test)]
 mod tests {
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
   use super::*;
   use crate::{reset_test_env, test_helper::*};
 
@@ -524,4 +752,112 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(*outer_layout.read(), 2);
   }
+
+  #[test]
+  fn filter_listeners_by_buttons() {
+    reset_test_env!();
+
+    let fired = Stateful::new(vec![]);
+    let w_fired = fired.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        on_middle_tap: move |_| $w_fired.write().push("middle".to_string()),
+        on_back: move |_| $w_fired.write().push("back".to_string()),
+        on_forward: move |_| $w_fired.write().push("forward".to_string()),
+        @MockBox {
+          size: Size::new(100., 100.),
+          buttons: MouseButtons::PRIMARY,
+          on_pointer_down: move |_| $w_fired.write().push("down".to_string()),
+          on_tap: move |e| $w_fired.write().push(format!("tap {:?}", e.chord_buttons())),
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (1., 1.).into() });
+    let click = |buttons: &[MouseButton]| {
+      for b in buttons {
+        wnd.process_mouse_input(device_id, ElementState::Pressed, *b);
+      }
+      for b in buttons {
+        wnd.process_mouse_input(device_id, ElementState::Released, *b);
+      }
+      wnd.run_frame_tasks();
+      std::mem::take(&mut *fired.write())
+    };
+
+    assert_eq!(click(&[MouseButton::Middle]), ["middle"]);
+    assert_eq!(click(&[MouseButton::Back]), ["back"]);
+    assert_eq!(click(&[MouseButton::Forward]), ["forward"]);
+    assert_eq!(click(&[MouseButton::Left]), ["down", "tap MouseButtons(PRIMARY)"]);
+    // The chorded press reports all its buttons.
+    assert_eq!(
+      click(&[MouseButton::Left, MouseButton::Back]),
+      ["down", "tap MouseButtons(PRIMARY | FOURTH)", "back"]
+    );
+  }
+
+  #[test]
+  fn hover_state() {
+    reset_test_env!();
+
+    let (show, w_show) = split_value(true);
+    let w = fn_widget! {
+      let mut parent = @MockBox { size: Size::new(50., 50.) };
+      let mut child = @MockBox { size: Size::new(20., 20.) };
+      let parent_hover = pipe!($parent.is_hover());
+      let child_hover = pipe!($child.is_hover());
+      let mut child = Some(child);
+      @MockMulti {
+        @ $parent {
+          @ { pipe!(*$show).map(move |show| show.then(|| child.take()).flatten()) }
+        }
+        @MockBox {
+          size: parent_hover.map(|h| if h { Size::new(10., 10.) } else { Size::zero() }),
+        }
+        @MockBox {
+          size: child_hover.map(|h| if h { Size::new(5., 5.) } else { Size::zero() }),
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |wnd: &mut TestWindow, x: f64, y: f64| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+      wnd.draw_frame();
+    };
+    let hovers = |wnd: &TestWindow| {
+      let size = |path: &[usize]| {
+        wnd
+          .layout_info_by_path(path)
+          .unwrap()
+          .size
+          .unwrap()
+      };
+      (size(&[0, 1]) != Size::zero(), size(&[0, 2]) != Size::zero())
+    };
+    assert_eq!(hovers(&wnd), (false, false));
+
+    move_to(&mut wnd, 40., 40.);
+    assert_eq!(hovers(&wnd), (true, false));
+
+    // Entering the child keeps the parent hovered.
+    move_to(&mut wnd, 10., 10.);
+    assert_eq!(hovers(&wnd), (true, true));
+
+    // The child disposed under the pointer isn't hovered anymore.
+    *w_show.write() = false;
+    wnd.draw_frame();
+    assert_eq!(hovers(&wnd), (true, false));
+
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorLeft { device_id });
+    wnd.draw_frame();
+    assert_eq!(hovers(&wnd), (false, false));
+  }
 }
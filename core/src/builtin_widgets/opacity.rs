@@ -29,4 +29,6 @@ impl WrapRender for Opacity {
       host.paint(ctx)
     }
   }
+
+  fn get_opacity(&self, host: &dyn Render) -> f32 { self.opacity * host.get_opacity() }
 }
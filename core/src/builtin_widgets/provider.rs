@@ -156,6 +156,15 @@ pub trait ProviderCtx {
 
   fn write_of<Q: 'static>(&self) -> Option<WriteRef<Q>> { self.all_write_of().next() }
 
+  /// Retrieve the custom style `S` from the nearest ancestor that provides it,
+  /// see [`CustomStyle`].
+  fn theme_of<S: CustomStyle>(&self) -> S
+  where
+    Self: Sized,
+  {
+    S::of(self)
+  }
+
   fn all_providers(&self) -> impl Iterator<Item = &dyn Query>;
 }
 
@@ -175,10 +184,13 @@ impl ProviderCtx for BuildCtx {
 
 impl<T: Deref<Target: WidgetCtxImpl>> ProviderCtx for T {
   fn all_providers(&self) -> impl Iterator<Item = &dyn Query> {
-    self.id().ancestors(self.tree()).filter_map(|id| {
-      let r = id.assert_get(self.tree());
-      r.queryable().then(|| r.as_query())
-    })
+    self
+      .id()
+      .provider_ancestors(self.tree())
+      .filter_map(|id| {
+        let r = id.assert_get(self.tree());
+        r.queryable().then(|| r.as_query())
+      })
   }
 }
 
@@ -212,6 +224,8 @@ impl<const M: usize> Query for [Box<dyn Query>; M] {
   }
 
   fn queryable(&self) -> bool { true }
+
+  fn type_bloom(&self, bloom: &mut QueryBloom) { self.iter().for_each(|q| q.type_bloom(bloom)) }
 }
 
 #[cfg(test)]
@@ -0,0 +1,136 @@
+use crate::prelude::*;
+
+/// The physics that a [`ScrollableWidget`] moves by after the user lifts the
+/// finger, or when it scrolls by the wheel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollPhysics {
+  /// Whether the widget keeps moving by the velocity of the fling after the
+  /// finger lifts.
+  pub momentum: bool,
+  /// The rate that the velocity of the momentum decays by per second, the
+  /// velocity after `t` seconds is `v * e^(-decay * t)`.
+  pub decay: f32,
+  /// The momentum stops when its speed drops below this value, in logical
+  /// pixels per second.
+  pub min_velocity: f32,
+  /// Whether the widget can be dragged or flung past its edges, and bounces
+  /// back with a spring.
+  pub overscroll: bool,
+  /// The angular frequency of the spring that bounces the widget back from an
+  /// overscroll or moves it to a snap position. A bigger value is stiffer.
+  pub spring: f32,
+  /// Where the widget rests after the momentum stops.
+  pub snap: ScrollSnap,
+  /// Whether the wheel moves the widget smoothly to the position instead of
+  /// jumping to it.
+  pub smooth_wheel: bool,
+}
+
+/// The positions that a [`ScrollableWidget`] rests at, see
+/// [`ScrollPhysics::snap`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ScrollSnap {
+  /// Rest wherever the momentum stops.
+  #[default]
+  None,
+  /// Rest at a multiple of the interval.
+  Interval(f32),
+  /// Rest at one of the offsets, e.g. the start of every item in a list.
+  Offsets(Vec<f32>),
+}
+
+impl Default for ScrollPhysics {
+  fn default() -> Self {
+    Self {
+      momentum: true,
+      decay: 2.,
+      min_velocity: 20.,
+      overscroll: false,
+      spring: 20.,
+      snap: ScrollSnap::None,
+      smooth_wheel: false,
+    }
+  }
+}
+
+impl ScrollSnap {
+  /// The snap position nearest to `pos`, it's in the range of `0.` to `max`.
+  pub fn nearest(&self, pos: f32, max: f32) -> f32 {
+    let snapped = match self {
+      ScrollSnap::None => pos,
+      ScrollSnap::Interval(interval) if *interval > 0. => (pos / interval).round() * interval,
+      ScrollSnap::Interval(_) => pos,
+      ScrollSnap::Offsets(offsets) => offsets
+        .iter()
+        .copied()
+        .min_by(|a, b| (a - pos).abs().total_cmp(&(b - pos).abs()))
+        .unwrap_or(pos),
+    };
+    snapped.clamp(0., max)
+  }
+}
+
+/// A running motion of a scrollable widget, it's also used by the widgets that
+/// move their content by the [`ScrollPhysics`] themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollMotion {
+  /// Moves by the velocity that decays over time.
+  Momentum { start: Instant, from: Point, velocity: Vector },
+  /// Moves to the position `to` by a critically damped spring.
+  Settle { start: Instant, from: Point, to: Point, velocity: Vector },
+}
+
+/// The state of a [`ScrollMotion`] at a time.
+pub enum MotionStep {
+  /// The motion goes on with the position and the velocity.
+  Running(Point, Vector),
+  /// The momentum stops at the position with the velocity.
+  Stopped(Point, Vector),
+  /// The motion settles at the position.
+  Settled(Point),
+}
+
+impl ScrollMotion {
+  /// The state of the motion at the time `now`.
+  pub fn step(&self, physics: &ScrollPhysics, now: Instant) -> MotionStep {
+    match *self {
+      ScrollMotion::Momentum { start, from, velocity } => {
+        let k = physics.decay.max(f32::EPSILON);
+        let speed = velocity.length();
+        let mut t = now.duration_since(start).as_secs_f32();
+        // The time when the speed drops to the minimum velocity, the momentum
+        // stops there whatever the frame rate is.
+        let stop_at = (speed / physics.min_velocity.max(f32::EPSILON))
+          .ln()
+          .max(0.)
+          / k;
+        let stopped = t >= stop_at;
+        if stopped {
+          t = stop_at;
+        }
+        let decay = (-k * t).exp();
+        let pos = from + velocity * (1. - decay) / k;
+        let velocity = velocity * decay;
+        if stopped {
+          MotionStep::Stopped(pos, velocity)
+        } else {
+          MotionStep::Running(pos, velocity)
+        }
+      }
+      ScrollMotion::Settle { start, from, to, velocity } => {
+        let w = physics.spring;
+        let t = now.duration_since(start).as_secs_f32();
+        let x0 = from - to;
+        let c = velocity + x0 * w;
+        let decay = (-w * t).exp();
+        let x = (x0 + c * t) * decay;
+        let v = (velocity - c * (w * t)) * decay;
+        if x.length() < 0.5 && v.length() < physics.min_velocity {
+          MotionStep::Settled(to)
+        } else {
+          MotionStep::Running(to + x, v)
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,116 @@
+use crate::prelude::*;
+
+/// Where a scrollable widget was scrolled to when it's disposed, it's restored
+/// when a widget of the same restoration id is built again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollAnchor {
+  /// The scroll position, it's clamped to the new content.
+  Offset(Point),
+  /// The index of the first visible item of a list, and how far the list was
+  /// scrolled past its top. The list restores to the same item even if the
+  /// items before it resize.
+  Item { index: usize, offset: f32 },
+}
+
+/// The scroll anchors that the scrollable widgets of a window save by their
+/// restoration ids, see [`FatObj::restoration_id`] and
+/// [`Window::scroll_restoration`].
+///
+/// The ids are shared by the whole window, prefix them with the route of the
+/// screen, such as `settings/list`, to keep the lists of the screens apart.
+#[derive(Debug, Default)]
+pub struct ScrollRestoration {
+  anchors: ahash::HashMap<CowArc<str>, ScrollAnchor>,
+}
+
+impl ScrollRestoration {
+  /// Save the `anchor` of the `id`, it replaces the anchor saved before.
+  pub fn save(&mut self, id: CowArc<str>, anchor: ScrollAnchor) { self.anchors.insert(id, anchor); }
+
+  /// The anchor saved by the `id`.
+  pub fn get(&self, id: &str) -> Option<ScrollAnchor> { self.anchors.get(id).copied() }
+
+  /// Forget the anchor of the `id`, the widget of it starts at the top the next
+  /// time.
+  pub fn remove(&mut self, id: &str) -> Option<ScrollAnchor> { self.anchors.remove(id) }
+
+  /// Forget all the anchors.
+  pub fn clear(&mut self) { self.anchors.clear(); }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, MouseScrollDelta, TouchPhase, WindowEvent},
+  };
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn wheel(wnd: &mut TestWindow, delta: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: LogicalPosition::new(50., 50.).to_physical(1.),
+    });
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::PixelDelta((0., -delta as f64).into()),
+      phase: TouchPhase::Started,
+    });
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn restore_offset_by_id() {
+    reset_test_env!();
+
+    let show = Stateful::new(true);
+    let content = Stateful::new(1000.);
+    let (c_show, c_content) = (show.clone_watcher(), content.clone_watcher());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @ {
+          pipe!(*$c_show).map(move |show| show.then(|| @MockBox {
+            size: pipe!(Size::new(100., *$c_content)),
+            restoration_id: "list",
+            scrollable: Scrollable::Y,
+          }))
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    let scroll_pos = |wnd: &TestWindow| {
+      let tree = wnd.tree();
+      let scroll = tree
+        .content_root()
+        .query_ref::<ScrollableWidget>(tree)
+        .unwrap();
+      scroll.get_scroll_pos()
+    };
+
+    wheel(&mut wnd, 300.);
+    assert_eq!(scroll_pos(&wnd), Point::new(0., 300.));
+
+    *show.write() = false;
+    wnd.draw_frame();
+    let saved = wnd.scroll_restoration().borrow().get("list");
+    assert_eq!(saved, Some(ScrollAnchor::Offset(Point::new(0., 300.))));
+
+    *show.write() = true;
+    wnd.draw_frame();
+    assert_eq!(scroll_pos(&wnd), Point::new(0., 300.));
+
+    // Clamped to the shorter content.
+    *show.write() = false;
+    *content.write() = 250.;
+    wnd.draw_frame();
+    *show.write() = true;
+    wnd.draw_frame();
+    assert_eq!(scroll_pos(&wnd), Point::new(0., 150.));
+  }
+}
@@ -1,4 +1,6 @@
-use crate::prelude::*;
+use std::{cell::Cell, rc::Rc};
+
+use crate::{prelude::*, ticker::FrameMsg, window::DelayEvent};
 /// Enumerate to describe which direction allow widget to scroll.
 #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Hash)]
 pub enum Scrollable {
@@ -15,12 +17,23 @@ pub enum Scrollable {
 }
 
 /// Helper struct for builtin scrollable field.
+///
+/// Besides the wheel, the widget can be dragged by a touch or a pen, and keeps
+/// moving after the pointer lifts, as described by its [`ScrollPhysics`].
 #[derive(Default)]
 pub struct ScrollableWidget {
   pub scrollable: Scrollable,
+  pub physics: ScrollPhysics,
+  /// Save the scroll position by the id when the widget is disposed, and
+  /// restore it when a widget of the same id is built in the window, see
+  /// [`ScrollRestoration`].
+  pub restoration_id: Option<CowArc<str>>,
   scroll_pos: Point,
+  /// The position to jump to once the widget and its content are laid out.
+  pending_restore: Option<Point>,
   page: Size,
   content_size: Size,
+  motion: Option<ScrollMotion>,
 }
 
 impl Declare for ScrollableWidget {
@@ -29,9 +42,40 @@ impl Declare for ScrollableWidget {
   fn declarer() -> Self::Builder { FatObj::new(()) }
 }
 
+/// The state of a pointer that drags the nested scrollable widgets.
+struct ScrollDrag {
+  _grab: GrabPointer,
+  last: Point,
+  tracker: VelocityTracker,
+  /// The scrollable widgets under the pointer, from the innermost to the
+  /// outermost.
+  chain: Vec<WidgetId>,
+  /// The axis that the drag is locked to, decided by its first move if there
+  /// are nested scrollable widgets.
+  lock: Option<Scrollable>,
+  /// Whether the pointer has moved farther than the slop, see
+  /// [`GestureSettings`].
+  started: bool,
+}
+
 impl<'c> ComposeChild<'c> for ScrollableWidget {
   type Child = Widget<'c>;
   fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    // Attach the scrollable widget to the tree, so its descendants can find it
+    // to scroll the nested scrollable widgets together.
+    let handle: Box<dyn Query> = Box::new(this.clone_writer());
+    let restoration_id = this.read().restoration_id.clone();
+    let saving = this.clone_reader();
+    if let Some(id) = &restoration_id {
+      let saved = BuildCtx::get()
+        .window()
+        .scroll_restoration()
+        .borrow()
+        .get(id);
+      if let Some(ScrollAnchor::Offset(pos)) = saved {
+        this.silent().restore_to(pos);
+      }
+    }
     fn_widget! {
       let mut view = @UnconstrainedBox {
         dir: pipe!{
@@ -61,14 +105,212 @@ impl<'c> ComposeChild<'c> for ScrollableWidget {
         .distinct_until_changed()
         .subscribe(move |v| $this.write().set_page(v));
 
-      @Clip {
+      // Keep the window drawing while the widget moves by itself.
+      let wnd = BuildCtx::get().window();
+      let moving = watch!($this.is_moving())
+        .distinct_until_changed()
+        .skip(1)
+        .subscribe(move |moving| if moving {
+          wnd.inc_running_animate();
+        } else {
+          wnd.dec_running_animate();
+        });
+      let ticks = BuildCtx::get()
+        .window()
+        .frame_tick_stream()
+        .subscribe(move |msg| if let FrameMsg::NewFrame(time) = msg {
+          if $this.is_moving() {
+            $this.write().advance_motion(time);
+          }
+        });
+
+      // Tell the listeners of the scroll event once the widget is laid out, and
+      // whenever it scrolls or its content resizes.
+      let mut clip = @Clip {};
+      let track = $clip.track_id();
+      let c_track = track.clone();
+      let reported = Rc::new(Cell::new(None));
+      let c_reported = reported.clone();
+      let wnd = BuildCtx::get().window();
+      let scrolled = watch!($this.scroll_extent())
+        .subscribe(move |extent| if let Some(id) = c_track.get() {
+          report_scroll(id, extent, &c_reported, &wnd);
+        });
+
+      let drag: Stateful<Option<ScrollDrag>> = Stateful::new(None);
+      @ $clip {
+        on_disposed: move |e| {
+          if $this.is_moving() {
+            BuildCtx::get().window().dec_running_animate();
+          }
+          if let Some(id) = restoration_id.clone() {
+            let anchor = ScrollAnchor::Offset(saving.read().get_scroll_pos());
+            e.window().scroll_restoration().borrow_mut().save(id, anchor);
+          }
+          moving.unsubscribe();
+          ticks.unsubscribe();
+          scrolled.unsubscribe();
+        },
+        on_performed_layout: move |e| if let Some(id) = track.get() {
+          report_scroll(id, $this.scroll_extent(), &reported, &e.window());
+        },
         @ $view {
-          on_wheel: move |e| $this.write().scroll(-e.delta_x, -e.delta_y),
+          on_pointer_down: move |e| {
+            if e.point_type == PointerType::Mouse {
+              return;
+            }
+            if $this.is_moving() {
+              $this.write().motion = None;
+            }
+            // The innermost scrollable widget grabs the pointer and drags all
+            // the scrollable widgets under the pointer.
+            if let Some(grab) = GrabPointer::grab(e.current_target(), &e.window()) {
+              let mut tracker = VelocityTracker::default();
+              tracker.add_sample(clock::now(), e.global_pos());
+              let wnd = e.window();
+              let chain = scroll_chain(e.current_target(), wnd.tree());
+              *$drag.write() = Some(ScrollDrag {
+                _grab: grab,
+                last: e.global_pos(),
+                tracker,
+                chain,
+                lock: None,
+                started: false,
+              });
+            }
+          },
+          on_pointer_move: move |e| {
+            let mut drag = $drag.write();
+            if let Some(drag) = drag.as_mut() {
+              let pos = e.global_pos();
+              drag.tracker.add_sample(clock::now(), pos);
+              let delta = drag.last - pos;
+              if !drag.started {
+                // The drag starts from where it was pressed, but only after it
+                // moves farther than the slop.
+                if !GestureSettings::of(e).is_drag(&e.point_type, delta) {
+                  return;
+                }
+                drag.started = true;
+              }
+              drag.last = pos;
+              if drag.lock.is_none() && drag.chain.len() > 1 && delta != Vector::zero() {
+                let horizontal = delta.x.abs() > delta.y.abs();
+                drag.lock = Some(if horizontal { Scrollable::X } else { Scrollable::Y });
+              }
+              let delta = lock_axis(drag.lock, delta);
+              let wnd = e.window();
+              nested_drag(&drag.chain, wnd.tree(), delta);
+            }
+          },
+          on_pointer_up: move |e| {
+            let drag = $drag.write().take();
+            if let Some(drag) = drag {
+              let velocity = lock_axis(drag.lock, -drag.tracker.velocity());
+              let wnd = e.window();
+              nested_fling(&drag.chain, wnd.tree(), velocity);
+            }
+          },
           @ { child }
         }
       }
     }
     .into_widget()
+    .attach_data(handle)
+  }
+}
+
+/// The scrollable widgets that contain `id`, from the innermost to the
+/// outermost.
+fn scroll_chain(id: WidgetId, tree: &WidgetTree) -> Vec<WidgetId> {
+  // A scrollable widget may also be provided by its ancestor, only keep the
+  // nearest one.
+  let mut seen: Vec<*const ScrollableWidget> = vec![];
+  id.ancestors(tree)
+    .filter(|id| {
+      id.query_ref::<ScrollableWidget>(tree)
+        .is_some_and(|s| {
+          let ptr = &*s as *const ScrollableWidget;
+          let new = !seen.contains(&ptr);
+          if new {
+            seen.push(ptr);
+          }
+          new
+        })
+    })
+    .collect()
+}
+
+type ScrollExtent = (Point, Point, Size);
+
+/// Emit the scroll event to the scrollable widget `id` if its extent changed
+/// since the last report.
+fn report_scroll(
+  id: WidgetId, extent: ScrollExtent, reported: &Cell<Option<ScrollExtent>>, wnd: &Window,
+) {
+  if reported.replace(Some(extent)) != Some(extent) {
+    let (offset, max_offset, _) = extent;
+    // The event stops before the outer scrollable widget.
+    let up = scroll_chain(id, wnd.tree()).get(1).copied();
+    wnd.add_delay_event(DelayEvent::Scroll { id, offset, max_offset, up });
+  }
+}
+
+fn lock_axis(lock: Option<Scrollable>, mut v: Vector) -> Vector {
+  match lock {
+    Some(Scrollable::X) => v.y = 0.,
+    Some(Scrollable::Y) => v.x = 0.,
+    _ => {}
+  }
+  v
+}
+
+/// The default action of a wheel event, scroll the scrollable widgets that
+/// contain `id` by the `delta`, the innermost first, and pass what is left to
+/// the outer ones. It's skipped if a wheel listener prevents the default.
+pub(crate) fn scroll_by_wheel(id: WidgetId, tree: &WidgetTree, delta: Vector) {
+  let mut rest = delta;
+  for id in scroll_chain(id, tree) {
+    if rest == Vector::zero() {
+      break;
+    }
+    if let Some(mut scroll) = id.query_write::<ScrollableWidget>(tree) {
+      rest = scroll.wheel(rest.x, rest.y);
+    }
+  }
+}
+
+/// Drag the scrollable widgets of the `chain`, each one takes the delta it can
+/// and passes the rest to its parent. What is left at last overscrolls the
+/// outermost one that allows.
+fn nested_drag(chain: &[WidgetId], tree: &WidgetTree, delta: Vector) {
+  let mut rest = delta;
+  for id in chain {
+    if let Some(mut scroll) = id.query_write::<ScrollableWidget>(tree) {
+      rest = scroll.drag_by(rest);
+    }
+  }
+  for id in chain.iter().rev() {
+    if rest == Vector::zero() {
+      break;
+    }
+    if let Some(mut scroll) = id.query_write::<ScrollableWidget>(tree) {
+      rest = scroll.overscroll_by(rest);
+    }
+  }
+}
+
+/// Fling the scrollable widgets of the `chain`, the velocity of an axis goes
+/// to the innermost one that can move along it. The others settle where they
+/// are.
+fn nested_fling(chain: &[WidgetId], tree: &WidgetTree, velocity: Vector) {
+  let mut rest = velocity;
+  for id in chain {
+    if let Some(mut scroll) = id.query_write::<ScrollableWidget>(tree) {
+      let take = scroll.fling_part(rest);
+      rest -= take;
+      scroll.fling(take);
+    }
   }
 }
 
@@ -85,13 +327,69 @@ impl ScrollableWidget {
   }
 
   pub fn jump_to(&mut self, top_left: Point) {
-    let max = self.max_scrollable();
-    self.scroll_pos = top_left.clamp(Point::zero(), max.to_vector().to_point());
+    self.motion = None;
+    self.scroll_pos = self.clamp_pos(top_left);
+  }
+
+  /// Jump to the `top_left` once the widget and its content are laid out, it's
+  /// clamped to the content then. It's how a saved position is restored, when
+  /// the content may not be built yet.
+  pub fn restore_to(&mut self, top_left: Point) {
+    self.pending_restore = Some(top_left);
+    self.apply_pending_restore();
+  }
+
+  /// Start to move by the `velocity` in logical pixels per second, as the user
+  /// flings the widget. It decays by the [`ScrollPhysics`] of the widget, and
+  /// finally rests at a snap position.
+  pub fn fling(&mut self, velocity: Vector) {
+    let velocity = self.mask(velocity);
+    let now = clock::now();
+    if self.physics.momentum && velocity.length() >= self.physics.min_velocity {
+      self.motion = Some(ScrollMotion::Momentum { start: now, from: self.scroll_pos, velocity });
+    } else {
+      self.settle(now, self.scroll_pos, velocity);
+    }
+  }
+
+  /// Whether the widget is moving by a fling, a bounce back or a smooth wheel.
+  pub fn is_moving(&self) -> bool { self.motion.is_some() }
+
+  /// Scroll by the wheel, it moves smoothly if the
+  /// [`ScrollPhysics::smooth_wheel`] is enabled. Return the delta that is left
+  /// when the widget reaches its edges.
+  pub fn wheel(&mut self, x: f32, y: f32) -> Vector {
+    let delta = Vector::new(x, y);
+    if delta == Vector::zero() {
+      return delta;
+    }
+    if !self.physics.smooth_wheel {
+      let target = self.scroll_pos;
+      self.jump_to(target + self.mask(delta));
+      return target + delta - self.scroll_pos;
+    }
+    let now = clock::now();
+    let (from, target, velocity) = match self
+      .motion
+      .map(|m| (m, m.step(&self.physics, now)))
+    {
+      Some((ScrollMotion::Settle { to, .. }, MotionStep::Running(pos, v))) => (pos, to, v),
+      _ => (self.scroll_pos, self.scroll_pos, Vector::zero()),
+    };
+    let to = self.clamp_pos(target + self.mask(delta));
+    if to != target {
+      self.motion = Some(ScrollMotion::Settle { start: now, from, to, velocity });
+    }
+    target + delta - to
   }
 
   #[inline]
   pub fn scroll_view_size(&self) -> Size { self.page }
 
+  fn scroll_extent(&self) -> ScrollExtent {
+    (self.scroll_pos, self.max_scrollable(), self.content_size)
+  }
+
   #[inline]
   pub fn scroll_content_size(&self) -> Size { self.content_size }
 
@@ -122,16 +420,150 @@ impl ScrollableWidget {
     if content.is_infinite() || content.is_nan() { 0. } else { self.scroll_pos.y / content }
   }
 
-  fn sync_pos(&mut self) { self.jump_to(self.scroll_pos) }
+  fn sync_pos(&mut self) {
+    if self.motion.is_none() {
+      self.scroll_pos = self.clamp_pos(self.scroll_pos);
+    }
+  }
+
+  fn clamp_pos(&self, pos: Point) -> Point { pos.clamp(Point::zero(), self.max_scrollable()) }
+
+  /// Keep only the axes that the widget can scroll.
+  fn mask(&self, mut v: Vector) -> Vector {
+    match self.scrollable {
+      Scrollable::X => v.y = 0.,
+      Scrollable::Y => v.x = 0.,
+      Scrollable::Both => {}
+    }
+    v
+  }
+
+  /// Move by a dragging pointer until the edges, return the delta that is
+  /// left. If the widget is already past its edges, it takes all the delta but
+  /// resists.
+  fn drag_by(&mut self, delta: Vector) -> Vector {
+    let max = self.max_scrollable();
+    let enabled = self.mask(Vector::new(1., 1.));
+    let drag = |pos: &mut f32, max: f32, delta: f32| {
+      if *pos < 0. || *pos > max {
+        let to = *pos + delta / 2.;
+        // Stop at the edge when it's dragged back.
+        *pos = if *pos < 0. { to.min(0.) } else { to.max(max) };
+        0.
+      } else {
+        let to = *pos + delta;
+        *pos = to.clamp(0., max);
+        to - *pos
+      }
+    };
+    let mut rest = delta;
+    if enabled.x != 0. {
+      rest.x = drag(&mut self.scroll_pos.x, max.x, delta.x);
+    }
+    if enabled.y != 0. {
+      rest.y = drag(&mut self.scroll_pos.y, max.y, delta.y);
+    }
+    rest
+  }
+
+  /// Drag the widget past its edges with resistance if its
+  /// [`ScrollPhysics::overscroll`] is enabled, return the delta that is left.
+  fn overscroll_by(&mut self, delta: Vector) -> Vector {
+    if !self.physics.overscroll {
+      return delta;
+    }
+    let take = self.mask(delta);
+    self.scroll_pos += take / 2.;
+    delta - take
+  }
+
+  /// The part of the `velocity` that the widget can move along, it's not
+  /// stopped by the edges.
+  fn fling_part(&self, velocity: Vector) -> Vector {
+    let max = self.max_scrollable();
+    let pos = self.scroll_pos;
+    let can_move = |pos: f32, max: f32, v: f32| {
+      pos < 0. || pos > max || (v > 0. && pos < max) || (v < 0. && pos > 0.)
+    };
+    let mut take = self.mask(velocity);
+    if !can_move(pos.x, max.x, take.x) {
+      take.x = 0.;
+    }
+    if !can_move(pos.y, max.y, take.y) {
+      take.y = 0.;
+    }
+    take
+  }
+
+  /// Rest at the snap position nearest to `pos`, or stop if it's already there.
+  fn settle(&mut self, now: Instant, pos: Point, velocity: Vector) {
+    let max = self.max_scrollable();
+    let pos = if self.physics.overscroll { pos } else { self.clamp_pos(pos) };
+    let snap = &self.physics.snap;
+    let mut to = pos.clamp(Point::zero(), max);
+    if self.scrollable != Scrollable::Y {
+      to.x = snap.nearest(pos.x, max.x);
+    }
+    if self.scrollable != Scrollable::X {
+      to.y = snap.nearest(pos.y, max.y);
+    }
+    self.scroll_pos = pos;
+    self.motion =
+      (to != pos).then_some(ScrollMotion::Settle { start: now, from: pos, to, velocity });
+  }
+
+  fn advance_motion(&mut self, now: Instant) {
+    let Some(motion) = self.motion else { return };
+    match (motion, motion.step(&self.physics, now)) {
+      (ScrollMotion::Momentum { .. }, MotionStep::Running(pos, mut velocity)) => {
+        let clamped = self.clamp_pos(pos);
+        if clamped == pos {
+          self.scroll_pos = pos;
+        } else if self.physics.overscroll {
+          self.scroll_pos = pos;
+          self.motion = Some(ScrollMotion::Settle { start: now, from: pos, to: clamped, velocity });
+        } else {
+          // Stop the axes that hit the edge, and go on with the others.
+          if clamped.x != pos.x {
+            velocity.x = 0.;
+          }
+          if clamped.y != pos.y {
+            velocity.y = 0.;
+          }
+          self.scroll_pos = clamped;
+          self.motion = Some(ScrollMotion::Momentum { start: now, from: clamped, velocity });
+          if velocity.length() < self.physics.min_velocity {
+            self.settle(now, clamped, Vector::zero());
+          }
+        }
+      }
+      (_, MotionStep::Running(pos, _)) => self.scroll_pos = pos,
+      (_, MotionStep::Stopped(pos, velocity)) => self.settle(now, pos, velocity),
+      (_, MotionStep::Settled(pos)) => {
+        self.scroll_pos = pos;
+        self.motion = None;
+      }
+    }
+  }
 
   fn set_content_size(&mut self, content_size: Size) {
     self.content_size = content_size;
-    self.sync_pos()
+    self.sync_pos();
+    self.apply_pending_restore();
   }
 
   fn set_page(&mut self, page: Size) {
     self.page = page;
-    self.sync_pos()
+    self.sync_pos();
+    self.apply_pending_restore();
+  }
+
+  fn apply_pending_restore(&mut self) {
+    if self.page != Size::zero() && self.content_size != Size::zero() {
+      if let Some(pos) = self.pending_restore.take() {
+        self.jump_to(pos);
+      }
+    }
   }
 }
 
@@ -234,4 +666,274 @@ mod tests {
     let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
     wnd.draw_frame();
   }
+
+  fn scroll_window(physics: ScrollPhysics) -> (TestWindow, Stateful<ScrollableWidget>) {
+    let scroll = Stateful::new(ScrollableWidget { physics, ..Default::default() });
+    let c_scroll = scroll.clone_writer();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let scroll = c_scroll.clone_writer();
+        @$scroll {
+          @MockBox { size: Size::new(100., 1000.) }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    (wnd, scroll)
+  }
+
+  fn run_until_rest(wnd: &mut TestWindow, scroll: &Stateful<ScrollableWidget>) -> Point {
+    let mut frames = 0;
+    while scroll.read().is_moving() {
+      wnd.advance_time(Duration::from_millis(16));
+      frames += 1;
+      assert!(frames < 1000, "the scrollable widget never rests");
+    }
+    scroll.read().get_scroll_pos()
+  }
+
+  #[test]
+  fn fling_settles_by_decay() {
+    reset_test_env!();
+
+    let (mut wnd, scroll) = scroll_window(ScrollPhysics::default());
+    scroll.write().fling(Vector::new(0., 1000.));
+    wnd.advance_time(Duration::from_millis(100));
+    let pos = scroll.read().get_scroll_pos();
+    assert!(pos.y > 0. && pos.y < 490.);
+
+    // Decay at 2 per second and stop below 20px/s: (1000 - 20) / 2.
+    let pos = run_until_rest(&mut wnd, &scroll);
+    assert!((pos.y - 490.).abs() < 0.01, "{pos:?}");
+    assert_eq!(pos.x, 0.);
+    wnd.draw_frame();
+    let child_pos = wnd.layout_info_by_path(&[0, 0, 0]).unwrap().pos;
+    assert!((child_pos.y + 490.).abs() < 0.01);
+  }
+
+  #[test]
+  fn fling_stops_at_edge() {
+    reset_test_env!();
+
+    let (mut wnd, scroll) = scroll_window(ScrollPhysics::default());
+    scroll.write().fling(Vector::new(0., 5000.));
+    assert_eq!(run_until_rest(&mut wnd, &scroll), Point::new(0., 900.));
+  }
+
+  #[test]
+  fn overscroll_bounces_back() {
+    reset_test_env!();
+
+    let physics = ScrollPhysics { overscroll: true, ..Default::default() };
+    let (mut wnd, scroll) = scroll_window(physics);
+    scroll.write().fling(Vector::new(0., 5000.));
+    let mut max = 0f32;
+    while scroll.read().is_moving() {
+      wnd.advance_time(Duration::from_millis(16));
+      max = max.max(scroll.read().get_scroll_pos().y);
+    }
+    assert!(max > 900.);
+    assert_eq!(scroll.read().get_scroll_pos(), Point::new(0., 900.));
+  }
+
+  #[test]
+  fn snap_to_nearest() {
+    reset_test_env!();
+
+    let snap = ScrollSnap::Offsets(vec![0., 120., 450., 700.]);
+    assert_eq!(snap.nearest(300., 900.), 450.);
+    assert_eq!(snap.nearest(200., 900.), 120.);
+    assert_eq!(ScrollSnap::Interval(100.).nearest(1230., 900.), 900.);
+
+    let physics = ScrollPhysics { snap: ScrollSnap::Interval(100.), ..Default::default() };
+    let (mut wnd, scroll) = scroll_window(physics);
+    scroll.write().fling(Vector::new(0., 1000.));
+    assert_eq!(run_until_rest(&mut wnd, &scroll), Point::new(0., 500.));
+
+    // From 500, the momentum stops at 990 and clamps to 900, the item nearest
+    // to it is 700.
+    scroll.write().physics.snap = snap;
+    scroll.write().fling(Vector::new(0., 1000.));
+    assert_eq!(run_until_rest(&mut wnd, &scroll), Point::new(0., 700.));
+  }
+
+  #[test]
+  fn smooth_wheel() {
+    reset_test_env!();
+
+    let physics = ScrollPhysics { smooth_wheel: true, ..Default::default() };
+    let (mut wnd, scroll) = scroll_window(physics);
+    scroll.write().wheel(0., 100.);
+    wnd.advance_time(Duration::from_millis(16));
+    let pos = scroll.read().get_scroll_pos().y;
+    assert!(pos > 0. && pos < 100.);
+    // The steps accumulate to the target of the previous one.
+    scroll.write().wheel(0., 100.);
+    assert_eq!(run_until_rest(&mut wnd, &scroll), Point::new(0., 200.));
+  }
+
+  fn pen(position: Point, state: PenState) -> PenPacket {
+    PenPacket {
+      position,
+      state,
+      pressure: 0.5,
+      tilt_x: 0.,
+      tilt_y: 0.,
+      twist: 0.,
+      is_eraser: false,
+    }
+  }
+
+  #[test]
+  fn pen_drag_to_fling() {
+    reset_test_env!();
+
+    let (mut wnd, scroll) = scroll_window(ScrollPhysics::default());
+    let pen = |y: f32, state: PenState| pen(Point::new(50., y), state);
+    wnd.process_pen_input(pen(90., PenState::Contact));
+    wnd.draw_frame();
+    for i in 1..=5 {
+      wnd.advance_time(Duration::from_millis(10));
+      wnd.process_pen_input(pen(90. - i as f32 * 10., PenState::Contact));
+      wnd.draw_frame();
+    }
+    assert_eq!(scroll.read().get_scroll_pos(), Point::new(0., 50.));
+
+    // Lift at 1000px/s, keeps moving for another 490px.
+    wnd.process_pen_input(pen(40., PenState::Hover));
+    wnd.draw_frame();
+    assert!(scroll.read().is_moving());
+    let pos = run_until_rest(&mut wnd, &scroll);
+    assert!((pos.y - 540.).abs() < 0.01, "{pos:?}");
+  }
+
+  /// A page that scrolls vertically, and has a nested scrollable widget of
+  /// 100x50 at its top.
+  fn nested_window(
+    inner: ScrollableWidget, content: Size,
+  ) -> (TestWindow, Stateful<ScrollableWidget>, Stateful<ScrollableWidget>) {
+    let outer = Stateful::new(ScrollableWidget::default());
+    let inner = Stateful::new(inner);
+    let (c_outer, c_inner) = (outer.clone_writer(), inner.clone_writer());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let (outer, inner) = (c_outer.clone_writer(), c_inner.clone_writer());
+        @$outer {
+          @MockStack {
+            @MockBox { size: Size::new(100., 300.) }
+            @MockBox {
+              size: Size::new(100., 50.),
+              @$inner { @MockBox { size: content } }
+            }
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    (wnd, outer, inner)
+  }
+
+  /// Drag the pen by the `steps` and lift it without a velocity.
+  fn pen_drag(wnd: &mut TestWindow, from: Point, steps: &[Vector]) {
+    wnd.process_pen_input(pen(from, PenState::Contact));
+    wnd.draw_frame();
+    let mut pos = from;
+    for step in steps {
+      pos += *step;
+      wnd.advance_time(Duration::from_millis(10));
+      wnd.process_pen_input(pen(pos, PenState::Contact));
+      wnd.draw_frame();
+    }
+    wnd.advance_time(Duration::from_millis(200));
+    wnd.process_pen_input(pen(pos, PenState::Hover));
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn nested_drag_hands_over_at_edge() {
+    reset_test_env!();
+
+    let (mut wnd, outer, inner) = nested_window(<_>::default(), Size::new(100., 150.));
+    pen_drag(&mut wnd, Point::new(50., 40.), &[Vector::new(0., -20.); 6]);
+    assert_eq!(inner.read().get_scroll_pos(), Point::new(0., 100.));
+    assert_eq!(outer.read().get_scroll_pos(), Point::new(0., 20.));
+
+    // Back down, the inner one takes it first.
+    pen_drag(&mut wnd, Point::new(50., 10.), &[Vector::new(0., 20.); 2]);
+    assert_eq!(inner.read().get_scroll_pos(), Point::new(0., 60.));
+    assert_eq!(outer.read().get_scroll_pos(), Point::new(0., 20.));
+  }
+
+  #[test]
+  fn nested_drag_locks_axis() {
+    reset_test_env!();
+
+    let carousel = ScrollableWidget { scrollable: Scrollable::X, ..Default::default() };
+    let (mut wnd, outer, inner) = nested_window(carousel, Size::new(300., 50.));
+
+    // A mostly vertical drag over the carousel scrolls the page only.
+    pen_drag(&mut wnd, Point::new(60., 40.), &[Vector::new(-5., -20.); 4]);
+    assert_eq!(inner.read().get_scroll_pos(), Point::zero());
+    assert_eq!(outer.read().get_scroll_pos(), Point::new(0., 80.));
+
+    // A mostly horizontal drag scrolls the carousel only.
+    outer.write().jump_to(Point::zero());
+    wnd.draw_frame();
+    pen_drag(&mut wnd, Point::new(90., 40.), &[Vector::new(-20., 5.); 4]);
+    assert_eq!(inner.read().get_scroll_pos(), Point::new(80., 0.));
+    assert_eq!(outer.read().get_scroll_pos(), Point::zero());
+  }
+
+  #[test]
+  fn nested_overscroll() {
+    reset_test_env!();
+
+    let carousel = ScrollableWidget {
+      scrollable: Scrollable::X,
+      physics: ScrollPhysics { overscroll: true, ..Default::default() },
+      ..Default::default()
+    };
+    let (mut wnd, outer, inner) = nested_window(carousel, Size::new(300., 50.));
+
+    // 200 to the edge, and the rest 60 overscrolls the carousel by half.
+    let steps = [Vector::new(-20., 0.); 13];
+    wnd.process_pen_input(pen(Point::new(95., 40.), PenState::Contact));
+    wnd.draw_frame();
+    let mut pos = Point::new(95., 40.);
+    for step in steps {
+      pos += step;
+      wnd.advance_time(Duration::from_millis(10));
+      wnd.process_pen_input(pen(pos, PenState::Contact));
+      wnd.draw_frame();
+    }
+    assert_eq!(inner.read().get_scroll_pos(), Point::new(230., 0.));
+    assert_eq!(outer.read().get_scroll_pos(), Point::zero());
+
+    // Lift, it bounces back to the edge.
+    wnd.process_pen_input(pen(pos, PenState::Hover));
+    wnd.draw_frame();
+    assert_eq!(run_until_rest(&mut wnd, &inner), Point::new(200., 0.));
+    assert_eq!(outer.read().get_scroll_pos(), Point::zero());
+  }
+
+  #[test]
+  fn nested_wheel() {
+    reset_test_env!();
+
+    let (mut wnd, outer, inner) = nested_window(<_>::default(), Size::new(100., 150.));
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (50., 20.).into() });
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::PixelDelta((0., -150.).into()),
+      phase: TouchPhase::Started,
+    });
+    wnd.draw_frame();
+    assert_eq!(inner.read().get_scroll_pos(), Point::new(0., 100.));
+    assert_eq!(outer.read().get_scroll_pos(), Point::new(0., 50.));
+  }
 }
@@ -0,0 +1,229 @@
+use crate::{clock, prelude::*};
+
+/// The builtin widget that names its widget for the assistive technologies,
+/// such as a screen reader.
+///
+/// A focusable widget without a label is named by the text in it, so a label
+/// is only needed if the widget has no text, like an icon button, or its text
+/// doesn't describe it well. See [`Window::accessible_name`].
+///
+/// ```rust
+/// use ribir_core::prelude::*;
+///
+/// let _w = fn_widget! {
+///   @Container {
+///     size: Size::new(24., 24.),
+///     tab_index: 0i16,
+///     semantics_label: "Close",
+///   }
+/// };
+/// ```
+#[derive(Default)]
+pub struct SemanticsLabel {
+  pub semantics_label: CowArc<str>,
+}
+
+impl Declare for SemanticsLabel {
+  type Builder = FatObj<()>;
+  #[inline]
+  fn declarer() -> Self::Builder { FatObj::new(()) }
+}
+
+impl<'c> ComposeChild<'c> for SemanticsLabel {
+  type Child = Widget<'c>;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    child.try_unwrap_state_and_attach(this)
+  }
+}
+
+impl Window {
+  /// The name of the widget `id` for the assistive technologies. It's the
+  /// [`SemanticsLabel`] of the widget if it has one, or the texts in the
+  /// widget joined by spaces. `None` if the widget has neither.
+  pub fn accessible_name(&self, id: WidgetId) -> Option<CowArc<str>> {
+    accessible_name(id, self.tree())
+  }
+}
+
+pub(crate) fn accessible_name(id: WidgetId, tree: &WidgetTree) -> Option<CowArc<str>> {
+  let label = id
+    .query_ref::<SemanticsLabel>(tree)
+    .map(|l| l.semantics_label.clone())
+    .filter(|l| !l.is_empty());
+  if label.is_some() {
+    return label;
+  }
+
+  text_content_of(id, tree)
+}
+
+/// The texts in the widget `id` joined by spaces, `None` if it has no text.
+pub(crate) fn text_content_of(id: WidgetId, tree: &WidgetTree) -> Option<CowArc<str>> {
+  let texts: Vec<_> = id
+    .descendants(tree)
+    .filter_map(|id| id.get(tree).and_then(|r| r.text_content()))
+    .filter(|t| !t.trim().is_empty())
+    .collect();
+  (!texts.is_empty()).then(|| texts.join(" ").into())
+}
+
+/// The semantics of the window for the assistive technologies.
+pub struct Semantics;
+
+impl Semantics {
+  /// Queue the `message` to speak to the users of the assistive technologies,
+  /// even if the focus is elsewhere. The queued messages are spoken at the end
+  /// of the frame.
+  ///
+  /// The same message is announced only once in a short while, and an
+  /// [`Politeness::Assertive`] message drops the polite ones that are queued
+  /// before it.
+  ///
+  /// ```rust
+  /// use ribir_core::prelude::*;
+  ///
+  /// let _w = fn_widget! {
+  ///   @Container {
+  ///     size: Size::new(100., 100.),
+  ///     on_tap: move |e| {
+  ///       Semantics::announce(&e.window(), "3 new messages", Politeness::Polite);
+  ///     },
+  ///   }
+  /// };
+  /// ```
+  pub fn announce(wnd: &Window, message: impl Into<CowArc<str>>, politeness: Politeness) {
+    wnd
+      .announcer
+      .borrow_mut()
+      .push(message.into(), politeness, clock::now());
+  }
+}
+
+/// A widget that announces the texts in it when they change, such as a status
+/// label. The texts that it shows at the beginning are not announced.
+///
+/// ```rust
+/// use ribir_core::prelude::*;
+///
+/// let unread = Stateful::new(0);
+/// let _w = fn_widget! {
+///   @LiveRegion {
+///     @Text { text: pipe!(*$unread).map(|n| format!("{n} new messages")) }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct LiveRegion {
+  /// The politeness of the announcements, it's read when the widget is
+  /// mounted.
+  #[declare(default)]
+  pub politeness: Politeness,
+}
+
+impl<'c> ComposeChild<'c> for LiveRegion {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    let w = fn_widget! {
+      let child = FatObj::new(child);
+      @ $child {
+        on_mounted: move |e| {
+          let wnd = e.window();
+          wnd
+            .announcer
+            .borrow_mut()
+            .add_live_region(e.current_target(), $this.politeness, wnd.tree());
+        },
+        on_disposed: move |e| {
+          e.window()
+            .announcer
+            .borrow_mut()
+            .remove_live_region(e.current_target());
+        },
+      }
+    };
+    w.into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn name_by_label_or_text() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox {
+          size: Size::new(20., 20.),
+          tab_index: 0i16,
+          semantics_label: "Close",
+          @Text { text: "X" }
+        }
+        @MockBox {
+          size: Size::new(20., 20.),
+          tab_index: 0i16,
+          @MockMulti {
+            @Text { text: "Save" }
+            @Text { text: "all" }
+          }
+        }
+        @MockBox { size: Size::new(20., 20.), tab_index: 0i16 }
+      }
+    });
+    wnd.draw_frame();
+
+    let dump = wnd.tree_dump();
+    let names: Vec<_> = dump
+      .nodes
+      .iter()
+      .filter(|n| n.path.len() == 2)
+      .map(|n| n.accessible_name.clone())
+      .collect();
+    assert_eq!(names, [Some("Close".into()), Some("Save all".into()), None]);
+    assert!(dump.normalized().contains("\"Save all\""));
+  }
+
+  #[test]
+  fn announce_live_region_changes() {
+    reset_test_env!();
+
+    let sink = crate::announce::MockAnnounceSink::default();
+    AppCtx::set_announce_sink(Box::new(sink.clone()));
+    let unread = Stateful::new(0);
+    let c_unread = unread.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @LiveRegion {
+        @Text { text: pipe!(*$unread).map(|n| format!("{n} new messages")) }
+      }
+    });
+    wnd.draw_frame();
+    assert!(sink.announced().is_empty());
+
+    *c_unread.write() = 3;
+    wnd.draw_frame();
+    let polite = |m: &str| (m.to_string(), Politeness::Polite);
+    assert_eq!(sink.announced(), [polite("3 new messages")]);
+
+    // The same message in a short while is announced once.
+    *c_unread.write() = 4;
+    wnd.draw_frame();
+    *c_unread.write() = 3;
+    wnd.draw_frame();
+    assert_eq!(sink.announced(), [polite("3 new messages"), polite("4 new messages")]);
+
+    // An assertive message drops the polite ones queued before it.
+    Semantics::announce(&wnd, "Syncing", Politeness::Polite);
+    Semantics::announce(&wnd, "Connection lost", Politeness::Assertive);
+    wnd.draw_frame();
+    assert_eq!(sink.announced()[2..], [("Connection lost".to_string(), Politeness::Assertive)]);
+
+    wnd.advance_time(Duration::from_secs(1));
+    *c_unread.write() = 4;
+    wnd.draw_frame();
+    assert_eq!(sink.announced().last(), Some(&polite("4 new messages")));
+  }
+}
@@ -40,6 +40,41 @@ pub mod named_svgs {
   /// [`named_svgs::register`](register).
   pub fn get(name: &str) -> Option<Svg> { SVGS.lock().unwrap().get(name).cloned() }
 
+  /// A task that parses the SVG in the idle time and registers it with the
+  /// `name`, so the screen that's likely shown next can
+  /// [`named_svgs::get`](get) it without parsing it in its build. It's skipped
+  /// if the `name` is registered when the task runs.
+  ///
+  /// Spawn it by [`AppCtx::spawn_idle`], and own it by the widget that it
+  /// prepares for, so it's dropped if that widget is disposed first:
+  ///
+  /// ```no_run
+  /// use ribir_core::prelude::*;
+  ///
+  /// let logo = r#"<svg xmlns="http://www.w3.org/2000/svg" height="24" width="24"/>"#;
+  /// let _w = fn_widget! {
+  ///   @Void {
+  ///     on_mounted: move |e| {
+  ///       let task = named_svgs::prefetch("app::logo", logo, true, false);
+  ///       AppCtx::spawn_idle(task.owned_by(&*e));
+  ///     }
+  ///   }
+  /// };
+  /// ```
+  pub fn prefetch(
+    name: &'static str, data: impl AsRef<[u8]> + 'static, inherit_fill: bool, inherit_stroke: bool,
+  ) -> IdleTask {
+    IdleTask::new(move || {
+      if get(name).is_none() {
+        match Svg::parse_from_bytes(data.as_ref(), inherit_fill, inherit_stroke) {
+          Ok(svg) => register(name, svg),
+          Err(err) => log::warn!("Prefetch the svg `{name}` failed: {err}"),
+        }
+      }
+      IdleStep::Done
+    })
+  }
+
   /// Functions similarly to [`named_svgs::get`](get), but returns the
   /// default SVG if not found.
   pub fn get_or_default(name: &str) -> Svg {
@@ -81,4 +116,17 @@ mod tests {
   }
 
   painter_backend_eq_image_test!(svgs_smoke, comparison = 0.001);
+
+  #[test]
+  fn prefetch_named_svg() {
+    crate::reset_test_env!();
+
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" height="24" width="24"/>"#;
+    let handle = AppCtx::spawn_idle(named_svgs::prefetch("test::prefetch", svg, false, false));
+    assert!(named_svgs::get("test::prefetch").is_none());
+
+    AppCtx::run_idle_tasks(crate::clock::now() + Duration::from_millis(4));
+    assert!(handle.is_finished());
+    assert_eq!(named_svgs::get("test::prefetch").unwrap().size(), Size::new(24., 24.));
+  }
 }
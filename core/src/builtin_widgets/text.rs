@@ -1,4 +1,7 @@
-use std::cell::{Ref, RefCell};
+use std::{
+  cell::{Cell, Ref, RefCell},
+  rc::Rc,
+};
 
 use font_db::GlyphBaseline;
 use typography::PlaceLineDirection;
@@ -14,6 +17,16 @@ pub struct Text {
   pub text_align: TextAlign,
   #[declare(skip)]
   glyphs: RefCell<Option<VisualGlyphs>>,
+  #[declare(skip)]
+  overflow: Cell<bool>,
+  /// The shadows and the outline of the text style in the last layout, they
+  /// are painted but not laid out.
+  #[declare(skip)]
+  effects: RefCell<(Vec<TextShadow>, Option<TextStroke>)>,
+  /// The full text when it is truncated, otherwise an empty string. Only used
+  /// when `overflow_tooltip` is enabled.
+  #[declare(custom, default)]
+  overflow_tips: Option<Rc<RefCell<CowArc<str>>>>,
 }
 
 impl Render for Text {
@@ -31,7 +44,13 @@ impl Render for Text {
       );
 
     let size = info.visual_rect().size;
+    let overflow = info.metrics(clamp.max).did_overflow;
     *self.glyphs.borrow_mut() = Some(info);
+    *self.effects.borrow_mut() = (style.shadows.clone(), style.stroke);
+    self.overflow.set(overflow);
+    if let Some(tips) = &self.overflow_tips {
+      *tips.borrow_mut() = if overflow { self.text.clone() } else { CowArc::default() };
+    }
 
     clamp.clamp(size)
   }
@@ -41,9 +60,16 @@ impl Render for Text {
 
   fn paint(&self, ctx: &mut PaintingCtx) {
     let box_rect = Rect::from_size(ctx.box_size().unwrap());
+    let (shadows, stroke) = &*self.effects.borrow();
+    // The shadows and the outline may paint outside of the box.
+    let ink_rect = shadows
+      .iter()
+      .map(|s| s.ink_rect(&box_rect))
+      .chain(stroke.map(|s| s.ink_rect(&box_rect)))
+      .fold(box_rect, |ink, rect| ink.union(&rect));
     if ctx
       .painter()
-      .intersection_paint_bounds(&box_rect)
+      .intersection_paint_bounds(&ink_rect)
       .is_none()
     {
       return;
@@ -51,19 +77,66 @@ impl Render for Text {
 
     let visual_glyphs = self.glyphs().unwrap();
     let font_db = AppCtx::font_db().clone();
-    ctx
-      .painter()
-      .draw_glyphs_in_rect(&visual_glyphs, box_rect, &font_db.borrow());
+    ctx.painter().draw_glyphs_with_effects(
+      &visual_glyphs,
+      box_rect,
+      shadows,
+      stroke.as_ref(),
+      &font_db.borrow(),
+    );
+  }
+
+  fn text_content(&self) -> Option<CowArc<str>> { Some(self.text.clone()) }
+
+  fn text_baselines(&self) -> Vec<f32> {
+    self
+      .glyphs()
+      .map_or(vec![], |g| g.line_baselines())
   }
 }
 
 impl Text {
   pub fn new<const M: u8>(text: impl Into<CowArc<str>>) -> Self {
-    Self { text: text.into(), text_align: TextAlign::Start, glyphs: Default::default() }
+    Self {
+      text: text.into(),
+      text_align: TextAlign::Start,
+      glyphs: Default::default(),
+      overflow: Default::default(),
+      effects: Default::default(),
+      overflow_tips: None,
+    }
   }
   pub fn glyphs(&self) -> Option<Ref<VisualGlyphs>> {
     Ref::filter_map(self.glyphs.borrow(), |v| v.as_ref()).ok()
   }
+
+  /// Return true if the text was truncated by its clamp in the last layout.
+  pub fn did_overflow(&self) -> bool { self.overflow.get() }
+}
+
+pub trait TextDeclarerCustomExtend {
+  /// Show the full text as the tooltips when the text is truncated.
+  ///
+  /// It follows the layout of the text, so the tooltips disappear when the
+  /// text is no longer truncated, e.g. after the window is enlarged.
+  fn overflow_tooltip(self, enable: bool) -> Self;
+}
+
+impl TextDeclarerCustomExtend for FatObj<TextDeclarer> {
+  fn overflow_tooltip(mut self, enable: bool) -> Self {
+    if !enable {
+      return self;
+    }
+    let full_text = Rc::new(RefCell::new(CowArc::default()));
+    self.overflow_tips = Some(DeclareInit::Value(Some(full_text.clone())));
+    let tooltips = self.get_tooltips_widget().clone_writer();
+    self.on_performed_layout(move |_| {
+      let full_text = full_text.borrow();
+      if tooltips.read().tooltips != *full_text {
+        tooltips.write().tooltips = full_text.clone();
+      }
+    })
+  }
 }
 
 macro_rules! define_text_with_theme_style {
@@ -96,7 +169,11 @@ define_text_with_theme_style!(H6, title_small);
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
-  use ribir::{core::test_helper::*, material as ribir_material, prelude::*};
+  use ribir::{
+    core::{reset_test_env, test_helper::*},
+    material as ribir_material,
+    prelude::*,
+  };
   use ribir_dev_helper::*;
 
   const WND_SIZE: Size = Size::new(164., 64.);
@@ -151,4 +228,150 @@ mod tests {
     .with_wnd_size(WND_SIZE)
     .with_comparison(0.00004)
   );
+
+  #[test]
+  fn effects_keep_layout_size() {
+    reset_test_env!();
+
+    let mut plain = TestWindow::new_with_size(
+      fn_widget! {
+        @Text { text: "Hello ribir!", font_size: 24., margin: EdgeInsets::all(8.) }
+      },
+      WND_SIZE,
+    );
+    plain.draw_frame();
+    let mut effects = TestWindow::new_with_size(
+      fn_widget! {
+        @Text {
+          text: "Hello ribir!",
+          font_size: 24.,
+          margin: EdgeInsets::all(8.),
+          text_shadows: vec![
+            TextShadow { offset: Vector::new(3., 3.), blur: 0., color: Color::RED },
+            TextShadow { offset: Vector::new(-2., 4.), blur: 4., color: Color::BLUE },
+          ],
+          text_stroke: Some(TextStroke { width: 3., color: Color::GREEN }),
+        }
+      },
+      WND_SIZE,
+    );
+    effects.draw_frame();
+    let size = |wnd: &TestWindow| wnd.layout_info_by_path(&[0]).unwrap().size;
+    assert_eq!(size(&plain), size(&effects));
+    // The shadows and the outline paint around the glyphs.
+    let cmds = |wnd: &mut TestWindow| wnd.take_last_frame().unwrap().commands.len();
+    assert_eq!(cmds(&mut effects), cmds(&mut plain) * 12);
+  }
+
+  #[test]
+  fn measure_as_laid_out() {
+    reset_test_env!();
+
+    let style = TextStyle { overflow: TextOverflow::AutoWrap, ..<_>::default() };
+    let cases = [
+      ("Hello ribir!", 160.),
+      ("hello world, nice to meet you.", 60.),
+      ("line one\nline two", 160.),
+      ("Unbreakable", 20.),
+      ("", 100.),
+    ];
+    for (text, max_width) in cases {
+      let c_style = style.clone();
+      let mut wnd = TestWindow::new_with_size(
+        fn_widget! {
+          @MockBox {
+            size: Size::new(max_width, 500.),
+            @Text { text, text_style: c_style.clone() }
+          }
+        },
+        Size::new(500., 500.),
+      );
+      wnd.draw_frame();
+
+      let metrics = AppCtx::typography_store()
+        .borrow_mut()
+        .measure(text, &style, max_width);
+      let laid_out = wnd.layout_info_by_path(&[0, 0]).unwrap();
+      assert_eq!(metrics.size, laid_out.size.unwrap(), "{text:?}");
+    }
+  }
+
+  #[test]
+  fn measure_lines_and_overflow() {
+    reset_test_env!();
+
+    let style = TextStyle { overflow: TextOverflow::AutoWrap, ..<_>::default() };
+    let mut store = AppCtx::typography_store().borrow_mut();
+    let one_line = store.measure("Hello ribir!", &style, f32::INFINITY);
+    assert_eq!(one_line.line_count, 1);
+    assert_eq!(one_line.baselines.len(), 1);
+    assert!(!one_line.did_overflow);
+
+    let wrapped = store.measure("Hello ribir!", &style, one_line.size.width / 2.);
+    assert!(wrapped.line_count > 1);
+    assert_eq!(wrapped.baselines.len(), wrapped.line_count);
+    assert!(wrapped.size.height > one_line.size.height);
+
+    let clipped = TextStyle { overflow: TextOverflow::Clip, ..style.clone() };
+    let clipped = store.measure("Hello ribir!", &clipped, one_line.size.width / 2.);
+    assert_eq!(clipped.line_count, 1);
+    assert!(clipped.did_overflow);
+
+    let glyphs = store.glyph_positions("Hi", &style, f32::INFINITY);
+    assert_eq!(glyphs.len(), 2);
+    assert_eq!((glyphs[0].cluster, glyphs[1].cluster), (0, 1));
+    assert_eq!(glyphs[0].rect.max_x(), glyphs[1].rect.min_x());
+    let width = store
+      .measure("Hi", &style, f32::INFINITY)
+      .size
+      .width;
+    assert_eq!(glyphs[1].rect.max_x(), width);
+  }
+
+  widget_image_tests!(
+    shadowed_text,
+    WidgetTester::new(fn_widget! {
+      @Text {
+        text: "Hello ribir!",
+        font_size: 24.,
+        margin: EdgeInsets::all(8.),
+        text_shadows: vec![TextShadow { offset: Vector::new(2., 2.), blur: 3., color: Color::RED }],
+      }
+    })
+    .with_wnd_size(WND_SIZE)
+  );
+
+  widget_image_tests!(
+    outlined_text,
+    WidgetTester::new(fn_widget! {
+      @Text {
+        text: "Hello ribir!",
+        font_size: 24.,
+        margin: EdgeInsets::all(8.),
+        text_stroke: Some(TextStroke { width: 2., color: Color::RED }),
+      }
+    })
+    .with_wnd_size(WND_SIZE)
+  );
+
+  #[cfg(feature = "png")]
+  widget_image_tests!(
+    color_emoji_text,
+    WidgetTester::new(fn_widget! {
+      @Text {
+        text: "Hi \u{2764}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}",
+        font_size: 24.,
+        margin: EdgeInsets::all(8.),
+      }
+    })
+    .with_wnd_size(WND_SIZE)
+    .with_env_init(|| {
+      let mut font_db = AppCtx::font_db().borrow_mut();
+      font_db.load_from_bytes(include_bytes!("../../../fonts/RibirTestEmoji.ttf").to_vec());
+      font_db.set_emoji_fonts(&FontFace {
+        families: Box::new([FontFamily::Name("Ribir Test Emoji".into())]),
+        ..<_>::default()
+      });
+    })
+  );
 }
@@ -20,7 +20,9 @@ mod compose_decorators;
 pub use compose_decorators::*;
 mod custom_styles;
 pub use custom_styles::*;
+mod sound_theme;
 pub use ribir_painter::*;
+pub use sound_theme::*;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Brightness {
@@ -98,6 +100,39 @@ pub struct Theme {
   /// allows the application developer to supply the font file. Certainly, the
   /// icon also works with `SVG` and [`named_svgs`](super::named_svgs).
   pub icon_font: FontFace,
+  /// The minimum hit area of the built-in controls in a touch-first window.
+  pub touch_target_size: TouchTargetSize,
+  /// The sounds that the built-in controls play for the interactions.
+  pub sound_theme: SoundTheme,
+  /// The outline of the widget that the keyboard moves the focus to.
+  pub focus_ring: FocusRing,
+  /// The longest time between two taps of a double tap or any other multi-tap,
+  /// unless the listener gives its own interval.
+  pub multi_tap_interval: Duration,
+}
+
+/// The minimum size of the area that a control receives taps in a
+/// touch-first window, see [`TouchTarget`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TouchTargetSize(pub Size);
+
+impl Default for TouchTargetSize {
+  fn default() -> Self { Self(Size::new(48., 48.)) }
+}
+
+/// The outline that the window paints around the focused widget when the
+/// focus is moved by the keyboard, see [`Window::focus_ring_rect`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FocusRing {
+  pub color: Color,
+  /// The width of the outline, in logical pixels.
+  pub thickness: f32,
+  /// The gap between the outline and the widget, in logical pixels.
+  pub offset: f32,
+}
+
+impl Default for FocusRing {
+  fn default() -> Self { Self { color: Palette::default().primary(), thickness: 2., offset: 1. } }
 }
 
 impl Theme {
@@ -229,6 +264,18 @@ impl<T: StateWriter<Value = Theme>> Query for ThemeQuerier<T> {
     })
   }
   fn queryable(&self) -> bool { true }
+
+  fn type_bloom(&self, bloom: &mut QueryBloom) {
+    bloom.insert(&QueryId::of::<Theme>());
+    bloom.insert(&QueryId::of::<Palette>());
+    bloom.insert(&QueryId::of::<TypographyTheme>());
+    bloom.insert(&QueryId::of::<TextStyle>());
+    bloom.insert(&QueryId::of::<Classes>());
+    bloom.insert(&QueryId::of::<IconTheme>());
+    bloom.insert(&QueryId::of::<TransitionTheme>());
+    bloom.insert(&QueryId::of::<ComposeDecorators>());
+    bloom.insert(&QueryId::of::<CustomStyles>());
+  }
 }
 
 impl Default for Theme {
@@ -254,6 +301,10 @@ impl Default for Theme {
       font_bytes: vec![],
       font_files: vec![],
       icon_font: Default::default(),
+      touch_target_size: Default::default(),
+      sound_theme: Default::default(),
+      focus_ring: Default::default(),
+      multi_tap_interval: Duration::from_millis(250),
     }
   }
 }
@@ -267,7 +318,14 @@ fn typography_theme() -> TypographyTheme {
     };
     let overflow = TextOverflow::Clip;
     TextTheme {
-      text: TextStyle { line_height, font_size, letter_space, font_face, overflow },
+      text: TextStyle {
+        line_height,
+        font_size,
+        letter_space,
+        font_face,
+        overflow,
+        ..<_>::default()
+      },
       decoration: TextDecorationStyle {
         decoration: TextDecoration::NONE,
         decoration_color: Color::BLACK.with_alpha(0.87).into(),
@@ -330,4 +388,36 @@ mod tests {
 
     assert_eq!(*watcher.read(), [Brightness::Light, Brightness::Dark, Brightness::Light]);
   }
+
+  #[test]
+  fn custom_style_shadowing() {
+    reset_test_env!();
+
+    #[derive(Clone, Default, Debug, PartialEq, CustomStyle)]
+    struct Tokens(u32);
+
+    let (watcher, writer) = split_value(vec![]);
+    let w = fn_widget! {
+      // No ancestor provides the tokens, the default style is used.
+      $writer.write().push(BuildCtx::get().theme_of::<Tokens>());
+      let writer = writer.clone_writer();
+      let mut theme = Theme::default();
+      theme.custom_styles.set_custom_style(Tokens(1));
+      theme.with_child(fn_widget! {
+        $writer.write().push(BuildCtx::get().theme_of::<Tokens>());
+        let writer = writer.clone_writer();
+        Provider::new(Box::new(Queryable(Tokens(2))))
+          .with_child(fn_widget! {
+            $writer.write().push(BuildCtx::get().theme_of::<Tokens>());
+            Void
+          })
+          .into_widget()
+      })
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    assert_eq!(*watcher.read(), [Tokens(0), Tokens(1), Tokens(2)]);
+  }
 }
@@ -4,6 +4,14 @@ use crate::prelude::*;
 
 type ComposeDecoratorFn = dyn for<'r> Fn(Box<dyn Any>, Widget<'r>, &BuildCtx) -> Widget<'r>;
 /// Compose style is a compose child widget to decoration its child.
+///
+/// The theme can override how a decorator composes its host, or stack more
+/// decorators on it. When more than one decorator applies to a host, the
+/// inner one composes the host first, and the outer one receives the result:
+///
+/// - for nested decorators, such as `@A { @B { host } }`, `B` is the inner one.
+/// - for the decorators registered to the same type, the later registered one
+///   by [`ComposeDecorators::wrap_compose_decorator`] is the outer one.
 #[derive(Default)]
 pub struct ComposeDecorators {
   pub(crate) styles: ahash::HashMap<TypeId, Box<ComposeDecoratorFn>>,
@@ -14,6 +22,8 @@ pub trait ComposeDecorator: Sized {
 }
 
 impl ComposeDecorators {
+  /// Replace how the decorator `W` composes its host, all the decorators
+  /// registered to `W` before are dropped.
   #[inline]
   pub fn override_compose_decorator<W: ComposeDecorator + 'static>(
     &mut self,
@@ -22,16 +32,40 @@ impl ComposeDecorators {
     self.styles.insert(
       TypeId::of::<W>(),
       Box::new(move |this: Box<dyn Any>, host: Widget, ctx: &BuildCtx| {
-        let this = this.downcast().unwrap_or_else(|_| {
-          panic!("Caller should guarantee the boxed type is Stateful<{}>.", type_name::<W>())
-        });
+        compose_decorator(downcast_state(this), host, ctx)
+      }),
+    );
+  }
 
-        compose_decorator(*this, host, ctx)
+  /// Add a decorator to `W` that wraps the host after the decorators
+  /// registered to `W` before, or after the default composition of `W` if no
+  /// one registered.
+  pub fn wrap_compose_decorator<W: ComposeDecorator + 'static>(
+    &mut self,
+    compose_decorator: impl for<'r> Fn(State<W>, Widget<'r>, &BuildCtx) -> Widget<'r> + 'static,
+  ) {
+    let inner = self.styles.remove(&TypeId::of::<W>());
+    self.styles.insert(
+      TypeId::of::<W>(),
+      Box::new(move |this: Box<dyn Any>, host: Widget, ctx: &BuildCtx| {
+        let this: State<W> = downcast_state(this);
+        let inner_this = State::stateful(this.as_stateful().clone_writer());
+        let host = match &inner {
+          Some(inner) => inner(Box::new(inner_this), host, ctx),
+          None => W::compose_decorator(inner_this, host),
+        };
+        compose_decorator(this, host, ctx)
       }),
     );
   }
 }
 
+fn downcast_state<W: 'static>(this: Box<dyn Any>) -> State<W> {
+  *this.downcast().unwrap_or_else(|_| {
+    panic!("Caller should guarantee the boxed type is State<{}>.", type_name::<W>())
+  })
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -72,4 +106,77 @@ mod tests {
     wnd.draw_frame();
     wnd.assert_root_size(Size::new(100., 100.));
   }
+
+  #[derive(Declare)]
+  struct Tag;
+
+  impl ComposeDecorator for Tag {
+    fn compose_decorator(_: State<Self>, host: Widget) -> Widget { host }
+  }
+
+  fn sized_box(size: f32, host: Widget) -> Widget {
+    MockBox { size: Size::splat(size) }
+      .with_child(host)
+      .into_widget()
+  }
+
+  #[test]
+  fn stacked_decorators_order() {
+    reset_test_env!();
+
+    let (order, w_order) = split_value(vec![]);
+    let mut theme = Theme::default();
+    let decorators = &mut theme.compose_decorators;
+    let c_order = w_order.clone_writer();
+    decorators.override_compose_decorator::<Tag>(move |_, host, _| {
+      c_order.write().push("override");
+      sized_box(100., host)
+    });
+    decorators.wrap_compose_decorator::<Tag>(move |_, host, _| {
+      w_order.write().push("wrap");
+      sized_box(200., host)
+    });
+    AppCtx::set_app_theme(theme);
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! { @Tag { @MockBox { size: Size::zero() } } },
+      Size::new(500., 500.),
+    );
+    wnd.draw_frame();
+
+    assert_eq!(*order.read(), ["override", "wrap"]);
+    // The outer box is the one added by the later decorator.
+    wnd.assert_root_size(Size::new(200., 200.));
+    assert_eq!(wnd.layout_info_by_path(&[0, 0]).unwrap().size, Some(Size::splat(100.)));
+  }
+
+  #[test]
+  fn replace_theme_at_runtime() {
+    reset_test_env!();
+
+    fn theme_with_size(size: f32) -> Theme {
+      let mut theme = Theme::default();
+      theme
+        .compose_decorators
+        .override_compose_decorator::<Tag>(move |_, host, _| sized_box(size, host));
+      theme
+    }
+
+    let theme = Stateful::new(theme_with_size(100.));
+    let c_theme = theme.clone_writer();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        c_theme.clone_writer().with_child(fn_widget! {
+          @Tag { @MockBox { size: Size::zero() } }
+        })
+      },
+      Size::new(500., 500.),
+    );
+    wnd.draw_frame();
+    wnd.assert_root_size(Size::new(100., 100.));
+
+    *theme.write() = theme_with_size(50.);
+    wnd.draw_frame();
+    wnd.assert_root_size(Size::new(50., 50.));
+  }
 }
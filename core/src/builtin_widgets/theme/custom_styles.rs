@@ -15,6 +15,31 @@ macro_rules! fill_custom_style {
   };
 }
 
+/// A typed style token that a widget or an application defines when the
+/// `Palette` and `TypographyTheme` are not enough.
+///
+/// The style is retrieved from the nearest ancestor that provides it, either a
+/// `Theme` that sets it in its `custom_styles`, or a provider of the style
+/// itself. So a subtree can shadow the style of the theme by providing its own.
+/// If no ancestor provides it, the `default_style` is used.
+///
+/// Derive it to use the `Default` value as the default style.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// #[derive(Clone, Default, CustomStyle)]
+/// struct BrandTokens {
+///   accent: Color,
+/// }
+///
+/// let _w = fn_widget! {
+///   let accent = BuildCtx::get().theme_of::<BrandTokens>().accent;
+///   @Container { size: Size::new(10., 10.), background: accent }
+/// };
+/// ```
 pub trait CustomStyle: Sized + Clone + 'static {
   fn default_style(ctx: &impl ProviderCtx) -> Self;
 
@@ -22,9 +47,19 @@ pub trait CustomStyle: Sized + Clone + 'static {
   fn of(ctx: &impl ProviderCtx) -> Self {
     let tid = TypeId::of::<Self>();
     ctx
-      .all_of::<CustomStyles>()
-      .find_map(|t| {
-        t.themes
+      .all_providers()
+      .find_map(|p| {
+        if let Some(style) = p
+          .query(&QueryId::of::<Self>())
+          .and_then(QueryHandle::into_ref::<Self>)
+        {
+          return Some((*style).clone());
+        }
+        let styles = p
+          .query(&QueryId::of::<CustomStyles>())
+          .and_then(QueryHandle::into_ref::<CustomStyles>)?;
+        styles
+          .themes
           .get(&tid)
           .and_then(|c| c.downcast_ref::<Self>())
           .cloned()
@@ -24,6 +24,17 @@ pub struct IconSize {
   pub huge: Size,
 }
 
+/// A level of the [`IconSize`] of the theme, use it to size an icon without
+/// knowing the exact size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IconScale {
+  Tiny,
+  Small,
+  Medium,
+  Large,
+  Huge,
+}
+
 /// default icon, use if a icon miss in `icons`.
 pub const MISS_ICON: NamedSvg = NamedSvg(0);
 /// The icon you can named start from.
@@ -75,7 +86,18 @@ impl IconTheme {
     let mut icons = HashMap::<_, _, ahash::RandomState>::default();
     icons.insert(MISS_ICON, miss_icon);
 
-    Self { icon_size, svgs: icons }
+    let mut theme = Self { icon_size, svgs: icons };
+    // A tiny icon set that the built-in widgets use, so they work without a
+    // full icon theme. A theme can replace them by its own icons.
+    fill_svgs! { theme,
+      svgs::ARROW_DROP_DOWN: "src/builtin_widgets/default_icons/arrow_drop_down.svg",
+      svgs::CHECK_BOX: "src/builtin_widgets/default_icons/check_box.svg",
+      svgs::CHECK_BOX_OUTLINE_BLANK: "src/builtin_widgets/default_icons/check_box_outline_blank.svg",
+      svgs::DONE: "src/builtin_widgets/default_icons/done.svg",
+      svgs::EXPAND_MORE: "src/builtin_widgets/default_icons/expand_more.svg",
+      svgs::INDETERMINATE_CHECK_BOX: "src/builtin_widgets/default_icons/indeterminate_check_box.svg"
+    };
+    theme
   }
 
   /// Retrieve the nearest `IconTheme` from the context among its ancestors
@@ -106,6 +128,17 @@ impl IconSize {
   pub fn of(ctx: &impl ProviderCtx) -> QueryRef<Self> {
     QueryRef::map(IconTheme::of(ctx), |i| &i.icon_size)
   }
+
+  /// The size of the `scale` level.
+  pub fn size_of(&self, scale: IconScale) -> Size {
+    match scale {
+      IconScale::Tiny => self.tiny,
+      IconScale::Small => self.small,
+      IconScale::Medium => self.medium,
+      IconScale::Large => self.large,
+      IconScale::Huge => self.huge,
+    }
+  }
 }
 
 impl NamedSvg {
@@ -149,10 +182,13 @@ pub mod svgs {
     INDETERMINATE_CHECK_BOX,
     LOGIN,
     LOGOUT,
+    MAXIMIZE,
     MENU,
+    MINIMIZE,
     MORE_HORIZ,
     MORE_VERT,
     OPEN_IN_NEW,
+    RESTORE,
     SEARCH,
     SETTINGS,
     STAR,
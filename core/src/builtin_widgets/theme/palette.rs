@@ -134,6 +134,27 @@ impl Palette {
     Provider::write_of::<Self>(ctx).unwrap()
   }
 
+  /// Return the palette with the `brightness`. It's a convenient way to force a
+  /// subtree to use the dark or light colors of its ancestor's palette.
+  ///
+  /// ```rust
+  /// use ribir_core::prelude::*;
+  ///
+  /// let _w = fn_widget! {
+  ///   Palette::of(BuildCtx::get())
+  ///     .clone()
+  ///     .with_brightness(Brightness::Dark)
+  ///     .with_child(fn_widget! {
+  ///       // The widgets in this scope use the dark colors.
+  ///       Void
+  ///     })
+  /// };
+  /// ```
+  pub fn with_brightness(mut self, brightness: Brightness) -> Self {
+    self.brightness = brightness;
+    self
+  }
+
   #[inline]
   pub fn primary(&self) -> Color { self.base_of(&self.primary) }
 
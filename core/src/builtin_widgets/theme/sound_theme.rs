@@ -0,0 +1,57 @@
+use super::*;
+
+/// The interactions that a theme can play a sound for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEvent {
+  /// A button is pressed.
+  Press,
+  /// An error is shown to the user, such as an error toast.
+  Error,
+}
+
+/// The sounds of the interactions, the built-in controls play the sound of
+/// their interaction by [`SoundTheme::play`].
+///
+/// No sound is configured by default.
+///
+/// # Example
+///
+/// ```no_run
+/// use ribir::prelude::*;
+///
+/// let mut theme = Theme::default();
+/// let click = AppCtx::load_sound(std::fs::read("./click.ogg").unwrap());
+/// theme.sound_theme.set(SoundEvent::Press, click);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoundTheme {
+  /// Whether to play the sounds, no sound is played if it's false.
+  pub enabled: bool,
+  pub sounds: HashMap<SoundEvent, SoundId>,
+}
+
+impl SoundTheme {
+  /// Play the sound of the `event` of the nearest theme, nothing is played if
+  /// the theme has no sound for it or the sounds are disabled.
+  pub fn play(ctx: &impl ProviderCtx, event: SoundEvent) {
+    let sound = Theme::of(ctx).sound_theme.sound_of(event);
+    if let Some(id) = sound {
+      AppCtx::play_sound(id);
+    }
+  }
+
+  /// The sound to play for the `event`, it's `None` if the sounds are
+  /// disabled.
+  pub fn sound_of(&self, event: SoundEvent) -> Option<SoundId> {
+    if self.enabled { self.sounds.get(&event).copied() } else { None }
+  }
+
+  /// Set the sound of the `event`.
+  pub fn set(&mut self, event: SoundEvent, id: SoundId) -> Option<SoundId> {
+    self.sounds.insert(event, id)
+  }
+}
+
+impl Default for SoundTheme {
+  fn default() -> Self { Self { enabled: true, sounds: HashMap::new() } }
+}
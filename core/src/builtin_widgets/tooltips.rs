@@ -33,7 +33,11 @@ impl Declare for Tooltips {
 impl Tooltips {
   fn tooltips(&self) -> &CowArc<str> { &self.tooltips }
 
+  /// Show the tooltips, nothing happens if the tooltips is empty.
   pub fn show(&self, wnd: Sc<Window>) {
+    if self.tooltips.is_empty() {
+      return;
+    }
     if let Some(overlay) = self.overlay.borrow().clone() {
       if !overlay.is_showing() {
         overlay.show(wnd);
@@ -48,6 +52,15 @@ impl Tooltips {
       }
     }
   }
+
+  /// Return true if the tooltips is showing.
+  pub fn is_showing(&self) -> bool {
+    self
+      .overlay
+      .borrow()
+      .as_ref()
+      .is_some_and(Overlay::is_showing)
+  }
 }
 
 impl<'c> ComposeChild<'c> for Tooltips {
@@ -78,23 +91,36 @@ impl<'c> ComposeChild<'c> for Tooltips {
           auto_close_policy: AutoClosePolicy::NOT_AUTO_CLOSE,
           mask: None,
         }
-      ));
+      ).with_host($child.track_id()));
 
       let wnd = BuildCtx::get().window();
+      let c_wnd = wnd.clone();
       let u = watch!($child.is_hover())
         .delay(Duration::from_millis(50), AppCtx::scheduler())
         .distinct_until_changed()
         .subscribe(move |_| {
           if $child.is_hover() {
-            $this.show(wnd.clone());
+            $this.show(c_wnd.clone());
           } else {
             $this.hidden();
           }
         });
+      // An empty tooltips hides, and a hovering widget shows its tooltips once
+      // the tooltips is not empty.
+      let u2 = watch!($this.tooltips().is_empty())
+        .distinct_until_changed()
+        .subscribe(move |empty| {
+          if empty {
+            $this.hidden();
+          } else if $child.is_hover() {
+            $this.show(wnd.clone());
+          }
+        });
 
       @ $child {
         on_disposed: move|_| {
           u.unsubscribe();
+          u2.unsubscribe();
           $this.hidden();
         },
       }
@@ -102,3 +128,112 @@ impl<'c> ComposeChild<'c> for Tooltips {
     .into_widget()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::rc::Rc;
+
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, WindowEvent},
+  };
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn overflow_tooltip_only_when_truncated() {
+    reset_test_env!();
+
+    let tips: Rc<RefCell<Option<Stateful<Tooltips>>>> = <_>::default();
+    let c_tips = tips.clone();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let mut text = @Text {
+          text: "A long text that is truncated in a narrow window",
+          overflow_tooltip: true,
+        };
+        *c_tips.borrow_mut() = Some(text.get_tooltips_widget().clone_writer());
+        text
+      },
+      Size::new(80., 40.),
+    );
+    wnd.draw_frame();
+
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id: unsafe { DeviceId::dummy() },
+      position: LogicalPosition::new(5., 5.).to_physical(1.),
+    });
+    // The tooltips follows the hover state after a delay.
+    let hover_frame = |wnd: &mut TestWindow| {
+      wnd.draw_frame();
+      wnd.advance_time(Duration::from_millis(60));
+    };
+    hover_frame(&mut wnd);
+    let is_showing = || {
+      tips
+        .borrow()
+        .as_ref()
+        .unwrap()
+        .read()
+        .is_showing()
+    };
+    assert!(is_showing());
+
+    // The window is wide enough to show the full text, so no tooltips.
+    wnd.request_resize(Size::new(800., 40.));
+    hover_frame(&mut wnd);
+    assert!(!is_showing());
+
+    wnd.request_resize(Size::new(80., 40.));
+    hover_frame(&mut wnd);
+    assert!(is_showing());
+  }
+
+  #[test]
+  fn tooltips_inherit_host_palette() {
+    reset_test_env!();
+
+    thread_local! {
+      static BRIGHTNESS: RefCell<Vec<Brightness>> = const { RefCell::new(vec![]) };
+    }
+    let mut wnd = TestWindow::new(fn_widget! {
+      let mut classes = Classes::default();
+      classes.insert(TOOLTIPS, |w| {
+        let brightness = Palette::of(BuildCtx::get()).brightness;
+        BRIGHTNESS.with_borrow_mut(|b| b.push(brightness));
+        let w = FatObj::new(w);
+        @ $w {
+          on_mounted: move |e| {
+            let brightness = Palette::of(&e).brightness;
+            BRIGHTNESS.with_borrow_mut(|b| b.push(brightness));
+          },
+        }
+        .into_widget()
+      });
+      classes.with_child(fn_widget! {
+        // The app theme is light, but the subtree forces the dark colors.
+        Palette::of(BuildCtx::get())
+          .clone()
+          .with_brightness(Brightness::Dark)
+          .with_child(fn_widget! {
+            @MockBox { size: Size::new(20., 20.), tooltips: "tips" }
+          })
+      })
+    });
+    wnd.draw_frame();
+
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id: unsafe { DeviceId::dummy() },
+      position: LogicalPosition::new(5., 5.).to_physical(1.),
+    });
+    wnd.draw_frame();
+    wnd.advance_time(Duration::from_millis(60));
+    wnd.draw_frame();
+
+    // The tooltips is built and mounted with the palette of its host.
+    BRIGHTNESS.with_borrow(|b| assert_eq!(b, &[Brightness::Dark, Brightness::Dark]));
+  }
+}
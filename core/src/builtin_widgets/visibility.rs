@@ -54,6 +54,8 @@ impl WrapRender for VisibilityRender {
       HitTest { hit: false, can_hit_child: false }
     }
   }
+
+  fn is_displayed(&self, host: &dyn Render) -> bool { self.display && host.is_displayed() }
 }
 
 impl Visibility {
@@ -0,0 +1,115 @@
+use crate::{prelude::*, wrap_render::*};
+
+/// A builtin that changes the order that the widget is painted and hit among
+/// its siblings without changing the layout, e.g. to raise a dragged card
+/// above the cards after it. See [`FatObj::z_index`].
+///
+/// It works for the children of any parent, because the children are painted
+/// and hit by the framework rather than by the parent.
+#[derive(Default, Clone)]
+pub struct ZIndex {
+  pub z_index: i32,
+}
+
+impl Declare for ZIndex {
+  type Builder = FatObj<()>;
+  #[inline]
+  fn declarer() -> Self::Builder { FatObj::new(()) }
+}
+
+impl_compose_child_for_wrap_render!(ZIndex);
+
+impl WrapRender for ZIndex {
+  fn perform_layout(&self, clamp: BoxClamp, host: &dyn Render, ctx: &mut LayoutCtx) -> Size {
+    host.perform_layout(clamp, ctx)
+  }
+
+  fn z_index(&self, _: &dyn Render) -> i32 { self.z_index }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_painter::{CommandBrush, PaintCommand, PaintPathAction};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn raise_sibling_by_z_index() {
+    reset_test_env!();
+
+    let raise_first = Stateful::new(false);
+    let tapped = Stateful::new(vec![]);
+    let (c_raise, c_tapped) = (raise_first.clone_watcher(), tapped.clone_writer());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let c_tapped2 = c_tapped.clone_writer();
+        @MockStack {
+          @Container {
+            size: Size::new(100., 100.),
+            background: Color::RED,
+            z_index: pipe!(if *$c_raise { 1 } else { 0 }),
+            on_tap: move |_| $c_tapped.write().push("red"),
+          }
+          @Container {
+            size: Size::new(100., 100.),
+            background: Color::BLUE,
+            on_tap: move |_| $c_tapped2.write().push("blue"),
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+
+    let paint_order = |wnd: &mut TestWindow| {
+      wnd
+        .take_last_frame()
+        .unwrap()
+        .commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+          PaintCommand::Path(p) => match &p.action {
+            PaintPathAction::Paint { brush: CommandBrush::Color(c), .. } => Some(*c),
+            _ => None,
+          },
+          _ => None,
+        })
+        .collect::<Vec<_>>()
+    };
+    let tap = |wnd: &mut TestWindow| {
+      let device_id = unsafe { DeviceId::dummy() };
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: (50., 50.).into(),
+      });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.draw_frame();
+    };
+
+    assert_eq!(paint_order(&mut wnd), [Color::RED, Color::BLUE]);
+    tap(&mut wnd);
+    assert_eq!(*tapped.read(), ["blue"]);
+
+    *raise_first.write() = true;
+    wnd.draw_frame();
+    assert_eq!(paint_order(&mut wnd), [Color::BLUE, Color::RED]);
+    tap(&mut wnd);
+    assert_eq!(*tapped.read(), ["blue", "red"]);
+
+    // The layout order is kept.
+    let tree = wnd.tree();
+    let stack = tree.content_root();
+    let first = stack.first_child(tree).unwrap();
+    assert_eq!(stack.paint_ordered_children(tree).last(), Some(&first));
+
+    *raise_first.write() = false;
+    wnd.draw_frame();
+    assert_eq!(paint_order(&mut wnd), [Color::RED, Color::BLUE]);
+    tap(&mut wnd);
+    assert_eq!(*tapped.read(), ["blue", "red", "blue"]);
+  }
+}
@@ -1,8 +1,31 @@
-use std::{borrow::Cow, io::Error};
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  io::{Error, ErrorKind},
+};
 
-use log::warn;
 use ribir_painter::PixelImage;
 
+/// The formats of the data that the clipboard holds, see
+/// [`Clipboard::read_formats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipboardFormat {
+  Text,
+  Html,
+  Image,
+}
+
+/// A representation of the content to write to the clipboard, see
+/// [`Clipboard::write_data`].
+#[derive(PartialEq)]
+pub enum ClipboardData {
+  Text(String),
+  /// A HTML fragment, write a plain text of it together for the readers that
+  /// don't understand HTML.
+  Html(String),
+  Image(PixelImage),
+}
+
 pub trait Clipboard {
   // read the string from the clipboard
   fn read_text(&mut self) -> Result<String, Error>;
@@ -24,43 +47,252 @@ pub trait Clipboard {
 
   // clear all content in the clipboard
   fn clear(&mut self) -> Result<(), Error>;
+
+  /// Read the HTML from the clipboard.
+  fn read_html(&mut self) -> Result<String, Error> {
+    Err(Error::new(ErrorKind::Unsupported, "clipboard read_html"))
+  }
+
+  /// Write the representations of the same content at once, it replaces the
+  /// content before. The readers pick the format they prefer, see
+  /// [`Clipboard::read_formats`].
+  ///
+  /// The clipboards that hold only one format keep the last representation
+  /// they support by default.
+  fn write_data(&mut self, data: &[ClipboardData]) -> Result<(), Error> {
+    self.clear()?;
+    let mut res = Err(Error::new(ErrorKind::Unsupported, "clipboard write_data"));
+    for d in data {
+      let written = match d {
+        ClipboardData::Text(text) => self.write_text(text),
+        ClipboardData::Image(img) => self.write_img(img),
+        ClipboardData::Html(_) => continue,
+      };
+      if written.is_ok() || res.is_err() {
+        res = written;
+      }
+    }
+    res
+  }
+
+  /// The formats that the clipboard holds now, so a paste handler can choose
+  /// one of them before reading it.
+  fn read_formats(&mut self) -> Vec<ClipboardFormat> {
+    let mut formats = vec![];
+    if self.read_text().is_ok() {
+      formats.push(ClipboardFormat::Text);
+    }
+    if self.read_html().is_ok() {
+      formats.push(ClipboardFormat::Html);
+    }
+    if self.read_img().is_ok() {
+      formats.push(ClipboardFormat::Image);
+    }
+    formats
+  }
+}
+
+impl ClipboardData {
+  pub fn format(&self) -> ClipboardFormat {
+    match self {
+      ClipboardData::Text(_) => ClipboardFormat::Text,
+      ClipboardData::Html(_) => ClipboardFormat::Html,
+      ClipboardData::Image(_) => ClipboardFormat::Image,
+    }
+  }
+}
+
+/// A clipboard keeps its content in memory, it holds all the formats written
+/// at once. Use it when there is no system clipboard, such as in the tests.
+#[derive(Default)]
+pub struct MemoryClipboard {
+  data: Vec<ClipboardData>,
+  custom: HashMap<String, Vec<u8>>,
 }
 
-pub(crate) struct MockClipboard {}
+impl MemoryClipboard {
+  fn find(&self, format: ClipboardFormat) -> Result<&ClipboardData, Error> {
+    self
+      .data
+      .iter()
+      .find(|d| d.format() == format)
+      .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("no {format:?} in the clipboard")))
+  }
+}
 
-impl Clipboard for MockClipboard {
+impl Clipboard for MemoryClipboard {
   fn read_text(&mut self) -> Result<String, Error> {
-    warn!("read text from clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard read_text"))
+    match self.find(ClipboardFormat::Text)? {
+      ClipboardData::Text(text) => Ok(text.clone()),
+      _ => unreachable!(),
+    }
   }
 
-  fn write_text(&mut self, _text: &str) -> Result<(), Error> {
-    warn!("write text to clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard write_text"))
+  fn write_text(&mut self, text: &str) -> Result<(), Error> {
+    self.write_data(&[ClipboardData::Text(text.to_string())])
   }
 
   fn read_img(&mut self) -> Result<PixelImage, Error> {
-    warn!("read img from clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard read_img"))
+    match self.find(ClipboardFormat::Image)? {
+      ClipboardData::Image(img) => Ok(copy_img(img)),
+      _ => unreachable!(),
+    }
   }
 
-  fn write_img(&mut self, _img: &PixelImage) -> Result<(), Error> {
-    warn!("write img to clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard write_img"))
+  fn write_img(&mut self, img: &PixelImage) -> Result<(), Error> {
+    self.write_data(&[ClipboardData::Image(copy_img(img))])
   }
 
   fn read(&mut self, format: &str) -> Result<Cow<[u8]>, Error> {
-    warn!("read {format} data from clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard read format {format}"))
+    self
+      .custom
+      .get(format)
+      .map(|data| Cow::Borrowed(data.as_slice()))
+      .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("no {format} in the clipboard")))
   }
 
-  fn write(&mut self, format: &str, _data: &[u8]) -> Result<(), Error> {
-    warn!("write {format} data to clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard write format {format}"))
+  fn write(&mut self, format: &str, data: &[u8]) -> Result<(), Error> {
+    self
+      .custom
+      .insert(format.to_string(), data.to_vec());
+    Ok(())
   }
 
   fn clear(&mut self) -> Result<(), Error> {
-    warn!("clear content of clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard clear"))
+    self.data.clear();
+    self.custom.clear();
+    Ok(())
+  }
+
+  fn read_html(&mut self) -> Result<String, Error> {
+    match self.find(ClipboardFormat::Html)? {
+      ClipboardData::Html(html) => Ok(html.clone()),
+      _ => unreachable!(),
+    }
+  }
+
+  fn write_data(&mut self, data: &[ClipboardData]) -> Result<(), Error> {
+    self.clear()?;
+    self.data = data
+      .iter()
+      .map(|d| match d {
+        ClipboardData::Text(text) => ClipboardData::Text(text.clone()),
+        ClipboardData::Html(html) => ClipboardData::Html(html.clone()),
+        ClipboardData::Image(img) => ClipboardData::Image(copy_img(img)),
+      })
+      .collect();
+    Ok(())
+  }
+
+  fn read_formats(&mut self) -> Vec<ClipboardFormat> {
+    self.data.iter().map(|d| d.format()).collect()
+  }
+}
+
+fn copy_img(img: &PixelImage) -> PixelImage {
+  PixelImage::new(img.pixel_bytes().to_vec().into(), img.width(), img.height(), img.color_format())
+}
+
+/// Escape the plain `text` as a HTML fragment, the line breaks are kept.
+pub fn text_to_html(text: &str) -> String {
+  let mut html = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => html.push_str("&amp;"),
+      '<' => html.push_str("&lt;"),
+      '>' => html.push_str("&gt;"),
+      '"' => html.push_str("&quot;"),
+      '\n' => html.push_str("<br>"),
+      c => html.push(c),
+    }
+  }
+  html
+}
+
+/// The plain text of a HTML fragment, the tags are dropped and the common
+/// entities are unescaped. It's for pasting the HTML into a plain text field
+/// when the clipboard holds no text.
+pub fn html_to_text(html: &str) -> String {
+  let mut text = String::with_capacity(html.len());
+  let mut rest = html;
+  while let Some(start) = rest.find(['<', '&']) {
+    text.push_str(&rest[..start]);
+    rest = &rest[start..];
+    if rest.starts_with('<') {
+      let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+      let tag = rest[1..end]
+        .trim_end_matches('>')
+        .to_ascii_lowercase();
+      if tag.starts_with("br") || tag.starts_with("/p") || tag.starts_with("/div") {
+        text.push('\n');
+      }
+      rest = &rest[end..];
+    } else {
+      let entity = [("&amp;", '&'), ("&lt;", '<'), ("&gt;", '>'), ("&quot;", '"'), ("&nbsp;", ' ')]
+        .into_iter()
+        .find(|(e, _)| rest.starts_with(e));
+      match entity {
+        Some((e, c)) => {
+          text.push(c);
+          rest = &rest[e.len()..];
+        }
+        None => {
+          text.push('&');
+          rest = &rest[1..];
+        }
+      }
+    }
+  }
+  text.push_str(rest);
+  text.trim_end_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_painter::image::ColorFormat;
+
+  use super::*;
+
+  #[test]
+  fn html_and_text_round_trip() {
+    let mut clipboard = MemoryClipboard::default();
+    let text = "1 < 2\nand 3 > 2";
+    let html = text_to_html(text);
+    assert_eq!(html, "1 &lt; 2<br>and 3 &gt; 2");
+    clipboard
+      .write_data(&[ClipboardData::Text(text.into()), ClipboardData::Html(html.clone())])
+      .unwrap();
+
+    assert_eq!(clipboard.read_formats(), [ClipboardFormat::Text, ClipboardFormat::Html]);
+    assert_eq!(clipboard.read_text().unwrap(), text);
+    assert_eq!(clipboard.read_html().unwrap(), html);
+    assert_eq!(html_to_text(&html), text);
+    assert!(clipboard.read_img().is_err());
+
+    // A new write replaces all the formats.
+    clipboard.write_text("plain").unwrap();
+    assert_eq!(clipboard.read_formats(), [ClipboardFormat::Text]);
+  }
+
+  #[test]
+  fn paste_image() {
+    let mut clipboard = MemoryClipboard::default();
+    let img = PixelImage::new(vec![255; 16].into(), 2, 2, ColorFormat::Rgba8);
+    clipboard
+      .write_data(&[ClipboardData::Image(copy_img(&img))])
+      .unwrap();
+
+    assert_eq!(clipboard.read_formats(), [ClipboardFormat::Image]);
+    let pasted = clipboard.read_img().unwrap();
+    assert!(pasted == img);
+    assert!(clipboard.read_text().is_err());
+  }
+
+  #[test]
+  fn strip_html_tags() {
+    assert_eq!(
+      html_to_text("<p>Hello <b>rich</b> &amp; plain</p><p>text</p>"),
+      "Hello rich & plain\ntext"
+    );
   }
 }
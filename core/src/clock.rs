@@ -0,0 +1,90 @@
+//! The time source of the framework.
+//!
+//! Everything time-related in the framework, such as timers, multi-tap
+//! detection and animations, reads the current time from the global clock by
+//! [`now`] instead of the system time. By default, it's the system time. A
+//! headless window can replace it with a [`TestClock`] that only goes forward
+//! when it's advanced manually, so tests don't need to wait for the wall-clock
+//! time.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::ticker::{Duration, Instant};
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+  fn now(&self) -> Instant;
+}
+
+/// The clock that reads the system time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+/// A clock that is stopped until you advance it.
+#[derive(Debug)]
+pub struct TestClock {
+  now: Mutex<Instant>,
+}
+
+static CLOCK: RwLock<Option<Arc<dyn Clock>>> = RwLock::new(None);
+
+/// Return the current time of the global clock.
+pub fn now() -> Instant {
+  match &*CLOCK.read().unwrap() {
+    Some(clock) => clock.now(),
+    None => Instant::now(),
+  }
+}
+
+/// Replace the global clock, return the previous one. Pass `None` to back to
+/// the system time.
+pub fn set_clock(clock: Option<Arc<dyn Clock>>) -> Option<Arc<dyn Clock>> {
+  std::mem::replace(&mut *CLOCK.write().unwrap(), clock)
+}
+
+impl Clock for SystemClock {
+  fn now(&self) -> Instant { Instant::now() }
+}
+
+impl TestClock {
+  /// Create a clock stopped at the current system time.
+  pub fn new() -> Self { Self { now: Mutex::new(Instant::now()) } }
+
+  /// Move the clock forward by `dur`.
+  pub fn advance(&self, dur: Duration) { *self.now.lock().unwrap() += dur; }
+
+  /// Move the clock to `time`, the clock never goes back, so an earlier time
+  /// is ignored.
+  pub fn advance_to(&self, time: Instant) {
+    let mut now = self.now.lock().unwrap();
+    if *now < time {
+      *now = time;
+    }
+  }
+}
+
+impl Default for TestClock {
+  fn default() -> Self { Self::new() }
+}
+
+impl Clock for TestClock {
+  fn now(&self) -> Instant { *self.now.lock().unwrap() }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_clock_only_go_forward() {
+    let clock = TestClock::new();
+    let start = clock.now();
+    assert_eq!(clock.now(), start);
+
+    clock.advance(Duration::from_millis(10));
+    assert_eq!(clock.now(), start + Duration::from_millis(10));
+
+    clock.advance_to(start);
+    assert_eq!(clock.now(), start + Duration::from_millis(10));
+  }
+}
@@ -0,0 +1,391 @@
+//! The commands of the application, the actions that the menus, the buttons
+//! and the shortcuts trigger. Register a command once by its id, then the menu
+//! items, the buttons and the shortcuts refer to it by the id, so they share
+//! the title, the icon, whether it's enabled and the handler, see
+//! [`AppCtx::register_command`].
+
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+/// The definition of a command, register it by [`AppCtx::register_command`].
+///
+/// # Example
+///
+/// ```
+/// # use ribir_core::prelude::*;
+/// let can_save = Stateful::new(false);
+/// AppCtx::register_command(
+///   "app::save",
+///   Command::new("save", || println!("saved!"))
+///     .with_icon("app::save")
+///     .with_enabled(can_save.clone_watcher()),
+/// );
+/// AppCtx::bind_shortcut(
+///   Shortcut::new(ModifiersState::CONTROL, VirtualKey::Character("s".into())),
+///   "app::save",
+/// );
+///
+/// // Nothing happens, the command is disabled.
+/// assert!(!AppCtx::execute_command("app::save"));
+/// *can_save.write() = true;
+/// assert!(AppCtx::execute_command("app::save"));
+/// ```
+pub struct Command {
+  title: CowArc<str>,
+  icon: Option<&'static str>,
+  handler: Rc<dyn Fn()>,
+  is_enabled: Box<dyn Fn() -> bool>,
+  /// Follows `is_enabled` for the widgets to watch.
+  enabled: Stateful<bool>,
+  subscription: Option<BoxSubscription<'static>>,
+}
+
+/// What the widgets show of a command, see [`AppCtx::command_info`].
+pub struct CommandInfo {
+  /// The key of the localized title of the command.
+  pub title: CowArc<str>,
+  /// The name of the icon of the command in [`named_svgs`].
+  pub icon: Option<&'static str>,
+  /// Whether the command is enabled, watch it to update the widgets.
+  pub enabled: Watcher<Reader<bool>>,
+}
+
+/// A key with the modifiers that executes a command, see
+/// [`AppCtx::bind_shortcut`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcut {
+  pub modifiers: ModifiersState,
+  pub key: VirtualKey,
+}
+
+/// The commands and the shortcuts of the application.
+#[derive(Default)]
+pub(crate) struct Commands {
+  commands: ahash::HashMap<&'static str, Command>,
+  shortcuts: Vec<(Shortcut, &'static str)>,
+}
+
+impl Command {
+  /// A command titled by the l10n key `title`, it calls the `handler` when
+  /// it's executed. It's always enabled unless [`Command::with_enabled`].
+  pub fn new(title: impl Into<CowArc<str>>, handler: impl Fn() + 'static) -> Self {
+    Self {
+      title: title.into(),
+      icon: None,
+      handler: Rc::new(handler),
+      is_enabled: Box::new(|| true),
+      enabled: Stateful::new(true),
+      subscription: None,
+    }
+  }
+
+  /// Show the icon of the `name` in [`named_svgs`] with the command.
+  pub fn with_icon(mut self, name: &'static str) -> Self {
+    self.icon = Some(name);
+    self
+  }
+
+  /// Enable the command only if the state `enabled` is true, the menu items
+  /// and the buttons of the command follow its changes.
+  pub fn with_enabled(mut self, enabled: impl StateWatcher<Value = bool>) -> Self {
+    let reader = enabled.clone_reader();
+    *self.enabled.write() = *reader.read();
+    let mirror = self.enabled.clone_writer();
+    let u = enabled.modifies().subscribe(move |_| {
+      let enabled = *reader.read();
+      if *mirror.read() != enabled {
+        *mirror.write() = enabled;
+      }
+    });
+    let reader = enabled.clone_reader();
+    self.is_enabled = Box::new(move || *reader.read());
+    if let Some(u) = self.subscription.replace(u) {
+      u.unsubscribe();
+    }
+    self
+  }
+}
+
+impl Shortcut {
+  pub fn new(modifiers: ModifiersState, key: VirtualKey) -> Self { Self { modifiers, key } }
+
+  /// Whether the `key` pressed with the `modifiers` is the shortcut, the
+  /// letters match in either case.
+  fn matches(&self, modifiers: ModifiersState, key: &VirtualKey) -> bool {
+    let key_matched = match (&self.key, key) {
+      (VirtualKey::Character(a), VirtualKey::Character(b)) => a.eq_ignore_ascii_case(b),
+      (a, b) => a == b,
+    };
+    self.modifiers == modifiers && key_matched
+  }
+}
+
+impl Drop for Command {
+  fn drop(&mut self) {
+    if let Some(u) = self.subscription.take() {
+      u.unsubscribe();
+    }
+  }
+}
+
+impl AppCtx {
+  /// Register the `command` by the `id`, it replaces the command of the same
+  /// `id`. To prevent conflicts, add a namespace prefix of your library or
+  /// application to the id, such as `my_app::save`.
+  pub fn register_command(id: &'static str, command: Command) {
+    let old = Self::shared()
+      .commands
+      .borrow_mut()
+      .commands
+      .insert(id, command);
+    drop(old);
+  }
+
+  /// Remove the command of the `id`, and the shortcuts bound to it.
+  pub fn unregister_command(id: &str) {
+    let mut commands = Self::shared().commands.borrow_mut();
+    let old = commands.commands.remove(id);
+    commands.shortcuts.retain(|(_, c)| *c != id);
+    drop(commands);
+    drop(old);
+  }
+
+  /// Execute the command of the `id`. It's a no-op if the command is disabled
+  /// or not registered. Return whether the command is executed.
+  pub fn execute_command(id: &str) -> bool {
+    let commands = Self::shared().commands.borrow();
+    let Some(command) = commands.commands.get(id) else {
+      log::debug!("Execute the command `{id}` that isn't registered.");
+      return false;
+    };
+    if !(command.is_enabled)() {
+      log::debug!("Execute the command `{id}` that is disabled.");
+      return false;
+    }
+    // Not borrow the commands while the handler runs, it may register or
+    // execute a command too.
+    let handler = command.handler.clone();
+    drop(commands);
+    handler();
+    true
+  }
+
+  /// Whether the command of the `id` is registered and enabled.
+  pub fn is_command_enabled(id: &str) -> bool {
+    let commands = Self::shared().commands.borrow();
+    commands
+      .commands
+      .get(id)
+      .is_some_and(|c| (c.is_enabled)())
+  }
+
+  /// The title, the icon and the enabled state of the command of the `id`,
+  /// for the widgets that show it.
+  pub fn command_info(id: &str) -> Option<CommandInfo> {
+    let commands = Self::shared().commands.borrow();
+    commands.commands.get(id).map(|c| CommandInfo {
+      title: c.title.clone(),
+      icon: c.icon,
+      enabled: c.enabled.clone_watcher(),
+    })
+  }
+
+  /// Execute the command of the `id` when the `shortcut` is pressed in any
+  /// window, before the focused widget receives the key. The key goes to the
+  /// focused widget as usual if the command is disabled.
+  pub fn bind_shortcut(shortcut: Shortcut, id: &'static str) {
+    let mut commands = Self::shared().commands.borrow_mut();
+    commands.shortcuts.retain(|(s, _)| s != &shortcut);
+    commands.shortcuts.push((shortcut, id));
+  }
+
+  /// Remove the binding of the `shortcut`.
+  pub fn unbind_shortcut(shortcut: &Shortcut) {
+    let mut commands = Self::shared().commands.borrow_mut();
+    commands.shortcuts.retain(|(s, _)| s != shortcut);
+  }
+
+  /// Execute the command bound to the `key` pressed with the `modifiers`,
+  /// return whether a command is executed.
+  pub(crate) fn execute_shortcut(modifiers: ModifiersState, key: &VirtualKey) -> bool {
+    let commands = Self::shared().commands.borrow();
+    let id = commands
+      .shortcuts
+      .iter()
+      .find(|(s, _)| s.matches(modifiers, key))
+      .map(|(_, id)| *id);
+    drop(commands);
+    id.is_some_and(Self::execute_command)
+  }
+}
+
+/// Execute the command of the `command` id when its child is tapped, and show
+/// the child as disabled when the command is disabled. The menu items and the
+/// buttons refer to a command by it.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// let _w = fn_widget! {
+///   @CommandBinding {
+///     command: "app::save",
+///     @Text { text: "Save" }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct CommandBinding {
+  pub command: &'static str,
+}
+
+/// The opacity of the child of a [`CommandBinding`] whose command is disabled.
+pub const DISABLED_COMMAND_OPACITY: f32 = 0.38;
+
+impl<'c> ComposeChild<'c> for CommandBinding {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    let id = this.read().command;
+    let mut child = FatObj::new(child);
+    if let Some(CommandInfo { enabled, .. }) = AppCtx::command_info(id) {
+      child = child.opacity(pipe!(if *$enabled { 1. } else { DISABLED_COMMAND_OPACITY }));
+    } else {
+      log::warn!("The command `{id}` of the binding isn't registered.");
+    }
+    child
+      .on_tap(move |_| {
+        AppCtx::execute_command(id);
+      })
+      .into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::Cell;
+
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn disabled_command_is_noop() {
+    reset_test_env!();
+
+    let executed = Rc::new(Cell::new(0));
+    let c_executed = executed.clone();
+    let enabled = Stateful::new(false);
+    AppCtx::register_command(
+      "test::count",
+      Command::new("count", move || c_executed.set(c_executed.get() + 1))
+        .with_enabled(enabled.clone_watcher()),
+    );
+
+    assert!(!AppCtx::execute_command("test::count"));
+    assert!(!AppCtx::execute_command("test::missing"));
+    assert_eq!(executed.get(), 0);
+
+    *enabled.write() = true;
+    assert!(AppCtx::is_command_enabled("test::count"));
+    assert!(AppCtx::execute_command("test::count"));
+    assert_eq!(executed.get(), 1);
+
+    // The info follows the enabled state.
+    let info = AppCtx::command_info("test::count").unwrap();
+    AppCtx::run_until_stalled();
+    assert!(*info.enabled.read());
+    *enabled.write() = false;
+    AppCtx::run_until_stalled();
+    assert!(!*info.enabled.read());
+    assert_eq!(&*info.title, "count");
+
+    AppCtx::unregister_command("test::count");
+    assert!(AppCtx::command_info("test::count").is_none());
+  }
+
+  #[test]
+  fn shortcut_to_command() {
+    reset_test_env!();
+
+    let executed = Rc::new(Cell::new(0));
+    let c_executed = executed.clone();
+    AppCtx::register_command("test::save", Command::new("save", move || c_executed.set(1)));
+    AppCtx::bind_shortcut(
+      Shortcut::new(ModifiersState::CONTROL, VirtualKey::Character("s".into())),
+      "test::save",
+    );
+
+    let pressed = Stateful::new(vec![]);
+    let c_pressed = pressed.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: Size::new(10., 10.),
+        auto_focus: true,
+        on_key_down: move |e| $c_pressed.write().push(e.key().clone()),
+      }
+    });
+    wnd.draw_frame();
+
+    let press = |wnd: &TestWindow| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(KeyCode::KeyS),
+        VirtualKey::Character("S".into()),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.run_frame_tasks();
+    };
+
+    // Not the modifiers of the shortcut.
+    press(&wnd);
+    assert_eq!(executed.get(), 0);
+    assert_eq!(pressed.read().len(), 1);
+
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::ModifiersChanged(ModifiersState::CONTROL.into()));
+    press(&wnd);
+    assert_eq!(executed.get(), 1);
+    assert_eq!(pressed.read().len(), 1);
+  }
+
+  #[test]
+  fn tap_binding() {
+    reset_test_env!();
+
+    let executed = Rc::new(Cell::new(0));
+    let c_executed = executed.clone();
+    let enabled = Stateful::new(false);
+    AppCtx::register_command(
+      "test::tap",
+      Command::new("tap", move || c_executed.set(c_executed.get() + 1))
+        .with_enabled(enabled.clone_watcher()),
+    );
+    let mut wnd = TestWindow::new(fn_widget! {
+      @CommandBinding {
+        command: "test::tap",
+        @MockBox { size: Size::new(10., 10.) }
+      }
+    });
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let tap = |wnd: &TestWindow| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (5., 5.).into() });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+    tap(&wnd);
+    assert_eq!(executed.get(), 0);
+
+    *enabled.write() = true;
+    wnd.draw_frame();
+    tap(&wnd);
+    assert_eq!(executed.get(), 1);
+  }
+}
@@ -1,12 +1,17 @@
 mod painting_ctx;
 pub use painting_ctx::PaintingCtx;
 mod layout_ctx;
+mod layout_result_ctx;
 mod widget_ctx;
 pub use layout_ctx::*;
+pub(crate) use layout_result_ctx::AfterLayoutQueue;
+pub use layout_result_ctx::LayoutResultCtx;
 pub use widget_ctx::*;
 pub(crate) mod build_ctx;
 pub use build_ctx::BuildCtx;
 pub mod app_ctx;
+mod app_proxy;
 #[cfg(feature = "tokio-async")]
 pub use app_ctx::tokio_async::*;
 pub use app_ctx::*;
+pub use app_proxy::*;
@@ -0,0 +1,147 @@
+use std::sync::{Arc, Mutex};
+
+use futures::{
+  StreamExt,
+  channel::mpsc::{UnboundedSender, unbounded},
+};
+
+use super::{AppCtx, RuntimeWaker};
+
+pub(crate) type UiTask = Box<dyn FnOnce() + Send>;
+
+/// A handle to run the closures in the UI thread from any thread, e.g. in the
+/// callback of an external library.
+///
+/// The closures run in the order they are posted, before the next frame. The
+/// event loop is woken up to run them if it's waiting, a proxy created before
+/// the application starts wakes it too.
+///
+/// The closures don't get the [`AppCtx`], it's not `Send` and only accessed by
+/// its associated functions in the UI thread, which the closures can call.
+///
+/// Holding a proxy doesn't keep the application alive, and the closures
+/// posted after the application exits are dropped without running.
+///
+/// # Example
+///
+/// ```no_run
+/// use ribir_core::{local_sender::LocalSender, prelude::*};
+///
+/// let count = Stateful::new(0);
+/// let count = LocalSender::new(count);
+/// let proxy = AppCtx::proxy();
+/// std::thread::spawn(move || {
+///   proxy.run_on_ui(move || *count.write() += 1);
+/// });
+/// ```
+#[derive(Clone)]
+pub struct AppProxy {
+  sender: UnboundedSender<UiTask>,
+  /// The runtime waker of the application, it's replaced if the application
+  /// sets a new one after the proxy is created.
+  waker: Arc<Mutex<Box<dyn RuntimeWaker + Send>>>,
+}
+
+impl AppCtx {
+  /// Return a proxy to run the closures in the UI thread, see [`AppProxy`].
+  ///
+  /// The posted closures run by the executor of the application, so the
+  /// headless windows run them by [`AppCtx::run_until_stalled`] like the other
+  /// tasks.
+  pub fn proxy() -> AppProxy {
+    let ctx = AppCtx::shared();
+    let mut tasks = ctx.ui_tasks.borrow_mut();
+    let proxy = tasks.get_or_insert_with(|| {
+      let (sender, mut receiver) = unbounded::<UiTask>();
+      let _ = AppCtx::spawn_local(async move {
+        while let Some(task) = receiver.next().await {
+          task();
+        }
+      });
+      let waker = Arc::new(Mutex::new(ctx.runtime_waker.borrow().clone_box()));
+      AppProxy { sender, waker }
+    });
+    proxy.clone()
+  }
+}
+
+impl AppProxy {
+  /// Post the closure to run in the UI thread, return false if the
+  /// application has exited.
+  pub fn run_on_ui(&self, f: impl FnOnce() + Send + 'static) -> bool {
+    let sent = self.sender.unbounded_send(Box::new(f)).is_ok();
+    if sent {
+      self.waker.lock().unwrap().wake();
+    }
+    sent
+  }
+
+  pub(super) fn set_waker(&self, waker: Box<dyn RuntimeWaker + Send>) {
+    *self.waker.lock().unwrap() = waker;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use crate::{local_sender::LocalSender, prelude::*, reset_test_env, test_helper::*};
+
+  #[test]
+  fn run_on_ui_in_order() {
+    reset_test_env!();
+
+    let size = Stateful::new(Size::new(10., 10.));
+    let c_size = size.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox { size: pipe!(*$c_size) }
+    });
+    wnd.draw_frame();
+    wnd.assert_root_size(Size::new(10., 10.));
+
+    let order = Arc::new(Mutex::new(vec![]));
+    let (c_order, size) = (order.clone(), LocalSender::new(size));
+    let proxy = AppCtx::proxy();
+    std::thread::spawn(move || {
+      for i in 0..3 {
+        let order = c_order.clone();
+        proxy.run_on_ui(move || order.lock().unwrap().push(i));
+      }
+      proxy.run_on_ui(move || *size.write() = Size::new(20., 20.));
+    })
+    .join()
+    .unwrap();
+    assert!(order.lock().unwrap().is_empty());
+
+    wnd.draw_frame();
+    assert_eq!(*order.lock().unwrap(), [0, 1, 2]);
+    wnd.assert_root_size(Size::new(20., 20.));
+  }
+
+  #[test]
+  fn wake_by_the_waker_set_later() {
+    reset_test_env!();
+
+    #[derive(Clone)]
+    struct WakeCnt(Arc<Mutex<usize>>);
+    impl RuntimeWaker for WakeCnt {
+      fn wake(&self) { *self.0.lock().unwrap() += 1; }
+      fn clone_box(&self) -> Box<dyn RuntimeWaker + Send> { Box::new(self.clone()) }
+    }
+
+    // The proxy is created before the application sets its waker.
+    let proxy = AppCtx::proxy();
+    let wakes = Arc::new(Mutex::new(0));
+    AppCtx::set_runtime_waker(Box::new(WakeCnt(wakes.clone())));
+
+    let ran = Arc::new(Mutex::new(false));
+    let c_ran = ran.clone();
+    std::thread::spawn(move || proxy.run_on_ui(move || *c_ran.lock().unwrap() = true))
+      .join()
+      .unwrap();
+    assert_eq!(*wakes.lock().unwrap(), 1);
+
+    AppCtx::run_until_stalled();
+    assert!(*ran.lock().unwrap());
+  }
+}
@@ -23,6 +23,23 @@ impl BuildCtx {
   /// Return the window of this context is created from.
   pub fn window(&self) -> Sc<Window> { self.tree().window() }
 
+  /// The media information of the window, such as its logical size and
+  /// scale, watch it to adapt the build to the window.
+  pub fn media(&self) -> Watcher<Reader<MediaInfo>> { self.window().media() }
+
+  /// The orientation of the window, it only emits when the orientation
+  /// changes.
+  pub fn orientation(&self) -> impl Pipe<Value = Orientation> {
+    let media = self.media();
+    pipe!($media.orientation()).distinct()
+  }
+
+  /// The [`DeviceClass`] of the window, it only emits when the class changes.
+  pub fn device_class(&self) -> impl Pipe<Value = DeviceClass> {
+    let media = self.media();
+    pipe!($media.device_class()).distinct()
+  }
+
   pub fn text_style(&self) -> QueryRef<TextStyle> { Provider::of::<TextStyle>(self).unwrap() }
 
   /// This method returns the color of the current build process, with the
@@ -58,7 +75,11 @@ impl BuildCtx {
   }
 
   pub(crate) fn alloc(&mut self, node: Box<dyn RenderQueryable>) -> WidgetId {
-    new_node(&mut self.tree_mut().arena, node)
+    let tree = self.tree_mut();
+    let id = new_node(&mut tree.arena, node);
+    #[cfg(debug_assertions)]
+    tree.debug.built.push(id);
+    id
   }
 }
 
@@ -106,7 +127,7 @@ impl BuildCtx {
   pub(crate) fn set_for(startup: WidgetId, tree: NonNull<WidgetTree>) {
     let t = unsafe { tree.as_ref() };
     let mut providers: SmallVec<[WidgetId; 1]> = startup
-      .ancestors(t)
+      .provider_ancestors(t)
       .filter(|id| id.queryable(t))
       .collect();
     providers.reverse();
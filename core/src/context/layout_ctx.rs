@@ -1,10 +1,11 @@
 use ribir_geom::{Point, Size};
-use ribir_painter::{PaintingStyle, TextStyle};
+use ribir_painter::{PaintingStyle, TextDirection, TextStyle};
 
 use super::{WidgetCtx, WidgetCtxImpl};
 use crate::{
+  error::CoreError,
   widget::{BoxClamp, WidgetTree},
-  widget_tree::WidgetId,
+  widget_tree::{TreePhase, WidgetId, guard_widget_panic},
   window::DelayEvent,
 };
 
@@ -45,6 +46,15 @@ impl<'a> LayoutCtx<'a> {
     Self { id, tree, painting_style, text_style }
   }
 
+  /// The text direction provided by the ancestors, left to right if there is
+  /// none. The layouts that have a logical start and end resolve them by it.
+  pub fn text_direction(&self) -> TextDirection {
+    self
+      .id
+      .query_ancestors_ref::<TextDirection>(self.tree)
+      .map_or(TextDirection::LeftToRight, |d| *d)
+  }
+
   /// Perform layout of the widget of the context and return its size.
   pub(crate) fn perform_layout(&mut self, clamp: BoxClamp) -> Size {
     // Safety: the `tree` just use to get the widget of `id`, and `tree2` not drop
@@ -52,8 +62,25 @@ impl<'a> LayoutCtx<'a> {
     let tree2 = unsafe { &*(self.tree as *mut WidgetTree) };
 
     let id = self.id();
-    let w = id.assert_get(tree2);
-    let size = w.perform_layout(clamp, self);
+    let Some(w) = id.get(tree2) else {
+      self.tree.recover(CoreError::LostLayoutWidget(id));
+      return Size::zero();
+    };
+    let timing = self.tree.frame_costs.get_mut().start_layout();
+    let size = guard_widget_panic(
+      self,
+      TreePhase::Layout,
+      id,
+      |ctx| w.perform_layout(clamp, ctx),
+      |ctx, id| ctx.tree.provenance_path(id),
+    );
+    if let Some(timing) = timing {
+      self
+        .tree
+        .frame_costs
+        .get_mut()
+        .end_layout(id, timing);
+    }
 
     self
       .window()
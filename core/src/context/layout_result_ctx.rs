@@ -0,0 +1,79 @@
+use std::panic::Location;
+
+use ribir_geom::{Point, Rect};
+
+use super::WidgetCtxImpl;
+use crate::{widget::WidgetTree, widget_tree::WidgetId};
+
+/// The context of the callbacks that run after the layout of a frame, see
+/// [`Window::once_after_layout`](crate::prelude::Window::once_after_layout).
+/// It represents the root of the window, and reads the fresh layout of every
+/// widget.
+pub struct LayoutResultCtx<'a> {
+  pub(crate) tree: &'a WidgetTree,
+}
+
+impl<'a> WidgetCtxImpl for LayoutResultCtx<'a> {
+  #[inline]
+  fn id(&self) -> WidgetId { self.tree.root() }
+
+  #[inline]
+  fn tree(&self) -> &WidgetTree { self.tree }
+}
+
+impl<'a> LayoutResultCtx<'a> {
+  /// The box rect of the widget `wid` in the window, `None` if it's not laid
+  /// out.
+  pub fn global_rect(&self, wid: WidgetId) -> Option<Rect> {
+    let size = self.tree.store.layout_box_size(wid)?;
+    Some(Rect::new(self.tree.map_to_global(Point::zero(), wid), size))
+  }
+}
+
+/// A call site is warned if its callbacks run in this many frames in a row.
+const LOOP_WARN_FRAMES: u32 = 60;
+
+type AfterLayoutCallback = Box<dyn FnOnce(&LayoutResultCtx)>;
+
+/// The callbacks to run after the next layout of a window.
+#[derive(Default)]
+pub(crate) struct AfterLayoutQueue {
+  callbacks: Vec<(&'static Location<'static>, AfterLayoutCallback)>,
+  frame: u64,
+  /// The last frame that the callbacks of a call site ran and how many frames
+  /// in a row they ran until it.
+  streaks: ahash::HashMap<&'static Location<'static>, (u64, u32)>,
+}
+
+impl AfterLayoutQueue {
+  pub(crate) fn push(&mut self, at: &'static Location<'static>, f: AfterLayoutCallback) {
+    self.callbacks.push((at, f));
+  }
+
+  /// Take the callbacks to run after the layout of a new frame, and warn the
+  /// call sites that may schedule themselves endlessly.
+  pub(crate) fn take_frame(&mut self) -> Vec<AfterLayoutCallback> {
+    self.frame += 1;
+    let frame = self.frame;
+    for (at, _) in self.callbacks.iter() {
+      let (last, streak) = self.streaks.entry(*at).or_insert((0, 0));
+      if *last == frame {
+        continue;
+      }
+      *streak = if *last + 1 == frame { *streak + 1 } else { 1 };
+      *last = frame;
+      if *streak == LOOP_WARN_FRAMES {
+        log::warn!(
+          "The callbacks scheduled by `Window::once_after_layout` at {at} ran in {streak} frames \
+           in a row, they may schedule themselves endlessly."
+        );
+      }
+    }
+    self.streaks.retain(|_, (last, _)| *last == frame);
+
+    std::mem::take(&mut self.callbacks)
+      .into_iter()
+      .map(|(_, f)| f)
+      .collect()
+  }
+}
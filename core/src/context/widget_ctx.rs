@@ -1,4 +1,4 @@
-use std::ptr::NonNull;
+use std::{ops::ControlFlow, ptr::NonNull};
 
 use ribir_algo::Sc;
 use ribir_geom::{Point, Rect, Size};
@@ -44,6 +44,10 @@ pub trait WidgetCtx {
   fn box_size(&self) -> Option<Size>;
   /// Return the widget box lef-top position .
   fn box_pos(&self) -> Option<Point>;
+  /// Return the layout rect of the widget in the last painted frame. It stays
+  /// the same while the layout of the current frame is computing, so the
+  /// widgets can animate from it.
+  fn prev_layout_rect(&self) -> Option<Rect>;
   /// Return the clamp of the widget that used in last layout.
   fn layout_clamp(&self) -> Option<BoxClamp>;
   /// Return the box size of the widget `wid`.
@@ -85,9 +89,25 @@ pub trait WidgetCtx {
   fn query_write<T: 'static>(&self) -> Option<WriteRef<T>>;
   /// Query a reference to the `T` if it is shared within the widget `w`.
   fn query_of_widget<T: 'static>(&self, w: WidgetId) -> Option<QueryRef<T>>;
+  /// Query all the references to the `T` that are shared within the widget
+  /// `w`.
+  fn query_all_of_widget<T: 'static>(
+    &self, w: WidgetId,
+  ) -> impl DoubleEndedIterator<Item = QueryRef<'_, T>>;
   // Query a write reference to the `T` if a writer of `T` is shared within the
   // widget `w`.
   fn query_write_of_widget<T: 'static>(&self, w: WidgetId) -> Option<WriteRef<T>>;
+  /// Query all the descendants of the widget `w` that share a `T`, in
+  /// depth-first order, not including `w` itself.
+  fn query_descendants<T: 'static>(&self, w: WidgetId) -> Vec<(WidgetId, QueryRef<'_, T>)>;
+  /// Query the nearest ancestor of the widget `w` that shares a `T`, not
+  /// including `w` itself.
+  fn query_ancestor<T: 'static>(&self, w: WidgetId) -> Option<(WidgetId, QueryRef<'_, T>)>;
+  /// Visit the descendants of the widget `w` that share a `T` in depth-first
+  /// order, stop when `f` returns `ControlFlow::Break`.
+  fn visit_descendants<T: 'static>(
+    &self, w: WidgetId, f: impl FnMut(WidgetId, QueryRef<T>) -> ControlFlow<()>,
+  ) -> ControlFlow<()>;
   /// Retrieve the window associated with this context.
   fn window(&self) -> Sc<Window>;
 }
@@ -129,6 +149,8 @@ impl<T: WidgetCtxImpl> WidgetCtx for T {
   #[inline]
   fn box_size(&self) -> Option<Size> { self.widget_box_size(self.id()) }
 
+  fn prev_layout_rect(&self) -> Option<Rect> { self.tree().store.prev_layout_rect(self.id()) }
+
   fn layout_clamp(&self) -> Option<BoxClamp> {
     self
       .tree()
@@ -194,10 +216,30 @@ impl<T: WidgetCtxImpl> WidgetCtx for T {
     w.query_ref::<Q>(self.tree())
   }
 
+  fn query_all_of_widget<Q: 'static>(
+    &self, w: WidgetId,
+  ) -> impl DoubleEndedIterator<Item = QueryRef<'_, Q>> {
+    w.query_all_iter(self.tree())
+  }
+
   fn query_write_of_widget<Q: 'static>(&self, w: WidgetId) -> Option<WriteRef<Q>> {
     w.query_write(self.tree())
   }
 
+  fn query_descendants<Q: 'static>(&self, w: WidgetId) -> Vec<(WidgetId, QueryRef<'_, Q>)> {
+    w.query_descendants(self.tree())
+  }
+
+  fn query_ancestor<Q: 'static>(&self, w: WidgetId) -> Option<(WidgetId, QueryRef<'_, Q>)> {
+    w.query_ancestor(self.tree())
+  }
+
+  fn visit_descendants<Q: 'static>(
+    &self, w: WidgetId, f: impl FnMut(WidgetId, QueryRef<Q>) -> ControlFlow<()>,
+  ) -> ControlFlow<()> {
+    w.visit_descendants(self.tree(), f)
+  }
+
   fn window(&self) -> Sc<Window> { self.tree().window() }
 }
 
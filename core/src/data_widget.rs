@@ -66,6 +66,11 @@ impl Query for DataAttacher {
 
   fn queryable(&self) -> bool { true }
 
+  fn type_bloom(&self, bloom: &mut QueryBloom) {
+    self.render.type_bloom(bloom);
+    self.data.type_bloom(bloom);
+  }
+
   fn query_match(
     &self, ids: &[QueryId], filter: &dyn Fn(&QueryId, &QueryHandle) -> bool,
   ) -> Option<(QueryId, QueryHandle)> {
@@ -95,6 +100,8 @@ impl Query for AnonymousAttacher {
 
   fn queryable(&self) -> bool { self.render.queryable() }
 
+  fn type_bloom(&self, bloom: &mut QueryBloom) { self.render.type_bloom(bloom) }
+
   fn query_match(
     &self, ids: &[QueryId], filter: &dyn Fn(&QueryId, &QueryHandle) -> bool,
   ) -> Option<(QueryId, QueryHandle)> {
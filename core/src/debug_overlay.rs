@@ -0,0 +1,179 @@
+//! Paint the guides over a window to debug its layout, see
+//! [`Window::set_debug_overlay`].
+
+use crate::{prelude::*, window::WindowFlags};
+
+/// The guides that a window paints over all its widgets to debug the layout.
+/// They don't take part in the layout or the hit test.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DebugOverlay {
+  /// Paint nothing.
+  #[default]
+  None,
+  /// A grid of lines every `spacing` logical pixels, to check the alignment.
+  Grid { spacing: f32 },
+  /// The outline of the layout box of every widget, colored by its depth in
+  /// the tree.
+  Bounds,
+  /// The baseline of every text line.
+  Baselines,
+}
+
+const GRID_COLOR: Color = Color::from_u32(0xFF00FF66);
+const BASELINE_COLOR: Color = Color::from_u32(0x00A0FFCC);
+const BOUNDS_COLORS: [Color; 4] = [
+  Color::from_u32(0xFF3B30CC),
+  Color::from_u32(0x34C759CC),
+  Color::from_u32(0x007AFFCC),
+  Color::from_u32(0xFF9500CC),
+];
+
+impl Window {
+  /// The guides that the window paints over its widgets.
+  pub fn debug_overlay(&self) -> DebugOverlay { self.debug_overlay.get() }
+
+  /// Paint the guides over the widgets of the window to debug its layout, it
+  /// follows the changes of the tree until it's set to [`DebugOverlay::None`].
+  ///
+  /// The frames taken by [`Window::capture_frame`] leave the guides out if
+  /// the window has the flag [`WindowFlags::CAPTURE_WITHOUT_DEBUG_OVERLAY`].
+  pub fn set_debug_overlay(&self, overlay: DebugOverlay) {
+    if self.debug_overlay.replace(overlay) != overlay {
+      let tree = self.tree();
+      tree.dirty_marker().mark(tree.root());
+    }
+  }
+
+  /// Paint the window again and take the paint commands, such as for a
+  /// screenshot.
+  pub fn capture_frame(&self) -> Vec<PaintCommand> {
+    self.layout();
+    self.painter.borrow_mut().reset();
+    self.tree().draw();
+    self.draw_delay_drop_widgets();
+    self.draw_focus_ring();
+    if !self
+      .flags()
+      .contains(WindowFlags::CAPTURE_WITHOUT_DEBUG_OVERLAY)
+    {
+      self.draw_debug_overlay();
+    }
+    self.painter.borrow_mut().finish().take()
+  }
+
+  /// Paint the debug overlay over all the widgets.
+  pub(crate) fn draw_debug_overlay(&self) {
+    let overlay = self.debug_overlay.get();
+    if overlay == DebugOverlay::None {
+      return;
+    }
+
+    let mut painter = self.painter.borrow_mut();
+    let mut painter = painter.save_guard();
+    painter.set_line_width(1.);
+    match overlay {
+      DebugOverlay::None => {}
+      DebugOverlay::Grid { spacing } => {
+        if spacing <= 0. {
+          return;
+        }
+        let size = self.size();
+        painter.set_stroke_brush(GRID_COLOR);
+        let mut x = spacing;
+        while x < size.width {
+          painter
+            .begin_path(Point::new(x, 0.))
+            .line_to(Point::new(x, size.height))
+            .end_path(false);
+          x += spacing;
+        }
+        let mut y = spacing;
+        while y < size.height {
+          painter
+            .begin_path(Point::new(0., y))
+            .line_to(Point::new(size.width, y))
+            .end_path(false);
+          y += spacing;
+        }
+        painter.stroke();
+      }
+      DebugOverlay::Bounds => {
+        let tree = self.tree();
+        let root = tree.root();
+        let mut stack = vec![(root, 0)];
+        while let Some((id, depth)) = stack.pop() {
+          if let Some(size) = tree.store.layout_box_size(id) {
+            let pos = tree.map_to_global(Point::zero(), id);
+            painter
+              .set_stroke_brush(BOUNDS_COLORS[depth % BOUNDS_COLORS.len()])
+              .rect(&Rect::new(pos, size))
+              .stroke();
+          }
+          stack.extend(id.children(tree).map(|c| (c, depth + 1)));
+        }
+      }
+      DebugOverlay::Baselines => {
+        let tree = self.tree();
+        painter.set_stroke_brush(BASELINE_COLOR);
+        for id in tree.root().descendants(tree) {
+          let Some(size) = tree.store.layout_box_size(id) else { continue };
+          let baselines = id.assert_get(tree).text_baselines();
+          if baselines.is_empty() {
+            continue;
+          }
+          let pos = tree.map_to_global(Point::zero(), id);
+          for y in baselines {
+            painter
+              .begin_path(Point::new(pos.x, pos.y + y))
+              .line_to(Point::new(pos.x + size.width, pos.y + y))
+              .end_path(false);
+          }
+          painter.stroke();
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn paint_overlay_of_each_mode() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockMulti {
+          @MockBox { size: Size::new(40., 40.) }
+          @Text { text: "Hello" }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    let plain = wnd.take_last_frame().unwrap().commands.len();
+
+    for overlay in
+      [DebugOverlay::Grid { spacing: 10. }, DebugOverlay::Bounds, DebugOverlay::Baselines]
+    {
+      wnd.set_debug_overlay(overlay);
+      wnd.draw_frame();
+      let frame = wnd
+        .take_last_frame()
+        .expect("the overlay repaints the window");
+      assert!(frame.commands.len() > plain, "{overlay:?}");
+      assert!(wnd.capture_frame().len() > plain, "{overlay:?}");
+
+      wnd.set_flags(wnd.flags() | WindowFlags::CAPTURE_WITHOUT_DEBUG_OVERLAY);
+      assert_eq!(wnd.capture_frame().len(), plain, "{overlay:?}");
+      wnd.set_flags(WindowFlags::DEFAULT);
+    }
+
+    wnd.set_debug_overlay(DebugOverlay::None);
+    wnd.draw_frame();
+    assert_eq!(wnd.take_last_frame().unwrap().commands.len(), plain);
+  }
+}
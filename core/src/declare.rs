@@ -2,13 +2,45 @@ use std::convert::Infallible;
 
 use rxrust::ops::box_it::BoxOp;
 
-use crate::{pipe::Pipe, prelude::BoxPipe, state::ModifyScope};
+use crate::{
+  pipe::Pipe,
+  prelude::{BoxPipe, IntoWidget, Widget},
+  state::ModifyScope,
+};
 
 /// Trait used to create a widget declarer that can interact with the `BuildCtx`
 /// to create a widget.
 pub trait Declare {
   type Builder: ObjDeclarer;
   fn declarer() -> Self::Builder;
+
+  /// Return the builder of the widget, this is the entry to build a widget in
+  /// plain Rust without the macros, and it's the same builder the `@` syntax
+  /// uses.
+  ///
+  /// The builder has a setter for every field of the widget, and the setters
+  /// of the builtin fields, such as `on_tap`, `padding` and `background`. Call
+  /// `finish` to get the widget and compose it with children by `with_child`,
+  /// or call `build` if it's a leaf.
+  ///
+  /// It's not named `builder` to not conflict with [`Template::builder`] of
+  /// the templates, which implement `Declare` too.
+  ///
+  /// [`Template::builder`]: crate::prelude::Template::builder
+  ///
+  /// ```
+  /// use ribir_core::prelude::*;
+  ///
+  /// let _w = fn_widget! {
+  ///   Text::widget_builder()
+  ///     .text("Hi!")
+  ///     .on_tap(|_| println!("tapped"))
+  ///     .margin(EdgeInsets::all(4.))
+  ///     .build()
+  /// };
+  /// ```
+  #[inline]
+  fn widget_builder() -> Self::Builder { Self::declarer() }
 }
 
 /// An object declarer is a type that can be used to create a object with the
@@ -17,6 +49,17 @@ pub trait ObjDeclarer {
   type Target;
   /// Finish the object creation with the given context.
   fn finish(self) -> Self::Target;
+
+  /// Finish the object creation and convert it to a widget.
+  #[inline]
+  #[track_caller]
+  fn build<'w, const M: usize>(self) -> Widget<'w>
+  where
+    Self: Sized,
+    Self::Target: IntoWidget<'w, M>,
+  {
+    self.finish().into_widget()
+  }
 }
 
 /// Used to do conversion from a value to the `DeclareInit` type.
@@ -0,0 +1,389 @@
+//! Land the links that launch the application on their screens, such as
+//! `myapp://settings/account` opened by the system or a document path from the
+//! command line. The application maps the route patterns to the handlers that
+//! navigate to the screens, see [`AppCtx::handle_link`].
+
+use std::{rc::Rc, str::FromStr};
+
+use crate::context::AppCtx;
+
+type RouteHandler = Rc<dyn Fn(&LinkParams)>;
+type FallbackHandler = Rc<dyn Fn(&str)>;
+/// The pairs of the names and the values.
+type Pairs = Vec<(String, String)>;
+
+/// The routes of the links of the application, set it by
+/// [`AppCtx::set_deep_link`].
+///
+/// A pattern is a path of the segments split by `/`:
+/// - `settings` matches the segment `settings` only.
+/// - `:section` matches any segment as the parameter `section`.
+/// - `*path` matches all the rest segments as the parameter `path`, it must be
+///   the last segment.
+///
+/// The scheme of a link is ignored, so `myapp://settings/account` and
+/// `/settings/account` both match `/settings/:section`. If several patterns
+/// match a link, the one whose segments are more specific from the start wins,
+/// a literal segment over a parameter over the rest, then the one added first.
+#[derive(Default)]
+pub struct DeepLink {
+  routes: Vec<Route>,
+  fallback: Option<FallbackHandler>,
+}
+
+/// The parameters of a link matched by a route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkParams {
+  link: String,
+  params: Pairs,
+  query: Pairs,
+}
+
+struct Route {
+  segments: Vec<Segment>,
+  handler: RouteHandler,
+}
+
+enum Segment {
+  Literal(String),
+  Param(String),
+  Rest(String),
+}
+
+impl DeepLink {
+  /// Handle the links that match the `pattern` by the `handler`, such as
+  /// pushing the screen of the link to the navigator.
+  ///
+  /// # Panics
+  ///
+  /// If a `*rest` segment is not the last segment of the `pattern`.
+  pub fn with_route(mut self, pattern: &str, handler: impl Fn(&LinkParams) + 'static) -> Self {
+    let segments: Vec<_> = path_segments(pattern)
+      .map(|s| {
+        if let Some(name) = s.strip_prefix(':') {
+          Segment::Param(name.to_string())
+        } else if let Some(name) = s.strip_prefix('*') {
+          Segment::Rest(name.to_string())
+        } else {
+          Segment::Literal(percent_decode(s, false))
+        }
+      })
+      .collect();
+    let rest = segments
+      .iter()
+      .position(|s| matches!(s, Segment::Rest(_)));
+    assert!(
+      rest.is_none_or(|idx| idx + 1 == segments.len()),
+      "The rest segment must be the last segment of the pattern `{pattern}`."
+    );
+    self
+      .routes
+      .push(Route { segments, handler: Rc::new(handler) });
+    self
+  }
+
+  /// Handle the links that match no route by the `handler`, such as showing a
+  /// "not found" screen.
+  pub fn with_fallback(mut self, handler: impl Fn(&str) + 'static) -> Self {
+    self.fallback = Some(Rc::new(handler));
+    self
+  }
+
+  /// Find the handler of the route that the `link` matches, and the parameters
+  /// for it.
+  fn resolve(&self, link: &str) -> Option<(RouteHandler, LinkParams)> {
+    let (path, query) = split_link(link);
+    let segments: Vec<_> = path_segments(path)
+      .map(|s| percent_decode(s, false))
+      .collect();
+
+    let mut best: Option<(&Route, Pairs, Vec<u8>)> = None;
+    for route in self.routes.iter() {
+      let Some((params, ranks)) = route.matches(&segments) else { continue };
+      if best
+        .as_ref()
+        .is_none_or(|(.., best)| ranks > *best)
+      {
+        best = Some((route, params, ranks));
+      }
+    }
+
+    let (route, params, _) = best?;
+    let query = query
+      .split('&')
+      .filter(|pair| !pair.is_empty())
+      .map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (percent_decode(key, true), percent_decode(value, true))
+      })
+      .collect();
+    let params = LinkParams { link: link.to_string(), params, query };
+    Some((route.handler.clone(), params))
+  }
+}
+
+impl Route {
+  /// The parameters of the `segments` if they match the route, and the ranks
+  /// of the route segments to compare how specific the routes are.
+  fn matches(&self, segments: &[String]) -> Option<(Pairs, Vec<u8>)> {
+    let mut params = vec![];
+    let mut ranks = vec![];
+    let mut iter = segments.iter();
+    for s in self.segments.iter() {
+      match s {
+        Segment::Literal(literal) => {
+          (iter.next()? == literal).then_some(())?;
+          ranks.push(2);
+        }
+        Segment::Param(name) => {
+          params.push((name.clone(), iter.next()?.clone()));
+          ranks.push(1);
+        }
+        Segment::Rest(name) => {
+          let rest: Vec<_> = iter.by_ref().map(String::as_str).collect();
+          (!rest.is_empty()).then_some(())?;
+          params.push((name.clone(), rest.join("/")));
+          ranks.push(0);
+        }
+      }
+    }
+    iter.next().is_none().then_some((params, ranks))
+  }
+}
+
+impl LinkParams {
+  /// The link that's handled.
+  pub fn link(&self) -> &str { &self.link }
+
+  /// The decoded value of the parameter `name` of the pattern.
+  pub fn get(&self, name: &str) -> Option<&str> { find(&self.params, name) }
+
+  /// Parse the parameter `name` of the pattern as a `T`, `None` if it's
+  /// missing or not a valid `T`.
+  pub fn parse<T: FromStr>(&self, name: &str) -> Option<T> { self.get(name)?.parse().ok() }
+
+  /// The decoded value of the key `name` in the query of the link, such as
+  /// `tab` of `myapp://settings?tab=account`.
+  pub fn query(&self, name: &str) -> Option<&str> { find(&self.query, name) }
+}
+
+impl AppCtx {
+  /// Set the routes of the links that the application handles.
+  pub fn set_deep_link(deep_link: DeepLink) { *Self::shared().deep_link.borrow_mut() = deep_link; }
+
+  /// Handle the `link` by the route that it matches, or by the fallback if it
+  /// matches none. Return whether a route handles it.
+  ///
+  /// Call it with the arguments of the command line at startup, and with the
+  /// urls that the system asks the application to open when it runs.
+  pub fn handle_link(link: &str) -> bool {
+    // Not borrow the routes while the handler runs, it may handle a link too.
+    let deep_link = Self::shared().deep_link.borrow();
+    if let Some((handler, params)) = deep_link.resolve(link) {
+      drop(deep_link);
+      handler(&params);
+      true
+    } else {
+      let fallback = deep_link.fallback.clone();
+      drop(deep_link);
+      if let Some(fallback) = fallback {
+        fallback(link);
+      }
+      false
+    }
+  }
+}
+
+fn find<'a>(pairs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+  pairs
+    .iter()
+    .find(|(k, _)| k == name)
+    .map(|(_, v)| v.as_str())
+}
+
+/// Split the path and the query of the `link`, without its scheme and its
+/// fragment.
+fn split_link(link: &str) -> (&str, &str) {
+  let link = link
+    .split_once('#')
+    .map_or(link, |(link, _)| link);
+  let link = link
+    .split_once("://")
+    .map_or(link, |(_, rest)| rest);
+  link.split_once('?').unwrap_or((link, ""))
+}
+
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+  path.split('/').filter(|s| !s.is_empty())
+}
+
+/// Decode the `%XX` escapes of the `s`, and the `+` as a space in a query.
+/// The invalid escapes are kept as they are.
+fn percent_decode(s: &str, plus_as_space: bool) -> String {
+  let bytes = s.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  let hex = |idx: usize| {
+    bytes
+      .get(idx)
+      .and_then(|b| (*b as char).to_digit(16))
+  };
+  while i < bytes.len() {
+    match bytes[i] {
+      b'%' => match (hex(i + 1), hex(i + 2)) {
+        (Some(hi), Some(lo)) => {
+          decoded.push((hi * 16 + lo) as u8);
+          i += 3;
+          continue;
+        }
+        _ => decoded.push(b'%'),
+      },
+      b'+' if plus_as_space => decoded.push(b' '),
+      b => decoded.push(b),
+    }
+    i += 1;
+  }
+  String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+
+  use super::*;
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  /// Resolve the `link` and return the index of the route it matches, with the
+  /// parameters.
+  fn resolve(patterns: &[&str], link: &str) -> Option<(usize, LinkParams)> {
+    let matched = Rc::new(RefCell::new(None));
+    let mut deep_link = DeepLink::default();
+    for (idx, pattern) in patterns.iter().enumerate() {
+      let matched = matched.clone();
+      deep_link = deep_link.with_route(pattern, move |params| {
+        *matched.borrow_mut() = Some((idx, params.clone()));
+      });
+    }
+    let (handler, params) = deep_link.resolve(link)?;
+    handler(&params);
+    matched.take()
+  }
+
+  #[test]
+  fn match_patterns() {
+    let routes = ["/settings/:section", "/files/*path", "/about"];
+
+    let (idx, params) = resolve(&routes, "myapp://settings/account").unwrap();
+    assert_eq!(idx, 0);
+    assert_eq!(params.get("section"), Some("account"));
+    assert_eq!(params.link(), "myapp://settings/account");
+
+    let (idx, params) = resolve(&routes, "/files/docs/2024/report.md").unwrap();
+    assert_eq!(idx, 1);
+    assert_eq!(params.get("path"), Some("docs/2024/report.md"));
+
+    let (idx, _) = resolve(&routes, "myapp://about/").unwrap();
+    assert_eq!(idx, 2);
+
+    // The segments must match all.
+    assert!(resolve(&routes, "myapp://settings").is_none());
+    assert!(resolve(&routes, "myapp://settings/account/more").is_none());
+    assert!(resolve(&routes, "myapp://files").is_none());
+    assert!(resolve(&routes, "myapp://help").is_none());
+  }
+
+  #[test]
+  fn specific_route_first() {
+    let routes = ["/items/*rest", "/items/:id", "/items/new", "/items/:id/edit"];
+    assert_eq!(resolve(&routes, "/items/new").unwrap().0, 2);
+    assert_eq!(resolve(&routes, "/items/42").unwrap().0, 1);
+    assert_eq!(resolve(&routes, "/items/42/edit").unwrap().0, 3);
+    assert_eq!(resolve(&routes, "/items/42/share").unwrap().0, 0);
+
+    // The first added wins the routes of the same segments.
+    let (idx, params) = resolve(&["/:a", "/:b"], "/x").unwrap();
+    assert_eq!(idx, 0);
+    assert_eq!(params.get("a"), Some("x"));
+  }
+
+  #[test]
+  fn percent_decoding() {
+    let routes = ["/search/:keyword", "/caf%C3%A9"];
+    let (_, params) =
+      resolve(&routes, "myapp://search/hello%20world%2Fall?page=2&q=a+b%26c#top").unwrap();
+    assert_eq!(params.get("keyword"), Some("hello world/all"));
+    assert_eq!(params.query("page"), Some("2"));
+    assert_eq!(params.query("q"), Some("a b&c"));
+    assert_eq!(params.query("top"), None);
+
+    assert_eq!(resolve(&routes, "/café").unwrap().0, 1);
+    assert_eq!(resolve(&routes, "/caf%c3%a9").unwrap().0, 1);
+
+    // The invalid escapes are kept.
+    let (_, params) = resolve(&routes, "/search/100%").unwrap();
+    assert_eq!(params.get("keyword"), Some("100%"));
+    let (_, params) = resolve(&routes, "/search/%zz").unwrap();
+    assert_eq!(params.get("keyword"), Some("%zz"));
+  }
+
+  #[test]
+  fn typed_params() {
+    let (_, params) = resolve(&["/page/:index/:ratio"], "/page/3/0.5").unwrap();
+    assert_eq!(params.parse::<usize>("index"), Some(3));
+    assert_eq!(params.parse::<f32>("ratio"), Some(0.5));
+    assert_eq!(params.parse::<usize>("ratio"), None);
+    assert_eq!(params.parse::<usize>("missing"), None);
+  }
+
+  #[test]
+  #[should_panic]
+  fn rest_must_be_last() { let _ = DeepLink::default().with_route("/*rest/edit", |_| {}); }
+
+  #[test]
+  fn unmatched_to_fallback() {
+    reset_test_env!();
+
+    let unmatched = Rc::new(RefCell::new(vec![]));
+    let c_unmatched = unmatched.clone();
+    AppCtx::set_deep_link(
+      DeepLink::default()
+        .with_route("/home", |_| {})
+        .with_fallback(move |link| c_unmatched.borrow_mut().push(link.to_string())),
+    );
+
+    assert!(AppCtx::handle_link("myapp://home"));
+    assert!(!AppCtx::handle_link("myapp://missing/page"));
+    assert_eq!(&*unmatched.borrow(), &["myapp://missing/page"]);
+  }
+
+  #[test]
+  fn nested_link_at_startup() {
+    reset_test_env!();
+
+    // A navigator that shows the screen on the top of its stack.
+    let stack = Stateful::new(vec!["home".to_string()]);
+    let c_stack = stack.clone_writer();
+    AppCtx::set_deep_link(DeepLink::default().with_route(
+      "/settings/:section/:item",
+      move |params| {
+        let mut stack = c_stack.write();
+        stack.push("settings".into());
+        stack.push(params.get("section").unwrap().into());
+        stack.push(params.get("item").unwrap().into());
+      },
+    ));
+
+    // Handled before the window is created, as the startup arguments.
+    assert!(AppCtx::handle_link("myapp://settings/account/two%20factor"));
+
+    let c_stack = stack.clone_watcher();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @Text { text: pipe!($c_stack.last().unwrap().clone()) }
+    });
+    wnd.draw_frame();
+    assert_eq!(&*stack.read(), &["home", "settings", "account", "two factor"]);
+    let tree = wnd.tree();
+    let shown = crate::builtin_widgets::text_content_of(tree.root(), tree);
+    assert_eq!(shown.as_deref(), Some("two factor"));
+  }
+}
@@ -0,0 +1,398 @@
+//! Typed messaging between the UI and an engine that runs on another thread,
+//! such as a simulation, see [`EngineBridge`].
+
+use std::{
+  cell::RefCell,
+  convert::Infallible,
+  rc::Rc,
+  sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError},
+  },
+};
+
+use futures::{
+  StreamExt,
+  channel::mpsc::{UnboundedSender, unbounded},
+};
+use ops::box_it::BoxOp;
+
+use crate::{prelude::*, ticker::FrameMsg};
+
+/// The UI end of a bridge to an engine on another thread.
+///
+/// The UI sends the commands through a bounded queue, and the bridge is busy
+/// while the queue is full, so the UI can bind the [`EngineBridge::busy`] state
+/// to hold the user back instead of piling up the work. The engine takes the
+/// commands and posts the events by the [`EngineHandle`], the events are
+/// delivered on the UI thread in the order they're posted, before the next
+/// frame, see [`EngineBridge::events`].
+///
+/// The bridge disconnects once every clone of it is dropped, such as the
+/// handlers of the widget that holds it are disposed, or its owner widget is
+/// disposed, see [`EngineBridge::owned_by`]. Then the engine can't take the
+/// commands or post the events anymore, and it should stop.
+///
+/// # Example
+///
+/// ```no_run
+/// use ribir_core::prelude::*;
+///
+/// let _w = fn_widget! {
+///   let (bridge, engine) = EngineBridge::<u32, u32>::new(8);
+///   std::thread::spawn(move || {
+///     let mut sum = 0;
+///     while let Some(n) = engine.recv() {
+///       sum += n;
+///       if engine.post(sum).is_err() {
+///         break;
+///       }
+///     }
+///   });
+///
+///   let sum = Stateful::new(0);
+///   let u = bridge.events().subscribe(move |n| *$sum.write() = n);
+///   @Text {
+///     text: pipe!($sum.to_string()),
+///     on_tap: move |_| { let _ = bridge.send(1); },
+///     on_disposed: move |_| u.unsubscribe(),
+///   }
+/// };
+/// ```
+pub struct EngineBridge<Cmd, Evt> {
+  inner: Rc<BridgeInner<Cmd, Evt>>,
+}
+
+/// The engine end of an [`EngineBridge`], move it to the thread of the engine.
+pub struct EngineHandle<Cmd, Evt> {
+  commands: Receiver<Cmd>,
+  events: UnboundedSender<BridgeMsg<Evt>>,
+  shared: Arc<BridgeShared>,
+}
+
+struct BridgeInner<Cmd, Evt> {
+  commands: RefCell<Option<SyncSender<Cmd>>>,
+  events: Subject<'static, Evt, Infallible>,
+  busy: Stateful<bool>,
+  shared: Arc<BridgeShared>,
+  /// Close the channel of the events, so the UI stops waiting for them.
+  close_events: UnboundedSender<BridgeMsg<Evt>>,
+  owner_guard: RefCell<Option<SubscriptionGuard<BoxSubscription<'static>>>>,
+}
+
+/// The state of a bridge that both ends access.
+struct BridgeShared {
+  capacity: usize,
+  /// The commands sent but not taken by the engine.
+  pending: AtomicUsize,
+  connected: AtomicBool,
+}
+
+enum BridgeMsg<Evt> {
+  Event(Evt),
+  /// The engine took a command from the full queue.
+  Drained,
+}
+
+impl<Cmd, Evt> EngineBridge<Cmd, Evt>
+where
+  Cmd: Send + 'static,
+  Evt: Clone + Send + 'static,
+{
+  /// Create a bridge whose queue holds at most `capacity` commands, return
+  /// the UI end and the engine end.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the `capacity` is zero.
+  pub fn new(capacity: usize) -> (Self, EngineHandle<Cmd, Evt>) {
+    assert!(capacity > 0, "The capacity of the engine bridge must be positive.");
+    let (cmd_tx, cmd_rx) = mpsc::sync_channel(capacity);
+    let (evt_tx, mut evt_rx) = unbounded();
+    let shared = Arc::new(BridgeShared {
+      capacity,
+      pending: AtomicUsize::new(0),
+      connected: AtomicBool::new(true),
+    });
+    let inner = Rc::new(BridgeInner {
+      commands: RefCell::new(Some(cmd_tx)),
+      events: Subject::default(),
+      busy: Stateful::new(false),
+      shared: shared.clone(),
+      close_events: evt_tx.clone(),
+      owner_guard: RefCell::new(None),
+    });
+
+    let mut events = inner.events.clone();
+    let busy = inner.busy.clone_writer();
+    let c_shared = shared.clone();
+    let _ = AppCtx::spawn_local(async move {
+      while let Some(msg) = evt_rx.next().await {
+        if !c_shared.is_connected() {
+          break;
+        }
+        match msg {
+          BridgeMsg::Event(evt) => events.next(evt),
+          BridgeMsg::Drained => update_busy(&busy, &c_shared),
+        }
+      }
+    });
+
+    let handle = EngineHandle { commands: cmd_rx, events: evt_tx, shared };
+    (Self { inner }, handle)
+  }
+
+  /// Send the `cmd` to the engine. It fails with [`TrySendError::Full`] if
+  /// the queue is full, or with [`TrySendError::Disconnected`] if the bridge
+  /// is disconnected.
+  pub fn send(&self, cmd: Cmd) -> Result<(), TrySendError<Cmd>> {
+    let inner = &self.inner;
+    let commands = inner.commands.borrow();
+    let Some(tx) = commands.as_ref() else {
+      return Err(TrySendError::Disconnected(cmd));
+    };
+    // Count the command before it's sent, so the engine never takes a command
+    // that isn't counted.
+    inner
+      .shared
+      .pending
+      .fetch_add(1, Ordering::AcqRel);
+    let res = tx.try_send(cmd);
+    if res.is_err() {
+      inner
+        .shared
+        .pending
+        .fetch_sub(1, Ordering::AcqRel);
+    }
+    update_busy(&inner.busy, &inner.shared);
+    res
+  }
+
+  /// Whether the queue of the commands is full, the following commands fail
+  /// to send until the engine takes one.
+  pub fn busy(&self) -> Watcher<Reader<bool>> { self.inner.busy.clone_watcher() }
+
+  /// The stream of the events that the engine posts, they're emitted on the
+  /// UI thread in the order they're posted.
+  pub fn events(&self) -> Subject<'static, Evt, Infallible> { self.inner.events.clone() }
+
+  /// Disconnect the bridge once the widget of the `ctx` is disposed.
+  pub fn owned_by(&self, ctx: &impl WidgetCtx) {
+    let wnd = ctx.window();
+    let (wnd_id, id) = (wnd.id(), ctx.widget_id());
+    let bridge = Rc::downgrade(&self.inner);
+    let disposed: BoxOp<'static, _, _> = wnd
+      .frame_tick_stream()
+      .filter(move |msg| {
+        matches!(msg, FrameMsg::Finish(_))
+          && AppCtx::get_window(wnd_id).is_none_or(|wnd| id.is_dropped(wnd.tree()))
+      })
+      .take(1)
+      .box_it();
+    let guard = disposed
+      .subscribe(move |_| {
+        if let Some(bridge) = bridge.upgrade() {
+          bridge.disconnect();
+        }
+      })
+      .unsubscribe_when_dropped();
+    *self.inner.owner_guard.borrow_mut() = Some(guard);
+  }
+
+  /// Disconnect the bridge, the events that aren't delivered yet are dropped.
+  pub fn disconnect(&self) {
+    self.inner.disconnect();
+    self.inner.owner_guard.borrow_mut().take();
+  }
+
+  /// Whether the bridge is connected.
+  pub fn is_connected(&self) -> bool { self.inner.shared.is_connected() }
+}
+
+impl<Cmd, Evt> EngineHandle<Cmd, Evt> {
+  /// Wait for the next command, `None` if the bridge is disconnected.
+  pub fn recv(&self) -> Option<Cmd> {
+    let cmd = self.commands.recv().ok()?;
+    self.take(cmd)
+  }
+
+  /// Take the next command if there is one. It fails with
+  /// [`TryRecvError::Empty`] if the queue is empty, or with
+  /// [`TryRecvError::Disconnected`] if the bridge is disconnected.
+  pub fn try_recv(&self) -> Result<Cmd, TryRecvError> {
+    let cmd = self.commands.try_recv()?;
+    self.take(cmd).ok_or(TryRecvError::Disconnected)
+  }
+
+  /// Post the `evt` to the UI, it's given back if the bridge is disconnected.
+  pub fn post(&self, evt: Evt) -> Result<(), Evt> {
+    if !self.is_connected() {
+      return Err(evt);
+    }
+    self
+      .events
+      .unbounded_send(BridgeMsg::Event(evt))
+      .map_err(|e| match e.into_inner() {
+        BridgeMsg::Event(evt) => evt,
+        BridgeMsg::Drained => unreachable!(),
+      })
+  }
+
+  /// Whether the bridge is connected.
+  pub fn is_connected(&self) -> bool { self.shared.is_connected() }
+
+  /// Count off a command taken from the queue, the commands left in the queue
+  /// of a disconnected bridge are dropped.
+  fn take(&self, cmd: Cmd) -> Option<Cmd> {
+    if !self.is_connected() {
+      return None;
+    }
+    let shared = &self.shared;
+    if shared.pending.fetch_sub(1, Ordering::AcqRel) == shared.capacity {
+      let _ = self.events.unbounded_send(BridgeMsg::Drained);
+    }
+    Some(cmd)
+  }
+}
+
+impl<Cmd, Evt> BridgeInner<Cmd, Evt> {
+  fn disconnect(&self) {
+    self
+      .shared
+      .connected
+      .store(false, Ordering::Release);
+    // Wake the engine that waits for the commands, and the UI that waits for
+    // the events.
+    self.commands.borrow_mut().take();
+    self.close_events.close_channel();
+  }
+}
+
+impl BridgeShared {
+  fn is_connected(&self) -> bool { self.connected.load(Ordering::Acquire) }
+}
+
+fn update_busy(busy: &Stateful<bool>, shared: &BridgeShared) {
+  let is_busy = shared.pending.load(Ordering::Acquire) >= shared.capacity;
+  if *busy.read() != is_busy {
+    *busy.write() = is_busy;
+  }
+}
+
+impl<Cmd, Evt> Clone for EngineBridge<Cmd, Evt> {
+  fn clone(&self) -> Self { Self { inner: self.inner.clone() } }
+}
+
+impl<Cmd, Evt> Drop for BridgeInner<Cmd, Evt> {
+  fn drop(&mut self) { self.disconnect() }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::thread;
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn backpressure() {
+    reset_test_env!();
+
+    let (bridge, engine) = EngineBridge::<i32, i32>::new(2);
+    let busy = bridge.busy();
+    assert!(bridge.send(1).is_ok());
+    assert!(!*busy.read());
+    assert!(bridge.send(2).is_ok());
+    assert!(*busy.read());
+    assert!(matches!(bridge.send(3), Err(TrySendError::Full(3))));
+
+    // The bridge isn't busy after the engine takes a command.
+    assert_eq!(engine.try_recv(), Ok(1));
+    AppCtx::run_until_stalled();
+    assert!(!*busy.read());
+    assert!(bridge.send(3).is_ok());
+    assert_eq!(engine.try_recv(), Ok(2));
+    assert_eq!(engine.try_recv(), Ok(3));
+    assert_eq!(engine.try_recv(), Err(TryRecvError::Empty));
+  }
+
+  #[test]
+  fn ordered_delivery() {
+    reset_test_env!();
+
+    let (bridge, engine) = EngineBridge::<i32, i32>::new(4);
+    let engine = thread::spawn(move || {
+      for _ in 0..10 {
+        let n = engine.recv().unwrap();
+        for i in 0..10 {
+          engine.post(n * 10 + i).unwrap();
+        }
+      }
+    });
+
+    let received = Rc::new(RefCell::new(vec![]));
+    let c_received = received.clone();
+    bridge
+      .events()
+      .subscribe(move |n| c_received.borrow_mut().push(n));
+
+    let mut wnd = TestWindow::new(fn_widget! { @Void {} });
+    for n in 0..10 {
+      while let Err(TrySendError::Full(_)) = bridge.send(n) {
+        thread::yield_now();
+        AppCtx::run_until_stalled();
+      }
+    }
+    engine.join().unwrap();
+    wnd.draw_frame();
+
+    assert_eq!(*received.borrow(), (0..100).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn disconnect_with_owner() {
+    reset_test_env!();
+
+    let (bridge, engine) = EngineBridge::<i32, i32>::new(4);
+    let engine = thread::spawn(move || {
+      while let Some(n) = engine.recv() {
+        let _ = engine.post(n);
+      }
+      // The bridge is disconnected.
+      assert!(engine.post(0).is_err());
+    });
+
+    let show = Stateful::new(true);
+    let c_show = show.clone_watcher();
+    let c_bridge = bridge.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let bridge = c_bridge.clone();
+      @ {
+        pipe!(*$c_show).map(move |show| {
+          let bridge = bridge.clone();
+          fn_widget! {
+            if show {
+              @MockBox {
+                size: Size::zero(),
+                on_mounted: move |e| bridge.owned_by(&*e),
+              }
+              .into_widget()
+            } else {
+              Void.into_widget()
+            }
+          }
+        })
+      }
+    });
+    wnd.draw_frame();
+    assert!(bridge.send(1).is_ok());
+    assert!(bridge.is_connected());
+
+    *show.write() = false;
+    wnd.draw_frame();
+    assert!(!bridge.is_connected());
+    assert!(matches!(bridge.send(2), Err(TrySendError::Disconnected(2))));
+    engine.join().unwrap();
+  }
+}
@@ -0,0 +1,123 @@
+//! The inconsistencies of the framework that an application survives.
+//!
+//! A debug build panics where such an inconsistency is found, so the bug
+//! surfaces where it happens. A release build logs it with the provenance of
+//! the widget and goes on, the event is skipped or the widget is laid out as
+//! empty, a small glitch rather than a crash.
+
+use std::fmt;
+
+use crate::widget_tree::{WidgetId, WidgetTree};
+
+/// An inconsistency of the widget tree that the framework recovers from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreError {
+  /// An event is dispatched to a widget that is already removed from the
+  /// tree, the event is skipped.
+  LostEventTarget(WidgetId),
+  /// A widget to lay out is not in the tree, it's laid out as zero size.
+  LostLayoutWidget(WidgetId),
+  /// A widget to replace has neither a parent nor siblings, the replacement
+  /// isn't placed in the tree.
+  OrphanWidget(WidgetId),
+}
+
+impl CoreError {
+  /// The widget the error is about.
+  pub fn widget(&self) -> WidgetId {
+    match self {
+      CoreError::LostEventTarget(id)
+      | CoreError::LostLayoutWidget(id)
+      | CoreError::OrphanWidget(id) => *id,
+    }
+  }
+}
+
+impl fmt::Display for CoreError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CoreError::LostEventTarget(_) => f.write_str("The target of the event is removed"),
+      CoreError::LostLayoutWidget(_) => f.write_str("The widget to lay out is removed"),
+      CoreError::OrphanWidget(_) => f.write_str("The widget to replace isn't in the tree"),
+    }
+  }
+}
+
+impl std::error::Error for CoreError {}
+
+impl WidgetTree {
+  /// Report the `err` that the caller recovers from, it panics in debug
+  /// builds and logs the error in release builds.
+  #[track_caller]
+  pub(crate) fn recover(&mut self, err: CoreError) {
+    let msg = format!("{err}: {}", self.describe_provenance(err.widget()));
+    #[cfg(debug_assertions)]
+    if !self.debug.simulate_release() {
+      debug_assert!(false, "{msg}");
+    }
+    log::error!("{msg}, recovered.");
+    #[cfg(all(test, debug_assertions))]
+    self.debug.recovered.push(err);
+  }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+  use super::*;
+  use crate::{prelude::*, reset_test_env, test_helper::*, window::DelayEvent};
+
+  fn wnd_with_lost_leaf() -> (TestWindow, WidgetId) {
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(10., 10.) }
+        @MockBox { size: Size::new(20., 20.) }
+      }
+    });
+    wnd.draw_frame();
+
+    let tree = wnd.tree_mut();
+    let leaf = tree.root().descendants(tree).last().unwrap();
+    tree.detach(leaf);
+    tree.remove_subtree(leaf);
+    tree.debug.release = true;
+    (wnd, leaf)
+  }
+
+  fn assert_keep_rendering(wnd: &mut TestWindow) {
+    let tree = wnd.tree();
+    tree.dirty_marker().mark(tree.root());
+    wnd.draw_frame();
+    assert!(wnd.take_last_frame().is_some());
+  }
+
+  #[test]
+  fn skip_event_of_removed_widget() {
+    reset_test_env!();
+
+    let (mut wnd, leaf) = wnd_with_lost_leaf();
+    wnd.add_delay_event(DelayEvent::Mounted(leaf));
+    wnd.add_delay_event(DelayEvent::Tap(leaf));
+    wnd.run_frame_tasks();
+    assert_eq!(
+      wnd.tree().debug.recovered,
+      [CoreError::LostEventTarget(leaf), CoreError::LostEventTarget(leaf)]
+    );
+
+    assert_keep_rendering(&mut wnd);
+  }
+
+  #[test]
+  fn lay_out_removed_widget_as_empty() {
+    reset_test_env!();
+
+    let (mut wnd, leaf) = wnd_with_lost_leaf();
+    let tree = wnd.tree_mut();
+    let root = tree.root();
+    let mut ctx = LayoutCtx::new(root, tree);
+    let size = ctx.perform_child_layout(leaf, BoxClamp::default());
+    assert_eq!(size, Size::zero());
+    assert_eq!(wnd.tree().debug.recovered, [CoreError::LostLayoutWidget(leaf)]);
+
+    assert_keep_rendering(&mut wnd);
+  }
+}
@@ -1,4 +1,6 @@
-use std::ptr::NonNull;
+use std::{cell::RefCell, ptr::NonNull};
+
+use ribir_geom::Transform;
 
 use self::dispatcher::DispatchInfo;
 use crate::{
@@ -22,15 +24,79 @@ mod ime_pre_edit;
 pub use ime_pre_edit::*;
 mod lifecycle;
 pub use lifecycle::*;
+mod pointer_capture;
+mod pointer_lock;
+pub use pointer_lock::*;
+mod scroll;
+pub use scroll::*;
+mod gesture_settings;
+pub use gesture_settings::*;
 
 pub(crate) mod focus_mgr;
+mod hit_index;
 mod listener_impl_helper;
 
 define_widget_context!(
   CommonEvent,
   target: WidgetId,
   propagation: bool,
-  prevent_default: bool
+  prevent_default: bool,
+  visual_states: RefCell<ahash::HashMap<WidgetId, VisualState>>
+);
+
+/// How a widget shows in the window, combined with its ancestors.
+#[derive(Clone, Copy)]
+pub(crate) struct VisualState {
+  opacity: f32,
+  displayed: bool,
+  /// Whether the widget is in the tree of the window.
+  attached: bool,
+  transform: Transform,
+}
+
+/// An action that runs by default after the event `E` is dispatched.
+pub(crate) type DefaultAction<E> = Box<dyn FnOnce(&E)>;
+
+/// The default actions that the listeners add to an event, they run after the
+/// event is dispatched to all its listeners if none of them prevents it.
+pub(crate) struct DefaultActions<E>(Vec<DefaultAction<E>>);
+
+impl<E> DefaultActions<E> {
+  pub(crate) fn push(&mut self, action: impl FnOnce(&E) + 'static) {
+    self.0.push(Box::new(action));
+  }
+
+  /// Run the actions with the dispatched `event`, unless it's prevented.
+  pub(crate) fn run(actions: Vec<DefaultAction<E>>, event: &E)
+  where
+    E: std::ops::Deref<Target = CommonEvent>,
+  {
+    if !event.is_default_prevented() {
+      actions
+        .into_iter()
+        .for_each(|action| action(event));
+    }
+  }
+
+  pub(crate) fn take(&mut self) -> Vec<DefaultAction<E>> { std::mem::take(&mut self.0) }
+}
+
+impl<E> Default for DefaultActions<E> {
+  fn default() -> Self { Self(vec![]) }
+}
+
+impl<E> std::fmt::Debug for DefaultActions<E> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_tuple("DefaultActions")
+      .field(&self.0.len())
+      .finish()
+  }
+}
+
+define_widget_context!(
+  /// The context of the widget that an event was dispatched to, get it by
+  /// [`CommonEvent::ctx_of_target`].
+  TargetCtx
 );
 
 pub type FocusEvent = CommonEvent;
@@ -47,20 +113,58 @@ impl CommonEvent {
   /// this has been changed along the way through retargeting.
   #[inline]
   pub fn current_target(&self) -> WidgetId { self.id }
+  /// Return the context of the target widget, so the handler can access the
+  /// layout rect and the data of the target. It's helpful when one listener
+  /// handles the events for all its descendants.
+  ///
+  /// ```rust
+  /// use ribir_core::prelude::*;
+  ///
+  /// let _w = fn_widget! {
+  ///   @Container {
+  ///     size: Size::new(100., 100.),
+  ///     on_tap: |e| {
+  ///       let target = e.ctx_of_target();
+  ///       let name = target.query::<&'static str>().map(|n| *n);
+  ///       println!("tap {name:?} at {:?}", target.box_rect());
+  ///     },
+  ///     @ {
+  ///       let text = @Text { text: "Hi!" };
+  ///       text.into_widget().attach_data(Box::new(Queryable("greeting")))
+  ///     }
+  ///   }
+  /// };
+  /// ```
+  pub fn ctx_of_target(&self) -> TargetCtx { TargetCtx { id: self.target, tree: self.tree } }
   /// Prevent event bubbling to parent.
   #[inline]
   pub fn stop_propagation(&mut self) { self.propagation = false }
   /// Whether the event is bubbling or not.
   #[inline]
   pub fn is_propagation(&self) -> bool { self.propagation }
-  /// Tells the user agent that if the event does not get explicitly handled,
-  /// its default action should not be taken as it normally would be.
+  /// Skip the built-in default action of the event. Like the
+  /// `preventDefault` of the web, any listener of the capture or the bubble
+  /// phase can call it, because the default actions run after all the
+  /// listeners, and it doesn't stop the propagation of the event.
+  ///
+  /// The default actions of the framework are:
+  /// - `PointerDown`: focus the nearest focus node of the pressed widget.
+  /// - `Wheel`: scroll the scrollable widgets under the pointer.
+  /// - `KeyDown`: move the focus by the `Tab` and the arrow keys, and type the
+  ///   characters of the key, they're not received as a `Chars` event if it's
+  ///   prevented.
+  /// - `KeyDown` and `Chars` of a focused text input: edit its text.
+  ///
+  /// A widget adds its own default action to a `KeyDown` or a `Chars` event
+  /// by [`KeyboardEvent::add_default_action`] or
+  /// [`CharsEvent::add_default_action`].
   #[inline]
   pub fn prevent_default(&mut self) { self.prevent_default = true; }
 
-  /// Whether the event is prevented the default action or not.
+  /// Whether a listener has prevented the default action of the event, see
+  /// [`CommonEvent::prevent_default`].
   #[inline]
-  pub(crate) fn is_prevent_default(&self) -> bool { self.prevent_default }
+  pub fn is_default_prevented(&self) -> bool { self.prevent_default }
 
   /// Represents the current state of the keyboard modifiers
   #[inline]
@@ -101,10 +205,35 @@ impl CommonEvent {
   #[inline]
   pub fn mouse_buttons(&self) -> MouseButtons { self.pick_info(DispatchInfo::mouse_buttons) }
 
+  /// All the buttons pressed since the first button of the current or the last
+  /// press went down. Unlike [`Self::mouse_buttons`], the pointer up and tap
+  /// events see the full set of a chorded press, such as two buttons held.
+  #[inline]
+  pub fn chord_buttons(&self) -> MouseButtons { self.pick_info(DispatchInfo::chord_buttons) }
+
   /// The button number that was pressed (if applicable) when the mouse event
   /// was fired.
   #[inline]
   pub fn button_num(&self) -> u32 { self.mouse_buttons().bits().count_ones() }
+
+  /// The opacity that the current target is painted with, the product of the
+  /// opacities of it and its ancestors.
+  pub fn effective_opacity(&self) -> f32 { self.visual_state(self.id).opacity }
+
+  /// Whether the current target shows in the window. It's not shown if it or
+  /// one of its ancestors is invisible or fully transparent, or it's not in the
+  /// tree of the window anymore.
+  ///
+  /// A widget is still hit by the pointer while it's fading out, check it to
+  /// ignore the taps, for example.
+  pub fn is_visually_shown(&self) -> bool {
+    let state = self.visual_state(self.id);
+    state.attached && state.displayed && state.opacity > 0.
+  }
+
+  /// The transform that maps the coordinate of the current target to the
+  /// window.
+  pub fn global_transform(&self) -> Transform { self.visual_state(self.id).transform }
 }
 
 pub enum Event {
@@ -126,6 +255,20 @@ pub enum Event {
   PointerLeave(PointerEvent),
   Tap(PointerEvent),
   TapCapture(PointerEvent),
+  /// Firing the event when a pointer is held down on the widget without
+  /// moving for the [`GestureSettings::long_press`] duration, the tap of the
+  /// press is not fired then.
+  LongPress(PointerEvent),
+  /// Same as `LongPress` but emit in capture phase.
+  LongPressCapture(PointerEvent),
+  /// Firing the event when a pointer pressed on the widget moves farther than
+  /// the slop of [`GestureSettings`], the tap of the press is not fired then.
+  DragStart(DragEvent),
+  /// Firing the event when the dragging pointer moves, even if it leaves the
+  /// widget.
+  DragUpdate(DragEvent),
+  /// Firing the event when the dragging pointer is released or canceled.
+  DragEnd(DragEvent),
   ImePreEdit(ImePreEditEvent),
   ImePreEditCapture(ImePreEditEvent),
   /// Firing the wheel event when the user rotates a wheel button on a pointing
@@ -133,6 +276,16 @@ pub enum Event {
   Wheel(WheelEvent),
   /// Same as `Wheel` but emit in capture phase.
   WheelCapture(WheelEvent),
+  /// Firing the scroll event when the scroll position or the scrollable extent
+  /// of a scrollable widget changes.
+  Scroll(ScrollEvent),
+  /// Firing the event when the widget locks the pointer or loses the lock.
+  PointerLockChange(PointerLockEvent),
+  /// Firing the event when the widget captures a pointer, see
+  /// [`CommonEvent::set_pointer_capture`].
+  GotPointerCapture(PointerEvent),
+  /// Firing the event when the widget loses the capture of a pointer.
+  LostPointerCapture(PointerEvent),
   Chars(CharsEvent),
   CharsCapture(CharsEvent),
   /// The `KeyDown` event is fired when a key is pressed.
@@ -193,9 +346,16 @@ impl std::ops::Deref for Event {
       | Event::PointerEnter(e)
       | Event::PointerLeave(e)
       | Event::Tap(e)
-      | Event::TapCapture(e) => e,
+      | Event::TapCapture(e)
+      | Event::LongPress(e)
+      | Event::LongPressCapture(e)
+      | Event::GotPointerCapture(e)
+      | Event::LostPointerCapture(e) => e,
       Event::ImePreEdit(e) | Event::ImePreEditCapture(e) => e,
       Event::Wheel(e) | Event::WheelCapture(e) => e,
+      Event::Scroll(e) => e,
+      Event::DragStart(e) | Event::DragUpdate(e) | Event::DragEnd(e) => e,
+      Event::PointerLockChange(e) => e,
       Event::Chars(e) | Event::CharsCapture(e) => e,
       Event::KeyDown(e) | Event::KeyDownCapture(e) | Event::KeyUp(e) | Event::KeyUpCapture(e) => e,
     }
@@ -224,9 +384,16 @@ impl std::ops::DerefMut for Event {
       | Event::PointerEnter(e)
       | Event::PointerLeave(e)
       | Event::Tap(e)
-      | Event::TapCapture(e) => e,
+      | Event::TapCapture(e)
+      | Event::LongPress(e)
+      | Event::LongPressCapture(e)
+      | Event::GotPointerCapture(e)
+      | Event::LostPointerCapture(e) => e,
       Event::ImePreEdit(e) | Event::ImePreEditCapture(e) => e,
       Event::Wheel(e) | Event::WheelCapture(e) => e,
+      Event::Scroll(e) => e,
+      Event::DragStart(e) | Event::DragUpdate(e) | Event::DragEnd(e) => e,
+      Event::PointerLockChange(e) => e,
       Event::Chars(e) | Event::CharsCapture(e) => e,
       Event::KeyDown(e) | Event::KeyDownCapture(e) | Event::KeyUp(e) | Event::KeyUpCapture(e) => e,
     }
@@ -247,8 +414,14 @@ impl Event {
       | Event::PointerEnter(_)
       | Event::PointerLeave(_)
       | Event::Tap(_)
-      | Event::TapCapture(_) => MixFlags::Pointer,
+      | Event::TapCapture(_)
+      | Event::PointerLockChange(_)
+      | Event::GotPointerCapture(_)
+      | Event::LostPointerCapture(_) => MixFlags::Pointer,
+      Event::LongPress(_) | Event::LongPressCapture(_) => MixFlags::LongPress,
       Event::Wheel(_) | Event::WheelCapture(_) => MixFlags::Wheel,
+      Event::Scroll(_) => MixFlags::Scroll,
+      Event::DragStart(_) | Event::DragUpdate(_) | Event::DragEnd(_) => MixFlags::Drag,
       Event::ImePreEdit(_)
       | Event::ImePreEditCapture(_)
       | Event::Chars(_)
@@ -269,7 +442,7 @@ impl Event {
 impl std::fmt::Debug for CommonEvent {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.debug_struct("CommonEvent")
-      .field("target", &self.id)
+      .field("target", &self.target)
       .field("current_target", &self.id)
       .field("is_propagation", &self.propagation)
       .finish()
@@ -283,7 +456,14 @@ impl CommonEvent {
   /// it because in most case the event create in a environment that the
   /// `Dispatcher` already borrowed.
   pub(crate) fn new(target: WidgetId, tree: NonNull<WidgetTree>) -> Self {
-    Self { target, tree, id: target, propagation: true, prevent_default: false }
+    Self {
+      target,
+      tree,
+      id: target,
+      propagation: true,
+      prevent_default: false,
+      visual_states: <_>::default(),
+    }
   }
 
   pub(crate) fn set_current_target(&mut self, id: WidgetId) { self.id = id; }
@@ -291,4 +471,50 @@ impl CommonEvent {
   fn pick_info<R>(&self, f: impl FnOnce(&DispatchInfo) -> R) -> R {
     f(&self.window().dispatcher.borrow().info)
   }
+
+  /// Compute the visual state of the widget from its ancestors, the states are
+  /// cached until the event is dispatched, so the bubbling listeners share
+  /// them.
+  fn visual_state(&self, id: WidgetId) -> VisualState {
+    if let Some(state) = self.visual_states.borrow().get(&id) {
+      return *state;
+    }
+
+    let tree = self.tree();
+    let state = if id.is_dropped(tree) {
+      VisualState {
+        opacity: 0.,
+        displayed: false,
+        attached: false,
+        transform: Transform::identity(),
+      }
+    } else {
+      let render = id.assert_get(tree);
+      let offset = tree.store.layout_box_pos(id).unwrap_or_default();
+      let transform = render
+        .get_transform()
+        .unwrap_or_default()
+        .then_translate(offset.to_vector());
+      let local = VisualState {
+        opacity: render.get_opacity(),
+        displayed: render.is_displayed(),
+        attached: id == tree.root(),
+        transform,
+      };
+      match id.parent(tree) {
+        Some(p) => {
+          let parent = self.visual_state(p);
+          VisualState {
+            opacity: local.opacity * parent.opacity,
+            displayed: local.displayed && parent.displayed,
+            attached: parent.attached,
+            transform: local.transform.then(&parent.transform),
+          }
+        }
+        None => local,
+      }
+    };
+    self.visual_states.borrow_mut().insert(id, state);
+    state
+  }
 }
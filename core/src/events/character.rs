@@ -1,9 +1,11 @@
+use super::{DefaultAction, DefaultActions};
 use crate::{impl_common_event_deref, prelude::*};
 
 #[derive(Debug)]
 pub struct CharsEvent {
   pub chars: String,
   pub common: CommonEvent,
+  default_actions: DefaultActions<CharsEvent>,
 }
 
 impl_common_event_deref!(CharsEvent);
@@ -11,7 +13,17 @@ impl_common_event_deref!(CharsEvent);
 impl CharsEvent {
   #[inline]
   pub fn new(chars: String, id: WidgetId, wnd: &Window) -> Self {
-    Self { chars, common: CommonEvent::new(id, wnd.tree) }
+    Self { chars, common: CommonEvent::new(id, wnd.tree), default_actions: <_>::default() }
+  }
+
+  /// Run `action` after the event is dispatched to all its listeners, unless
+  /// a listener prevents the default action of the event.
+  pub fn add_default_action(&mut self, action: impl FnOnce(&CharsEvent) + 'static) {
+    self.default_actions.push(action);
+  }
+
+  pub(crate) fn take_default_actions(&mut self) -> Vec<DefaultAction<CharsEvent>> {
+    self.default_actions.take()
   }
 }
 
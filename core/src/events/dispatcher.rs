@@ -1,7 +1,11 @@
 use std::cell::RefCell;
 
-use winit::event::{DeviceId, ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use rxrust::prelude::*;
+use winit::event::{
+  DeviceId, ElementState, MouseButton, MouseScrollDelta, Touch, TouchPhase, WindowEvent,
+};
 
+use super::hit_index::HitIndex;
 use crate::{
   prelude::*,
   window::{DelayEvent, WindowId},
@@ -35,9 +39,15 @@ impl Drop for GrabPointer {
 pub(crate) struct Dispatcher {
   wnd_id: WindowId,
   pub(crate) info: DispatchInfo,
-  pub(crate) entered_widgets: Vec<WidgetId>,
+  pub(crate) entered_widgets: WidgetPath,
   grab_mouse_wid: Sc<RefCell<Option<WidgetId>>>,
   pointer_down_wid: Option<WidgetId>,
+  pointer_down_pos: Point,
+  /// The timer that recognizes the press as a long press.
+  long_press: Option<TaskHandle<NormalReturn<()>>>,
+  /// The drag of the pressed pointer, if any widget under it listens to it.
+  drag: Option<DragGesture>,
+  hit_index: RefCell<HitIndex>,
 }
 
 impl Dispatcher {
@@ -45,9 +55,13 @@ impl Dispatcher {
     Self {
       wnd_id,
       info: <_>::default(),
-      entered_widgets: vec![],
+      entered_widgets: WidgetPath::new(),
       grab_mouse_wid: Sc::new(RefCell::new(None)),
       pointer_down_wid: None,
+      pointer_down_pos: Point::zero(),
+      long_press: None,
+      drag: None,
+      hit_index: <_>::default(),
     }
   }
 
@@ -63,15 +77,45 @@ impl Dispatcher {
   fn window(&self) -> Sc<Window> {
     AppCtx::get_window(self.wnd_id).expect("The window of the `Dispatcher` already dropped")
   }
+
+  /// The widget that takes all the pointer presses, the widget that grabs the
+  /// pointer or locks it.
+  fn grab_target(&self) -> Option<WidgetId> {
+    let grab = *self.grab_mouse_wid.borrow();
+    grab.or_else(|| self.window().pointer_lock_widget())
+  }
+}
+
+/// The drag that a pressed pointer starts on the widget listening to it.
+#[derive(Clone, Copy)]
+enum DragGesture {
+  /// The pointer is pressed but hasn't moved beyond the slop yet.
+  Pending {
+    wid: WidgetId,
+    origin: Point,
+  },
+  Dragging {
+    wid: WidgetId,
+    origin: Point,
+    last: Point,
+  },
 }
+
 #[derive(Default)]
 pub(crate) struct DispatchInfo {
   /// The current state of mouse button press state.
   mouse_button: (Option<DeviceId>, MouseButtons),
+  /// All the buttons pressed since the first button went down, kept until the
+  /// next press.
+  chord_buttons: MouseButtons,
   /// The current global position (relative to window) of mouse
   cursor_pos: Point,
   /// The current state of the keyboard modifiers
   modifiers: ModifiersState,
+  /// The last packet of the pen, if the pointer is driven by a pen.
+  pen: Option<PenPacket>,
+  /// The id of the finger, if the pointer is driven by a touch.
+  touch: Option<u64>,
 }
 
 impl Dispatcher {
@@ -79,11 +123,14 @@ impl Dispatcher {
     match event {
       WindowEvent::ModifiersChanged(s) => self.info.modifiers = s.state(),
       WindowEvent::CursorMoved { position, .. } => {
+        self.info.pen = None;
+        self.info.touch = None;
         let pos = position.to_logical::<f32>(wnd_factor);
         self.cursor_move_to(Point::new(pos.x, pos.y))
       }
       WindowEvent::CursorLeft { .. } => self.on_cursor_left(),
       WindowEvent::MouseWheel { delta, .. } => self.dispatch_wheel(delta, wnd_factor),
+      WindowEvent::Touch(touch) => self.dispatch_touch(touch, wnd_factor),
       _ => log::info!("not processed event {:?}", event),
     }
   }
@@ -121,26 +168,84 @@ impl Dispatcher {
   }
 
   fn cursor_press_down(&mut self, hit: Option<WidgetId>) {
-    let grab_pointer = *self.grab_mouse_wid.borrow();
-    if let Some(grab_pointer) = grab_pointer {
+    if let Some(grab_pointer) = self.grab_target() {
       self
         .window()
         .add_delay_event(DelayEvent::GrabPointerDown(grab_pointer));
     } else {
       self.pointer_down_wid = None;
+      self.cancel_long_press();
+      self.drag = None;
       if let Some(hit) = hit {
         self.pointer_down_wid = Some(hit);
+        self.pointer_down_pos = self.info.cursor_pos;
         self
           .window()
           .add_delay_event(DelayEvent::PointerDown(hit));
+        self.start_long_press(hit);
+        self.drag = self
+          .listener_of(hit, MixFlags::Drag)
+          .map(|wid| DragGesture::Pending { wid, origin: self.info.cursor_pos });
       }
     }
   }
 
+  /// The nearest widget from `hit` to the root that listens to the events of
+  /// the `flag`.
+  fn listener_of(&self, hit: WidgetId, flag: MixFlags) -> Option<WidgetId> {
+    let wnd = self.window();
+    let tree = wnd.tree();
+    let listener = hit.ancestors(tree).find(|id| {
+      id.query_all_iter::<MixBuiltin>(tree)
+        .any(|m| m.contain_flag(flag))
+    });
+    listener
+  }
+
+  /// Start to recognize a long press if any widget under the pointer listens
+  /// to it.
+  fn start_long_press(&mut self, hit: WidgetId) {
+    if self
+      .listener_of(hit, MixFlags::LongPress)
+      .is_none()
+    {
+      return;
+    }
+
+    let wnd = self.window();
+    let tree = wnd.tree();
+    let wnd_id = self.wnd_id;
+    let dur = GestureSettings::of_widget(hit, tree).long_press;
+    let h = observable::timer((), dur, AppCtx::scheduler()).subscribe(move |_| {
+      let Some(wnd) = AppCtx::get_window(wnd_id) else { return };
+      let mut dispatcher = wnd.dispatcher.borrow_mut();
+      dispatcher.long_press = None;
+      // The press is released, canceled, or dragged away already.
+      if dispatcher.pointer_down_wid == Some(hit) && !wnd.is_dragging() {
+        // The release of a long press isn't a tap.
+        dispatcher.pointer_down_wid = None;
+        drop(dispatcher);
+        wnd.add_delay_event(DelayEvent::LongPress(hit));
+      }
+    });
+    self.long_press = Some(h);
+  }
+
+  fn cancel_long_press(&mut self) {
+    if let Some(h) = self.long_press.take() {
+      h.unsubscribe();
+    }
+  }
+
   fn cursor_press_up(&mut self, hit: Option<WidgetId>) {
+    self.cancel_long_press();
     let wnd = self.window();
-    let grab_pointer = *self.grab_mouse_wid.borrow();
-    if let Some(grab_pointer) = grab_pointer {
+    if wnd.is_dragging() {
+      // The drag ends with a drop rather than a tap.
+      self.pointer_down_wid = None;
+      wnd.add_delay_event(DelayEvent::DragDrop(self.info.cursor_pos));
+    }
+    if let Some(grab_pointer) = self.grab_target() {
       wnd.add_delay_event(DelayEvent::GrabPointerUp(grab_pointer));
     } else {
       if let Some(hit) = hit {
@@ -153,26 +258,124 @@ impl Dispatcher {
       }
       self.pointer_down_wid = None;
     }
+    self.end_drag(false);
+    self.release_pointer_capture();
+  }
+
+  /// The captured pointer is released with the press, the pointer enters the
+  /// widget under it then.
+  fn release_pointer_capture(&mut self) {
+    let pointer = self.info.pointer_id();
+    if self.window().release_pointer_capture(pointer) {
+      self.pointer_enter_leave_dispatch();
+    }
   }
 
   pub fn cursor_move_to(&mut self, position: Point) {
+    if self.window().pointer_lock_widget().is_some() {
+      // The locked pointer moves by the raw movements of the mouse.
+      return;
+    }
     self.info.cursor_pos = position;
+    let pressure = self.info.pressure();
+    let sample = PointerSample { position, pressure, timestamp: clock::now() };
+    let wnd = self.window();
+    wnd
+      .cursor_sprite
+      .borrow_mut()
+      .move_to(position, self.wnd_id);
+    if wnd.is_dragging() {
+      // A drag takes all the moves of the pointer.
+      wnd.add_delay_event(DelayEvent::DragMove(position));
+      return;
+    }
     let grab_pointer = *self.grab_mouse_wid.borrow();
     if let Some(grab_pointer) = grab_pointer {
       self
         .window()
-        .add_delay_event(DelayEvent::GrabPointerMove(grab_pointer));
+        .add_pointer_move(grab_pointer, true, sample);
     } else {
+      self.update_drag(position);
+      self.cancel_tap_if_dragged();
       self.pointer_enter_leave_dispatch();
-      if let Some(hit) = self.hit_widget() {
-        self
-          .window()
-          .add_delay_event(DelayEvent::PointerMove(hit));
+      if let Some(hit) = self.pointer_target() {
+        self.window().add_pointer_move(hit, false, sample);
       }
     }
   }
 
+  /// A pressed pointer that moves farther than the slop is a drag, not a tap
+  /// or a long press.
+  fn cancel_tap_if_dragged(&mut self) {
+    let Some(wid) = self.pointer_down_wid else { return };
+    let wnd = self.window();
+    let tree = wnd.tree();
+    let offset = self.info.cursor_pos - self.pointer_down_pos;
+    if wid.is_dropped(tree)
+      || GestureSettings::of_widget(wid, tree).is_drag(&self.info.pointer_type(), offset)
+    {
+      self.pointer_down_wid = None;
+      self.cancel_long_press();
+    }
+  }
+
+  /// Start the pending drag once the pointer moves beyond the slop, and follow
+  /// the pointer after it starts.
+  fn update_drag(&mut self, position: Point) {
+    let Some(drag) = self.drag else { return };
+    let wnd = self.window();
+    let tree = wnd.tree();
+    match drag {
+      DragGesture::Pending { wid, .. } | DragGesture::Dragging { wid, .. }
+        if wid.is_dropped(tree) =>
+      {
+        self.drag = None;
+      }
+      DragGesture::Pending { wid, origin } => {
+        let offset = position - origin;
+        if GestureSettings::of_widget(wid, tree).is_drag(&self.info.pointer_type(), offset) {
+          // The release of a drag isn't a tap.
+          self.pointer_down_wid = None;
+          self.cancel_long_press();
+          self.drag = Some(DragGesture::Dragging { wid, origin, last: position });
+          wnd.add_delay_event(DelayEvent::DragStart(wid));
+          wnd.add_delay_event(DelayEvent::DragUpdate { id: wid, delta: offset, offset });
+        }
+      }
+      DragGesture::Dragging { wid, origin, last } => {
+        self.drag = Some(DragGesture::Dragging { wid, origin, last: position });
+        let (delta, offset) = (position - last, position - origin);
+        wnd.add_delay_event(DelayEvent::DragUpdate { id: wid, delta, offset });
+      }
+    }
+  }
+
+  /// End the drag wherever the pointer is, a drag that hasn't started ends
+  /// silently.
+  fn end_drag(&mut self, canceled: bool) {
+    if let Some(DragGesture::Dragging { wid, origin, .. }) = self.drag.take() {
+      let offset = self.info.cursor_pos - origin;
+      self
+        .window()
+        .add_delay_event(DelayEvent::DragEnd { id: wid, offset, canceled });
+    }
+  }
+
+  /// The platform takes the pressed pointer away, e.g. the touch turns into a
+  /// gesture of the system, the press ends without a release.
+  fn cursor_press_cancel(&mut self) {
+    self.cancel_long_press();
+    self.end_drag(true);
+    if let Some(wid) = self.pointer_down_wid.take() {
+      self
+        .window()
+        .add_delay_event(DelayEvent::PointerCancel(wid));
+    }
+    self.release_pointer_capture();
+  }
+
   pub fn on_cursor_left(&mut self) {
+    self.window().cursor_sprite.borrow_mut().hide();
     if self.grab_mouse_wid.borrow().is_none() {
       self.info.cursor_pos = Point::new(-1., -1.);
       self.pointer_enter_leave_dispatch();
@@ -183,13 +386,18 @@ impl Dispatcher {
     &mut self, device_id: DeviceId, state: ElementState, button: MouseButton,
   ) {
     // A mouse press/release emit during another mouse's press will ignored.
+    self.info.pen = None;
+    self.info.touch = None;
     if self.info.mouse_button.0.get_or_insert(device_id) == &device_id {
       match state {
         ElementState::Pressed => {
           self.info.mouse_button.1 |= button.into();
           // only the first button press emit event.
           if self.info.mouse_button.1 == button.into() {
+            self.info.chord_buttons = button.into();
             self.bubble_pointer_down();
+          } else {
+            self.info.chord_buttons |= button.into();
           }
         }
         ElementState::Released => {
@@ -197,7 +405,7 @@ impl Dispatcher {
           // only the last button release emit event.
           if self.info.mouse_button.1.is_empty() {
             self.info.mouse_button.0 = None;
-            let hit = self.hit_widget();
+            let hit = self.pointer_target();
 
             self.cursor_press_up(hit);
           }
@@ -206,44 +414,111 @@ impl Dispatcher {
     }
   }
 
+  pub fn dispatch_pen_input(&mut self, packet: PenPacket) {
+    let was_contact = self
+      .info
+      .pen
+      .is_some_and(|p| p.state == PenState::Contact);
+    self.info.pen = Some(packet);
+    self.info.touch = None;
+    if packet.state == PenState::Leave {
+      self.info.mouse_button.1 = MouseButtons::empty();
+      if was_contact {
+        // The pen leaves without lifting from the surface, its press ends without
+        // a release.
+        self.cursor_press_cancel();
+      }
+      self.on_cursor_left();
+      return;
+    }
+
+    self.cursor_move_to(packet.position);
+    let is_contact = packet.state == PenState::Contact;
+    if is_contact && !was_contact {
+      // The pen contact acts as the primary button of the mouse.
+      self.info.mouse_button.1 = MouseButtons::PRIMARY;
+      self.info.chord_buttons = MouseButtons::PRIMARY;
+      self.bubble_pointer_down();
+    } else if !is_contact && was_contact {
+      self.info.mouse_button.1 = MouseButtons::empty();
+      let hit = self.pointer_target();
+      self.cursor_press_up(hit);
+    }
+  }
+
+  /// The first finger that touches the window drives the pointer as the
+  /// primary button of the mouse, the other fingers are ignored until it
+  /// lifts.
+  pub fn dispatch_touch(&mut self, touch: Touch, wnd_factor: f64) {
+    let pressed = !self.info.mouse_button.1.is_empty();
+    let driving = self.info.touch == Some(touch.id) && pressed;
+    let started = touch.phase == TouchPhase::Started && !pressed;
+    if !driving && !started {
+      return;
+    }
+
+    self.info.pen = None;
+    self.info.touch = Some(touch.id);
+    let pos = touch.location.to_logical::<f32>(wnd_factor);
+    let pos = Point::new(pos.x, pos.y);
+    match touch.phase {
+      TouchPhase::Started => {
+        self.cursor_move_to(pos);
+        self.info.mouse_button = (None, MouseButtons::PRIMARY);
+        self.info.chord_buttons = MouseButtons::PRIMARY;
+        self.bubble_pointer_down();
+      }
+      TouchPhase::Moved => self.cursor_move_to(pos),
+      TouchPhase::Ended => {
+        self.cursor_move_to(pos);
+        self.info.mouse_button.1 = MouseButtons::empty();
+        let hit = self.pointer_target();
+        self.cursor_press_up(hit);
+      }
+      TouchPhase::Cancelled => {
+        self.info.mouse_button.1 = MouseButtons::empty();
+        self.cursor_press_cancel();
+      }
+    }
+  }
+
   pub fn dispatch_wheel(&mut self, delta: MouseScrollDelta, wnd_factor: f64) {
     if let Some(wid) = self.hit_widget() {
-      let (delta_x, delta_y) = match delta {
-        MouseScrollDelta::LineDelta(x, y) => (x * 16., y * 16.),
+      let (delta_x, delta_y, delta_mode) = match delta {
+        MouseScrollDelta::LineDelta(x, y) => {
+          let line = WheelDeltaMode::LINE_PIXELS;
+          (x * line, y * line, WheelDeltaMode::Line)
+        }
         MouseScrollDelta::PixelDelta(delta) => {
           let winit::dpi::LogicalPosition { x, y } = delta.to_logical(wnd_factor);
-          (x, y)
+          (x, y, WheelDeltaMode::Pixel)
         }
       };
 
       self
         .window()
-        .add_delay_event(DelayEvent::Wheel { id: wid, delta_x, delta_y });
+        .add_delay_event(DelayEvent::Wheel { id: wid, delta_x, delta_y, delta_mode });
     }
   }
 
   fn bubble_pointer_down(&mut self) {
-    let hit = self.hit_widget();
+    let hit = self.pointer_target();
     let wnd = self.window();
-    let tree = wnd.tree();
-
-    let nearest_focus = hit.and_then(|wid| {
-      wid.ancestors(tree).find(|id| {
-        id.query_all_iter::<MixBuiltin>(tree)
-          .any(|m| m.contain_flag(MixFlags::Focus))
-      })
-    });
-    if let Some(focus_id) = nearest_focus {
-      wnd.focus_mgr.borrow_mut().focus(focus_id, tree);
-    } else {
-      wnd.focus_mgr.borrow_mut().blur(tree);
+    wnd
+      .focus_mgr
+      .borrow_mut()
+      .set_focus_visible(false);
+    // The press focuses after the pointer down event, as its default action,
+    // but a press that no widget receives can't be prevented.
+    if hit.is_none() || self.grab_target().is_some() {
+      wnd.focus_pressed(hit);
     }
 
     self.cursor_press_down(hit);
   }
 
   fn pointer_enter_leave_dispatch(&mut self) {
-    let new_hit = self.hit_widget();
+    let new_hit = self.pointer_target();
     let wnd = self.window();
     let tree = wnd.tree();
 
@@ -253,67 +528,41 @@ impl Dispatcher {
       .find(|wid| !(*wid).is_dropped(tree))
       .copied();
 
-    if let Some(old) = old {
-      let ancestor = new_hit.and_then(|w| w.lowest_common_ancestor(old, tree));
-      wnd.add_delay_event(DelayEvent::PointerLeave { bottom: old, up: ancestor });
-    };
+    // The hit widget isn't changed, nothing to enter or leave. Don't add the
+    // empty events, so the pointer moves can be coalesced.
+    if old != new_hit {
+      if let Some(old) = old {
+        let ancestor = new_hit.and_then(|w| w.lowest_common_ancestor(old, tree));
+        wnd.add_delay_event(DelayEvent::PointerLeave { bottom: old, up: ancestor });
+      };
 
-    if let Some(new) = new_hit {
-      let ancestor = old.and_then(|o| o.lowest_common_ancestor(new, tree));
-      wnd.add_delay_event(DelayEvent::PointerEnter { bottom: new, up: ancestor });
+      if let Some(new) = new_hit {
+        let ancestor = old.and_then(|o| o.lowest_common_ancestor(new, tree));
+        wnd.add_delay_event(DelayEvent::PointerEnter { bottom: new, up: ancestor });
+      }
     }
 
-    self.entered_widgets = new_hit.map_or(vec![], |wid| wid.ancestors(tree).collect::<Vec<_>>());
+    self.entered_widgets.clear();
+    if let Some(wid) = new_hit {
+      self.entered_widgets.extend(wid.ancestors(tree));
+    }
   }
 
-  fn hit_widget(&self) -> Option<WidgetId> {
-    let mut hit_target = None;
-    let wnd = self.window();
-    let tree = wnd.tree();
-
-    let mut w = Some(tree.root());
-    let mut pos = self.info.cursor_pos;
-    while let Some(id) = w {
-      let r = id.assert_get(tree);
-      let ctx = HitTestCtx { id, tree: wnd.tree };
-      let HitTest { hit, can_hit_child } = r.hit_test(&ctx, pos);
-
-      pos = tree.map_from_parent(id, pos);
-
-      if hit {
-        hit_target = w;
-      }
-
-      w = id
-        .last_child(tree)
-        .filter(|_| can_hit_child)
-        .or_else(|| {
-          if hit {
-            return None;
-          }
+  /// The widget that the events of the pointer go to, the widget that captures
+  /// the pointer, or the widget under it.
+  fn pointer_target(&self) -> Option<WidgetId> {
+    let captured = self
+      .window()
+      .pointer_capture_widget(self.info.pointer_id());
+    captured.or_else(|| self.hit_widget())
+  }
 
-          pos = tree.map_to_parent(id, pos);
-
-          let mut node = w;
-          while let Some(p) = node {
-            node = p.previous_sibling(tree);
-            if node.is_some() {
-              break;
-            } else {
-              node = p.parent(tree);
-
-              if let Some(id) = node {
-                pos = tree.map_to_parent(id, pos);
-                if node == hit_target {
-                  node = None;
-                }
-              }
-            }
-          }
-          node
-        });
-    }
-    hit_target
+  pub(crate) fn hit_widget(&self) -> Option<WidgetId> {
+    let wnd = self.window();
+    self
+      .hit_index
+      .borrow_mut()
+      .hit(wnd.tree(), self.info.cursor_pos)
   }
 }
 
@@ -326,6 +575,35 @@ impl DispatchInfo {
 
   #[inline]
   pub fn mouse_buttons(&self) -> MouseButtons { self.mouse_button.1 }
+
+  #[inline]
+  pub fn chord_buttons(&self) -> MouseButtons { self.chord_buttons }
+
+  #[inline]
+  pub fn pen(&self) -> Option<&PenPacket> { self.pen.as_ref() }
+
+  /// The id of the current pointer, only one pointer is traced now.
+  #[inline]
+  pub fn pointer_id(&self) -> PointerId { PointerId(0) }
+
+  /// The pressure of the current pointer.
+  pub fn pointer_type(&self) -> PointerType {
+    if self.pen.is_some() {
+      PointerType::Pen
+    } else if self.touch.is_some() {
+      PointerType::Touch
+    } else {
+      PointerType::Mouse
+    }
+  }
+
+  pub fn pressure(&self) -> f32 {
+    match &self.pen {
+      Some(pen) => pen.pressure(),
+      None if self.mouse_button.1.is_empty() => 0.,
+      None => 0.5,
+    }
+  }
 }
 
 #[cfg(test)]
@@ -643,14 +921,29 @@ mod tests {
       position: (50f64, 50f64).into(),
     });
 
-    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
-    #[allow(deprecated)]
-    wnd.processes_native_event(WindowEvent::CursorMoved {
-      device_id,
-      position: (50f64, 150f64).into(),
-    });
-    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
-    wnd.run_frame_tasks();
+    let press_move_release = |wnd: &TestWindow| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: (50f64, 50f64).into(),
+      });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: (50f64, 150f64).into(),
+      });
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+
+    // The pointer moves farther than the slop, it's not a tap.
+    press_move_release(&wnd);
+    assert_eq!(*click_path.read(), 0);
+
+    // Otherwise, tap the common ancestor.
+    AppCtx::set_gesture_settings(GestureSettings { mouse_slop: 200., ..Default::default() });
+    press_move_release(&wnd);
     assert_eq!(*click_path.read(), 1);
   }
 
@@ -680,6 +973,8 @@ mod tests {
     });
 
     wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    // The focus is the default action of the pointer down event.
+    wnd.run_frame_tasks();
 
     // point down on a focus widget
     assert!(wnd.focus_mgr.borrow().focusing().is_some());
@@ -692,6 +987,7 @@ mod tests {
     });
 
     wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
 
     assert!(wnd.focus_mgr.borrow().focusing().is_none());
   }
@@ -18,6 +18,8 @@ pub(crate) struct FocusManager {
   arena: Arena<FocusNodeInfo>,
   root: NodeId,
   wnd_id: WindowId,
+  /// Whether the focus is moved by the keyboard rather than the pointer.
+  focus_visible: bool,
 }
 
 pub struct FocusHandle {
@@ -92,6 +94,7 @@ impl FocusManager {
       node_ids: ahash::HashMap::default(),
       arena,
       root,
+      focus_visible: false,
     }
   }
 
@@ -195,6 +198,18 @@ impl FocusManager {
     let mut scope_id = node_id
       .and_then(|id| self.scope_id(id))
       .or(Some(self.root));
+    // Leave the innermost scope that is a single tab stop.
+    let single_stop = node_id.and_then(|id| {
+      self.scope_list(id).find(|s| {
+        self
+          .scope_property(self.assert_get(*s).wid)
+          .skip_descendants_on_tab
+      })
+    });
+    if let Some(scope) = single_stop {
+      node_id = Some(scope);
+      scope_id = self.scope_id(scope);
+    }
     loop {
       scope_id?;
       let next = self.focus_step_in_scope(scope_id.unwrap(), node_id, backward);
@@ -311,6 +326,16 @@ impl FocusManager {
     None
   }
 
+  /// The focusable widgets in the scope, in the tab order.
+  fn focusable_in_scope(&self, scope_id: NodeId, widgets: &mut Vec<WidgetId>) {
+    for (_, id, focus_type) in self.collect_tab_index_in_scope(scope_id, false) {
+      match focus_type {
+        FocusType::Node => widgets.extend(self.assert_get(id).wid),
+        FocusType::Scope => self.focusable_in_scope(id, widgets),
+      }
+    }
+  }
+
   fn scope_id(&self, node_id: NodeId) -> Option<NodeId> { self.scope_list(node_id).next() }
 
   fn scope_list(&self, node_id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
@@ -480,6 +505,56 @@ impl FocusManager {
     self.refresh_focus(tree);
   }
 
+  /// Move the focus by the arrow `key` in the innermost scope of the focusing
+  /// widget that navigates by the arrow keys, see [`FocusScope`]. Returns
+  /// false if the focus isn't moved.
+  pub(crate) fn focus_by_arrow(&mut self, key: &NamedKey, tree: &WidgetTree) -> bool {
+    let dir = match key {
+      NamedKey::ArrowLeft => Vector::new(-1., 0.),
+      NamedKey::ArrowRight => Vector::new(1., 0.),
+      NamedKey::ArrowUp => Vector::new(0., -1.),
+      NamedKey::ArrowDown => Vector::new(0., 1.),
+      _ => return false,
+    };
+    let Some(focusing) = self.focusing.filter(|id| !id.is_dropped(tree)) else {
+      return false;
+    };
+    let Some(&node_id) = self.node_ids.get(&focusing) else { return false };
+
+    let wnd = self.window();
+    let center = |id: WidgetId| {
+      let size = wnd.widget_size(id).unwrap_or_default();
+      wnd.map_to_global(Rect::from_size(size).center(), id)
+    };
+    let from = center(focusing);
+    let scopes: Vec<_> = self.scope_list(node_id).collect();
+    for scope in scopes {
+      let property = self.scope_property(self.assert_get(scope).wid);
+      if !property.arrow_navigation.accept(dir) {
+        continue;
+      }
+      let mut widgets = vec![];
+      self.focusable_in_scope(scope, &mut widgets);
+      let candidates: Vec<_> = widgets
+        .into_iter()
+        .filter(|id| *id != focusing && !id.is_dropped(tree))
+        .map(|id| (id, center(id) - from))
+        .collect();
+      let target = nearest_in_direction(&candidates, dir).or_else(|| {
+        property
+          .arrow_wrap
+          .then(|| wrap_around(&candidates, dir))
+          .flatten()
+      });
+      if let Some(target) = target {
+        self.set_focus_visible(true);
+        self.focus(target, tree);
+        return true;
+      }
+    }
+    false
+  }
+
   pub fn focus(&mut self, wid: WidgetId, tree: &WidgetTree) {
     self.request_focus_to(Some(wid));
     self.refresh_focus(tree);
@@ -495,6 +570,24 @@ impl FocusManager {
   /// return the focusing widget.
   pub fn focusing(&self) -> Option<WidgetId> { self.focusing }
 
+  /// Whether the focus is moved by the keyboard, only then the focusing widget
+  /// shows its focus ring, see [`Window::focus_ring_rect`].
+  pub fn is_focus_visible(&self) -> bool { self.focus_visible }
+
+  /// The keyboard moves the focus with `visible` true, the pointer with false.
+  pub(crate) fn set_focus_visible(&mut self, visible: bool) {
+    if self.focus_visible != visible {
+      self.focus_visible = visible;
+      self.repaint_focus_ring();
+    }
+  }
+
+  fn repaint_focus_ring(&self) {
+    let wnd = self.window();
+    let tree = wnd.tree();
+    tree.dirty_marker().mark(tree.root());
+  }
+
   pub fn refresh_focus(&mut self, tree: &WidgetTree) {
     let new_focus = self.next_focus(tree);
     if self.focus_widgets.first() != new_focus.as_ref() {
@@ -512,6 +605,9 @@ impl FocusManager {
     self.request_focusing = Some(focus_to);
   }
 
+  /// Whether a focus change is requested but not applied yet.
+  pub(crate) fn is_requesting(&self) -> bool { self.request_focusing.is_some() }
+
   fn change_focusing_to(&mut self, node: Option<WidgetId>) -> Option<WidgetId> {
     let wnd = self.window();
     let tree = wnd.tree();
@@ -541,10 +637,41 @@ impl FocusManager {
 
     self.focus_widgets = node.map_or(vec![], |wid| wid.ancestors(tree).collect::<Vec<_>>());
     self.focusing = node;
+    if self.focus_visible {
+      self.repaint_focus_ring();
+    }
     old
   }
 }
 
+/// The candidate nearest to the focusing widget in the `dir`, the offsets of
+/// the candidates are relative to the focusing widget. It prefers the
+/// candidates in line with the focusing widget.
+fn nearest_in_direction(candidates: &[(WidgetId, Vector)], dir: Vector) -> Option<WidgetId> {
+  candidates
+    .iter()
+    .filter_map(|(id, offset)| {
+      let main = offset.dot(dir);
+      let cross = (*offset - dir * main).length();
+      (main > 0.).then_some((*id, main + cross * 2.))
+    })
+    .min_by(|a, b| a.1.total_cmp(&b.1))
+    .map(|(id, _)| id)
+}
+
+/// The candidate farthest in the opposite of the `dir` and in line with the
+/// focusing widget, such as the start of the row when moving right.
+fn wrap_around(candidates: &[(WidgetId, Vector)], dir: Vector) -> Option<WidgetId> {
+  candidates
+    .iter()
+    .map(|(id, offset)| {
+      let main = offset.dot(dir);
+      (*id, (*offset - dir * main).length(), main)
+    })
+    .min_by(|a, b| a.1.total_cmp(&b.1).then(a.2.total_cmp(&b.2)))
+    .map(|(id, ..)| id)
+}
+
 #[cfg(test)]
 mod tests {
   use std::cell::RefCell;
@@ -901,4 +1028,43 @@ mod tests {
     assert_eq!(*input.read(), "nice to see you");
     wnd.draw_frame();
   }
+
+  #[test]
+  fn focus_ring_only_for_keyboard() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(10., 10.), tab_index: 0i16 }
+        @MockBox { size: Size::new(20., 20.), tab_index: 0i16 }
+      }
+    });
+    wnd.draw_frame();
+    assert_eq!(wnd.focus_ring_rect(), None);
+
+    wnd.processes_keyboard_event(
+      PhysicalKey::Code(KeyCode::Tab),
+      VirtualKey::Named(NamedKey::Tab),
+      false,
+      KeyLocation::Standard,
+      winit::event::ElementState::Pressed,
+    );
+    wnd.draw_frame();
+    assert_eq!(wnd.focus_ring_rect(), Some(Rect::new(Point::zero(), Size::new(10., 10.))));
+
+    let device_id = unsafe { winit::event::DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(winit::event::WindowEvent::CursorMoved {
+      device_id,
+      position: (15., 5.).into(),
+    });
+    wnd.process_mouse_input(
+      device_id,
+      winit::event::ElementState::Pressed,
+      winit::event::MouseButton::Left,
+    );
+    wnd.draw_frame();
+    assert_eq!(wnd.focus_ring_rect(), None);
+    assert!(wnd.focus_mgr.borrow().focusing().is_some());
+  }
 }
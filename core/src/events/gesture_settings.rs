@@ -0,0 +1,144 @@
+use crate::prelude::*;
+
+/// The thresholds to recognize the gestures of the pointers.
+///
+/// A finger is less precise than a mouse and a pen is more precise, so every
+/// type of pointer has its own slop, the distance a pointer can move before a
+/// tap turns into a drag. The recognizers read the settings when the events
+/// happen, so the settings can be changed at any time.
+///
+/// The settings of the application are set by
+/// [`AppCtx::set_gesture_settings`], and a subtree can use its own settings by
+/// providing a `GestureSettings` to it:
+///
+/// ```
+/// use ribir::prelude::*;
+///
+/// let settings = GestureSettings { touch_slop: 16., ..Default::default() };
+/// providers![Queryable(settings)].with_child(fn_widget! {
+///   @Text { text: "A less sensitive area" }
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GestureSettings {
+  /// The slop of a mouse, in logical pixels.
+  pub mouse_slop: f32,
+  /// The slop of a finger, in logical pixels.
+  pub touch_slop: f32,
+  /// The slop of a pen, in logical pixels.
+  pub pen_slop: f32,
+  /// How long a pointer holds still before it's a long press.
+  pub long_press: Duration,
+}
+
+impl Default for GestureSettings {
+  fn default() -> Self {
+    Self { mouse_slop: 4., touch_slop: 10., pen_slop: 2., long_press: Duration::from_millis(500) }
+  }
+}
+
+impl GestureSettings {
+  /// The settings that the widget of `ctx` uses, they're provided by its
+  /// nearest ancestor, or the settings of the application.
+  pub fn of(ctx: &impl ProviderCtx) -> Self {
+    ctx
+      .of::<GestureSettings>()
+      .map_or_else(AppCtx::gesture_settings, |s| s.clone())
+  }
+
+  pub(crate) fn of_widget(id: WidgetId, tree: &WidgetTree) -> Self {
+    id.query_ancestors_ref::<GestureSettings>(tree)
+      .map_or_else(AppCtx::gesture_settings, |s| s.clone())
+  }
+
+  /// The slop of the type of pointer.
+  pub fn slop(&self, pointer: &PointerType) -> f32 {
+    match pointer {
+      PointerType::Mouse => self.mouse_slop,
+      PointerType::Touch => self.touch_slop,
+      PointerType::Pen => self.pen_slop,
+    }
+  }
+
+  /// Whether a pointer that moves by `offset` from where it was pressed starts
+  /// a drag.
+  pub fn is_drag(&self, pointer: &PointerType, offset: Vector) -> bool {
+    offset.length() > self.slop(pointer)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn tap_by_pen(wnd: &mut TestWindow, from: Point, to: Point) {
+    let pen = |position, state| PenPacket {
+      position,
+      state,
+      pressure: 0.5,
+      tilt_x: 0.,
+      tilt_y: 0.,
+      twist: 0.,
+      is_eraser: false,
+    };
+    wnd.process_pen_input(pen(from, PenState::Contact));
+    wnd.process_pen_input(pen(to, PenState::Contact));
+    wnd.process_pen_input(pen(to, PenState::Hover));
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn default_slop() {
+    let settings = GestureSettings::default();
+    let moved = Vector::new(6., 0.);
+    assert!(settings.is_drag(&PointerType::Pen, moved));
+    assert!(settings.is_drag(&PointerType::Mouse, moved));
+    assert!(!settings.is_drag(&PointerType::Touch, moved));
+  }
+
+  #[test]
+  fn move_cancels_tap() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_tap: move |_| *$w_taps.write() += 1,
+      }
+    });
+    wnd.draw_frame();
+
+    tap_by_pen(&mut wnd, Point::new(50., 50.), Point::new(51., 50.));
+    assert_eq!(*taps.read(), 1);
+    tap_by_pen(&mut wnd, Point::new(50., 50.), Point::new(56., 50.));
+    assert_eq!(*taps.read(), 1);
+
+    // The settings are read when the pointer moves.
+    AppCtx::set_gesture_settings(GestureSettings { pen_slop: 10., ..Default::default() });
+    tap_by_pen(&mut wnd, Point::new(50., 50.), Point::new(56., 50.));
+    assert_eq!(*taps.read(), 2);
+  }
+
+  #[test]
+  fn provided_settings() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let settings = GestureSettings { pen_slop: 10., ..Default::default() };
+      let w_taps = w_taps.clone_writer();
+      Provider::new(Box::new(Queryable(settings))).with_child(fn_widget! {
+        @MockBox {
+          size: Size::new(100., 100.),
+          on_tap: move |_| *$w_taps.write() += 1,
+        }
+      })
+    });
+    wnd.draw_frame();
+
+    tap_by_pen(&mut wnd, Point::new(50., 50.), Point::new(56., 50.));
+    assert_eq!(*taps.read(), 1);
+  }
+}
@@ -0,0 +1,371 @@
+use std::ptr::NonNull;
+
+use ahash::HashMap;
+use smallvec::SmallVec;
+
+use crate::prelude::*;
+
+/// The parents that have at least this many children index their children in
+/// a grid.
+const GRID_MIN_CHILDREN: usize = 32;
+/// A child that overlaps more cells than this is checked for every position
+/// rather than put in the cells.
+const MAX_CELLS_OF_CHILD: usize = 16;
+
+/// A cache of the areas where the widgets may be hit, to find the widget hit
+/// by a position without visiting the whole tree. The result is always the
+/// same as [`walk_hit`].
+///
+/// The subtrees whose areas don't contain the position are skipped, and the
+/// children of a wide parent are looked up in a grid. The subtrees with a
+/// transform can't be bounded, so they're always visited.
+#[derive(Default)]
+pub(crate) struct HitIndex {
+  /// The layout version of the tree that the index is built for.
+  layout_version: Option<usize>,
+  /// The area in the coordinate of the parent where the widget or its
+  /// descendants may be hit, `None` if it's unbounded.
+  bounds: HashMap<WidgetId, Option<Rect>>,
+  grids: HashMap<WidgetId, ChildGrid>,
+  /// The buffer of the walk, kept to not allocate for every hit.
+  steps: Vec<HitStep>,
+}
+
+/// A step of walking the tree to find the hit widget.
+enum HitStep {
+  /// Hit test the widget, the position is in the coordinate of its parent.
+  Visit(WidgetId, Point),
+  /// The widget is hit, and none of its descendants is.
+  Hit(WidgetId),
+}
+
+/// The children of a wide parent put in the cells of a grid by their areas.
+struct ChildGrid {
+  children: Vec<WidgetId>,
+  /// The children that are checked for every position, in order.
+  always: Vec<usize>,
+  area: Rect,
+  cols: usize,
+  rows: usize,
+  cell_size: Size,
+  /// The children in every cell, in order.
+  cells: Vec<Vec<usize>>,
+}
+
+impl HitIndex {
+  /// Find the widget hit by the `pos`.
+  pub(crate) fn hit(&mut self, tree: &WidgetTree, pos: Point) -> Option<WidgetId> {
+    // The layout is outdated until the dirty widgets are laid out.
+    let mut steps = std::mem::take(&mut self.steps);
+    let hit = if tree.is_dirty() {
+      walk_hit_with(tree, pos, &mut steps)
+    } else {
+      if self.layout_version != Some(tree.layout_version) {
+        self.rebuild(tree);
+      }
+      walk(tree, pos, &mut steps, |id, pos, steps| self.push_children(tree, id, pos, steps))
+    };
+    self.steps = steps;
+    hit
+  }
+
+  fn rebuild(&mut self, tree: &WidgetTree) {
+    self.bounds.clear();
+    self.grids.clear();
+    self.subtree_bounds(tree, tree.root());
+    self.layout_version = Some(tree.layout_version);
+  }
+
+  fn subtree_bounds(&mut self, tree: &WidgetTree, id: WidgetId) -> Option<Rect> {
+    let r = id.assert_get(tree);
+    let ctx = hit_ctx(tree, id);
+    let mut bounds = r.hit_bounds(&ctx);
+    let offset = tree
+      .store
+      .layout_box_pos(id)
+      .unwrap_or_default()
+      .to_vector();
+
+    let children = id.paint_ordered_children(tree);
+    let mut children_bounds = SmallVec::<[_; 8]>::with_capacity(children.len());
+    for &c in &children {
+      let b = self.subtree_bounds(tree, c);
+      bounds = bounds
+        .zip(b)
+        .map(|(a, b)| a.union(&b.translate(offset)));
+      children_bounds.push(b);
+    }
+    if children.len() >= GRID_MIN_CHILDREN {
+      self
+        .grids
+        .insert(id, ChildGrid::new(children.into_vec(), &children_bounds));
+    }
+
+    let bounds = if r.get_transform().is_some() { None } else { bounds };
+    self.bounds.insert(id, bounds);
+    bounds
+  }
+
+  /// Push the children of `id` that may be hit by the `pos` to the `steps`,
+  /// the `pos` is in the coordinate of `id`.
+  fn push_children(&self, tree: &WidgetTree, id: WidgetId, pos: Point, steps: &mut Vec<HitStep>) {
+    let may_hit = |c: &WidgetId| self.may_hit(*c, pos);
+    match self.grids.get(&id) {
+      Some(grid) => {
+        // The candidates are in the order to hit, the first one is hit first.
+        let children: SmallVec<[_; 8]> = grid.candidates(pos).filter(may_hit).collect();
+        steps.extend(
+          children
+            .into_iter()
+            .rev()
+            .map(|c| HitStep::Visit(c, pos)),
+        );
+      }
+      None => {
+        let children = id.paint_ordered_children(tree);
+        steps.extend(
+          children
+            .into_iter()
+            .filter(may_hit)
+            .map(|c| HitStep::Visit(c, pos)),
+        );
+      }
+    }
+  }
+
+  /// The widgets not indexed or unbounded may be hit anywhere.
+  fn may_hit(&self, id: WidgetId, pos: Point) -> bool {
+    match self.bounds.get(&id) {
+      Some(Some(rect)) => rect.contains(pos),
+      _ => true,
+    }
+  }
+}
+
+impl ChildGrid {
+  fn new(children: Vec<WidgetId>, bounds: &[Option<Rect>]) -> Self {
+    let area = bounds
+      .iter()
+      .flatten()
+      .fold(Rect::zero(), |area, b| area.union(b));
+    let side = (children.len() as f32).sqrt().ceil() as usize;
+    let cell_size = area.size / side as f32;
+    let mut grid = Self {
+      children,
+      always: vec![],
+      area,
+      cols: side,
+      rows: side,
+      cell_size,
+      cells: vec![vec![]; side * side],
+    };
+
+    let degenerate = cell_size.is_empty();
+    for (idx, b) in bounds.iter().enumerate() {
+      match b {
+        // An empty area is never hit.
+        Some(rect) if rect.is_empty() => {}
+        Some(rect) if !degenerate => {
+          let (min_col, min_row) = grid.cell_of(rect.min());
+          let (max_col, max_row) = grid.cell_of(rect.max());
+          if (max_col - min_col + 1) * (max_row - min_row + 1) > MAX_CELLS_OF_CHILD {
+            grid.always.push(idx);
+          } else {
+            for row in min_row..=max_row {
+              for col in min_col..=max_col {
+                grid.cells[row * grid.cols + col].push(idx);
+              }
+            }
+          }
+        }
+        _ => grid.always.push(idx),
+      }
+    }
+    grid
+  }
+
+  fn cell_of(&self, pos: Point) -> (usize, usize) {
+    let offset = pos - self.area.origin;
+    let col = (offset.x / self.cell_size.width).max(0.) as usize;
+    let row = (offset.y / self.cell_size.height).max(0.) as usize;
+    (col.min(self.cols - 1), row.min(self.rows - 1))
+  }
+
+  /// The children that may be hit by the `pos`, from the last to the first.
+  fn candidates(&self, pos: Point) -> impl Iterator<Item = WidgetId> + '_ {
+    let cell: &[usize] = if !self.cell_size.is_empty() && self.area.contains(pos) {
+      let (col, row) = self.cell_of(pos);
+      &self.cells[row * self.cols + col]
+    } else {
+      &[]
+    };
+
+    // Merge the two ordered lists in the reverse order.
+    let (mut a, mut b) = (cell.iter().rev().peekable(), self.always.iter().rev().peekable());
+    std::iter::from_fn(move || match (a.peek(), b.peek()) {
+      (Some(x), Some(y)) if x > y => a.next(),
+      (Some(_), Some(_)) | (None, Some(_)) => b.next(),
+      (Some(_), None) => a.next(),
+      (None, None) => None,
+    })
+    .map(|idx| self.children[*idx])
+  }
+}
+
+fn hit_ctx(tree: &WidgetTree, id: WidgetId) -> HitTestCtx {
+  HitTestCtx { id, tree: NonNull::from(tree) }
+}
+
+/// Find the widget hit by the `pos` by walking the whole tree.
+///
+/// The children are visited in the reverse order that they're painted, and
+/// the deepest hit widget of the first hit subtree is the result.
+#[cfg(test)]
+pub(crate) fn walk_hit(tree: &WidgetTree, pos: Point) -> Option<WidgetId> {
+  walk_hit_with(tree, pos, &mut vec![])
+}
+
+fn walk_hit_with(tree: &WidgetTree, pos: Point, steps: &mut Vec<HitStep>) -> Option<WidgetId> {
+  walk(tree, pos, steps, |id, pos, steps| {
+    let children = id.paint_ordered_children(tree);
+    steps.extend(
+      children
+        .into_iter()
+        .map(|c| HitStep::Visit(c, pos)),
+    );
+  })
+}
+
+/// Walk the tree from the root without recursion, the `push_children` pushes
+/// the children to visit of a widget, the last pushed one is visited first.
+fn walk(
+  tree: &WidgetTree, pos: Point, steps: &mut Vec<HitStep>,
+  push_children: impl Fn(WidgetId, Point, &mut Vec<HitStep>),
+) -> Option<WidgetId> {
+  steps.clear();
+  steps.push(HitStep::Visit(tree.root(), pos));
+  while let Some(step) = steps.pop() {
+    match step {
+      HitStep::Hit(id) => {
+        steps.clear();
+        return Some(id);
+      }
+      HitStep::Visit(id, pos) => {
+        let HitTest { hit, can_hit_child } = id
+          .assert_get(tree)
+          .hit_test(&hit_ctx(tree, id), pos);
+        // The widget is the result if none of its children is hit.
+        if hit {
+          steps.push(HitStep::Hit(id));
+        }
+        if can_hit_child {
+          push_children(id, tree.map_from_parent(id, pos), steps);
+        }
+      }
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  /// A xorshift generator to build the same random trees in every run.
+  struct Rng(u64);
+
+  impl Rng {
+    fn below(&mut self, n: u64) -> u64 {
+      self.0 ^= self.0 << 13;
+      self.0 ^= self.0 >> 7;
+      self.0 ^= self.0 << 17;
+      self.0 % n
+    }
+
+    fn coord(&mut self, max: u64) -> f32 { self.below(max) as f32 }
+  }
+
+  fn random_tree(rng: &mut Rng, depth: usize) -> Widget<'static> {
+    let size = Size::new(rng.coord(120), rng.coord(120));
+    let anchor = Point::new(rng.coord(400), rng.coord(400));
+    let count = match depth {
+      0 => 0,
+      // Some wide parents to index their children in a grid.
+      _ if rng.below(4) == 0 => 40,
+      _ => rng.below(4) as usize,
+    };
+    let children: Vec<_> = (0..count)
+      .map(|_| random_tree(rng, depth - 1))
+      .collect();
+
+    let w = if children.is_empty() {
+      rdl! { @MockBox { size, anchor } }.into_widget()
+    } else {
+      rdl! { @MockStack { anchor, @ { children } } }.into_widget()
+    };
+
+    match rng.below(6) {
+      0 => {
+        let slop = rng.coord(20);
+        rdl! { @HitSlop { slop: EdgeInsets::all(slop), @ { w } } }.into_widget()
+      }
+      1 => rdl! { @IgnorePointer { @ { w } } }.into_widget(),
+      2 => {
+        let scale = 0.5 + rng.coord(3) * 0.5;
+        rdl! {
+          @MockBox { size, transform: Transform::scale(scale, scale), @ { w } }
+        }
+        .into_widget()
+      }
+      _ => w,
+    }
+  }
+
+  #[test]
+  fn same_hit_as_walk() {
+    reset_test_env!();
+
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for seed in 1..=8 {
+      let tree = move || random_tree(&mut Rng(seed), 3);
+      let mut wnd = TestWindow::new_with_size(tree, Size::new(600., 600.));
+      wnd.draw_frame();
+
+      let tree = wnd.tree();
+      let mut index = HitIndex::default();
+      for _ in 0..500 {
+        let pos = Point::new(rng.coord(600), rng.coord(600));
+        assert_eq!(index.hit(tree, pos), walk_hit(tree, pos), "hit at {pos:?}");
+      }
+    }
+  }
+
+  #[test]
+  fn rebuild_only_after_relayout() {
+    reset_test_env!();
+
+    let size = Stateful::new(Size::new(50., 50.));
+    let c_size = size.clone_watcher();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! { @MockBox { size: pipe!(*$c_size) } },
+      Size::new(200., 200.),
+    );
+    wnd.draw_frame();
+    let mut index = HitIndex::default();
+    let pos = Point::new(80., 80.);
+    let content = wnd.tree().root().first_child(wnd.tree());
+    assert_ne!(index.hit(wnd.tree(), pos), content);
+
+    // A frame without any dirty widget keeps the index.
+    let version = wnd.tree().layout_version;
+    wnd.draw_frame();
+    assert_eq!(wnd.tree().layout_version, version);
+    assert_eq!(index.layout_version, Some(version));
+
+    *size.write() = Size::new(100., 100.);
+    wnd.draw_frame();
+    assert_ne!(wnd.tree().layout_version, version);
+    assert_eq!(index.hit(wnd.tree(), pos), content);
+  }
+}
@@ -2,6 +2,7 @@ pub use winit::keyboard::{
   Key as VirtualKey, KeyCode, KeyLocation, ModifiersState, NamedKey, PhysicalKey,
 };
 
+use super::{DefaultAction, DefaultActions};
 use crate::{impl_common_event_deref, prelude::*};
 
 #[derive(Debug)]
@@ -11,6 +12,7 @@ pub struct KeyboardEvent {
   is_repeat: bool,
   location: KeyLocation,
   common: CommonEvent,
+  default_actions: DefaultActions<KeyboardEvent>,
 }
 
 impl KeyboardEvent {
@@ -25,6 +27,17 @@ impl KeyboardEvent {
 
   #[inline]
   pub fn location(&self) -> KeyLocation { self.location }
+
+  /// Run `action` after the event is dispatched to all its listeners, unless
+  /// a listener prevents the default action of the event, even an ancestor
+  /// in the bubble phase. See [`CommonEvent::prevent_default`].
+  pub fn add_default_action(&mut self, action: impl FnOnce(&KeyboardEvent) + 'static) {
+    self.default_actions.push(action);
+  }
+
+  pub(crate) fn take_default_actions(&mut self) -> Vec<DefaultAction<KeyboardEvent>> {
+    self.default_actions.take()
+  }
 }
 
 impl_common_event_deref!(KeyboardEvent);
@@ -35,7 +48,14 @@ impl KeyboardEvent {
     wnd: &Window, id: WidgetId, physical_key: PhysicalKey, key: VirtualKey, is_repeat: bool,
     location: KeyLocation,
   ) -> Self {
-    Self { physical_key, key, is_repeat, location, common: CommonEvent::new(id, wnd.tree) }
+    Self {
+      physical_key,
+      key,
+      is_repeat,
+      location,
+      common: CommonEvent::new(id, wnd.tree),
+      default_actions: <_>::default(),
+    }
   }
 }
 
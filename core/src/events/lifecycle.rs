@@ -1,8 +1,26 @@
+use ribir_geom::Size;
+
 use super::*;
 
 /// The event fired when the widget is mounted, performed layout or disposed.
 pub type LifecycleEvent = CommonEvent;
 
+/// The layout of a widget changed from `old` to `new` since the last frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutChangedEvent<T> {
+  pub old: T,
+  pub new: T,
+}
+
+/// The event fired when the layout size of the widget changed, see
+/// [`FatObj::on_size_changed`](crate::prelude::FatObj::on_size_changed).
+pub type SizeChangedEvent = LayoutChangedEvent<Size>;
+
+/// The event fired when the position of the widget relative to its parent
+/// changed, see
+/// [`FatObj::on_position_changed`](crate::prelude::FatObj::on_position_changed).
+pub type PositionChangedEvent = LayoutChangedEvent<Point>;
+
 #[cfg(test)]
 mod tests {
   use std::collections::HashSet;
@@ -126,4 +144,48 @@ mod tests {
     assert_eq!(mounted_ids.len(), 3);
     assert_eq!(&mounted_ids, &*c_disposed.read());
   }
+
+  #[test]
+  fn size_and_position_changed() {
+    reset_test_env!();
+
+    let (size, w_size) = split_value(Size::new(50., 50.));
+    let sizes = Stateful::new(vec![]);
+    let positions = Stateful::new(vec![]);
+    let (c_sizes, c_positions) = (sizes.clone_reader(), positions.clone_reader());
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox {
+          size: pipe!(*$size),
+          on_size_changed: move |e| $sizes.write().push(*e),
+        }
+        @MockBox {
+          size: Size::new(10., 10.),
+          on_position_changed: move |e| $positions.write().push(*e),
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+    assert!(c_sizes.read().is_empty());
+    assert!(c_positions.read().is_empty());
+
+    *w_size.write() = Size::new(80., 60.);
+    wnd.draw_frame();
+    assert_eq!(
+      &**c_sizes.read(),
+      [SizeChangedEvent { old: Size::new(50., 50.), new: Size::new(80., 60.) }]
+    );
+    assert_eq!(
+      &**c_positions.read(),
+      [PositionChangedEvent { old: Point::new(50., 0.), new: Point::new(80., 0.) }]
+    );
+
+    // Layout again with the same result.
+    *w_size.write() = Size::new(80., 60.);
+    wnd.draw_frame();
+    assert_eq!(c_sizes.read().len(), 1);
+    assert_eq!(c_positions.read().len(), 1);
+  }
 }
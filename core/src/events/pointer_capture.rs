@@ -0,0 +1,224 @@
+use crate::{prelude::*, window::DelayEvent};
+
+impl CommonEvent {
+  /// Capture the `pointer` to the current target of the event, such as to
+  /// drag the thumb of a slider. Like the `setPointerCapture` of the web.
+  /// Reference: <https://developer.mozilla.org/en-US/docs/Web/API/Element/setPointerCapture>
+  ///
+  /// While the pointer is captured, its events go to the widget without a hit
+  /// test, even if the pointer leaves the widget, and bubble from it. The
+  /// capture is released when the pointer is released or canceled, or by
+  /// [`CommonEvent::release_pointer_capture`].
+  ///
+  /// It does nothing if no button of the pointer is pressed. The widget
+  /// receives an `on_got_pointer_capture` event when it captures the pointer,
+  /// and an `on_lost_pointer_capture` event when it loses the capture.
+  pub fn set_pointer_capture(&self, pointer: PointerId) {
+    if !self.mouse_buttons().is_empty() {
+      self
+        .window()
+        .set_pointer_capture(pointer, self.current_target());
+    }
+  }
+
+  /// Release the `pointer` if the current target of the event captures it.
+  pub fn release_pointer_capture(&self, pointer: PointerId) {
+    let wnd = self.window();
+    if wnd.pointer_capture_widget(pointer) == Some(self.current_target()) {
+      wnd.release_pointer_capture(pointer);
+    }
+  }
+
+  /// Whether the current target of the event captures the `pointer`.
+  pub fn has_pointer_capture(&self, pointer: PointerId) -> bool {
+    self.window().pointer_capture_widget(pointer) == Some(self.current_target())
+  }
+}
+
+impl Window {
+  /// The widget that captures the `pointer`, see
+  /// [`CommonEvent::set_pointer_capture`].
+  pub fn pointer_capture_widget(&self, pointer: PointerId) -> Option<WidgetId> {
+    self
+      .pointer_captures
+      .borrow()
+      .get(&pointer)
+      .copied()
+      .filter(|id| !id.is_dropped(self.tree()))
+  }
+
+  pub(crate) fn set_pointer_capture(&self, pointer: PointerId, id: WidgetId) {
+    let old = self
+      .pointer_captures
+      .borrow_mut()
+      .insert(pointer, id);
+    if old != Some(id) {
+      self.lose_pointer_capture(old);
+      self.add_delay_event(DelayEvent::PointerCaptureChange { id, captured: true });
+    }
+  }
+
+  /// Release the `pointer`, return if it was captured.
+  pub(crate) fn release_pointer_capture(&self, pointer: PointerId) -> bool {
+    let old = self
+      .pointer_captures
+      .borrow_mut()
+      .remove(&pointer);
+    self.lose_pointer_capture(old);
+    old.is_some()
+  }
+
+  fn lose_pointer_capture(&self, old: Option<WidgetId>) {
+    if let Some(id) = old {
+      if !id.is_dropped(self.tree()) {
+        self.add_delay_event(DelayEvent::PointerCaptureChange { id, captured: false });
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  #[test]
+  fn captured_pointer_leaves_the_widget() {
+    reset_test_env!();
+
+    let logs = Stateful::new(vec![]);
+    let w_logs = logs.clone_writer();
+    let log = move |msg: &'static str| {
+      let w_logs = w_logs.clone_writer();
+      move |_: &mut PointerEvent| w_logs.write().push(msg)
+    };
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox {
+          size: Size::new(50., 50.),
+          on_pointer_down: move |e| e.set_pointer_capture(e.id),
+          on_got_pointer_capture: log("got"),
+          on_lost_pointer_capture: log("lost"),
+          on_pointer_move: log("thumb move"),
+          on_pointer_up: log("thumb up"),
+        }
+        @MockBox {
+          size: Size::new(50., 50.),
+          on_pointer_enter: log("other enter"),
+          on_pointer_move: log("other move"),
+          on_pointer_up: log("other up"),
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |wnd: &TestWindow, x: f64| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, 10.).into() });
+      wnd.run_frame_tasks();
+    };
+    move_to(&wnd, 10.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    logs.write().clear();
+
+    // The moves and the release over the other widget go to the captured one.
+    move_to(&wnd, 70.);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert_eq!(*logs.read(), ["thumb move", "thumb up", "lost", "other enter"]);
+
+    // The capture is released with the pointer.
+    logs.write().clear();
+    move_to(&wnd, 80.);
+    assert_eq!(*logs.read(), ["other move"]);
+  }
+
+  #[test]
+  fn release_capture_by_handler() {
+    reset_test_env!();
+
+    let logs = Stateful::new(vec![]);
+    let w_logs = logs.clone_writer();
+    let log = move |msg: &'static str| {
+      let w_logs = w_logs.clone_writer();
+      move |_: &mut PointerEvent| w_logs.write().push(msg)
+    };
+    let w = fn_widget! {
+      let move_log = log("move");
+      @MockBox {
+        size: Size::new(50., 50.),
+        on_pointer_down: move |e| {
+          e.set_pointer_capture(e.id);
+          assert!(e.has_pointer_capture(e.id));
+        },
+        on_pointer_move: move |e| {
+          move_log(e);
+          e.release_pointer_capture(e.id);
+        },
+        on_got_pointer_capture: log("got"),
+        on_lost_pointer_capture: log("lost"),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |wnd: &TestWindow, x: f64| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, 10.).into() });
+      wnd.run_frame_tasks();
+    };
+    move_to(&wnd, 10.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    move_to(&wnd, 70.);
+    // Not captured anymore, the pointer is out of the widget.
+    move_to(&wnd, 80.);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert_eq!(*logs.read(), ["move", "got", "move", "lost"]);
+  }
+
+  #[test]
+  fn capture_is_kept_per_pointer() {
+    reset_test_env!();
+
+    let logs = Stateful::new(vec![]);
+    let w_logs = logs.clone_writer();
+    let log = move |msg: &'static str| {
+      let w_logs = w_logs.clone_writer();
+      move |_: &mut PointerEvent| w_logs.write().push(msg)
+    };
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(50., 50.), on_pointer_move: log("thumb move") }
+        @MockBox { size: Size::new(50., 50.), on_pointer_move: log("other move") }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+    let thumb = wnd
+      .tree()
+      .content_root()
+      .first_child(wnd.tree())
+      .unwrap();
+
+    // Another pointer captured by the thumb doesn't take the current pointer.
+    let other = PointerId(1);
+    wnd.set_pointer_capture(other, thumb);
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (70., 10.).into() });
+    wnd.run_frame_tasks();
+    assert_eq!(*logs.read(), ["other move"]);
+    assert_eq!(wnd.pointer_capture_widget(other), Some(thumb));
+    assert_eq!(wnd.pointer_capture_widget(PointerId(0)), None);
+
+    assert!(wnd.release_pointer_capture(other));
+    assert!(!wnd.release_pointer_capture(other));
+  }
+}
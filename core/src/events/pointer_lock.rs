@@ -0,0 +1,174 @@
+use crate::{impl_common_event_deref, prelude::*, window::DelayEvent};
+
+/// The event fired to a widget when it locks the pointer or loses the lock,
+/// see [`PointerEvent::request_pointer_lock`].
+#[derive(Debug)]
+pub struct PointerLockEvent {
+  /// Whether the widget holds the pointer lock now.
+  pub locked: bool,
+  pub common: CommonEvent,
+}
+
+impl_common_event_deref!(PointerLockEvent);
+
+impl PointerLockEvent {
+  #[inline]
+  pub fn new(locked: bool, id: WidgetId, wnd: &Window) -> Self {
+    Self { locked, common: CommonEvent::new(id, wnd.tree) }
+  }
+}
+
+impl PointerEvent {
+  /// Lock the pointer to the current target of the event and hide the cursor,
+  /// such as to orbit the camera of a 3D view. Return `false` if another
+  /// widget holds the lock.
+  ///
+  /// While the pointer is locked, all the pointer events go to the widget, and
+  /// its pointer move events carry the raw movement of the mouse, see
+  /// [`PointerEvent::movement`]. The lock exits if the window loses the focus,
+  /// `Escape` is pressed or [`PointerEvent::exit_pointer_lock`] is called.
+  pub fn request_pointer_lock(&self) -> bool { self.window().lock_pointer(self.current_target()) }
+
+  /// Exit the pointer lock if it's held, and show the cursor again.
+  pub fn exit_pointer_lock(&self) { self.window().exit_pointer_lock(); }
+}
+
+impl Window {
+  /// The widget that holds the pointer lock, see
+  /// [`PointerEvent::request_pointer_lock`].
+  pub fn pointer_lock_widget(&self) -> Option<WidgetId> { self.pointer_lock.get() }
+
+  pub(crate) fn lock_pointer(&self, id: WidgetId) -> bool {
+    match self.pointer_lock.get() {
+      Some(locked) => locked == id,
+      None => {
+        self.pointer_lock.set(Some(id));
+        self
+          .shell_wnd()
+          .borrow_mut()
+          .set_pointer_locked(true);
+        self.add_delay_event(DelayEvent::PointerLockChange { id, locked: true });
+        true
+      }
+    }
+  }
+
+  /// Exit the pointer lock if it's held, and show the cursor again.
+  pub fn exit_pointer_lock(&self) {
+    if let Some(id) = self.pointer_lock.take() {
+      self
+        .shell_wnd()
+        .borrow_mut()
+        .set_pointer_locked(false);
+      if !id.is_dropped(self.tree()) {
+        self.add_delay_event(DelayEvent::PointerLockChange { id, locked: false });
+      }
+    }
+  }
+
+  /// Process the raw movement of the mouse, the platform backends call it
+  /// while the pointer is locked. The movement is in the unit of the device.
+  pub fn process_mouse_motion(&self, movement: Vector) {
+    let Some(id) = self.pointer_lock.get() else { return };
+    if id.is_dropped(self.tree()) {
+      self.exit_pointer_lock();
+      return;
+    }
+
+    let mut emitter = self.delay_emitter.borrow_mut();
+    match emitter.back_mut() {
+      Some(DelayEvent::LockedPointerMove { id: last, movement: sum }) if *last == id => {
+        *sum += movement
+      }
+      _ => emitter.push_back(DelayEvent::LockedPointerMove { id, movement }),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn pointer_locks(wnd: &TestWindow) -> Vec<bool> {
+    wnd
+      .shell_wnd()
+      .borrow()
+      .as_any()
+      .downcast_ref::<TestShellWindow>()
+      .unwrap()
+      .pointer_locks
+      .clone()
+  }
+
+  #[test]
+  fn locked_moves_go_to_the_locking_widget() {
+    reset_test_env!();
+
+    let viewport: Rc<RefCell<Vec<Option<Vector>>>> = <_>::default();
+    let other: Rc<RefCell<Vec<Option<Vector>>>> = <_>::default();
+    let changes: Rc<RefCell<Vec<bool>>> = <_>::default();
+    let (c_viewport, c_other, c_changes) = (viewport.clone(), other.clone(), changes.clone());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let (viewport, other, changes) = (c_viewport.clone(), c_other.clone(), c_changes.clone());
+        @MockMulti {
+          @MockBox {
+            size: Size::new(50., 50.),
+            on_pointer_down: move |e| { e.request_pointer_lock(); },
+            on_pointer_move: move |e| viewport.borrow_mut().push(e.movement()),
+            on_pointer_lock_change: move |e| changes.borrow_mut().push(e.locked),
+          }
+          @MockBox {
+            size: Size::new(50., 50.),
+            on_pointer_move: move |e| other.borrow_mut().push(e.movement()),
+          }
+        }
+      },
+      Size::new(100., 50.),
+    );
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (10., 10.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert_eq!(pointer_locks(&wnd), [true]);
+    assert_eq!(&*changes.borrow(), &[true]);
+    viewport.borrow_mut().clear();
+
+    wnd.process_mouse_motion(Vector::new(3., 1.));
+    wnd.process_mouse_motion(Vector::new(2., -1.));
+    // The absolute position is ignored while the pointer is locked.
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (70., 10.).into() });
+    wnd.run_frame_tasks();
+    assert_eq!(&*viewport.borrow(), &[Some(Vector::new(5., 0.))]);
+    assert!(other.borrow().is_empty());
+
+    wnd.processes_keyboard_event(
+      PhysicalKey::Code(KeyCode::Escape),
+      VirtualKey::Named(NamedKey::Escape),
+      false,
+      KeyLocation::Standard,
+      ElementState::Pressed,
+    );
+    wnd.run_frame_tasks();
+    assert_eq!(pointer_locks(&wnd), [true, false]);
+    assert_eq!(&*changes.borrow(), &[true, false]);
+    assert_eq!(wnd.pointer_lock_widget(), None);
+
+    // Lock again and exit by the API.
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert!(wnd.pointer_lock_widget().is_some());
+    wnd.exit_pointer_lock();
+    assert_eq!(pointer_locks(&wnd), [true, false, true, false]);
+  }
+}
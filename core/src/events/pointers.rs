@@ -1,8 +1,16 @@
+use ribir_geom::{Point, Vector};
+
 use super::CommonEvent;
-use crate::impl_common_event_deref;
+use crate::{impl_common_event_deref, ticker::Instant};
+mod drag;
+pub use drag::*;
 mod from_mouse;
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct PointerId(usize);
+mod from_pen;
+pub use from_pen::*;
+mod velocity_tracker;
+pub use velocity_tracker::*;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointerId(pub(crate) usize);
 
 /// The pointer is a hardware-agnostic device that can target a specific set of
 /// screen coordinates.
@@ -45,8 +53,28 @@ pub struct PointerEvent {
   /// Indicates if the pointer represents the primary pointer of this pointer
   /// type.
   pub is_primary: bool,
+  /// Indicates if the event is caused by the eraser end of a pen.
+  pub is_eraser: bool,
 
   pub common: CommonEvent,
+  coalesced_events: Vec<PointerSample>,
+  movement: Option<Vector>,
+}
+
+/// A sample of the pointer that moved between two frames.
+///
+/// The pointer may move many times between two frames, the framework only
+/// dispatches one pointer move event for them, and keeps every move as a sample
+/// you can read by [`PointerEvent::coalesced_events`]. Like the
+/// `getCoalescedEvents` of the web. Reference: <https://developer.mozilla.org/en-US/docs/Web/API/PointerEvent/getCoalescedEvents>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerSample {
+  /// The position of the pointer relative to the window.
+  pub position: Point,
+  /// The normalized pressure of the pointer, see [`PointerEvent::pressure`].
+  pub pressure: f32,
+  /// The time the pointer moved.
+  pub timestamp: Instant,
 }
 
 bitflags! {
@@ -75,13 +103,38 @@ pub enum PointerType {
   Touch,
 }
 
+impl PointerEvent {
+  /// The samples of the pointer moves coalesced into this event, in the order
+  /// they happened. The last one is where the pointer is now.
+  ///
+  /// Only the pointer move events have samples, it's empty for other events.
+  /// Use it when you need every position of the pointer, such as drawing a
+  /// stroke, rather than the position of the last frame.
+  pub fn coalesced_events(&self) -> &[PointerSample] { &self.coalesced_events }
+
+  pub(crate) fn with_coalesced_events(mut self, samples: Vec<PointerSample>) -> Self {
+    self.coalesced_events = samples;
+    self
+  }
+
+  /// The raw movement of the mouse since the last move event, in the unit of
+  /// the device. It's only available for the move events of the widget that
+  /// locks the pointer, see [`PointerEvent::request_pointer_lock`].
+  pub fn movement(&self) -> Option<Vector> { self.movement }
+
+  pub(crate) fn with_movement(mut self, movement: Vector) -> Self {
+    self.movement = Some(movement);
+    self
+  }
+}
+
 impl_common_event_deref!(PointerEvent);
 #[cfg(test)]
 mod tests {
 
   use winit::{
     dpi::LogicalPosition,
-    event::{DeviceId, ElementState, MouseButton, WindowEvent},
+    event::{DeviceId, ElementState, MouseButton, Touch, TouchPhase, WindowEvent},
   };
 
   use crate::{prelude::*, reset_test_env, test_helper::*};
@@ -137,4 +190,351 @@ mod tests {
     assert_eq!(*tap.read(), 2);
     assert!(*focused.read());
   }
+
+  #[test]
+  fn target_of_bubbled_tap() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(vec![]);
+    let (captures, w_captures) = split_value(vec![]);
+    let w = fn_widget! {
+      @MockMulti {
+        on_tap: move |e| {
+          let rect = e.ctx_of_target().box_rect().unwrap();
+          $w_taps.write().push((e.target(), e.current_target(), rect));
+        },
+        on_tap_capture: move |e| {
+          $w_captures.write().push((e.target(), e.current_target()));
+        },
+        @MockBox { size: Size::new(10., 10.) }
+        @MockBox { size: Size::new(20., 20.) }
+        @MockBox { size: Size::new(30., 30.) }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    tap_on(&wnd, 5., 5.);
+    tap_on(&wnd, 15., 5.);
+    tap_on(&wnd, 35., 5.);
+    wnd.draw_frame();
+
+    let tree = wnd.tree();
+    let parent = tree.content_root();
+    let children: Vec<_> = parent.children(tree).collect();
+    let taps = taps.read();
+    let captures = captures.read();
+    assert_eq!(taps.len(), 3);
+    for (i, child) in children.iter().enumerate() {
+      let (target, current, _) = taps[i];
+      assert_eq!(target, *child);
+      assert_eq!(current, parent);
+      assert_eq!(captures[i], (*child, parent));
+    }
+    assert_eq!(taps[0].2, Rect::new(Point::zero(), Size::new(10., 10.)));
+    assert_eq!(taps[1].2, Rect::new(Point::new(10., 0.), Size::new(20., 20.)));
+    assert_eq!(taps[2].2, Rect::new(Point::new(30., 0.), Size::new(30., 30.)));
+  }
+
+  #[test]
+  fn coalesce_moves_between_frames() {
+    reset_test_env!();
+
+    let (moves, w_moves) = split_value(vec![]);
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_pointer_move: move |e| {
+          let positions: Vec<_> = e.coalesced_events().iter().map(|s| s.position).collect();
+          $w_moves.write().push(positions);
+        },
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    for i in 0..10 {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: LogicalPosition::new(i as f32, 5.).to_physical(1.),
+      });
+    }
+    wnd.draw_frame();
+
+    let moves = moves.read();
+    assert_eq!(moves.len(), 1);
+    let expected: Vec<_> = (0..10)
+      .map(|i| Point::new(i as f32, 5.))
+      .collect();
+    assert_eq!(moves[0], expected);
+  }
+
+  #[test]
+  fn pen_hover_and_contact() {
+    reset_test_env!();
+
+    type Entry = (&'static str, PointerType, f32, f32, bool);
+    fn entry(name: &'static str, e: &PointerEvent) -> Entry {
+      (name, e.point_type.clone(), e.pressure, e.tilt_x, e.is_eraser)
+    }
+    let events = Stateful::new(Vec::<Entry>::new());
+    let c_events = events.clone_writer();
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_pointer_enter: move |e| $c_events.write().push(entry("enter", e)),
+        on_pointer_move: move |e| $c_events.write().push(entry("move", e)),
+        on_pointer_down: move |e| $c_events.write().push(entry("down", e)),
+        on_pointer_up: move |e| $c_events.write().push(entry("up", e)),
+        on_pointer_cancel: move |e| $c_events.write().push(entry("cancel", e)),
+        on_pointer_leave: move |e| $c_events.write().push(entry("leave", e)),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let packet = |state, pressure, is_eraser| PenPacket {
+      position: Point::new(10., 10.),
+      state,
+      pressure,
+      tilt_x: 30.,
+      tilt_y: -15.,
+      twist: 90.,
+      is_eraser,
+    };
+    let pen = PointerType::Pen;
+    wnd.process_pen_input(packet(PenState::Hover, 0.7, false));
+    wnd.draw_frame();
+    assert_eq!(
+      *events.read(),
+      [("enter", pen.clone(), 0., 30., false), ("move", pen.clone(), 0., 30., false)]
+    );
+
+    events.write().clear();
+    wnd.process_pen_input(packet(PenState::Contact, 0.7, true));
+    wnd.draw_frame();
+    assert_eq!(
+      *events.read(),
+      [("move", pen.clone(), 0.7, 30., true), ("down", pen.clone(), 0.7, 30., true)]
+    );
+
+    events.write().clear();
+    wnd.process_pen_input(packet(PenState::Hover, 0.7, true));
+    wnd.draw_frame();
+    assert_eq!(
+      *events.read(),
+      [("move", pen.clone(), 0., 30., true), ("up", pen.clone(), 0., 30., true)]
+    );
+
+    events.write().clear();
+    wnd.process_pen_input(packet(PenState::Leave, 0., false));
+    wnd.draw_frame();
+    assert_eq!(*events.read(), [("leave", pen.clone(), 0., 30., false)]);
+
+    // The pen leaves while it's touching the surface, the press is canceled.
+    wnd.process_pen_input(packet(PenState::Contact, 0.7, false));
+    wnd.draw_frame();
+    events.write().clear();
+    wnd.process_pen_input(packet(PenState::Leave, 0., false));
+    wnd.draw_frame();
+    assert_eq!(
+      *events.read(),
+      [("cancel", pen.clone(), 0., 30., false), ("leave", pen, 0., 30., false)]
+    );
+  }
+
+  #[test]
+  fn read_visual_state_in_handler() {
+    reset_test_env!();
+
+    let (seen, w_seen) = split_value((1., false, Point::zero()));
+    let w = fn_widget! {
+      let mut parent = @MockBox { size: Size::new(100., 100.) };
+      let opacity = part_writer!(&mut parent.opacity);
+      let fade = @Animate {
+        transition: Box::new(EasingTransition {
+          duration: Duration::from_millis(100),
+          easing: easing::LINEAR,
+        }),
+        state: opacity.clone_writer(),
+        from: 1.,
+      };
+      *opacity.write() = 0.3;
+      fade.run();
+
+      @$parent {
+        @MockBox {
+          size: Size::new(50., 50.),
+          transform: Transform::translation(10., 20.),
+          on_tap: move |e| {
+            let origin = e.global_transform().transform_point(Point::zero());
+            *$w_seen.write() = (e.effective_opacity(), e.is_visually_shown(), origin);
+          },
+        }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    wnd.advance_time(Duration::from_millis(200));
+
+    tap_on(&wnd, 15., 25.);
+    wnd.draw_frame();
+    let (opacity, shown, origin) = *seen.read();
+    assert!((opacity - 0.3).abs() < f32::EPSILON);
+    assert!(shown);
+    assert_eq!(origin, Point::new(10., 20.));
+  }
+
+  #[test]
+  fn hidden_ancestor_not_shown() {
+    reset_test_env!();
+
+    let (shown, w_shown) = split_value(vec![]);
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox {
+          size: Size::new(50., 50.),
+          visible: false,
+          @MockBox {
+            size: Size::new(10., 10.),
+            on_mounted: move |e| $w_shown.write().push(e.is_visually_shown()),
+          }
+        }
+        @MockBox {
+          size: Size::new(50., 50.),
+          opacity: 0.,
+          on_tap: move |e| $w_shown.write().push(e.is_visually_shown()),
+        }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    // The transparent widget is still hit, the hidden one takes no space.
+    tap_on(&wnd, 25., 25.);
+    wnd.draw_frame();
+
+    assert_eq!(*shown.read(), [false, false]);
+  }
+
+  fn touch(wnd: &Window, phase: TouchPhase, x: f32, y: f32) {
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::Touch(Touch {
+      device_id: unsafe { DeviceId::dummy() },
+      phase,
+      location: LogicalPosition::new(x, y).to_physical(1.),
+      force: None,
+      id: 0,
+    }));
+  }
+
+  fn long_press_wnd() -> (TestWindow, Stateful<Vec<(&'static str, PointerType)>>) {
+    let events = Stateful::new(vec![]);
+    let w_events = events.clone_writer();
+    let w = fn_widget! {
+      let w_events2 = w_events.clone_writer();
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_long_press: move |e| $w_events.write().push(("long press", e.point_type.clone())),
+        on_tap: move |e| $w_events2.write().push(("tap", e.point_type.clone())),
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    (wnd, events)
+  }
+
+  #[test]
+  fn long_press() {
+    reset_test_env!();
+
+    let (mut wnd, events) = long_press_wnd();
+    let device_id = unsafe { DeviceId::dummy() };
+    let hold = GestureSettings::default().long_press;
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: LogicalPosition::new(50., 50.).to_physical(1.),
+    });
+
+    // Released early, it's a tap.
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.draw_frame();
+    wnd.advance_time(hold / 2);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.advance_time(hold);
+    assert_eq!(*events.read(), [("tap", PointerType::Mouse)]);
+
+    // Held down, the release isn't a tap anymore.
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.draw_frame();
+    wnd.advance_time(hold);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+    assert_eq!(*events.read(), [("tap", PointerType::Mouse), ("long press", PointerType::Mouse)]);
+
+    // Moved farther than the slop, it's neither.
+    events.write().clear();
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: LogicalPosition::new(60., 50.).to_physical(1.),
+    });
+    wnd.draw_frame();
+    wnd.advance_time(hold);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+    assert!(events.read().is_empty());
+  }
+
+  #[test]
+  fn long_press_by_touch() {
+    reset_test_env!();
+
+    let (mut wnd, events) = long_press_wnd();
+    let hold = GestureSettings::default().long_press;
+
+    // A finger is allowed to move more than a mouse.
+    touch(&wnd, TouchPhase::Started, 50., 50.);
+    touch(&wnd, TouchPhase::Moved, 56., 50.);
+    wnd.draw_frame();
+    wnd.advance_time(hold);
+    touch(&wnd, TouchPhase::Ended, 56., 50.);
+    wnd.draw_frame();
+    assert_eq!(*events.read(), [("long press", PointerType::Touch)]);
+
+    events.write().clear();
+    touch(&wnd, TouchPhase::Started, 50., 50.);
+    touch(&wnd, TouchPhase::Ended, 50., 50.);
+    wnd.draw_frame();
+    assert_eq!(*events.read(), [("tap", PointerType::Touch)]);
+  }
+
+  #[test]
+  fn cancel_long_press() {
+    reset_test_env!();
+
+    let (events, w_events) = split_value(vec![]);
+    let w = fn_widget! {
+      let (w_events2, w_events3) = (w_events.clone_writer(), w_events.clone_writer());
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_long_press: move |_| $w_events.write().push("long press"),
+        on_tap: move |_| $w_events2.write().push("tap"),
+        on_pointer_cancel: move |_| $w_events3.write().push("cancel"),
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    touch(&wnd, TouchPhase::Started, 50., 50.);
+    touch(&wnd, TouchPhase::Cancelled, 50., 50.);
+    wnd.draw_frame();
+    wnd.advance_time(GestureSettings::default().long_press);
+    touch(&wnd, TouchPhase::Ended, 50., 50.);
+    wnd.draw_frame();
+    assert_eq!(*events.read(), ["cancel"]);
+  }
 }
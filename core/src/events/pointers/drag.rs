@@ -0,0 +1,85 @@
+use ribir_geom::Vector;
+
+use crate::{impl_common_event_deref, prelude::*};
+
+/// The event of a drag gesture, a pointer that is pressed on the widget and
+/// moves farther than the slop of [`GestureSettings`], so a tap never starts a
+/// drag.
+///
+/// A drag starts with a `DragStart`, follows the pointer by the `DragUpdate`
+/// even if it leaves the widget, and ends with a `DragEnd` when the pointer is
+/// released or canceled.
+#[derive(Debug)]
+pub struct DragEvent {
+  /// The movement of the pointer since the last drag event.
+  pub delta: Vector,
+  /// The movement of the pointer since it was pressed.
+  pub offset: Vector,
+  /// Whether the drag ended by a pointer cancel rather than a release.
+  pub canceled: bool,
+  pub common: CommonEvent,
+}
+
+impl_common_event_deref!(DragEvent);
+
+impl DragEvent {
+  #[inline]
+  pub fn new(delta: Vector, offset: Vector, id: WidgetId, wnd: &Window) -> Self {
+    Self { delta, offset, canceled: false, common: CommonEvent::new(id, wnd.tree) }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, Touch, TouchPhase, WindowEvent},
+  };
+
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  #[test]
+  fn drag_offset_and_cancel() {
+    reset_test_env!();
+
+    let (events, w_events) = split_value(vec![]);
+    let w = fn_widget! {
+      let (w_events2, w_events3) = (w_events.clone_writer(), w_events.clone_writer());
+      @MockBox {
+        size: Size::new(50., 50.),
+        on_drag_start: move |e| $w_events.write().push(("start", e.delta, e.offset, false)),
+        on_drag_update: move |e| $w_events2.write().push(("update", e.delta, e.offset, false)),
+        on_drag_end: move |e| $w_events3.write().push(("end", e.delta, e.offset, e.canceled)),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let touch = |phase, x: f32, y: f32| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::Touch(Touch {
+        device_id: unsafe { DeviceId::dummy() },
+        phase,
+        location: LogicalPosition::new(x, y).to_physical(1.),
+        force: None,
+        id: 0,
+      }));
+    };
+    touch(TouchPhase::Started, 10., 10.);
+    touch(TouchPhase::Moved, 25., 10.);
+    touch(TouchPhase::Moved, 25., 30.);
+    touch(TouchPhase::Cancelled, 25., 30.);
+    wnd.draw_frame();
+
+    let zero = Vector::zero();
+    assert_eq!(
+      *events.read(),
+      [
+        ("start", zero, zero, false),
+        ("update", Vector::new(15., 0.), Vector::new(15., 0.), false),
+        ("update", Vector::new(0., 20.), Vector::new(15., 20.), false),
+        ("end", zero, Vector::new(15., 20.), true),
+      ]
+    );
+  }
+}
@@ -1,28 +1,29 @@
 use winit::event::MouseButton;
 
-use super::PointerId;
 use crate::prelude::*;
 
 impl PointerEvent {
   pub(crate) fn from_mouse(target: WidgetId, wnd: &Window) -> Self {
-    let no_button = wnd
-      .dispatcher
-      .borrow()
-      .info
-      .mouse_buttons()
-      .is_empty();
-    PointerEvent {
-      // todo: we need to trace the pressed pointer, how to generate pointer id, by device + button?
-      id: PointerId(0),
+    let dispatcher = wnd.dispatcher.borrow();
+    let no_button = dispatcher.info.mouse_buttons().is_empty();
+    let e = PointerEvent {
+      id: dispatcher.info.pointer_id(),
       width: 1.0,
       height: 1.0,
       pressure: if no_button { 0. } else { 0.5 },
       tilt_x: 90.,
       tilt_y: 90.,
       twist: 0.,
-      point_type: PointerType::Mouse,
+      point_type: dispatcher.info.pointer_type(),
       is_primary: true,
+      is_eraser: false,
       common: CommonEvent::new(target, wnd.tree),
+      coalesced_events: vec![],
+      movement: None,
+    };
+    match dispatcher.info.pen() {
+      Some(pen) => e.with_pen(pen),
+      None => e,
     }
   }
 }
@@ -0,0 +1,59 @@
+use crate::prelude::*;
+
+/// A packet of the pen or stylus input reported by the platform.
+///
+/// The window doesn't read the pen from the platform by itself, a platform
+/// backend that supports the pen converts its tablet data to the packets and
+/// feeds them to [`Window::process_pen_input`]. The pen dispatches the same
+/// pointer events as the mouse, and the events carry the pressure, the tilt and
+/// the eraser state of the pen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PenPacket {
+  /// The position of the pen relative to the window, in logical pixels.
+  pub position: Point,
+  /// The state of the pen.
+  pub state: PenState,
+  /// The normalized pressure of the pen, it's ignored when the pen isn't
+  /// touching the surface.
+  pub pressure: f32,
+  /// The plane angle (in degrees, in the range of -90 to 90) between the Y–Z
+  /// plane and the plane containing both the pen axis and the Y axis.
+  pub tilt_x: f32,
+  /// The plane angle (in degrees, in the range of -90 to 90) between the X–Z
+  /// plane and the plane containing both the pen axis and the X axis.
+  pub tilt_y: f32,
+  /// The clockwise rotation of the pen around its major axis in degrees, in the
+  /// range of 0 to 359.
+  pub twist: f32,
+  /// If the eraser end of the pen is used.
+  pub is_eraser: bool,
+}
+
+/// The state of a pen relative to the surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PenState {
+  /// The pen is near the surface but not touching it.
+  Hover,
+  /// The pen is touching the surface.
+  Contact,
+  /// The pen leaves the detectable range of the surface.
+  Leave,
+}
+
+impl PenPacket {
+  /// The pressure of the pen, it's zero when the pen isn't touching the
+  /// surface.
+  pub fn pressure(&self) -> f32 { if self.state == PenState::Contact { self.pressure } else { 0. } }
+}
+
+impl PointerEvent {
+  pub(super) fn with_pen(mut self, pen: &PenPacket) -> Self {
+    self.pressure = pen.pressure();
+    self.tilt_x = pen.tilt_x;
+    self.tilt_y = pen.tilt_y;
+    self.twist = pen.twist;
+    self.point_type = PointerType::Pen;
+    self.is_eraser = pen.is_eraser;
+    self
+  }
+}
@@ -0,0 +1,63 @@
+use std::collections::VecDeque;
+
+use ribir_geom::{Point, Vector};
+
+use crate::ticker::{Duration, Instant};
+
+/// Estimates the velocity of a pointer from its recent positions, e.g. the
+/// velocity that a finger lifts at to fling a scrollable widget.
+///
+/// Only the samples in the last 100 milliseconds are counted, so a pointer that
+/// stops before it lifts has no velocity.
+#[derive(Debug, Clone, Default)]
+pub struct VelocityTracker {
+  samples: VecDeque<(Instant, Point)>,
+}
+
+impl VelocityTracker {
+  const HORIZON: Duration = Duration::from_millis(100);
+
+  /// Record the `position` of the pointer at the time `at`.
+  pub fn add_sample(&mut self, at: Instant, position: Point) {
+    self.samples.push_back((at, position));
+    while self
+      .samples
+      .front()
+      .is_some_and(|(t, _)| at.duration_since(*t) > Self::HORIZON)
+    {
+      self.samples.pop_front();
+    }
+  }
+
+  /// The velocity of the pointer in logical pixels per second.
+  pub fn velocity(&self) -> Vector {
+    let (Some((start, from)), Some((end, to))) = (self.samples.front(), self.samples.back()) else {
+      return Vector::zero();
+    };
+    let secs = end.duration_since(*start).as_secs_f32();
+    if secs > 0. { (*to - *from) / secs } else { Vector::zero() }
+  }
+
+  /// Forget all the samples.
+  pub fn reset(&mut self) { self.samples.clear(); }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn velocity_of_recent_samples() {
+    let start = Instant::now();
+    let mut tracker = VelocityTracker::default();
+    assert_eq!(tracker.velocity(), Vector::zero());
+
+    tracker.add_sample(start, Point::new(0., 0.));
+    tracker.add_sample(start + Duration::from_millis(50), Point::new(0., 50.));
+    assert_eq!(tracker.velocity(), Vector::new(0., 1000.));
+
+    // The pointer stays still long enough, the velocity drops to zero.
+    tracker.add_sample(start + Duration::from_millis(300), Point::new(0., 50.));
+    assert_eq!(tracker.velocity(), Vector::zero());
+  }
+}
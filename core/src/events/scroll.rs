@@ -0,0 +1,80 @@
+use crate::{impl_common_event_deref, prelude::*};
+
+/// The event fired when the scroll position or the scrollable extent of a
+/// scrollable widget changes.
+///
+/// It bubbles from the scrollable widget to its ancestors, but stops before
+/// the outer scrollable widget, so the listener only receives the scrolls of
+/// the nearest scrollable widget inside it.
+#[derive(Debug)]
+pub struct ScrollEvent {
+  /// The scroll position, the pixel displayed in the upper left.
+  pub offset: Point,
+  /// The max scroll position, see [`ScrollableWidget::max_scrollable`].
+  pub max_offset: Point,
+  pub common: CommonEvent,
+}
+
+impl_common_event_deref!(ScrollEvent);
+
+impl ScrollEvent {
+  #[inline]
+  pub fn new(offset: Point, max_offset: Point, id: WidgetId, wnd: &Window) -> Self {
+    Self { offset, max_offset, common: CommonEvent::new(id, wnd.tree) }
+  }
+
+  /// How far it can still scroll before reaching the end of each axis.
+  pub fn remaining(&self) -> Vector { self.max_offset - self.offset }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
+  use winit::event::{DeviceId, MouseScrollDelta, TouchPhase, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn scroll_not_bubble_to_outer_scrollable() {
+    reset_test_env!();
+
+    let inner: Rc<RefCell<Vec<(Point, Point)>>> = <_>::default();
+    let outer: Rc<RefCell<Vec<Point>>> = <_>::default();
+    let (c_inner, c_outer) = (inner.clone(), outer.clone());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let (inner, outer) = (c_inner.clone(), c_outer.clone());
+        @MockBox {
+          size: Size::new(100., 100.),
+          scrollable: Scrollable::Y,
+          on_scroll: move |e| outer.borrow_mut().push(e.offset),
+          @MockBox {
+            size: Size::new(100., 200.),
+            scrollable: Scrollable::Y,
+            on_scroll: move |e| inner.borrow_mut().push((e.offset, e.max_offset)),
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    // The scrollable extents are known after the first layout.
+    assert_eq!(inner.borrow().last(), Some(&(Point::zero(), Point::new(0., 100.))));
+    assert_eq!(outer.borrow().last(), Some(&Point::zero()));
+    let outer_cnt = outer.borrow().len();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::PixelDelta((0., -50.).into()),
+      phase: TouchPhase::Started,
+    });
+    wnd.draw_frame();
+
+    assert_eq!(inner.borrow().last(), Some(&(Point::new(0., 50.), Point::new(0., 100.))));
+    assert_eq!(outer.borrow().len(), outer_cnt);
+  }
+}
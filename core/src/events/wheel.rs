@@ -1,18 +1,43 @@
 use crate::{impl_common_event_deref, prelude::*};
 
+/// The event of a mouse wheel or a trackpad scroll. It bubbles from the widget
+/// under the pointer, the modifiers pressed with it, such as the `Ctrl` to
+/// zoom, are read from the [`CommonEvent`].
 #[derive(Debug)]
 pub struct WheelEvent {
+  /// The horizontal delta in logical pixels.
   pub delta_x: f32,
+  /// The vertical delta in logical pixels.
   pub delta_y: f32,
+  /// The unit that the device reports the delta in.
+  pub delta_mode: WheelDeltaMode,
   pub common: CommonEvent,
 }
 
+/// The unit that a device reports the delta of a wheel in. The delta of the
+/// [`WheelEvent`] is always in logical pixels, a line is mapped to
+/// [`WheelDeltaMode::LINE_PIXELS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WheelDeltaMode {
+  /// The notches of a mouse wheel.
+  Line,
+  /// The precise delta of a trackpad.
+  Pixel,
+}
+
 impl_common_event_deref!(WheelEvent);
 
+impl WheelDeltaMode {
+  /// The logical pixels that a line of the wheel scrolls.
+  pub const LINE_PIXELS: f32 = 16.;
+}
+
 impl WheelEvent {
   #[inline]
-  pub fn new(delta_x: f32, delta_y: f32, id: WidgetId, wnd: &Window) -> Self {
-    Self { delta_x, delta_y, common: CommonEvent::new(id, wnd.tree) }
+  pub fn new(
+    delta_x: f32, delta_y: f32, delta_mode: WheelDeltaMode, id: WidgetId, wnd: &Window,
+  ) -> Self {
+    Self { delta_x, delta_y, delta_mode, common: CommonEvent::new(id, wnd.tree) }
   }
 }
 
@@ -72,4 +97,114 @@ mod tests {
     assert_eq!(*source_receive_for_capture.borrow(), (1., 1.));
     assert_eq!(*event_order.borrow(), ["capture", "bubble"]);
   }
+
+  #[test]
+  fn prevent_scroll() {
+    reset_test_env!();
+
+    let prevent = Stateful::new(true);
+    let c_prevent = prevent.clone_watcher();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockBox {
+          size: Size::new(100., 500.),
+          scrollable: Scrollable::Y,
+          @MockBox {
+            size: Size::new(100., 500.),
+            on_wheel: move |e| if *$c_prevent {
+              e.prevent_default();
+            },
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    let scroll_by = |wnd: &mut TestWindow| {
+      let device_id = unsafe { DeviceId::dummy() };
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: (50., 50.).into(),
+      });
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::MouseWheel {
+        device_id,
+        delta: MouseScrollDelta::PixelDelta((0., -50.).into()),
+        phase: TouchPhase::Started,
+      });
+      wnd.draw_frame();
+    };
+    let pos = |wnd: &TestWindow| {
+      let tree = wnd.tree();
+      let scroll = tree
+        .content_root()
+        .query_ref::<ScrollableWidget>(tree)
+        .unwrap();
+      scroll.get_scroll_pos()
+    };
+
+    scroll_by(&mut wnd);
+    assert_eq!(pos(&wnd), Point::zero());
+
+    *prevent.write() = false;
+    scroll_by(&mut wnd);
+    assert_eq!(pos(&wnd), Point::new(0., 50.));
+  }
+
+  #[test]
+  fn bubble_line_delta_with_modifiers() {
+    reset_test_env!();
+
+    let logs = Stateful::new(vec![]);
+    let w_logs = logs.clone_writer();
+    let log = move |name: &'static str, stop: bool| {
+      let w_logs = w_logs.clone_writer();
+      move |e: &mut WheelEvent| {
+        w_logs
+          .write()
+          .push((name, e.delta_x, e.delta_y, e.delta_mode, e.with_ctrl_key()));
+        if stop {
+          e.stop_propagation();
+        }
+      }
+    };
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_wheel: log("outer", false),
+        @MockBox {
+          size: Size::new(100., 100.),
+          on_wheel: log("middle", true),
+          @MockBox {
+            size: Size::new(100., 100.),
+            on_wheel: log("inner", false),
+          }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (10., 10.).into() });
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::ModifiersChanged(ModifiersState::CONTROL.into()));
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::LineDelta(1., -2.),
+      phase: TouchPhase::Moved,
+    });
+    wnd.run_frame_tasks();
+
+    assert_eq!(
+      *logs.read(),
+      [
+        ("inner", 16., -32., WheelDeltaMode::Line, true),
+        ("middle", 16., -32., WheelDeltaMode::Line, true)
+      ]
+    );
+  }
 }
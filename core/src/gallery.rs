@@ -0,0 +1,225 @@
+//! Render the registered widgets to PNGs for the visual QA.
+//!
+//! Register the widgets by names, then a [`GalleryRunner`] renders every one
+//! of them in the sizes and the themes it's given. The images are written to a
+//! directory with a `manifest.json` that lists the name, the size and the hash
+//! of every image, so the tools can compare the runs of the gallery.
+//!
+//! ```ignore
+//! use ribir::prelude::*;
+//! use ribir_dev_helper::wgpu_render_commands;
+//!
+//! gallery::register("button/filled", fn_widget! { @FilledButton { @{ "Filled" } } });
+//! gallery::GalleryRunner::new("./gallery")
+//!   .with_sizes([Size::new(200., 100.)])
+//!   .run(|frame| {
+//!     let viewport = frame.viewport.to_i32().cast_unit();
+//!     wgpu_render_commands(&frame.commands, viewport, frame.surface)
+//!   })
+//!   .unwrap();
+//! ```
+//!
+//! It needs the `png` feature.
+
+use std::{cell::RefCell, io::Error, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{prelude::*, test_helper::*};
+
+/// The file of the manifest in the directory of a gallery run.
+pub const MANIFEST: &str = "manifest.json";
+
+thread_local! {
+  static GALLERY: RefCell<Vec<(CowArc<str>, GenWidget)>> = const { RefCell::new(vec![]) };
+}
+
+/// Register the `widget` to the gallery by its `name`, the one registered
+/// before with the same name is replaced.
+///
+/// The name is also the directory of its images, a name like `button/filled`
+/// puts its images in `button/filled/` of the gallery.
+pub fn register(name: impl Into<CowArc<str>>, widget: impl Into<GenWidget>) {
+  let name = name.into();
+  let widget = widget.into();
+  GALLERY.with_borrow_mut(|gallery| match gallery.iter_mut().find(|(n, _)| *n == name) {
+    Some(item) => item.1 = widget,
+    None => gallery.push((name, widget)),
+  });
+}
+
+/// The names of the registered widgets, in the order they're registered.
+pub fn names() -> Vec<CowArc<str>> {
+  GALLERY.with_borrow(|gallery| {
+    gallery
+      .iter()
+      .map(|(name, _)| name.clone())
+      .collect()
+  })
+}
+
+/// An image of a gallery run, the manifest is a list of them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GalleryEntry {
+  /// The name of the widget.
+  pub name: String,
+  /// The name of the theme it's rendered in.
+  pub theme: String,
+  pub width: u32,
+  pub height: u32,
+  /// The path of the image relative to the directory of the run.
+  pub file: String,
+  /// The hex of the hash of the image pixels, the same pixels have the same
+  /// hash.
+  pub hash: String,
+}
+
+/// Render every registered widget in every size and every theme, see the
+/// [module](self) for more details.
+pub struct GalleryRunner {
+  dir: PathBuf,
+  sizes: Vec<Size>,
+  themes: Vec<NamedTheme>,
+}
+
+type NamedTheme = (CowArc<str>, Box<dyn Fn() -> Theme>);
+
+impl GalleryRunner {
+  /// A runner that writes the images to the `dir`. It renders the widgets in
+  /// 400x400 and the default theme if no sizes or themes are given.
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self { dir: dir.into(), sizes: vec![], themes: vec![] }
+  }
+
+  /// Render the widgets in the window of the `sizes`.
+  pub fn with_sizes(mut self, sizes: impl IntoIterator<Item = Size>) -> Self {
+    self.sizes.extend(sizes);
+    self
+  }
+
+  /// Render the widgets in the theme that the `theme` creates, `name` is the
+  /// name of the theme in the file names and the manifest.
+  pub fn with_theme(
+    mut self, name: impl Into<CowArc<str>>, theme: impl Fn() -> Theme + 'static,
+  ) -> Self {
+    self.themes.push((name.into(), Box::new(theme)));
+    self
+  }
+
+  /// Render the widgets by the `render` function that turns the frame of a
+  /// window into an image, such as `ribir_dev_helper::wgpu_render_commands`.
+  /// The images and the manifest are written to the directory, and the
+  /// entries of the manifest are returned.
+  ///
+  /// The theme of the application is changed to the themes of the runner.
+  pub fn run(
+    &self, mut render: impl FnMut(Frame) -> PixelImage,
+  ) -> Result<Vec<GalleryEntry>, Error> {
+    let default_sizes = [Size::new(400., 400.)];
+    let sizes = if self.sizes.is_empty() { &default_sizes[..] } else { &self.sizes };
+    let default_theme: [NamedTheme; 1] = [("default".into(), Box::new(Theme::default))];
+    let themes = if self.themes.is_empty() { &default_theme[..] } else { &self.themes };
+    let gallery = GALLERY.with_borrow(|gallery| gallery.clone());
+
+    let mut entries = vec![];
+    for (theme_name, theme) in themes {
+      AppCtx::set_app_theme(theme());
+      let theme_name = &**theme_name;
+      for (name, widget) in gallery.iter() {
+        let name = &**name;
+        for size in sizes {
+          let mut wnd = TestWindow::new_with_size(widget.clone(), *size);
+          wnd.draw_frame();
+          let frame = wnd
+            .take_last_frame()
+            .expect("A new window always paints its first frame.");
+          let img = render(frame);
+
+          let file = format!("{name}/{theme_name}_{}x{}.png", size.width, size.height);
+          let path = self.dir.join(&file);
+          if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+          }
+          let mut writer = std::io::BufWriter::new(std::fs::File::create(&path)?);
+          img
+            .write_as_png(&mut writer)
+            .map_err(|e| Error::other(e.to_string()))?;
+
+          entries.push(GalleryEntry {
+            name: name.to_string(),
+            theme: theme_name.to_string(),
+            width: img.width(),
+            height: img.height(),
+            file,
+            hash: image_hash(&img),
+          });
+        }
+      }
+    }
+
+    let manifest = serde_json::to_string_pretty(&entries)?;
+    std::fs::write(self.dir.join(MANIFEST), manifest)?;
+    Ok(entries)
+  }
+}
+
+/// The hash of the pixels of the `img` in the manifest.
+pub fn image_hash(img: &PixelImage) -> String {
+  let mut hasher = blake3::Hasher::new();
+  hasher.update(&img.width().to_le_bytes());
+  hasher.update(&img.height().to_le_bytes());
+  hasher.update(img.pixel_bytes());
+  hasher.finalize().to_hex().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_painter::image::ColorFormat;
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::MockBox};
+
+  fn fake_render(frame: Frame) -> PixelImage {
+    let size = frame.viewport.size.to_i32();
+    let fill = frame.commands.len() as u8;
+    let data = vec![fill; (size.width * size.height * 4) as usize];
+    PixelImage::new(data.into(), size.width as u32, size.height as u32, ColorFormat::Rgba8)
+  }
+
+  #[test]
+  fn render_gallery() {
+    reset_test_env!();
+
+    register("box/small", fn_widget! { @MockBox { size: Size::new(10., 10.) } });
+    register("box/large", fn_widget! { @MockBox { size: Size::new(50., 50.) } });
+    assert_eq!(names(), [CowArc::from("box/small"), CowArc::from("box/large")]);
+
+    let dir = std::env::temp_dir().join("ribir_render_gallery");
+    let _ = std::fs::remove_dir_all(&dir);
+    let entries = GalleryRunner::new(&dir)
+      .with_sizes([Size::new(20., 30.), Size::new(40., 40.)])
+      .run(fake_render)
+      .unwrap();
+
+    let files: Vec<_> = entries.iter().map(|e| e.file.as_str()).collect();
+    assert_eq!(
+      files,
+      [
+        "box/small/default_20x30.png",
+        "box/small/default_40x40.png",
+        "box/large/default_20x30.png",
+        "box/large/default_40x40.png",
+      ]
+    );
+
+    let manifest = std::fs::read_to_string(dir.join(MANIFEST)).unwrap();
+    let manifest: Vec<GalleryEntry> = serde_json::from_str(&manifest).unwrap();
+    assert_eq!(manifest, entries);
+    for e in &manifest {
+      let img = PixelImage::from_png(&std::fs::read(dir.join(&e.file)).unwrap());
+      assert_eq!((img.width(), img.height()), (e.width, e.height));
+      assert_eq!(image_hash(&img), e.hash);
+    }
+    assert_eq!((manifest[0].width, manifest[0].height), (20, 30));
+  }
+}
@@ -0,0 +1,260 @@
+//! Run the work that can wait in the idle time between the frames, such as
+//! preparing the content of a screen that's likely shown next, so it's ready
+//! when it's shown instead of janking the frame that shows it. See
+//! [`AppCtx::spawn_idle`].
+
+use std::{cell::Cell, rc::Rc};
+
+use crate::{
+  context::AppCtx,
+  prelude::{Instant, WidgetCtx},
+  widget_tree::WidgetId,
+  window::WindowId,
+};
+
+/// What an idle task does after a chunk of its work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleStep {
+  /// Run the next chunk in the idle time.
+  Continue,
+  /// The work is done, drop the task.
+  Done,
+}
+
+/// A piece of work that runs in chunks in the idle time. Every chunk should
+/// be short, the queue only checks its budget between the chunks.
+pub struct IdleTask {
+  priority: i32,
+  owner: Option<(WindowId, WidgetId)>,
+  chunk: Box<dyn FnMut() -> IdleStep>,
+}
+
+/// The handle of a spawned [`IdleTask`], to cancel it.
+#[derive(Clone)]
+pub struct IdleTaskHandle(Rc<Cell<bool>>);
+
+struct IdleEntry {
+  task: IdleTask,
+  /// The order of spawn, the tasks of the same priority run in it.
+  seq: u64,
+  finished: Rc<Cell<bool>>,
+}
+
+/// The idle tasks of the application.
+#[derive(Default)]
+pub(crate) struct IdleQueue {
+  entries: Vec<IdleEntry>,
+  next_seq: u64,
+}
+
+impl IdleTask {
+  /// A task that runs the `chunk` again and again in the idle time until it
+  /// returns [`IdleStep::Done`].
+  pub fn new(chunk: impl FnMut() -> IdleStep + 'static) -> Self {
+    Self { priority: 0, owner: None, chunk: Box::new(chunk) }
+  }
+
+  /// The tasks of a higher priority run first, it's `0` by default.
+  pub fn with_priority(mut self, priority: i32) -> Self {
+    self.priority = priority;
+    self
+  }
+
+  /// Drop the task once the widget of the `ctx` is disposed, such as the
+  /// screen that it prepares the content for.
+  pub fn owned_by(mut self, ctx: &impl WidgetCtx) -> Self {
+    self.owner = Some((ctx.window().id(), ctx.widget_id()));
+    self
+  }
+
+  fn is_orphan(&self) -> bool {
+    let Some((wnd, id)) = self.owner else { return false };
+    match AppCtx::get_window(wnd) {
+      Some(wnd) => id.is_dropped(wnd.tree()),
+      None => true,
+    }
+  }
+}
+
+impl IdleTaskHandle {
+  /// Drop the task before it runs its next chunk.
+  pub fn cancel(&self) { self.0.set(true); }
+
+  /// Whether the task is canceled, disposed with its owner or done.
+  pub fn is_finished(&self) -> bool { self.0.get() }
+}
+
+impl IdleQueue {
+  pub(crate) fn push(&mut self, task: IdleTask) -> IdleTaskHandle {
+    let finished = Rc::new(Cell::new(false));
+    let seq = self.next_seq;
+    self.next_seq += 1;
+    self
+      .entries
+      .push(IdleEntry { task, seq, finished: finished.clone() });
+    IdleTaskHandle(finished)
+  }
+
+  /// Drop the canceled tasks and the tasks whose owner is disposed, then take
+  /// the next task to run.
+  fn pop(&mut self) -> Option<IdleEntry> {
+    self.entries.retain(|e| {
+      let drop = e.finished.get() || e.task.is_orphan();
+      e.finished.set(drop);
+      !drop
+    });
+    let idx = self
+      .entries
+      .iter()
+      .enumerate()
+      .max_by_key(|(_, e)| (e.task.priority, std::cmp::Reverse(e.seq)))?
+      .0;
+    Some(self.entries.swap_remove(idx))
+  }
+
+  pub(crate) fn is_empty(&self) -> bool { self.entries.is_empty() }
+}
+
+impl AppCtx {
+  /// Spawn a task that runs only in the idle time of the application, the
+  /// time that no window waits to draw a frame.
+  pub fn spawn_idle(task: IdleTask) -> IdleTaskHandle {
+    Self::shared().idle_tasks.borrow_mut().push(task)
+  }
+
+  /// Run the chunks of the idle tasks until the `deadline`, return if any task
+  /// has more work to do. The event loop calls it when all the windows are
+  /// idle.
+  pub fn run_idle_tasks(deadline: Instant) -> bool {
+    let queue = &Self::shared().idle_tasks;
+    while crate::clock::now() < deadline {
+      // Not borrow the queue while the chunk runs, it may spawn new tasks.
+      let Some(mut entry) = queue.borrow_mut().pop() else { break };
+      if (entry.task.chunk)() == IdleStep::Continue && !entry.finished.get() {
+        queue.borrow_mut().entries.push(entry);
+      } else {
+        entry.finished.set(true);
+      }
+    }
+    !queue.borrow().is_empty()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, sync::Arc};
+
+  use super::*;
+  use crate::{
+    clock::{self, TestClock},
+    prelude::*,
+    reset_test_env,
+    test_helper::*,
+  };
+
+  const CHUNK: Duration = Duration::from_millis(2);
+
+  fn counting_task(clock: &Arc<TestClock>, count: &Rc<Cell<u32>>, total: u32) -> IdleTask {
+    let (clock, count) = (clock.clone(), count.clone());
+    IdleTask::new(move || {
+      clock.advance(CHUNK);
+      count.set(count.get() + 1);
+      if count.get() < total { IdleStep::Continue } else { IdleStep::Done }
+    })
+  }
+
+  #[test]
+  fn run_only_in_the_budget() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { @Void {} });
+    let test_clock = Arc::new(TestClock::new());
+    clock::set_clock(Some(test_clock.clone()));
+
+    let count = Rc::new(Cell::new(0));
+    let handle = AppCtx::spawn_idle(counting_task(&test_clock, &count, 5));
+
+    // The frames don't run the idle tasks.
+    wnd.draw_frame();
+    assert_eq!(count.get(), 0);
+
+    // No idle time is left.
+    assert!(AppCtx::run_idle_tasks(clock::now()));
+    assert_eq!(count.get(), 0);
+
+    // The budget is checked between the chunks.
+    assert!(AppCtx::run_idle_tasks(clock::now() + Duration::from_millis(3)));
+    assert_eq!(count.get(), 2);
+
+    assert!(!AppCtx::run_idle_tasks(clock::now() + Duration::from_secs(1)));
+    assert_eq!(count.get(), 5);
+    assert!(handle.is_finished());
+  }
+
+  #[test]
+  fn higher_priority_first() {
+    reset_test_env!();
+
+    let order = Rc::new(RefCell::new(vec![]));
+    for (name, priority) in [("low", -1), ("normal", 0), ("high", 1), ("normal2", 0)] {
+      let order = order.clone();
+      let task = IdleTask::new(move || {
+        order.borrow_mut().push(name);
+        IdleStep::Done
+      });
+      AppCtx::spawn_idle(task.with_priority(priority));
+    }
+
+    AppCtx::run_idle_tasks(clock::now() + Duration::from_secs(1));
+    assert_eq!(&*order.borrow(), &["high", "normal", "normal2", "low"]);
+  }
+
+  #[test]
+  fn dropped_with_its_owner() {
+    reset_test_env!();
+
+    struct DropFlag(Rc<Cell<bool>>);
+    impl Drop for DropFlag {
+      fn drop(&mut self) { self.0.set(true); }
+    }
+
+    let test_clock = Arc::new(TestClock::new());
+    let dropped = Rc::new(Cell::new(false));
+    let runs = Rc::new(Cell::new(0));
+    let show = Stateful::new(true);
+    let (c_clock, c_dropped, c_runs) = (test_clock.clone(), dropped.clone(), runs.clone());
+    let c_show = show.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {
+      let (clock, dropped, runs) = (c_clock.clone(), c_dropped.clone(), c_runs.clone());
+      @ {
+        pipe!(*$c_show).map(move |show| {
+          let (clock, dropped, runs) = (clock.clone(), dropped.clone(), runs.clone());
+          show.then(move || @Void {
+            on_mounted: move |e| {
+              let flag = DropFlag(dropped);
+              let task = IdleTask::new(move || {
+                let _ = &flag;
+                clock.advance(CHUNK);
+                runs.set(runs.get() + 1);
+                IdleStep::Continue
+              });
+              AppCtx::spawn_idle(task.owned_by(&*e));
+            }
+          })
+        })
+      }
+    });
+    clock::set_clock(Some(test_clock.clone()));
+    wnd.draw_frame();
+
+    assert!(AppCtx::run_idle_tasks(clock::now() + CHUNK));
+    assert_eq!(runs.get(), 1);
+    assert!(!dropped.get());
+
+    *show.write() = false;
+    wnd.draw_frame();
+    assert!(!AppCtx::run_idle_tasks(clock::now() + CHUNK));
+    assert_eq!(runs.get(), 1);
+    assert!(dropped.get());
+  }
+}
@@ -0,0 +1,499 @@
+use std::{
+  future::Future,
+  hash::{DefaultHasher, Hash, Hasher},
+};
+
+use futures::{
+  FutureExt,
+  future::{LocalBoxFuture, Shared},
+};
+use smallvec::SmallVec;
+
+use crate::{prelude::*, window::WindowId};
+
+/// The budget of the decoded images by default, in bytes.
+const DEFAULT_BUDGET: usize = 256 * 1024 * 1024;
+
+/// The key of an image in the [`ImageCache`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ImageKey {
+  /// A key named by the user, such as the url of the image.
+  Name(CowArc<str>),
+  /// The hash of the encoded content of the image.
+  Content(u64),
+}
+
+/// The size that an image is decoded in. The images shown smaller than their
+/// sizes are downscaled when they're decoded, to save the memory, see
+/// [`Image`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeSize {
+  /// The original size of the image.
+  Original,
+  /// Downscaled to fit in a square of the side, in device pixels.
+  Fit(u32),
+}
+
+/// An image decoded in a [`DecodeSize`].
+#[derive(Debug, Clone)]
+pub struct DecodedImage {
+  /// The pixels of the image, downscaled if it's larger than the decode size.
+  pub img: Resource<PixelImage>,
+  /// The size of the image before it's downscaled.
+  pub original_size: DeviceSize,
+}
+
+/// The future of a load shared by all the widgets that wait for the same
+/// image.
+pub type ImageLoad = Shared<LocalBoxFuture<'static, Option<DecodedImage>>>;
+
+/// The decoded images of the application, so the widgets that show the same
+/// image share one load and one decoded image, see [`AppCtx::image_cache`].
+/// An image is cached for every [`DecodeSize`] it's decoded in.
+///
+/// The images are kept under a budget of bytes. When it's exceeded, the least
+/// recently used images are evicted, except the visible ones. Since the images
+/// are shared, so are their textures in the GPU.
+pub struct ImageCache {
+  budget: usize,
+  used: usize,
+  /// Increase at every use, to find the least recently used image.
+  clock: u64,
+  entries: ahash::HashMap<(ImageKey, DecodeSize), CacheEntry>,
+}
+
+enum CacheEntry {
+  Loading(ImageLoad),
+  Ready {
+    img: DecodedImage,
+    used_at: u64,
+    /// The windows that painted the image, with the count of their painted
+    /// frames at that time.
+    painted: SmallVec<[(WindowId, usize); 1]>,
+  },
+}
+
+/// The smallest side of the decode sizes, the smaller images are not worth
+/// another decode.
+const MIN_DECODE_SIDE: u32 = 32;
+
+impl ImageKey {
+  /// The key of an image by the hash of its encoded `content`.
+  pub fn of_content(content: &[u8]) -> Self {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    ImageKey::Content(hasher.finish())
+  }
+}
+
+impl DecodeSize {
+  /// The decode size to show an image in the `size` of device pixels. The
+  /// sides are rounded up to powers of two, so the images shown in similar
+  /// sizes share one decode.
+  pub fn bucket_of(size: Size) -> Self {
+    let side = size.width.max(size.height);
+    if !side.is_finite() {
+      return DecodeSize::Original;
+    }
+    (side.ceil() as u32)
+      .max(MIN_DECODE_SIDE)
+      .checked_next_power_of_two()
+      .map_or(DecodeSize::Original, DecodeSize::Fit)
+  }
+
+  /// Whether an image decoded in this size is detailed enough to be shown in
+  /// the `other` size.
+  pub fn covers(self, other: DecodeSize) -> bool {
+    match (self, other) {
+      (DecodeSize::Original, _) => true,
+      (DecodeSize::Fit(_), DecodeSize::Original) => false,
+      (DecodeSize::Fit(a), DecodeSize::Fit(b)) => a >= b,
+    }
+  }
+
+  fn decode(self, img: PixelImage) -> DecodedImage {
+    let original_size = img.size();
+    let img = match self {
+      DecodeSize::Fit(side) if img.width().max(img.height()) > side => {
+        let scale = side as f64 / img.width().max(img.height()) as f64;
+        let width = (img.width() as f64 * scale).round() as u32;
+        let height = (img.height() as f64 * scale).round() as u32;
+        img.downscale(width, height)
+      }
+      _ => img,
+    };
+    DecodedImage { img: Resource::new(img), original_size }
+  }
+}
+
+impl ImageCache {
+  /// The maximum bytes of the decoded images.
+  pub fn budget(&self) -> usize { self.budget }
+
+  /// Set the maximum bytes of the decoded images, the images over it are
+  /// evicted.
+  pub fn set_budget(&mut self, bytes: usize) {
+    self.budget = bytes;
+    self.evict();
+  }
+
+  /// The bytes of the decoded images in the cache.
+  pub fn used_bytes(&self) -> usize { self.used }
+
+  /// Whether the image of the `key` is decoded in the `size` in the cache.
+  pub fn contains(&self, key: &ImageKey, size: DecodeSize) -> bool {
+    matches!(self.entries.get(&(key.clone(), size)), Some(CacheEntry::Ready { .. }))
+  }
+
+  /// Get the image of the `key` decoded in the `size`.
+  pub fn get(&mut self, key: &ImageKey, size: DecodeSize) -> Option<DecodedImage> {
+    self.clock += 1;
+    match self.entries.get_mut(&(key.clone(), size))? {
+      CacheEntry::Ready { img, used_at, .. } => {
+        *used_at = self.clock;
+        Some(img.clone())
+      }
+      CacheEntry::Loading(_) => None,
+    }
+  }
+
+  /// Put the image of the `key` decoded in the `size` in the cache.
+  pub fn insert(&mut self, key: ImageKey, size: DecodeSize, img: DecodedImage) {
+    self.remove_entry(&(key.clone(), size));
+    self.clock += 1;
+    self.used += img.img.pixel_bytes().len();
+    let entry = CacheEntry::Ready { img, used_at: self.clock, painted: <_>::default() };
+    self.entries.insert((key, size), entry);
+    self.evict();
+  }
+
+  /// Remove the image of the `key` in all the sizes, the loads of it are not
+  /// canceled but their results will not be cached.
+  pub fn remove(&mut self, key: &ImageKey) {
+    let sizes: SmallVec<[DecodeSize; 2]> = self
+      .entries
+      .keys()
+      .filter_map(|(k, size)| (k == key).then_some(*size))
+      .collect();
+    for size in sizes {
+      self.remove_entry(&(key.clone(), size));
+    }
+  }
+
+  /// Load the image of the `key` in the `size` by the `loader`, unless it's
+  /// cached or being loaded, then the `loader` is dropped without being
+  /// polled. The image that the `loader` decodes is downscaled to the `size`,
+  /// and the load resolves to `None` if the `loader` fails.
+  pub fn load(
+    &mut self, key: ImageKey, size: DecodeSize,
+    loader: impl Future<Output = Option<PixelImage>> + 'static,
+  ) -> ImageLoad {
+    if let Some(img) = self.get(&key, size) {
+      return async move { Some(img) }.boxed_local().shared();
+    }
+    let entry_key = (key, size);
+    if let Some(CacheEntry::Loading(load)) = self.entries.get(&entry_key) {
+      return load.clone();
+    }
+
+    let c_key = entry_key.clone();
+    let load = async move {
+      let img = loader.await.map(|img| size.decode(img));
+      let mut cache = AppCtx::image_cache().borrow_mut();
+      let loading = matches!(cache.entries.get(&c_key), Some(CacheEntry::Loading(_)));
+      match &img {
+        Some(img) if loading => cache.insert(c_key.0, size, img.clone()),
+        _ if loading => {
+          cache.entries.remove(&c_key);
+        }
+        _ => {}
+      }
+      img
+    }
+    .boxed_local()
+    .shared();
+    self
+      .entries
+      .insert(entry_key, CacheEntry::Loading(load.clone()));
+    load
+  }
+
+  /// Record that the image of the `key` decoded in the `size` is painted in
+  /// the frame of the window.
+  pub(crate) fn mark_painted(
+    &mut self, key: &ImageKey, size: DecodeSize, wnd: WindowId, frame: usize,
+  ) {
+    if let Some(CacheEntry::Ready { painted, .. }) = self.entries.get_mut(&(key.clone(), size)) {
+      match painted.iter_mut().find(|(id, _)| *id == wnd) {
+        Some(p) => p.1 = frame,
+        None => painted.push((wnd, frame)),
+      }
+    }
+  }
+
+  fn remove_entry(&mut self, key: &(ImageKey, DecodeSize)) {
+    if let Some(CacheEntry::Ready { img, .. }) = self.entries.remove(key) {
+      self.used -= img.img.pixel_bytes().len();
+    }
+  }
+
+  fn evict(&mut self) {
+    while self.used > self.budget {
+      let lru = self
+        .entries
+        .iter()
+        .filter_map(|(key, entry)| match entry {
+          CacheEntry::Ready { used_at, painted, .. } if !is_visible(painted) => {
+            Some((*used_at, key))
+          }
+          _ => None,
+        })
+        .min_by_key(|(used_at, _)| *used_at)
+        .map(|(_, key)| key.clone());
+      let Some(key) = lru else { break };
+      self.remove_entry(&key);
+    }
+  }
+}
+
+/// An image is visible if it's painted in the last painted frame of a window,
+/// or in the frame being painted.
+fn is_visible(painted: &[(WindowId, usize)]) -> bool {
+  painted.iter().any(|(wnd, frame)| {
+    AppCtx::get_window(*wnd).is_some_and(|wnd| {
+      let painted = wnd.frame_stats().painted;
+      painted == *frame || painted == frame + 1
+    })
+  })
+}
+
+impl Default for ImageCache {
+  fn default() -> Self {
+    Self { budget: DEFAULT_BUDGET, used: 0, clock: 0, entries: <_>::default() }
+  }
+}
+
+impl From<&'static str> for ImageKey {
+  fn from(name: &'static str) -> Self { ImageKey::Name(name.into()) }
+}
+
+impl From<String> for ImageKey {
+  fn from(name: String) -> Self { ImageKey::Name(name.into()) }
+}
+
+impl From<CowArc<str>> for ImageKey {
+  fn from(name: CowArc<str>) -> Self { ImageKey::Name(name) }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, rc::Rc};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  /// A 10x10 image of 400 bytes.
+  fn img() -> PixelImage { PixelImage::new(vec![0; 400].into(), 10, 10, image::ColorFormat::Rgba8) }
+
+  /// A 800x600 image that counts its decodes.
+  fn photo(
+    decodes: &Rc<Cell<usize>>,
+  ) -> impl Fn(DecodeSize) -> std::future::Ready<Option<PixelImage>> + Clone {
+    let decodes = decodes.clone();
+    move |_| {
+      decodes.set(decodes.get() + 1);
+      let data = vec![128; 800 * 600 * 4];
+      std::future::ready(Some(PixelImage::new(data.into(), 800, 600, image::ColorFormat::Rgba8)))
+    }
+  }
+
+  fn decoded_size(key: &str, size: DecodeSize) -> Option<DeviceSize> {
+    let mut cache = AppCtx::image_cache().borrow_mut();
+    cache
+      .get(&key.to_string().into(), size)
+      .map(|d| d.img.size())
+  }
+
+  #[test]
+  fn share_a_load() {
+    reset_test_env!();
+
+    let decodes = Rc::new(Cell::new(0));
+    let c_decodes = decodes.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @ {
+          let decodes = c_decodes.clone();
+          (0..3).map(move |_| {
+            let decodes = decodes.clone();
+            Image::from_cache("a", move |_| {
+              let decodes = decodes.clone();
+              async move {
+                decodes.set(decodes.get() + 1);
+                Some(img())
+              }
+            })
+          })
+        }
+      }
+    });
+    wnd.draw_frame();
+    wnd.draw_frame();
+
+    assert_eq!(decodes.get(), 1);
+    wnd.assert_root_size(Size::new(30., 10.));
+    assert_eq!(AppCtx::image_cache().borrow().used_bytes(), 400);
+  }
+
+  #[test]
+  fn keep_visible_images() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockStack {
+          @MockBox {
+            size: Size::new(10., 10.),
+            @ { Image::from_cache("visible", |_| async { Some(img()) }) }
+          }
+          @MockBox {
+            size: Size::new(10., 10.),
+            anchor: Point::new(200., 0.),
+            @ { Image::from_cache("off_screen", |_| async { Some(img()) }) }
+          }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    wnd.draw_frame();
+
+    let size = DecodeSize::Fit(MIN_DECODE_SIDE);
+    let mut cache = AppCtx::image_cache().borrow_mut();
+    assert_eq!(cache.used_bytes(), 800);
+    // The visible image is the least recently used one.
+    cache.get(&"off_screen".into(), size);
+    cache.set_budget(500);
+    assert!(cache.contains(&"visible".into(), size));
+    assert!(!cache.contains(&"off_screen".into(), size));
+
+    // The visible images are kept even over the budget.
+    cache.set_budget(0);
+    assert!(cache.contains(&"visible".into(), size));
+  }
+
+  #[test]
+  fn prefetch_in_idle_time() {
+    reset_test_env!();
+
+    let decodes = Rc::new(Cell::new(0));
+    let loader = photo(&decodes);
+    let (show, w_show) = split_value(false);
+    // The next page is hidden, it's not laid out until it's shown.
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        visible: pipe!(*$show),
+        @ { Image::from_cache("next", loader.clone()).prefetch(true) }
+      }
+    });
+    wnd.draw_frame();
+    AppCtx::run_until_stalled();
+    assert_eq!(decodes.get(), 0);
+
+    AppCtx::run_idle_tasks(clock::now() + Duration::from_millis(4));
+    AppCtx::run_until_stalled();
+    assert_eq!(decodes.get(), 1);
+
+    // Shown smaller than the prefetched size, it's not decoded again.
+    *w_show.write() = true;
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert_eq!(decodes.get(), 1);
+  }
+
+  #[test]
+  fn prefetch_dropped_with_the_image() {
+    reset_test_env!();
+
+    let decodes = Rc::new(Cell::new(0));
+    let loader = photo(&decodes);
+    let (mounted, w_mounted) = split_value(true);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let loader = loader.clone();
+      @MockBox {
+        size: Size::new(100., 100.),
+        visible: false,
+        @ {
+          pipe!(*$mounted).map(move |mounted| {
+            mounted.then(|| Image::from_cache("next", loader.clone()).prefetch(true))
+          })
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    // Disposed before the idle time, the image is never loaded.
+    *w_mounted.write() = false;
+    wnd.draw_frame();
+    assert!(!AppCtx::run_idle_tasks(clock::now() + Duration::from_millis(4)));
+    AppCtx::run_until_stalled();
+    assert_eq!(decodes.get(), 0);
+  }
+
+  #[test]
+  fn downscale_to_shown_size() {
+    reset_test_env!();
+
+    let decodes = Rc::new(Cell::new(0));
+    let avatar = Stateful::new(Size::new(64., 64.));
+    let c_avatar = avatar.clone_writer();
+    let loader = photo(&decodes);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let loader = loader.clone();
+      @MockBox {
+        size: pipe!(*$c_avatar),
+        @ { Image::from_cache("photo", loader) }
+      }
+    });
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert_eq!(decodes.get(), 1);
+    assert_eq!(decoded_size("photo", DecodeSize::Fit(64)), Some(DeviceSize::new(64, 48)));
+    assert_eq!(AppCtx::image_cache().borrow().used_bytes(), 64 * 48 * 4);
+
+    // Grow out of the decoded size.
+    *avatar.write() = Size::new(100., 100.);
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert_eq!(decodes.get(), 2);
+    assert_eq!(decoded_size("photo", DecodeSize::Fit(128)), Some(DeviceSize::new(128, 96)));
+
+    // Still in the decoded size, and shrinking never decodes again.
+    *avatar.write() = Size::new(120., 120.);
+    wnd.draw_frame();
+    *avatar.write() = Size::new(40., 40.);
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert_eq!(decodes.get(), 2);
+  }
+
+  #[test]
+  fn cache_original() {
+    reset_test_env!();
+
+    let decodes = Rc::new(Cell::new(0));
+    let loader = photo(&decodes);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let loader = loader.clone();
+      @MockBox {
+        size: Size::new(64., 64.),
+        @ { Image::from_cache("photo", loader).cache_original(true) }
+      }
+    });
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert_eq!(decodes.get(), 1);
+    assert_eq!(decoded_size("photo", DecodeSize::Original), Some(DeviceSize::new(800, 600)));
+  }
+}
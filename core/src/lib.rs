@@ -3,19 +3,35 @@
 #[macro_use]
 extern crate bitflags;
 
+pub mod alloc_track;
 pub mod animation;
+pub mod announce;
+pub mod build_profile;
 pub mod builtin_widgets;
 pub mod clipboard;
+pub mod clock;
+pub mod command;
 mod context;
 pub mod data_widget;
+pub mod debug_overlay;
 pub mod declare;
+pub mod deep_link;
+pub mod engine_bridge;
+pub mod error;
 pub mod events;
+#[cfg(feature = "png")]
+pub mod gallery;
+pub mod idle;
+pub mod image_cache;
 pub mod local_sender;
+pub mod opener;
 pub mod pipe;
 pub(crate) mod render_helper;
+pub mod sound;
 mod state;
 pub mod ticker;
 pub mod timer;
+pub mod watchdog;
 pub mod widget;
 pub mod widget_children;
 pub(crate) mod widget_tree;
@@ -49,23 +65,37 @@ pub mod prelude {
   pub use super::{
     Measure,
     animation::*,
+    announce::Politeness,
+    build_profile::{BuildCost, BuildKind, BuildProfile},
     builtin_widgets::*,
     class_names,
+    command::*,
     context::*,
+    debug_overlay::DebugOverlay,
     declare::*,
+    deep_link::{DeepLink, LinkParams},
+    engine_bridge::{EngineBridge, EngineHandle},
+    error::CoreError,
     events::*,
+    idle::{IdleStep, IdleTask, IdleTaskHandle},
+    image_cache::{DecodeSize, DecodedImage, ImageCache, ImageKey},
     multi_class,
     overlay::{AutoClosePolicy, Overlay, OverlayStyle},
-    pipe::{BoxPipe, FinalChain, MapPipe, ModifiesPipe, Pipe},
+    pipe::{BoxPipe, DistinctPipe, FinalChain, MapPipe, ModifiesPipe, Pipe},
     providers,
     query::*,
+    sound::SoundId,
     state::*,
     style_class,
     ticker::{Duration, Instant},
+    watchdog::{FrameWatchdog, JankReport, WidgetCost},
     widget::*,
     widget_children::*,
-    widget_tree::{BoxClamp, LayoutInfo, TrackId, WidgetId},
-    window::Window,
+    widget_tree::{
+      BoxClamp, DumpNode, LayoutDiagnostics, LayoutInfo, RelayoutRecord, RelayoutStop, TrackId,
+      TreeDump, WidgetId,
+    },
+    window::{DeviceClass, MediaInfo, Orientation, ResizeEdge, Window},
   };
   pub use crate::{timer, *};
 }
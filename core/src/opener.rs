@@ -0,0 +1,93 @@
+use std::{
+  cell::{Cell, RefCell},
+  io::{Error, ErrorKind},
+  path::{Path, PathBuf},
+  rc::Rc,
+};
+
+/// Hand the urls and the files to the applications of the system, see
+/// [`AppCtx::open_url`](crate::prelude::AppCtx::open_url) and
+/// [`AppCtx::reveal_path`](crate::prelude::AppCtx::reveal_path).
+pub trait Opener {
+  /// Open the `url` by the default application of the system, such as a
+  /// browser for a web page.
+  fn open_url(&mut self, url: &str) -> Result<(), Error>;
+
+  /// Show the `path` in the file manager of the system, selected if the file
+  /// manager can.
+  fn reveal_path(&mut self, path: &Path) -> Result<(), Error>;
+}
+
+/// An opener that records what it's asked to open rather than open it, for
+/// the tests. The clones share the records.
+#[derive(Default, Clone)]
+pub struct MockOpener {
+  urls: Rc<RefCell<Vec<String>>>,
+  paths: Rc<RefCell<Vec<PathBuf>>>,
+  error: Rc<Cell<Option<ErrorKind>>>,
+}
+
+impl MockOpener {
+  /// The urls that it's asked to open, in order.
+  pub fn opened_urls(&self) -> Vec<String> { self.urls.borrow().clone() }
+
+  /// The paths that it's asked to reveal, in order.
+  pub fn revealed_paths(&self) -> Vec<PathBuf> { self.paths.borrow().clone() }
+
+  /// Fail the later requests with the error of the `kind`, or succeed if it's
+  /// `None`. The failed requests are not recorded.
+  pub fn fail_with(&self, kind: Option<ErrorKind>) { self.error.set(kind); }
+
+  fn check(&self) -> Result<(), Error> {
+    match self.error.get() {
+      Some(kind) => Err(Error::new(kind, "the mock opener fails")),
+      None => Ok(()),
+    }
+  }
+}
+
+impl Opener for MockOpener {
+  fn open_url(&mut self, url: &str) -> Result<(), Error> {
+    self.check()?;
+    self.urls.borrow_mut().push(url.to_string());
+    Ok(())
+  }
+
+  fn reveal_path(&mut self, path: &Path) -> Result<(), Error> {
+    self.check()?;
+    self.paths.borrow_mut().push(path.to_path_buf());
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{prelude::AppCtx, reset_test_env};
+
+  #[test]
+  fn open_by_the_opener() {
+    reset_test_env!();
+
+    let opener = MockOpener::default();
+    AppCtx::set_opener(Box::new(opener.clone()));
+    AppCtx::open_url("https://github.com/RibirX/Ribir").unwrap();
+    AppCtx::reveal_path(Path::new("/tmp/crash.log")).unwrap();
+    assert_eq!(opener.opened_urls(), ["https://github.com/RibirX/Ribir"]);
+    assert_eq!(opener.revealed_paths(), [PathBuf::from("/tmp/crash.log")]);
+  }
+
+  #[test]
+  fn report_the_failure() {
+    reset_test_env!();
+
+    let opener = MockOpener::default();
+    opener.fail_with(Some(ErrorKind::NotFound));
+    AppCtx::set_opener(Box::new(opener.clone()));
+    let err = AppCtx::open_url("https://github.com/RibirX/Ribir").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    let err = AppCtx::reveal_path(Path::new("/tmp/crash.log")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(opener.opened_urls().is_empty());
+  }
+}
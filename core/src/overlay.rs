@@ -56,6 +56,7 @@ struct InnerOverlay {
   mask: Option<Brush>,
   showing: Option<ShowingInfo>,
   track_id: RefCell<Option<TrackId>>,
+  host: Option<TrackId>,
 }
 
 struct ShowingInfo {
@@ -74,9 +75,22 @@ impl Overlay {
       mask,
       showing: None,
       track_id: RefCell::new(None),
+      host: None,
     })))
   }
 
+  /// Set the widget that the overlay is shown for, e.g. the widget that a
+  /// tooltips or a menu belongs to.
+  ///
+  /// The overlay is inserted at the root of the window, but it inherits the
+  /// providers of its host instead of the root, so it uses the same theme as
+  /// the host even if the host is in a subtree that overrides the theme. The
+  /// overlay falls back to the root if the host is disposed.
+  pub fn with_host(self, host: TrackId) -> Self {
+    self.0.borrow_mut().host = Some(host);
+    self
+  }
+
   /// Return the overlay that the `ctx` belongs to if it is within an overlay.
   pub fn of(ctx: &impl WidgetCtx) -> Option<Self> {
     let wnd = ctx.window();
@@ -151,16 +165,29 @@ impl Overlay {
     self.inner_show(gen.into(), wnd);
   }
 
-  /// Show the widget at the give position.
-  /// if the overlay is showing, nothing will happen.
+  /// Show the widget at the give global position, such as a context menu at
+  /// the pointer. if the overlay is showing, nothing will happen.
+  ///
+  /// The widget is kept inside the window: if it overflows the right or the
+  /// bottom edge, it flips to end at the position, and if it still overflows,
+  /// it slides back into the window.
   pub fn show_at(&self, pos: Point, wnd: Sc<Window>) {
     if self.is_showing() {
       return;
     }
     self.show_map(
       move |w| {
+        let x = GlobalAnchorX::custom(move |host, wnd| {
+          let (size, wnd_size) = overlay_sizes(host, wnd)?;
+          Ok(fit_in_window(pos.x, size.width, wnd_size.width))
+        });
+        let y = GlobalAnchorY::custom(move |host, wnd| {
+          let (size, wnd_size) = overlay_sizes(host, wnd)?;
+          Ok(fit_in_window(pos.y, size.height, wnd_size.height))
+        });
         FatObj::new(w)
-          .anchor(Anchor::from_point(pos))
+          .global_anchor_x(x)
+          .global_anchor_y(y)
           .into_widget()
       },
       wnd,
@@ -236,7 +263,10 @@ impl Overlay {
       @ { w }
     };
 
-    let _guard = BuildCtx::init_for(wnd.tree().root(), wnd.tree);
+    let startup = self
+      .host(wnd.tree())
+      .unwrap_or_else(|| wnd.tree().root());
+    let _guard = BuildCtx::init_for(startup, wnd.tree);
 
     let wid = gen().build();
     let tree = wnd.tree_mut();
@@ -259,6 +289,32 @@ impl Overlay {
       .as_ref()
       .and_then(|s| s.get())
   }
+
+  fn host(&self, tree: &WidgetTree) -> Option<WidgetId> {
+    let inner = self.0.borrow();
+    let host = inner.host.as_ref()?.get()?;
+    (!host.is_dropped(tree)).then_some(host)
+  }
+}
+
+fn overlay_sizes(host: &TrackId, wnd: &Sc<Window>) -> Result<(Size, Size), ()> {
+  let id = host.get().ok_or(())?;
+  if id.is_dropped(wnd.tree()) {
+    return Err(());
+  }
+  Ok((wnd.widget_size(id).unwrap_or_default(), wnd.size()))
+}
+
+/// The start of a widget of `size` placed at `pos` in a window of `wnd_size`
+/// along an axis.
+fn fit_in_window(pos: f32, size: f32, wnd_size: f32) -> f32 {
+  if pos + size <= wnd_size {
+    pos
+  } else if pos >= size {
+    pos - size
+  } else {
+    (wnd_size - size).max(0.)
+  }
 }
 
 pub(crate) struct ShowingOverlays(RefCell<Vec<Overlay>>);
@@ -283,6 +339,16 @@ impl ShowingOverlays {
     }
   }
 
+  /// The host of the overlay whose root is `id`, see [`Overlay::with_host`].
+  pub(crate) fn host_of(&self, id: WidgetId, tree: &WidgetTree) -> Option<WidgetId> {
+    self
+      .0
+      .borrow()
+      .iter()
+      .find(|o| o.showing_root() == Some(id))
+      .and_then(|o| o.host(tree))
+  }
+
   fn add(&self, overlay: Overlay) {
     assert!(overlay.showing_root().is_some());
     self.0.borrow_mut().push(overlay)
@@ -367,4 +433,30 @@ mod tests {
     assert_eq!(*r_log.borrow(), &["mounted", "disposed"]);
     assert_eq!(wnd.tree().count(root), 3);
   }
+
+  #[test]
+  fn show_at_keeps_in_window() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! { @MockBox { size: Size::zero() } },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    let style = || OverlayStyle { auto_close_policy: AutoClosePolicy::NOT_AUTO_CLOSE, mask: None };
+
+    // Flip to end at the position at the bottom-right corner.
+    let overlay = Overlay::new(fn_widget! { @MockBox { size: Size::new(40., 30.) } }, style());
+    overlay.show_at(Point::new(90., 95.), wnd.0.clone());
+    wnd.draw_frame();
+    assert_eq!(wnd.layout_info_by_path(&[1]).unwrap().pos, Point::new(50., 65.));
+    overlay.close();
+    wnd.draw_frame();
+
+    // Slide into the window if it can't flip.
+    let overlay = Overlay::new(fn_widget! { @MockBox { size: Size::new(40., 80.) } }, style());
+    overlay.show_at(Point::new(10., 50.), wnd.0.clone());
+    wnd.draw_frame();
+    assert_eq!(wnd.layout_info_by_path(&[1]).unwrap().pos, Point::new(10., 20.));
+  }
 }
@@ -10,7 +10,12 @@ use rxrust::ops::box_it::BoxOp;
 use smallvec::SmallVec;
 use widget_id::RenderQueryable;
 
-use crate::{builtin_widgets::key::AnyKey, prelude::*, render_helper::PureRender};
+use crate::{
+  build_profile::{BuildKind, profile_build},
+  builtin_widgets::key::AnyKey,
+  prelude::*,
+  render_helper::PureRender,
+};
 
 pub type ValueStream<V> = BoxOp<'static, (ModifyScope, V), Infallible>;
 
@@ -43,6 +48,16 @@ pub trait Pipe: 'static {
     FinalChain { source: self, f, _marker: PhantomData }
   }
 
+  /// Only emit the value when it is different from the last one, so the
+  /// consumer, e.g., a pipe widget, does not rebuild for an equal value.
+  fn distinct(self) -> DistinctPipe<Self>
+  where
+    Self: Sized,
+    Self::Value: PartialEq + Clone,
+  {
+    DistinctPipe(self)
+  }
+
   /// Unzip the `Pipe` into its inner value and the stream of changes for that
   /// value.
   ///
@@ -80,6 +95,8 @@ pub struct FinalChain<V, S, F> {
   _marker: PhantomData<V>,
 }
 
+pub struct DistinctPipe<S>(S);
+
 impl<V: 'static> BoxPipe<V> {
   #[inline]
   pub fn value(v: V) -> Self { Self(Box::new(ValuePipe(v))) }
@@ -92,10 +109,12 @@ impl<V: 'static> BoxPipe<V> {
 }
 
 pub(crate) trait InnerPipe: Pipe + Sized {
+  #[track_caller]
   fn build_single<const M: usize>(self) -> Widget<'static>
   where
     Self::Value: IntoWidget<'static, M>,
   {
+    let location = std::panic::Location::caller();
     let f = move || {
       let ctx = BuildCtx::get();
       let info = DynWidgetsInfo::new(GenRange::Single(ctx.tree().root()));
@@ -103,7 +122,7 @@ pub(crate) trait InnerPipe: Pipe + Sized {
       let updater = PipeWidgetUpdater::new_with_tree(info.clone(), BuildCtx::get().tree.as_ptr());
       let (w, modifies) = self.unzip(ModifyScope::FRAMEWORK, Some(updater));
 
-      w.into_widget().on_build(|w| {
+      w.into_widget().on_build(move |w| {
         info
           .borrow_mut()
           .set_gen_range(GenRange::Single(w));
@@ -116,7 +135,9 @@ pub(crate) trait InnerPipe: Pipe + Sized {
 
           let ctx = BuildCtx::get_mut();
           let old_node = pipe_node.remove_old_data();
-          let new = w.into_widget().build();
+          let name = std::any::type_name::<Self::Value>();
+          let new =
+            profile_build(BuildKind::Pipe, name, Some(location), || w.into_widget().build());
           let tree = ctx.tree_mut();
           pipe_node.transplant_to_new(old_node, new, tree);
 
@@ -174,14 +195,16 @@ pub(crate) trait InnerPipe: Pipe + Sized {
         let tree = BuildCtx::get_mut().tree_mut();
         let old_node = pipe_node.remove_old_data();
         let mut new = vec![];
-        for (idx, w) in m.into_iter().enumerate() {
-          let id = w.into_widget().build();
-          new.push(id);
-          set_pos_of_multi(id, idx, tree);
-        }
-        if new.is_empty() {
-          new.push(Void.into_widget().build());
-        }
+        profile_build(BuildKind::Pipe, std::any::type_name::<Self::Value>(), None, || {
+          for (idx, w) in m.into_iter().enumerate() {
+            let id = w.into_widget().build();
+            new.push(id);
+            set_pos_of_multi(id, idx, tree);
+          }
+          if new.is_empty() {
+            new.push(Void.into_widget().build());
+          }
+        });
 
         pipe_node.transplant_to_new(old_node, new[0], tree);
 
@@ -268,7 +291,8 @@ pub(crate) trait InnerPipe: Pipe + Sized {
           };
 
           let old_node = pipe_node.remove_old_data();
-          let p = w.into_widget().build();
+          let name = std::any::type_name::<Self::Value>();
+          let p = profile_build(BuildKind::Pipe, name, None, || w.into_widget().build());
           let tree = BuildCtx::get_mut().tree_mut();
           pipe_node.transplant_to_new(old_node, p, tree);
 
@@ -425,6 +449,39 @@ where
   }
 }
 
+impl<S> Pipe for DistinctPipe<S>
+where
+  S: Pipe,
+  S::Value: PartialEq + Clone,
+{
+  type Value = S::Value;
+
+  fn unzip(
+    self, scope: ModifyScope, updater: Option<PipeWidgetUpdater>,
+  ) -> (Self::Value, ValueStream<Self::Value>) {
+    let (v, stream) = self.0.unzip(scope, updater);
+    let last = RefCell::new(v.clone());
+    let stream = stream
+      .filter(move |(_, v)| {
+        let mut last = last.borrow_mut();
+        let changed = *v != *last;
+        if changed {
+          *last = v.clone();
+        }
+        changed
+      })
+      .box_it();
+    (v, stream)
+  }
+
+  #[inline]
+  fn box_unzip(
+    self: Box<Self>, scope: ModifyScope, updater: Option<PipeWidgetUpdater>,
+  ) -> (Self::Value, ValueStream<Self::Value>) {
+    (*self).unzip(scope, updater)
+  }
+}
+
 /// A pipe that never changes, help to construct a pipe from a value.
 struct ValuePipe<V>(V);
 
@@ -453,6 +510,7 @@ where
   Self: InnerPipe<Value = V>,
   V: IntoWidget<'static, M>,
 {
+  #[track_caller]
   fn into_widget_strict(self) -> Widget<'static> { self.build_single() }
 }
 
@@ -461,6 +519,7 @@ where
   Self: InnerPipe<Value = V>,
   V: IntoWidget<'static, M>,
 {
+  #[track_caller]
   fn into_widget_strict(self) -> Widget<'static> { self.build_single() }
 }
 
@@ -468,6 +527,7 @@ impl<const M: usize, V> IntoWidgetStrict<'static, M> for Box<dyn Pipe<Value = V>
 where
   V: IntoWidget<'static, M>,
 {
+  #[track_caller]
   fn into_widget_strict(self) -> Widget<'static> { self.build_single() }
 }
 
@@ -476,6 +536,7 @@ where
   Self: InnerPipe<Value = Option<V>>,
   V: IntoWidget<'static, M>,
 {
+  #[track_caller]
   fn into_widget(self) -> Widget<'static> { option_into_widget(self) }
 }
 
@@ -484,6 +545,7 @@ where
   Self: InnerPipe<Value = Option<V>>,
   V: IntoWidget<'static, M>,
 {
+  #[track_caller]
   fn into_widget(self) -> Widget<'static> { option_into_widget(self) }
 }
 
@@ -491,9 +553,11 @@ impl<const M: usize, V> IntoWidget<'static, M> for Box<dyn Pipe<Value = Option<V
 where
   V: IntoWidget<'static, M>,
 {
+  #[track_caller]
   fn into_widget(self) -> Widget<'static> { option_into_widget(self) }
 }
 
+#[track_caller]
 fn option_into_widget<const M: usize>(
   p: impl InnerPipe<Value = Option<impl IntoWidget<'static, M>>>,
 ) -> Widget<'static> {
@@ -743,6 +807,7 @@ impl PipeNode {
 
     std::mem::swap(&mut self.as_mut().data, old);
     *old = old_node;
+    tree.refresh_bloom(new_id);
   }
 
   fn as_ref(&self) -> &InnerPipeNode {
@@ -847,6 +912,11 @@ impl Query for PipeNode {
   }
 
   fn queryable(&self) -> bool { true }
+
+  fn type_bloom(&self, bloom: &mut QueryBloom) {
+    self.as_ref().data.type_bloom(bloom);
+    bloom.insert(&QueryId::of::<DynInfo>());
+  }
 }
 
 impl Render for PipeNode {
@@ -865,7 +935,21 @@ impl Render for PipeNode {
     self.as_ref().data.hit_test(ctx, pos)
   }
 
+  fn hit_bounds(&self, ctx: &HitTestCtx) -> Option<Rect> { self.as_ref().data.hit_bounds(ctx) }
+
   fn get_transform(&self) -> Option<Transform> { self.as_ref().data.get_transform() }
+
+  fn get_opacity(&self) -> f32 { self.as_ref().data.get_opacity() }
+
+  fn is_displayed(&self) -> bool { self.as_ref().data.is_displayed() }
+
+  fn z_index(&self) -> i32 { self.as_ref().data.z_index() }
+
+  fn debug_name(&self) -> std::borrow::Cow<'static, str> { self.as_ref().data.debug_name() }
+
+  fn text_content(&self) -> Option<CowArc<str>> { self.as_ref().data.text_content() }
+
+  fn text_baselines(&self) -> Vec<f32> { self.as_ref().data.text_baselines() }
 }
 
 #[derive(Clone)]
@@ -926,7 +1010,10 @@ impl Priority for PipeWidgetUpdater {
 
 #[cfg(test)]
 mod tests {
-  use std::{cell::Cell, rc::Rc};
+  use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+  };
 
   use crate::{
     builtin_widgets::key::{AnyKey, KeyChange},
@@ -1783,4 +1870,90 @@ mod tests {
     *m_writer.write() += 1;
     wnd.draw_frame();
   }
+
+  #[test]
+  fn distinct_pipe_skip_equal_value() {
+    reset_test_env!();
+    let (watcher, writer) = split_value(0);
+    let builds = Rc::new(Cell::new(0));
+    let c_builds = builds.clone();
+    let widget = fn_widget! {
+      let c_builds = c_builds.clone();
+      pipe!(*$watcher / 10).distinct().map(move |_| {
+        c_builds.set(c_builds.get() + 1);
+        Void
+      })
+    };
+
+    let mut wnd = TestWindow::new(widget);
+    wnd.draw_frame();
+    assert_eq!(builds.get(), 1);
+
+    *writer.write() = 5;
+    wnd.draw_frame();
+    assert_eq!(builds.get(), 1);
+
+    *writer.write() = 12;
+    wnd.draw_frame();
+    assert_eq!(builds.get(), 2);
+  }
+
+  #[test]
+  fn only_rebuild_pipes_reading_changed_part() {
+    reset_test_env!();
+
+    struct Form {
+      name: usize,
+      age: usize,
+    }
+    let form = Stateful::new(Form { name: 0, age: 0 });
+    // The split writers have their own notifiers, unlike `part_writer!`.
+    let name = form.split_writer(|f| PartData::from_ref_mut(&mut f.name));
+    let age = form.split_writer(|f| PartData::from_ref_mut(&mut f.age));
+    let builds = Rc::new(RefCell::new([0; 3]));
+    let (c_form, c_name, c_age, c_builds) =
+      (form.clone_writer(), name.clone_writer(), age.clone_writer(), builds.clone());
+    let widget = fn_widget! {
+      let (b1, b2, b3) = (c_builds.clone(), c_builds.clone(), c_builds.clone());
+      @MockMulti {
+        @ {
+          pipe!(*$c_name).map(move |_| {
+            b1.borrow_mut()[0] += 1;
+            Void
+          })
+        }
+        @ {
+          pipe!(*$c_age).map(move |_| {
+            b2.borrow_mut()[1] += 1;
+            Void
+          })
+        }
+        @ {
+          pipe!($c_form.name + $c_form.age).map(move |_| {
+            b3.borrow_mut()[2] += 1;
+            Void
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(widget);
+    wnd.draw_frame();
+    assert_eq!(*builds.borrow(), [1, 1, 1]);
+
+    // The sibling reading the other part is kept as it is, and the part change
+    // doesn't notify the readers of the whole form.
+    *name.write() += 1;
+    wnd.draw_frame();
+    assert_eq!(*builds.borrow(), [2, 1, 1]);
+
+    *age.write() += 1;
+    wnd.draw_frame();
+    assert_eq!(*builds.borrow(), [2, 2, 1]);
+
+    // Only the pipe reading the whole form rebuilds when it's modified.
+    form.write().name += 1;
+    wnd.draw_frame();
+    assert_eq!(*builds.borrow(), [2, 2, 2]);
+  }
 }
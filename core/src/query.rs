@@ -35,8 +35,20 @@ pub trait Query: Any {
 
   /// Hint this is a non-queryable type.
   fn queryable(&self) -> bool { true }
+
+  /// Add all the types that can be queried from this object to the `bloom`.
+  ///
+  /// The widget tree uses it to skip the subtrees that can't contain a type.
+  /// The default implementation fills the whole bloom, which is always correct
+  /// but disables the pruning.
+  fn type_bloom(&self, bloom: &mut QueryBloom) { bloom.fill() }
 }
 
+/// A bloom filter of the types that can be queried, it may report a type that
+/// is not there, but never misses a type that is there.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueryBloom([u64; 4]);
+
 /// This wrapper transforms a non-queryable type into a queryable one, limiting
 /// query access to its own type only.
 ///
@@ -233,6 +245,8 @@ impl<T: Any> Query for Queryable<T> {
   }
 
   fn queryable(&self) -> bool { true }
+
+  fn type_bloom(&self, bloom: &mut QueryBloom) { bloom.insert(&QueryId::of::<T>()) }
 }
 
 impl<T: StateWriter> Query for T
@@ -286,6 +300,11 @@ where
     })
   }
   fn queryable(&self) -> bool { true }
+
+  fn type_bloom(&self, bloom: &mut QueryBloom) {
+    bloom.insert(&QueryId::of::<T::Value>());
+    bloom.insert(&QueryId::of::<T>());
+  }
 }
 
 macro_rules! impl_query_for_reader {
@@ -325,6 +344,11 @@ macro_rules! impl_query_for_reader {
     }
 
     fn queryable(&self) -> bool { true }
+
+    fn type_bloom(&self, bloom: &mut QueryBloom) {
+      bloom.insert(&QueryId::of::<V>());
+      bloom.insert(&QueryId::of::<Self>());
+    }
   };
 }
 
@@ -393,6 +417,53 @@ impl QueryId {
   }
 }
 
+impl QueryBloom {
+  /// Add the type of `id` to the bloom.
+  pub fn insert(&mut self, id: &QueryId) {
+    let [a, b] = Self::bits(id);
+    self.0[a / 64] |= 1 << (a % 64);
+    self.0[b / 64] |= 1 << (b % 64);
+  }
+
+  /// Return if the type of `id` may be in the bloom.
+  pub fn may_contain(&self, id: &QueryId) -> bool {
+    Self::bits(id)
+      .iter()
+      .all(|i| self.0[i / 64] & (1 << (i % 64)) != 0)
+  }
+
+  /// Mark all types as present.
+  pub fn fill(&mut self) { self.0 = [u64::MAX; 4]; }
+
+  /// Add all the types in `other` to this bloom.
+  pub fn union(&mut self, other: &QueryBloom) {
+    self
+      .0
+      .iter_mut()
+      .zip(other.0.iter())
+      .for_each(|(a, b)| *a |= b);
+  }
+
+  /// Return if all the types in `other` are already in this bloom.
+  pub fn contains_all(&self, other: &QueryBloom) -> bool {
+    self
+      .0
+      .iter()
+      .zip(other.0.iter())
+      .all(|(a, b)| a & b == *b)
+  }
+
+  fn bits(id: &QueryId) -> [usize; 2] {
+    // Hash the type name rather than the `TypeId`, so the same type in
+    // different binaries hits the same bits.
+    let hash = (id.info)()
+      .name
+      .bytes()
+      .fold(0xcbf29ce484222325_u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    [(hash & 0xff) as usize, ((hash >> 32) & 0xff) as usize]
+  }
+}
+
 impl PartialEq for QueryId {
   fn eq(&self, other: &Self) -> bool { self.is_same(other) }
 }
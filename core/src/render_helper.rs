@@ -27,6 +27,8 @@ impl<R: Render> Query for PureRender<R> {
   }
 
   fn queryable(&self) -> bool { false }
+
+  fn type_bloom(&self, _: &mut QueryBloom) {}
 }
 
 impl<R: Render> RenderProxy for PureRender<R> {
@@ -51,8 +53,23 @@ where
   #[inline]
   fn hit_test(&self, ctx: &HitTestCtx, pos: Point) -> HitTest { self.proxy().hit_test(ctx, pos) }
 
+  #[inline]
+  fn hit_bounds(&self, ctx: &HitTestCtx) -> Option<Rect> { self.proxy().hit_bounds(ctx) }
+
   #[inline]
   fn get_transform(&self) -> Option<Transform> { self.proxy().get_transform() }
+
+  fn get_opacity(&self) -> f32 { self.proxy().get_opacity() }
+
+  fn is_displayed(&self) -> bool { self.proxy().is_displayed() }
+
+  fn z_index(&self) -> i32 { self.proxy().z_index() }
+
+  fn debug_name(&self) -> std::borrow::Cow<'static, str> { self.proxy().debug_name() }
+
+  fn text_content(&self) -> Option<CowArc<str>> { self.proxy().text_content() }
+
+  fn text_baselines(&self) -> Vec<f32> { self.proxy().text_baselines() }
 }
 
 impl<R: Render> RenderProxy for RefCell<R> {
@@ -0,0 +1,67 @@
+use std::{cell::RefCell, rc::Rc};
+
+/// The id of a sound loaded by a [`SoundPlayer`], see
+/// [`AppCtx::load_sound`](crate::prelude::AppCtx::load_sound).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoundId(pub u32);
+
+/// Play the short sounds of the interactions, such as pressing a button, see
+/// [`AppCtx::play_sound`](crate::prelude::AppCtx::play_sound).
+///
+/// The player works off the UI thread, neither of its methods should wait for
+/// the audio device. A player that fails to start the audio stays silent
+/// rather than fails, the interactions don't depend on the sounds.
+pub trait SoundPlayer {
+  /// Load a sound from its encoded `bytes`. A sound that can't be decoded
+  /// still gets an id, it plays nothing.
+  fn load_from_bytes(&mut self, bytes: Vec<u8>) -> SoundId;
+
+  /// Start playing the sound of the `id` and return at once.
+  fn play(&mut self, id: SoundId);
+}
+
+/// A player that never makes a sound, it records what it's asked to play for
+/// the tests. The clones share the records.
+#[derive(Default, Clone)]
+pub struct MockSoundPlayer {
+  loaded: Rc<RefCell<Vec<Vec<u8>>>>,
+  played: Rc<RefCell<Vec<SoundId>>>,
+}
+
+impl MockSoundPlayer {
+  /// The sounds that it's asked to play, in order.
+  pub fn played(&self) -> Vec<SoundId> { self.played.borrow().clone() }
+
+  /// How many sounds are loaded.
+  pub fn loaded_count(&self) -> usize { self.loaded.borrow().len() }
+}
+
+impl SoundPlayer for MockSoundPlayer {
+  fn load_from_bytes(&mut self, bytes: Vec<u8>) -> SoundId {
+    let mut loaded = self.loaded.borrow_mut();
+    loaded.push(bytes);
+    SoundId(loaded.len() as u32 - 1)
+  }
+
+  fn play(&mut self, id: SoundId) { self.played.borrow_mut().push(id); }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{prelude::AppCtx, reset_test_env};
+
+  #[test]
+  fn play_by_the_player() {
+    reset_test_env!();
+
+    let player = MockSoundPlayer::default();
+    AppCtx::set_sound_player(Box::new(player.clone()));
+    let click = AppCtx::load_sound(vec![1, 2, 3]);
+    let error = AppCtx::load_sound(vec![4, 5, 6]);
+    AppCtx::play_sound(error);
+    AppCtx::play_sound(click);
+    assert_eq!(player.loaded_count(), 2);
+    assert_eq!(player.played(), [error, click]);
+  }
+}
@@ -1,12 +1,20 @@
+mod computed;
 mod map_state;
+mod persistent;
 mod prior_op;
 mod splitted_state;
 mod stateful;
+mod sync_reader;
+mod time_travel;
+mod transaction;
+mod undo_history;
 mod watcher;
 use std::{cell::UnsafeCell, convert::Infallible, mem::MaybeUninit, ops::DerefMut};
 pub mod state_cell;
 
+pub use computed::*;
 pub use map_state::*;
+pub use persistent::*;
 pub use prior_op::*;
 use ribir_algo::Sc;
 use rxrust::ops::box_it::{BoxOp, CloneableBoxOp};
@@ -14,6 +22,10 @@ pub use splitted_state::*;
 pub use state_cell::{PartData, ReadRef};
 use state_cell::{StateCell, ValueMutRef};
 pub use stateful::*;
+pub use sync_reader::*;
+pub use time_travel::*;
+pub use transaction::*;
+pub use undo_history::*;
 pub use watcher::*;
 
 use crate::{prelude::*, render_helper::RenderProxy};
@@ -364,6 +376,9 @@ impl<'a, W: ?Sized> Drop for WriteRef<'a, W> {
     if !*modified {
       return;
     }
+    if modify_scope.contains(ModifyScope::DATA) {
+      info.data_version.set(info.data_version.get() + 1);
+    }
 
     let batched_modifies = &info.batched_modifies;
     if batched_modifies.get().is_empty() && !modify_scope.is_empty() {
@@ -0,0 +1,273 @@
+use std::{
+  cell::{Cell, RefCell},
+  convert::Infallible,
+  rc::{Rc, Weak},
+};
+
+use rxrust::{ops::box_it::CloneableBoxOp, prelude::*};
+
+use super::state_cell::StateCell;
+use crate::prelude::*;
+
+/// A state derived from other states, it caches the value computed from the
+/// upstream states.
+///
+/// The value is recomputed only when it is read after the upstream states
+/// changed. But if someone watches the modifies of it, the value is
+/// recomputed as soon as the upstream states notify their changes, and the
+/// watchers are only notified when the new value is not equal to the old one.
+///
+/// Like the other states, the changes of the upstream states are known after
+/// they are notified, not when they are written.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let list = Stateful::new(vec![1, 2, 3]);
+/// let total = Computed::new(&list, |list| list.iter().sum::<i32>());
+/// assert_eq!(*total.read(), 6);
+/// ```
+pub struct Computed<T>(Rc<ComputedInner<T>>);
+
+type EqFn<T> = Box<dyn Fn(&T, &T) -> bool>;
+
+struct ComputedInner<T> {
+  value: StateCell<Option<T>>,
+  dirty: Cell<bool>,
+  compute: Box<dyn Fn() -> T>,
+  eq: RefCell<EqFn<T>>,
+  notifier: Notifier,
+  upstream: RefCell<Option<BoxSubscription<'static>>>,
+}
+
+impl<T: PartialEq + 'static> Computed<T> {
+  /// Create a state that derived from the value of `upstream` by `compute`.
+  pub fn new<S>(upstream: &S, compute: impl Fn(&S::Value) -> T + 'static) -> Self
+  where
+    S: StateWatcher,
+  {
+    let reader = upstream.clone_reader();
+    Self::from_modifies(upstream.raw_modifies(), move || compute(&reader.read()))
+  }
+
+  /// Create a state computed by `compute`, and recomputed after the `modifies`
+  /// emit a data change. It's used to derive from multiple states, by merging
+  /// their modifies.
+  pub fn from_modifies(
+    modifies: CloneableBoxOp<'static, ModifyScope, Infallible>, compute: impl Fn() -> T + 'static,
+  ) -> Self {
+    let inner = Rc::new(ComputedInner {
+      value: StateCell::new(None),
+      dirty: Cell::new(true),
+      compute: Box::new(compute),
+      eq: RefCell::new(Box::new(T::eq)),
+      notifier: <_>::default(),
+      upstream: RefCell::new(None),
+    });
+    let weak = Rc::downgrade(&inner);
+    let u = modifies
+      .filter(|s| s.contains(ModifyScope::DATA))
+      .subscribe(move |_| {
+        if let Some(inner) = Weak::upgrade(&weak) {
+          inner.upstream_changed()
+        }
+      });
+    *inner.upstream.borrow_mut() = Some(u);
+    Self(inner)
+  }
+}
+
+impl<T> Computed<T> {
+  /// Use `eq` instead of `PartialEq` to tell if the recomputed value is equal
+  /// to the old one. The old value is kept if they are equal.
+  pub fn with_eq(self, eq: impl Fn(&T, &T) -> bool + 'static) -> Self {
+    *self.0.eq.borrow_mut() = Box::new(eq);
+    self
+  }
+}
+
+impl<T> ComputedInner<T> {
+  /// Recompute the value if the upstream changed, return if the value changed.
+  fn refresh(&self) -> bool {
+    if !self.dirty.replace(false) {
+      return false;
+    }
+    let new = (self.compute)();
+    let mut value = self.value.write();
+    match value.as_ref() {
+      Some(old) if (self.eq.borrow())(old, &new) => false,
+      _ => {
+        *value = Some(new);
+        true
+      }
+    }
+  }
+
+  fn upstream_changed(&self) {
+    self.dirty.set(true);
+    // Nobody watches the changes, wait for the next read.
+    if self.notifier.has_observers() && self.refresh() {
+      self.notifier.next(ModifyScope::BOTH);
+    }
+  }
+}
+
+impl<T: 'static> StateReader for Computed<T> {
+  type Value = T;
+  type OriginReader = Self;
+  type Reader = Self;
+
+  fn read(&self) -> ReadRef<'_, T> {
+    self.0.refresh();
+    ReadRef::map(self.0.value.read(), |v| PartData::from_ref(v.as_ref().unwrap()))
+  }
+
+  #[inline]
+  fn clone_reader(&self) -> Self { Self(self.0.clone()) }
+
+  #[inline]
+  fn origin_reader(&self) -> &Self::OriginReader { self }
+
+  fn try_into_value(self) -> Result<T, Self> {
+    if Rc::strong_count(&self.0) == 1 {
+      self.0.refresh();
+      Ok(self.0.value.write().take().unwrap())
+    } else {
+      Err(self)
+    }
+  }
+}
+
+impl<T: 'static> StateWatcher for Computed<T> {
+  #[inline]
+  fn raw_modifies(&self) -> CloneableBoxOp<'static, ModifyScope, Infallible> {
+    self.0.notifier.raw_modifies()
+  }
+}
+
+impl<T> Drop for ComputedInner<T> {
+  fn drop(&mut self) {
+    if let Some(u) = self.upstream.get_mut().take() {
+      u.unsubscribe();
+    }
+    self.notifier.unsubscribe();
+  }
+}
+
+impl<T: std::fmt::Debug + 'static> std::fmt::Debug for Computed<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_tuple("Computed")
+      .field(&*self.read())
+      .finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn counted_sum(list: &Stateful<Vec<i32>>) -> (Computed<i32>, Rc<Cell<usize>>) {
+    let cnt = Rc::new(Cell::new(0));
+    let c_cnt = cnt.clone();
+    let sum = Computed::new(list, move |list| {
+      c_cnt.set(c_cnt.get() + 1);
+      list.iter().sum()
+    });
+    (sum, cnt)
+  }
+
+  #[test]
+  fn compute_lazily() {
+    reset_test_env!();
+
+    let list = Stateful::new(vec![1, 2, 3]);
+    let (sum, cnt) = counted_sum(&list);
+    assert_eq!(cnt.get(), 0);
+    for _ in 0..10 {
+      assert_eq!(*sum.read(), 6);
+    }
+    assert_eq!(cnt.get(), 1);
+
+    list.write().push(4);
+    AppCtx::run_until_stalled();
+    // Not read yet.
+    assert_eq!(cnt.get(), 1);
+    assert_eq!(*sum.read(), 10);
+    assert_eq!(*sum.read(), 10);
+    assert_eq!(cnt.get(), 2);
+  }
+
+  #[test]
+  fn notify_only_if_changed() {
+    reset_test_env!();
+
+    let list = Stateful::new(vec![1, 2, 3]);
+    let (sum, cnt) = counted_sum(&list);
+    let notified = Rc::new(Cell::new(0));
+    let c_notified = notified.clone();
+    sum
+      .modifies()
+      .subscribe(move |_| c_notified.set(c_notified.get() + 1));
+    assert_eq!(*sum.read(), 6);
+
+    list.write().reverse();
+    AppCtx::run_until_stalled();
+    assert_eq!(cnt.get(), 2);
+    assert_eq!(notified.get(), 0);
+
+    list.write().push(1);
+    AppCtx::run_until_stalled();
+    assert_eq!(notified.get(), 1);
+    assert_eq!(*sum.read(), 7);
+    assert_eq!(cnt.get(), 3);
+  }
+
+  #[test]
+  fn custom_eq() {
+    reset_test_env!();
+
+    let value = Stateful::new(1.);
+    let rounded = Computed::new(&value, |v: &f32| *v).with_eq(|a, b| a.round() == b.round());
+    let notified = Rc::new(Cell::new(0));
+    let c_notified = notified.clone();
+    rounded
+      .modifies()
+      .subscribe(move |_| c_notified.set(c_notified.get() + 1));
+    assert_eq!(*rounded.read(), 1.);
+
+    *value.write() = 1.2;
+    AppCtx::run_until_stalled();
+    assert_eq!(notified.get(), 0);
+    assert_eq!(*rounded.read(), 1.);
+
+    *value.write() = 2.;
+    AppCtx::run_until_stalled();
+    assert_eq!(notified.get(), 1);
+    assert_eq!(*rounded.read(), 2.);
+  }
+
+  #[test]
+  fn computed_from_states_in_pipe() {
+    reset_test_env!();
+
+    let w = Stateful::new(10.);
+    let h = Stateful::new(20.);
+    let (c_w, c_h) = (w.clone_reader(), h.clone_reader());
+    let size =
+      Computed::from_modifies(w.raw_modifies().merge(h.raw_modifies()).box_it(), move || {
+        Size::new(*c_w.read(), *c_h.read())
+      });
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox { size: pipe!(*$size) }
+    });
+    wnd.draw_frame();
+    wnd.assert_root_size(Size::new(10., 20.));
+
+    *h.write() = 30.;
+    wnd.draw_frame();
+    wnd.assert_root_size(Size::new(10., 30.));
+  }
+}
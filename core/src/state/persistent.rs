@@ -0,0 +1,383 @@
+use std::{
+  cell::RefCell,
+  collections::HashMap,
+  convert::Infallible,
+  io,
+  marker::PhantomData,
+  path::{Path, PathBuf},
+  rc::Rc,
+  time::Duration,
+};
+
+use rxrust::ops::box_it::CloneableBoxOp;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::prelude::*;
+
+/// A storage of the serialized [`PersistentState`]s.
+pub trait StateStore {
+  /// Load the data saved by the `key`.
+  fn load(&self, key: &str) -> Option<String>;
+
+  /// Save the data by the `key`.
+  fn save(&self, key: &str, data: &str) -> io::Result<()>;
+}
+
+/// A [`StateStore`] saves the data in memory, the clones of it share the
+/// same data.
+#[derive(Default, Clone)]
+pub struct MemoryStore(Rc<RefCell<HashMap<String, String>>>);
+
+/// A [`StateStore`] saves every state as a JSON file in a directory, the
+/// file is named by the key of the state.
+#[derive(Debug, Clone)]
+pub struct JsonFileStore {
+  dir: PathBuf,
+}
+
+/// A state that is saved to a [`StateStore`] and restored when it's created
+/// again, e.g. the preferences of the user.
+///
+/// The state is saved after it's modified, the saves are debounced so a
+/// series of changes is saved once. It's also saved before the application
+/// exits.
+///
+/// The state is saved with its version, if the version of the saved data is
+/// older than the version of the state, it's migrated before being restored.
+/// The default value is used if the saved data can't be restored.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let store = MemoryStore::default();
+/// let volume = PersistentState::builder("volume")
+///   .store(store.clone())
+///   .build(|| 5);
+/// *volume.write() = 8;
+/// volume.save();
+///
+/// let restored = PersistentState::builder("volume")
+///   .store(store)
+///   .build(|| 5);
+/// assert_eq!(*restored.read(), 8);
+/// ```
+pub struct PersistentState<T> {
+  state: Stateful<T>,
+  saver: Rc<Saver<T>>,
+}
+
+/// The builder of [`PersistentState`].
+pub struct PersistentStateBuilder<T> {
+  key: CowArc<str>,
+  store: Option<Rc<dyn StateStore>>,
+  version: u32,
+  migrate: Option<Box<MigrateFn>>,
+  debounce: Duration,
+  _value: PhantomData<T>,
+}
+
+/// Migrate the saved data from the version to the next version, return `None`
+/// if the data can't be migrated.
+type MigrateFn = dyn Fn(u32, Value) -> Option<Value>;
+
+struct Saver<T> {
+  key: CowArc<str>,
+  version: u32,
+  store: Rc<dyn StateStore>,
+  state: Reader<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + 'static> PersistentState<T> {
+  /// Start to build a state saved by the `key`.
+  pub fn builder(key: impl Into<CowArc<str>>) -> PersistentStateBuilder<T> {
+    PersistentStateBuilder {
+      key: key.into(),
+      store: None,
+      version: 0,
+      migrate: None,
+      debounce: Duration::from_secs(1),
+      _value: PhantomData,
+    }
+  }
+
+  /// Save the state now.
+  pub fn save(&self) { self.saver.save(); }
+}
+
+impl<T: Serialize + DeserializeOwned + 'static> PersistentStateBuilder<T> {
+  /// The store to save the state, the default store is a [`JsonFileStore`] in
+  /// the config directory of the application, or a [`MemoryStore`] if the
+  /// directory is unknown.
+  pub fn store(mut self, store: impl StateStore + 'static) -> Self {
+    self.store = Some(Rc::new(store));
+    self
+  }
+
+  /// The version of the data, the default version is `0`.
+  pub fn version(mut self, version: u32) -> Self {
+    self.version = version;
+    self
+  }
+
+  /// Migrate the saved data of an older version. The `migrate` is called with
+  /// the version of the data to upgrade it to the next version, until it's
+  /// the current version.
+  pub fn migrate(mut self, migrate: impl Fn(u32, Value) -> Option<Value> + 'static) -> Self {
+    self.migrate = Some(Box::new(migrate));
+    self
+  }
+
+  /// How long to wait after the last modification to save the state, the
+  /// default is one second.
+  pub fn debounce(mut self, debounce: Duration) -> Self {
+    self.debounce = debounce;
+    self
+  }
+
+  /// Restore the state from the store, or use the `default` value if it's not
+  /// saved or can't be restored.
+  pub fn build(self, default: impl FnOnce() -> T) -> PersistentState<T> {
+    let store = self.store.clone().unwrap_or_else(default_store);
+    let value = self.restore(&*store).unwrap_or_else(default);
+    let state = Stateful::new(value);
+    let Self { key, version, debounce, .. } = self;
+    let saver = Rc::new(Saver { key, version, store, state: state.clone_reader() });
+
+    let c_saver = saver.clone();
+    state
+      .modifies()
+      .debounce(debounce, AppCtx::scheduler())
+      .subscribe(move |_| c_saver.save());
+    let c_saver = saver.clone();
+    AppCtx::exiting()
+      .clone()
+      .subscribe(move |_| c_saver.save());
+
+    PersistentState { state, saver }
+  }
+
+  fn restore(&self, store: &dyn StateStore) -> Option<T> {
+    let data = store.load(&self.key)?;
+    let mut saved: Value = serde_json::from_str(&data)
+      .map_err(|err| log::warn!("The saved state `{}` is broken: {err}", &*self.key))
+      .ok()?;
+    let mut version = saved
+      .get("version")
+      .and_then(Value::as_u64)
+      .unwrap_or(0) as u32;
+    let mut value = saved.get_mut("value").map(Value::take)?;
+
+    if version > self.version {
+      log::warn!("The saved state `{}` is newer than the current version.", &*self.key);
+      return None;
+    }
+    while version < self.version {
+      let Some(migrate) = self.migrate.as_ref() else {
+        log::warn!("The saved state `{}` of version {version} can't be migrated.", &*self.key);
+        return None;
+      };
+      value = migrate(version, value)?;
+      version += 1;
+    }
+
+    serde_json::from_value(value)
+      .map_err(|err| log::warn!("Can't restore the saved state `{}`: {err}", &*self.key))
+      .ok()
+  }
+}
+
+impl<T: Serialize + 'static> Saver<T> {
+  fn save(&self) {
+    let saved = serde_json::json!({ "version": self.version, "value": &*self.state.read() });
+    if let Err(err) = self.store.save(&self.key, &saved.to_string()) {
+      log::error!("Save the state `{}` failed: {err}", &*self.key);
+    }
+  }
+}
+
+fn default_store() -> Rc<dyn StateStore> {
+  match JsonFileStore::for_app() {
+    Some(store) => Rc::new(store),
+    None => Rc::new(MemoryStore::default()),
+  }
+}
+
+impl StateStore for MemoryStore {
+  fn load(&self, key: &str) -> Option<String> { self.0.borrow().get(key).cloned() }
+
+  fn save(&self, key: &str, data: &str) -> io::Result<()> {
+    self
+      .0
+      .borrow_mut()
+      .insert(key.to_string(), data.to_string());
+    Ok(())
+  }
+}
+
+impl JsonFileStore {
+  /// Save the states in the `dir`.
+  pub fn new(dir: impl Into<PathBuf>) -> Self { Self { dir: dir.into() } }
+
+  /// The store in the config directory of the platform, in a directory named
+  /// by the executable of the application.
+  pub fn for_app() -> Option<Self> {
+    let exe = std::env::current_exe().ok()?;
+    let name = exe.file_stem()?;
+    Some(Self::new(config_dir()?.join(name)))
+  }
+
+  /// The directory where the states are saved.
+  pub fn dir(&self) -> &Path { &self.dir }
+
+  fn file(&self, key: &str) -> PathBuf { self.dir.join(format!("{key}.json")) }
+}
+
+impl StateStore for JsonFileStore {
+  fn load(&self, key: &str) -> Option<String> { std::fs::read_to_string(self.file(key)).ok() }
+
+  fn save(&self, key: &str, data: &str) -> io::Result<()> {
+    std::fs::create_dir_all(&self.dir)?;
+    // Write to a temporary file first, so a crash doesn't break the saved data.
+    let file = self.file(key);
+    let tmp = file.with_extension("json.tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(tmp, file)
+  }
+}
+
+fn config_dir() -> Option<PathBuf> {
+  let env_dir = |name| std::env::var_os(name).map(PathBuf::from);
+  if cfg!(target_os = "windows") {
+    env_dir("APPDATA")
+  } else if cfg!(target_os = "macos") {
+    env_dir("HOME").map(|home| home.join("Library/Application Support"))
+  } else if cfg!(target_arch = "wasm32") {
+    None
+  } else {
+    env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+  }
+}
+
+impl<T: 'static> StateReader for PersistentState<T> {
+  type Value = T;
+  type OriginReader = Stateful<T>;
+  type Reader = Reader<T>;
+
+  #[inline]
+  fn read(&self) -> ReadRef<'_, T> { self.state.read() }
+
+  #[inline]
+  fn clone_reader(&self) -> Self::Reader { self.state.clone_reader() }
+
+  #[inline]
+  fn origin_reader(&self) -> &Self::OriginReader { &self.state }
+
+  fn try_into_value(self) -> Result<T, Self> { Err(self) }
+}
+
+impl<T: 'static> StateWatcher for PersistentState<T> {
+  #[inline]
+  fn raw_modifies(&self) -> CloneableBoxOp<'static, ModifyScope, Infallible> {
+    self.state.raw_modifies()
+  }
+}
+
+impl<T: 'static> StateWriter for PersistentState<T> {
+  type Writer = Stateful<T>;
+  type OriginWriter = Stateful<T>;
+
+  fn into_reader(self) -> Result<Self::Reader, Self> { Err(self) }
+
+  #[inline]
+  fn write(&self) -> WriteRef<'_, T> { self.state.write() }
+
+  #[inline]
+  fn silent(&self) -> WriteRef<'_, T> { self.state.silent() }
+
+  #[inline]
+  fn shallow(&self) -> WriteRef<'_, T> { self.state.shallow() }
+
+  #[inline]
+  fn clone_writer(&self) -> Self::Writer { self.state.clone_writer() }
+
+  #[inline]
+  fn origin_writer(&self) -> &Self::OriginWriter { &self.state }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde::Deserialize;
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+  struct Prefs {
+    dark: bool,
+    font_size: f32,
+  }
+
+  fn prefs(store: &MemoryStore) -> PersistentState<Prefs> {
+    PersistentState::builder("prefs")
+      .store(store.clone())
+      .version(1)
+      .migrate(|version, mut value| {
+        assert_eq!(version, 0);
+        // The font size was an integer in points.
+        let size = value.get("font_size")?.as_u64()?;
+        value["font_size"] = (size as f32 * 4. / 3.).into();
+        Some(value)
+      })
+      .debounce(Duration::from_millis(100))
+      .build(|| Prefs { dark: false, font_size: 16. })
+  }
+
+  #[test]
+  fn restore_after_restart() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { @MockBox { size: Size::zero() } });
+    let store = MemoryStore::default();
+    let state = prefs(&store);
+    assert_eq!(*state.read(), Prefs { dark: false, font_size: 16. });
+
+    state.write().dark = true;
+    state.write().font_size = 20.;
+    AppCtx::run_until_stalled();
+    assert!(store.load("prefs").is_none());
+    wnd.advance_time(Duration::from_millis(100));
+    drop(state);
+
+    let state = prefs(&store);
+    assert_eq!(*state.read(), Prefs { dark: true, font_size: 20. });
+
+    // Saved before exit, without waiting for the debounce.
+    state.write().dark = false;
+    AppCtx::run_until_stalled();
+    AppCtx::exiting().clone().next(());
+    drop(state);
+    assert_eq!(*prefs(&store).read(), Prefs { dark: false, font_size: 20. });
+  }
+
+  #[test]
+  fn migrate_old_data() {
+    reset_test_env!();
+
+    let store = MemoryStore::default();
+    store
+      .save("prefs", r#"{ "version": 0, "value": { "dark": true, "font_size": 12 } }"#)
+      .unwrap();
+    assert_eq!(*prefs(&store).read(), Prefs { dark: true, font_size: 16. });
+
+    // Fallback to the default value if the data can't be restored.
+    store
+      .save("prefs", r#"{ "version": 0, "value": { "dark": true } }"#)
+      .unwrap();
+    assert_eq!(*prefs(&store).read(), Prefs { dark: false, font_size: 16. });
+    store.save("prefs", "broken").unwrap();
+    assert_eq!(*prefs(&store).read(), Prefs { dark: false, font_size: 16. });
+  }
+}
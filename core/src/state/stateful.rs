@@ -1,7 +1,7 @@
 use std::{cell::Cell, convert::Infallible};
 
 use ribir_algo::Sc;
-use rxrust::{ops::box_it::CloneableBoxOp, prelude::*};
+use rxrust::{ops::box_it::CloneableBoxOp, prelude::*, subject::SubjectSize};
 
 use super::state_cell::StateCell;
 use crate::prelude::*;
@@ -41,6 +41,8 @@ pub(crate) struct WriterInfo {
   pub(crate) writer_count: Cell<usize>,
   /// The batched modifies of the `State` which will be notified.
   pub(crate) batched_modifies: Cell<ModifyScope>,
+  /// The count of the modifies that effect the data.
+  pub(crate) data_version: Cell<usize>,
 }
 
 impl<W: 'static> StateReader for Stateful<W> {
@@ -149,6 +151,10 @@ impl<W> Stateful<W> {
     Self { data: Sc::new(StateCell::new(data)), info: Sc::new(WriterInfo::new()) }
   }
 
+  /// The count of the data modifies, it's used to tell if the data is modified
+  /// since the last time, without waiting for the notification.
+  pub(crate) fn data_version(&self) -> usize { self.info.data_version.get() }
+
   fn write_ref(&self, scope: ModifyScope) -> WriteRef<'_, W> {
     let value = self.data.write();
     WriteRef { value, modified: false, modify_scope: scope, info: &self.info }
@@ -165,6 +171,7 @@ impl WriterInfo {
     WriterInfo {
       batched_modifies: <_>::default(),
       writer_count: Cell::new(1),
+      data_version: Cell::new(0),
       notifier: <_>::default(),
     }
   }
@@ -197,6 +204,9 @@ impl Notifier {
   }
 
   pub(crate) fn next(&self, scope: ModifyScope) { self.0.clone().next(scope) }
+
+  /// If anyone subscribes to the notifier.
+  pub(crate) fn has_observers(&self) -> bool { !self.0.is_empty() }
 }
 
 impl<W: std::fmt::Debug> std::fmt::Debug for Stateful<W> {
@@ -0,0 +1,142 @@
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::prelude::*;
+
+/// A reader of a [`Stateful`] that can be sent to other threads.
+///
+/// It holds a snapshot of the state, which is updated by the UI thread after
+/// every batch of the modifies is notified. So the value read from it is
+/// always a consistent value the state had, but it may be a little older than
+/// the state, and it can't tell the modifies not notified yet.
+///
+/// The snapshot stops being updated after all the writers of the state are
+/// dropped.
+///
+/// A read only clones the `Arc` of the snapshot, so the readers never block the
+/// UI thread from updating it, however long they keep the value.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let doc = Stateful::new(String::from("Hello"));
+/// let reader = doc.sync_reader();
+/// let len = std::thread::spawn(move || reader.read().len())
+///   .join()
+///   .unwrap();
+/// assert_eq!(len, 5);
+/// ```
+pub struct SyncReader<T>(Arc<RwLock<Arc<T>>>);
+
+impl<T: Clone + 'static> Stateful<T> {
+  /// Clone the current value of the state.
+  pub fn cloned_snapshot(&self) -> T { self.read().clone() }
+
+  /// Create a reader that can read the snapshot of the state from other
+  /// threads, see [`SyncReader`].
+  pub fn sync_reader(&self) -> SyncReader<T>
+  where
+    T: Send + Sync,
+  {
+    let snapshot = Arc::new(RwLock::new(Arc::new(self.cloned_snapshot())));
+    let c_snapshot = snapshot.clone();
+    let state = self.clone_reader();
+    self.modifies().subscribe(move |_| {
+      // Only swap the `Arc` in the lock, the old snapshot is dropped after the
+      // lock is released.
+      let value = Arc::new(state.read().clone());
+      let _old = std::mem::replace(
+        &mut *c_snapshot
+          .write()
+          .unwrap_or_else(PoisonError::into_inner),
+        value,
+      );
+    });
+    SyncReader(snapshot)
+  }
+}
+
+impl<T> SyncReader<T> {
+  /// The latest snapshot, it's not changed by the later updates.
+  pub fn read(&self) -> Arc<T> {
+    self
+      .0
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .clone()
+  }
+
+  /// Clone the value of the snapshot.
+  pub fn snapshot(&self) -> T
+  where
+    T: Clone,
+  {
+    (*self.read()).clone()
+  }
+}
+
+impl<T> Clone for SyncReader<T> {
+  fn clone(&self) -> Self { Self(self.0.clone()) }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicBool, Ordering};
+
+  use super::*;
+  use crate::reset_test_env;
+
+  #[derive(Clone)]
+  struct Pair {
+    a: usize,
+    b: usize,
+  }
+
+  #[test]
+  fn read_from_other_thread() {
+    reset_test_env!();
+
+    let pair = Stateful::new(Pair { a: 0, b: 0 });
+    let reader = pair.sync_reader();
+    let stop = Arc::new(AtomicBool::new(false));
+    let c_stop = stop.clone();
+    let worker = std::thread::spawn(move || {
+      let mut last = 0;
+      while !c_stop.load(Ordering::Acquire) {
+        let Pair { a, b } = reader.snapshot();
+        // Never read a half modified value.
+        assert_eq!(b, a * 2);
+        assert!(a >= last);
+        last = a;
+      }
+      reader
+    });
+
+    for i in 1..=1000 {
+      let mut pair = pair.write();
+      pair.a = i;
+      pair.b = i * 2;
+      drop(pair);
+      AppCtx::run_until_stalled();
+    }
+    pair.write().a = 1001;
+    // Not notified yet.
+    assert_eq!(pair.cloned_snapshot().a, 1001);
+
+    stop.store(true, Ordering::Release);
+    let reader = worker.join().unwrap();
+    assert_eq!(reader.read().a, 1000);
+    pair.write().b = 2002;
+    AppCtx::run_until_stalled();
+    assert_eq!(reader.read().a, 1001);
+    assert_eq!(reader.read().b, 2002);
+
+    // A held snapshot doesn't block the update.
+    let held = reader.read();
+    pair.write().a = 1002;
+    AppCtx::run_until_stalled();
+    assert_eq!(held.a, 1001);
+    assert_eq!(reader.read().a, 1002);
+  }
+}
@@ -0,0 +1,397 @@
+use std::{
+  cell::{Cell, RefCell},
+  collections::{HashMap, VecDeque},
+  error::Error,
+  fmt::Debug,
+  rc::{Rc, Weak},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::prelude::*;
+
+/// A change of a state that the [`StateRecorder`] recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateMutation {
+  /// The index of the mutation since the recorder started, it's kept when the
+  /// older mutations are dropped from the recorder.
+  pub index: usize,
+  /// The key that the state is recorded by.
+  pub key: String,
+  pub old: Value,
+  pub new: Value,
+  /// The kind of the input that caused the mutation, see
+  /// [`AppCtx::last_input`].
+  pub input: Option<String>,
+  /// The number of the frames since the recorder started.
+  pub frame: usize,
+}
+
+/// The format that the log of the [`StateRecorder`] is exported to, and
+/// loaded from to replay it.
+pub trait MutationFormat {
+  fn encode(&self, log: &[StateMutation]) -> String;
+
+  fn decode(&self, data: &str) -> Result<Vec<StateMutation>, Box<dyn Error>>;
+}
+
+/// Export the log as a JSON array.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonFormat;
+
+/// Export the log as a JSON object per line, a tool can append the lines of
+/// the new mutations to the lines it got before.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonLinesFormat;
+
+/// Record the changes of the states to debug them by time traveling, replay
+/// the log by a [`StateReplay`] against a fresh application to the state at
+/// any mutation.
+///
+/// A state is recorded by its key, and its value is serialized for every
+/// notified change, so the modifies batched in one notification are recorded
+/// as one mutation. The recorder keeps at most `capacity` mutations, the
+/// oldest ones are dropped first.
+///
+/// The recorder is opt-in, nothing is recorded before a state is recorded
+/// by it, and it stops when all its clones are dropped.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let count = Stateful::new(0);
+/// let recorder = StateRecorder::new(100);
+/// recorder.record("count", &count);
+/// *count.write() = 1;
+/// AppCtx::run_until_stalled();
+///
+/// let log = recorder.export(&JsonFormat);
+/// let fresh = Stateful::new(0);
+/// let replay = StateReplay::decode(&log, &JsonFormat)
+///   .unwrap()
+///   .bind("count", fresh.clone_writer());
+/// replay.replay_to(0);
+/// assert_eq!(*fresh.read(), 1);
+/// ```
+#[derive(Clone)]
+pub struct StateRecorder(Rc<RecorderInner>);
+
+struct RecorderInner {
+  capacity: usize,
+  log: RefCell<VecDeque<StateMutation>>,
+  next_index: Cell<usize>,
+  frame: Cell<usize>,
+  subscriptions: RefCell<Vec<BoxSubscription<'static>>>,
+}
+
+impl StateRecorder {
+  /// Create a recorder that keeps at most `capacity` mutations.
+  pub fn new(capacity: usize) -> Self {
+    let inner = Rc::new(RecorderInner {
+      capacity,
+      log: RefCell::new(VecDeque::new()),
+      next_index: Cell::new(0),
+      frame: Cell::new(0),
+      subscriptions: RefCell::new(vec![]),
+    });
+    let weak = Rc::downgrade(&inner);
+    let u = AppCtx::frame_ticks().clone().subscribe(move |_| {
+      if let Some(inner) = Weak::upgrade(&weak) {
+        inner.frame.set(inner.frame.get() + 1);
+      }
+    });
+    inner
+      .subscriptions
+      .borrow_mut()
+      .push(BoxSubscription::new(u));
+    Self(inner)
+  }
+
+  /// Record the changes of the `state` by the `key`, the value is serialized
+  /// so the mutations can be replayed.
+  pub fn record<S>(&self, key: &str, state: &S)
+  where
+    S: StateWatcher,
+    S::Value: Serialize + Sized,
+  {
+    self.record_by(key, state, |v| serde_json::to_value(v).unwrap_or(Value::Null));
+  }
+
+  /// Record the changes of the `state` by the `key` with the `Debug` text of
+  /// its value, for the states that aren't serializable. The mutations of it
+  /// are for inspecting only, they can't be replayed.
+  pub fn record_debug<S>(&self, key: &str, state: &S)
+  where
+    S: StateWatcher,
+    S::Value: Debug,
+  {
+    self.record_by(key, state, |v| Value::String(format!("{v:?}")));
+  }
+
+  /// The mutations the recorder keeps, the oldest first.
+  pub fn mutations(&self) -> Vec<StateMutation> { self.0.log.borrow().iter().cloned().collect() }
+
+  /// The number of the mutations the recorder keeps.
+  pub fn len(&self) -> usize { self.0.log.borrow().len() }
+
+  pub fn is_empty(&self) -> bool { self.0.log.borrow().is_empty() }
+
+  /// Export the mutations in the `format`, e.g. to send them to a tool that
+  /// inspects them.
+  pub fn export(&self, format: &dyn MutationFormat) -> String {
+    format.encode(self.0.log.borrow_mut().make_contiguous())
+  }
+
+  /// Forget the recorded mutations, the states are still recorded.
+  pub fn clear(&self) { self.0.log.borrow_mut().clear(); }
+
+  fn record_by<S>(&self, key: &str, state: &S, to_value: fn(&S::Value) -> Value)
+  where
+    S: StateWatcher,
+  {
+    let reader = state.clone_reader();
+    let mut last = to_value(&reader.read());
+    let key = key.to_string();
+    let weak = Rc::downgrade(&self.0);
+    let u = state.modifies().subscribe(move |_| {
+      let Some(inner) = Weak::upgrade(&weak) else { return };
+      let new = to_value(&reader.read());
+      if new != last {
+        let old = std::mem::replace(&mut last, new.clone());
+        inner.push(key.clone(), old, new);
+      }
+    });
+    self.0.subscriptions.borrow_mut().push(u);
+  }
+}
+
+impl RecorderInner {
+  fn push(&self, key: String, old: Value, new: Value) {
+    let index = self.next_index.get();
+    self.next_index.set(index + 1);
+    let input = AppCtx::last_input().map(str::to_string);
+    let mutation = StateMutation { index, key, old, new, input, frame: self.frame.get() };
+    let mut log = self.log.borrow_mut();
+    log.push_back(mutation);
+    if log.len() > self.capacity {
+      log.pop_front();
+    }
+  }
+}
+
+impl Drop for RecorderInner {
+  fn drop(&mut self) {
+    self
+      .subscriptions
+      .get_mut()
+      .drain(..)
+      .for_each(|u| u.unsubscribe());
+  }
+}
+
+type ApplyFn = Box<dyn Fn(Value)>;
+
+/// Replay the log of a [`StateRecorder`] against the states bound by their
+/// keys, usually the states of a fresh application.
+pub struct StateReplay {
+  log: Vec<StateMutation>,
+  targets: HashMap<String, ApplyFn>,
+}
+
+impl StateReplay {
+  pub fn new(log: Vec<StateMutation>) -> Self { Self { log, targets: HashMap::new() } }
+
+  /// Load the log exported in the `format`.
+  pub fn decode(data: &str, format: &dyn MutationFormat) -> Result<Self, Box<dyn Error>> {
+    format.decode(data).map(Self::new)
+  }
+
+  /// Replay the mutations of the `key` to the `state`.
+  pub fn bind<S>(mut self, key: &str, state: S) -> Self
+  where
+    S: StateWriter,
+    S::Value: DeserializeOwned + Sized,
+  {
+    let key_name = key.to_string();
+    let apply = move |value| match serde_json::from_value(value) {
+      Ok(value) => *state.write() = value,
+      Err(err) => log::warn!("Can't replay the state `{key_name}`: {err}"),
+    };
+    self
+      .targets
+      .insert(key.to_string(), Box::new(apply));
+    self
+  }
+
+  /// The mutations to replay.
+  pub fn mutations(&self) -> &[StateMutation] { &self.log }
+
+  /// Restore the bound states to the values right after the mutation of the
+  /// `index`, the states not changed until then are restored to the values
+  /// before their first mutation in the log. It can seek backward and
+  /// forward, return how many mutations are replayed.
+  pub fn replay_to(&self, index: usize) -> usize {
+    let mut values: HashMap<&str, &Value> = HashMap::new();
+    let mut replayed = 0;
+    for m in &self.log {
+      if m.index <= index {
+        values.insert(&m.key, &m.new);
+        replayed += 1;
+      } else {
+        values.entry(&m.key).or_insert(&m.old);
+      }
+    }
+    for (key, value) in values {
+      if let Some(apply) = self.targets.get(key) {
+        apply(value.clone());
+      }
+    }
+    replayed
+  }
+}
+
+impl MutationFormat for JsonFormat {
+  fn encode(&self, log: &[StateMutation]) -> String {
+    serde_json::to_string(log).unwrap_or_default()
+  }
+
+  fn decode(&self, data: &str) -> Result<Vec<StateMutation>, Box<dyn Error>> {
+    Ok(serde_json::from_str(data)?)
+  }
+}
+
+impl MutationFormat for JsonLinesFormat {
+  fn encode(&self, log: &[StateMutation]) -> String {
+    log
+      .iter()
+      .filter_map(|m| serde_json::to_string(m).ok())
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  fn decode(&self, data: &str) -> Result<Vec<StateMutation>, Box<dyn Error>> {
+    data
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(|line| Ok(serde_json::from_str(line)?))
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn app(count: Stateful<usize>, items: Stateful<Vec<f32>>) -> impl Fn() -> Widget<'static> {
+    move || {
+      let (count, items) = (count.clone_writer(), items.clone_writer());
+      fn_widget! {
+        @MockMulti {
+          @MockBox {
+            size: pipe!(Size::new(10. + *$count as f32, 10.)),
+            on_tap: move |_| {
+              let n = *$count.write() + 1;
+              *$count.write() = n;
+              $items.write().push(n as f32 * 5.);
+            },
+          }
+          @ {
+            pipe!($items.clone()).map(|items| {
+              items.into_iter().map(|w| @MockBox { size: Size::new(w, 5.) }.into_widget())
+                .collect::<Vec<_>>()
+            })
+          }
+        }
+      }
+      .into_widget()
+    }
+  }
+
+  #[test]
+  fn replay_to_midpoint() {
+    reset_test_env!();
+
+    let size = Size::new(200., 200.);
+    let (count, items) = (Stateful::new(0), Stateful::new(vec![]));
+    let recorder = StateRecorder::new(16);
+    recorder.record("count", &count);
+    recorder.record("items", &items);
+    let mut wnd = TestWindow::new_with_size(app(count, items), size);
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let mut live_dumps = vec![];
+    for _ in 0..4 {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (2., 2.).into() });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.draw_frame();
+      let last = recorder.mutations().last().unwrap().index;
+      live_dumps.push((last, wnd.tree_dump().normalized()));
+    }
+
+    let log = recorder.mutations();
+    assert_eq!(log.len(), 8);
+    assert!(
+      log
+        .iter()
+        .all(|m| m.input.as_deref() == Some("mouse_input"))
+    );
+    assert_eq!(log[0].old, serde_json::json!(0));
+    assert_eq!(log[0].new, serde_json::json!(1));
+
+    // Replay the exported log against a fresh window.
+    let (fresh_count, fresh_items) = (Stateful::new(0), Stateful::new(vec![]));
+    let replay = StateReplay::decode(&recorder.export(&JsonLinesFormat), &JsonLinesFormat)
+      .unwrap()
+      .bind("count", fresh_count.clone_writer())
+      .bind("items", fresh_items.clone_writer());
+    let mut fresh = TestWindow::new_with_size(app(fresh_count, fresh_items), size);
+    fresh.draw_frame();
+
+    let (mid, live) = &live_dumps[1];
+    assert_eq!(replay.replay_to(*mid), 4);
+    fresh.draw_frame();
+    assert_eq!(&fresh.tree_dump().normalized(), live);
+
+    // Seek forward to the end.
+    let (end, live) = &live_dumps[3];
+    replay.replay_to(*end);
+    fresh.draw_frame();
+    assert_eq!(&fresh.tree_dump().normalized(), live);
+  }
+
+  #[test]
+  fn ring_buffer_keeps_latest() {
+    reset_test_env!();
+
+    let state = Stateful::new(0);
+    let recorder = StateRecorder::new(2);
+    recorder.record("state", &state);
+    for i in 1..=3 {
+      *state.write() = i;
+      AppCtx::run_until_stalled();
+    }
+    let indexes: Vec<_> = recorder
+      .mutations()
+      .iter()
+      .map(|m| m.index)
+      .collect();
+    assert_eq!(indexes, [1, 2]);
+
+    // Replay the truncated log from the value before its first mutation.
+    let fresh = Stateful::new(100);
+    let replay = StateReplay::new(recorder.mutations()).bind("state", fresh.clone_writer());
+    assert_eq!(replay.replay_to(0), 0);
+    assert_eq!(*fresh.read(), 1);
+    replay.replay_to(2);
+    assert_eq!(*fresh.read(), 3);
+  }
+}
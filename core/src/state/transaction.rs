@@ -0,0 +1,178 @@
+use std::convert::Infallible;
+
+use rxrust::ops::box_it::CloneableBoxOp;
+
+use crate::prelude::*;
+
+/// An edit buffer of a [`Stateful`], it edits a copy of the state and writes
+/// it back to the origin state only when it's committed.
+///
+/// The transaction is a state writer of the copy, so the widgets can bind to
+/// it like a normal `Stateful`. Meanwhile, the origin state keeps its value
+/// until the transaction is committed, and the changes are discarded if the
+/// transaction is rolled back or dropped.
+///
+/// The transactions are layered, a transaction begun from another
+/// transaction edits a copy of the outer one, and commits to the outer one.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let volume = Stateful::new(5);
+/// let tx = volume.begin_transaction();
+/// *tx.write() = 8;
+/// assert_eq!(*volume.read(), 5);
+/// tx.commit();
+/// assert_eq!(*volume.read(), 8);
+/// ```
+pub struct Transaction<W> {
+  origin: Stateful<W>,
+  copy: Stateful<W>,
+}
+
+impl<W: Clone + 'static> Stateful<W> {
+  /// Begin a transaction to edit a copy of the state, see [`Transaction`].
+  pub fn begin_transaction(&self) -> Transaction<W> {
+    let copy = Stateful::new(self.read().clone());
+    Transaction { origin: self.clone_writer(), copy }
+  }
+}
+
+impl<W: Clone + 'static> Transaction<W> {
+  /// Write the edited value back to the origin state, the origin state
+  /// notifies once.
+  pub fn commit(self) {
+    let value = self.copy.read().clone();
+    *self.origin.write() = value;
+  }
+
+  /// Discard the changes, the same as dropping the transaction.
+  pub fn rollback(self) {}
+
+  /// Begin a transaction layered on this one, it commits to this transaction.
+  pub fn begin_transaction(&self) -> Transaction<W> { self.copy.begin_transaction() }
+
+  /// The state that the transaction commits to.
+  pub fn origin(&self) -> &Stateful<W> { &self.origin }
+}
+
+impl<W: 'static> StateReader for Transaction<W> {
+  type Value = W;
+  type OriginReader = Stateful<W>;
+  type Reader = Reader<W>;
+
+  #[inline]
+  fn read(&self) -> ReadRef<'_, W> { self.copy.read() }
+
+  #[inline]
+  fn clone_reader(&self) -> Self::Reader { self.copy.clone_reader() }
+
+  #[inline]
+  fn origin_reader(&self) -> &Self::OriginReader { &self.copy }
+
+  fn try_into_value(self) -> Result<W, Self> {
+    let Self { origin, copy } = self;
+    copy
+      .try_into_value()
+      .map_err(|copy| Self { origin, copy })
+  }
+}
+
+impl<W: 'static> StateWatcher for Transaction<W> {
+  #[inline]
+  fn raw_modifies(&self) -> CloneableBoxOp<'static, ModifyScope, Infallible> {
+    self.copy.raw_modifies()
+  }
+}
+
+impl<W: 'static> StateWriter for Transaction<W> {
+  type Writer = Stateful<W>;
+  type OriginWriter = Stateful<W>;
+
+  fn into_reader(self) -> Result<Self::Reader, Self> {
+    let Self { origin, copy } = self;
+    copy
+      .into_reader()
+      .map_err(|copy| Self { origin, copy })
+  }
+
+  #[inline]
+  fn write(&self) -> WriteRef<'_, W> { self.copy.write() }
+
+  #[inline]
+  fn silent(&self) -> WriteRef<'_, W> { self.copy.silent() }
+
+  #[inline]
+  fn shallow(&self) -> WriteRef<'_, W> { self.copy.shallow() }
+
+  #[inline]
+  fn clone_writer(&self) -> Self::Writer { self.copy.clone_writer() }
+
+  #[inline]
+  fn origin_writer(&self) -> &Self::OriginWriter { &self.copy }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, rc::Rc};
+
+  use super::*;
+  use crate::reset_test_env;
+
+  fn notify_count(state: &Stateful<Vec<i32>>) -> Rc<Cell<usize>> {
+    let cnt = Rc::new(Cell::new(0));
+    let c_cnt = cnt.clone();
+    state
+      .modifies()
+      .subscribe(move |_| c_cnt.set(c_cnt.get() + 1));
+    cnt
+  }
+
+  #[test]
+  fn rollback_and_commit() {
+    reset_test_env!();
+
+    let list = Stateful::new(vec![1]);
+    let cnt = notify_count(&list);
+
+    let tx = list.begin_transaction();
+    tx.write().push(2);
+    tx.rollback();
+    AppCtx::run_until_stalled();
+    assert_eq!(*list.read(), [1]);
+    assert_eq!(cnt.get(), 0);
+
+    let tx = list.begin_transaction();
+    tx.write().push(2);
+    tx.write().push(3);
+    AppCtx::run_until_stalled();
+    assert_eq!(*list.read(), [1]);
+    tx.commit();
+    AppCtx::run_until_stalled();
+    assert_eq!(*list.read(), [1, 2, 3]);
+    assert_eq!(cnt.get(), 1);
+  }
+
+  #[test]
+  fn layered_transactions() {
+    reset_test_env!();
+
+    let list = Stateful::new(vec![1]);
+    let outer = list.begin_transaction();
+    outer.write().push(2);
+
+    let inner = outer.begin_transaction();
+    inner.write().push(3);
+    inner.commit();
+    assert_eq!(*outer.read(), [1, 2, 3]);
+    assert_eq!(*list.read(), [1]);
+
+    let inner = outer.begin_transaction();
+    inner.write().clear();
+    drop(inner);
+    outer.commit();
+    assert_eq!(*list.read(), [1, 2, 3]);
+  }
+}
@@ -0,0 +1,272 @@
+use std::{
+  cell::{Cell, RefCell},
+  collections::VecDeque,
+  rc::{Rc, Weak},
+};
+
+use crate::prelude::*;
+
+/// The undo and redo history of a [`Stateful`].
+///
+/// The history records a snapshot of the state for every notified change, so
+/// all the modifies batched in one notification, which are usually made by one
+/// user action, are undone in one step. Use [`UndoHistory::group`] to make
+/// the modifies a step by themselves.
+///
+/// The changes made by [`UndoHistory::undo`] and [`UndoHistory::redo`] are
+/// not recorded.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let doc = Stateful::new(String::new());
+/// let history = UndoHistory::new(doc.clone_writer(), 100);
+/// history.group(|| doc.write().push_str("Hello"));
+/// history.group(|| doc.write().push_str(" world"));
+/// history.undo();
+/// assert_eq!(*doc.read(), "Hello");
+/// history.redo();
+/// assert_eq!(*doc.read(), "Hello world");
+/// ```
+pub struct UndoHistory<T>(Rc<HistoryInner<T>>);
+
+/// If the [`UndoHistory`] can undo or redo, it's a state that the widgets can
+/// watch, e.g. to enable the menu items.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UndoStatus {
+  pub can_undo: bool,
+  pub can_redo: bool,
+}
+
+struct HistoryInner<T> {
+  state: Stateful<T>,
+  steps: RefCell<Steps<T>>,
+  capacity: usize,
+  /// The data version of the state when the last step is recorded.
+  recorded_version: Cell<usize>,
+  grouping: Cell<bool>,
+  status: Stateful<UndoStatus>,
+  subscription: RefCell<Option<BoxSubscription<'static>>>,
+}
+
+struct Steps<T> {
+  undo: VecDeque<T>,
+  redo: Vec<T>,
+  /// The snapshot of the state after the last recorded step.
+  current: T,
+}
+
+impl<T: Clone + 'static> UndoHistory<T> {
+  /// Record the history of the `state`, at most `capacity` steps are kept.
+  pub fn new(state: Stateful<T>, capacity: usize) -> Self {
+    let current = state.read().clone();
+    let recorded_version = Cell::new(state.data_version());
+    let inner = Rc::new(HistoryInner {
+      state,
+      steps: RefCell::new(Steps { undo: VecDeque::new(), redo: vec![], current }),
+      capacity,
+      recorded_version,
+      grouping: Cell::new(false),
+      status: Stateful::new(UndoStatus::default()),
+      subscription: RefCell::new(None),
+    });
+
+    let weak = Rc::downgrade(&inner);
+    let u = inner.state.modifies().subscribe(move |_| {
+      if let Some(inner) = Weak::upgrade(&weak) {
+        inner.record_pending();
+      }
+    });
+    *inner.subscription.borrow_mut() = Some(u);
+    Self(inner)
+  }
+
+  /// Run `f` and record its modifies as one step. The modifies not recorded
+  /// before are recorded as a step before it.
+  pub fn group(&self, f: impl FnOnce()) {
+    let inner = &self.0;
+    if inner.grouping.get() {
+      // A nested group is a part of the outer one.
+      f();
+      return;
+    }
+    inner.record_pending();
+    inner.grouping.set(true);
+    f();
+    inner.grouping.set(false);
+    inner.record_pending();
+  }
+
+  /// Restore the state to the previous step, return if there was a step to
+  /// undo.
+  pub fn undo(&self) -> bool {
+    let inner = &self.0;
+    inner.record_pending();
+    let mut steps = inner.steps.borrow_mut();
+    let Some(prev) = steps.undo.pop_back() else { return false };
+    let current = std::mem::replace(&mut steps.current, prev.clone());
+    steps.redo.push(current);
+    drop(steps);
+    inner.replay(prev);
+    true
+  }
+
+  /// Restore the state to the step undone last time, return if there was a
+  /// step to redo.
+  pub fn redo(&self) -> bool {
+    let inner = &self.0;
+    inner.record_pending();
+    let mut steps = inner.steps.borrow_mut();
+    let Some(next) = steps.redo.pop() else { return false };
+    let current = std::mem::replace(&mut steps.current, next.clone());
+    steps.undo.push_back(current);
+    drop(steps);
+    inner.replay(next);
+    true
+  }
+}
+
+impl<T> UndoHistory<T> {
+  /// The state that the history records.
+  pub fn state(&self) -> &Stateful<T> { &self.0.state }
+
+  pub fn can_undo(&self) -> bool { self.0.status.read().can_undo }
+
+  pub fn can_redo(&self) -> bool { self.0.status.read().can_redo }
+
+  /// The state of whether the history can undo or redo.
+  pub fn status(&self) -> Watcher<Reader<UndoStatus>> { self.0.status.clone_watcher() }
+}
+
+impl<T: Clone + 'static> HistoryInner<T> {
+  /// Record the modifies since the last step as a new step.
+  fn record_pending(&self) {
+    let version = self.state.data_version();
+    if self.grouping.get() || self.recorded_version.get() == version {
+      return;
+    }
+    self.recorded_version.set(version);
+    let mut steps = self.steps.borrow_mut();
+    let current = std::mem::replace(&mut steps.current, self.state.read().clone());
+    steps.undo.push_back(current);
+    if steps.undo.len() > self.capacity {
+      steps.undo.pop_front();
+    }
+    steps.redo.clear();
+    drop(steps);
+    self.update_status();
+  }
+
+  fn replay(&self, value: T) {
+    *self.state.write() = value;
+    self
+      .recorded_version
+      .set(self.state.data_version());
+    self.update_status();
+  }
+
+  fn update_status(&self) {
+    let steps = self.steps.borrow();
+    let status = UndoStatus { can_undo: !steps.undo.is_empty(), can_redo: !steps.redo.is_empty() };
+    if *self.status.read() != status {
+      *self.status.write() = status;
+    }
+  }
+}
+
+impl<T> Drop for HistoryInner<T> {
+  fn drop(&mut self) {
+    if let Some(u) = self.subscription.get_mut().take() {
+      u.unsubscribe();
+    }
+  }
+}
+
+impl<T> Clone for UndoHistory<T> {
+  fn clone(&self) -> Self { Self(self.0.clone()) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::reset_test_env;
+
+  fn assert_status(history: &UndoHistory<Vec<i32>>, can_undo: bool, can_redo: bool) {
+    assert_eq!(history.can_undo(), can_undo);
+    assert_eq!(history.can_redo(), can_redo);
+  }
+
+  #[test]
+  fn undo_redo_groups() {
+    reset_test_env!();
+
+    let list = Stateful::new(vec![]);
+    let history = UndoHistory::new(list.clone_writer(), 10);
+    assert_status(&history, false, false);
+
+    history.group(|| list.write().push(1));
+    history.group(|| {
+      list.write().push(2);
+      list.write().push(3);
+    });
+    history.group(|| list.write().push(4));
+    AppCtx::run_until_stalled();
+    assert_eq!(*list.read(), [1, 2, 3, 4]);
+    assert_status(&history, true, false);
+
+    assert!(history.undo());
+    AppCtx::run_until_stalled();
+    assert_eq!(*list.read(), [1, 2, 3]);
+    assert_status(&history, true, true);
+
+    assert!(history.undo());
+    AppCtx::run_until_stalled();
+    assert_eq!(*list.read(), [1]);
+    assert_status(&history, true, true);
+
+    assert!(history.redo());
+    AppCtx::run_until_stalled();
+    assert_eq!(*list.read(), [1, 2, 3]);
+    assert_status(&history, true, true);
+
+    // A new change drops the steps to redo.
+    list.write().push(5);
+    AppCtx::run_until_stalled();
+    assert_status(&history, true, false);
+    assert!(history.undo());
+    assert_eq!(*list.read(), [1, 2, 3]);
+  }
+
+  #[test]
+  fn notified_changes_as_steps() {
+    reset_test_env!();
+
+    let list = Stateful::new(vec![]);
+    let history = UndoHistory::new(list.clone_writer(), 2);
+    let status = history.status();
+    let notified = Rc::new(Cell::new(0));
+    let c_notified = notified.clone();
+    status
+      .modifies()
+      .subscribe(move |_| c_notified.set(c_notified.get() + 1));
+
+    for i in 0..3 {
+      // Batched in one step.
+      list.write().push(i);
+      list.write().push(i);
+      AppCtx::run_until_stalled();
+    }
+    assert_eq!(notified.get(), 1);
+
+    // Only two steps are kept.
+    assert!(history.undo());
+    assert!(history.undo());
+    assert!(!history.undo());
+    AppCtx::run_until_stalled();
+    assert_eq!(*list.read(), [0, 0]);
+    assert_eq!(*status.read(), UndoStatus { can_undo: false, can_redo: true });
+    assert_eq!(notified.get(), 2);
+  }
+}
@@ -1,4 +1,7 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{
+  Arc, LazyLock,
+  atomic::{AtomicU64, Ordering},
+};
 
 #[cfg(test)]
 #[cfg(target_family = "wasm")]
@@ -10,6 +13,7 @@ wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
 pub use crate::timer::Timer;
 use crate::{
+  clock::{Clock, TestClock},
   prelude::*,
   window::{ShellWindow, WindowFlags, WindowId},
 };
@@ -25,8 +29,15 @@ pub fn split_value<T: 'static>(v: T) -> (Watcher<Reader<T>>, Stateful<T>) {
   (src.clone_watcher(), src.clone_writer())
 }
 
+/// The clock shared by all the test windows.
+pub(crate) static TEST_CLOCK: LazyLock<Arc<TestClock>> = LazyLock::new(<_>::default);
+
 /// The Window assists in writing unit tests; animations are disabled by
 /// default.
+///
+/// The time of the application is driven by a [`TestClock`] once a test
+/// window is created, so the time only goes forward by
+/// [`TestWindow::advance_time`].
 #[derive(Clone)]
 pub struct TestWindow(pub Sc<Window>);
 
@@ -38,6 +49,35 @@ macro_rules! reset_test_env {
   };
 }
 
+/// Draw the frames of a settled window and fail if any of them allocates more
+/// than `max_per_frame` times, e.g. a static window should not allocate in its
+/// idle frames. The frames are drawn 16ms apart, 10 frames by default.
+///
+/// It needs the `ribir_alloc_track` feature.
+#[cfg(feature = "ribir_alloc_track")]
+#[macro_export]
+macro_rules! assert_steady_state_allocs {
+  ($wnd:expr, $max_per_frame:expr) => {
+    $crate::assert_steady_state_allocs!($wnd, $max_per_frame, 10)
+  };
+  ($wnd:expr, $max_per_frame:expr, $frames:expr) => {{
+    let wnd: &mut $crate::test_helper::TestWindow = &mut $wnd;
+    // The first frames fill the caches, they're not the steady state.
+    wnd.draw_frame();
+    wnd.draw_frame();
+    for frame in 0..$frames {
+      wnd.advance_time($crate::prelude::Duration::from_millis(16));
+      let allocs = wnd.frame_stats().allocs;
+      let count = allocs.total().count;
+      assert!(
+        count <= $max_per_frame,
+        "the steady frame {frame} allocates {count} times, over {}: {allocs:?}",
+        $max_per_frame
+      );
+    }
+  }};
+}
+
 impl TestWindow {
   /// Create a 1024x1024 window for test
   pub fn new(root: impl Into<GenWidget>) -> Self { Self::new_wnd(root, None) }
@@ -54,6 +94,7 @@ impl TestWindow {
 
   fn new_wnd(root: impl Into<GenWidget>, size: Option<Size>) -> Self {
     let _ = NEW_TIMER_FN.set(Timer::new_timer_future);
+    clock::set_clock(Some(TEST_CLOCK.clone() as Arc<dyn Clock>));
     AppCtx::run_until_stalled();
 
     let wnd = AppCtx::new_window(Box::new(TestShellWindow::new(size)), root.into());
@@ -79,6 +120,46 @@ impl TestWindow {
     tree.store.layout_info(node).cloned()
   }
 
+  /// Dump the content of the window as text, a line for every widget with its
+  /// type name and its layout rect, the children are indented below their
+  /// parent.
+  ///
+  /// It's helpful to assert two widgets build the same tree.
+  pub fn dump_tree(&self) -> String {
+    // Only the content under the root.
+    self
+      .tree_dump()
+      .nodes
+      .iter()
+      .filter(|n| n.path.first() == Some(&0))
+      .map(|n| {
+        let indent = (n.path.len() - 1) * 2;
+        format!("{:indent$}{} {:?}\n", "", n.name, n.rect)
+      })
+      .collect()
+  }
+
+  /// Visit every laid out widget of the window in the order of
+  /// [`TestWindow::dump_tree`], with its type name, its layout info and its
+  /// position in the window.
+  pub fn walk_layout(&self, mut f: impl FnMut(&str, &LayoutInfo, Point)) {
+    fn walk(
+      tree: &WidgetTree, id: WidgetId, offset: Point, f: &mut dyn FnMut(&str, &LayoutInfo, Point),
+    ) {
+      let Some(info) = tree.store.layout_info(id) else { return };
+      let pos = offset + info.pos.to_vector();
+      f(&id.assert_get(tree).debug_name(), info, pos);
+      for c in id.children(tree) {
+        walk(tree, c, pos, f);
+      }
+    }
+
+    let tree = self.0.tree();
+    if let Some(content) = tree.root().first_child(tree) {
+      walk(tree, content, Point::zero(), &mut f);
+    }
+  }
+
   pub fn take_last_frame(&mut self) -> Option<Frame> {
     self
       .shell_wnd()
@@ -104,9 +185,25 @@ impl TestWindow {
     AppCtx::run_until_stalled();
     self.run_frame_tasks();
 
-    AppCtx::frame_ticks().clone().next(Instant::now());
+    AppCtx::frame_ticks().clone().next(clock::now());
     self.0.draw_frame();
   }
+
+  /// Move the time forward by `dur` and draw a frame. Every timer that comes
+  /// due in this period is fired at its own time in order, so an interval
+  /// emits as many times as it does in the real time.
+  #[track_caller]
+  pub fn advance_time(&mut self, dur: Duration) {
+    let target = TEST_CLOCK.now() + dur;
+    while let Some(at) = Timer::recently_timeout().filter(|at| *at <= target) {
+      TEST_CLOCK.advance_to(at);
+      Timer::wake_timeout_futures();
+      AppCtx::run_until_stalled();
+      self.run_frame_tasks();
+    }
+    TEST_CLOCK.advance_to(target);
+    self.draw_frame();
+  }
 }
 
 impl std::ops::Deref for TestWindow {
@@ -118,9 +215,27 @@ impl std::ops::Deref for TestWindow {
 pub struct TestShellWindow {
   pub size: Size,
   pub cursor: CursorIcon,
+  /// Whether the cursor of the platform is shown, the test window can't use
+  /// the image cursors, so it's hidden when the window paints them.
+  pub cursor_visible: bool,
   pub id: WindowId,
   pub surface_color: Color,
   pub last_frame: Option<Frame>,
+  /// The window drags that are begun, in order.
+  pub drags: Vec<WindowDrag>,
+  pub minimized: bool,
+  pub maximized: bool,
+  /// Whether the window is asked to close.
+  pub close_requested: bool,
+  /// The calls to lock or release the pointer, in order.
+  pub pointer_locks: Vec<bool>,
+}
+
+/// A window drag that a [`TestShellWindow`] is asked to begin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowDrag {
+  Move,
+  Resize(ResizeEdge),
 }
 
 impl ShellWindow for TestShellWindow {
@@ -141,6 +256,10 @@ impl ShellWindow for TestShellWindow {
 
   fn cursor(&self) -> CursorIcon { self.cursor }
 
+  fn set_image_cursor(&mut self, _: &ImageCursor) -> bool { false }
+
+  fn set_cursor_visible(&mut self, visible: bool) { self.cursor_visible = visible; }
+
   fn set_title(&mut self, _: &str) {}
 
   fn set_icon(&mut self, _: &PixelImage) {}
@@ -159,9 +278,21 @@ impl ShellWindow for TestShellWindow {
 
   fn set_decorations(&mut self, _: bool) {}
 
-  fn is_minimized(&self) -> bool { false }
+  fn begin_move_drag(&mut self) { self.drags.push(WindowDrag::Move); }
+
+  fn begin_resize_drag(&mut self, edge: ResizeEdge) { self.drags.push(WindowDrag::Resize(edge)); }
+
+  fn set_pointer_locked(&mut self, locked: bool) { self.pointer_locks.push(locked); }
+
+  fn is_minimized(&self) -> bool { self.minimized }
+
+  fn set_minimized(&mut self, minimized: bool) { self.minimized = minimized; }
+
+  fn is_maximized(&self) -> bool { self.maximized }
+
+  fn set_maximized(&mut self, maximized: bool) { self.maximized = maximized; }
 
-  fn set_minimized(&mut self, _: bool) {}
+  fn request_close(&mut self) { self.close_requested = true; }
 
   fn set_ime_allowed(&mut self, _: bool) {}
 
@@ -190,9 +321,15 @@ impl TestShellWindow {
     TestShellWindow {
       size,
       cursor: CursorIcon::Default,
+      cursor_visible: true,
       id: ID.fetch_add(1, Ordering::Relaxed).into(),
       last_frame: None,
       surface_color: Color::WHITE,
+      drags: vec![],
+      minimized: false,
+      maximized: false,
+      close_requested: false,
+      pointer_locks: vec![],
     }
   }
 }
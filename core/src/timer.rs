@@ -11,7 +11,10 @@ use std::{
 
 use rxrust::scheduler::BoxFuture;
 
-use crate::ticker::{Duration, Instant};
+use crate::{
+  clock,
+  ticker::{Duration, Instant},
+};
 
 #[derive(Default)]
 pub(crate) struct TimeReactor {
@@ -66,14 +69,14 @@ impl Timer {
   pub fn recently_timeout() -> Option<Instant> { TIME_REACTOR.lock().unwrap().recently_timeout() }
 
   pub fn new_timer_future(dur: Duration) -> BoxFuture<'static, ()> {
-    Box::pin(Timer::new(Instant::now() + dur))
+    Box::pin(Timer::new(clock::now() + dur))
   }
 
   pub fn wake_timeout_futures() {
     let notifies = TIME_REACTOR
       .lock()
       .unwrap()
-      .timeout_wakers(Instant::now());
+      .timeout_wakers(clock::now());
     notifies.for_each(|waker| waker.wake());
   }
 }
@@ -83,7 +86,7 @@ impl Future for Timer {
   fn poll(
     mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>,
   ) -> std::task::Poll<Self::Output> {
-    let now = Instant::now();
+    let now = clock::now();
     let when = self.as_ref().when;
     if let Some(id) = self.as_mut().id.take() {
       TIME_REACTOR
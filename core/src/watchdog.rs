@@ -0,0 +1,346 @@
+//! Catch the frames that take longer than their budget in production, and
+//! report why they're slow. See [`AppCtx::set_frame_watchdog`].
+//!
+//! While the watchdog is on, the windows time the layout and the paint of
+//! every widget, and a frame that takes longer than the threshold is reported
+//! as a [`JankReport`] with the widgets that cost it the most.
+
+use std::{collections::VecDeque, convert::Infallible};
+
+use rxrust::prelude::*;
+
+use crate::{
+  clock,
+  context::AppCtx,
+  prelude::{Duration, Instant},
+  widget_tree::{WidgetId, WidgetTree},
+  window::{Window, WindowId},
+};
+
+/// The settings of the frame watchdog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameWatchdog {
+  /// A frame that takes longer than it is reported.
+  pub threshold: Duration,
+  /// The most reports kept by [`AppCtx::jank_reports`], the oldest ones are
+  /// dropped.
+  pub capacity: usize,
+  /// The shortest time between two reports, the slow frames in between are
+  /// only counted in the next report.
+  pub min_interval: Duration,
+  /// How many widgets of the largest costs a report names.
+  pub top_widgets: usize,
+}
+
+/// A frame that took longer than the threshold of the [`FrameWatchdog`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct JankReport {
+  /// The window that drew the frame.
+  pub window: WindowId,
+  /// When the frame started.
+  pub at: Instant,
+  /// The whole time of the frame.
+  pub total: Duration,
+  /// Running the tasks and the animations before the layout.
+  pub tasks: Duration,
+  /// Laying out the widgets.
+  pub layout: Duration,
+  /// Painting the widgets.
+  pub paint: Duration,
+  /// Submitting the painted commands to the render backend and presenting
+  /// them.
+  pub present: Duration,
+  /// The widgets that took the longest to lay out and paint themselves, the
+  /// costliest first. The time of a widget doesn't include its children.
+  pub widgets: Vec<WidgetCost>,
+  /// The count of the widgets in the window.
+  pub tree_size: usize,
+  /// The kind of the input event that triggered the frame, see
+  /// [`AppCtx::last_input`].
+  pub input: Option<&'static str>,
+  /// The slow frames before this one that weren't reported, because they
+  /// were too close to the previous report.
+  pub suppressed: usize,
+}
+
+/// The time that a widget took in a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WidgetCost {
+  /// The type of the widget, and where it's declared if it's known.
+  pub name: String,
+  pub layout: Duration,
+  pub paint: Duration,
+}
+
+impl Default for FrameWatchdog {
+  fn default() -> Self {
+    Self {
+      threshold: Duration::from_millis(50),
+      capacity: 32,
+      min_interval: Duration::from_secs(1),
+      top_widgets: 5,
+    }
+  }
+}
+
+impl WidgetCost {
+  /// The whole time of the widget in the frame.
+  pub fn total(&self) -> Duration { self.layout + self.paint }
+}
+
+/// The state of the frame watchdog of the application.
+#[derive(Default)]
+pub(crate) struct Watchdog {
+  settings: Option<FrameWatchdog>,
+  reports: VecDeque<JankReport>,
+  last_report: Option<Instant>,
+  suppressed: usize,
+}
+
+/// The layout and paint time of the widgets in the current frame of a
+/// window, only timed while the watchdog is on.
+#[derive(Default)]
+pub(crate) struct FrameCosts {
+  enabled: bool,
+  /// The layout time of the children of the widget in layout.
+  children_layout: Duration,
+  layout: Vec<(WidgetId, Duration)>,
+  paint: Vec<(WidgetId, Duration)>,
+}
+
+/// The starts of the phases of a frame.
+pub(crate) struct FramePhases {
+  pub(crate) start: Instant,
+  pub(crate) layout: Option<Instant>,
+  pub(crate) paint: Option<Instant>,
+  pub(crate) present: Option<Instant>,
+}
+
+impl FrameCosts {
+  /// Start to time a new frame, the widgets are timed only if `enabled`.
+  pub(crate) fn begin(&mut self, enabled: bool) {
+    self.enabled = enabled;
+    self.children_layout = Duration::ZERO;
+    self.layout.clear();
+    self.paint.clear();
+  }
+
+  pub(crate) fn is_enabled(&self) -> bool { self.enabled }
+
+  /// Start to time the layout of a widget, return the layout time of its
+  /// siblings to restore.
+  pub(crate) fn start_layout(&mut self) -> Option<(Instant, Duration)> {
+    self
+      .enabled
+      .then(|| (clock::now(), std::mem::take(&mut self.children_layout)))
+  }
+
+  pub(crate) fn end_layout(&mut self, id: WidgetId, (start, siblings): (Instant, Duration)) {
+    let elapsed = clock::now() - start;
+    let own = elapsed.saturating_sub(self.children_layout);
+    self.children_layout = siblings + elapsed;
+    self.layout.push((id, own));
+  }
+
+  pub(crate) fn add_paint(&mut self, id: WidgetId, cost: Duration) { self.paint.push((id, cost)); }
+
+  /// The widgets of the largest costs, the costliest first.
+  fn top_widgets(&self, tree: &WidgetTree, count: usize) -> Vec<WidgetCost> {
+    let mut costs: ahash::HashMap<WidgetId, (Duration, Duration)> = <_>::default();
+    for (id, cost) in &self.layout {
+      costs.entry(*id).or_default().0 += *cost;
+    }
+    for (id, cost) in &self.paint {
+      costs.entry(*id).or_default().1 += *cost;
+    }
+    let mut costs: Vec<_> = costs.into_iter().collect();
+    costs.sort_by_key(|(_, (layout, paint))| std::cmp::Reverse(*layout + *paint));
+    costs
+      .into_iter()
+      .take(count)
+      .map(|(id, (layout, paint))| WidgetCost { name: tree.describe_provenance(id), layout, paint })
+      .collect()
+  }
+}
+
+impl AppCtx {
+  /// Turn on the frame watchdog with its `settings`, or turn it off with
+  /// `None`. The reports are kept by [`AppCtx::jank_reports`] and emitted by
+  /// [`AppCtx::on_jank`].
+  pub fn set_frame_watchdog(settings: Option<FrameWatchdog>) {
+    let mut watchdog = Self::shared().watchdog.borrow_mut();
+    if let Some(settings) = &settings {
+      while watchdog.reports.len() > settings.capacity {
+        watchdog.reports.pop_front();
+      }
+    }
+    watchdog.settings = settings;
+  }
+
+  /// The settings of the frame watchdog, `None` if it's off.
+  pub fn frame_watchdog() -> Option<FrameWatchdog> {
+    Self::shared().watchdog.borrow().settings.clone()
+  }
+
+  /// The latest reports of the slow frames, the oldest first.
+  pub fn jank_reports() -> Vec<JankReport> {
+    Self::shared()
+      .watchdog
+      .borrow()
+      .reports
+      .iter()
+      .cloned()
+      .collect()
+  }
+
+  /// A stream emits every report of a slow frame, e.g. to send it to the
+  /// telemetry.
+  pub fn on_jank() -> &'static Subject<'static, JankReport, Infallible> { &Self::shared().jank }
+
+  /// Forget the reports of the slow frames.
+  pub fn clear_jank_reports() {
+    Self::shared()
+      .watchdog
+      .borrow_mut()
+      .reports
+      .clear();
+  }
+
+  pub(crate) fn is_watchdog_on() -> bool {
+    Self::shared()
+      .watchdog
+      .borrow()
+      .settings
+      .is_some()
+  }
+
+  /// Report the frame of the `wnd` if it's slower than the threshold.
+  pub(crate) fn watch_frame(wnd: &Window, phases: FramePhases) {
+    let now = clock::now();
+    let total = now - phases.start;
+    let mut watchdog = Self::shared().watchdog.borrow_mut();
+    let Some(settings) = watchdog.settings.clone() else { return };
+    if total < settings.threshold {
+      return;
+    }
+    let limited = watchdog
+      .last_report
+      .is_some_and(|last| now.saturating_duration_since(last) < settings.min_interval);
+    if limited {
+      watchdog.suppressed += 1;
+      return;
+    }
+
+    let FramePhases { start, layout, paint, present } = phases;
+    let between = |from: Option<Instant>, to: Option<Instant>| match (from, to) {
+      (Some(from), Some(to)) => to - from,
+      _ => Duration::ZERO,
+    };
+    let tree = wnd.tree();
+    let report = JankReport {
+      window: wnd.id(),
+      at: start,
+      total,
+      tasks: layout.unwrap_or(now) - start,
+      layout: between(layout, paint),
+      paint: between(paint, present),
+      present: between(present, Some(now)),
+      widgets: tree
+        .frame_costs
+        .borrow()
+        .top_widgets(tree, settings.top_widgets),
+      tree_size: tree.count(tree.root()),
+      input: AppCtx::last_input(),
+      suppressed: std::mem::take(&mut watchdog.suppressed),
+    };
+    watchdog.last_report = Some(now);
+    if watchdog.reports.len() >= settings.capacity {
+      watchdog.reports.pop_front();
+    }
+    if settings.capacity > 0 {
+      watchdog.reports.push_back(report.clone());
+    }
+    drop(watchdog);
+    AppCtx::on_jank().clone().next(report);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
+  use super::*;
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  struct SlowRender(Duration);
+
+  impl Render for SlowRender {
+    fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size {
+      TEST_CLOCK.advance(self.0);
+      clamp.max
+    }
+  }
+
+  #[test]
+  fn report_slow_frame() {
+    reset_test_env!();
+
+    AppCtx::set_frame_watchdog(Some(FrameWatchdog {
+      threshold: Duration::from_millis(20),
+      ..Default::default()
+    }));
+    let received = Rc::new(RefCell::new(vec![]));
+    let c_received = received.clone();
+    let _sub = AppCtx::on_jank()
+      .clone()
+      .subscribe(move |r: JankReport| {
+        c_received.borrow_mut().push(r.window);
+      });
+
+    let slow = Stateful::new(Duration::from_millis(30));
+    let c_slow = slow.clone_watcher();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockMulti {
+          @MockBox { size: Size::new(10., 10.) }
+          @ { pipe!(SlowRender(*$c_slow)) }
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+
+    let reports = AppCtx::jank_reports();
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.total, Duration::from_millis(30));
+    assert_eq!(report.layout, Duration::from_millis(30));
+    assert!(report.widgets[0].name.contains("SlowRender"));
+    assert_eq!(report.widgets[0].layout, Duration::from_millis(30));
+    assert!(report.tree_size >= 3);
+    assert_eq!(*received.borrow(), [wnd.id()]);
+
+    // The next slow frame is too close to the last report.
+    *slow.write() = Duration::from_millis(25);
+    wnd.draw_frame();
+    assert_eq!(AppCtx::jank_reports().len(), 1);
+
+    // A fast frame is not reported.
+    AppCtx::clear_jank_reports();
+    AppCtx::set_frame_watchdog(Some(FrameWatchdog {
+      threshold: Duration::from_millis(20),
+      min_interval: Duration::ZERO,
+      ..Default::default()
+    }));
+    *slow.write() = Duration::ZERO;
+    wnd.draw_frame();
+    assert!(AppCtx::jank_reports().is_empty());
+
+    *slow.write() = Duration::from_millis(25);
+    wnd.draw_frame();
+    let reports = AppCtx::jank_reports();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].suppressed, 1);
+    AppCtx::set_frame_watchdog(None);
+  }
+}
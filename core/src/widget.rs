@@ -12,7 +12,13 @@ use smallvec::SmallVec;
 use widget_id::RenderQueryable;
 
 pub(crate) use crate::widget_tree::*;
-use crate::{context::*, prelude::*, render_helper::PureRender};
+use crate::{
+  build_profile::{BuildKind, BuildSpan},
+  context::*,
+  prelude::*,
+  render_helper::PureRender,
+  widget_tree::{TreePhase, guard_widget_panic},
+};
 pub trait Compose: Sized {
   /// Describes the part of the user interface represented by this widget.
   /// Called by framework, should never directly call it.
@@ -26,6 +32,54 @@ pub struct HitTest {
 
 /// RenderWidget is a widget which want to paint something or do a layout to
 /// calc itself size and update children positions.
+///
+/// Implement it to create your own low-level widget, the framework treats it
+/// the same as the builtin ones:
+///
+/// - derive `Declare` to declare it by `@`, and `SingleChild` or `MultiChild`
+///   if it accepts children.
+/// - use it as a widget directly, or by a `Stateful` of it if it changes, every
+///   modification of the state marks it dirty to relayout and repaint.
+/// - the framework caches its layout result, `perform_layout` is only called
+///   when it's dirty or its constraints changed. Return `true` from
+///   `only_sized_by_parent` if its size only depends on the constraints, then
+///   its change doesn't relayout its parent.
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// /// A bar that fills the `ratio` of the width it's given.
+/// #[derive(Declare)]
+/// struct Bar {
+///   ratio: f32,
+/// }
+///
+/// impl Render for Bar {
+///   fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size {
+///     clamp.clamp(Size::new(clamp.max.width, 4.))
+///   }
+///
+///   fn only_sized_by_parent(&self) -> bool { true }
+///
+///   fn paint(&self, ctx: &mut PaintingCtx) {
+///     let size = ctx.box_size().unwrap();
+///     let rect = Rect::from_size(Size::new(size.width * self.ratio, size.height));
+///     ctx
+///       .painter()
+///       .set_fill_brush(Color::RED)
+///       .rect(&rect)
+///       .fill();
+///   }
+/// }
+///
+/// let bar = Stateful::new(Bar { ratio: 0.3 });
+/// let c_bar = bar.clone_writer();
+/// let _w = fn_widget! {
+///   @ { c_bar.clone_writer() }
+/// };
+/// // Repaints the bar in the next frame.
+/// bar.write().ratio = 0.6;
+/// ```
 pub trait Render: 'static {
   /// Do the work of computing the layout for this widget, and return the
   /// size it need.
@@ -54,8 +108,41 @@ pub trait Render: 'static {
     HitTest { hit, can_hit_child: hit || !self.only_sized_by_parent() }
   }
 
+  /// The area where the widget can be hit, in the coordinate of its parent, or
+  /// `None` if the area can't be bounded by a rect. The box of the widget by
+  /// default.
+  ///
+  /// The pointer skips the widgets whose area and the areas of their
+  /// descendants don't contain it, so a widget that overrides `hit_test` to be
+  /// hit outside its box must override this too.
+  fn hit_bounds(&self, ctx: &HitTestCtx) -> Option<Rect> {
+    Some(ctx.box_rect().unwrap_or_default())
+  }
+
   /// Return a transform to map the coordinate from its parent to this widget.
   fn get_transform(&self) -> Option<Transform> { None }
+
+  /// The opacity that the widget paints itself and its descendants with.
+  fn get_opacity(&self) -> f32 { 1. }
+
+  /// Whether the widget and its descendants are displayed.
+  fn is_displayed(&self) -> bool { true }
+
+  /// The order that the widget is painted and hit among its siblings, see
+  /// [`FatObj::z_index`].
+  fn z_index(&self) -> i32 { 0 }
+
+  /// The name to describe the widget in the debug messages, the type name by
+  /// default.
+  fn debug_name(&self) -> std::borrow::Cow<'static, str> { std::any::type_name::<Self>().into() }
+
+  /// The text that the widget shows, a focusable widget without a label is
+  /// named by the texts in it, see [`Window::accessible_name`].
+  fn text_content(&self) -> Option<CowArc<str>> { None }
+
+  /// The baselines of the text lines that the widget shows, in its own
+  /// coordinate, see [`DebugOverlay::Baselines`].
+  fn text_baselines(&self) -> Vec<f32> { vec![] }
 }
 
 /// The common type of all widget can convert to.
@@ -118,6 +205,11 @@ pub const FN: usize = 3;
 /// implemented by the framework.
 ///
 /// Instead, focus on implementing `Compose`, `Render`, or `ComposeChild`.
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` is not a widget: it does not implement `IntoWidget`",
+  label = "not a widget",
+  note = "implement `Compose`, `Render` or `ComposeChild` for `{Self}`, or use a function widget"
+)]
 pub trait IntoWidget<'w, const M: usize>: 'w {
   fn into_widget(self) -> Widget<'w>;
 }
@@ -126,6 +218,11 @@ pub trait IntoWidget<'w, const M: usize>: 'w {
 /// `IntoWidget`, this trait is not implemented for `Widget` itself. This design
 /// choice allows the framework to use either `IntoWidget` or `IntoWidgetStrict`
 /// as a generic bound, preventing implementation conflicts.
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` is not a widget: it does not implement `IntoWidget`",
+  label = "not a widget",
+  note = "implement `Compose`, `Render` or `ComposeChild` for `{Self}`, or use a function widget"
+)]
 pub(crate) trait IntoWidgetStrict<'w, const M: usize>: 'w {
   fn into_widget_strict(self) -> Widget<'w>;
 }
@@ -149,17 +246,37 @@ impl<'w> IntoWidget<'w, FN> for Widget<'w> {
 
 impl<'w, const M: usize, T: IntoWidgetStrict<'w, M>> IntoWidget<'w, M> for T {
   #[inline(always)]
+  #[track_caller]
   fn into_widget(self) -> Widget<'w> { self.into_widget_strict() }
 }
 
 impl<C: Compose + 'static> IntoWidgetStrict<'static, COMPOSE> for C {
   #[inline]
+  #[track_caller]
   fn into_widget_strict(self) -> Widget<'static> {
-    Compose::compose(State::value(self)).into_widget()
+    let Some(span) = BuildSpan::start() else {
+      return Compose::compose(State::value(self)).into_widget();
+    };
+    // The build closure of the widget runs when the widget is built, so it's
+    // timed in two parts, but counted once.
+    let name = std::any::type_name::<C>();
+    let location = Some(std::panic::Location::caller());
+    let w = Compose::compose(State::value(self)).into_widget();
+    span.end(BuildKind::Compose, name, location, false);
+    let lazy = move || {
+      let span = BuildSpan::start();
+      let node = w.into_node();
+      if let Some(span) = span {
+        span.end(BuildKind::Compose, name, location, true);
+      }
+      Widget(InnerWidget::Node(node))
+    };
+    Widget(InnerWidget::Lazy(LazyNode::new(lazy)))
   }
 }
 
 impl<R: Render + 'static> IntoWidgetStrict<'static, RENDER> for R {
+  #[track_caller]
   fn into_widget_strict(self) -> Widget<'static> { Widget::from_render(Box::new(PureRender(self))) }
 }
 
@@ -228,8 +345,18 @@ impl<'w> Widget<'w> {
       .attach_anonymous_data(h)
   }
 
+  #[track_caller]
   pub(crate) fn from_render(r: Box<dyn RenderQueryable>) -> Widget<'static> {
-    Widget(InnerWidget::Node(Node::Leaf(Box::new(|| BuildCtx::get_mut().alloc(r)))))
+    let location = std::panic::Location::caller();
+    Widget(InnerWidget::Node(Node::Leaf(Box::new(move || {
+      let ctx = BuildCtx::get_mut();
+      let id = ctx.alloc(r);
+      ctx
+        .tree_mut()
+        .provenance
+        .record_build_location(id, location);
+      id
+    }))))
   }
 
   /// Attach anonymous data to a widget and user can't query it.
@@ -264,7 +391,13 @@ impl<'w> Widget<'w> {
       } else if ctx.providers.last() != Some(&p) && p.queryable(ctx.tree()) {
         ctx.providers.push(p);
       }
-      let c = child.into_node().build(&mut subtrees);
+      let c = guard_widget_panic(
+        &mut subtrees,
+        TreePhase::Build,
+        p,
+        |subtrees| child.into_node().build(subtrees),
+        |_, id| BuildCtx::get().tree().provenance_path(id),
+      );
       p.append(c, ctx.tree_mut());
     }
     root
@@ -155,11 +155,24 @@ pub trait ComposeChild<'c>: Sized {
 }
 
 /// The trait converts a type into a child of the `SingleChild`.
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` cannot be a child of a single-child widget: it does not implement \
+             `IntoWidget`",
+  label = "not a widget",
+  note = "a child can be any widget, a function widget or an `Option` of them"
+)]
 pub trait IntoChildSingle<'c, const M: usize> {
   fn into_child_single(self) -> Option<Widget<'c>>;
 }
 
 /// The trait converts a type into a child of the `MultiChild`.
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` cannot be a child of a multi-child widget: it is neither a widget nor an \
+             iterator of widgets",
+  label = "not a widget or an iterator of widgets",
+  note = "a child can be any widget, an iterator of widgets or a pipe of them, check that the \
+          items of an iterator implement `IntoWidget`"
+)]
 pub trait IntoChildMulti<'c, const N: usize, const M: usize> {
   fn into_child_multi(self) -> impl Iterator<Item = Widget<'c>>;
 }
@@ -172,6 +185,11 @@ pub trait IntoChildMulti<'c, const N: usize, const M: usize> {
 ///
 /// It is similar to `Into` but with a const marker to automatically implement
 /// all possible conversions without implementing conflicts.
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` cannot be converted to the child type `{C}`",
+  label = "expected a child that can convert to `{C}`",
+  note = "implement `ComposeChildFrom<{Self}, _>` for `{C}` to accept it as a child"
+)]
 pub trait IntoChildCompose<C, const M: usize> {
   fn into_child_compose(self) -> C;
 }
@@ -12,6 +12,12 @@ use super::*;
 /// - The `TML` marker if it is a template.
 /// - The `N` marker is used to distinguish the type fill in `Template`
 /// - The `M` marker is used for child conversion.
+#[diagnostic::on_unimplemented(
+  message = "`{C}` cannot be a child of `{Self}`",
+  label = "`{C}` does not match the child type of `{Self}`",
+  note = "the child must convert to the `ComposeChild::Child` of the parent, if the child type is \
+          a template, the child must fit one of its fields"
+)]
 pub trait ComposeWithChild<
   'w,
   C,
@@ -1,9 +1,15 @@
+use smallvec::SmallVec;
+
 use super::*;
 use crate::pipe::InnerPipe;
 
+/// Most of the multi-child parents have a few children, so they're collected
+/// inline and only the larger lists go to the heap.
+type MultiChildren<'a> = SmallVec<[Widget<'a>; 4]>;
+
 pub struct MultiPair<'a> {
   parent: Widget<'static>,
-  children: Vec<Widget<'a>>,
+  children: MultiChildren<'a>,
 }
 
 impl<'a> MultiPair<'a> {
@@ -22,8 +28,11 @@ impl<'a> MultiPair<'a> {
     'a: 'c,
     'b: 'c,
   {
-    let mut children: Vec<Widget<'c>> = self.children;
-    for c in child.into_child_multi() {
+    let mut children = shorten_children(self.children);
+    let child = child.into_child_multi();
+    children.reserve(child.size_hint().0);
+    // Push one by one, so every child is coerced to the shorter lifetime.
+    for c in child {
       children.push(c);
     }
 
@@ -31,6 +40,21 @@ impl<'a> MultiPair<'a> {
   }
 }
 
+/// `SmallVec` is invariant over its items, so move them to shorten their
+/// lifetime, the heap buffer of the spilled children is kept.
+fn shorten_children<'a: 'c, 'c>(children: MultiChildren<'a>) -> MultiChildren<'c> {
+  if children.spilled() {
+    let children: Vec<Widget<'c>> = children.into_vec();
+    SmallVec::from_vec(children)
+  } else {
+    let mut shorter = MultiChildren::new();
+    for c in children {
+      shorter.push(c);
+    }
+    shorter
+  }
+}
+
 impl<'c> IntoChildMulti<'c, 0, 0> for Widget<'c> {
   fn into_child_multi(self) -> impl Iterator<Item = Widget<'c>> { std::iter::once(self) }
 }
@@ -139,7 +163,7 @@ impl<'w> IntoWidgetStrict<'w, RENDER> for MultiPair<'w> {
   fn into_widget_strict(self) -> Widget<'w> {
     let f = move || {
       let MultiPair { parent, children } = self;
-      parent.directly_compose_children(children)
+      parent.directly_compose_children(children.into_vec())
     };
 
     f.into_widget()
@@ -34,6 +34,39 @@ where
   fn into_child_single(self) -> Option<Widget<'c>> { self.map(IntoWidget::into_widget) }
 }
 
+/// A trait without any implementation, used to report a readable error when a
+/// child is given to a `Widget`. That usually happens when a single-child
+/// widget is given two children, because it's a `Widget` after composing the
+/// first child.
+///
+/// It's sealed, so no type outside can implement it and make the extra child
+/// slip through:
+///
+/// ```compile_fail,E0277
+/// use ribir_core::prelude::*;
+///
+/// struct Extra;
+/// impl ExtraChildOfWidget for Extra {}
+/// ```
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` cannot be a child of a `Widget`: a single-child widget accepts only one \
+             child",
+  label = "this is an extra child",
+  note = "wrap the children with a multi-child widget, such as `Row`, `Column` or `Stack`"
+)]
+pub trait ExtraChildOfWidget: sealed::Sealed {}
+
+mod sealed {
+  pub trait Sealed {}
+}
+
+impl<'w> Widget<'w> {
+  #[doc(hidden)]
+  pub fn with_child<C: ExtraChildOfWidget>(self, _: C) -> Self {
+    unreachable!("`ExtraChildOfWidget` is never implemented.")
+  }
+}
+
 impl<T: SingleChild> SingleChild for FatObj<T> {
   fn with_child<'c, const M: usize>(self, child: impl IntoChildSingle<'c, M>) -> Widget<'c> {
     self
@@ -1,4 +1,9 @@
-use std::{cell::RefCell, cmp::Reverse, collections::HashSet, mem::MaybeUninit};
+use std::{
+  cell::RefCell,
+  cmp::Reverse,
+  collections::{HashMap, HashSet},
+  mem::MaybeUninit,
+};
 
 pub mod widget_id;
 use indextree::Arena;
@@ -6,11 +11,25 @@ use widget_id::RenderQueryable;
 pub use widget_id::{TrackId, WidgetId};
 mod layout_info;
 pub use layout_info::*;
+mod layout_diagnostics;
+pub use layout_diagnostics::*;
+mod provenance;
+pub(crate) use provenance::*;
+mod tree_dump;
+pub use tree_dump::*;
+#[cfg(debug_assertions)]
+mod validator;
 
 use self::widget::widget_id::new_node;
-use crate::{overlay::ShowingOverlays, prelude::*, render_helper::PureRender, window::WindowId};
+use crate::{
+  build_profile::BuildProfiler, overlay::ShowingOverlays, prelude::*, render_helper::PureRender,
+  watchdog::FrameCosts, window::WindowId,
+};
 
 pub(crate) type DirtySet = Sc<RefCell<HashSet<WidgetId, ahash::RandomState>>>;
+/// The widgets from a widget up to its ancestor, the paths of the events are
+/// rarely deeper than its inline capacity, so they don't allocate.
+pub(crate) type WidgetPath = smallvec::SmallVec<[WidgetId; 16]>;
 
 pub(crate) struct WidgetTree {
   pub(crate) root: WidgetId,
@@ -19,6 +38,31 @@ pub(crate) struct WidgetTree {
   pub(crate) store: LayoutStore,
   pub(crate) dirty_set: DirtySet,
   pub(crate) dummy_id: WidgetId,
+  /// The bloom of the queryable types in the subtree of every widget, used to
+  /// skip the subtrees that can't contain the type of a descendant query.
+  ///
+  /// The bloom only grows until the widget is removed, so it may be a superset
+  /// of the subtree after a widget is detached or unwrapped.
+  pub(crate) query_blooms: HashMap<WidgetId, QueryBloom, ahash::RandomState>,
+  /// The relayout records of the current frame, `None` if the window doesn't
+  /// enable the layout diagnostics.
+  pub(crate) layout_diagnostics: Option<LayoutDiagnostics>,
+  /// The layout and paint time of the widgets in the current frame, for the
+  /// frame watchdog.
+  pub(crate) frame_costs: RefCell<FrameCosts>,
+  /// The buffer of the widgets to paint, kept to not allocate for every
+  /// paint.
+  pub(crate) paint_stack: RefCell<Vec<Option<WidgetId>>>,
+  /// The builds of the frames, only recorded while the build profiler is on.
+  pub(crate) build_profiler: RefCell<BuildProfiler>,
+  /// Increased every time the dirty widgets are laid out, so the caches of
+  /// the layout can tell if they are outdated.
+  pub(crate) layout_version: usize,
+  /// Where the widgets were declared, only collected in debug builds or with
+  /// the `widget-provenance` feature.
+  pub(crate) provenance: ProvenanceStore,
+  #[cfg(debug_assertions)]
+  pub(crate) debug: validator::TreeDebugInfo,
 }
 
 /// A tool that help you to mark a widget as dirty
@@ -79,6 +123,7 @@ impl WidgetTree {
       let Some(mut needs_layout) = self.layout_list() else {
         break;
       };
+      self.layout_version += 1;
       while let Some(wid) = needs_layout.pop() {
         if wid.is_dropped(self) {
           continue;
@@ -117,7 +162,7 @@ impl WidgetTree {
   }
 
   fn display_node(&self, mut prefix: String, id: WidgetId, display: &mut String) {
-    display.push_str(&format!("{prefix}{:?}\n", id.0));
+    display.push_str(&format!("{prefix}{:?} {}\n", id.0, self.describe_provenance(id)));
 
     prefix.pop();
     match prefix.pop() {
@@ -140,23 +185,27 @@ impl WidgetTree {
 
     let mut needs_layout = vec![];
 
-    let dirty_widgets = {
-      let mut state_changed = self.dirty_set.borrow_mut();
-      let dirty_widgets = state_changed.clone();
-      state_changed.clear();
-      dirty_widgets
-    };
+    // Sort the dirty widgets, so the same changes relayout in the same order and
+    // record the same diagnostics in every run.
+    let mut dirty_widgets: Vec<_> = self.dirty_set.borrow_mut().drain().collect();
+    dirty_widgets.sort();
 
     for id in dirty_widgets.iter() {
       if id.is_dropped(self) {
+        // The removed widgets are cleared from the dirty set, so someone marked it
+        // after it was removed.
+        #[cfg(debug_assertions)]
+        self.debug.dirty_after_removed.push(*id);
         continue;
       }
 
-      if let Some(info) = self.store.get_mut(id) {
+      if let Some(info) = self.store.layout_info_mut(*id) {
         info.size.take();
       }
 
       let mut relayout_root = *id;
+      let mut escalated = vec![];
+      let mut stop = RelayoutStop::Root;
       // All ancestors of this render widget should relayout until the one which only
       // sized by parent.
       for p in id.0.ancestors(&self.arena).skip(1).map(WidgetId) {
@@ -164,18 +213,26 @@ impl WidgetTree {
         // isn't layout information, it indicates that the ancestor marked for relayout
         // already.
         if self.store.layout_box_size(p).is_none() {
+          stop = RelayoutStop::ParentPending;
           break;
         }
 
         relayout_root = p;
-        if let Some(info) = self.store.get_mut(&p) {
+        if let Some(info) = self.store.layout_info_mut(p) {
           info.size.take();
         }
 
+        if self.layout_diagnostics.is_some() {
+          escalated.push(p);
+        }
         if p.assert_get(self).only_sized_by_parent() {
+          stop = RelayoutStop::SizedByParent;
           break;
         }
       }
+      self.record_relayout(*id, escalated, stop);
+      #[cfg(debug_assertions)]
+      self.debug.changed.push(relayout_root);
       needs_layout.push(relayout_root);
     }
 
@@ -201,9 +258,15 @@ impl WidgetTree {
   pub(crate) fn remove_subtree(&mut self, id: WidgetId) {
     assert_ne!(id, self.root(), "You should detach the root widget before remove it.");
 
+    let mut dirty_set = self.dirty_set.borrow_mut();
     id.0.descendants(&self.arena).for_each(|id| {
-      self.store.remove(WidgetId(id));
+      let id = WidgetId(id);
+      self.store.remove(id);
+      dirty_set.remove(&id);
+      self.query_blooms.remove(&id);
+      self.provenance.remove(id);
     });
+    drop(dirty_set);
     id.0.remove_subtree(&mut self.arena);
   }
 
@@ -224,6 +287,40 @@ impl WidgetTree {
       outs.assume_init()
     }
   }
+
+  /// Return the bloom of the queryable types in the subtree of `id`.
+  pub(crate) fn subtree_bloom(&mut self, id: WidgetId) -> QueryBloom {
+    if let Some(bloom) = self.query_blooms.get(&id) {
+      return *bloom;
+    }
+    let mut bloom = QueryBloom::default();
+    id.assert_get(self).type_bloom(&mut bloom);
+    self.query_blooms.insert(id, bloom);
+    bloom
+  }
+
+  /// Add the types of `bloom` to the subtree bloom of `id` and its ancestors.
+  pub(crate) fn spread_bloom(&mut self, id: WidgetId, bloom: QueryBloom) {
+    let mut next = Some(id);
+    while let Some(id) = next {
+      let mut subtree = self.subtree_bloom(id);
+      // The ancestors already contain all the types if this widget does.
+      if subtree.contains_all(&bloom) {
+        break;
+      }
+      subtree.union(&bloom);
+      self.query_blooms.insert(id, subtree);
+      next = id.node_feature(self, |node| node.parent());
+    }
+  }
+
+  /// Update the subtree blooms after the render object of `id` is replaced or
+  /// wrapped.
+  pub(crate) fn refresh_bloom(&mut self, id: WidgetId) {
+    let mut bloom = QueryBloom::default();
+    id.assert_get(self).type_bloom(&mut bloom);
+    self.spread_bloom(id, bloom);
+  }
 }
 
 impl WidgetTree {
@@ -233,7 +330,23 @@ impl WidgetTree {
     let dummy_id = new_node(&mut arena, Box::new(PureRender(Void)));
     dummy_id.0.remove(&mut arena);
 
-    Self { root, dummy_id, wnd_id, arena, store: <_>::default(), dirty_set: <_>::default() }
+    Self {
+      root,
+      dummy_id,
+      wnd_id,
+      arena,
+      store: <_>::default(),
+      dirty_set: <_>::default(),
+      query_blooms: <_>::default(),
+      layout_diagnostics: None,
+      frame_costs: <_>::default(),
+      paint_stack: <_>::default(),
+      build_profiler: <_>::default(),
+      layout_version: 0,
+      provenance: <_>::default(),
+      #[cfg(debug_assertions)]
+      debug: <_>::default(),
+    }
   }
 }
 
@@ -470,4 +583,91 @@ mod tests {
     let len_1_widget = wnd.painter.borrow_mut().finish().len();
     assert_eq!(len_1_widget, len_100_widget);
   }
+
+  struct Field(usize);
+
+  fn with_field(v: usize) -> Widget<'static> {
+    MockBox { size: Size::zero() }
+      .into_widget()
+      .attach_data(Box::new(Queryable(Field(v))))
+  }
+
+  #[test]
+  fn query_descendants_with_pruning() {
+    reset_test_env!();
+
+    let (trigger, w_trigger) = split_value(1);
+    let w = fn_widget! {
+      @MockMulti {
+        @MockMulti { @ { with_field(0) } }
+        @MockMulti { @MockBox { size: Size::zero() } }
+        @MockMulti { @ { pipe!(*$trigger).map(with_field) } }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let root = wnd.tree().content_root();
+    let fields = |wnd: &TestWindow| {
+      wnd
+        .query_descendants::<Field>(root)
+        .iter()
+        .map(|(_, f)| f.0)
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(fields(&wnd), [0, 1]);
+
+    let tree = wnd.tree();
+    let field_id = QueryId::of::<Field>();
+    let [with, without, by_pipe] = [0, 1, 2].map(|i| root.children(tree).nth(i).unwrap());
+    assert!(tree.query_blooms[&with].may_contain(&field_id));
+    assert!(!tree.query_blooms[&without].may_contain(&field_id));
+    assert!(tree.query_blooms[&by_pipe].may_contain(&field_id));
+
+    // The new widget generated by the pipe can be found.
+    *w_trigger.write() = 2;
+    wnd.draw_frame();
+    assert_eq!(fields(&wnd), [0, 2]);
+
+    // Break the visit early.
+    let mut visited = vec![];
+    let _ = root.visit_descendants::<Field>(wnd.tree(), |_, f| {
+      visited.push(f.0);
+      std::ops::ControlFlow::Break(())
+    });
+    assert_eq!(visited, [0]);
+  }
+
+  #[test]
+  fn query_ancestor_through_builtin_layers() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      let form = @MockBox {
+        size: Size::new(100., 100.),
+        margin: EdgeInsets::all(1.),
+        @MockMulti {
+          padding: EdgeInsets::all(1.),
+          @MockBox {
+            size: Size::new(10., 10.),
+            background: Color::RED,
+            on_tap: |_| {},
+          }
+        }
+      };
+      form
+        .into_widget()
+        .attach_data(Box::new(Queryable(Field(7))))
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let tree = wnd.tree();
+    let form = tree.content_root();
+    let leaf = form.single_leaf(tree);
+    let (id, field) = wnd.query_ancestor::<Field>(leaf).unwrap();
+    assert_eq!(id, form);
+    assert_eq!(field.0, 7);
+    assert!(wnd.query_ancestor::<Field>(form).is_none());
+  }
 }
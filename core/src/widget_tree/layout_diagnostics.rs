@@ -0,0 +1,172 @@
+use std::{borrow::Cow, fmt::Write};
+
+use super::*;
+
+/// The report of how the dirty widgets spread the relayout to their ancestors
+/// in the last frame.
+///
+/// It's only collected when the window enables the `LAYOUT_DIAGNOSTICS` flag of
+/// [`WindowFlags`](crate::window::WindowFlags), and you can read it by
+/// [`Window::layout_diagnostics`].
+#[derive(Debug, Default, Clone)]
+pub struct LayoutDiagnostics {
+  /// A record for every widget marked dirty in the frame.
+  pub records: Vec<RelayoutRecord>,
+}
+
+/// How the relayout of a dirty widget spreads to its ancestors.
+#[derive(Debug, Clone)]
+pub struct RelayoutRecord {
+  /// The widget marked dirty.
+  pub origin: WidgetId,
+  /// The type name of the `origin`.
+  pub origin_name: Cow<'static, str>,
+  /// The ancestors of the `origin` that relayout with it, from the parent of
+  /// `origin` to the relayout root. Every one of them may change its size with
+  /// its children, so it can't stop the relayout.
+  pub escalated: Vec<WidgetId>,
+  /// The widget the relayout starts from.
+  pub relayout_root: WidgetId,
+  /// The type name of the `relayout_root`.
+  pub relayout_root_name: Cow<'static, str>,
+  /// Why the relayout doesn't spread to the parent of the `relayout_root`.
+  pub stop: RelayoutStop,
+  /// The count of the widgets in the subtree of the `relayout_root`, the
+  /// widgets that may perform layout because of the `origin`.
+  pub scope: usize,
+}
+
+/// Why the relayout stops spreading at a widget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelayoutStop {
+  /// The widget is only sized by its parent, such as a [`LayoutBoundary`], so
+  /// its parent doesn't need to relayout.
+  SizedByParent,
+  /// The parent of the widget is already waiting for relayout.
+  ParentPending,
+  /// The widget is the root of the tree.
+  Root,
+}
+
+impl LayoutDiagnostics {
+  /// Return the `n` records that relayout the most widgets.
+  pub fn top_offenders(&self, n: usize) -> Vec<&RelayoutRecord> {
+    let mut records: Vec<_> = self.records.iter().collect();
+    records.sort_by_key(|r| Reverse(r.scope));
+    records.truncate(n);
+    records
+  }
+
+  /// Describe the `n` records that relayout the most widgets, a line for every
+  /// record.
+  pub fn summary(&self, n: usize) -> String {
+    let mut out = String::new();
+    for r in self.top_offenders(n) {
+      let _ = writeln!(
+        out,
+        "`{}` relayouts {} widgets from `{}` through {} ancestors, stopped by {:?}.",
+        r.origin_name,
+        r.scope,
+        r.relayout_root_name,
+        r.escalated.len(),
+        r.stop
+      );
+    }
+    out
+  }
+}
+
+impl WidgetTree {
+  pub(crate) fn record_relayout(
+    &mut self, origin: WidgetId, escalated: Vec<WidgetId>, stop: RelayoutStop,
+  ) {
+    if self.layout_diagnostics.is_none() {
+      return;
+    }
+    let relayout_root = escalated.last().copied().unwrap_or(origin);
+    let record = RelayoutRecord {
+      origin,
+      origin_name: origin.assert_get(self).debug_name(),
+      escalated,
+      relayout_root,
+      relayout_root_name: relayout_root.assert_get(self).debug_name(),
+      stop,
+      scope: relayout_root.descendants(self).count(),
+    };
+    if let Some(diagnostics) = self.layout_diagnostics.as_mut() {
+      diagnostics.records.push(record);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::any::type_name;
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*, window::WindowFlags};
+
+  fn leaf_change_records(boundary: bool) -> Vec<RelayoutRecord> {
+    let (size, w_size) = split_value(Size::new(10., 10.));
+    let w = fn_widget! {
+      let leaf = @MockMulti {
+        @MockMulti { @MockBox { size: pipe!(*$size) } }
+      };
+      let leaf = if boundary {
+        @LayoutBoundary { size: Size::new(20., 20.), @ { leaf } }.into_widget()
+      } else {
+        leaf.into_widget()
+      };
+      @MockMulti {
+        @MockBox { size: Size::new(20., 20.) }
+        @MockMulti { @ { leaf } }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.set_flags(wnd.flags() | WindowFlags::LAYOUT_DIAGNOSTICS);
+    wnd.draw_frame();
+
+    *w_size.write() = Size::new(20., 20.);
+    wnd.draw_frame();
+    wnd.layout_diagnostics().unwrap().records.clone()
+  }
+
+  #[test]
+  fn leaf_change_escalates_to_root() {
+    reset_test_env!();
+
+    let records = leaf_change_records(false);
+    assert_eq!(records.len(), 1);
+    let r = &records[0];
+    assert_eq!(r.origin_name, type_name::<MockBox>());
+    assert_eq!(r.stop, RelayoutStop::Root);
+    assert!(r.scope >= 6);
+  }
+
+  #[test]
+  fn boundary_reduces_scope() {
+    reset_test_env!();
+
+    let whole = leaf_change_records(false);
+    let records = leaf_change_records(true);
+    assert_eq!(records.len(), 1);
+    let r = &records[0];
+    assert_eq!(r.relayout_root_name, type_name::<LayoutBoundary>());
+    assert_eq!(r.stop, RelayoutStop::SizedByParent);
+    assert_eq!(r.escalated.len(), 3);
+    assert_eq!(r.scope, 4);
+    assert!(r.scope < whole[0].scope);
+
+    let summary = LayoutDiagnostics { records }.summary(5);
+    assert!(summary.contains("relayouts 4 widgets"));
+  }
+
+  #[test]
+  fn disabled_by_default() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { @MockBox { size: Size::zero() } });
+    wnd.draw_frame();
+    assert!(wnd.layout_diagnostics().is_none());
+  }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use ribir_geom::ZERO_SIZE;
+use ribir_geom::{Rect, ZERO_SIZE};
 
 use super::{Lerp, WidgetId, WidgetTree};
 use crate::prelude::{INFINITY_SIZE, Point, Size};
@@ -109,13 +109,19 @@ pub struct LayoutInfo {
 #[derive(Default)]
 pub(crate) struct LayoutStore {
   data: HashMap<WidgetId, LayoutInfo, ahash::RandomState>,
+  /// The rects of the last painted frame of the widgets that are changed in
+  /// the current frame.
+  prev: HashMap<WidgetId, Option<Rect>, ahash::RandomState>,
 }
 
 impl LayoutStore {
   /// Remove the layout info of the `wid`
   pub(crate) fn force_layout(&mut self, id: WidgetId) -> Option<LayoutInfo> { self.remove(id) }
 
-  pub(crate) fn remove(&mut self, id: WidgetId) -> Option<LayoutInfo> { self.data.remove(&id) }
+  pub(crate) fn remove(&mut self, id: WidgetId) -> Option<LayoutInfo> {
+    self.record_prev(id);
+    self.data.remove(&id)
+  }
 
   pub(crate) fn layout_box_size(&self, id: WidgetId) -> Option<Size> {
     self.layout_info(id).and_then(|info| info.size)
@@ -127,11 +133,41 @@ impl LayoutStore {
 
   pub(crate) fn layout_info(&self, id: WidgetId) -> Option<&LayoutInfo> { self.data.get(&id) }
 
+  pub(crate) fn layout_info_mut(&mut self, id: WidgetId) -> Option<&mut LayoutInfo> {
+    self.record_prev(id);
+    self.data.get_mut(&id)
+  }
+
   /// return a mutable reference of the layout info  of `id`, if it's not exist
   /// insert a default value before return
   pub(crate) fn layout_info_or_default(&mut self, id: WidgetId) -> &mut LayoutInfo {
+    self.record_prev(id);
     self.data.entry(id).or_default()
   }
+
+  /// The rect of the widget in the last painted frame, it stays the same
+  /// while the layout of the current frame is computing.
+  pub(crate) fn prev_layout_rect(&self, id: WidgetId) -> Option<Rect> {
+    match self.prev.get(&id) {
+      Some(rect) => *rect,
+      None => self.layout_rect(id),
+    }
+  }
+
+  /// The current frame is painted, its rects become the previous ones.
+  pub(crate) fn end_frame(&mut self) { self.prev.clear(); }
+
+  fn layout_rect(&self, id: WidgetId) -> Option<Rect> {
+    let info = self.layout_info(id)?;
+    info.size.map(|size| Rect::new(info.pos, size))
+  }
+
+  fn record_prev(&mut self, id: WidgetId) {
+    if !self.prev.contains_key(&id) {
+      let rect = self.layout_rect(id);
+      self.prev.insert(id, rect);
+    }
+  }
 }
 
 impl WidgetTree {
@@ -206,10 +242,6 @@ impl std::ops::Deref for LayoutStore {
   fn deref(&self) -> &Self::Target { &self.data }
 }
 
-impl std::ops::DerefMut for LayoutStore {
-  fn deref_mut(&mut self) -> &mut Self::Target { &mut self.data }
-}
-
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -0,0 +1,266 @@
+//! Where the widgets were declared, to tell which widget is responsible when
+//! the build, layout or paint of the tree panics.
+//!
+//! The provenance is only collected in debug builds, or when the
+//! `widget-provenance` feature is enabled.
+
+use std::{
+  any::Any,
+  cell::{Cell, RefCell},
+  collections::HashMap,
+  fmt::Write,
+  panic::{AssertUnwindSafe, Location},
+};
+
+use super::*;
+
+/// If the provenance is collected.
+pub(crate) const PROVENANCE_ENABLED: bool =
+  cfg!(any(debug_assertions, feature = "widget-provenance"));
+
+/// The provenance of a declared widget, the location of its declaration and
+/// its debug name.
+#[derive(Default, Clone)]
+pub(crate) struct Provenance {
+  pub(crate) location: Option<&'static Location<'static>>,
+  pub(crate) debug_name: Option<CowArc<str>>,
+}
+
+/// The provenance of the widgets in a tree.
+#[derive(Default)]
+pub(crate) struct ProvenanceStore(HashMap<WidgetId, Provenance, ahash::RandomState>);
+
+/// The work the tree was doing with a widget when it panicked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TreePhase {
+  /// Building the children of the widget.
+  Build,
+  Layout,
+  Paint,
+}
+
+thread_local! {
+  /// How many guarded calls are running, only the outermost one re-throws the
+  /// panic with the breadcrumb.
+  static GUARD_DEPTH: Cell<usize> = const { Cell::new(0) };
+  /// The innermost widget that panicked, and what the tree was doing with it.
+  static PANICKED: RefCell<Option<(TreePhase, WidgetId)>> = const { RefCell::new(None) };
+}
+
+impl Provenance {
+  pub(crate) fn is_empty(&self) -> bool { self.location.is_none() && self.debug_name.is_none() }
+}
+
+impl ProvenanceStore {
+  pub(crate) fn get(&self, id: WidgetId) -> Option<&Provenance> { self.0.get(&id) }
+
+  /// Record the provenance of the widget, the later one replaces the location
+  /// of the earlier, so the outermost declaration of a node wins.
+  pub(crate) fn record(&mut self, id: WidgetId, p: Provenance) {
+    if !PROVENANCE_ENABLED || p.is_empty() {
+      return;
+    }
+    let Provenance { location, debug_name } = p;
+    let item = self.0.entry(id).or_default();
+    item.location = location.or(item.location);
+    item.debug_name = debug_name.or(item.debug_name.take());
+  }
+
+  /// Record the location where the render object of the widget was converted
+  /// to a widget, if the widget doesn't know where it was declared.
+  pub(crate) fn record_build_location(&mut self, id: WidgetId, loc: &'static Location<'static>) {
+    if !PROVENANCE_ENABLED {
+      return;
+    }
+    self
+      .0
+      .entry(id)
+      .or_default()
+      .location
+      .get_or_insert(loc);
+  }
+
+  pub(crate) fn remove(&mut self, id: WidgetId) { self.0.remove(&id); }
+}
+
+impl TreePhase {
+  fn doing(self) -> &'static str {
+    match self {
+      TreePhase::Build => "building the children of",
+      TreePhase::Layout => "laying out",
+      TreePhase::Paint => "painting",
+    }
+  }
+}
+
+impl WidgetTree {
+  /// Describe a widget with its type name, debug name and the location it was
+  /// declared.
+  pub(crate) fn describe_provenance(&self, id: WidgetId) -> String {
+    let name = id
+      .get(self)
+      .map_or("<removed>".into(), |r| r.debug_name());
+    let mut desc = format!("`{name}`");
+    if let Some(p) = self.provenance.get(id) {
+      if let Some(debug_name) = &p.debug_name {
+        let _ = write!(desc, " (debug_name: {})", &**debug_name);
+      }
+      if let Some(loc) = p.location {
+        let _ = write!(desc, " declared at {loc}");
+      }
+    }
+    desc
+  }
+
+  /// The path from the top of the tree to the widget, every widget is
+  /// described with its index in its parent.
+  pub(crate) fn provenance_path(&self, id: WidgetId) -> String {
+    let mut path: Vec<_> = id
+      .ancestors(self)
+      .take_while(|id| *id != self.root())
+      .collect();
+    path.reverse();
+    let mut out = String::new();
+    for (i, id) in path.iter().enumerate() {
+      if i > 0 {
+        let idx = id
+          .parent(self)
+          .and_then(|p| p.children(self).position(|c| c == *id))
+          .unwrap_or(0);
+        let _ = write!(out, " → child {idx} ");
+      }
+      out.push_str(&self.describe_provenance(*id));
+    }
+    out
+  }
+}
+
+/// Call `f` with the widget `id`, if it panics, the panic is re-thrown with the
+/// path of the innermost widget that panicked, which is described by
+/// `describe`.
+pub(crate) fn guard_widget_panic<C: ?Sized, R>(
+  ctx: &mut C, phase: TreePhase, id: WidgetId, f: impl FnOnce(&mut C) -> R,
+  describe: impl FnOnce(&C, WidgetId) -> String,
+) -> R {
+  if !PROVENANCE_ENABLED {
+    return f(ctx);
+  }
+  let depth = GUARD_DEPTH.get();
+  if depth == 0 {
+    PANICKED.take();
+  }
+  GUARD_DEPTH.set(depth + 1);
+  let res = std::panic::catch_unwind(AssertUnwindSafe(|| f(ctx)));
+  GUARD_DEPTH.set(depth);
+
+  match res {
+    Ok(r) => r,
+    Err(payload) => {
+      let panicked = PANICKED.with_borrow_mut(|p| *p.get_or_insert((phase, id)));
+      if depth > 0 {
+        std::panic::resume_unwind(payload);
+      }
+      PANICKED.take();
+      let (phase, id) = panicked;
+      panic!("{}\n  while {} {}", panic_message(&payload), phase.doing(), describe(ctx, id));
+    }
+  }
+}
+
+fn panic_message(payload: &Box<dyn Any + Send>) -> &str {
+  if let Some(s) = payload.downcast_ref::<&str>() {
+    s
+  } else if let Some(s) = payload.downcast_ref::<String>() {
+    s
+  } else {
+    "Box<dyn Any>"
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  struct PanicLayout;
+
+  impl Render for PanicLayout {
+    fn perform_layout(&self, _: BoxClamp, _: &mut LayoutCtx) -> Size { panic!("bad layout") }
+  }
+
+  struct PanicPaint;
+
+  impl Render for PanicPaint {
+    fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size { clamp.min }
+
+    fn paint(&self, _: &mut PaintingCtx) { panic!("bad paint") }
+  }
+
+  fn panic_message_of(new_wnd: impl FnOnce() -> TestWindow) -> String {
+    let payload = std::panic::catch_unwind(AssertUnwindSafe(|| {
+      let mut wnd = new_wnd();
+      wnd.draw_frame();
+    }))
+    .unwrap_err();
+    panic_message(&payload).to_string()
+  }
+
+  #[test]
+  fn layout_panic_with_provenance() {
+    reset_test_env!();
+
+    let line = line!() + 3;
+    let msg = panic_message_of(|| {
+      TestWindow::new(fn_widget! {
+        @MockMulti {
+          debug_name: "sidebar",
+          @MockBox { size: Size::zero() }
+          @ { PanicLayout }
+        }
+      })
+    });
+    assert!(msg.starts_with("bad layout\n  while laying out "));
+    let multi = format!(
+      "`{}` (debug_name: sidebar) declared at {}:{line}:",
+      std::any::type_name::<MockMulti>(),
+      file!()
+    );
+    assert!(msg.contains(&multi));
+    assert!(msg.contains(&format!(" → child 1 `{}`", std::any::type_name::<PanicLayout>())));
+  }
+
+  #[test]
+  fn paint_panic_with_provenance() {
+    reset_test_env!();
+
+    let line = line!() + 3;
+    let msg = panic_message_of(|| {
+      TestWindow::new(fn_widget! {
+        @MockBox {
+          size: Size::new(10., 10.),
+          @ { PanicPaint }
+        }
+      })
+    });
+    assert!(msg.starts_with("bad paint\n  while painting "));
+    let mock_box =
+      format!("`{}` declared at {}:{line}:", std::any::type_name::<MockBox>(), file!());
+    assert!(msg.contains(&mock_box));
+  }
+
+  #[test]
+  fn build_panic_with_provenance() {
+    reset_test_env!();
+
+    let msg = panic_message_of(|| {
+      TestWindow::new(fn_widget! {
+        @MockMulti {
+          debug_name: "list",
+          @ { || -> Widget<'static> { panic!("bad build") } }
+        }
+      })
+    });
+    assert!(msg.starts_with("bad build\n  while building the children of "));
+    assert!(msg.contains("(debug_name: list)"));
+  }
+}
@@ -0,0 +1,144 @@
+use std::{borrow::Cow, fmt};
+
+use super::*;
+use crate::builtin_widgets::accessible_name;
+
+/// A snapshot of the widgets of a window, see [`Window::tree_dump`].
+///
+/// It displays the raw ids of the widgets, which depend on how the tree has
+/// been built and rebuilt. [`TreeDump::normalized`] identifies the widgets by
+/// their paths from the root instead, such as `root/0/2/1`, so the windows that
+/// show the same UI have the same normalized dump.
+#[derive(Debug, Clone)]
+pub struct TreeDump {
+  /// The widgets of the tree in depth-first order.
+  pub nodes: Vec<DumpNode>,
+}
+
+/// A widget of a [`TreeDump`].
+#[derive(Debug, Clone)]
+pub struct DumpNode {
+  pub id: WidgetId,
+  /// The index of every widget among its siblings, from the child of the root
+  /// to this widget. It's empty for the root.
+  pub path: Vec<usize>,
+  /// The type name of the widget.
+  pub name: Cow<'static, str>,
+  /// The layout rect of the widget relative to its parent, `None` if it's not
+  /// laid out.
+  pub rect: Option<Rect>,
+  /// The accessible name of a focusable widget, see
+  /// [`Window::accessible_name`].
+  pub accessible_name: Option<CowArc<str>>,
+}
+
+impl TreeDump {
+  pub(crate) fn new(tree: &WidgetTree) -> Self {
+    fn collect(tree: &WidgetTree, id: WidgetId, path: &mut Vec<usize>, nodes: &mut Vec<DumpNode>) {
+      let rect = tree
+        .store
+        .layout_info(id)
+        .and_then(|info| info.size.map(|size| Rect::new(info.pos, size)));
+      let name = id.assert_get(tree).debug_name();
+      let focusable = id
+        .query_all_iter::<MixBuiltin>(tree)
+        .any(|m| m.contain_flag(MixFlags::Focus));
+      let accessible_name = if focusable { accessible_name(id, tree) } else { None };
+      nodes.push(DumpNode { id, path: path.clone(), name, rect, accessible_name });
+      for (idx, c) in id.children(tree).enumerate() {
+        path.push(idx);
+        collect(tree, c, path, nodes);
+        path.pop();
+      }
+    }
+
+    let mut nodes = vec![];
+    collect(tree, tree.root(), &mut vec![], &mut nodes);
+    Self { nodes }
+  }
+
+  /// The dump with the path of every widget in place of its id, a line for
+  /// every widget.
+  pub fn normalized(&self) -> String {
+    let mut out = String::new();
+    for n in self.nodes.iter() {
+      out.push_str(&format!("{} {} {:?}{}\n", path_name(&n.path), n.name, n.rect, name_suffix(n)));
+    }
+    out
+  }
+
+  /// The path of the widget `id` in the normalized dump, such as `root/0/2`.
+  pub fn path_of(&self, id: WidgetId) -> Option<String> {
+    self
+      .nodes
+      .iter()
+      .find(|n| n.id == id)
+      .map(|n| path_name(&n.path))
+  }
+}
+
+fn name_suffix(n: &DumpNode) -> String {
+  n.accessible_name
+    .as_ref()
+    .map_or(String::new(), |name| format!(" {:?}", &**name))
+}
+
+fn path_name(path: &[usize]) -> String {
+  path
+    .iter()
+    .fold("root".to_string(), |name, idx| format!("{name}/{idx}"))
+}
+
+impl fmt::Display for TreeDump {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for n in self.nodes.iter() {
+      let indent = n.path.len() * 2;
+      writeln!(f, "{:indent$}{:?} {} {:?}{}", "", n.id.0, n.name, n.rect, name_suffix(n))?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{prelude::*, reset_test_env, test_helper::*};
+
+  fn ui(trailing: impl StateWatcher<Value = bool>) -> GenWidget {
+    let trailing = trailing.clone_watcher();
+    let ui = fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(10., 10.) }
+        @ {
+          pipe!(*$trailing).map(move |trailing| {
+            let size = if trailing { Size::new(20., 5.) } else { Size::new(5., 20.) };
+            fn_widget! { @MockStack { @MockBox { size } } }
+          })
+        }
+      }
+    };
+    ui.into()
+  }
+
+  #[test]
+  fn same_ui_same_normalized_dump() {
+    reset_test_env!();
+
+    // The first window rebuilds the pipe to get to the same UI.
+    let rebuilt = Stateful::new(false);
+    let mut wnd1 = TestWindow::new(ui(rebuilt.clone_watcher()));
+    wnd1.draw_frame();
+    *rebuilt.write() = true;
+    wnd1.draw_frame();
+
+    let mut wnd2 = TestWindow::new(ui(Stateful::new(true)));
+    wnd2.draw_frame();
+
+    let dump1 = wnd1.tree_dump();
+    let dump2 = wnd2.tree_dump();
+    assert_eq!(dump1.normalized(), dump2.normalized());
+
+    let leaf = dump1.nodes.last().unwrap();
+    assert_eq!(leaf.rect, Some(Rect::new(Point::zero(), Size::new(20., 5.))));
+    assert_eq!(dump1.path_of(leaf.id).unwrap(), "root/0/1/0");
+  }
+}
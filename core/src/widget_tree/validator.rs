@@ -0,0 +1,363 @@
+//! Integrity checks of the widget tree.
+//!
+//! The checks only run in debug builds, the window validates its tree after
+//! every frame, so a bug in compose or pipe code panics where it happens,
+//! instead of surfacing later as a confusing panic.
+//!
+//! Only the subtrees changed in the frame are validated, the ones relaid out
+//! and the new built widgets, so the cost follows the changes instead of the
+//! size of the tree.
+
+use std::{collections::HashSet, fmt};
+
+use super::*;
+use crate::builtin_widgets::key::{AnyKey, Key};
+
+/// The debug information the tree collects to describe and validate itself.
+#[derive(Default)]
+pub(crate) struct TreeDebugInfo {
+  /// The widgets that were marked dirty after they were removed from the tree.
+  pub(crate) dirty_after_removed: Vec<WidgetId>,
+  /// The roots of the subtrees relaid out since the last validation.
+  pub(crate) changed: Vec<WidgetId>,
+  /// The widgets built since the last validation.
+  pub(crate) built: Vec<WidgetId>,
+  /// Test-only hook to corrupt the parent link of a node.
+  #[cfg(test)]
+  pub(crate) corrupted_parents: std::collections::HashMap<WidgetId, Option<WidgetId>>,
+  /// Test-only hook to recover from the errors like a release build.
+  #[cfg(test)]
+  pub(crate) release: bool,
+  /// The errors recovered by the tree, see [`WidgetTree::recover`].
+  #[cfg(test)]
+  pub(crate) recovered: Vec<crate::error::CoreError>,
+}
+
+impl TreeDebugInfo {
+  pub(crate) fn simulate_release(&self) -> bool {
+    #[cfg(test)]
+    return self.release;
+    #[cfg(not(test))]
+    false
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TreeViolation {
+  /// The node can't be reached from the root.
+  Unreachable(WidgetId),
+  /// The parent and the child don't link to each other.
+  AsymmetricLink { parent: WidgetId, child: WidgetId },
+  /// The node has its layout, but its parent doesn't.
+  MissingParentLayout { id: WidgetId, parent: WidgetId },
+  /// A subscription still marks the widget dirty after it was removed.
+  DirtyAfterRemoved(WidgetId),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TreeWarning {
+  /// More than one child of the parent use the same key.
+  DuplicateKey { parent: WidgetId, key: Key },
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct IntegrityReport {
+  pub(crate) violations: Vec<TreeViolation>,
+  pub(crate) warnings: Vec<TreeWarning>,
+}
+
+impl WidgetTree {
+  /// Validate the tree, panic at the first violation and log the warnings.
+  ///
+  /// The subtrees in `detached` are kept out of the tree on purpose, such as
+  /// the widgets waiting for disposal.
+  #[track_caller]
+  pub(crate) fn validate(&mut self, detached: &[WidgetId]) {
+    let IntegrityReport { violations, warnings } = self.check_integrity(detached);
+    for w in warnings {
+      log::warn!("{}", self.describe_warning(&w));
+    }
+    if let Some(v) = violations.first() {
+      panic!("Widget tree integrity violation: {}", self.describe_violation(v));
+    }
+  }
+
+  pub(crate) fn check_integrity(&mut self, detached: &[WidgetId]) -> IntegrityReport {
+    let mut report = IntegrityReport::default();
+    report.violations.extend(
+      self
+        .debug
+        .dirty_after_removed
+        .drain(..)
+        .map(TreeViolation::DirtyAfterRemoved),
+    );
+
+    let mut changed = std::mem::take(&mut self.debug.changed);
+    let mut built = std::mem::take(&mut self.debug.built);
+    changed.retain(|id| !id.is_dropped(self));
+    built.retain(|id| !id.is_dropped(self));
+
+    // The nodes known to be reachable from the root, so every ancestor path is
+    // walked only once.
+    let mut reachable = HashSet::new();
+    let mut orphans = HashSet::new();
+    for id in changed.iter().chain(built.iter()) {
+      let mut path = vec![];
+      let mut top = *id;
+      for p in id.ancestors(self) {
+        if reachable.contains(&p) {
+          top = self.root();
+          break;
+        }
+        top = p;
+        path.push(p);
+      }
+      if top == self.root() {
+        reachable.extend(path);
+      } else if !detached.contains(&top) && orphans.insert(top) {
+        report
+          .violations
+          .push(TreeViolation::Unreachable(top));
+      }
+    }
+
+    // Check the new built widgets and every node of the changed subtrees once.
+    let mut checked = HashSet::new();
+    for id in built {
+      if checked.insert(id) {
+        self.check_node(id, &mut report);
+      }
+    }
+    let mut walked = HashSet::new();
+    let mut stack = changed;
+    while let Some(id) = stack.pop() {
+      if !walked.insert(id) {
+        continue;
+      }
+      if checked.insert(id) {
+        self.check_node(id, &mut report);
+      }
+      stack.extend(id.children(self));
+    }
+
+    report
+  }
+
+  fn check_node(&self, id: WidgetId, report: &mut IntegrityReport) {
+    let mut keys: HashSet<Key> = HashSet::default();
+    for c in id.children(self) {
+      if self.parent_link(c) != Some(id) {
+        report
+          .violations
+          .push(TreeViolation::AsymmetricLink { parent: id, child: c });
+      }
+      if let Some(key) = c.query_ref::<Box<dyn AnyKey>>(self) {
+        let key = key.key();
+        if !keys.insert(key.clone()) {
+          report
+            .warnings
+            .push(TreeWarning::DuplicateKey { parent: id, key });
+        }
+      }
+    }
+
+    if self.store.layout_box_size(id).is_some() {
+      if let Some(parent) = id.parent(self) {
+        if self.store.layout_box_size(parent).is_none() {
+          report
+            .violations
+            .push(TreeViolation::MissingParentLayout { id, parent });
+        }
+      }
+    }
+  }
+
+  fn parent_link(&self, id: WidgetId) -> Option<WidgetId> {
+    #[cfg(test)]
+    if let Some(p) = self.debug.corrupted_parents.get(&id) {
+      return *p;
+    }
+    id.parent(self)
+  }
+
+  fn describe_violation(&self, v: &TreeViolation) -> String {
+    match v {
+      TreeViolation::Unreachable(id) => {
+        format!("{} can't be reached from the root.", self.describe(*id))
+      }
+      TreeViolation::AsymmetricLink { parent, child } => format!(
+        "{} is a child of {}, but doesn't link back to it as its parent.",
+        self.describe(*child),
+        self.describe(*parent)
+      ),
+      TreeViolation::MissingParentLayout { id, parent } => format!(
+        "{} has its layout, but its parent {} doesn't.",
+        self.describe(*id),
+        self.describe(*parent)
+      ),
+      TreeViolation::DirtyAfterRemoved(id) => format!(
+        "the widget {id:?} was marked dirty after it was removed from the tree, a subscription \
+         outlives its widget."
+      ),
+    }
+  }
+
+  fn describe_warning(&self, w: &TreeWarning) -> String {
+    match w {
+      TreeWarning::DuplicateKey { parent, key } => {
+        format!("The children of {} use the same key `{key:?}`.", self.describe(*parent))
+      }
+    }
+  }
+
+  /// Describe a live widget with its type name and the location it was built.
+  fn describe(&self, id: WidgetId) -> String {
+    let name = id
+      .get(self)
+      .map_or("<removed>".into(), |r| r.debug_name());
+    let loc = std::iter::once(id)
+      .chain(id.ancestors(self).skip(1))
+      .find_map(|id| self.provenance.get(id)?.location);
+    match loc {
+      Some(loc) => format!("`{name}` (built at {loc})"),
+      None => format!("`{name}`"),
+    }
+  }
+}
+
+impl fmt::Debug for TreeDebugInfo {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("TreeDebugInfo")
+      .field("dirty_after_removed", &self.dirty_after_removed)
+      .finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*, widget_tree::widget_id::new_node};
+
+  fn wnd_with_leaf() -> (TestWindow, WidgetId, WidgetId) {
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti { @MockBox { size: Size::new(10., 10.) } }
+    });
+    wnd.draw_frame();
+    let tree = wnd.tree();
+    let multi = tree.content_root();
+    let leaf = multi.first_child(tree).unwrap();
+    (wnd, multi, leaf)
+  }
+
+  #[test]
+  fn valid_tree() {
+    reset_test_env!();
+
+    let (wnd, ..) = wnd_with_leaf();
+    let report = wnd.tree_mut().check_integrity(&[]);
+    assert!(report.violations.is_empty());
+    assert!(report.warnings.is_empty());
+  }
+
+  #[test]
+  fn unreachable_node() {
+    reset_test_env!();
+
+    let (wnd, ..) = wnd_with_leaf();
+    let tree = wnd.tree_mut();
+    let orphan = new_node(&mut tree.arena, Box::new(PureRender(Void)));
+    tree.debug.built.push(orphan);
+    let report = tree.check_integrity(&[]);
+    assert_eq!(report.violations, [TreeViolation::Unreachable(orphan)]);
+    tree.debug.built.push(orphan);
+    assert!(
+      tree
+        .check_integrity(&[orphan])
+        .violations
+        .is_empty()
+    );
+  }
+
+  #[test]
+  fn asymmetric_link() {
+    reset_test_env!();
+
+    let (wnd, multi, leaf) = wnd_with_leaf();
+    let tree = wnd.tree_mut();
+    tree.debug.corrupted_parents.insert(leaf, None);
+    tree.debug.changed.push(multi);
+    let report = tree.check_integrity(&[]);
+    assert_eq!(report.violations, [TreeViolation::AsymmetricLink { parent: multi, child: leaf }]);
+  }
+
+  #[test]
+  fn missing_parent_layout() {
+    reset_test_env!();
+
+    let (wnd, multi, leaf) = wnd_with_leaf();
+    let tree = wnd.tree_mut();
+    tree.store.remove(multi);
+    tree.debug.changed.push(multi);
+    let report = tree.check_integrity(&[]);
+    assert_eq!(report.violations, [TreeViolation::MissingParentLayout { id: leaf, parent: multi }]);
+  }
+
+  #[test]
+  fn dirty_after_removed() {
+    reset_test_env!();
+
+    let (wnd, _, leaf) = wnd_with_leaf();
+    let tree = wnd.tree_mut();
+    tree.remove_subtree(leaf);
+    tree.dirty_marker().mark(leaf);
+    tree.layout(Size::new(100., 100.));
+    let report = tree.check_integrity(&[]);
+    assert_eq!(report.violations, [TreeViolation::DirtyAfterRemoved(leaf)]);
+  }
+
+  #[test]
+  fn duplicate_keys() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @KeyWidget { key: "a", value: (), @MockBox { size: Size::zero() } }
+        @KeyWidget { key: "a", value: (), @MockBox { size: Size::zero() } }
+      }
+    });
+    wnd.draw_frame();
+    let tree = wnd.tree_mut();
+    let parent = tree.content_root();
+    tree.debug.changed.push(parent);
+    let report = tree.check_integrity(&[]);
+    assert!(report.violations.is_empty());
+    assert_eq!(report.warnings, [TreeWarning::DuplicateKey { parent, key: "a".into() }]);
+  }
+
+  #[test]
+  #[should_panic(expected = "`ribir_core::test_helper::MockBox` (built at core/src/")]
+  fn panic_with_type_name_and_location() {
+    reset_test_env!();
+
+    let (wnd, multi, _) = wnd_with_leaf();
+    let tree = wnd.tree_mut();
+    tree.store.remove(multi);
+    tree.debug.changed.push(multi);
+    tree.validate(&[]);
+  }
+
+  #[test]
+  fn only_validate_changed_subtrees() {
+    reset_test_env!();
+
+    let (wnd, multi, leaf) = wnd_with_leaf();
+    let tree = wnd.tree_mut();
+    tree.debug.corrupted_parents.insert(leaf, None);
+    assert!(tree.check_integrity(&[]).violations.is_empty());
+
+    tree.dirty_marker().mark(leaf);
+    tree.layout(Size::new(100., 100.));
+    let report = tree.check_integrity(&[]);
+    assert_eq!(report.violations, [TreeViolation::AsymmetricLink { parent: multi, child: leaf }]);
+  }
+}
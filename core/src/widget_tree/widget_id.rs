@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::ops::ControlFlow;
 
 use indextree::{Node, NodeId};
 use smallvec::{SmallVec, smallvec};
@@ -100,13 +100,15 @@ impl WidgetId {
       .map(|(a, _)| a)
   }
 
-  pub(crate) fn place_holder(self, tree: &WidgetTree) -> PlaceHolder {
+  /// The place of the widget in the tree, `None` if it has neither a parent
+  /// nor siblings.
+  pub(crate) fn place_holder(self, tree: &WidgetTree) -> Option<PlaceHolder> {
     if let Some(prev) = self.prev_sibling(tree) {
-      PlaceHolder::PrevSibling(prev)
+      Some(PlaceHolder::PrevSibling(prev))
     } else if let Some(next) = self.next_sibling(tree) {
-      PlaceHolder::NextSibling(next)
+      Some(PlaceHolder::NextSibling(next))
     } else {
-      PlaceHolder::Parent(self.parent(tree).unwrap())
+      self.parent(tree).map(PlaceHolder::Parent)
     }
   }
 
@@ -144,10 +146,6 @@ impl WidgetId {
     self.node_feature(tree, Node::previous_sibling)
   }
 
-  pub(crate) fn previous_sibling(self, tree: &WidgetTree) -> Option<WidgetId> {
-    self.node_feature(tree, |node| node.previous_sibling())
-  }
-
   #[allow(unused)]
   pub(crate) fn ancestor_of(self, other: WidgetId, tree: &WidgetTree) -> bool {
     other.ancestors(tree).any(|p| self == p)
@@ -158,21 +156,39 @@ impl WidgetId {
     // another node,so we need check it manually.
     assert!(!self.is_dropped(tree));
 
-    let last_elem = Rc::new(RefCell::new(self));
-    let last_elem2 = last_elem.clone();
-    self
-      .0
-      .ancestors(&tree.arena)
-      .map(move |id| {
-        *last_elem2.borrow_mut() = WidgetId(id);
-        WidgetId(id)
-      })
-      .chain(
-        Some(last_elem)
-          .into_iter()
-          .filter_map(|v| v.borrow().delay_drop_parent(tree))
-          .flat_map(|wid| wid.0.ancestors(&tree.arena).map(WidgetId)),
-      )
+    // The top of the detached subtree continues from its delay drop parent, only
+    // once.
+    let mut jumped = false;
+    std::iter::successors(Some(self), move |id| {
+      id.node_feature(tree, |node| node.parent())
+        .or_else(|| {
+          if jumped {
+            return None;
+          }
+          jumped = true;
+          id.delay_drop_parent(tree)
+        })
+    })
+  }
+
+  /// The ancestors that the widget inherits the providers from. It's the same
+  /// as `ancestors`, except that an overlay continues from its host, see
+  /// [`Overlay::with_host`].
+  pub(crate) fn provider_ancestors(self, tree: &WidgetTree) -> impl Iterator<Item = WidgetId> + '_ {
+    assert!(!self.is_dropped(tree));
+    let root = tree.root();
+    std::iter::successors(Some(self), move |id| {
+      let parent = id.parent(tree)?;
+      if parent == root {
+        let host = root
+          .query_ref::<ShowingOverlays>(tree)
+          .and_then(|overlays| overlays.host_of(*id, tree));
+        if host.is_some() {
+          return host;
+        }
+      }
+      Some(parent)
+    })
   }
 
   #[inline]
@@ -215,14 +231,24 @@ impl WidgetId {
 
   pub(crate) fn insert_after(self, next: WidgetId, tree: &mut WidgetTree) {
     self.0.insert_after(next.0, &mut tree.arena);
+    next.spread_bloom_to_parent(tree);
   }
 
   pub(crate) fn insert_before(self, prev: WidgetId, tree: &mut WidgetTree) {
     self.0.insert_before(prev.0, &mut tree.arena);
+    prev.spread_bloom_to_parent(tree);
   }
 
   pub(crate) fn append(self, child: WidgetId, tree: &mut WidgetTree) {
     self.0.append(child.0, &mut tree.arena);
+    child.spread_bloom_to_parent(tree);
+  }
+
+  fn spread_bloom_to_parent(self, tree: &mut WidgetTree) {
+    let bloom = tree.subtree_bloom(self);
+    if let Some(p) = self.node_feature(tree, |node| node.parent()) {
+      tree.spread_bloom(p, bloom);
+    }
   }
 
   /// Traverses to the leaf widget in the widget tree, returning it. Panics if
@@ -240,7 +266,7 @@ impl WidgetId {
     self.first_child(tree)
   }
 
-  fn node_feature(
+  pub(super) fn node_feature(
     self, tree: &WidgetTree, method: impl FnOnce(&Node<Box<dyn RenderQueryable>>) -> Option<NodeId>,
   ) -> Option<WidgetId> {
     tree
@@ -268,6 +294,7 @@ impl WidgetId {
       let copied = std::mem::replace(node, f(data));
       std::mem::forget(copied)
     }
+    tree.refresh_bloom(self);
   }
 
   pub(crate) fn attach_data(self, data: Box<dyn Query>, tree: &mut WidgetTree) {
@@ -279,8 +306,16 @@ impl WidgetId {
   }
 
   pub(crate) fn paint_subtree(self, ctx: &mut PaintingCtx) {
-    let mut w = Some(self);
-    while let Some(id) = w {
+    // The widgets to paint, a `None` restores the painter after the subtree of
+    // a widget is painted. It's taken out of the tree, so a nested paint uses
+    // its own buffer.
+    let mut stack = ctx.tree.paint_stack.take();
+    stack.push(Some(self));
+    while let Some(item) = stack.pop() {
+      let Some(id) = item else {
+        ctx.painter.restore();
+        continue;
+      };
       ctx.id = id;
       ctx.painter.save();
       let wnd = ctx.window();
@@ -293,30 +328,50 @@ impl WidgetId {
           ctx
             .painter
             .translate(layout_box.min_x(), layout_box.min_y());
-          render.paint(ctx);
+          let start = tree
+            .frame_costs
+            .borrow()
+            .is_enabled()
+            .then(clock::now);
+          guard_widget_panic(
+            ctx,
+            TreePhase::Paint,
+            id,
+            |ctx| render.paint(ctx),
+            |_, id| tree.provenance_path(id),
+          );
+          if let Some(start) = start {
+            tree
+              .frame_costs
+              .borrow_mut()
+              .add_paint(id, clock::now() - start);
+          }
           need_paint = true;
         }
       }
 
-      w = id
-        .first_child(tree)
-        .filter(|_| need_paint)
-        .or_else(|| {
-          let mut node = w;
-          while let Some(p) = node {
-            // self node sub-tree paint finished, goto sibling
-            ctx.painter.restore();
-            node = p.next_sibling(tree);
-            if node.is_some() {
-              break;
-            } else if p != self {
-              // if there is no more sibling, back to parent to find sibling.
-              node = p.parent(tree);
-            }
-          }
-          node
-        });
+      stack.push(None);
+      if need_paint {
+        let children = id.paint_ordered_children(tree);
+        stack.extend(children.into_iter().rev().map(Some));
+      }
     }
+    ctx.tree.paint_stack.replace(stack);
+  }
+
+  /// The children in the order that they're painted, the later ones are
+  /// painted above and hit first. They're ordered by their
+  /// [`Render::z_index`], and by their order in the tree if the same.
+  pub(crate) fn paint_ordered_children(self, tree: &WidgetTree) -> SmallVec<[WidgetId; 8]> {
+    let mut children: SmallVec<[_; 8]> = self
+      .children(tree)
+      .map(|c| (c.assert_get(tree).z_index(), c))
+      .collect();
+    if children.iter().any(|(z, _)| *z != 0) {
+      // A stable sort keeps the order of the same z index.
+      children.sort_by_key(|(z, _)| *z);
+    }
+    children.into_iter().map(|(_, c)| c).collect()
   }
 }
 
@@ -372,6 +427,51 @@ impl WidgetId {
       .find_map(|id| id.query_ref::<T>(tree))
   }
 
+  /// Query the nearest ancestor that has a `T`, not including this widget.
+  pub(crate) fn query_ancestor<T: Any>(
+    self, tree: &WidgetTree,
+  ) -> Option<(WidgetId, QueryRef<'_, T>)> {
+    self
+      .ancestors(tree)
+      .skip(1)
+      .find_map(|id| id.query_ref::<T>(tree).map(|r| (id, r)))
+  }
+
+  /// Query all the descendants that have a `T`, not including this widget.
+  pub(crate) fn query_descendants<T: Any>(
+    self, tree: &WidgetTree,
+  ) -> Vec<(WidgetId, QueryRef<'_, T>)> {
+    let mut out = vec![];
+    let _ = self.visit_descendants(tree, |id, q| {
+      out.push((id, q));
+      ControlFlow::Continue(())
+    });
+    out
+  }
+
+  /// Visit the descendants that have a `T` in depth-first order, not including
+  /// this widget, until `f` breaks. The subtrees that can't contain a `T` are
+  /// skipped.
+  pub(crate) fn visit_descendants<'t, T: Any>(
+    self, tree: &'t WidgetTree, mut f: impl FnMut(WidgetId, QueryRef<'t, T>) -> ControlFlow<()>,
+  ) -> ControlFlow<()> {
+    let query_id = QueryId::of::<T>();
+    let mut stack: SmallVec<[WidgetId; 8]> = self.children(tree).rev().collect();
+    while let Some(w) = stack.pop() {
+      let may_contain = tree
+        .query_blooms
+        .get(&w)
+        .is_none_or(|bloom| bloom.may_contain(&query_id));
+      if may_contain {
+        if let Some(r) = w.query_ref::<T>(tree) {
+          f(w, r)?;
+        }
+        stack.extend(w.children(tree).rev());
+      }
+    }
+    ControlFlow::Continue(())
+  }
+
   /// return if this object contain type `T`
   pub(crate) fn contain_type<T: Any>(self, tree: &WidgetTree) -> bool {
     self
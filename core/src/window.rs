@@ -2,6 +2,7 @@ use std::{
   cell::{Cell, RefCell},
   collections::VecDeque,
   convert::Infallible,
+  panic::Location,
   ptr::NonNull,
 };
 
@@ -11,13 +12,20 @@ use widget_id::TrackId;
 use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 pub use winit::window::CursorIcon;
 
+#[cfg(feature = "ribir_alloc_track")]
+use crate::alloc_track::FrameAllocs;
 use crate::{
+  alloc_track::{FrameAllocRecorder, FramePhase},
+  announce::Announcer,
+  context::AfterLayoutQueue,
   events::{
+    DefaultActions,
     dispatcher::Dispatcher,
     focus_mgr::{FocusManager, FocusType},
   },
   prelude::*,
   ticker::{FrameMsg, FrameTicker},
+  watchdog::FramePhases,
 };
 
 /// Window is the root to represent.
@@ -35,6 +43,9 @@ pub struct Window {
   pub(crate) focus_mgr: RefCell<FocusManager>,
   pub(crate) running_animates: Sc<Cell<u32>>,
   pre_edit: RefCell<Option<String>>,
+  /// A listener prevented the default action of the last key down, so the
+  /// characters it types are not received.
+  key_chars_prevented: Cell<bool>,
   /// This vector store the task to emit events. When perform layout, dispatch
   /// event and so on, some part of window may be already mutable borrowed and
   /// the user event callback may also query borrow that part, so we can't emit
@@ -54,6 +65,36 @@ pub struct Window {
   ///
   /// This widgets it's detached from its parent, but still need to paint.
   pub(crate) delay_drop_widgets: RefCell<Vec<(Option<WidgetId>, TrackId)>>,
+  /// The widgets attached with a global key, see [`GlobalKey`].
+  pub(crate) global_keys: RefCell<ahash::HashMap<GlobalKey, TrackId>>,
+  /// The drag in progress, see [`Draggable`].
+  pub(crate) drag: RefCell<Option<DragSession>>,
+  /// The widget that holds the pointer lock, see
+  /// [`PointerEvent::request_pointer_lock`].
+  pub(crate) pointer_lock: Cell<Option<WidgetId>>,
+  /// The widgets that capture the pointers, see
+  /// [`CommonEvent::set_pointer_capture`].
+  pub(crate) pointer_captures: RefCell<ahash::HashMap<PointerId, WidgetId>>,
+  /// The announcements to speak at the end of the frame, see
+  /// [`Semantics::announce`].
+  pub(crate) announcer: RefCell<Announcer>,
+  /// The cursor of the pointer, see [`Window::set_pointer_cursor`].
+  pointer_cursor: RefCell<PointerCursor>,
+  pub(crate) cursor_sprite: RefCell<CursorSprite>,
+  scroll_restoration: RefCell<ScrollRestoration>,
+  /// The guides painted over the widgets, see [`Window::set_debug_overlay`].
+  pub(crate) debug_overlay: Cell<DebugOverlay>,
+  /// The callbacks to run after the next layout, see
+  /// [`Window::once_after_layout`].
+  after_layout: RefCell<AfterLayoutQueue>,
+  /// The media information of the window, updated at the start of a frame.
+  media: Stateful<MediaInfo>,
+  /// The zoom of the UI chosen by the user, see [`Window::set_ui_scale`].
+  ui_scale: Cell<f32>,
+  clear_color: Cell<Option<Color>>,
+  resize: RefCell<ResizeState>,
+  frame_stats: Cell<FrameStats>,
+  startup: RefCell<Startup>,
 
   flags: Cell<WindowFlags>,
 }
@@ -66,10 +107,168 @@ bitflags! {
     activate all animations; if this flag is not marked, all animations\
     will not run."]
     const ANIMATIONS = 1 << 0;
+    #[doc="Set this flag to record how the dirty widgets spread the relayout \
+    to their ancestors in every frame, see `Window::layout_diagnostics`."]
+    const LAYOUT_DIAGNOSTICS = 1 << 1;
+    #[doc="Set this flag to zoom the UI by the keyboard, `Ctrl` + `=` zooms in, \
+    `Ctrl` + `-` zooms out and `Ctrl` + `0` resets the zoom. It's `Cmd` \
+    instead of `Ctrl` on macOS. See `Window::set_ui_scale`."]
+    const ZOOM_SHORTCUTS = 1 << 2;
+    #[doc="Set this flag to leave the debug overlay out of the frames taken by \
+    `Window::capture_frame`, see `Window::set_debug_overlay`."]
+    const CAPTURE_WITHOUT_DEBUG_OVERLAY = 1 << 3;
     const DEFAULT = Self::ANIMATIONS.bits();
   }
 }
 
+/// The minimum zoom of the UI, see [`Window::set_ui_scale`].
+pub const MIN_UI_SCALE: f32 = 0.5;
+/// The maximum zoom of the UI, see [`Window::set_ui_scale`].
+pub const MAX_UI_SCALE: f32 = 3.;
+/// The step that the zoom shortcuts change the zoom of the UI by, see
+/// [`WindowFlags::ZOOM_SHORTCUTS`].
+pub const UI_SCALE_STEP: f32 = 0.1;
+
+/// How the window relayouts its content while it's resizing, see
+/// [`Window::set_resize_strategy`].
+///
+/// A deferred resize stretches the last frame to the new size until the
+/// relayout, and the pointer is mapped through the stretch to hit the
+/// widgets. The final size of the window always gets a relayout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResizeStrategy {
+  /// Relayout for every change of the size.
+  #[default]
+  Immediate,
+  /// Relayout once the size has not changed for the duration.
+  Debounced(Duration),
+  /// Relayout at most the number of times per second.
+  Throttled(u32),
+}
+
+/// The resize deferred by the [`ResizeStrategy`] of the window.
+#[derive(Default)]
+struct ResizeState {
+  strategy: ResizeStrategy,
+  /// The size of the shell window that the content is laid out for.
+  size: Size,
+  /// The size that the shell window is resizing to, and when it changed.
+  pending: Option<(Size, Instant)>,
+  /// When the content was last laid out for a new size.
+  applied_at: Option<Instant>,
+  /// The commands of the last frame, to stretch it while the resize is
+  /// deferred.
+  last_frame: Option<Resource<Box<[PaintCommand]>>>,
+  /// The size that the last frame was stretched to.
+  stretched_to: Option<Size>,
+  wakeup: Option<TaskHandle<NormalReturn<()>>>,
+}
+
+/// The count of the frames that the window painted or skipped, see
+/// [`Window::frame_stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStats {
+  /// The frames that painted the window.
+  pub painted: usize,
+  /// The frames that skipped painting because nothing changed.
+  pub skipped: usize,
+  /// The allocations in the phases of the last frame, see
+  /// [`alloc_track`](crate::alloc_track).
+  #[cfg(feature = "ribir_alloc_track")]
+  pub allocs: FrameAllocs,
+}
+
+/// How long the phases before the first frame of the window took, see
+/// [`Window::first_frame_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstFrameReport {
+  /// From the start of the application to the creation of the window, it
+  /// includes starting the render backend of the window.
+  pub startup: Duration,
+  /// Building the widgets of the window.
+  pub build: Duration,
+  /// Laying out the widgets in the first frame.
+  pub layout: Duration,
+  /// Painting the widgets in the first frame.
+  pub paint: Duration,
+  /// Submitting the painted commands to the render backend and presenting
+  /// them.
+  pub present: Duration,
+  /// From the start of the application to the first frame presented.
+  pub total: Duration,
+  /// The services that weren't used before the first frame, they're
+  /// initialized at their first use.
+  pub lazy_services: Vec<LazyService>,
+}
+
+/// The startup of the window, until its first frame.
+struct Startup {
+  created_at: Instant,
+  build: Duration,
+  report: Option<FirstFrameReport>,
+}
+
+/// The information of the window that an app may adapt its layout to, see
+/// [`BuildCtx::media`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MediaInfo {
+  /// The logical size of the window.
+  pub size: Size,
+  /// The ratio of the physical pixels to the logical pixels.
+  pub scale: f32,
+  /// Whether touch is the primary input of the window, see
+  /// [`Window::set_touch_first`].
+  pub touch: bool,
+}
+
+/// Whether the window is taller than it is wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+  Portrait,
+  Landscape,
+}
+
+/// The class of the device that the window is shown on, guessed from the
+/// size of the window and whether it is touch-first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceClass {
+  Phone,
+  Tablet,
+  Desktop,
+}
+
+impl MediaInfo {
+  pub fn orientation(&self) -> Orientation {
+    if self.size.height > self.size.width { Orientation::Portrait } else { Orientation::Landscape }
+  }
+
+  /// A touch-first window is a phone if its shortest side is less than 600
+  /// logical pixels, otherwise a tablet. A window without touch is a desktop.
+  pub fn device_class(&self) -> DeviceClass {
+    if !self.touch {
+      DeviceClass::Desktop
+    } else if self.size.width.min(self.size.height) < 600. {
+      DeviceClass::Phone
+    } else {
+      DeviceClass::Tablet
+    }
+  }
+}
+
+/// The edge or the corner of a window that a resize drag moves, see
+/// [`Window::begin_resize_drag`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResizeEdge {
+  Top,
+  Bottom,
+  Left,
+  Right,
+  TopLeft,
+  TopRight,
+  BottomLeft,
+  BottomRight,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
 pub struct WindowId(u64);
 
@@ -85,6 +284,11 @@ pub trait ShellWindow {
   fn set_min_size(&mut self, size: Size);
   fn cursor(&self) -> CursorIcon;
   fn set_cursor(&mut self, cursor: CursorIcon);
+  /// Use the image as the cursor, returns `false` if the platform can't, then
+  /// the window paints the image following the pointer instead.
+  fn set_image_cursor(&mut self, cursor: &ImageCursor) -> bool;
+  /// Show or hide the cursor of the platform.
+  fn set_cursor_visible(&mut self, visible: bool);
   fn set_title(&mut self, str: &str);
   fn set_icon(&mut self, icon: &PixelImage);
   fn is_visible(&self) -> Option<bool>;
@@ -93,8 +297,22 @@ pub trait ShellWindow {
   fn set_resizable(&mut self, resizable: bool);
   fn is_minimized(&self) -> bool;
   fn set_minimized(&mut self, minimized: bool);
+  fn is_maximized(&self) -> bool;
+  fn set_maximized(&mut self, maximized: bool);
+  /// Ask to close the window, as if the user clicks the close button of the
+  /// window.
+  fn request_close(&mut self);
   fn focus_window(&mut self);
   fn set_decorations(&mut self, decorations: bool);
+  /// Start to move the window with the pressed pointer, until the pointer is
+  /// released.
+  fn begin_move_drag(&mut self);
+  /// Start to resize the window from the `edge` with the pressed pointer,
+  /// until the pointer is released.
+  fn begin_resize_drag(&mut self, edge: ResizeEdge);
+  /// Hide the cursor and lock it in place if `locked`, or release it and show
+  /// it again.
+  fn set_pointer_locked(&mut self, locked: bool);
   fn as_any(&self) -> &dyn Any;
   fn as_any_mut(&mut self) -> &mut dyn Any;
   /// The device pixel ratio of Window interface returns the ratio of the
@@ -110,7 +328,30 @@ impl Window {
   #[deprecated(note = "The core window should not depends on shell window event.")]
   #[inline]
   /// processes native events from this native window
-  pub fn processes_native_event(&self, event: WindowEvent) {
+  pub fn processes_native_event(&self, mut event: WindowEvent) {
+    let input = match &event {
+      WindowEvent::CursorMoved { .. } => Some("cursor_moved"),
+      WindowEvent::CursorLeft { .. } => Some("cursor_left"),
+      WindowEvent::MouseWheel { .. } => Some("mouse_wheel"),
+      WindowEvent::Touch(_) => Some("touch"),
+      WindowEvent::ModifiersChanged(_) => Some("modifiers_changed"),
+      _ => None,
+    };
+    if let Some(input) = input {
+      AppCtx::set_last_input(input);
+    }
+    if matches!(event, WindowEvent::Touch(_)) {
+      self.set_touch_first(true);
+    }
+    if let WindowEvent::CursorMoved { position, .. } = &mut event {
+      // Map the pointer through the stretched frame to the laid out content.
+      let content = self.resize.borrow().size;
+      let size = self.shell_wnd.borrow().inner_size();
+      if content != size && !size.is_empty() {
+        position.x *= (content.width / size.width) as f64;
+        position.y *= (content.height / size.height) as f64;
+      }
+    }
     let ratio = self.device_pixel_ratio() as f64;
     self
       .dispatcher
@@ -122,13 +363,54 @@ impl Window {
     &self, physical_key: PhysicalKey, key: VirtualKey, is_repeat: bool, location: KeyLocation,
     state: ElementState,
   ) {
+    AppCtx::set_last_input("keyboard");
+    if state == ElementState::Pressed
+      && key == VirtualKey::Named(NamedKey::Escape)
+      && self.pointer_lock.get().is_some()
+    {
+      self.exit_pointer_lock();
+      return;
+    }
+    if state == ElementState::Pressed
+      && self.flags().contains(WindowFlags::ZOOM_SHORTCUTS)
+      && self.zoom_by_shortcut(&key)
+    {
+      return;
+    }
+    if state == ElementState::Pressed {
+      let modifiers = self.dispatcher.borrow().info.modifiers();
+      if AppCtx::execute_shortcut(modifiers, &key) {
+        return;
+      }
+    }
     self
       .dispatcher
       .borrow_mut()
       .dispatch_keyboard_input(physical_key, key, is_repeat, location, state);
   }
 
+  fn zoom_by_shortcut(&self, key: &VirtualKey) -> bool {
+    let modifiers = self.dispatcher.borrow().info.modifiers();
+    #[cfg(target_os = "macos")]
+    let command = modifiers.super_key();
+    #[cfg(not(target_os = "macos"))]
+    let command = modifiers.control_key();
+    if !command {
+      return false;
+    }
+
+    let scale = match key {
+      VirtualKey::Character(c) if c == "=" || c == "+" => self.ui_scale() + UI_SCALE_STEP,
+      VirtualKey::Character(c) if c == "-" => self.ui_scale() - UI_SCALE_STEP,
+      VirtualKey::Character(c) if c == "0" => 1.,
+      _ => return false,
+    };
+    self.set_ui_scale(scale);
+    true
+  }
+
   pub fn processes_receive_chars(&self, chars: String) {
+    AppCtx::set_last_input("chars");
     self
       .dispatcher
       .borrow_mut()
@@ -136,6 +418,7 @@ impl Window {
   }
 
   pub fn processes_ime_pre_edit(&self, ime: ImePreEdit) {
+    AppCtx::set_last_input("ime_pre_edit");
     self
       .dispatcher
       .borrow_mut()
@@ -143,12 +426,23 @@ impl Window {
   }
 
   pub fn process_mouse_input(&self, device_id: DeviceId, state: ElementState, button: MouseButton) {
+    AppCtx::set_last_input("mouse_input");
     self
       .dispatcher
       .borrow_mut()
       .dispatch_mouse_input(device_id, state, button);
   }
 
+  /// Process a packet of the pen input, the platform backends that support the
+  /// pen call it to dispatch the pen as pointer events.
+  pub fn process_pen_input(&self, packet: PenPacket) {
+    AppCtx::set_last_input("pen");
+    self
+      .dispatcher
+      .borrow_mut()
+      .dispatch_pen_input(packet);
+  }
+
   /// Request switch the focus to next widget.
   pub fn request_next_focus(&self) {
     self
@@ -180,6 +474,23 @@ impl Window {
     self.once_on_lifecycle(f, |msg| matches!(msg, FrameMsg::BeforeLayout(_)))
   }
 
+  /// Execute the callback after the next layout, before the frame is painted.
+  /// It reads the fresh layout of the widgets, such as to place a popup by its
+  /// anchor. The states that it modifies are laid out in the next frame, the
+  /// layout of the current frame never changes.
+  ///
+  /// It requests a new frame. If the callbacks of a call site schedule
+  /// themselves in many frames in a row, a warning is logged.
+  #[track_caller]
+  pub fn once_after_layout(&self, f: impl FnOnce(&LayoutResultCtx) + 'static) {
+    self
+      .after_layout
+      .borrow_mut()
+      .push(Location::caller(), Box::new(f));
+    let tree = self.tree();
+    tree.dirty_marker().mark(tree.root());
+  }
+
   /// Return an `rxRust` Scheduler, which will guarantee all task add to the
   /// scheduler will finished before current frame finished.
   #[inline]
@@ -212,49 +523,185 @@ impl Window {
   /// Draw an image what current render tree represent.
   #[track_caller]
   pub fn draw_frame(&self) -> bool {
+    let frame_start = clock::now();
+    let mut allocs = FrameAllocRecorder::start();
+    self
+      .tree()
+      .frame_costs
+      .borrow_mut()
+      .begin(AppCtx::is_watchdog_on());
     AppCtx::run_until_stalled();
     let mut ticker = self.frame_ticker.clone();
-    ticker.next(FrameMsg::NewFrame(Instant::now()));
+    // The time of the frame, every animation in this frame samples at it, so
+    // they step by the real interval of the frames whatever the refresh rate.
+    let frame_time = clock::now();
+    ticker.next(FrameMsg::NewFrame(frame_time));
+    self.update_painter_viewport();
+    self.update_media();
     self.run_frame_tasks();
 
-    self.update_painter_viewport();
-    let draw = self.need_draw() && !self.size().is_empty();
+    let repaint = self.need_draw();
+    let draw = (repaint || self.need_stretch()) && !self.size().is_empty();
+    let mut phases = None;
     if draw {
       let root = self.tree().root();
 
-      let surface = {
+      let surface = self.clear_color.get().unwrap_or_else(|| {
         let _guard = BuildCtx::init_for(root, self.tree);
         Palette::of(BuildCtx::get()).surface()
-      };
+      });
       self.shell_wnd.borrow_mut().begin_frame(surface);
 
-      ticker.next(FrameMsg::BeforeLayout(Instant::now()));
-      self.layout();
-
-      self.tree().draw();
-      self.draw_delay_drop_widgets();
+      if repaint {
+        ticker.next(FrameMsg::BeforeLayout(frame_time));
+        allocs.end(FramePhase::Tasks);
+        let layout_start = clock::now();
+        self.layout();
+        self.run_after_layout();
+        allocs.end(FramePhase::Layout);
+
+        let paint_start = clock::now();
+        self.tree().draw();
+        self.draw_delay_drop_widgets();
+        self.draw_focus_ring();
+        self.draw_debug_overlay();
+        self.tree_mut().store.end_frame();
+        #[cfg(debug_assertions)]
+        self.validate_tree();
+        allocs.end(FramePhase::Paint);
+        phases = Some([layout_start, paint_start, clock::now()]);
+      }
 
-      let mut shell = self.shell_wnd.borrow_mut();
-      let inner_size = shell.inner_size();
-      let mut painter = self.painter.borrow_mut();
-      shell.draw_commands(Rect::from_size(inner_size), &painter.finish());
+      self.present_frame(repaint);
+      self.shell_wnd.borrow_mut().end_frame();
+      if let Some(phases) = phases {
+        self.report_first_frame(phases);
+      }
+    }
 
-      shell.end_frame();
+    let mut stats = self.frame_stats.get();
+    if draw {
+      stats.painted += 1;
+    } else {
+      stats.skipped += 1;
     }
+    self.frame_stats.set(stats);
+    self
+      .tree()
+      .build_profiler
+      .borrow_mut()
+      .end_frame();
+    self.send_announcements();
+    AppCtx::watch_frame(
+      self,
+      FramePhases {
+        start: frame_start,
+        layout: phases.map(|[layout, ..]| layout),
+        paint: phases.map(|[_, paint, _]| paint),
+        present: phases.map(|[.., present]| present),
+      },
+    );
 
     AppCtx::end_frame();
-    ticker.next(FrameMsg::Finish(Instant::now()));
+    ticker.next(FrameMsg::Finish(clock::now()));
     ticker.retain();
 
+    allocs.end(if draw { FramePhase::Present } else { FramePhase::Tasks });
+    #[cfg(feature = "ribir_alloc_track")]
+    self
+      .frame_stats
+      .set(FrameStats { allocs: allocs.finish(), ..self.frame_stats.get() });
+
     draw
   }
 
+  fn run_after_layout(&self) {
+    let callbacks = self.after_layout.borrow_mut().take_frame();
+    if !callbacks.is_empty() {
+      let ctx = LayoutResultCtx { tree: self.tree() };
+      callbacks.into_iter().for_each(|f| f(&ctx));
+    }
+  }
+
+  /// Record the first painted frame, the `phases` are the starts of its layout,
+  /// paint and present.
+  fn report_first_frame(&self, phases: [Instant; 3]) {
+    let mut startup = self.startup.borrow_mut();
+    if startup.report.is_some() {
+      return;
+    }
+    let [layout_start, paint_start, present_start] = phases;
+    let now = clock::now();
+    let app_start = AppCtx::started_at();
+    let lazy_services = [LazyService::Clipboard, LazyService::Ime, LazyService::SystemFonts]
+      .into_iter()
+      .filter(|s| !AppCtx::is_service_initialized(*s))
+      .collect();
+    startup.report = Some(FirstFrameReport {
+      startup: startup
+        .created_at
+        .saturating_duration_since(app_start),
+      build: startup.build,
+      layout: paint_start - layout_start,
+      paint: present_start - paint_start,
+      present: now - present_start,
+      total: now.saturating_duration_since(app_start),
+      lazy_services,
+    });
+  }
+
+  /// Present the painted frame, or the last frame if it's not `repainted`. The
+  /// frame is stretched to the shell window if the resize is deferred.
+  fn present_frame(&self, repainted: bool) {
+    let mut shell = self.shell_wnd.borrow_mut();
+    let inner_size = shell.inner_size();
+    let viewport = Rect::from_size(inner_size);
+    let mut painter = self.painter.borrow_mut();
+    let mut resize = self.resize.borrow_mut();
+    if resize.strategy == ResizeStrategy::Immediate {
+      shell.draw_commands(viewport, &painter.finish());
+      return;
+    }
+
+    if repainted {
+      let cmds = painter.finish().take().into_boxed_slice();
+      resize.last_frame = Some(Resource::new(cmds));
+    }
+    let Some(cmds) = resize.last_frame.clone() else { return };
+    let size = resize.size;
+    if size == inner_size {
+      shell.draw_commands(viewport, &cmds);
+    } else {
+      resize.stretched_to = Some(inner_size);
+      let (sx, sy) = (inner_size.width / size.width, inner_size.height / size.height);
+      let stretched = PaintCommand::Bundle {
+        transform: Transform::scale(sx, sy),
+        opacity: 1.,
+        bounds: Rect::from_size(size),
+        cmds,
+      };
+      shell.draw_commands(viewport, &[stretched]);
+    }
+  }
+
+  /// Whether the last frame need to stretch to a new size of the shell window.
+  fn need_stretch(&self) -> bool {
+    let resize = self.resize.borrow();
+    resize.pending.is_some()
+      && resize.last_frame.is_some()
+      && resize.stretched_to != Some(self.shell_wnd.borrow().inner_size())
+  }
+
   pub fn layout(&self) {
+    self.tree_mut().layout_diagnostics = self
+      .flags()
+      .contains(WindowFlags::LAYOUT_DIAGNOSTICS)
+      .then(LayoutDiagnostics::default);
     loop {
       self.run_frame_tasks();
 
       let tree = self.tree_mut();
-      tree.layout(self.shell_wnd.borrow().inner_size());
+      tree.layout(self.size());
       self.run_frame_tasks();
 
       if !tree.is_dirty() {
@@ -263,7 +710,7 @@ impl Window {
       }
 
       if !tree.is_dirty() {
-        let ready = FrameMsg::LayoutReady(Instant::now());
+        let ready = FrameMsg::LayoutReady(clock::now());
         self.frame_ticker.clone().next(ready);
         self.run_frame_tasks();
       }
@@ -274,28 +721,142 @@ impl Window {
     }
   }
 
+  /// Follow the size of the shell window, unless the [`ResizeStrategy`] defers
+  /// it.
   pub fn update_painter_viewport(&self) {
     let size = self.shell_wnd.borrow().inner_size();
-    if self.painter.borrow().viewport().size != size {
-      let tree = self.tree_mut();
-      let root = tree.root();
-      tree.dirty_marker().mark(root);
-      tree.store.remove(root);
-      let mut painter = self.painter.borrow_mut();
-      painter.set_viewport(Rect::from_size(size));
-      painter.reset();
+    let mut resize = self.resize.borrow_mut();
+    if resize.size == size {
+      resize.pending = None;
+      return;
+    }
+
+    let now = clock::now();
+    let changed_at = match resize.pending {
+      // Already waiting for the size.
+      Some((pending, _)) if pending == size && resize.wakeup.is_some() => return,
+      Some((pending, at)) if pending == size => at,
+      _ => now,
+    };
+    resize.pending = Some((size, changed_at));
+    let due = match resize.strategy {
+      // Nothing to stretch from or to.
+      _ if resize.last_frame.is_none() || resize.size.is_empty() || size.is_empty() => None,
+      ResizeStrategy::Immediate => None,
+      ResizeStrategy::Debounced(delay) => Some(changed_at + delay),
+      ResizeStrategy::Throttled(max_hz) => {
+        let interval = Duration::from_secs(1) / max_hz.max(1);
+        resize.applied_at.map(|at| at + interval)
+      }
+    };
+
+    match due.filter(|due| *due > now) {
+      Some(due) => {
+        // Wake up to relayout, even if the window has nothing else to draw.
+        let id = self.id();
+        let wakeup = observable::timer((), due - now, AppCtx::scheduler()).subscribe(move |_| {
+          if let Some(wnd) = AppCtx::get_window(id) {
+            wnd.resize.borrow_mut().wakeup = None;
+            wnd.update_painter_viewport();
+          }
+        });
+        if let Some(old) = resize.wakeup.replace(wakeup) {
+          old.unsubscribe();
+        }
+      }
+      None => {
+        drop(resize);
+        self.reset_viewport(size);
+      }
     }
   }
 
+  /// Relayout the whole tree and reset the painter to paint on the `size` of
+  /// the shell window with the current zoom of the UI.
+  fn reset_viewport(&self, size: Size) {
+    let mut resize = self.resize.borrow_mut();
+    resize.size = size;
+    resize.pending = None;
+    resize.applied_at = Some(clock::now());
+    resize.stretched_to = None;
+    if let Some(wakeup) = resize.wakeup.take() {
+      wakeup.unsubscribe();
+    }
+    drop(resize);
+
+    let tree = self.tree_mut();
+    let root = tree.root();
+    tree.dirty_marker().mark(root);
+    tree.store.remove(root);
+    let mut painter = self.painter.borrow_mut();
+    painter.set_viewport(Rect::from_size(size));
+    let scale = self.ui_scale();
+    painter.set_init_transform(Transform::scale(scale, scale));
+    painter.reset();
+  }
+
+  fn update_media(&self) {
+    let touch = self.media.read().touch;
+    let media = MediaInfo { size: self.size(), scale: self.device_pixel_ratio(), touch };
+    if *self.media.read() != media {
+      *self.media.write() = media;
+    }
+  }
+
+  /// Paint the whole window again in the next frame, e.g. when the last frame
+  /// failed to present.
+  pub fn request_repaint(&self) {
+    let tree = self.tree();
+    tree.dirty_marker().mark(tree.root());
+  }
+
   pub fn need_draw(&self) -> bool { self.tree().is_dirty() || self.running_animates.get() > 0 }
 
+  /// Whether the window has nothing to paint, no dirty widget, no running
+  /// animation, no pending task of the frame and no resize to follow. The event
+  /// loop sleeps until something wakes it if all the windows are idle.
+  pub fn is_idle(&self) -> bool {
+    if self.need_draw()
+      || !self.delay_emitter.borrow().is_empty()
+      || !self.priority_task_queue.is_empty()
+    {
+      return false;
+    }
+    let size = self.shell_wnd.borrow().inner_size();
+    let resize = self.resize.borrow();
+    // A deferred resize wakes the window by its timer, after the last frame is
+    // stretched.
+    resize.size == size || (resize.wakeup.is_some() && resize.stretched_to == Some(size))
+  }
+
+  /// How many frames the window painted or skipped.
+  pub fn frame_stats(&self) -> FrameStats { self.frame_stats.get() }
+
+  /// The builds of the compose widgets and the regenerations of the pipes in
+  /// the last frame that built any, while the build profiler is on. The builds
+  /// before the first frame count in the first frame. See
+  /// [`AppCtx::set_build_profiling`].
+  pub fn build_profile(&self) -> BuildProfile { self.tree().build_profiler.borrow().last() }
+
+  /// How long the startup of the window took until its first frame, `None`
+  /// before the first frame is painted.
+  pub fn first_frame_report(&self) -> Option<FirstFrameReport> {
+    self.startup.borrow().report.clone()
+  }
+
   pub fn new(shell_wnd: Box<dyn ShellWindow>) -> Sc<Self> {
     let wnd_id = shell_wnd.id();
     let focus_mgr = RefCell::new(FocusManager::new(wnd_id));
     let tree = Box::new(WidgetTree::new(wnd_id));
     let dispatcher = RefCell::new(Dispatcher::new(wnd_id));
+    let ui_scale = AppCtx::ui_scale();
     let size = shell_wnd.inner_size();
-    let painter = Painter::new(Rect::from_size(size));
+    let scale = shell_wnd.device_pixel_ratio() * ui_scale;
+    let touch = cfg!(any(target_os = "android", target_os = "ios"));
+    let media = Stateful::new(MediaInfo { size: size / ui_scale, scale, touch });
+    let mut painter = Painter::new(Rect::from_size(size));
+    painter.set_init_transform(Transform::scale(ui_scale, ui_scale));
+    painter.reset();
     let window = Self {
       tree: NonNull::new(Box::into_raw(tree)).unwrap(),
       dispatcher,
@@ -308,15 +869,42 @@ impl Window {
       priority_task_queue: PriorityTaskQueue::default(),
       shell_wnd: RefCell::new(shell_wnd),
       delay_drop_widgets: <_>::default(),
+      drag: <_>::default(),
+      pointer_lock: <_>::default(),
+      pointer_captures: <_>::default(),
+      announcer: <_>::default(),
+      pointer_cursor: <_>::default(),
+      cursor_sprite: <_>::default(),
+      scroll_restoration: <_>::default(),
+      debug_overlay: <_>::default(),
+      after_layout: <_>::default(),
+      global_keys: <_>::default(),
+      media,
+      ui_scale: Cell::new(ui_scale),
+      clear_color: Cell::new(None),
+      resize: RefCell::new(ResizeState { size, ..Default::default() }),
+      frame_stats: Cell::default(),
+      startup: RefCell::new(Startup {
+        created_at: Instant::now(),
+        build: Duration::ZERO,
+        report: None,
+      }),
       flags: Cell::new(WindowFlags::DEFAULT),
       pre_edit: <_>::default(),
+      key_chars_prevented: <_>::default(),
     };
 
     Sc::new(window)
   }
 
   pub fn init(&self, content: GenWidget) {
+    let build_start = Instant::now();
     let root = self.tree_mut().init(self, content);
+    let mut startup = self.startup.borrow_mut();
+    if startup.report.is_none() {
+      startup.build += build_start.elapsed();
+    }
+    drop(startup);
     let _guard = BuildCtx::init_for(root, self.tree);
     let ctx = BuildCtx::get();
     let brush = Palette::of(ctx).on_surface_variant();
@@ -353,7 +941,69 @@ impl Window {
     self.delay_emitter.borrow_mut().push_back(e);
   }
 
-  fn draw_delay_drop_widgets(&self) {
+  /// Add a pointer move to the delay events. If the last delay event is a move
+  /// of the same target, the move is coalesced into it as a sample, so the
+  /// moves between two frames only dispatch one event.
+  pub(crate) fn add_pointer_move(&self, id: WidgetId, grab: bool, sample: PointerSample) {
+    let mut emitter = self.delay_emitter.borrow_mut();
+    let last = match emitter.back_mut() {
+      Some(DelayEvent::PointerMove { id: last, samples }) if !grab => Some((last, samples)),
+      Some(DelayEvent::GrabPointerMove { id: last, samples }) if grab => Some((last, samples)),
+      _ => None,
+    };
+    match last {
+      Some((last, samples)) if *last == id => samples.push(sample),
+      _ => {
+        let samples = vec![sample];
+        emitter.push_back(if grab {
+          DelayEvent::GrabPointerMove { id, samples }
+        } else {
+          DelayEvent::PointerMove { id, samples }
+        });
+      }
+    }
+  }
+
+  fn send_announcements(&self) {
+    let announcements = self
+      .announcer
+      .borrow_mut()
+      .take(self.tree(), clock::now());
+    for (message, politeness) in announcements {
+      AppCtx::announce(self.id(), &message, politeness);
+    }
+  }
+
+  /// The rect in the window that the focus ring outlines, `None` if no widget
+  /// is focused or the focus is moved by the pointer.
+  pub fn focus_ring_rect(&self) -> Option<Rect> {
+    let focus_mgr = self.focus_mgr.borrow();
+    let id = focus_mgr
+      .focusing()
+      .filter(|_| focus_mgr.is_focus_visible())?;
+    let tree = self.tree();
+    let size = tree.store.layout_box_size(id)?;
+    Some(Rect::new(tree.map_to_global(Point::zero(), id), size))
+  }
+
+  /// Paint the focus ring over all the widgets.
+  pub(crate) fn draw_focus_ring(&self) {
+    let Some(rect) = self.focus_ring_rect() else { return };
+    let ring = {
+      let _guard = BuildCtx::init_for(self.tree().root(), self.tree);
+      Theme::of(BuildCtx::get()).focus_ring
+    };
+    let outset = ring.offset + ring.thickness / 2.;
+    let rect = rect.inflate(outset, outset);
+    let mut painter = self.painter.borrow_mut();
+    painter
+      .set_stroke_brush(ring.color)
+      .set_line_width(ring.thickness)
+      .rect(&rect)
+      .stroke();
+  }
+
+  pub(crate) fn draw_delay_drop_widgets(&self) {
     let mut painter = self.painter.borrow_mut();
 
     self
@@ -392,6 +1042,29 @@ impl Window {
       });
   }
 
+  /// Validate the integrity of the widget tree, the subtrees waiting for
+  /// disposal are detached from the tree by design.
+  #[cfg(debug_assertions)]
+  fn validate_tree(&self) {
+    let mut detached: Vec<_> = self
+      .delay_drop_widgets
+      .borrow()
+      .iter()
+      .filter_map(|(_, wid)| wid.get())
+      .collect();
+    detached.extend(
+      self
+        .delay_emitter
+        .borrow()
+        .iter()
+        .filter_map(|e| match e {
+          DelayEvent::Disposed { id, .. } | DelayEvent::RemoveSubtree(id) => Some(*id),
+          _ => None,
+        }),
+    );
+    self.tree_mut().validate(&detached);
+  }
+
   fn run_priority_tasks(&self) {
     while let Some((task, _)) = self.priority_task_queue.pop() {
       // `pipe` used priority task queue to update the subtree, we need to force
@@ -410,6 +1083,12 @@ impl Window {
       let Some(e) = self.delay_emitter.borrow_mut().pop_front() else {
         break;
       };
+      if let Some(id) = e.target().filter(|id| id.is_dropped(self.tree())) {
+        self
+          .tree_mut()
+          .recover(CoreError::LostEventTarget(id));
+        continue;
+      }
 
       match e {
         DelayEvent::Mounted(id) => {
@@ -478,9 +1157,22 @@ impl Window {
           let Event::KeyDownCapture(e) = e else { unreachable!() };
           let mut e = Event::KeyDown(e);
           self.bottom_up_emit(&mut e, id, None);
-          let Event::KeyDown(e) = e else { unreachable!() };
-          if !e.is_prevent_default() && *e.key() == VirtualKey::Named(NamedKey::Tab) {
-            self.add_delay_event(DelayEvent::TabFocusMove);
+          let Event::KeyDown(mut e) = e else { unreachable!() };
+          DefaultActions::run(e.take_default_actions(), &e);
+          self
+            .key_chars_prevented
+            .set(e.is_default_prevented());
+          if !e.is_default_prevented() {
+            match e.key() {
+              VirtualKey::Named(NamedKey::Tab) => self.add_delay_event(DelayEvent::TabFocusMove),
+              VirtualKey::Named(key) => {
+                self
+                  .focus_mgr
+                  .borrow_mut()
+                  .focus_by_arrow(key, self.tree());
+              }
+              _ => {}
+            }
           }
         }
         DelayEvent::TabFocusMove => {
@@ -493,6 +1185,7 @@ impl Window {
           };
 
           let mut focus_mgr = self.focus_mgr.borrow_mut();
+          focus_mgr.set_focus_visible(true);
           if pressed_shift {
             focus_mgr.focus_prev_widget(self.tree());
           } else {
@@ -500,6 +1193,7 @@ impl Window {
           }
         }
         DelayEvent::KeyUp(event) => {
+          self.key_chars_prevented.set(false);
           let id = event.id();
           let mut e = Event::KeyUpCapture(event);
           self.top_down_emit(&mut e, id, None);
@@ -508,32 +1202,62 @@ impl Window {
           self.bottom_up_emit(&mut e, id, None);
         }
         DelayEvent::Chars { id, chars } => {
+          if self.key_chars_prevented.take() {
+            continue;
+          }
           let mut e = Event::CharsCapture(CharsEvent::new(chars, id, self));
           self.top_down_emit(&mut e, id, None);
           let Event::CharsCapture(e) = e else { unreachable!() };
           let mut e = Event::Chars(e);
           self.bottom_up_emit(&mut e, id, None);
+          let Event::Chars(mut e) = e else { unreachable!() };
+          DefaultActions::run(e.take_default_actions(), &e);
         }
-        DelayEvent::Wheel { id, delta_x, delta_y } => {
-          let mut e = Event::WheelCapture(WheelEvent::new(delta_x, delta_y, id, self));
+        DelayEvent::Wheel { id, delta_x, delta_y, delta_mode } => {
+          let e = WheelEvent::new(delta_x, delta_y, delta_mode, id, self);
+          let mut e = Event::WheelCapture(e);
           self.top_down_emit(&mut e, id, None);
-          let mut e = Event::Wheel(WheelEvent::new(delta_x, delta_y, id, self));
+          let prevented = e.is_default_prevented();
+          let mut e = Event::Wheel(WheelEvent::new(delta_x, delta_y, delta_mode, id, self));
+          if prevented {
+            e.prevent_default();
+          }
           self.bottom_up_emit(&mut e, id, None);
+          let Event::Wheel(e) = e else { unreachable!() };
+          if !e.is_default_prevented() {
+            scroll_by_wheel(id, self.tree(), Vector::new(-e.delta_x, -e.delta_y));
+          }
+        }
+        DelayEvent::Scroll { id, offset, max_offset, up } => {
+          let mut e = Event::Scroll(ScrollEvent::new(offset, max_offset, id, self));
+          self.bottom_up_emit(&mut e, id, up);
         }
         DelayEvent::PointerDown(id) => {
+          let focused = self.focusing();
           let mut e = Event::PointerDownCapture(PointerEvent::from_mouse(id, self));
           self.top_down_emit(&mut e, id, None);
+          let prevented = e.is_default_prevented();
           let mut e = Event::PointerDown(PointerEvent::from_mouse(id, self));
+          if prevented {
+            e.prevent_default();
+          }
           self.bottom_up_emit(&mut e, id, None);
+          // A listener that moves the focus by itself handles the press.
+          let moved = self.focusing() != focused || self.focus_mgr.borrow().is_requesting();
+          if !e.is_default_prevented() && !moved {
+            self.focus_pressed(Some(id));
+          }
           self
             .focus_mgr
             .borrow_mut()
             .refresh_focus(self.tree());
         }
-        DelayEvent::PointerMove(id) => {
-          let mut e = Event::PointerMoveCapture(PointerEvent::from_mouse(id, self));
+        DelayEvent::PointerMove { id, samples } => {
+          let e = PointerEvent::from_mouse(id, self).with_coalesced_events(samples.clone());
+          let mut e = Event::PointerMoveCapture(e);
           self.top_down_emit(&mut e, id, None);
-          let mut e = Event::PointerMove(PointerEvent::from_mouse(id, self));
+          let e = PointerEvent::from_mouse(id, self).with_coalesced_events(samples);
+          let mut e = Event::PointerMove(e);
           self.bottom_up_emit(&mut e, id, None);
         }
         DelayEvent::PointerUp(id) => {
@@ -542,7 +1266,7 @@ impl Window {
           let mut e = Event::PointerUp(PointerEvent::from_mouse(id, self));
           self.bottom_up_emit(&mut e, id, None);
         }
-        DelayEvent::_PointerCancel(id) => {
+        DelayEvent::PointerCancel(id) => {
           let mut e = Event::PointerCancel(PointerEvent::from_mouse(id, self));
           self.bottom_up_emit(&mut e, id, None);
         }
@@ -560,6 +1284,26 @@ impl Window {
           let mut e = Event::Tap(PointerEvent::from_mouse(wid, self));
           self.bottom_up_emit(&mut e, wid, None);
         }
+        DelayEvent::LongPress(wid) => {
+          let mut e = Event::LongPressCapture(PointerEvent::from_mouse(wid, self));
+          self.top_down_emit(&mut e, wid, None);
+          let mut e = Event::LongPress(PointerEvent::from_mouse(wid, self));
+          self.bottom_up_emit(&mut e, wid, None);
+        }
+        DelayEvent::DragStart(id) => {
+          let zero = Vector::zero();
+          let mut e = Event::DragStart(DragEvent::new(zero, zero, id, self));
+          self.emit(id, &mut e);
+        }
+        DelayEvent::DragUpdate { id, delta, offset } => {
+          let mut e = Event::DragUpdate(DragEvent::new(delta, offset, id, self));
+          self.emit(id, &mut e);
+        }
+        DelayEvent::DragEnd { id, offset, canceled } => {
+          let mut e = DragEvent::new(Vector::zero(), offset, id, self);
+          e.canceled = canceled;
+          self.emit(id, &mut Event::DragEnd(e));
+        }
         DelayEvent::ImePreEdit { wid, pre_edit } => {
           let mut e = Event::ImePreEditCapture(ImePreEditEvent::new(pre_edit, wid, self));
           self.top_down_emit(&mut e, wid, None);
@@ -571,14 +1315,30 @@ impl Window {
           let mut e = Event::PointerDown(PointerEvent::from_mouse(wid, self));
           self.emit(wid, &mut e);
         }
-        DelayEvent::GrabPointerMove(wid) => {
-          let mut e = Event::PointerMove(PointerEvent::from_mouse(wid, self));
-          self.emit(wid, &mut e);
+        DelayEvent::GrabPointerMove { id, samples } => {
+          let e = PointerEvent::from_mouse(id, self).with_coalesced_events(samples);
+          self.emit(id, &mut Event::PointerMove(e));
         }
         DelayEvent::GrabPointerUp(wid) => {
           let mut e = Event::PointerUp(PointerEvent::from_mouse(wid, self));
           self.emit(wid, &mut e);
         }
+        DelayEvent::LockedPointerMove { id, movement } => {
+          let e = PointerEvent::from_mouse(id, self).with_movement(movement);
+          self.emit(id, &mut Event::PointerMove(e));
+        }
+        DelayEvent::PointerLockChange { id, locked } => {
+          let mut e = Event::PointerLockChange(PointerLockEvent::new(locked, id, self));
+          self.emit(id, &mut e);
+        }
+        DelayEvent::PointerCaptureChange { id, captured } => {
+          let e = PointerEvent::from_mouse(id, self);
+          let mut e =
+            if captured { Event::GotPointerCapture(e) } else { Event::LostPointerCapture(e) };
+          self.bottom_up_emit(&mut e, id, None);
+        }
+        DelayEvent::DragMove(pos) => self.drag_to(pos),
+        DelayEvent::DragDrop(pos) => self.drop_drag(pos),
       }
     }
   }
@@ -597,7 +1357,7 @@ impl Window {
     let path = bottom
       .ancestors(tree)
       .take_while(|id| Some(*id) != up)
-      .collect::<Vec<_>>();
+      .collect::<WidgetPath>();
 
     path.iter().rev().all(|id| {
       id.query_all_iter::<MixBuiltin>(tree)
@@ -632,6 +1392,24 @@ impl Window {
       });
   }
 
+  /// The default action of a pointer press, focus the nearest focus node of the
+  /// pressed widget, or blur the window if there isn't one.
+  pub(crate) fn focus_pressed(&self, hit: Option<WidgetId>) {
+    let tree = self.tree();
+    let nearest_focus = hit.and_then(|wid| {
+      wid.ancestors(tree).find(|id| {
+        id.query_all_iter::<MixBuiltin>(tree)
+          .any(|m| m.contain_flag(MixFlags::Focus))
+      })
+    });
+    let mut focus_mgr = self.focus_mgr.borrow_mut();
+    if let Some(focus_id) = nearest_focus {
+      focus_mgr.focus(focus_id, tree);
+    } else {
+      focus_mgr.blur(tree);
+    }
+  }
+
   /// Run all async tasks need finished in current frame and emit all delay
   /// events.
   pub fn run_frame_tasks(&self) {
@@ -662,6 +1440,28 @@ impl Window {
 
   pub fn widget_pos(&self, id: WidgetId) -> Option<Point> { self.tree().store.layout_box_pos(id) }
 
+  /// Return how the dirty widgets spread the relayout to their ancestors in
+  /// the last frame, or `None` if the window doesn't enable the
+  /// [`WindowFlags::LAYOUT_DIAGNOSTICS`] flag.
+  pub fn layout_diagnostics(&self) -> Option<&LayoutDiagnostics> {
+    self.tree().layout_diagnostics.as_ref()
+  }
+
+  /// Take a snapshot of the widgets of the window, see [`TreeDump`].
+  pub fn tree_dump(&self) -> TreeDump { TreeDump::new(self.tree()) }
+
+  /// Query all the descendants of the widget `id` that share a `T`, in
+  /// depth-first order, not including `id` itself.
+  pub fn query_descendants<T: 'static>(&self, id: WidgetId) -> Vec<(WidgetId, QueryRef<'_, T>)> {
+    id.query_descendants(self.tree())
+  }
+
+  /// Query the nearest ancestor of the widget `id` that shares a `T`, not
+  /// including `id` itself.
+  pub fn query_ancestor<T: 'static>(&self, id: WidgetId) -> Option<(WidgetId, QueryRef<'_, T>)> {
+    id.query_ancestor(self.tree())
+  }
+
   pub(crate) fn tree(&self) -> &WidgetTree {
     // Safety: Please refer to the comments in `WidgetTree::tree_mut` for more
     // information.
@@ -686,8 +1486,62 @@ impl Window {
 
   /// The device pixel ratio of Window interface returns the ratio of the
   /// resolution in physical pixels to the logic pixels for the current display
-  /// device.
-  pub fn device_pixel_ratio(&self) -> f32 { self.shell_wnd.borrow().device_pixel_ratio() }
+  /// device. It includes the zoom of the UI, see [`Window::set_ui_scale`].
+  pub fn device_pixel_ratio(&self) -> f32 {
+    self.shell_wnd.borrow().device_pixel_ratio() * self.ui_scale()
+  }
+
+  /// The zoom of the UI, `1.` if it's not zoomed.
+  pub fn ui_scale(&self) -> f32 { self.ui_scale.get() }
+
+  /// Zoom the UI of the window on top of the scale factor of the operating
+  /// system, the `scale` is clamped between [`MIN_UI_SCALE`] and
+  /// [`MAX_UI_SCALE`].
+  ///
+  /// The logical size of the window shrinks as the UI zooms in, so the whole
+  /// tree is laid out again and painted bigger. The zoom is kept by the window
+  /// across resizes and changes of the scale factor of the operating system.
+  pub fn set_ui_scale(&self, scale: f32) {
+    let scale = scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+    if self.ui_scale.get() != scale {
+      self.ui_scale.set(scale);
+      let size = self.shell_wnd.borrow().inner_size();
+      self.reset_viewport(size);
+    }
+  }
+
+  /// The color that the window is cleared with before painting every frame.
+  /// It's the surface color of the palette if it's not set.
+  pub fn clear_color(&self) -> Option<Color> { self.clear_color.get() }
+
+  /// Set the color that the window is cleared with before painting every frame,
+  /// pass `None` to use the surface color of the palette.
+  ///
+  /// A window created to be transparent shows what's behind it through a
+  /// transparent clear color, such as [`Color::TRANSPARENT`].
+  pub fn set_clear_color(&self, color: Option<Color>) {
+    if self.clear_color.replace(color) != color {
+      self
+        .tree()
+        .dirty_marker()
+        .mark(self.tree().root());
+    }
+  }
+
+  /// Start to move the window with the pressed pointer, until the pointer is
+  /// released. Call it when a pointer is pressed on a widget to make the widget
+  /// act as the title bar of a window without decorations.
+  pub fn begin_move_drag(&self) { self.shell_wnd.borrow_mut().begin_move_drag(); }
+
+  /// Start to resize the window from the `edge` with the pressed pointer, until
+  /// the pointer is released. Call it when a pointer is pressed on a widget to
+  /// make the widget a resize handle of a window without decorations.
+  pub fn begin_resize_drag(&self, edge: ResizeEdge) {
+    self
+      .shell_wnd
+      .borrow_mut()
+      .begin_resize_drag(edge);
+  }
 
   pub fn set_title(&self, title: &str) -> &Self {
     self.shell_wnd.borrow_mut().set_title(title);
@@ -703,22 +1557,55 @@ impl Window {
   pub fn get_cursor(&self) -> CursorIcon { self.shell_wnd.borrow().cursor() }
 
   /// Modifies the cursor icon of the window.
-  pub fn set_cursor(&self, cursor: CursorIcon) -> &Self {
-    self.shell_wnd.borrow_mut().set_cursor(cursor);
+  pub fn set_cursor(&self, cursor: CursorIcon) -> &Self { self.set_pointer_cursor(cursor) }
+
+  /// The scroll positions that the scrollable widgets of the window save by
+  /// their restoration ids, see [`ScrollRestoration`].
+  pub fn scroll_restoration(&self) -> &RefCell<ScrollRestoration> { &self.scroll_restoration }
+
+  /// Returns the cursor of the pointer, an icon or an image.
+  pub fn pointer_cursor(&self) -> PointerCursor { self.pointer_cursor.borrow().clone() }
+
+  /// Modifies the cursor of the pointer. If the platform can't use the image
+  /// cursor, the cursor of the platform is hidden and the image is painted
+  /// over the widgets following the pointer, it doesn't take the hits.
+  pub fn set_pointer_cursor(&self, cursor: impl Into<PointerCursor>) -> &Self {
+    let cursor = cursor.into();
+    let mut shell_wnd = self.shell_wnd.borrow_mut();
+    let sprite = match &cursor {
+      PointerCursor::Icon(icon) => {
+        shell_wnd.set_cursor(*icon);
+        None
+      }
+      PointerCursor::Image(image) => (!shell_wnd.set_image_cursor(image)).then(|| image.clone()),
+    };
+    shell_wnd.set_cursor_visible(sprite.is_none());
+    drop(shell_wnd);
+
+    let pos = self.dispatcher.borrow().info.global_pos();
+    self
+      .cursor_sprite
+      .borrow_mut()
+      .set(sprite, pos, self.id());
+    *self.pointer_cursor.borrow_mut() = cursor;
     self
   }
 
   /// Sets location of IME candidate box in window global coordinates relative
   /// to the top left.
   pub fn set_ime_cursor_area(&self, rect: &Rect) -> &Self {
+    let scale = self.ui_scale();
     self
       .shell_wnd
       .borrow_mut()
-      .set_ime_cursor_area(rect);
+      .set_ime_cursor_area(&rect.scale(scale, scale));
     self
   }
 
   pub fn set_ime_allowed(&self, allowed: bool) -> &Self {
+    if allowed {
+      AppCtx::mark_ime_used();
+    }
     self
       .shell_wnd
       .borrow_mut()
@@ -733,11 +1620,71 @@ impl Window {
     self
   }
 
-  pub fn request_resize(&self, size: Size) { self.shell_wnd.borrow_mut().request_resize(size) }
+  pub fn is_minimized(&self) -> bool { self.shell_wnd.borrow().is_minimized() }
+
+  pub fn set_minimized(&self, minimized: bool) -> &Self {
+    self
+      .shell_wnd
+      .borrow_mut()
+      .set_minimized(minimized);
+    self
+  }
+
+  pub fn is_maximized(&self) -> bool { self.shell_wnd.borrow().is_maximized() }
+
+  pub fn set_maximized(&self, maximized: bool) -> &Self {
+    self
+      .shell_wnd
+      .borrow_mut()
+      .set_maximized(maximized);
+    self
+  }
+
+  /// Ask to close the window, as if the user clicks the close button of the
+  /// window.
+  pub fn request_close(&self) { self.shell_wnd.borrow_mut().request_close(); }
 
-  pub fn size(&self) -> Size { self.shell_wnd.borrow().inner_size() }
+  pub fn request_resize(&self, size: Size) {
+    let size = size * self.ui_scale();
+    self.shell_wnd.borrow_mut().request_resize(size)
+  }
+
+  /// The logical size of the window, it shrinks as the UI zooms in.
+  ///
+  /// It's the size that the content is laid out for, which is behind the
+  /// shell window while the resize is deferred by the [`ResizeStrategy`].
+  pub fn size(&self) -> Size { self.resize.borrow().size / self.ui_scale() }
+
+  pub fn resize_strategy(&self) -> ResizeStrategy { self.resize.borrow().strategy }
+
+  /// Set how the window relayouts its content while it's resizing, see
+  /// [`ResizeStrategy`].
+  pub fn set_resize_strategy(&self, strategy: ResizeStrategy) {
+    let mut resize = self.resize.borrow_mut();
+    resize.strategy = strategy;
+    if strategy == ResizeStrategy::Immediate {
+      resize.last_frame = None;
+    }
+  }
+
+  /// The media information of the window, it's a state that you can watch to
+  /// adapt to the changes of the window.
+  pub fn media(&self) -> Watcher<Reader<MediaInfo>> { self.media.clone_watcher() }
+
+  /// Mark whether touch is the primary input of the window. The built-in
+  /// controls enlarge their hit area to the [`TouchTargetSize`] of the theme
+  /// in a touch-first window.
+  ///
+  /// A window is touch-first from the start on the mobile platforms, and it
+  /// becomes touch-first once it receives a touch on the others.
+  pub fn set_touch_first(&self, touch: bool) {
+    if self.media.read().touch != touch {
+      self.media.write().touch = touch;
+    }
+  }
 
   pub fn set_min_size(&self, size: Size) -> &Self {
+    let size = size * self.ui_scale();
     self.shell_wnd.borrow_mut().set_min_size(size);
     self
   }
@@ -806,18 +1753,67 @@ pub(crate) enum DelayEvent {
   KeyUp(KeyboardEvent),
   TabFocusMove,
   Chars { id: WidgetId, chars: String },
-  Wheel { id: WidgetId, delta_x: f32, delta_y: f32 },
+  Wheel { id: WidgetId, delta_x: f32, delta_y: f32, delta_mode: WheelDeltaMode },
+  Scroll { id: WidgetId, offset: Point, max_offset: Point, up: Option<WidgetId> },
   PointerDown(WidgetId),
-  PointerMove(WidgetId),
+  PointerMove { id: WidgetId, samples: Vec<PointerSample> },
   PointerUp(WidgetId),
-  _PointerCancel(WidgetId),
+  PointerCancel(WidgetId),
   PointerEnter { bottom: WidgetId, up: Option<WidgetId> },
   PointerLeave { bottom: WidgetId, up: Option<WidgetId> },
   Tap(WidgetId),
+  LongPress(WidgetId),
+  DragStart(WidgetId),
+  DragUpdate { id: WidgetId, delta: Vector, offset: Vector },
+  DragEnd { id: WidgetId, offset: Vector, canceled: bool },
   ImePreEdit { wid: WidgetId, pre_edit: ImePreEdit },
   GrabPointerDown(WidgetId),
-  GrabPointerMove(WidgetId),
+  GrabPointerMove { id: WidgetId, samples: Vec<PointerSample> },
   GrabPointerUp(WidgetId),
+  LockedPointerMove { id: WidgetId, movement: Vector },
+  PointerLockChange { id: WidgetId, locked: bool },
+  PointerCaptureChange { id: WidgetId, captured: bool },
+  DragMove(Point),
+  DragDrop(Point),
+}
+
+impl DelayEvent {
+  /// The widget the event is emitted to.
+  fn target(&self) -> Option<WidgetId> {
+    match self {
+      DelayEvent::Mounted(id)
+      | DelayEvent::PerformedLayout(id)
+      | DelayEvent::Disposed { id, .. }
+      | DelayEvent::RemoveSubtree(id)
+      | DelayEvent::Focus(id)
+      | DelayEvent::Blur(id)
+      | DelayEvent::FocusIn { bottom: id, .. }
+      | DelayEvent::FocusOut { bottom: id, .. }
+      | DelayEvent::Chars { id, .. }
+      | DelayEvent::Wheel { id, .. }
+      | DelayEvent::Scroll { id, .. }
+      | DelayEvent::PointerDown(id)
+      | DelayEvent::PointerMove { id, .. }
+      | DelayEvent::PointerUp(id)
+      | DelayEvent::PointerCancel(id)
+      | DelayEvent::PointerEnter { bottom: id, .. }
+      | DelayEvent::PointerLeave { bottom: id, .. }
+      | DelayEvent::Tap(id)
+      | DelayEvent::LongPress(id)
+      | DelayEvent::DragStart(id)
+      | DelayEvent::DragUpdate { id, .. }
+      | DelayEvent::DragEnd { id, .. }
+      | DelayEvent::ImePreEdit { wid: id, .. }
+      | DelayEvent::GrabPointerDown(id)
+      | DelayEvent::GrabPointerMove { id, .. }
+      | DelayEvent::GrabPointerUp(id)
+      | DelayEvent::LockedPointerMove { id, .. }
+      | DelayEvent::PointerLockChange { id, .. }
+      | DelayEvent::PointerCaptureChange { id, .. } => Some(*id),
+      DelayEvent::KeyDown(e) | DelayEvent::KeyUp(e) => Some(e.id()),
+      DelayEvent::TabFocusMove | DelayEvent::DragMove(_) | DelayEvent::DragDrop(_) => None,
+    }
+  }
 }
 
 impl From<u64> for WindowId {
@@ -852,6 +1848,368 @@ mod tests {
     wnd.assert_root_size(new_size);
   }
 
+  #[test]
+  fn media_follow_wnd_resize() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(fn_widget! { Void }, Size::new(200., 100.));
+    wnd.draw_frame();
+    let media = wnd.media();
+    assert_eq!(*media.read(), MediaInfo { size: Size::new(200., 100.), scale: 1., touch: false });
+    assert_eq!(media.read().orientation(), Orientation::Landscape);
+
+    wnd.request_resize(Size::new(100., 200.));
+    wnd.draw_frame();
+    assert_eq!(media.read().size, Size::new(100., 200.));
+    assert_eq!(media.read().orientation(), Orientation::Portrait);
+    assert_eq!(media.read().device_class(), DeviceClass::Desktop);
+
+    wnd.set_touch_first(true);
+    assert_eq!(media.read().device_class(), DeviceClass::Phone);
+    wnd.request_resize(Size::new(800., 600.));
+    wnd.draw_frame();
+    assert!(media.read().touch);
+    assert_eq!(media.read().device_class(), DeviceClass::Tablet);
+  }
+
+  #[test]
+  fn touch_first_after_touch() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new_with_size(fn_widget! { Void }, Size::new(200., 100.));
+    wnd.draw_frame();
+    let media = wnd.media();
+    assert!(!media.read().touch);
+
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::Touch(winit::event::Touch {
+      device_id: unsafe { DeviceId::dummy() },
+      phase: winit::event::TouchPhase::Started,
+      location: winit::dpi::PhysicalPosition::new(10., 10.),
+      force: None,
+      id: 0,
+    }));
+    assert!(media.read().touch);
+    assert_eq!(media.read().device_class(), DeviceClass::Phone);
+  }
+
+  fn paint_size(wnd: &mut TestWindow) -> Size {
+    let frame = wnd.take_last_frame().unwrap();
+    let Some(PaintCommand::Path(cmd)) = frame.commands.first() else { unreachable!() };
+    cmd.paint_bounds.size
+  }
+
+  #[test]
+  fn ui_scale_zoom_paint_not_layout() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @Container {
+          size: Size::new(40., 20.),
+          background: Color::RED,
+          on_tap: move |_| *$w_taps.write() += 1,
+        }
+      },
+      Size::new(200., 100.),
+    );
+    wnd.draw_frame();
+    assert_eq!(paint_size(&mut wnd), Size::new(40., 20.));
+
+    wnd.set_ui_scale(2.);
+    wnd.draw_frame();
+    assert_eq!(paint_size(&mut wnd), Size::new(80., 40.));
+    wnd.assert_root_size(Size::new(40., 20.));
+    assert_eq!(wnd.size(), Size::new(100., 50.));
+    assert_eq!(wnd.device_pixel_ratio(), 2.);
+
+    // The pointer in physical pixels hits the zoomed widget.
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: winit::dpi::LogicalPosition::new(70., 30.).to_physical(1.),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+    assert_eq!(*taps.read(), 1);
+
+    wnd.set_ui_scale(100.);
+    assert_eq!(wnd.ui_scale(), MAX_UI_SCALE);
+  }
+
+  fn layout_counter(strategy: ResizeStrategy) -> (TestWindow, impl Fn() -> usize) {
+    let (layouts, w_layouts) = split_value(0);
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockBox {
+          size: INFINITY_SIZE,
+          on_performed_layout: move |_| *$w_layouts.write() += 1,
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.set_resize_strategy(strategy);
+    wnd.draw_frame();
+    (wnd, move || *layouts.read())
+  }
+
+  /// Resize the window by 10 pixels every 10ms, for 100ms.
+  fn resize_burst(wnd: &mut TestWindow) {
+    for i in 1..=10 {
+      wnd.request_resize(Size::new(100. + i as f32 * 10., 100.));
+      wnd.advance_time(Duration::from_millis(10));
+    }
+  }
+
+  #[test]
+  fn relayout_every_resize() {
+    reset_test_env!();
+
+    let (mut wnd, layouts) = layout_counter(ResizeStrategy::Immediate);
+    assert_eq!(layouts(), 1);
+    resize_burst(&mut wnd);
+    assert_eq!(layouts(), 11);
+    wnd.assert_root_size(Size::new(200., 100.));
+  }
+
+  #[test]
+  fn debounce_resize() {
+    reset_test_env!();
+
+    let (mut wnd, layouts) = layout_counter(ResizeStrategy::Debounced(Duration::from_millis(50)));
+    resize_burst(&mut wnd);
+    assert_eq!(layouts(), 1);
+    wnd.assert_root_size(Size::new(100., 100.));
+
+    // The debounce starts from the frame that sees the last size.
+    wnd.advance_time(Duration::from_millis(40));
+    assert_eq!(layouts(), 1);
+    wnd.advance_time(Duration::from_millis(10));
+    assert_eq!(layouts(), 2);
+    wnd.assert_root_size(Size::new(200., 100.));
+  }
+
+  #[test]
+  fn throttle_resize() {
+    reset_test_env!();
+
+    let (mut wnd, layouts) = layout_counter(ResizeStrategy::Throttled(20));
+    resize_burst(&mut wnd);
+    assert_eq!(layouts(), 3);
+
+    wnd.advance_time(Duration::from_millis(50));
+    assert_eq!(layouts(), 4);
+    wnd.assert_root_size(Size::new(200., 100.));
+  }
+
+  #[test]
+  fn hit_stretched_frame() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @Container {
+          size: Size::new(50., 50.),
+          background: Color::RED,
+          on_tap: move |_| *$w_taps.write() += 1,
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.set_resize_strategy(ResizeStrategy::Debounced(Duration::from_millis(50)));
+    wnd.draw_frame();
+
+    wnd.request_resize(Size::new(200., 200.));
+    wnd.draw_frame();
+    let frame = wnd.take_last_frame().unwrap();
+    let Some(PaintCommand::Bundle { transform, .. }) = frame.commands.first() else {
+      panic!("The last frame is not stretched.");
+    };
+    assert_eq!(*transform, Transform::scale(2., 2.));
+
+    // The pointer is mapped to the stretched widget.
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: winit::dpi::PhysicalPosition::new(80., 80.),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+    assert_eq!(*taps.read(), 1);
+  }
+
+  #[test]
+  fn skip_frames_when_idle() {
+    reset_test_env!();
+
+    let (taps, w_taps) = split_value(0);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: pipe!(Size::new(10. + *$taps as f32, 10.)),
+        on_tap: move |_| *$w_taps.write() += 1,
+      }
+    });
+    wnd.draw_frame();
+    assert!(wnd.is_idle());
+    let settled = wnd.frame_stats();
+    for _ in 0..5 {
+      wnd.draw_frame();
+    }
+    let stats = wnd.frame_stats();
+    assert_eq!((stats.painted, stats.skipped), (settled.painted, settled.skipped + 5));
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: winit::dpi::PhysicalPosition::new(5., 5.),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    assert!(!wnd.is_idle());
+    for _ in 0..5 {
+      wnd.draw_frame();
+    }
+    assert_eq!(wnd.frame_stats().painted, settled.painted + 1);
+    assert!(wnd.is_idle());
+    wnd.assert_root_size(Size::new(11., 10.));
+  }
+
+  #[test]
+  fn report_first_frame() {
+    reset_test_env!();
+
+    let created = std::rc::Rc::new(Cell::new(false));
+    let c_created = created.clone();
+    AppCtx::set_clipboard_on_demand(move || {
+      c_created.set(true);
+      Box::new(crate::clipboard::MemoryClipboard::default())
+    });
+
+    let mut wnd = TestWindow::new(fn_widget! { @MockBox { size: Size::new(10., 10.) } });
+    assert!(wnd.first_frame_report().is_none());
+    wnd.draw_frame();
+    let report = wnd.first_frame_report().unwrap();
+    assert!(report.total >= report.layout + report.paint + report.present);
+    assert_eq!(
+      report.lazy_services,
+      [LazyService::Clipboard, LazyService::Ime, LazyService::SystemFonts]
+    );
+    assert!(!created.get());
+
+    // The clipboard is created at its first use, and the report is kept.
+    let _ = AppCtx::clipboard().borrow_mut().read_text();
+    assert!(created.get());
+    assert!(AppCtx::is_service_initialized(LazyService::Clipboard));
+    wnd.set_ime_allowed(true);
+    assert!(AppCtx::is_service_initialized(LazyService::Ime));
+    wnd.draw_frame();
+    assert_eq!(wnd.first_frame_report(), Some(report));
+  }
+
+  #[test]
+  fn zoom_shortcuts_opt_in() {
+    reset_test_env!();
+
+    let wnd = TestWindow::new(fn_widget! { Void });
+    #[cfg(target_os = "macos")]
+    let command = winit::keyboard::ModifiersState::SUPER;
+    #[cfg(not(target_os = "macos"))]
+    let command = winit::keyboard::ModifiersState::CONTROL;
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::ModifiersChanged(command.into()));
+    let press = |code: KeyCode, c: &str| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(code),
+        VirtualKey::Character(c.into()),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+    };
+
+    press(KeyCode::Equal, "=");
+    assert_eq!(wnd.ui_scale(), 1.);
+
+    wnd.set_flags(WindowFlags::DEFAULT | WindowFlags::ZOOM_SHORTCUTS);
+    press(KeyCode::Equal, "=");
+    press(KeyCode::Equal, "=");
+    assert!((wnd.ui_scale() - 1.2).abs() < f32::EPSILON);
+    press(KeyCode::Minus, "-");
+    assert!((wnd.ui_scale() - 1.1).abs() < f32::EPSILON);
+    press(KeyCode::Digit0, "0");
+    assert_eq!(wnd.ui_scale(), 1.);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn clear_color() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { MockBox { size: Size::new(10., 10.) } });
+    wnd.draw_frame();
+    let surface = wnd.take_last_frame().unwrap().surface;
+    assert_ne!(surface, Color::TRANSPARENT);
+
+    wnd.set_clear_color(Some(Color::TRANSPARENT));
+    wnd.draw_frame();
+    assert_eq!(wnd.take_last_frame().unwrap().surface, Color::TRANSPARENT);
+
+    wnd.set_clear_color(None);
+    wnd.draw_frame();
+    assert_eq!(wnd.take_last_frame().unwrap().surface, surface);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn drag_window() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox {
+          size: Size::new(100., 20.),
+          on_pointer_down: move |e| e.window().begin_move_drag(),
+        }
+        @MockBox {
+          size: Size::new(10., 10.),
+          on_pointer_down: move |e| e.window().begin_resize_drag(ResizeEdge::BottomRight),
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let mut press_at = |x: f32, y: f32| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: winit::dpi::LogicalPosition::new(x, y).to_physical(1.),
+      });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.draw_frame();
+    };
+    press_at(50., 10.);
+    press_at(105., 5.);
+
+    let drags = wnd
+      .shell_wnd()
+      .borrow()
+      .as_any()
+      .downcast_ref::<TestShellWindow>()
+      .unwrap()
+      .drags
+      .clone();
+    assert_eq!(drags, [WindowDrag::Move, WindowDrag::Resize(ResizeEdge::BottomRight)]);
+  }
+
   #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
   #[test]
   fn fire_tasks_before_new_window() {
@@ -871,4 +2229,47 @@ mod tests {
 
     wnd.draw_frame();
   }
+
+  #[test]
+  fn after_layout_reads_fresh_rects() {
+    reset_test_env!();
+
+    let (cnt, w_cnt) = split_value(2);
+    let (offset, w_offset) = split_value(0.);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: pipe!(*$offset).map(|x| Size::new(x, 10.)) }
+        @MockMulti {
+          @ {
+            pipe!(*$cnt).map(|cnt| {
+              (0..cnt).map(|_| @MockBox { size: Size::new(10., 10.) })
+            })
+          }
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    *w_cnt.write() = 3;
+    let rect = std::rc::Rc::new(Cell::new(None));
+    let c_rect = rect.clone();
+    wnd.once_after_layout(move |ctx| {
+      let list = ctx
+        .widget_id()
+        .first_child(ctx.tree)
+        .and_then(|multi| multi.last_child(ctx.tree))
+        .unwrap();
+      let item = list.last_child(ctx.tree).unwrap();
+      let item_rect = ctx.global_rect(item);
+      c_rect.set(item_rect);
+      *w_offset.write() = item_rect.unwrap().min_x();
+    });
+    wnd.draw_frame();
+    assert_eq!(rect.get(), Some(Rect::new(Point::new(20., 0.), Size::new(10., 10.))));
+    // The layout of the current frame is not changed by the callback.
+    wnd.assert_root_size(Size::new(30., 10.));
+
+    wnd.draw_frame();
+    wnd.assert_root_size(Size::new(50., 10.));
+  }
 }
@@ -1,4 +1,4 @@
-use ribir_geom::{Point, Size, Transform};
+use ribir_geom::{Point, Rect, Size, Transform};
 use smallvec::SmallVec;
 use widget_id::RenderQueryable;
 
@@ -27,8 +27,16 @@ pub trait WrapRender {
     host.hit_test(ctx, pos)
   }
 
+  fn hit_bounds(&self, host: &dyn Render, ctx: &HitTestCtx) -> Option<Rect> { host.hit_bounds(ctx) }
+
   fn get_transform(&self, host: &dyn Render) -> Option<Transform> { host.get_transform() }
 
+  fn get_opacity(&self, host: &dyn Render) -> f32 { host.get_opacity() }
+
+  fn is_displayed(&self, host: &dyn Render) -> bool { host.is_displayed() }
+
+  fn z_index(&self, host: &dyn Render) -> i32 { host.z_index() }
+
   fn combine_child(this: impl StateWriter<Value = Self>, mut child: Widget) -> Widget
   where
     Self: Sized + 'static,
@@ -82,6 +90,8 @@ impl Query for RenderPair {
   }
 
   fn queryable(&self) -> bool { self.host.queryable() }
+
+  fn type_bloom(&self, bloom: &mut QueryBloom) { self.host.type_bloom(bloom) }
 }
 
 impl Render for RenderPair {
@@ -105,7 +115,25 @@ impl Render for RenderPair {
       .hit_test(self.host.as_render(), ctx, pos)
   }
 
+  fn hit_bounds(&self, ctx: &HitTestCtx) -> Option<Rect> {
+    self
+      .wrapper
+      .hit_bounds(self.host.as_render(), ctx)
+  }
+
   fn get_transform(&self) -> Option<Transform> { self.wrapper.get_transform(self.host.as_render()) }
+
+  fn get_opacity(&self) -> f32 { self.wrapper.get_opacity(self.host.as_render()) }
+
+  fn is_displayed(&self) -> bool { self.wrapper.is_displayed(self.host.as_render()) }
+
+  fn z_index(&self) -> i32 { self.wrapper.z_index(self.host.as_render()) }
+
+  fn debug_name(&self) -> std::borrow::Cow<'static, str> { self.host.as_render().debug_name() }
+
+  fn text_content(&self) -> Option<CowArc<str>> { self.host.as_render().text_content() }
+
+  fn text_baselines(&self) -> Vec<f32> { self.host.as_render().text_baselines() }
 }
 
 impl<R> WrapRender for R
@@ -127,9 +155,19 @@ where
     self.read().hit_test(host, ctx, pos)
   }
 
+  fn hit_bounds(&self, host: &dyn Render, ctx: &HitTestCtx) -> Option<Rect> {
+    self.read().hit_bounds(host, ctx)
+  }
+
   fn get_transform(&self, host: &dyn Render) -> Option<Transform> {
     self.read().get_transform(host)
   }
+
+  fn get_opacity(&self, host: &dyn Render) -> f32 { self.read().get_opacity(host) }
+
+  fn is_displayed(&self, host: &dyn Render) -> bool { self.read().is_displayed(host) }
+
+  fn z_index(&self, host: &dyn Render) -> i32 { self.read().z_index(host) }
 }
 
 #[macro_export]
@@ -0,0 +1,51 @@
+use ribir::prelude::*;
+
+/// The size of the corner that resizes the window by dragging.
+const RESIZE_HANDLE: f32 = 16.;
+
+pub fn custom_chrome() -> Widget<'static> {
+  fn_widget! {
+    let palette = Palette::of(BuildCtx::get());
+    @Stack {
+      @Column {
+        clamp: BoxClamp::EXPAND_BOTH,
+        // The window is cleared to transparent, only the rounded card is
+        // visible.
+        background: palette.surface_container(),
+        border_radius: Radius::all(16.),
+        // Keep the title bar inside the rounded corners.
+        padding: EdgeInsets::all(8.),
+        // Drag the title bar to move the window, double-click it to maximize.
+        @TitleBar { @Text { text: "Custom Chrome" } }
+        @Expanded {
+          @Text {
+            h_align: HAlign::Center,
+            v_align: VAlign::Center,
+            text: "Drag the title bar to move, drag the corner to resize.",
+          }
+        }
+      }
+      @Container {
+        size: Size::splat(RESIZE_HANDLE),
+        h_align: HAlign::Right,
+        v_align: VAlign::Bottom,
+        cursor: CursorIcon::SeResize,
+        on_pointer_down: move |e| e.window().begin_resize_drag(ResizeEdge::BottomRight),
+      }
+    }
+  }
+  .into_widget()
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn run() {
+  #[cfg(target_arch = "wasm32")]
+  std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+
+  App::run(custom_chrome)
+    .with_app_theme(material::purple::light())
+    .with_size(Size::new(480., 320.))
+    .with_decorations(false)
+    .with_transparent(true)
+    .with_title("Custom Chrome");
+}
@@ -0,0 +1 @@
+fn main() { custom_chrome::run(); }
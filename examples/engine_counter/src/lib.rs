@@ -0,0 +1,71 @@
+use std::{thread, time::Duration};
+
+use ribir::prelude::*;
+
+/// The commands that the UI sends to the counter engine.
+pub enum CounterCmd {
+  Add(i64),
+  Reset,
+}
+
+pub fn engine_counter() -> Widget<'static> {
+  fn_widget! {
+    let (bridge, engine) = EngineBridge::<CounterCmd, i64>::new(4);
+    thread::spawn(move || run_engine(engine));
+
+    let count = Stateful::new(0);
+    let u = bridge
+      .events()
+      .subscribe(move |n| *$count.write() = n);
+    let busy = bridge.busy();
+    let (add, reset) = (bridge.clone(), bridge.clone());
+    @Column {
+      h_align: HAlign::Center,
+      v_align: VAlign::Center,
+      item_gap: 16.,
+      align_items: Align::Center,
+      on_mounted: move |e| bridge.owned_by(&*e),
+      on_disposed: move |_| u.unsubscribe(),
+      @Text { text: pipe!($count.to_string()) }
+      @Row {
+        item_gap: 8.,
+        @FilledButton {
+          on_tap: move |_| { let _ = add.send(CounterCmd::Add(1)); },
+          @ { "+1" }
+        }
+        @Button {
+          on_tap: move |_| { let _ = reset.send(CounterCmd::Reset); },
+          @ { "Reset" }
+        }
+      }
+      @Text {
+        text: pipe!(if *$busy { "The engine is busy, wait a moment." } else { "" }),
+      }
+    }
+  }
+  .into_widget()
+}
+
+/// A slow engine that counts on its own thread, it stops once the bridge is
+/// disconnected.
+fn run_engine(engine: EngineHandle<CounterCmd, i64>) {
+  let mut count = 0;
+  while let Some(cmd) = engine.recv() {
+    // Simulate a heavy step of the engine.
+    thread::sleep(Duration::from_millis(300));
+    match cmd {
+      CounterCmd::Add(n) => count += n,
+      CounterCmd::Reset => count = 0,
+    }
+    if engine.post(count).is_err() {
+      break;
+    }
+  }
+}
+
+pub fn run() {
+  App::run(engine_counter)
+    .with_app_theme(material::purple::light())
+    .with_size(Size::new(320., 240.))
+    .with_title("Engine Counter");
+}
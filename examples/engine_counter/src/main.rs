@@ -0,0 +1 @@
+fn main() { engine_counter::run(); }
@@ -0,0 +1,49 @@
+use ribir::prelude::*;
+
+const GAUGE_SIZE: f32 = 120.;
+const TRACK_WIDTH: f32 = 12.;
+
+/// A gauge paints its value as an arc, the `value` is in the range of 0 to 1.
+///
+/// It's a render widget implemented out of the framework, it has a preferred
+/// size, but the constraints of its parent have the final say.
+#[derive(Declare)]
+pub struct Gauge {
+  pub value: f32,
+}
+
+impl Render for Gauge {
+  fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size {
+    clamp.clamp(Size::splat(GAUGE_SIZE))
+  }
+
+  // The size of the gauge only depends on the constraints of its parent, so
+  // the change of the value only repaints it.
+  fn only_sized_by_parent(&self) -> bool { true }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let Some(size) = ctx.box_size().filter(|s| !s.is_empty()) else { return };
+    let center = Point::new(size.width / 2., size.height / 2.);
+    let radius = center.x.min(center.y) - TRACK_WIDTH / 2.;
+    // The arc starts at the bottom left and sweeps 270 degrees clockwise.
+    let start = Angle::degrees(135.);
+    let end = start + Angle::degrees(270. * self.value.clamp(0., 1.));
+    let painter = ctx.painter();
+    painter
+      .set_line_width(TRACK_WIDTH)
+      .set_line_cap(LineCap::Round)
+      .set_stroke_brush(Color::from_u32(0xE0E0E0FF));
+    draw_arc(painter, center, radius, start, start + Angle::degrees(270.));
+    painter.set_stroke_brush(Color::from_u32(0x6750A4FF));
+    draw_arc(painter, center, radius, start, end);
+  }
+}
+
+fn draw_arc(painter: &mut Painter, center: Point, radius: f32, start: Angle, end: Angle) {
+  let (sin, cos) = start.sin_cos();
+  painter
+    .begin_path(center + Vector::new(cos, sin) * radius)
+    .arc_to(center, radius, start, end)
+    .end_path(false)
+    .stroke();
+}
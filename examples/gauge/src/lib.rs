@@ -0,0 +1,37 @@
+use ribir::prelude::*;
+
+mod gauge;
+pub use gauge::*;
+
+pub fn gauge_app() -> Widget<'static> {
+  fn_widget! {
+    let meter = Stateful::new(Gauge { value: 0.3 });
+    @Row {
+      h_align: HAlign::Center,
+      v_align: VAlign::Center,
+      item_gap: 16.,
+      align_items: Align::Center,
+      @FilledButton {
+        on_tap: move |_| $meter.write().value -= 0.1,
+        @ { "-" }
+      }
+      @ { meter.clone_writer() }
+      @FilledButton {
+        on_tap: move |_| $meter.write().value += 0.1,
+        @ { "+" }
+      }
+    }
+  }
+  .into_widget()
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn run() {
+  #[cfg(target_arch = "wasm32")]
+  std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+
+  App::run(gauge_app)
+    .with_app_theme(material::purple::light())
+    .with_size(Size::new(320., 240.))
+    .with_title("Gauge");
+}
@@ -0,0 +1,83 @@
+use ribir::prelude::*;
+
+/// A point of a stroke, and the width of the stroke at the point.
+#[derive(Clone, Copy)]
+struct StrokePoint {
+  pos: Point,
+  width: f32,
+}
+
+/// The paper that paints all the strokes.
+#[derive(Default)]
+struct Sketch {
+  strokes: Vec<Vec<StrokePoint>>,
+}
+
+/// The heavier the pen presses, the wider the stroke is.
+fn stroke_width(pressure: f32) -> f32 { 1. + pressure * 11. }
+
+impl Render for Sketch {
+  fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size { clamp.max }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let painter = ctx.painter();
+    painter
+      .set_stroke_brush(Color::BLACK)
+      .set_line_cap(LineCap::Round);
+    for stroke in self.strokes.iter() {
+      for seg in stroke.windows(2) {
+        let [from, to] = [seg[0], seg[1]];
+        painter
+          .set_line_width((from.width + to.width) / 2.)
+          .begin_path(from.pos)
+          .line_to(to.pos)
+          .end_path(false)
+          .stroke();
+      }
+    }
+  }
+}
+
+pub fn pen_drawing() -> Widget<'static> {
+  fn_widget! {
+    let sketch = Stateful::new(Sketch::default());
+    let paper = FatObj::new(sketch.clone_writer());
+    @$paper {
+      on_pointer_down: move |e| {
+        let pos = e.position();
+        let width = stroke_width(e.pressure);
+        $sketch.write().strokes.push(vec![StrokePoint { pos, width }]);
+      },
+      on_pointer_move: move |e| {
+        if e.mouse_buttons().is_empty() {
+          return;
+        }
+        // Every sample of the move, to keep the stroke smooth when the pen
+        // moves fast.
+        let points: Vec<_> = e
+          .coalesced_events()
+          .iter()
+          .map(|s| {
+            let pos = e.map_from_global(s.position);
+            StrokePoint { pos, width: stroke_width(s.pressure) }
+          })
+          .collect();
+        if let Some(stroke) = $sketch.write().strokes.last_mut() {
+          stroke.extend(points);
+        }
+      },
+    }
+  }
+  .into_widget()
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn run() {
+  #[cfg(target_arch = "wasm32")]
+  std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+
+  App::run(pen_drawing)
+    .with_app_theme(material::purple::light())
+    .with_size(Size::new(640., 480.))
+    .with_title("Pen Drawing");
+}
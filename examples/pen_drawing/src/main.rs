@@ -0,0 +1 @@
+fn main() { pen_drawing::run(); }
@@ -156,6 +156,20 @@ where
   #[inline]
   pub fn into_impl(self) -> Impl { self.gpu_impl }
 
+  /// Recreate the device after it's lost. The atlases and the cached textures
+  /// are dropped with the old device, they're uploaded again when they're
+  /// drawn.
+  pub fn recover_device(&mut self) -> Result<(), Box<dyn Error>> {
+    self.gpu_impl.recreate_device()?;
+    self.tex_mgr = TexturesMgr::new(&mut self.gpu_impl);
+    self.tex_ids_map = <_>::default();
+    self.mask_layers.clear();
+    self.clip_layer_stack.clear();
+    self.skip_clip_cnt = 0;
+    self.current_phase = CurrentPhase::None;
+    Ok(())
+  }
+
   fn draw_command(
     &mut self, cmd: &PaintCommand, global_matrix: &Transform, output_tex_size: DeviceSize,
     output: &mut Impl::Texture,
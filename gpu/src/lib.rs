@@ -6,11 +6,13 @@ pub use gpu_backend::Texture;
 use ribir_geom::{DevicePoint, DeviceRect, DeviceSize};
 use ribir_painter::{Color, GradientStop, VertexBuffers, image::ColorFormat};
 mod gpu_backend;
+mod surface_renderer;
 use zerocopy::AsBytes;
 
 #[cfg(feature = "wgpu")]
 pub mod wgpu_impl;
 pub use gpu_backend::*;
+pub use surface_renderer::*;
 #[cfg(feature = "wgpu")]
 pub use wgpu_impl::*;
 
@@ -85,6 +87,14 @@ pub trait GPUBackendImpl {
   /// Returns the limits of the GPU backend.
   fn limits(&self) -> &DrawPhaseLimits;
 
+  /// Return the reason if the device is lost, such as the driver is reset or
+  /// the GPU is removed, `None` if the device works.
+  fn device_lost(&self) -> Option<String>;
+
+  /// Recreate the device and the resources on it after the device is lost. The
+  /// textures created before are invalid after it.
+  fn recreate_device(&mut self) -> Result<(), Box<dyn std::error::Error>>;
+
   /// Create a texture.
   fn new_texture(&mut self, size: DeviceSize, format: ColorFormat) -> Self::Texture;
   /// Load the vertices and indices buffer that `draw_alpha_triangles` &
@@ -0,0 +1,421 @@
+use ribir_geom::{DeviceRect, Transform};
+use ribir_painter::{Color, PaintCommand, PainterBackend};
+
+use crate::{GPUBackend, GPUBackendImpl, Texture};
+
+/// The errors of acquiring the texture of a frame from a surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceError {
+  /// It takes too long to acquire the texture.
+  Timeout,
+  /// The surface has changed, such as it's resized, the swapchain needs to be
+  /// configured again.
+  Outdated,
+  /// The swapchain is lost and needs to be configured again.
+  Lost,
+  /// There is no more memory to allocate the texture.
+  OutOfMemory,
+}
+
+/// A surface that a [`SurfaceRenderer`] draws and presents its frames to.
+pub trait FrameSurface<Impl: GPUBackendImpl> {
+  /// The texture to draw the current frame, it's the same one until the frame
+  /// is presented.
+  fn current_texture(&mut self) -> Result<&mut Impl::Texture, SurfaceError>;
+
+  /// Configure the swapchain of the surface again, the texture acquired before
+  /// is dropped.
+  fn reconfigure(&mut self, gpu_impl: &Impl);
+
+  /// Present the current texture.
+  fn present(&mut self);
+}
+
+/// What happened to the device at the beginning of a frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceEvent {
+  /// The device is lost, with the reason.
+  Lost(String),
+  /// The device and the surface are recreated after the device was lost.
+  Recovered,
+}
+
+/// Draw the frames of a window to its surface, and recover from the errors of
+/// the surface and the lost device.
+///
+/// - The swapchain is configured again if the surface is outdated or lost.
+/// - The device is recreated at the beginning of a frame if it's lost, the
+///   atlases and the cached textures are uploaded again when they're drawn.
+///
+/// A frame is skipped if the surface still fails or the device can't be
+/// recreated, [`SurfaceRenderer::end_frame`] returns `false` for it, the caller
+/// should draw it again.
+pub struct SurfaceRenderer<Impl: GPUBackendImpl, S> {
+  backend: GPUBackend<Impl>,
+  surface: S,
+  device_lost: bool,
+  skip_frame: bool,
+}
+
+impl<Impl, S> SurfaceRenderer<Impl, S>
+where
+  Impl: GPUBackendImpl,
+  Impl::Texture: Texture<Host = Impl>,
+  S: FrameSurface<Impl>,
+{
+  pub fn new(backend: GPUBackend<Impl>, surface: S) -> Self {
+    Self { backend, surface, device_lost: false, skip_frame: false }
+  }
+
+  #[inline]
+  pub fn backend(&self) -> &GPUBackend<Impl> { &self.backend }
+
+  #[inline]
+  pub fn surface(&self) -> &S { &self.surface }
+
+  /// Return the surface and the gpu implementation, e.g. to resize the
+  /// surface.
+  #[inline]
+  pub fn surface_mut(&mut self) -> (&mut S, &Impl) { (&mut self.surface, self.backend.get_impl()) }
+
+  /// Begin a frame, recreate the device first if it's lost. Return what
+  /// happened to the device.
+  pub fn begin_frame(&mut self, surface_color: Color) -> Vec<DeviceEvent> {
+    let mut events = vec![];
+    self.skip_frame = false;
+    if let Some(reason) = self.backend.get_impl().device_lost() {
+      if !self.device_lost {
+        self.device_lost = true;
+        events.push(DeviceEvent::Lost(reason));
+      }
+      if let Err(err) = self.backend.recover_device() {
+        log::error!("Failed to recreate the lost GPU device: {err}");
+        self.skip_frame = true;
+        return events;
+      }
+      self.device_lost = false;
+      self.surface.reconfigure(self.backend.get_impl());
+      events.push(DeviceEvent::Recovered);
+    }
+
+    self.backend.begin_frame(surface_color);
+    events
+  }
+
+  pub fn draw_commands(
+    &mut self, viewport: DeviceRect, global_matrix: &Transform, commands: &[PaintCommand],
+  ) {
+    if self.skip_frame {
+      return;
+    }
+
+    let mut res = self.surface.current_texture().map(|_| ());
+    if matches!(res, Err(SurfaceError::Outdated | SurfaceError::Lost)) {
+      self.surface.reconfigure(self.backend.get_impl());
+      res = self.surface.current_texture().map(|_| ());
+    }
+
+    match res.and_then(|_| self.surface.current_texture()) {
+      Ok(output) => self
+        .backend
+        .draw_commands(viewport, commands, global_matrix, output),
+      Err(err) => {
+        log::warn!("Skip the frame, failed to acquire the texture of the surface: {err:?}");
+        self.skip_frame = true;
+      }
+    }
+  }
+
+  /// End the frame and present it. Return `false` if the frame is skipped.
+  pub fn end_frame(&mut self) -> bool {
+    if !self.device_lost {
+      self.backend.end_frame();
+    }
+    if self.skip_frame {
+      false
+    } else {
+      self.surface.present();
+      true
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::VecDeque, error::Error, ops::Range};
+
+  use ribir_algo::Resource;
+  use ribir_geom::{DevicePoint, DeviceSize, Rect, Size};
+  use ribir_painter::{Brush, Painter, PixelImage, VertexBuffers, image::ColorFormat};
+
+  use super::*;
+  use crate::*;
+
+  /// A backend that draws nothing, but counts the textures it uploads and
+  /// checks that no texture of a lost device is used.
+  struct MockGpu {
+    limits: DrawPhaseLimits,
+    lost: Option<String>,
+    /// How many times recreating the device fails.
+    recreate_failures: usize,
+    /// Increase every time the device is recreated.
+    generation: usize,
+    uploads: usize,
+  }
+
+  struct MockTexture {
+    size: DeviceSize,
+    format: ColorFormat,
+    generation: usize,
+  }
+
+  #[derive(Default)]
+  struct MockSurface {
+    texture: Option<MockTexture>,
+    errors: VecDeque<SurfaceError>,
+    reconfigured: usize,
+    presented: usize,
+  }
+
+  impl MockGpu {
+    fn new() -> Self {
+      let limits = DrawPhaseLimits {
+        texture_size: DeviceSize::new(1024, 1024),
+        max_tex_load: 8,
+        max_image_primitives: 256,
+        max_radial_gradient_primitives: 256,
+        max_linear_gradient_primitives: 256,
+        max_gradient_stop_primitives: 256,
+        max_mask_layers: 256,
+      };
+      Self { limits, lost: None, recreate_failures: 0, generation: 0, uploads: 0 }
+    }
+
+    fn check(&self, texture: &MockTexture) {
+      assert_eq!(texture.generation, self.generation, "use a texture of the lost device");
+    }
+  }
+
+  impl GPUBackendImpl for MockGpu {
+    type Texture = MockTexture;
+
+    fn begin_frame(&mut self) {}
+
+    fn limits(&self) -> &DrawPhaseLimits { &self.limits }
+
+    fn device_lost(&self) -> Option<String> { self.lost.clone() }
+
+    fn recreate_device(&mut self) -> Result<(), Box<dyn Error>> {
+      if self.recreate_failures > 0 {
+        self.recreate_failures -= 1;
+        return Err("no adapter".into());
+      }
+      self.lost = None;
+      self.generation += 1;
+      Ok(())
+    }
+
+    fn new_texture(&mut self, size: DeviceSize, format: ColorFormat) -> Self::Texture {
+      MockTexture { size, format, generation: self.generation }
+    }
+
+    fn load_alpha_vertices(&mut self, _: &VertexBuffers<()>) {}
+
+    fn draw_alpha_triangles(&mut self, _: &Range<u32>, texture: &mut Self::Texture) {
+      self.check(texture);
+    }
+
+    fn draw_alpha_triangles_with_scissor(
+      &mut self, _: &Range<u32>, texture: &mut Self::Texture, _: DeviceRect,
+    ) {
+      self.check(texture);
+    }
+
+    fn load_textures(&mut self, textures: &[&Self::Texture]) {
+      textures.iter().for_each(|t| self.check(t));
+    }
+
+    fn load_mask_layers(&mut self, _: &[MaskLayer]) {}
+
+    fn load_color_vertices(&mut self, _: &VertexBuffers<ColorAttr>) {}
+
+    fn load_img_primitives(&mut self, _: &[ImgPrimitive]) {}
+
+    fn load_img_vertices(&mut self, _: &VertexBuffers<ImagePrimIndex>) {}
+
+    fn load_radial_gradient_primitives(&mut self, _: &[RadialGradientPrimitive]) {}
+
+    fn load_radial_gradient_stops(&mut self, _: &[GradientStopPrimitive]) {}
+
+    fn load_radial_gradient_vertices(&mut self, _: &VertexBuffers<RadialGradientPrimIndex>) {}
+
+    fn load_linear_gradient_primitives(&mut self, _: &[LinearGradientPrimitive]) {}
+
+    fn load_linear_gradient_stops(&mut self, _: &[GradientStopPrimitive]) {}
+
+    fn load_linear_gradient_vertices(&mut self, _: &VertexBuffers<LinearGradientPrimIndex>) {}
+
+    fn draw_color_triangles(&mut self, _: &mut Self::Texture, _: Range<u32>, _: Option<Color>) {}
+
+    fn draw_img_triangles(&mut self, _: &mut Self::Texture, _: Range<u32>, _: Option<Color>) {}
+
+    fn draw_radial_gradient_triangles(
+      &mut self, _: &mut Self::Texture, _: Range<u32>, _: Option<Color>,
+    ) {
+    }
+
+    fn draw_linear_gradient_triangles(
+      &mut self, _: &mut Self::Texture, _: Range<u32>, _: Option<Color>,
+    ) {
+    }
+
+    fn copy_texture_from_texture(
+      &mut self, dist_tex: &mut Self::Texture, _: DevicePoint, from_tex: &Self::Texture,
+      _: &DeviceRect,
+    ) {
+      self.check(dist_tex);
+      self.check(from_tex);
+    }
+
+    fn end_frame(&mut self) {}
+  }
+
+  impl Texture for MockTexture {
+    type Host = MockGpu;
+
+    fn clear_areas(&mut self, _: &[DeviceRect], host: &mut MockGpu) { host.check(self); }
+
+    fn write_data(&mut self, _: &DeviceRect, _: &[u8], host: &mut MockGpu) {
+      host.check(self);
+      host.uploads += 1;
+    }
+
+    fn copy_as_image(
+      &self, _: &DeviceRect, _: &mut MockGpu,
+    ) -> impl std::future::Future<Output = Result<PixelImage, Box<dyn Error>>> + 'static {
+      std::future::ready(Err("the mock texture has no data".into()))
+    }
+
+    fn color_format(&self) -> ColorFormat { self.format }
+
+    fn size(&self) -> DeviceSize { self.size }
+  }
+
+  impl FrameSurface<MockGpu> for MockSurface {
+    fn current_texture(&mut self) -> Result<&mut MockTexture, SurfaceError> {
+      if self.texture.is_none() {
+        if let Some(err) = self.errors.pop_front() {
+          return Err(err);
+        }
+        self.texture = Some(MockTexture {
+          size: DeviceSize::new(64, 64),
+          format: ColorFormat::Rgba8,
+          generation: 0,
+        });
+      }
+      Ok(self.texture.as_mut().unwrap())
+    }
+
+    fn reconfigure(&mut self, _: &MockGpu) {
+      self.texture = None;
+      self.reconfigured += 1;
+    }
+
+    fn present(&mut self) {
+      if self.texture.take().is_some() {
+        self.presented += 1;
+      }
+    }
+  }
+
+  type MockRenderer = SurfaceRenderer<MockGpu, MockSurface>;
+
+  fn mock_renderer(errors: impl IntoIterator<Item = SurfaceError>) -> MockRenderer {
+    let surface = MockSurface { errors: errors.into_iter().collect(), ..<_>::default() };
+    SurfaceRenderer::new(GPUBackend::new(MockGpu::new()), surface)
+  }
+
+  fn image_commands() -> Vec<PaintCommand> {
+    let img =
+      Resource::new(PixelImage::new(vec![255; 16 * 16 * 4].into(), 16, 16, ColorFormat::Rgba8));
+    let mut painter = Painter::new(Rect::from_size(Size::new(64., 64.)));
+    painter
+      .set_fill_brush(Brush::Image(img))
+      .rect(&Rect::from_size(Size::new(16., 16.)))
+      .fill();
+    painter.finish().take()
+  }
+
+  fn frame(renderer: &mut MockRenderer, commands: &[PaintCommand]) -> (Vec<DeviceEvent>, bool) {
+    let events = renderer.begin_frame(Color::WHITE);
+    let viewport = DeviceRect::from_size(DeviceSize::new(64, 64));
+    renderer.draw_commands(viewport, &Transform::identity(), commands);
+    (events, renderer.end_frame())
+  }
+
+  #[test]
+  fn reconfigure_outdated_or_lost_surface() {
+    let mut renderer = mock_renderer([SurfaceError::Outdated]);
+    let commands = image_commands();
+
+    assert_eq!(frame(&mut renderer, &commands), (vec![], true));
+    assert_eq!(renderer.surface().reconfigured, 1);
+    renderer
+      .surface
+      .errors
+      .push_back(SurfaceError::Lost);
+    assert_eq!(frame(&mut renderer, &commands), (vec![], true));
+    assert_eq!(renderer.surface().reconfigured, 2);
+    assert_eq!(renderer.surface().presented, 2);
+  }
+
+  #[test]
+  fn skip_one_frame_if_surface_fails() {
+    let mut renderer = mock_renderer([SurfaceError::Timeout]);
+    let commands = image_commands();
+    assert_eq!(frame(&mut renderer, &commands), (vec![], false));
+    assert_eq!(frame(&mut renderer, &commands), (vec![], true));
+
+    let mut renderer = mock_renderer([SurfaceError::Outdated, SurfaceError::Outdated]);
+    assert_eq!(frame(&mut renderer, &commands), (vec![], false));
+    assert_eq!(frame(&mut renderer, &commands), (vec![], true));
+    assert_eq!(renderer.surface().presented, 1);
+  }
+
+  #[test]
+  fn recover_lost_device() {
+    let mut renderer = mock_renderer([]);
+    let commands = image_commands();
+
+    frame(&mut renderer, &commands);
+    frame(&mut renderer, &commands);
+    // The image is cached in the atlas after the first frame.
+    assert_eq!(renderer.backend().get_impl().uploads, 1);
+
+    renderer.backend.get_impl_mut().lost = Some("driver reset".into());
+    let lost = DeviceEvent::Lost("driver reset".into());
+    assert_eq!(frame(&mut renderer, &commands), (vec![lost, DeviceEvent::Recovered], true));
+    assert_eq!(renderer.surface().reconfigured, 1);
+    // The image is uploaded to the new device once, then it's cached again.
+    assert_eq!(renderer.backend().get_impl().uploads, 2);
+    assert_eq!(frame(&mut renderer, &commands), (vec![], true));
+    assert_eq!(renderer.backend().get_impl().uploads, 2);
+  }
+
+  #[test]
+  fn retry_recreating_device() {
+    let mut renderer = mock_renderer([]);
+    let commands = image_commands();
+    frame(&mut renderer, &commands);
+
+    let gpu = renderer.backend.get_impl_mut();
+    gpu.lost = Some("removed".into());
+    gpu.recreate_failures = 1;
+
+    let lost = DeviceEvent::Lost("removed".into());
+    assert_eq!(frame(&mut renderer, &commands), (vec![lost], false));
+    assert_eq!(frame(&mut renderer, &commands), (vec![DeviceEvent::Recovered], true));
+    assert_eq!(renderer.surface().presented, 2);
+    assert_eq!(renderer.backend().get_impl().uploads, 2);
+  }
+}
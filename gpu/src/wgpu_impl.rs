@@ -2,6 +2,7 @@ use std::{
   error::Error,
   mem::{MaybeUninit, size_of},
   ops::Range,
+  sync::{Arc, Mutex},
 };
 
 use futures::channel::oneshot;
@@ -18,9 +19,9 @@ use self::{
   uniform::Uniform,
 };
 use crate::{
-  ColorAttr, DrawPhaseLimits, GPUBackendImpl, GradientStopPrimitive, ImagePrimIndex, ImgPrimitive,
-  LinearGradientPrimIndex, LinearGradientPrimitive, MaskLayer, RadialGradientPrimIndex,
-  RadialGradientPrimitive, gpu_backend::Texture,
+  ColorAttr, DrawPhaseLimits, FrameSurface, GPUBackendImpl, GradientStopPrimitive, ImagePrimIndex,
+  ImgPrimitive, LinearGradientPrimIndex, LinearGradientPrimitive, MaskLayer,
+  RadialGradientPrimIndex, RadialGradientPrimitive, SurfaceError, gpu_backend::Texture,
 };
 mod shaders;
 mod uniform;
@@ -38,6 +39,9 @@ pub const TEX_PER_DRAW: usize = 8;
 pub struct WgpuImpl {
   device: wgpu::Device,
   queue: wgpu::Queue,
+  adapter: Arc<wgpu::Adapter>,
+  /// The reason the device is lost, it's set by the device lost callback.
+  lost: Arc<Mutex<Option<String>>>,
 
   command_encoder: Option<wgpu::CommandEncoder>,
   command_buffers: Vec<wgpu::CommandBuffer>,
@@ -130,6 +134,7 @@ pub(crate) use command_encoder;
 pub struct Surface<'a> {
   surface: wgpu::Surface<'a>,
   config: wgpu::SurfaceConfiguration,
+  alpha_modes: Vec<wgpu::CompositeAlphaMode>,
   current_texture: Option<WgpuTexture>,
 }
 
@@ -138,6 +143,21 @@ impl GPUBackendImpl for WgpuImpl {
 
   fn limits(&self) -> &DrawPhaseLimits { &self.limits }
 
+  fn device_lost(&self) -> Option<String> { self.lost.lock().unwrap().clone() }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  fn recreate_device(&mut self) -> Result<(), Box<dyn Error>> {
+    // The passes and the pipelines compiled on the lost device are dropped with
+    // it, they're created again when they're used.
+    *self = futures::executor::block_on(Self::with_adapter(self.adapter.clone()))?;
+    Ok(())
+  }
+
+  #[cfg(target_arch = "wasm32")]
+  fn recreate_device(&mut self) -> Result<(), Box<dyn Error>> {
+    Err("recreating the device is not supported on the web".into())
+  }
+
   fn begin_frame(&mut self) {
     if self.command_encoder.is_none() {
       #[cfg(debug_assertions)]
@@ -346,16 +366,48 @@ impl<'a> Surface<'a> {
     }
   }
 
+  /// Set whether the surface composites with what's behind it by the alpha of
+  /// its pixels. It falls back to the opaque composition if the platform does
+  /// not support it.
+  pub fn set_transparent(&mut self, transparent: bool, backend: &WgpuImpl) {
+    use wgpu::CompositeAlphaMode::*;
+    let mode = if transparent {
+      [PreMultiplied, PostMultiplied]
+        .into_iter()
+        .find(|m| self.alpha_modes.contains(m))
+        .unwrap_or(Auto)
+    } else {
+      Auto
+    };
+    if self.config.alpha_mode != mode {
+      self.config.alpha_mode = mode;
+      if !self.size().is_empty() {
+        self
+          .surface
+          .configure(backend.device(), &self.config);
+      }
+    }
+  }
+
   /// Get the size of the surface.
   pub fn size(&self) -> DeviceSize {
     DeviceSize::new(self.config.width as i32, self.config.height as i32)
   }
 
-  pub fn get_current_texture(&mut self) -> &mut WgpuTexture {
-    self.current_texture.get_or_insert_with(|| {
-      let tex = self.surface.get_current_texture().unwrap();
-      WgpuTexture::new(InnerTexture::SurfaceTexture(tex))
-    })
+  pub fn get_current_texture(&mut self) -> Result<&mut WgpuTexture, SurfaceError> {
+    if self.current_texture.is_none() {
+      let tex = self
+        .surface
+        .get_current_texture()
+        .map_err(|err| match err {
+          wgpu::SurfaceError::Timeout => SurfaceError::Timeout,
+          wgpu::SurfaceError::Outdated => SurfaceError::Outdated,
+          wgpu::SurfaceError::Lost => SurfaceError::Lost,
+          wgpu::SurfaceError::OutOfMemory => SurfaceError::OutOfMemory,
+        })?;
+      self.current_texture = Some(WgpuTexture::new(InnerTexture::SurfaceTexture(tex)));
+    }
+    Ok(self.current_texture.as_mut().unwrap())
   }
 
   /// Present the current texture to the surface.
@@ -366,6 +418,25 @@ impl<'a> Surface<'a> {
     }
   }
 }
+
+impl<'a> FrameSurface<WgpuImpl> for Surface<'a> {
+  fn current_texture(&mut self) -> Result<&mut WgpuTexture, SurfaceError> {
+    self.get_current_texture()
+  }
+
+  fn reconfigure(&mut self, gpu_impl: &WgpuImpl) {
+    // Drop the texture of the old swapchain before configuring a new one.
+    self.current_texture = None;
+    if !self.size().is_empty() {
+      self
+        .surface
+        .configure(gpu_impl.device(), &self.config);
+    }
+  }
+
+  fn present(&mut self) { Surface::present(self) }
+}
+
 pub struct WgpuTexture {
   inner_tex: InnerTexture,
   view: wgpu::TextureView,
@@ -575,6 +646,38 @@ impl WgpuImpl {
       .await
       .expect("No suitable GPU adapters found on the system!");
 
+    let gpu_impl = Self::with_adapter(Arc::new(adapter))
+      .await
+      .expect("Unable to find a suitable GPU adapter!");
+
+    let surface = surface.map(|surface| {
+      use wgpu::TextureFormat::*;
+      let capabilities = surface.get_capabilities(&gpu_impl.adapter);
+      let format = capabilities
+        .formats
+        .into_iter()
+        .find(|&f| f == Rgba8Unorm || f == Bgra8Unorm)
+        .expect("No suitable format found for the surface!");
+
+      let config = wgpu::SurfaceConfiguration {
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        format,
+        width: 0,
+        height: 0,
+        present_mode: wgpu::PresentMode::Fifo,
+        alpha_mode: wgpu::CompositeAlphaMode::Auto,
+        view_formats: vec![format],
+        desired_maximum_frame_latency: 2,
+      };
+
+      Surface { surface, config, alpha_modes: capabilities.alpha_modes, current_texture: None }
+    });
+
+    (gpu_impl, surface)
+  }
+
+  /// Create the device on the `adapter` and all the resources on it.
+  async fn with_adapter(adapter: Arc<wgpu::Adapter>) -> Result<Self, wgpu::RequestDeviceError> {
     let (device, queue) = adapter
       .request_device(
         &wgpu::DeviceDescriptor {
@@ -584,8 +687,18 @@ impl WgpuImpl {
         },
         None,
       )
-      .await
-      .expect("Unable to find a suitable GPU adapter!");
+      .await?;
+
+    let lost = Arc::new(Mutex::new(None));
+    let lost_flag = lost.clone();
+    device.set_device_lost_callback(move |reason, msg| {
+      use wgpu::DeviceLostReason::*;
+      // The callback is also called when the device is dropped or the callback
+      // is replaced, the device is not lost in these cases.
+      if matches!(reason, Unknown | Destroyed | DeviceInvalid) {
+        *lost_flag.lock().unwrap() = Some(format!("{reason:?}: {msg}"));
+      }
+    });
 
     let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
       address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -625,9 +738,11 @@ impl WgpuImpl {
       Uniform::new(&device, wgpu::ShaderStages::FRAGMENT, limits.max_mask_layers);
     let clear_tex_pass = ClearTexturePass::new(&device);
     let texs_layout = textures_layout(&device);
-    let gpu_impl = WgpuImpl {
+    Ok(WgpuImpl {
       device,
       queue,
+      adapter,
+      lost,
       command_encoder: None,
       command_buffers: vec![],
       sampler,
@@ -642,32 +757,7 @@ impl WgpuImpl {
       textures_bind: None,
       mask_layers_uniform,
       limits,
-    };
-
-    let surface = surface.map(|surface| {
-      use wgpu::TextureFormat::*;
-      let format = surface
-        .get_capabilities(&adapter)
-        .formats
-        .into_iter()
-        .find(|&f| f == Rgba8Unorm || f == Bgra8Unorm)
-        .expect("No suitable format found for the surface!");
-
-      let config = wgpu::SurfaceConfiguration {
-        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-        format,
-        width: 0,
-        height: 0,
-        present_mode: wgpu::PresentMode::Fifo,
-        alpha_mode: wgpu::CompositeAlphaMode::Auto,
-        view_formats: vec![format],
-        desired_maximum_frame_latency: 2,
-      };
-
-      Surface { surface, config, current_texture: None }
-    });
-
-    (gpu_impl, surface)
+    })
   }
 
   pub fn start_capture(&self) { self.device.start_capture(); }
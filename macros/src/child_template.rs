@@ -66,6 +66,11 @@ pub(crate) fn derive_child_template(input: &mut syn::DeriveInput) -> syn::Result
           quote!(#f_idx)
         };
         let ty = option_type_extract(&f.ty).unwrap_or(&f.ty);
+        let err = format!(
+          "The child `{}` of the template `{name}` is filled twice, a field of the template only \
+           accepts one child.",
+          quote! { #ty }
+        );
 
         let gen = with_child_generics(generics, ty);
         let (g_impl, _, g_where) = gen.split_for_impl();
@@ -76,7 +81,7 @@ pub(crate) fn derive_child_template(input: &mut syn::DeriveInput) -> syn::Result
             type Target = Self;
             #[track_caller]
             fn with_child(mut self, c: _C) -> Self::Target {
-              assert!(self.#field_name.is_none(), "Try to fill same type twice.");
+              assert!(self.#field_name.is_none(), "{}", #err);
               self.#field_name = Some(c.into_child_compose());
               self
             }
@@ -104,7 +109,7 @@ pub(crate) fn derive_child_template(input: &mut syn::DeriveInput) -> syn::Result
           let init_values = fields.iter().map(|field| {
             let field_name = field.ident.as_ref().unwrap();
             let ty = &field.ty;
-            let value = gen_init_value_tokens(quote!(#field_name), ty);
+            let value = gen_init_value_tokens(quote!(#field_name), ty, name);
             quote! {#field_name: #value}
           });
 
@@ -133,7 +138,7 @@ pub(crate) fn derive_child_template(input: &mut syn::DeriveInput) -> syn::Result
 
           let init_values = fields.iter().enumerate().map(|(idx, field)| {
             let idx = Index::from(idx);
-            gen_init_value_tokens(quote!(#idx), &field.ty)
+            gen_init_value_tokens(quote!(#idx), &field.ty, name)
           });
 
           tokens.extend(quote! {
@@ -153,7 +158,12 @@ pub(crate) fn derive_child_template(input: &mut syn::DeriveInput) -> syn::Result
       Ok(tokens)
     }
     syn::Data::Enum(DataEnum { variants, .. }) => {
-      let err_str = format!("Child `{}` not specify.", quote! { #name });
+      let err_str =
+        format!("The template `{}` requires a child, but no child is given.", quote! { #name });
+      let twice_err = format!(
+        "The template `{}` only accepts one child, but more than one is given.",
+        quote! { #name }
+      );
       tokens.extend(quote! {
         #[derive(Default)]
         #vis struct #builder #g_impl #g_where(Option<#name #g_ty>);
@@ -183,7 +193,7 @@ pub(crate) fn derive_child_template(input: &mut syn::DeriveInput) -> syn::Result
                 type Target = Self;
                 #[track_caller]
                 fn with_child(mut self, c: _C) -> Self::Target {
-                  assert!(self.0.is_none(), "Try to fill same type twice.");
+                  assert!(self.0.is_none(), "{}", #twice_err);
                   self.0 = Some(#name::#v_name(c.into_child_compose()));
                   self
                 }
@@ -242,10 +252,11 @@ fn convert_to_builder_pair(mut p: Pair<Field, Comma>) -> Pair<Field, Comma> {
   p
 }
 
-fn gen_init_value_tokens(field_name: TokenStream, ty: &syn::Type) -> TokenStream {
+fn gen_init_value_tokens(field_name: TokenStream, ty: &syn::Type, name: &Ident) -> TokenStream {
   let mut value = quote! { self.#field_name };
   if option_type_extract(ty).is_none() {
-    let err = format!("Required child `{}` not specify", quote! { #ty });
+    let err =
+      format!("The required child `{}` of the template `{name}` is not given.", quote! { #ty });
     value.extend(quote! { .expect(#err)});
   };
   value
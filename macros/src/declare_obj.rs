@@ -136,7 +136,9 @@ impl DeclareObj {
     match &self.this.node_type {
       ObjType::Type { ty, span } => {
         let name = Ident::new("_ಠ_ಠ", *span);
-        self.gen_fields_tokens(&name, quote! { #ty::declarer() }, quote! { .finish() }, tokens);
+        // The span of `finish` is the location where the widget was declared.
+        let finish = quote_spanned! { *span => .finish() };
+        self.gen_fields_tokens(&name, quote! { #ty::declarer() }, finish, tokens);
       }
       ObjType::Var { var, used_me } => {
         // if has capture self, rename to `_ಠ_ಠ` avoid conflict name.
@@ -76,6 +76,22 @@ pub fn child_of_compose(input: TokenStream) -> TokenStream {
   .into()
 }
 
+/// Implement `CustomStyle` for the type, using its `Default` value as the
+/// default style.
+#[proc_macro_derive(CustomStyle)]
+pub fn custom_style_derive(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+  let name = input.ident;
+
+  quote! {
+    impl #impl_generics CustomStyle for #name #ty_generics #where_clause {
+      fn default_style(_: &impl ProviderCtx) -> Self { <Self as Default>::default() }
+    }
+  }
+  .into()
+}
+
 #[proc_macro_derive(Lerp)]
 pub fn lerp_derive(input: TokenStream) -> TokenStream {
   let mut input = parse_macro_input!(input as DeriveInput);
@@ -288,6 +304,93 @@ pub fn include_svg(input: TokenStream) -> TokenStream {
   include_svg_from_path(file, inherit_fill, inherit_stroke)
 }
 
+/// Embeds a file as an asset and returns its `AssetId`.
+///
+/// The file is located relative to the current crate, like
+/// [`include_crate_svg!`]. It's registered as a font, an image or an SVG by
+/// its extension, or by the kind given after the path, such as
+/// `include_asset!("logo.bin", Image)`. The identical files share one
+/// `AssetId`.
+///
+/// This macro returns an expression of type `AssetId`.
+#[proc_macro]
+pub fn include_asset(input: TokenStream) -> TokenStream {
+  let IncludeAssetArgs { path, kind } = parse_macro_input! { input as IncludeAssetArgs };
+  let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+  let file = std::path::Path::new(&dir).join(path.value());
+
+  let kind = match kind {
+    Some(kind) => kind,
+    None => {
+      let ext = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+      let kind = match ext.as_deref() {
+        Some("ttf" | "otf" | "ttc" | "otc") => "Font",
+        Some("png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp") => "Image",
+        Some("svg") => "Svg",
+        _ => {
+          let err = "can't infer the kind of the asset by its extension, give it as \
+                     `include_asset!(path, Font | Image | Svg)`";
+          return syn::Error::new(path.span(), err)
+            .to_compile_error()
+            .into();
+        }
+      };
+      syn::Ident::new(kind, path.span())
+    }
+  };
+
+  let bytes = match std::fs::read(&file) {
+    Ok(bytes) => bytes,
+    Err(err) => {
+      let err = format!("{err}({file:?})");
+      return syn::Error::new(path.span(), err)
+        .to_compile_error()
+        .into();
+    }
+  };
+  // FNV-1a, stable in every build.
+  let hash = bytes
+    .iter()
+    .fold(0xcbf2_9ce4_8422_2325_u64, |h, b| (h ^ *b as u64).wrapping_mul(0x0100_0000_01b3));
+  let file = file.to_string_lossy().into_owned();
+  quote! {
+    AssetId::register(EmbeddedAsset {
+      path: #path,
+      kind: AssetKind::#kind,
+      hash: #hash,
+      bytes: include_bytes!(#file),
+    })
+  }
+  .into()
+}
+
+struct IncludeAssetArgs {
+  path: syn::LitStr,
+  kind: Option<syn::Ident>,
+}
+
+impl syn::parse::Parse for IncludeAssetArgs {
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    let path = input.parse::<syn::LitStr>()?;
+    let mut kind = None;
+    if input.parse::<Option<syn::Token![,]>>()?.is_some() && !input.is_empty() {
+      let ident = input.parse::<syn::Ident>()?;
+      if !matches!(ident.to_string().as_str(), "Font" | "Image" | "Svg") {
+        return Err(syn::Error::new(
+          ident.span(),
+          "the kind of an asset is `Font`, `Image` or `Svg`",
+        ));
+      }
+      kind = Some(ident);
+      input.parse::<Option<syn::Token![,]>>()?;
+    }
+    Ok(IncludeAssetArgs { path, kind })
+  }
+}
+
 fn include_svg_from_path(
   path: std::path::PathBuf, inherit_fill: bool, inherit_stroke: bool,
 ) -> TokenStream {
@@ -67,6 +67,8 @@ pub static BUILTIN_INFOS: phf::Map<&'static str, BuiltinMember> = phf_map! {
   "set_auto_focus" => builtin_member!{"MixFlags", Method, "mix_flags"},
   "tab_index" => builtin_member!{"MixFlags", Method, "mix_flags"},
   "set_tab_index" => builtin_member!{"MixFlags", Method, "mix_flags"},
+  "mouse_buttons_filter" => builtin_member!{"MixFlags", Method, "mix_flags"},
+  "set_mouse_buttons_filter" => builtin_member!{"MixFlags", Method, "mix_flags"},
   // MixBuiltin
   "on_event" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_mounted" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
@@ -81,18 +83,35 @@ pub static BUILTIN_INFOS: phf::Map<&'static str, BuiltinMember> = phf_map! {
   "on_pointer_cancel" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_pointer_enter" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_pointer_leave" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_got_pointer_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_lost_pointer_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_tap" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_tap_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_long_press" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_long_press_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_start" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_update" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_drag_end" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_middle_tap" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_back" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_forward" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_double_tap" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_double_tap_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_triple_tap" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_triple_tap_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_double_tap_within" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_double_tap_within_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_triple_tap_within" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_triple_tap_within_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_x_times_tap" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_x_times_tap_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_x_times_tap_within" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_x_times_tap_within_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_ime_pre_edit" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_ime_pre_edit_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_wheel" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_wheel_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
+  "on_scroll" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_chars" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_chars_capture" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
   "on_key_down" => builtin_member!{"MixBuiltin", Method, "mix_builtin"},
@@ -127,6 +146,11 @@ pub static BUILTIN_INFOS: phf::Map<&'static str, BuiltinMember> = phf_map! {
   "letter_space" => builtin_member! { "TextStyleWidget", Method, "text_style" },
   "text_line_height" => builtin_member! { "TextStyleWidget", Method, "text_style" },
   "text_overflow" => builtin_member! { "TextStyleWidget", Method, "text_style" },
+  "text_shadows" => builtin_member! { "TextStyleWidget", Method, "text_style" },
+  "text_stroke" => builtin_member! { "TextStyleWidget", Method, "text_style" },
+  "text_hyphenator" => builtin_member! { "TextStyleWidget", Method, "text_style" },
+  // Provenance
+  "debug_name" => builtin_member! { "FatObj", Method, "provenance" },
   // Padding
   "padding" => builtin_member!{"Padding", Field, "padding"},
   // LayoutBox
@@ -164,10 +188,16 @@ pub static BUILTIN_INFOS: phf::Map<&'static str, BuiltinMember> = phf_map! {
   "visible" => builtin_member!{"Visibility", Field, "visibility"},
   // Opacity
   "opacity" => builtin_member!{"Opacity", Field, "opacity"},
+  // ZIndex
+  "z_index" => builtin_member!{"ZIndex", Field, "z_index"},
   // KeepAlive
   "keep_alive" => builtin_member!{"KeepAlive", Field, "keep_alive"},
   // Tooltips
   "tooltips" => builtin_member!{"Tooltips", Field, "tooltips"},
+  // SemanticsLabel
+  "semantics_label" => builtin_member!{"SemanticsLabel", Field, "semantics_label"},
   // TrackWidgetId
   "track_id" => builtin_member!{"TrackWidgetId", Method, "track_id"},
+  // GlobalKeyWidget
+  "global_key" => builtin_member!{"GlobalKeyWidget", Field, "global_key"},
 };
@@ -0,0 +1,99 @@
+//! The assets embedded in the binary by `include_asset!`.
+//!
+//! An embedded asset is identified by an [`AssetId`], the identical files share
+//! one id. The fonts, images and SVGs can be loaded from it directly, such as
+//! [`FontDB::load_asset`](crate::font_db::FontDB::load_asset) and
+//! [`Svg::from_asset`](crate::Svg::from_asset).
+//!
+//! In the debug builds, the assets can be loaded from a directory rather than
+//! the embedded bytes, to try the changes of them without compiling again, see
+//! [`AssetOverride`].
+
+use std::{
+  borrow::Cow,
+  path::PathBuf,
+  sync::{LazyLock, Mutex, RwLock},
+};
+
+use ahash::HashMap;
+
+/// The id of an embedded asset, the hash of its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AssetId(u64);
+
+/// What an asset is loaded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+  Font,
+  Image,
+  Svg,
+}
+
+/// An asset embedded by `include_asset!`.
+#[doc(hidden)]
+pub struct EmbeddedAsset {
+  /// The path of the asset relative to the crate that embeds it.
+  pub path: &'static str,
+  pub kind: AssetKind,
+  /// The hash of the bytes, computed when it's embedded.
+  pub hash: u64,
+  pub bytes: &'static [u8],
+}
+
+/// The directory to load the assets from in the debug builds, rather than the
+/// embedded bytes. It's `RIBIR_ASSET_DIR` of the environment by default.
+///
+/// An asset is loaded from its path relative to the directory, the embedded
+/// bytes are used if the file doesn't exist. It's ignored in the release
+/// builds.
+pub struct AssetOverride;
+
+static ASSETS: LazyLock<Mutex<HashMap<AssetId, EmbeddedAsset>>> = LazyLock::new(<_>::default);
+
+static OVERRIDE_DIR: LazyLock<RwLock<Option<PathBuf>>> =
+  LazyLock::new(|| RwLock::new(std::env::var_os("RIBIR_ASSET_DIR").map(PathBuf::from)));
+
+impl AssetId {
+  /// Register an embedded asset, the identical ones get the same id.
+  #[doc(hidden)]
+  pub fn register(asset: EmbeddedAsset) -> AssetId {
+    let id = AssetId(asset.hash);
+    ASSETS.lock().unwrap().entry(id).or_insert(asset);
+    id
+  }
+
+  /// What the asset is loaded as.
+  pub fn kind(self) -> AssetKind { self.with(|a| a.kind) }
+
+  /// The path of the asset relative to the crate that embeds it.
+  pub fn path(self) -> &'static str { self.with(|a| a.path) }
+
+  /// The bytes of the asset, or of its file in the [`AssetOverride`]
+  /// directory in the debug builds.
+  pub fn bytes(self) -> Cow<'static, [u8]> {
+    let (path, bytes) = self.with(|a| (a.path, a.bytes));
+    if cfg!(debug_assertions) {
+      let file = AssetOverride::dir().and_then(|dir| std::fs::read(dir.join(path)).ok());
+      if let Some(file) = file {
+        return Cow::Owned(file);
+      }
+    }
+    Cow::Borrowed(bytes)
+  }
+
+  /// The hash of the embedded bytes.
+  pub fn hash(self) -> u64 { self.0 }
+
+  fn with<R>(self, f: impl FnOnce(&EmbeddedAsset) -> R) -> R {
+    // An id is only created by the registration, so it's always there.
+    f(&ASSETS.lock().unwrap()[&self])
+  }
+}
+
+impl AssetOverride {
+  /// Load the assets from the `dir`, or the embedded bytes if it's `None`.
+  pub fn set_dir(dir: Option<PathBuf>) { *OVERRIDE_DIR.write().unwrap() = dir; }
+
+  /// The directory that the assets are loaded from.
+  pub fn dir() -> Option<PathBuf> { OVERRIDE_DIR.read().unwrap().clone() }
+}
@@ -67,6 +67,49 @@ impl PixelImage {
   pub fn size(&self) -> DeviceSize { DeviceSize::new(self.width as i32, self.height as i32) }
   #[inline]
   pub fn pixel_bytes(&self) -> &[u8] { &self.data }
+
+  /// Downscale the image to `width` x `height` by averaging the pixels that
+  /// every new pixel covers. The image is returned as it is if it's not larger
+  /// than the new size.
+  pub fn downscale(self, width: u32, height: u32) -> PixelImage {
+    let (width, height) = (width.clamp(1, self.width), height.clamp(1, self.height));
+    if width == self.width && height == self.height {
+      return self;
+    }
+
+    let bpp = self.format.pixel_per_bytes() as usize;
+    let src_row = self.width as usize * bpp;
+    let mut data = Vec::with_capacity(width as usize * height as usize * bpp);
+    let span = |dst: u32, dst_len: u32, src_len: u32| {
+      let start = dst as u64 * src_len as u64 / dst_len as u64;
+      let end = (dst as u64 + 1) * src_len as u64 / dst_len as u64;
+      start as usize..(end as usize).max(start as usize + 1)
+    };
+    let mut sum = vec![0u32; bpp];
+    for y in 0..height {
+      let rows = span(y, height, self.height);
+      for x in 0..width {
+        let cols = span(x, width, self.width);
+        sum.iter_mut().for_each(|s| *s = 0);
+        for row in rows.clone() {
+          let line = &self.data[row * src_row..(row + 1) * src_row];
+          for px in line[cols.start * bpp..cols.end * bpp].chunks_exact(bpp) {
+            sum
+              .iter_mut()
+              .zip(px)
+              .for_each(|(s, c)| *s += *c as u32);
+          }
+        }
+        let count = (rows.len() * cols.len()) as u32;
+        data.extend(
+          sum
+            .iter()
+            .map(|s| ((s + count / 2) / count) as u8),
+        );
+      }
+    }
+    PixelImage::new(data.into(), width, height, self.format)
+  }
 }
 
 impl std::fmt::Debug for PixelImage {
@@ -76,3 +119,23 @@ impl std::fmt::Debug for PixelImage {
       .finish()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn downscale_average() {
+    // A 4x2 image of two gray levels, 0 on the left and 200 on the right.
+    let data = [0, 0, 200, 200, 0, 0, 200, 200];
+    let img = PixelImage::new(data.to_vec().into(), 4, 2, ColorFormat::Alpha8);
+    let small = img.downscale(2, 1);
+    assert_eq!(small.size(), DeviceSize::new(2, 1));
+    assert_eq!(small.pixel_bytes(), &[0, 200]);
+
+    let blend = small.downscale(1, 1);
+    assert_eq!(blend.pixel_bytes(), &[100]);
+    // Never upscale.
+    assert_eq!(blend.downscale(8, 8).size(), DeviceSize::new(1, 1));
+  }
+}
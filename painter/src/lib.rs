@@ -1,6 +1,7 @@
 #![allow(clippy::needless_lifetimes)]
 
 //! A 2d logic painter, generate the paint command
+pub mod asset;
 pub mod color;
 mod painter;
 pub mod path;
@@ -9,7 +10,10 @@ pub use path::*;
 mod text;
 pub use text::*;
 
+#[doc(hidden)]
+pub use crate::asset::EmbeddedAsset;
 pub use crate::{
+  asset::{AssetId, AssetKind, AssetOverride},
   color::{Color, GradientStop, LightnessTone},
   painter::*,
 };
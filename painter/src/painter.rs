@@ -2,12 +2,13 @@ use std::ops::{Deref, DerefMut};
 
 use ribir_algo::Resource;
 use ribir_geom::{Angle, DeviceRect, Point, Rect, Size, Transform, Vector};
+use rustybuzz::ttf_parser;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-  Brush, Color, Glyph, PixelImage, Svg, VisualGlyphs,
-  color::{LinearGradient, RadialGradient},
-  font_db::FontDB,
+  Brush, Color, Glyph, PixelImage, Svg, TextShadow, TextStroke, VisualGlyphs,
+  color::{GradientStop, LinearGradient, RadialGradient},
+  font_db::{Face, FontDB},
   path::*,
   path_builder::PathBuilder,
 };
@@ -203,6 +204,12 @@ impl Painter {
   /// the next time you call [`Painter::reset`]!.
   pub fn set_viewport(&mut self, bounds: Rect) { self.init_state.bounds = bounds; }
 
+  /// Change the transform that all the drawing starts with. But it won't take
+  /// effect until the next time you call [`Painter::reset`]!.
+  pub fn set_init_transform(&mut self, transform: Transform) {
+    self.init_state.transform = transform;
+  }
+
   pub fn intersection_paint_bounds(&self, rect: &Rect) -> Option<Rect> {
     self.paint_bounds().intersection(rect)
   }
@@ -630,13 +637,37 @@ impl Painter {
     let matrix = *self.transform();
 
     let bounds = g.bounds();
-    if let Some(path) = face.outline_glyph(g.glyph_id) {
+    if face.is_color_glyph(g.glyph_id) {
+      let scale = font_size / unit;
+      let foreground = match self.fill_brush() {
+        Brush::Color(c) => *c,
+        _ => Color::BLACK,
+      };
+      let foreground = ttf_parser::RgbaColor::new(
+        foreground.red,
+        foreground.green,
+        foreground.blue,
+        foreground.alpha,
+      );
+      self
+        .translate(bounds.min_x(), bounds.min_y())
+        .scale(scale, -scale)
+        .translate(0., -unit)
+        .save();
+      let mut painter = ColorGlyphPainter { painter: self, face, outline: None };
+      face.paint_color_glyph(g.glyph_id, 0, foreground, &mut painter);
+      self.restore();
+    } else if let Some(path) = face.outline_glyph(g.glyph_id) {
       let scale = font_size / unit;
+      // The line width is in logical pixels, but the outline is in font units.
+      let line_width = self.line_width();
       self
         .translate(bounds.min_x(), bounds.min_y())
         .scale(scale, -scale)
         .translate(0., -unit)
-        .draw_path(path.into());
+        .set_line_width(line_width / scale)
+        .draw_path(path.into())
+        .set_line_width(line_width);
     } else if let Some(svg) = face.glyph_svg_image(g.glyph_id) {
       let grid_scale = face
         .vertical_height()
@@ -650,18 +681,21 @@ impl Painter {
         .translate(bounds.min_x(), bounds.min_y())
         .scale(scale, scale)
         .draw_svg(&svg);
-    } else if let Some(img) = face.glyph_raster_image(g.glyph_id, (unit / font_size) as u16) {
-      let m_width = img.width() as f32;
-      let m_height = img.height() as f32;
-      let scale = (bounds.width() / m_width).min(bounds.height() / m_height);
-
-      let x_offset = bounds.min_x() + (bounds.width() - (m_width * scale)) / 2.;
-      let y_offset = bounds.min_y() + (bounds.height() - (m_height * scale)) / 2.;
-
-      self
-        .translate(x_offset, y_offset)
-        .scale(scale, scale)
-        .draw_img(img, &Rect::from_size(Size::new(m_width, m_height)), &None);
+    } else {
+      // Pick the strike by the size in device pixels.
+      let device_scale = Vector::new(matrix.m11, matrix.m12).length();
+      let pixels_per_em = (font_size * device_scale).ceil() as u16;
+      if let Some(glyph) = face.glyph_raster_image(g.glyph_id, pixels_per_em) {
+        // Scale the strike to the font size, the baseline is at the bottom of the
+        // em box.
+        let scale = font_size / glyph.pixels_per_em as f32;
+        let img = glyph.image;
+        let height = img.height() as f32;
+        let x = bounds.min_x() + glyph.x as f32 * scale;
+        let y = bounds.min_y() + font_size - (glyph.y as f32 + height) * scale;
+        let rect = Rect::new(Point::new(x, y), Size::new(img.width() as f32, height) * scale);
+        self.draw_img(img, &rect, &None);
+      }
     }
 
     self.set_transform(matrix);
@@ -693,6 +727,49 @@ impl Painter {
     self
   }
 
+  /// Draw the text glyphs within the `box_rect` like
+  /// [`Painter::draw_glyphs_in_rect`], and paint the `shadows` and the `stroke`
+  /// beneath them.
+  ///
+  /// A blurred shadow is approximated by painting the glyphs several times
+  /// around its offset, each with a part of its opacity.
+  pub fn draw_glyphs_with_effects(
+    &mut self, visual_glyphs: &VisualGlyphs, box_rect: Rect, shadows: &[TextShadow],
+    stroke: Option<&TextStroke>, font_db: &FontDB,
+  ) -> &mut Self {
+    for shadow in shadows.iter().rev() {
+      let taps = blur_taps(shadow.blur);
+      // The opacity that the taps cover the center of the shadow.
+      let alpha = 1. - 0.2_f32.powf(1. / taps.len() as f32);
+      for tap in taps {
+        let offset = shadow.offset + tap;
+        self
+          .save()
+          .set_style(PathStyle::Fill)
+          .set_fill_brush(shadow.color)
+          .translate(offset.x, offset.y);
+        if shadow.blur > 0. {
+          self.apply_alpha(alpha);
+        }
+        self
+          .draw_glyphs_in_rect(visual_glyphs, box_rect, font_db)
+          .restore();
+      }
+    }
+
+    if let Some(stroke) = stroke {
+      self
+        .save()
+        .set_style(PathStyle::Stroke)
+        .set_stroke_brush(stroke.color)
+        .set_line_width(stroke.width)
+        .draw_glyphs_in_rect(visual_glyphs, stroke.ink_rect(&box_rect), font_db)
+        .restore();
+    }
+
+    self.draw_glyphs_in_rect(visual_glyphs, box_rect, font_db)
+  }
+
   fn inner_draw_path(&mut self, path: PaintPath, path_style: PathStyle) -> &mut Self {
     invisible_return!(self);
     let line_width = matches!(path_style, PathStyle::Stroke).then(|| self.line_width());
@@ -726,6 +803,110 @@ impl Painter {
   }
 }
 
+/// Paints the layers of a `COLR` glyph, the outlines are in font units.
+///
+/// The linear and radial gradients are supported, a sweep gradient is painted
+/// by the color of its first stop. The composite modes of the layers are not
+/// supported, the layers are painted over each other.
+struct ColorGlyphPainter<'a> {
+  painter: &'a mut Painter,
+  face: &'a Face,
+  outline: Option<Resource<Path>>,
+}
+
+impl ttf_parser::colr::Painter<'static> for ColorGlyphPainter<'_> {
+  fn outline_glyph(&mut self, glyph_id: ttf_parser::GlyphId) {
+    self.outline = self.face.outline_glyph(glyph_id);
+  }
+
+  fn paint(&mut self, paint: ttf_parser::colr::Paint<'static>) {
+    use ttf_parser::colr::{GradientExtend, Paint};
+
+    let Some(path) = self.outline.clone() else { return };
+    let coords = self.face.variation_coordinates();
+    let to_color = |c: ttf_parser::RgbaColor| Color::new(c.red, c.green, c.blue, c.alpha);
+    let spread = |extend| match extend {
+      GradientExtend::Pad => SpreadMethod::Pad,
+      GradientExtend::Repeat => SpreadMethod::Repeat,
+      GradientExtend::Reflect => SpreadMethod::Reflect,
+    };
+    let stops = |stops: ttf_parser::colr::GradientStopsIter| {
+      stops
+        .map(|s| GradientStop::new(to_color(s.color), s.stop_offset))
+        .collect::<Vec<_>>()
+    };
+    let brush: Brush = match paint {
+      Paint::Solid(color) => to_color(color).into(),
+      Paint::LinearGradient(g) => Brush::LinearGradient(LinearGradient {
+        start: Point::new(g.x0, g.y0),
+        end: Point::new(g.x1, g.y1),
+        stops: stops(g.stops(0, coords)),
+        spread_method: spread(g.extend),
+      }),
+      Paint::RadialGradient(g) => Brush::RadialGradient(RadialGradient {
+        start_center: Point::new(g.x0, g.y0),
+        start_radius: g.r0,
+        end_center: Point::new(g.x1, g.y1),
+        end_radius: g.r1,
+        stops: stops(g.stops(0, coords)),
+        spread_method: spread(g.extend),
+      }),
+      Paint::SweepGradient(g) => match g.stops(0, coords).next() {
+        Some(stop) => to_color(stop.color).into(),
+        None => return,
+      },
+    };
+    // A stroke outlines the glyph with the stroke brush, like an outline glyph.
+    if matches!(self.painter.style(), PathStyle::Fill) {
+      self.painter.set_fill_brush(brush);
+    }
+    self.painter.draw_path(path.into());
+  }
+
+  fn push_clip(&mut self) {
+    self.painter.save();
+    if let Some(path) = self.outline.clone() {
+      self.painter.clip(path.into());
+    }
+  }
+
+  fn push_clip_box(&mut self, clip: ttf_parser::colr::ClipBox) {
+    let rect = Rect::new(
+      Point::new(clip.x_min, clip.y_min),
+      Size::new(clip.x_max - clip.x_min, clip.y_max - clip.y_min),
+    );
+    self.painter.save().clip(Path::rect(&rect).into());
+  }
+
+  fn pop_clip(&mut self) { self.painter.restore(); }
+
+  fn push_layer(&mut self, _: ttf_parser::colr::CompositeMode) { self.painter.save(); }
+
+  fn pop_layer(&mut self) { self.painter.restore(); }
+
+  fn push_transform(&mut self, t: ttf_parser::Transform) {
+    let ts = Transform::new(t.a, t.b, t.c, t.d, t.e, t.f);
+    self.painter.save().apply_transform(&ts);
+  }
+
+  fn pop_transform(&mut self) { self.painter.restore(); }
+}
+
+/// The offsets to paint a shadow with the `blur` radius, the center and a ring
+/// around it.
+fn blur_taps(blur: f32) -> Vec<Vector> {
+  if blur <= 0. {
+    return vec![Vector::zero()];
+  }
+  let radius = blur / 2.;
+  std::iter::once(Vector::zero())
+    .chain((0..8).map(|i| {
+      let angle = Angle::degrees(45. * i as f32);
+      Vector::from_angle_and_length(angle, radius)
+    }))
+    .collect()
+}
+
 impl PaintingStyle {
   pub fn line_width(&self) -> Option<f32> {
     match self {
@@ -798,6 +979,11 @@ impl From<Brush> for CommandBrush {
   }
 }
 
+impl PainterResult<'_> {
+  /// Take the commands out to keep them after the painter is reset.
+  pub fn take(self) -> Vec<PaintCommand> { std::mem::take(self.0) }
+}
+
 impl Drop for PainterResult<'_> {
   fn drop(&mut self) { self.0.clear() }
 }
@@ -990,6 +1176,64 @@ mod test {
     assert_eq!(painter.commands.len(), 0);
   }
 
+  #[cfg(feature = "png")]
+  #[test]
+  fn draw_color_glyphs() {
+    use std::cell::RefCell;
+
+    use ribir_algo::Sc;
+
+    use crate::{font_db::GlyphBaseline, typography::PlaceLineDirection, *};
+
+    let font_db = Sc::new(RefCell::new(FontDB::default()));
+    let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../fonts/RibirTestEmoji.ttf";
+    font_db.borrow_mut().load_font_file(path).unwrap();
+    let mut store = TypographyStore::new(font_db.clone());
+    let style = TextStyle {
+      font_size: 16.,
+      line_height: 16.,
+      font_face: FontFace {
+        families: Box::new([FontFamily::Name("Ribir Test Emoji".into())]),
+        ..<_>::default()
+      },
+      ..<_>::default()
+    };
+    let glyphs = store.typography(
+      "\u{2764}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}".into(),
+      &style,
+      Size::new(200., 50.),
+      TextAlign::Start,
+      GlyphBaseline::Alphabetic,
+      PlaceLineDirection::TopToBottom,
+    );
+
+    let mut painter = painter();
+    painter
+      .set_fill_brush(Color::BLACK)
+      .draw_glyphs_in_rect(&glyphs, Rect::from_size(Size::new(200., 50.)), &font_db.borrow());
+    let brushes = painter
+      .finish()
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand {
+          action: PaintPathAction::Paint { brush, .. }, ..
+        }) => Some(brush.clone()),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+
+    // The heart is a layer of the palette color and a layer of the foreground.
+    assert!(matches!(brushes[0], CommandBrush::Color(c) if c == Color::from_rgb(220, 40, 40)));
+    assert!(matches!(brushes[1], CommandBrush::Color(c) if c == Color::BLACK));
+    // The family are bitmaps, they're painted by the images in the color atlas.
+    assert_eq!(brushes.len(), 5);
+    assert!(
+      brushes[2..]
+        .iter()
+        .all(|b| matches!(b, CommandBrush::Image { img, .. } if img.width() == 32))
+    );
+  }
+
   #[test]
   fn draw_svg_gradient() {
     let mut painter = Painter::new(Rect::from_size(Size::new(64., 64.)));
@@ -6,8 +6,8 @@ use serde::{Deserialize, Serialize};
 use usvg::{Options, Stop, Tree};
 
 use crate::{
-  Brush, Color, CommandBrush, GradientStop, LineCap, LineJoin, PaintCommand, PaintPathAction, Path,
-  StrokeOptions,
+  AssetId, Brush, Color, CommandBrush, GradientStop, LineCap, LineJoin, PaintCommand,
+  PaintPathAction, Path, StrokeOptions,
   color::{LinearGradient, RadialGradient},
 };
 
@@ -78,6 +78,18 @@ impl Svg {
     })
   }
 
+  /// Parse SVG from an asset embedded by `include_asset!`.
+  ///
+  /// - **inherit_fill**: Indicates whether this SVG will inherit the fill color
+  ///   from the environment.
+  /// - **inherit_stroke**: Indicates whether this SVG will inherit the stroke
+  ///   color from the environment.
+  pub fn from_asset(
+    asset: AssetId, inherit_fill: bool, inherit_stroke: bool,
+  ) -> Result<Self, Box<dyn Error>> {
+    Self::parse_from_bytes(&asset.bytes(), inherit_fill, inherit_stroke)
+  }
+
   /// Parse SVG from a file.
   ///
   /// - **inherit_fill**: Indicates whether this SVG will inherit the fill color
@@ -4,14 +4,16 @@
 //! Some detail processing learn from [usvg](https://github.com/RazrFalcon/resvg/blob/master/usvg/src/text)
 pub mod font_db;
 pub mod shaper;
-use std::hash::Hash;
+use std::{hash::Hash, rc::Rc};
 
 use derive_more::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 use font_db::Face;
 pub use fontdb::{ID, Stretch as FontStretch, Style as FontStyle, Weight as FontWeight};
 pub use ribir_algo::Substr;
-use ribir_geom::{Rect, rect};
+use ribir_geom::{Rect, Vector, rect};
 use rustybuzz::{GlyphPosition, ttf_parser::GlyphId};
+
+use crate::Color;
 pub mod text_reorder;
 pub mod typography;
 pub use text_reorder::TextReorder;
@@ -93,6 +95,52 @@ pub struct TextStyle {
   pub line_height: f32,
   /// How to handle the visual overflow.
   pub overflow: TextOverflow,
+  /// The shadows painted beneath the text, the first one is on the top. They
+  /// don't change the layout size of the text.
+  pub shadows: Vec<TextShadow>,
+  /// The outline of the glyphs, painted beneath the fill of the text. It
+  /// doesn't change the layout size of the text.
+  pub stroke: Option<TextStroke>,
+  /// Breaks the word that overflows the line with a hyphen, only used when the
+  /// text is auto wrapped.
+  pub hyphenator: Option<Hyphenator>,
+}
+
+/// A shadow of the text, see [`TextStyle::shadows`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextShadow {
+  /// The offset of the shadow from the text in logical pixels.
+  pub offset: Vector,
+  /// The blur radius of the shadow in logical pixels, `0.` for a sharp
+  /// shadow.
+  pub blur: f32,
+  pub color: Color,
+}
+
+/// The outline of the text, see [`TextStyle::stroke`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextStroke {
+  /// The width of the outline in logical pixels, half of it is covered by the
+  /// fill of the glyphs.
+  pub width: f32,
+  pub color: Color,
+}
+
+impl TextShadow {
+  /// The rect the shadow of the text in `rect` may paint.
+  pub fn ink_rect(&self, rect: &Rect) -> Rect {
+    rect
+      .translate(self.offset)
+      .inflate(self.blur, self.blur)
+  }
+}
+
+impl TextStroke {
+  /// The rect the outline of the text in `rect` may paint.
+  pub fn ink_rect(&self, rect: &Rect) -> Rect {
+    let half = self.width / 2.;
+    rect.inflate(half, half)
+  }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
@@ -145,16 +193,149 @@ impl Default for FontFace {
   }
 }
 
-/// Text-align relative to the horizontal or vertical, not caring about whether
-/// the text is left-to-right or right-to-left, In the horizontal the left is
-/// the start, and in vertical the top is the start.
+/// How the lines of a text are aligned in the inline direction.
+///
+/// The `Start` and `End` are resolved against the direction of each paragraph,
+/// the start of a right-to-left paragraph is the right. The `Left` and `Right`
+/// are not, and in the vertical text the top is the left.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum TextAlign {
   Start,
   Center,
   End,
+  Left,
+  Right,
+  /// Stretch the lines to fill the width by widening the gaps between words,
+  /// except the last line of a paragraph, which is aligned to the start.
+  Justify,
+}
+
+impl TextAlign {
+  /// Resolves `Start` and `End` to `Left` or `Right` for a paragraph of the
+  /// direction, other alignments are kept.
+  pub fn resolve(self, rtl: bool) -> TextAlign {
+    match (self, rtl) {
+      (TextAlign::Start, false) | (TextAlign::End, true) => TextAlign::Left,
+      (TextAlign::Start, true) | (TextAlign::End, false) => TextAlign::Right,
+      (align, _) => align,
+    }
+  }
+}
+
+/// Finds the positions to break a word with a hyphen, when the word overflows
+/// the line, see [`TextStyle::hyphenator`].
+///
+/// It returns the byte offsets in the word where a line can break.
+#[derive(Clone)]
+pub struct Hyphenator(Rc<BreaksFn>);
+
+type BreaksFn = dyn Fn(&str) -> Vec<usize>;
+
+impl Hyphenator {
+  pub fn new(f: impl Fn(&str) -> Vec<usize> + 'static) -> Self { Self(Rc::new(f)) }
+
+  /// The byte offsets in the `word` where a line can break.
+  pub fn breaks(&self, word: &str) -> Vec<usize> { (self.0)(word) }
+
+  /// A built-in hyphenator for English.
+  ///
+  /// It's a rule-based approximation of the English syllables rather than a
+  /// dictionary, it breaks between the consonants that split the vowels, and
+  /// keeps at least two letters before and three letters after the break.
+  #[cfg(feature = "hyphenation")]
+  pub fn english() -> Self { Self::new(english_breaks) }
+}
+
+impl<F: Fn(&str) -> Vec<usize> + 'static> From<F> for Hyphenator {
+  fn from(f: F) -> Self { Self::new(f) }
 }
 
+impl PartialEq for Hyphenator {
+  fn eq(&self, other: &Self) -> bool { Rc::ptr_eq(&self.0, &other.0) }
+}
+
+impl Eq for Hyphenator {}
+
+impl Hash for Hyphenator {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    (Rc::as_ptr(&self.0) as *const ()).hash(state)
+  }
+}
+
+impl std::fmt::Debug for Hyphenator {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_tuple("Hyphenator").finish()
+  }
+}
+
+#[cfg(feature = "hyphenation")]
+fn english_breaks(word: &str) -> Vec<usize> {
+  const MIN_BEFORE: usize = 2;
+  const MIN_AFTER: usize = 3;
+  // The consonant pairs that are pronounced as one and not broken.
+  const DIGRAPHS: [&str; 9] = ["ch", "ck", "gh", "ng", "ph", "qu", "sh", "th", "wh"];
+  // The consonant pairs that begin a syllable together.
+  const BLENDS: [&str; 17] = [
+    "bl", "br", "cl", "cr", "dr", "fl", "fr", "gl", "gr", "pl", "pr", "sc", "sp", "st", "tr", "tw",
+    "wr",
+  ];
+
+  let chars: Vec<(usize, char)> = word.char_indices().collect();
+  if chars.len() < MIN_BEFORE + MIN_AFTER || !chars.iter().all(|(_, c)| c.is_ascii_alphabetic()) {
+    return vec![];
+  }
+  let lower: Vec<char> = chars
+    .iter()
+    .map(|(_, c)| c.to_ascii_lowercase())
+    .collect();
+  let is_vowel = |i: usize| match lower[i] {
+    'a' | 'e' | 'i' | 'o' | 'u' => true,
+    // The `y` is a vowel unless it begins a syllable.
+    'y' => i > 0 && !is_vowel_char(lower[i - 1]),
+    _ => false,
+  };
+  let pair = |i: usize| lower[i..i + 2].iter().collect::<String>();
+  let digraph = |i: usize| DIGRAPHS.contains(&pair(i).as_str());
+  let onset = |i: usize| digraph(i) || BLENDS.contains(&pair(i).as_str());
+
+  let mut breaks = vec![];
+  let mut i = 0;
+  while i < lower.len() {
+    if !is_vowel(i) {
+      i += 1;
+      continue;
+    }
+    // The consonants between this vowel and the next one.
+    let start = i + 1;
+    let mut end = start;
+    while end < lower.len() && !is_vowel(end) {
+      end += 1;
+    }
+    if end == lower.len() {
+      break;
+    }
+    let at = match end - start {
+      // The adjacent vowels are mostly one syllable.
+      0 => None,
+      // Break before the single consonant.
+      1 => Some(start),
+      // Keep the pair that begins the next syllable together.
+      _ if onset(end - 2) => Some(end - 2),
+      // Keep the digraph after this vowel together.
+      _ if digraph(start) => Some(start + 2),
+      n => Some(start + n / 2),
+    };
+    if let Some(at) = at.filter(|at| MIN_BEFORE <= *at && at + MIN_AFTER <= lower.len()) {
+      breaks.push(chars[at].0);
+    }
+    i = end;
+  }
+  breaks
+}
+
+#[cfg(feature = "hyphenation")]
+fn is_vowel_char(c: char) -> bool { matches!(c, 'a' | 'e' | 'i' | 'o' | 'u') }
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TextDirection {
   /// Text is set horizontally from left to right.
@@ -263,6 +444,9 @@ impl Default for TextStyle {
       letter_space: 0.,
       line_height: 16.,
       overflow: <_>::default(),
+      shadows: vec![],
+      stroke: None,
+      hyphenator: None,
     }
   }
 }
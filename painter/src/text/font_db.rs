@@ -5,18 +5,30 @@ use fontdb::{Database, Query};
 pub use fontdb::{FaceInfo, Family, ID};
 use ribir_algo::{Resource, Sc};
 use ribir_geom::{Point, Rect, rect};
-use rustybuzz::ttf_parser::{GlyphId, OutlineBuilder};
+use rustybuzz::ttf_parser::{GlyphId, OutlineBuilder, RasterImageFormat};
 
 use crate::{
-  Path, PixelImage, Svg,
+  AssetId, Path, PixelImage, Svg,
+  image::ColorFormat,
   path_builder::PathBuilder,
   text::{FontFace, FontFamily, svg_glyph_cache::SvgGlyphCache},
 };
 /// A wrapper of fontdb and cache font data.
 pub struct FontDB {
   default_fonts: Vec<ID>,
+  emoji_fonts: Vec<ID>,
   data_base: fontdb::Database,
   cache: HashMap<ID, Option<Face>>,
+  system_fonts: SystemFonts,
+}
+
+/// Whether the system fonts are loaded to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SystemFonts {
+  NotLoaded,
+  /// Load the system fonts at the first font that the loaded fonts can't match.
+  OnDemand,
+  Loaded,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -37,7 +49,7 @@ pub struct Face {
   pub source_data: Arc<dyn AsRef<[u8]> + Sync + Send>,
   pub face_data_index: u32,
   pub rb_face: rustybuzz::Face<'static>,
-  raster_image_glyphs: FontGlyphCache<GlyphId, Resource<PixelImage>>,
+  raster_image_glyphs: FontGlyphCache<(GlyphId, u16), RasterGlyph>,
   outline_glyphs: FontGlyphCache<GlyphId, Resource<Path>>,
   svg_glyphs: Sc<RefCell<SvgGlyphCache>>,
   x_height: u16,
@@ -57,6 +69,17 @@ impl FontDB {
 
   pub fn default_fonts(&self) -> &[ID] { &self.default_fonts }
 
+  /// Sets the emoji fonts for the entire application.
+  ///
+  /// When a text falls back to other fonts, an emoji is shaped by these fonts
+  /// before the others, so it's displayed in color rather than a monochrome
+  /// outline.
+  pub fn set_emoji_fonts(&mut self, face: &FontFace) {
+    self.emoji_fonts = self.select_all_match(face);
+  }
+
+  pub fn emoji_fonts(&self) -> &[ID] { &self.emoji_fonts }
+
   pub fn try_get_face_data(&self, face_id: ID) -> Option<&Face> {
     self.cache.get(&face_id)?.as_ref()
   }
@@ -89,6 +112,9 @@ impl FontDB {
   #[inline]
   pub fn load_from_bytes(&mut self, data: Vec<u8>) { self.data_base.load_font_data(data); }
 
+  /// Load the fonts of an asset embedded by `include_asset!`.
+  pub fn load_asset(&mut self, asset: AssetId) { self.load_from_bytes(asset.bytes().into_owned()); }
+
   /// Loads a font file into the `Database`.
   ///
   /// Will load all font faces in case of a font collection.
@@ -110,10 +136,23 @@ impl FontDB {
   /// Which means that fonts that are not in those directories must
   /// be added manually.
   pub fn load_system_fonts(&mut self) {
+    self.system_fonts = SystemFonts::Loaded;
     self.data_base.load_system_fonts();
     self.static_generic_families();
   }
 
+  /// Defer loading the system fonts until a font is queried that the loaded
+  /// fonts can't match, scanning the system fonts is slow and not needed by
+  /// an application that brings its fonts.
+  pub fn load_system_fonts_on_demand(&mut self) {
+    if self.system_fonts == SystemFonts::NotLoaded {
+      self.system_fonts = SystemFonts::OnDemand;
+    }
+  }
+
+  /// Whether the system fonts are loaded.
+  pub fn is_system_fonts_loaded(&self) -> bool { self.system_fonts == SystemFonts::Loaded }
+
   /// Performs a CSS-like query and returns the best matched font face id.
   pub fn select_best_match(&self, face: &FontFace) -> Option<ID> {
     let FontFace { families, stretch, style, weight } = face;
@@ -130,22 +169,52 @@ impl FontDB {
   }
 
   /// Performs a CSS-like query and returns the all matched font face ids
+  ///
+  /// If the system fonts load on demand, they're loaded when the first family
+  /// can't be matched. The other families are fallbacks, see
+  /// [`FontDB::load_system_fonts_for`].
   pub fn select_all_match(&mut self, face: &FontFace) -> Vec<ID> {
     let FontFace { families, stretch, style, weight } = face;
     families
       .iter()
-      .filter_map(|f| {
-        let id = self.data_base.query(&Query {
-          families: &[to_db_family(f)],
-          weight: *weight,
-          stretch: *stretch,
-          style: *style,
+      .enumerate()
+      .filter_map(|(idx, f)| {
+        let query =
+          Query { families: &[to_db_family(f)], weight: *weight, stretch: *stretch, style: *style };
+        let id = self.data_base.query(&query).or_else(|| {
+          if idx > 0 || self.system_fonts != SystemFonts::OnDemand {
+            return None;
+          }
+          self.load_system_fonts();
+          self.data_base.query(&query)
         })?;
         self.face_data_or_insert(id).map(|_| id)
       })
       .collect()
   }
 
+  /// Load the system fonts on demand if the faces of `ids` and the default
+  /// fonts can't show some chars of the `text`. Return if they're loaded, then
+  /// the faces should be selected again.
+  pub fn load_system_fonts_for(&mut self, ids: &[ID], text: &str) -> bool {
+    if self.system_fonts != SystemFonts::OnDemand {
+      return false;
+    }
+    let faces = ids
+      .iter()
+      .chain(&self.default_fonts)
+      .filter_map(|id| self.try_get_face_data(*id))
+      .collect::<Vec<_>>();
+    let missing = text
+      .chars()
+      .filter(|c| !c.is_control() && !c.is_whitespace())
+      .any(|c| !faces.iter().any(|f| f.has_char(c)));
+    if missing {
+      self.load_system_fonts();
+    }
+    missing
+  }
+
   fn static_generic_families(&mut self) {
     // We don't like to depends on some system library and not make the fallback
     // font too complicated. So here are some default fonts collect from web.
@@ -259,6 +328,20 @@ impl FontDB {
         set_fn(&mut self.data_base, name);
       }
     });
+
+    let emoji_families = [
+      #[cfg(any(target_os = "macos", target_os = "ios"))]
+      FontFamily::Name("Apple Color Emoji".into()),
+      #[cfg(target_os = "windows")]
+      FontFamily::Name("Segoe UI Emoji".into()),
+      #[cfg(any(target_os = "linux", target_os = "android"))]
+      FontFamily::Name("Noto Color Emoji".into()),
+      #[cfg(target_os = "linux")]
+      FontFamily::Name("Twemoji".into()),
+    ];
+    if self.emoji_fonts.is_empty() {
+      self.set_emoji_fonts(&FontFace { families: emoji_families.into(), ..<_>::default() });
+    }
   }
 }
 
@@ -267,7 +350,13 @@ impl Default for FontDB {
     let mut data_base = fontdb::Database::new();
     data_base.load_font_data(include_bytes!("./Lato-Regular.ttf").to_vec());
     let default_font = data_base.faces().next().map(|f| f.id).unwrap();
-    let mut this = FontDB { default_fonts: vec![default_font], data_base, cache: <_>::default() };
+    let mut this = FontDB {
+      default_fonts: vec![default_font],
+      emoji_fonts: vec![],
+      data_base,
+      cache: <_>::default(),
+      system_fonts: SystemFonts::NotLoaded,
+    };
     this.face_data_or_insert(default_font);
     this
   }
@@ -355,24 +444,43 @@ impl Face {
       .cloned()
   }
 
-  pub fn glyph_raster_image(
-    &self, glyph_id: GlyphId, pixels_per_em: u16,
-  ) -> Option<Resource<PixelImage>> {
+  /// Returns the bitmap of the glyph in the strike that best matches the
+  /// `pixels_per_em`, it's the color glyph of the `CBDT` or `sbix` table.
+  pub fn glyph_raster_image(&self, glyph_id: GlyphId, pixels_per_em: u16) -> Option<RasterGlyph> {
+    let img = self
+      .rb_face
+      .glyph_raster_image(glyph_id, pixels_per_em)?;
     self
       .raster_image_glyphs
       .borrow_mut()
-      .entry(glyph_id)
+      .entry((glyph_id, img.pixels_per_em))
       .or_insert_with(|| {
-        self
-          .rb_face
-          .glyph_raster_image(glyph_id, pixels_per_em)
-          .and_then(|img| match img.format {
-            #[cfg(feature = "png")]
-            rustybuzz::ttf_parser::RasterImageFormat::PNG => {
-              Some(Resource::new(PixelImage::from_png(img.data)))
-            }
-            _ => None,
-          })
+        let image = match img.format {
+          #[cfg(feature = "png")]
+          RasterImageFormat::PNG => PixelImage::from_png(img.data),
+          RasterImageFormat::BitmapPremulBgra32 => {
+            let data = img
+              .data
+              .chunks_exact(4)
+              .flat_map(|c| {
+                let unpremul = |v: u8| match c[3] {
+                  0 => 0,
+                  a => (v as u32 * 255 / a as u32).min(255) as u8,
+                };
+                [unpremul(c[2]), unpremul(c[1]), unpremul(c[0]), c[3]]
+              })
+              .collect::<Vec<_>>();
+            let (width, height) = (img.width as u32, img.height as u32);
+            PixelImage::new(data.into(), width, height, ColorFormat::Rgba8)
+          }
+          _ => return None,
+        };
+        Some(RasterGlyph {
+          image: Resource::new(image),
+          x: img.x,
+          y: img.y,
+          pixels_per_em: img.pixels_per_em,
+        })
       })
       .clone()
   }
@@ -400,6 +508,18 @@ fn to_db_family(f: &FontFamily) -> Family {
   }
 }
 
+/// A bitmap glyph of a strike, the offsets and the size of the image are in
+/// the pixels of the strike.
+#[derive(Clone)]
+pub struct RasterGlyph {
+  pub image: Resource<PixelImage>,
+  /// The horizontal offset from the glyph origin to the left of the image.
+  pub x: i16,
+  /// The vertical offset from the baseline to the bottom of the image.
+  pub y: i16,
+  pub pixels_per_em: u16,
+}
+
 #[derive(Default)]
 struct GlyphOutlineBuilder {
   builder: PathBuilder,
@@ -549,6 +669,37 @@ mod tests {
     assert!(db.faces_info_iter().next().is_some())
   }
 
+  #[test]
+  fn load_sys_fonts_at_missing_font() {
+    let mut db = FontDB::default();
+    db.load_system_fonts_on_demand();
+    let lato = FontFace { families: Box::new([FontFamily::Name("Lato".into())]), ..<_>::default() };
+    assert_eq!(db.select_all_match(&lato).len(), 1);
+    assert!(!db.is_system_fonts_loaded());
+
+    let missing =
+      FontFace { families: Box::new([FontFamily::Name("Not A Font".into())]), ..<_>::default() };
+    assert!(db.select_all_match(&missing).is_empty());
+    assert!(db.is_system_fonts_loaded());
+  }
+
+  #[test]
+  fn load_sys_fonts_at_missing_char() {
+    let mut db = FontDB::default();
+    db.load_system_fonts_on_demand();
+    let face = FontFace {
+      families: Box::new([FontFamily::Name("Lato".into()), FontFamily::Serif]),
+      ..<_>::default()
+    };
+    let ids = db.select_all_match(&face);
+    assert_eq!(ids.len(), 1);
+    assert!(!db.load_system_fonts_for(&ids, "Hello world"));
+    assert!(!db.is_system_fonts_loaded());
+
+    assert!(db.load_system_fonts_for(&ids, "你好"));
+    assert!(db.is_system_fonts_loaded());
+  }
+
   #[test]
   fn match_font() {
     let mut fonts = FontDB::default();
@@ -94,7 +94,7 @@ impl TextShaper {
       if new_part.is_empty() {
         break;
       }
-      let miss_part = collect_miss_part(&glyphs, &new_part);
+      let miss_part = collect_miss_part(text, &glyphs, &new_part);
       (buffer, new_part) = regen_miss_part(text, dir, baseline, &mut glyphs, miss_part, buffer);
     }
 
@@ -141,8 +141,10 @@ impl TextShaper {
   pub fn font_db(&self) -> &Sc<RefCell<FontDB>> { &self.font_db }
 }
 
+/// Collect the parts of the glyphs that should be shaped by the next fallback
+/// face, the missing glyphs and the emoji not shaped by an emoji font.
 fn collect_miss_part<'a>(
-  glyphs: &[Glyph], new_part: &[(usize, usize, FallBackFaceHelper<'a>)],
+  text: &str, glyphs: &[Glyph], new_part: &[(usize, usize, FallBackFaceHelper<'a>)],
 ) -> Vec<(usize, usize, FallBackFaceHelper<'a>)> {
   let mut miss_parts = vec![];
   for (start, end, helper) in new_part {
@@ -153,7 +155,7 @@ fn collect_miss_part<'a>(
       .enumerate()
       .map(|(idx, glyph)| (idx + *start, glyph))
       .for_each(|(idx, glyph)| {
-        if glyph.is_miss() {
+        if glyph.is_miss() || helper.prefer_emoji_face(text, glyph) {
           if miss_start.is_none() {
             miss_start = Some(idx);
           }
@@ -208,7 +210,10 @@ fn regen_miss_part<'a>(
       false => start_byte..end_byte,
     };
     let miss_text = &text[miss_range.clone()];
-    if let Some(face) = helper.next_fallback_face(miss_text) {
+    let face = helper
+      .emoji_face(miss_text)
+      .or_else(|| helper.next_fallback_face(miss_text));
+    if let Some(face) = face {
       buffer.push_str(miss_text);
       buffer.set_direction(hb_direction);
       let mut res = TextShaper::directly_shape(buffer, baseline, dir.is_horizontal(), &face);
@@ -292,6 +297,8 @@ struct FallBackFaceHelper<'a> {
   ids: Vec<ID>,
   font_db: &'a RefCell<FontDB>,
   face_idx: usize,
+  /// Whether the emoji fonts have been tried by this fallback.
+  emoji_tried: bool,
 }
 
 impl<'a> FallBackFaceHelper<'a> {
@@ -310,7 +317,47 @@ impl<'a> FallBackFaceHelper<'a> {
       }
     }
 
-    Self { ids, font_db, face_idx: 0 }
+    Self { ids, font_db, face_idx: 0, emoji_tried: false }
+  }
+
+  /// The emoji font to shape the `text` if it begins with an emoji, and the
+  /// emoji fonts have not been tried yet.
+  fn emoji_face(&mut self, text: &str) -> Option<Face> {
+    if self.emoji_tried {
+      return None;
+    }
+    let c = text.chars().next().filter(|c| is_emoji(*c))?;
+    let font_db = self.font_db.borrow();
+    let face = font_db
+      .emoji_fonts()
+      .iter()
+      .filter_map(|id| font_db.try_get_face_data(*id))
+      .find(|f| f.has_char(c))
+      .cloned()?;
+    self.emoji_tried = true;
+    Some(face)
+  }
+
+  /// Whether the `glyph` is an emoji that should be shaped by an emoji font
+  /// rather than its current face.
+  fn prefer_emoji_face(&self, text: &str, glyph: &Glyph) -> bool {
+    if self.emoji_tried {
+      return false;
+    }
+    let Some(c) = text
+      .get(glyph.cluster as usize..)
+      .and_then(|t| t.chars().next())
+      .filter(|c| is_emoji(*c))
+    else {
+      return false;
+    };
+    let font_db = self.font_db.borrow();
+    let emoji_fonts = font_db.emoji_fonts();
+    !emoji_fonts.contains(&glyph.face_id)
+      && emoji_fonts
+        .iter()
+        .filter_map(|id| font_db.try_get_face_data(*id))
+        .any(|f| f.has_char(c))
   }
 
   fn next_fallback_face(&mut self, text: &str) -> Option<Face> {
@@ -342,6 +389,12 @@ impl<'a> FallBackFaceHelper<'a> {
   }
 }
 
+/// Whether the char is displayed as an emoji by default, the pictographs,
+/// symbols and flags.
+fn is_emoji(c: char) -> bool {
+  matches!(c as u32, 0x231A..=0x23FF | 0x2600..=0x27BF | 0x2B05..=0x2B55 | 0x1F000..=0x1FAFF)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -461,6 +514,42 @@ mod tests {
     assert_eq!(&clusters, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 16, 19, 22, 25]);
   }
 
+  #[test]
+  fn emoji_prefer_emoji_font() {
+    let mut shaper = TextShaper::new(<_>::default());
+    let path = env!("CARGO_MANIFEST_DIR").to_owned();
+    let mut db = shaper.font_db.borrow_mut();
+    let _ = db.load_font_file(path.clone() + "/../fonts/DejaVuSans.ttf");
+    let _ = db.load_font_file(path + "/../fonts/RibirTestEmoji.ttf");
+    let ids = db.select_all_match(&FontFace {
+      families: Box::new([FontFamily::Name("DejaVu Sans".into())]),
+      ..<_>::default()
+    });
+    db.set_emoji_fonts(&FontFace {
+      families: Box::new([FontFamily::Name("Ribir Test Emoji".into())]),
+      ..<_>::default()
+    });
+    let emoji_id = db.emoji_fonts()[0];
+    drop(db);
+
+    // DejaVu Sans has a monochrome heart, but the emoji font is preferred.
+    let text = "a\u{2764}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}b";
+    let res =
+      shaper.shape_text(&text.into(), &ids, TextDirection::LeftToRight, GlyphBaseline::Alphabetic);
+    let glyphs = &res.glyphs;
+    // The ZWJs are removed, since the emoji font has no space glyph for them.
+    assert_eq!(glyphs.len(), 6);
+    assert!(glyphs.iter().all(|g| g.is_not_miss()));
+    assert!(glyphs[1..5].iter().all(|g| g.face_id == emoji_id));
+    assert!(glyphs[0].face_id != emoji_id && glyphs[5].face_id != emoji_id);
+    // The ZWJ sequence is one cluster.
+    let clusters = glyphs
+      .iter()
+      .map(|g| g.cluster)
+      .collect::<Vec<_>>();
+    assert_eq!(clusters, [0, 1, 4, 4, 4, 22]);
+  }
+
   #[test]
   fn shape_miss_font() {
     let mut shaper = TextShaper::new(<_>::default());
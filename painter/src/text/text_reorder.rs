@@ -7,6 +7,8 @@ pub struct Paragraph {
   pub levels: Vec<Level>,
   pub runs: Vec<LevelRun>,
   pub range: Range<usize>,
+  /// If the base direction of the paragraph is right-to-left.
+  pub is_rtl: bool,
 }
 pub struct ReorderResult {
   pub original_classes: Vec<BidiClass>,
@@ -32,7 +34,7 @@ impl TextReorder {
         .iter()
         .map(|p| {
           let (levels, runs) = info.visual_runs(p, p.range.clone());
-          Paragraph { levels, runs, range: p.range.clone() }
+          Paragraph { levels, runs, range: p.range.clone(), is_rtl: p.level.is_rtl() }
         })
         .collect();
 
@@ -41,6 +43,7 @@ impl TextReorder {
           levels: vec![],
           runs: vec![Range { start: text.len(), end: text.len() }],
           range: Range { start: text.len(), end: text.len() },
+          is_rtl: false,
         })
       }
 
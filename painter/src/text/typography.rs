@@ -6,7 +6,7 @@ use smallvec::{SmallVec, smallvec};
 use unicode_script::{Script, UnicodeScript};
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{Glyph, GlyphUnit, TextAlign, TextOverflow, shaper::ShapeResult};
+use crate::{Glyph, GlyphUnit, Hyphenator, TextAlign, TextOverflow, shaper::ShapeResult};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PlaceLineDirection {
@@ -50,6 +50,8 @@ pub struct VisualLine {
 
 pub struct VisualInfos {
   pub visual_lines: SmallVec<[VisualLine; 1]>,
+  /// The alignment of the whole text in the bounds, it's resolved to `Left`,
+  /// `Center` or `Right`.
   pub text_align: TextAlign,
   /// if the typography result over the bounds provide by caller.
   pub over_bounds: bool,
@@ -71,12 +73,27 @@ pub struct TypographyMan<Paras> {
   inputs: Paras,
   inline_cursor: GlyphUnit,
   visual_lines: SmallVec<[VisualLine; 1]>,
+  /// The resolved alignment of each visual line.
+  line_aligns: Vec<TextAlign>,
+  /// The index of the whitespace glyphs in the current line, which are widened
+  /// to justify the line.
+  line_gaps: Vec<usize>,
+  para_rtl: bool,
+  hyphenator: Option<Hyphenator>,
   over_bounds: bool,
 }
 
+/// A paragraph to typography, its runs are in the visual order.
+pub struct InputParagraph {
+  pub runs: SmallVec<[InputRun; 1]>,
+  /// If the base direction of the paragraph is right-to-left, the `Start` and
+  /// `End` alignments are resolved by it.
+  pub is_rtl: bool,
+}
+
 impl<Paras> TypographyMan<Paras>
 where
-  Paras: DoubleEndedIterator<Item = SmallVec<[InputRun; 1]>>,
+  Paras: DoubleEndedIterator<Item = InputParagraph>,
 {
   pub fn new(
     inputs: Paras, line_dir: PlaceLineDirection, text_align: TextAlign, line_height: GlyphUnit,
@@ -91,17 +108,31 @@ where
       inputs,
       inline_cursor: GlyphUnit::ZERO,
       visual_lines: smallvec![],
+      line_aligns: vec![],
+      line_gaps: vec![],
+      para_rtl: false,
+      hyphenator: None,
       over_bounds: false,
     }
   }
 
+  /// Break the word that overflows the line by the `hyphenator`, the runs
+  /// should have the hyphen glyph, see [`InputRun::with_hyphen`].
+  pub fn with_hyphenator(mut self, hyphenator: Option<Hyphenator>) -> Self {
+    self.hyphenator = hyphenator;
+    self
+  }
+
   pub fn typography_all(mut self) -> VisualInfos {
+    let mut text_align = None;
     while let Some(p) = self.inputs.next() {
+      text_align.get_or_insert(self.para_align(p.is_rtl));
       self.consume_paragraph(p);
     }
 
     if self.line_dir.is_reverse() {
       self.visual_lines.reverse();
+      self.line_aligns.reverse();
     }
 
     let (visual_width, visual_height) = self.visual_size();
@@ -110,7 +141,7 @@ where
     VisualInfos {
       visual_width,
       visual_height,
-      text_align: self.text_align,
+      text_align: text_align.unwrap_or(TextAlign::Left),
       visual_lines: self.visual_lines,
       over_bounds: self.over_bounds,
       line_dir: self.line_dir,
@@ -118,7 +149,6 @@ where
   }
 
   fn adjust_lines(&mut self, visual_width: GlyphUnit, visual_height: GlyphUnit) {
-    let text_align = self.text_align;
     let lines = self.visual_lines.iter_mut();
     match self.line_dir {
       PlaceLineDirection::LeftToRight => {
@@ -147,11 +177,15 @@ where
       }
     };
 
-    self.visual_lines.iter_mut().for_each(|l| {
+    let lines = self
+      .visual_lines
+      .iter_mut()
+      .zip(&self.line_aligns);
+    lines.for_each(|(l, align)| {
       if self.line_dir.is_horizontal() {
-        l.y += text_align_offset(l.height, visual_height, text_align);
+        l.y += text_align_offset(l.height, visual_height, *align);
       } else {
-        l.x += text_align_offset(l.width, visual_width, text_align);
+        l.x += text_align_offset(l.width, visual_width, *align);
       }
     });
   }
@@ -174,8 +208,19 @@ where
     (width, height)
   }
 
+  /// The alignment of the lines in the paragraph, the last line of the
+  /// justified paragraph is aligned to the start.
+  fn para_align(&self, rtl: bool) -> TextAlign {
+    match self.text_align {
+      TextAlign::Justify => TextAlign::Start.resolve(rtl),
+      align => align.resolve(rtl),
+    }
+  }
+
   /// consume paragraph and return if early break because over boundary.
-  fn consume_paragraph(&mut self, runs: SmallVec<[InputRun; 1]>) -> bool {
+  fn consume_paragraph(&mut self, p: InputParagraph) -> bool {
+    let InputParagraph { runs, is_rtl } = p;
+    self.para_rtl = is_rtl;
     self.begin_line();
 
     if self.line_dir.is_horizontal() {
@@ -215,35 +260,54 @@ where
 
     let new_line = |this: &mut Self, cursor: &mut dyn InlineCursor| {
       this.end_line();
+      if this.text_align == TextAlign::Justify {
+        this.justify_last_line();
+      }
       this.begin_line();
       cursor.reset();
     };
+    let push_glyph = |this: &mut Self, cursor: &mut dyn InlineCursor, g: &Glyph| {
+      let mut at = g.clone();
+      cursor.advance_glyph(&mut at, line_offset, text);
+      this.push_glyph(at, text, base);
+      this.inline_cursor = cursor.position();
+    };
 
     for word in run.word_glyphs() {
-      let width: GlyphUnit = word
-        .clone()
-        .fold(GlyphUnit::ZERO, |acc, g| acc + cursor.measure(&g, text));
-
-      if is_auto_wrap
-        && self.inline_cursor != GlyphUnit::ZERO
-        && self.is_over_line_bound(width + self.inline_cursor)
-      {
-        new_line(self, cursor);
+      let word: SmallVec<[Glyph; 8]> = word.collect();
+      let mut word = &word[..];
+
+      while is_auto_wrap && !word.is_empty() {
+        let width: GlyphUnit = word
+          .iter()
+          .fold(GlyphUnit::ZERO, |acc, g| acc + cursor.measure(g, text));
+        if !self.is_over_line_bound(width + self.inline_cursor) {
+          break;
+        }
+        if let Some((at, hyphen)) = self.hyphen_break(run, word, cursor) {
+          word[..at]
+            .iter()
+            .for_each(|g| push_glyph(self, cursor, g));
+          push_glyph(self, cursor, &hyphen);
+          new_line(self, cursor);
+          word = &word[at..];
+        } else if self.inline_cursor != GlyphUnit::ZERO {
+          new_line(self, cursor);
+        } else {
+          break;
+        }
       }
 
-      let mut word = word.peekable();
+      let mut word = word.iter().peekable();
       while let Some(g) = word.peek() {
         let mut at = (*g).clone();
-
         cursor.advance_glyph(&mut at, line_offset, text);
 
-        at.cluster += base;
-
         if self.inline_cursor == GlyphUnit::ZERO
           || !is_auto_wrap
           || !self.is_over_line_bound(cursor.position())
         {
-          self.push_glyph(at);
+          self.push_glyph(at, text, base);
           self.inline_cursor = cursor.position();
           word.next();
         } else {
@@ -253,9 +317,67 @@ where
     }
   }
 
-  fn push_glyph(&mut self, g: Glyph) {
-    let line = self.visual_lines.last_mut();
-    line.unwrap().glyphs.push(g)
+  /// Find where to break the overflowed `word` with a hyphen, return the index
+  /// of the first glyph after the break and the hyphen glyph.
+  fn hyphen_break(
+    &self, run: &InputRun, word: &[Glyph], cursor: &impl InlineCursor,
+  ) -> Option<(usize, Glyph)> {
+    let hyphenator = self.hyphenator.as_ref()?;
+    let hyphen = run.hyphen.as_ref()?;
+    // Only the left-to-right word is hyphenated.
+    if word.len() < 2
+      || word
+        .windows(2)
+        .any(|w| w[1].cluster <= w[0].cluster)
+    {
+      return None;
+    }
+
+    let text = run.text();
+    let start = word[0].cluster as usize;
+    let last = word[word.len() - 1].cluster;
+    let end = run
+      .shape_result
+      .glyphs
+      .iter()
+      .map(|g| g.cluster)
+      .filter(|c| *c > last)
+      .min()
+      .map_or(text.len(), |c| c as usize);
+
+    let mut breaks = hyphenator.breaks(&text[start..end]);
+    breaks.sort_unstable();
+    breaks.into_iter().rev().find_map(|b| {
+      let at = word
+        .iter()
+        .position(|g| g.cluster as usize == start + b)?;
+      if at == 0 {
+        return None;
+      }
+      let font_size = run.font_size_factor * GlyphUnit::PIXELS_PER_EM as f32;
+      let mut hyphen = hyphen.clone().cast_to(font_size);
+      hyphen.cluster = word[at - 1].cluster;
+      let width = word[..at]
+        .iter()
+        .chain(Some(&hyphen))
+        .fold(GlyphUnit::ZERO, |acc, g| acc + cursor.measure(g, text));
+      (!self.is_over_line_bound(width + self.inline_cursor)).then_some((at, hyphen))
+    })
+  }
+
+  /// Push the glyph to the current line, its cluster is relative to the `text`
+  /// of its run which starts at `base`.
+  fn push_glyph(&mut self, mut g: Glyph, text: &str, base: u32) {
+    let line = self.visual_lines.last_mut().unwrap();
+    let is_gap = text[g.cluster as usize..]
+      .chars()
+      .next()
+      .is_some_and(char::is_whitespace);
+    if is_gap {
+      self.line_gaps.push(line.glyphs.len());
+    }
+    g.cluster += base;
+    line.glyphs.push(g)
   }
 
   fn begin_line(&mut self) {
@@ -266,6 +388,56 @@ where
       line.height = self.line_height;
     }
     self.visual_lines.push(line);
+    self
+      .line_aligns
+      .push(self.para_align(self.para_rtl));
+    self.line_gaps.clear();
+  }
+
+  /// Widen the gaps between the words to let the last line fill the bound,
+  /// the leading and trailing gaps are not widened.
+  fn justify_last_line(&mut self) {
+    let inline_x = !self.line_dir.is_horizontal();
+    let bound = if inline_x { self.bounds.width } else { self.bounds.height };
+    let line = self.visual_lines.last_mut().unwrap();
+    let is_gap = |idx: &usize| self.line_gaps.contains(idx);
+
+    let Some(first) = (0..line.glyphs.len()).find(|i| !is_gap(i)) else { return };
+    let last = (0..line.glyphs.len())
+      .rfind(|i| !is_gap(i))
+      .unwrap();
+    let gaps: Vec<usize> = self
+      .line_gaps
+      .iter()
+      .copied()
+      .filter(|i| first < *i && *i < last)
+      .collect();
+    let g = &line.glyphs[last];
+    let end = if inline_x { g.x_offset + g.x_advance } else { g.y_offset + g.y_advance };
+    let extra = (bound - end).0 as i64;
+    if gaps.is_empty() || extra <= 0 {
+      return;
+    }
+
+    let n = gaps.len() as i64;
+    let mut shift = GlyphUnit::ZERO;
+    let mut gaps = gaps.iter().enumerate().peekable();
+    for (idx, g) in line.glyphs.iter_mut().enumerate() {
+      while let Some((k, _)) = gaps.next_if(|(_, gap)| **gap < idx) {
+        // Distribute the extra space by the integer units to fill it exactly.
+        shift = super::GlyphUnit((extra * (k as i64 + 1) / n) as i32);
+      }
+      if inline_x {
+        g.x_offset += shift;
+      } else {
+        g.y_offset += shift;
+      }
+    }
+    if inline_x {
+      line.width = bound;
+    } else {
+      line.height = bound;
+    }
   }
 
   fn end_line(&mut self) {
@@ -316,6 +488,9 @@ pub struct InputRun {
   pub(crate) font_size_factor: f32,
   pub(crate) letter_space: GlyphUnit,
   pub(crate) range: Range<usize>,
+  /// The hyphen glyph of the run relative to the standard size, to break a
+  /// word by the hyphenator.
+  pub(crate) hyphen: Option<Glyph>,
   reorder_text: String,
 }
 
@@ -439,9 +614,9 @@ pub(crate) fn text_align_offset(
   content: GlyphUnit, container: GlyphUnit, text_align: TextAlign,
 ) -> GlyphUnit {
   match text_align {
-    TextAlign::Start => GlyphUnit::ZERO,
+    TextAlign::Start | TextAlign::Left | TextAlign::Justify => GlyphUnit::ZERO,
     TextAlign::Center => (container - content) / 2.,
-    TextAlign::End => container - content,
+    TextAlign::End | TextAlign::Right => container - content,
   }
 }
 
@@ -484,7 +659,13 @@ impl InputRun {
       .iter()
       .filter_map(|gh| text[gh.cluster as usize..].chars().next())
       .collect();
-    Self { shape_result, font_size_factor, letter_space, range, reorder_text }
+    Self { shape_result, font_size_factor, letter_space, range, hyphen: None, reorder_text }
+  }
+
+  /// Set the hyphen glyph to break the words of this run.
+  pub(crate) fn with_hyphen(mut self, hyphen: Option<Glyph>) -> Self {
+    self.hyphen = hyphen;
+    self
   }
 
   #[inline]
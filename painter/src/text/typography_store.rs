@@ -31,6 +31,7 @@ pub struct TypographyKey {
   baseline: GlyphBaseline,
   line_dir: PlaceLineDirection,
   overflow: TextOverflow,
+  hyphenator: Option<Hyphenator>,
 }
 
 /// Do simple text typography and cache it.
@@ -41,6 +42,30 @@ pub struct TypographyStore {
   cache: FrameCache<TypographyKey, Sc<VisualInfos>>,
 }
 
+/// The metrics of a text measured by [`TypographyStore::measure`] without a
+/// text widget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextMetrics {
+  /// The size of the text, its width is limited by the max width as the laid
+  /// out text widget.
+  pub size: Size,
+  pub line_count: usize,
+  /// Whether the text doesn't fit the max width, for example a word longer
+  /// than the max width or the text not wrapped.
+  pub did_overflow: bool,
+  /// The baselines of the lines from the top of the text.
+  pub baselines: Vec<f32>,
+}
+
+/// The position of a glyph in pixels, relative to the top-left of the text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphPosition {
+  /// The byte index of the text that the glyph is shaped from.
+  pub cluster: usize,
+  /// The box of the glyph that the line advances by.
+  pub rect: Rect,
+}
+
 #[derive(Clone)]
 pub struct VisualGlyphs {
   font_size: f32,
@@ -93,7 +118,15 @@ impl TypographyStore {
     &mut self, text: Substr, style: &TextStyle, bounds: Size, text_align: TextAlign,
     baseline: GlyphBaseline, line_dir: PlaceLineDirection,
   ) -> VisualGlyphs {
-    let TextStyle { font_size, ref font_face, letter_space, line_height, overflow } = *style;
+    let TextStyle {
+      font_size,
+      ref font_face,
+      letter_space,
+      line_height,
+      overflow,
+      ref hyphenator,
+      ..
+    } = *style;
     // Since we cache the result of the standard font size, we must ensure that all
     // variables are cast relative to this standard font size.
     let scale = font_size / GlyphUnit::PIXELS_PER_EM as f32;
@@ -107,20 +140,32 @@ impl TypographyStore {
       GlyphUnit::from_pixel(line_height / font_size * GlyphUnit::PIXELS_PER_EM as f32);
 
     let info = self.reorder.reorder_text(&text).clone();
-    let ids = self
-      .font_db
-      .borrow_mut()
-      .select_all_match(font_face)
-      .into_boxed_slice();
+    let ids = {
+      let mut font_db = self.font_db.borrow_mut();
+      let mut ids = font_db.select_all_match(font_face);
+      if font_db.load_system_fonts_for(&ids, &text) {
+        ids = font_db.select_all_match(font_face);
+      }
+      ids.into_boxed_slice()
+    };
     let runs = [RunKey { ids, line_height, letter_space, text }].into();
-    let key = TypographyKey::new(runs, bounds, text_align, line_dir, overflow, baseline);
+    let hyphenator = hyphenator.clone();
+    let key =
+      TypographyKey::new(runs, bounds, text_align, line_dir, overflow, baseline, hyphenator);
     let infos = if let Some(infos) = self.cache.get(&key).cloned() {
       infos
     } else {
       let ids = &key.runs[0].ids;
       let text = &key.runs[0].text;
+      let hyphen = key.hyphenator.as_ref().and_then(|_| {
+        let hyphen = self
+          .shaper
+          .shape_text(&"-".into(), ids, TextDirection::LeftToRight, baseline);
+        hyphen.glyphs.first().cloned()
+      });
       let inputs = info.paras.iter().map(|p| {
-        p.runs
+        let runs = p
+          .runs
           .iter()
           .map(|r| {
             let dir = if r.is_empty() || p.levels[r.start].is_ltr() {
@@ -132,12 +177,14 @@ impl TypographyStore {
             let shape_result = self
               .shaper
               .shape_text(&text.substr(r.clone()), ids, dir, baseline);
-            InputRun::new(shape_result, 1., letter_space, r.clone())
+            InputRun::new(shape_result, 1., letter_space, r.clone()).with_hyphen(hyphen.clone())
           })
-          .collect()
+          .collect();
+        InputParagraph { runs, is_rtl: p.is_rtl }
       });
 
-      let t_man = TypographyMan::new(inputs, line_dir, text_align, line_height, bounds, overflow);
+      let t_man = TypographyMan::new(inputs, line_dir, text_align, line_height, bounds, overflow)
+        .with_hyphenator(key.hyphenator.clone());
       let visual_info = t_man.typography_all();
       let infos = Sc::new(visual_info);
       self.cache.put(key, infos.clone());
@@ -147,6 +194,47 @@ impl TypographyStore {
     VisualGlyphs::new(font_size, line_dir, info, bounds.width, bounds.height, infos.clone())
   }
 
+  /// Measure the `text` in the `style` as a text widget laid out in the
+  /// `max_width`, it shares the cache of the typography, so it's cheap to
+  /// measure the same text in the build and the layout.
+  pub fn measure(
+    &mut self, text: impl Into<Substr>, style: &TextStyle, max_width: f32,
+  ) -> TextMetrics {
+    let bounds = Size::new(max_width, f32::INFINITY);
+    self
+      .measure_typography(text.into(), style, bounds)
+      .metrics(bounds)
+  }
+
+  /// The positions of the glyphs of the `text` in the `style` laid out in the
+  /// `max_width`, in the visual order.
+  pub fn glyph_positions(
+    &mut self, text: impl Into<Substr>, style: &TextStyle, max_width: f32,
+  ) -> Vec<GlyphPosition> {
+    let bounds = Size::new(max_width, f32::INFINITY);
+    let glyphs = self.measure_typography(text.into(), style, bounds);
+    let origin = glyphs.visual_rect().origin.to_vector();
+    glyphs
+      .glyphs()
+      .map(|g| {
+        let pos = Point::new(g.x_offset.into_pixel(), g.y_offset.into_pixel()) + origin;
+        let size = Size::new(g.x_advance.into_pixel(), g.y_advance.into_pixel());
+        GlyphPosition { cluster: g.cluster as usize, rect: Rect::new(pos, size) }
+      })
+      .collect()
+  }
+
+  fn measure_typography(&mut self, text: Substr, style: &TextStyle, bounds: Size) -> VisualGlyphs {
+    self.typography(
+      text,
+      style,
+      bounds,
+      TextAlign::Start,
+      GlyphBaseline::Middle,
+      PlaceLineDirection::TopToBottom,
+    )
+  }
+
   pub fn font_db(&self) -> &Sc<RefCell<FontDB>> { &self.font_db }
 }
 
@@ -456,6 +544,35 @@ impl VisualGlyphs {
 
   pub fn glyph_row_count(&self) -> usize { self.visual_info.visual_lines.len() }
 
+  /// The alphabetic baselines of the horizontal lines in pixel, empty if the
+  /// lines are vertical.
+  pub fn line_baselines(&self) -> Vec<f32> {
+    if !self.is_horizontal_line() {
+      return vec![];
+    }
+    // The outline of a glyph is placed an em below the top of its box.
+    self
+      .visual_info
+      .visual_lines
+      .iter()
+      .filter_map(|line| {
+        let g = line.glyphs.first()?;
+        Some(self.to_pixel_value(self.y + line.y + g.y_offset) + self.font_size)
+      })
+      .collect()
+  }
+
+  /// The metrics of the text laid out in the `bounds`.
+  pub fn metrics(&self, bounds: Size) -> TextMetrics {
+    let size = self.visual_rect().size;
+    TextMetrics {
+      size: size.min(bounds),
+      line_count: self.glyph_row_count(),
+      did_overflow: size.width > bounds.width || size.height > bounds.height,
+      baselines: self.line_baselines(),
+    }
+  }
+
   fn is_horizontal_line(&self) -> bool { !self.visual_info.line_dir.is_horizontal() }
 }
 
@@ -463,6 +580,7 @@ impl TypographyKey {
   fn new(
     runs: Box<[RunKey]>, bounds: Size<GlyphUnit>, text_align: TextAlign,
     line_dir: PlaceLineDirection, overflow: TextOverflow, baseline: GlyphBaseline,
+    hyphenator: Option<Hyphenator>,
   ) -> Self {
     let line_width = match overflow {
       // line width is not so important in clip mode, the cache can be use even with difference line
@@ -478,7 +596,7 @@ impl TypographyKey {
       }
     };
 
-    Self { runs, line_width, text_align, line_dir, overflow, baseline }
+    Self { runs, line_width, text_align, line_dir, overflow, baseline, hyphenator }
   }
 }
 
@@ -500,7 +618,14 @@ mod tests {
     FontFace { families: Box::new([FontFamily::Name("DejaVu Sans".into())]), ..<_>::default() }
   }
   fn text_style(font_size: f32, overflow: TextOverflow, letter_space: f32) -> TextStyle {
-    TextStyle { font_size, font_face: test_face(), letter_space, line_height: font_size, overflow }
+    TextStyle {
+      font_size,
+      font_face: test_face(),
+      letter_space,
+      line_height: font_size,
+      overflow,
+      ..<_>::default()
+    }
   }
   fn zero_letter_space_style(font_size: f32, overflow: TextOverflow) -> TextStyle {
     text_style(font_size, overflow, 0.)
@@ -829,4 +954,124 @@ mod tests {
     );
     assert_eq!(1, store.cache.len());
   }
+
+  /// The inline end of each line, ignoring the trailing whitespace.
+  fn line_ends(glyphs: &VisualGlyphs, text: &str) -> Vec<GlyphUnit> {
+    glyphs
+      .visual_info
+      .visual_lines
+      .iter()
+      .map(|l| {
+        let g = l
+          .glyphs
+          .iter()
+          .rfind(|g| !text[g.cluster as usize..].starts_with(char::is_whitespace))
+          .unwrap();
+        l.x + g.x_offset + g.x_advance
+      })
+      .collect()
+  }
+
+  #[test]
+  fn justify_lines() {
+    let style = zero_letter_space_style(16., TextOverflow::AutoWrap);
+    let text = "The quick brown fox jumps over the lazy dog, again and again.";
+    let glyphs = typography_text(
+      text.into(),
+      &style,
+      Size::new(150., f32::MAX),
+      TextAlign::Justify,
+      PlaceLineDirection::TopToBottom,
+    );
+
+    let ends = line_ends(&glyphs, text);
+    assert!(ends.len() > 2);
+    let (last, interior) = ends.split_last().unwrap();
+    let bound = GlyphUnit::from_pixel(150.);
+    assert!(interior.iter().all(|end| *end == bound));
+    assert!(*last < bound);
+  }
+
+  #[test]
+  fn resolve_start_by_paragraph_direction() {
+    let style = zero_letter_space_style(16., TextOverflow::Clip);
+    let bounds = Size::new(200., f32::MAX);
+    let text = "abc\nאבג";
+    let glyphs = typography_text(
+      text.into(),
+      &style,
+      bounds,
+      TextAlign::Start,
+      PlaceLineDirection::TopToBottom,
+    );
+    let lines = &glyphs.visual_info.visual_lines;
+    assert_eq!(lines[0].x, GlyphUnit::ZERO);
+    assert_eq!(lines[1].x + lines[1].width, glyphs.visual_info.visual_width);
+
+    let glyphs = typography_text(
+      text.into(),
+      &style,
+      bounds,
+      TextAlign::Left,
+      PlaceLineDirection::TopToBottom,
+    );
+    let lines = &glyphs.visual_info.visual_lines;
+    assert_eq!(lines[1].x, GlyphUnit::ZERO);
+  }
+
+  #[test]
+  fn hyphenate_overflowed_word() {
+    let mut style = zero_letter_space_style(16., TextOverflow::AutoWrap);
+    // in-ter-na-tion-al-iza-tion
+    style.hyphenator = Some(Hyphenator::new(|w| {
+      if w == "internationalization" { vec![2, 5, 7, 11, 13, 16] } else { vec![] }
+    }));
+    let text = "an internationalization";
+    let glyphs = typography_text(
+      text.into(),
+      &style,
+      Size::new(120., f32::MAX),
+      TextAlign::Start,
+      PlaceLineDirection::TopToBottom,
+    );
+
+    let hyphen = typography_text(
+      "-".into(),
+      &style,
+      Size::new(120., f32::MAX),
+      TextAlign::Start,
+      PlaceLineDirection::TopToBottom,
+    );
+    let hyphen = hyphen.visual_info.visual_lines[0].glyphs[0].glyph_id;
+
+    let lines = &glyphs.visual_info.visual_lines;
+    assert_eq!(lines.len(), 2);
+    // "an internation-"
+    assert_eq!(lines[0].glyphs.len(), 15);
+    assert_eq!(lines[0].glyphs.last().unwrap().glyph_id, hyphen);
+    assert!(lines[0].width <= GlyphUnit::from_pixel(120.));
+    // "alization"
+    assert_eq!(lines[1].glyphs[0].cluster, 14);
+  }
+
+  #[cfg(feature = "hyphenation")]
+  #[test]
+  fn english_hyphenator() {
+    let english = Hyphenator::english();
+    let hyphenate = |w: &str| {
+      let mut w = w.to_string();
+      english
+        .breaks(&w.clone())
+        .iter()
+        .rev()
+        .for_each(|at| w.insert(*at, '-'));
+      w
+    };
+    assert_eq!(hyphenate("hyphenation"), "hy-phe-na-tion");
+    assert_eq!(hyphenate("paragraph"), "pa-ra-graph");
+    assert_eq!(hyphenate("wonderful"), "won-der-ful");
+    assert_eq!(hyphenate("together"), "to-ge-ther");
+    // Too short to break.
+    assert_eq!(hyphenate("cat"), "cat");
+  }
 }
@@ -2,7 +2,7 @@ use std::{cell::RefCell, convert::Infallible, sync::LazyLock};
 
 use ribir_core::{local_sender::LocalSender, prelude::*, timer::Timer, window::WindowId};
 use winit::{
-  event::{ElementState, Event, Ime, KeyEvent, StartCause, WindowEvent},
+  event::{DeviceEvent, ElementState, Event, Ime, KeyEvent, StartCause, WindowEvent},
   event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget},
 };
 
@@ -11,6 +11,10 @@ use crate::{
   winit_shell_wnd::{WinitShellWnd, new_id},
 };
 
+/// The time that the idle tasks run for every time the event loop is idle, so
+/// the events that come in meanwhile wait no longer than it.
+const IDLE_BUDGET: Duration = Duration::from_millis(4);
+
 pub struct App {
   event_loop_proxy: EventLoopProxy<AppEvent>,
   /// The event loop of the application, it's only available on native platform
@@ -27,6 +31,9 @@ pub struct WindowAttributes {
   pub maximized: bool,
   pub visible: bool,
   pub decorations: bool,
+  /// Whether the window shows what's behind it through its transparent pixels,
+  /// the window is cleared to transparent if it's `true`.
+  pub transparent: bool,
   pub title: String,
   pub size: Option<Size>,
   pub min_size: Option<Size>,
@@ -51,6 +58,9 @@ pub enum AppEvent {
   Hotkey(HotkeyEvent),
   /// The event is sent when the application window focus changed.
   WndFocusChanged(WindowId, bool),
+  /// The event is sent to close a window, as if the user clicks the close
+  /// button of the window.
+  CloseWindow(WindowId),
   /// The custom event, you can send any data with this event.
   Custom(Box<dyn Any + Send>),
 }
@@ -86,6 +96,55 @@ impl App {
     }
   }
 
+  fn close_wnd(wnd_id: WindowId, loop_handle: &EventLoopWindowTarget<AppEvent>) {
+    AppCtx::remove_wnd(wnd_id);
+    if !AppCtx::has_wnd() {
+      loop_handle.exit();
+    }
+  }
+
+  /// Dispatch an input `event` of the `wnd`. The delayed events are emitted at
+  /// once, except the pointer moves, they wait in the queue until the next
+  /// frame, or until another input emits them in order, so the moves between
+  /// two frames are coalesced into one.
+  fn process_input_event(wnd: &Window, event: WindowEvent) {
+    let is_move = matches!(event, WindowEvent::CursorMoved { .. });
+    match event {
+      WindowEvent::Focused(focused) => {
+        if !focused {
+          wnd.exit_pointer_lock();
+        }
+        let mut event = AppEvent::WndFocusChanged(wnd.id(), focused);
+
+        App::shared()
+          .events_stream
+          .clone()
+          .next(&mut event);
+      }
+      WindowEvent::KeyboardInput { event, .. } if !wnd.is_pre_editing() => {
+        let KeyEvent { physical_key, logical_key, text, location, repeat, state, .. } = event;
+        wnd.processes_keyboard_event(physical_key, logical_key, repeat, location, state);
+        if state == ElementState::Pressed {
+          if let Some(txt) = text {
+            wnd.processes_receive_chars(txt.to_string());
+          }
+        }
+      }
+      WindowEvent::Ime(ime) => App::process_winit_ime_event(wnd, ime),
+      WindowEvent::MouseInput { state, button, device_id, .. } => {
+        if state == ElementState::Pressed {
+          wnd.force_exit_pre_edit()
+        }
+        wnd.process_mouse_input(device_id, state, button);
+      }
+      #[allow(deprecated)]
+      event => wnd.processes_native_event(event),
+    }
+    if !is_move {
+      wnd.emit_events();
+    }
+  }
+
   fn event_loop_handle(event: Event<AppEvent>, loop_handle: &EventLoopWindowTarget<AppEvent>) {
     match event {
       Event::WindowEvent { event, window_id } => {
@@ -94,14 +153,9 @@ impl App {
           return;
         };
         match event {
-          WindowEvent::CloseRequested => {
-            AppCtx::remove_wnd(wnd_id);
-            if !AppCtx::has_wnd() {
-              loop_handle.exit();
-            }
-          }
+          WindowEvent::CloseRequested => App::close_wnd(wnd_id, loop_handle),
           WindowEvent::RedrawRequested => {
-            AppCtx::frame_ticks().clone().next(Instant::now());
+            AppCtx::frame_ticks().clone().next(clock::now());
 
             if let Some(wnd) = AppCtx::get_window(wnd_id) {
               // if the window is not visible, don't draw it./
@@ -119,50 +173,44 @@ impl App {
             wnd.shell_wnd().borrow_mut().on_resize(size);
             request_redraw(&wnd)
           }
-          WindowEvent::Focused(focused) => {
-            let mut event = AppEvent::WndFocusChanged(wnd_id, focused);
-
-            App::shared()
-              .events_stream
-              .clone()
-              .next(&mut event);
-          }
-          WindowEvent::KeyboardInput { event, .. } if !wnd.is_pre_editing() => {
-            let KeyEvent { physical_key, logical_key, text, location, repeat, state, .. } = event;
-            wnd.processes_keyboard_event(physical_key, logical_key, repeat, location, state);
-            if state == ElementState::Pressed {
-              if let Some(txt) = text {
-                wnd.processes_receive_chars(txt.to_string());
-              }
-            }
-          }
-          WindowEvent::Ime(ime) => App::process_winit_ime_event(&wnd, ime),
-          WindowEvent::MouseInput { state, button, device_id, .. } => {
-            if state == ElementState::Pressed {
-              wnd.force_exit_pre_edit()
+          event => {
+            App::process_input_event(&wnd, event);
+            if !wnd.is_idle() {
+              request_redraw(&wnd)
             }
-            wnd.process_mouse_input(device_id, state, button);
           }
-          #[allow(deprecated)]
-          event => wnd.processes_native_event(event),
         }
-        wnd.emit_events();
-
-        if wnd.need_draw() {
-          request_redraw(&wnd)
+      }
+      Event::DeviceEvent { event: DeviceEvent::MouseMotion { delta }, .. } => {
+        // The raw movements only go to the window that locks the pointer.
+        for wnd in AppCtx::windows().borrow().values() {
+          if wnd.pointer_lock_widget().is_some() {
+            wnd.process_mouse_motion(Vector::new(delta.0 as f32, delta.1 as f32));
+            wnd.emit_events();
+            request_redraw(wnd);
+          }
         }
       }
       Event::AboutToWait => {
-        let run_count = AppCtx::run_until_stalled();
-        if run_count > 0 {
-          for wnd in AppCtx::windows().borrow().values() {
+        AppCtx::run_until_stalled();
+        // Use the time that no window waits to draw for the idle tasks.
+        let idle = AppCtx::windows()
+          .borrow()
+          .values()
+          .all(|wnd| wnd.is_idle());
+        let idle_work = idle && AppCtx::run_idle_tasks(clock::now() + IDLE_BUDGET);
+        // Only redraw the windows changed by the tasks, and sleep if nothing
+        // changed. The loop is woken up by the events, the timers and the
+        // tasks from other threads.
+        for wnd in AppCtx::windows().borrow().values() {
+          if !wnd.is_idle() {
             request_redraw(wnd);
           }
         }
-        if run_count > 0 {
+        if idle_work {
           loop_handle.set_control_flow(ControlFlow::Poll);
         } else if let Some(t) = Timer::recently_timeout() {
-          let control = ControlFlow::wait_duration(t.duration_since(Instant::now()));
+          let control = ControlFlow::wait_duration(t.duration_since(clock::now()));
           loop_handle.set_control_flow(control);
         } else {
           loop_handle.set_control_flow(ControlFlow::Wait);
@@ -171,7 +219,12 @@ impl App {
       Event::NewEvents(StartCause::Poll | StartCause::ResumeTimeReached { .. }) => {
         Timer::wake_timeout_futures()
       }
+      Event::UserEvent(AppEvent::CloseWindow(wnd_id)) => App::close_wnd(wnd_id, loop_handle),
+      Event::LoopExiting => AppCtx::exiting().clone().next(()),
       Event::UserEvent(mut event) => {
+        if let AppEvent::OpenUrl(url) = &event {
+          AppCtx::handle_link(url);
+        }
         AppCtx::spawn_local(async move {
           App::shared()
             .events_stream
@@ -196,6 +249,7 @@ pub struct AppRunGuard {
   root: Option<GenWidget>,
   wnd_attrs: Option<WindowAttributes>,
   theme_initd: bool,
+  startup_links: Vec<String>,
 }
 
 impl App {
@@ -219,8 +273,12 @@ impl App {
     let event_loop = event_loop.as_ref().expect(
       " Event loop consumed. You can't create window after `App::exec` called in Web platform.",
     );
+    let transparent = attrs.transparent;
     let shell_wnd = WinitShellWnd::new_with_canvas(canvas, &event_loop, attrs).await;
     let wnd = AppCtx::new_window(Box::new(shell_wnd), root);
+    if transparent {
+      wnd.set_clear_color(Some(Color::TRANSPARENT));
+    }
     wnd
   }
 
@@ -232,8 +290,12 @@ impl App {
     let event_loop = event_loop.as_ref().expect(
       " Event loop consumed. You can't create window after `App::exec` called in Web platform.",
     );
+    let transparent = attrs.transparent;
     let shell_wnd = WinitShellWnd::new(event_loop, attrs).await;
     let wnd = AppCtx::new_window(Box::new(shell_wnd), root);
+    if transparent {
+      wnd.set_clear_color(Some(Color::TRANSPARENT));
+    }
 
     #[cfg(not(target_family = "wasm"))]
     if app.active_wnd.get().is_none() {
@@ -303,8 +365,12 @@ impl App {
       let waker = EventWaker(event_loop.create_proxy());
 
       #[cfg(not(target_family = "wasm"))]
-      AppCtx::set_clipboard(Box::new(crate::clipboard::Clipboard::new().unwrap()));
+      AppCtx::set_clipboard_on_demand(|| Box::new(crate::clipboard::Clipboard::new().unwrap()));
+      #[cfg(not(target_family = "wasm"))]
+      AppCtx::set_opener(Box::new(crate::opener::SystemOpener));
       AppCtx::set_runtime_waker(Box::new(waker));
+      #[cfg(target_family = "wasm")]
+      AppCtx::set_system_reduced_motion(prefers_reduced_motion());
 
       register_platform_app_events_handlers();
       let app = App {
@@ -326,7 +392,12 @@ impl AppRunGuard {
     assert!(!ONCE.is_completed(), "App::run can only be called once.");
     ONCE.call_once(|| {});
 
-    Self { root: Some(root), wnd_attrs: Some(Default::default()), theme_initd: false }
+    Self {
+      root: Some(root),
+      wnd_attrs: Some(Default::default()),
+      theme_initd: false,
+      startup_links: vec![],
+    }
   }
 
   /// Set the application theme, this will apply to whole application.
@@ -393,12 +464,30 @@ impl AppRunGuard {
     self
   }
 
+  /// Sets whether the window should be transparent, usually used with
+  /// `with_decorations(false)` to draw a window with a custom shape.
+  pub fn with_transparent(&mut self, transparent: bool) -> &mut Self {
+    self.wnd_attr().transparent = transparent;
+    self
+  }
+
   /// Sets the icon of the window.
   pub fn with_icon(&mut self, icon: Resource<PixelImage>) -> &mut Self {
     self.wnd_attr().icon = Some(icon);
     self
   }
 
+  /// Handle the `links` by [`AppCtx::handle_link`] before the window is
+  /// created, such as the arguments of the command line:
+  ///
+  /// ```ignore
+  /// App::run(root).with_startup_links(std::env::args().skip(1));
+  /// ```
+  pub fn with_startup_links(&mut self, links: impl IntoIterator<Item = String>) -> &mut Self {
+    self.startup_links.extend(links);
+    self
+  }
+
   fn wnd_attr(&mut self) -> &mut WindowAttributes {
     // Should be safe to unwrap because `wnd_attrs` is always `Some` before
     // drop.
@@ -413,6 +502,9 @@ impl Drop for AppRunGuard {
     if !self.theme_initd {
       AppCtx::set_app_theme(ribir_material::purple::light());
     }
+    for link in self.startup_links.drain(..) {
+      AppCtx::handle_link(&link);
+    }
 
     let root = self.root.take().unwrap();
     let attr = self.wnd_attrs.take().unwrap();
@@ -447,6 +539,18 @@ impl RuntimeWaker for EventWaker {
 /// EventWaker only send `RibirEvent::FuturesWake`.
 unsafe impl Send for EventWaker {}
 
+/// Read the "reduce motion" preference of the user from the browser.
+#[cfg(target_family = "wasm")]
+fn prefers_reduced_motion() -> bool {
+  web_sys::window()
+    .and_then(|w| {
+      w.match_media("(prefers-reduced-motion: reduce)")
+        .ok()
+        .flatten()
+    })
+    .is_some_and(|m| m.matches())
+}
+
 pub(crate) fn request_redraw(wnd: &Window) {
   let wnd = wnd.shell_wnd().borrow();
   let shell = wnd
@@ -514,6 +618,13 @@ impl WindowAttributes {
     self
   }
 
+  /// Sets whether the window should be transparent, usually used with
+  /// `with_decorations(false)` to draw a window with a custom shape.
+  pub fn with_transparent(&mut self, transparent: bool) -> &mut Self {
+    self.transparent = transparent;
+    self
+  }
+
   /// Sets the icon of the window.
   pub fn with_icon(&mut self, icon: Resource<PixelImage>) -> &mut Self {
     self.icon = Some(icon);
@@ -533,6 +644,7 @@ impl Default for WindowAttributes {
       maximized: false,
       visible: true,
       decorations: true,
+      transparent: false,
       icon: None,
     }
   }
@@ -541,13 +653,18 @@ impl Default for WindowAttributes {
 #[cfg(test)]
 mod tests {
 
-  use ribir_core::{prelude::*, test_helper::*};
-  use winit::event::Ime;
+  use ribir_core::{prelude::*, reset_test_env, test_helper::*};
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, ElementState, Ime, MouseButton, WindowEvent},
+  };
 
   use super::App;
 
   #[test]
   fn ime_pre_edit() {
+    reset_test_env!();
+
     let log = Stateful::new(vec![]);
     let log2 = log.clone_writer();
 
@@ -617,4 +734,40 @@ mod tests {
       "on_tap",
     ]);
   }
+
+  #[test]
+  fn coalesce_moves_of_event_loop() {
+    reset_test_env!();
+
+    let (log, w_log) = split_value(vec![]);
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_pointer_move: move |e| {
+          $w_log.write().push(format!("move {}", e.coalesced_events().len()));
+        },
+        on_pointer_down: move |_| $w_log.write().push("down".to_string()),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let move_to = |wnd: &TestWindow, x: f32| {
+      let position = LogicalPosition::new(x, 5.).to_physical(1.);
+      App::process_input_event(wnd, WindowEvent::CursorMoved { device_id, position });
+    };
+    // The moves wait for the frame.
+    (0..5).for_each(|i| move_to(&wnd, i as f32));
+    assert!(log.read().is_empty());
+    wnd.draw_frame();
+    assert_eq!(*log.read(), ["move 5"]);
+
+    // Another input emits the moves before it.
+    (5..8).for_each(|i| move_to(&wnd, i as f32));
+    let button = MouseButton::Left;
+    let press = WindowEvent::MouseInput { device_id, state: ElementState::Pressed, button };
+    App::process_input_event(&wnd, press);
+    assert_eq!(*log.read(), ["move 5", "move 3", "down"]);
+  }
 }
@@ -15,5 +15,5 @@ impl WinitBackend for MockBackend {
   ) {
   }
 
-  fn end_frame(&mut self) {}
+  fn end_frame(&mut self) -> bool { true }
 }
@@ -1,46 +1,60 @@
-use ribir_core::prelude::{Color, DeviceRect, DeviceSize, PaintCommand, PainterBackend, Transform};
-use ribir_gpu::Surface;
+use ribir_core::prelude::{
+  AppCtx, Color, DeviceRect, DeviceSize, Observer, PaintCommand, Transform, log,
+};
+use ribir_gpu::{DeviceEvent, GPUBackend, Surface, SurfaceRenderer, WgpuImpl};
 
 use crate::winit_shell_wnd::WinitBackend;
 
 pub struct WgpuBackend<'a> {
-  surface: Surface<'a>,
-  backend: ribir_gpu::GPUBackend<ribir_gpu::WgpuImpl>,
+  renderer: SurfaceRenderer<WgpuImpl, Surface<'a>>,
 }
 
 impl<'a> WinitBackend<'a> for WgpuBackend<'a> {
   async fn new(window: &'a winit::window::Window) -> WgpuBackend<'a> {
-    let (wgpu, surface) = ribir_gpu::WgpuImpl::new(window).await;
+    let (wgpu, surface) = WgpuImpl::new(window).await;
     let size = window.inner_size();
     let size = DeviceSize::new(size.width as i32, size.height as i32);
 
-    let mut wgpu = WgpuBackend { surface, backend: ribir_gpu::GPUBackend::new(wgpu) };
+    let mut wgpu = WgpuBackend { renderer: SurfaceRenderer::new(GPUBackend::new(wgpu), surface) };
     wgpu.on_resize(size);
 
     wgpu
   }
 
   fn on_resize(&mut self, size: DeviceSize) {
-    if size != self.surface.size() {
-      self.surface.resize(size, self.backend.get_impl());
+    let (surface, wgpu) = self.renderer.surface_mut();
+    if size != surface.size() {
+      surface.resize(size, wgpu);
     }
   }
 
-  fn begin_frame(&mut self, surface_color: Color) { self.backend.begin_frame(surface_color); }
+  fn set_transparent(&mut self, transparent: bool) {
+    let (surface, wgpu) = self.renderer.surface_mut();
+    surface.set_transparent(transparent, wgpu);
+  }
+
+  fn begin_frame(&mut self, surface_color: Color) {
+    for event in self.renderer.begin_frame(surface_color) {
+      match event {
+        DeviceEvent::Lost(reason) => {
+          log::warn!("The GPU device is lost: {reason}");
+          AppCtx::on_gpu_lost().clone().next(reason);
+        }
+        DeviceEvent::Recovered => {
+          log::info!("The GPU device is recreated.");
+          AppCtx::on_gpu_recovered().clone().next(());
+        }
+      }
+    }
+  }
 
   fn draw_commands(
     &mut self, viewport: DeviceRect, global_matrix: &Transform, commands: &[PaintCommand],
   ) {
-    self.backend.draw_commands(
-      viewport,
-      commands,
-      global_matrix,
-      self.surface.get_current_texture(),
-    );
+    self
+      .renderer
+      .draw_commands(viewport, global_matrix, commands);
   }
 
-  fn end_frame(&mut self) {
-    self.backend.end_frame();
-    self.surface.present();
-  }
+  fn end_frame(&mut self) -> bool { self.renderer.end_frame() }
 }
@@ -4,7 +4,10 @@ use std::{
 };
 
 use arboard::ImageData;
-use ribir_core::prelude::{PixelImage, image::ColorFormat, log::warn};
+use ribir_core::{
+  clipboard::{ClipboardData, ClipboardFormat},
+  prelude::{PixelImage, image::ColorFormat, log::warn},
+};
 
 pub struct Clipboard {
   pub clipboard: arboard::Clipboard,
@@ -63,6 +66,50 @@ impl ribir_core::clipboard::Clipboard for Clipboard {
   }
 
   fn clear(&mut self) -> Result<(), Error> { self.clipboard.clear().map_err(error_convert) }
+
+  fn read_html(&mut self) -> Result<String, Error> {
+    self.clipboard.get().html().map_err(error_convert)
+  }
+
+  fn write_data(&mut self, data: &[ClipboardData]) -> Result<(), Error> {
+    let text = data.iter().find_map(|d| match d {
+      ClipboardData::Text(text) => Some(text.as_str()),
+      _ => None,
+    });
+    let html = data.iter().find_map(|d| match d {
+      ClipboardData::Html(html) => Some(html.as_str()),
+      _ => None,
+    });
+    let img = data.iter().find_map(|d| match d {
+      ClipboardData::Image(img) => Some(img),
+      _ => None,
+    });
+    // The system clipboard holds the HTML with its plain text together, but
+    // not an image with them.
+    match (html, text, img) {
+      (Some(html), text, _) => self
+        .clipboard
+        .set_html(html, text)
+        .map_err(error_convert),
+      (None, Some(text), _) => self.write_text(text),
+      (None, None, Some(img)) => self.write_img(img),
+      (None, None, None) => self.clear(),
+    }
+  }
+
+  fn read_formats(&mut self) -> Vec<ClipboardFormat> {
+    let mut formats = vec![];
+    if self.clipboard.get().text().is_ok() {
+      formats.push(ClipboardFormat::Text);
+    }
+    if self.clipboard.get().html().is_ok() {
+      formats.push(ClipboardFormat::Html);
+    }
+    if self.clipboard.get().image().is_ok() {
+      formats.push(ClipboardFormat::Image);
+    }
+    formats
+  }
 }
 
 fn error_convert(err: arboard::Error) -> Error {
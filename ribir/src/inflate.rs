@@ -0,0 +1,539 @@
+//! Build a widget tree from a data description at runtime.
+//!
+//! A description is a JSON object for every widget, the `type` field names the
+//! widget, the `children` array or the `child` object describes its children,
+//! and the other fields are the attributes of the widget.
+//!
+//! ```json
+//! {
+//!   "type": "Column",
+//!   "item_gap": 8,
+//!   "margin": [4, 8, 4, 8],
+//!   "children": [
+//!     { "type": "Text", "text": "Visitors" },
+//!     { "type": "Container", "size": [100, 40], "background": "#6750A4", "on_tap": "refresh" }
+//!   ]
+//! }
+//! ```
+//!
+//! Besides the fields of the widget itself, every widget accepts the builtin
+//! attributes `margin`, `padding`, `background`, `opacity`, `h_align` and
+//! `v_align`, and the pointer events `on_tap`, `on_double_tap`,
+//! `on_pointer_down`, `on_pointer_up`, `on_pointer_enter` and
+//! `on_pointer_leave`. The value of an event is the name of a handler that's
+//! registered to the [`Inflater`].
+//!
+//! Widget types opt in to the description by implementing [`DynBuild`] and
+//! being registered to the [`Inflater`]. The built-in layout widgets, `Text`
+//! and `Image` are registered by default.
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+use serde_json::{Map, Value};
+
+use crate::prelude::*;
+
+/// Build a widget from a JSON description with the default [`Inflater`], see
+/// the [module-level documentation](self) for the format.
+pub fn inflate_from_str(src: &str) -> Result<Widget<'static>, InflateError> {
+  Inflater::default().inflate_from_str(src)
+}
+
+/// A widget type that can be built from its description.
+///
+/// ```
+/// use ribir::{inflate::*, prelude::*};
+///
+/// struct Badge;
+///
+/// impl DynBuild for Badge {
+///   fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+///     let count: f32 = node.required("count")?;
+///     Ok(
+///       Text::widget_builder()
+///         .text(count.to_string())
+///         .build(),
+///     )
+///   }
+/// }
+///
+/// let w = Inflater::default()
+///   .register::<Badge>("Badge")
+///   .inflate_from_str(r#"{ "type": "Badge", "count": 3 }"#);
+/// assert!(w.is_ok());
+/// ```
+pub trait DynBuild {
+  /// Build the widget from the node, the attributes and children the widget
+  /// uses should be taken from the node, any field left in the node is
+  /// reported as an unknown field.
+  fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError>;
+}
+
+type BuildFn = fn(&mut Node) -> Result<Widget<'static>, InflateError>;
+type Handler = Rc<RefCell<dyn FnMut(&mut PointerEvent)>>;
+
+/// The registry of the widget types and the event handlers that a description
+/// can use.
+pub struct Inflater {
+  builders: HashMap<&'static str, BuildFn>,
+  handlers: HashMap<String, Handler>,
+}
+
+/// A widget in the description that is being built.
+pub struct Node<'a> {
+  path: String,
+  fields: Map<String, Value>,
+  inflater: &'a Inflater,
+}
+
+/// The error of building a widget from its description.
+#[derive(Debug)]
+pub struct InflateError {
+  /// The path of the value that causes the error, like
+  /// `$.children[1].item_gap`.
+  pub path: String,
+  pub kind: InflateErrorKind,
+}
+
+#[derive(Debug)]
+pub enum InflateErrorKind {
+  /// The description isn't valid JSON.
+  Syntax(serde_json::Error),
+  /// A widget isn't described by an object.
+  NotObject,
+  /// A widget has no `type` field.
+  MissingType,
+  /// The type of the widget isn't registered.
+  UnknownType(String),
+  /// The widget doesn't have the field.
+  UnknownField,
+  /// A required field is missing.
+  MissingField,
+  /// The value of the field isn't the expected one.
+  BadField { expected: &'static str },
+  /// The event handler isn't registered.
+  UnknownHandler(String),
+}
+
+/// A type that can be read from the value of a field.
+pub trait FromValue: Sized {
+  /// The description of the expected value, used in the error message.
+  const EXPECTED: &'static str;
+
+  fn from_value(value: &Value) -> Option<Self>;
+}
+
+impl Inflater {
+  /// Register a widget type with the name that the `type` field uses.
+  pub fn register<W: DynBuild>(mut self, name: &'static str) -> Self {
+    self.builders.insert(name, W::dyn_build);
+    self
+  }
+
+  /// Register an event handler that events of the description can bind to by
+  /// the name.
+  pub fn handler(
+    mut self, name: impl Into<String>, f: impl FnMut(&mut PointerEvent) + 'static,
+  ) -> Self {
+    self
+      .handlers
+      .insert(name.into(), Rc::new(RefCell::new(f)));
+    self
+  }
+
+  /// Build a widget from a JSON description.
+  pub fn inflate_from_str(&self, src: &str) -> Result<Widget<'static>, InflateError> {
+    let value = serde_json::from_str(src)
+      .map_err(|e| InflateError { path: "$".into(), kind: InflateErrorKind::Syntax(e) })?;
+    self.inflate("$".into(), value)
+  }
+
+  /// Build a widget from the description that is already parsed.
+  pub fn inflate(&self, path: String, value: Value) -> Result<Widget<'static>, InflateError> {
+    let Value::Object(fields) = value else {
+      return Err(InflateError { path, kind: InflateErrorKind::NotObject });
+    };
+    let mut node = Node { path, fields, inflater: self };
+    let ty = match node.fields.remove("type") {
+      Some(Value::String(ty)) => ty,
+      Some(_) => return Err(node.field_error("type", "a string")),
+      None => return Err(node.error(InflateErrorKind::MissingType)),
+    };
+    let Some(build) = self.builders.get(ty.as_str()) else {
+      return Err(node.error(InflateErrorKind::UnknownType(ty)));
+    };
+
+    let mut builtin = FatObj::new(());
+    let mut has_builtin = false;
+    macro_rules! builtin_attrs {
+      ($($name:ident: $ty:ty),*) => {
+        $(
+          if let Some(v) = node.attr::<$ty>(stringify!($name))? {
+            builtin = builtin.$name(v);
+            has_builtin = true;
+          }
+        )*
+      };
+    }
+    macro_rules! builtin_events {
+      ($($name:ident),*) => {
+        $(
+          if let Some(h) = node.handler(stringify!($name))? {
+            builtin = builtin.$name(move |e| (h.borrow_mut())(e));
+            has_builtin = true;
+          }
+        )*
+      };
+    }
+    builtin_attrs!(
+      margin: EdgeInsets,
+      padding: EdgeInsets,
+      background: Color,
+      opacity: f32,
+      h_align: HAlign,
+      v_align: VAlign
+    );
+    builtin_events!(
+      on_tap,
+      on_double_tap,
+      on_pointer_down,
+      on_pointer_up,
+      on_pointer_enter,
+      on_pointer_leave
+    );
+
+    let widget = build(&mut node)?;
+    if let Some(name) = node.fields.keys().next() {
+      return Err(node.error_at(name, InflateErrorKind::UnknownField));
+    }
+
+    Ok(if has_builtin { builtin.map(|_| widget).into_widget() } else { widget })
+  }
+}
+
+impl<'a> Node<'a> {
+  /// The path of the node in the description.
+  pub fn path(&self) -> &str { &self.path }
+
+  /// Take the attribute of the name, return `None` if the node doesn't have
+  /// it.
+  pub fn attr<T: FromValue>(&mut self, name: &str) -> Result<Option<T>, InflateError> {
+    match self.fields.remove(name) {
+      Some(v) => T::from_value(&v)
+        .map(Some)
+        .ok_or_else(|| self.field_error(name, T::EXPECTED)),
+      None => Ok(None),
+    }
+  }
+
+  /// Take the attribute of the name, it's an error if the node doesn't have
+  /// it.
+  pub fn required<T: FromValue>(&mut self, name: &str) -> Result<T, InflateError> {
+    self
+      .attr(name)?
+      .ok_or_else(|| self.error_at(name, InflateErrorKind::MissingField))
+  }
+
+  /// Build the children described by the `children` array.
+  pub fn children(&mut self) -> Result<Vec<Widget<'static>>, InflateError> {
+    match self.fields.remove("children") {
+      Some(Value::Array(children)) => children
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| {
+          let path = format!("{}.children[{i}]", self.path);
+          self.inflater.inflate(path, c)
+        })
+        .collect(),
+      Some(_) => Err(self.field_error("children", "an array of widgets")),
+      None => Ok(vec![]),
+    }
+  }
+
+  /// Build the child described by the `child` object.
+  pub fn child(&mut self) -> Result<Option<Widget<'static>>, InflateError> {
+    self
+      .fields
+      .remove("child")
+      .map(|c| {
+        let path = format!("{}.child", self.path);
+        self.inflater.inflate(path, c)
+      })
+      .transpose()
+  }
+
+  /// Take the event handler that the field of the name binds to.
+  fn handler(&mut self, name: &str) -> Result<Option<Handler>, InflateError> {
+    let Some(handler) = self.attr::<String>(name)? else { return Ok(None) };
+    match self.inflater.handlers.get(&handler) {
+      Some(h) => Ok(Some(h.clone())),
+      None => Err(self.error_at(name, InflateErrorKind::UnknownHandler(handler))),
+    }
+  }
+
+  /// Create an error of the node.
+  pub fn error(&self, kind: InflateErrorKind) -> InflateError {
+    InflateError { path: self.path.clone(), kind }
+  }
+
+  /// Create an error of the field of the node.
+  pub fn error_at(&self, field: &str, kind: InflateErrorKind) -> InflateError {
+    InflateError { path: format!("{}.{field}", self.path), kind }
+  }
+
+  fn field_error(&self, field: &str, expected: &'static str) -> InflateError {
+    self.error_at(field, InflateErrorKind::BadField { expected })
+  }
+}
+
+impl Default for Inflater {
+  fn default() -> Self {
+    let inflater = Inflater { builders: HashMap::default(), handlers: HashMap::default() }
+      .register::<Container>("Container")
+      .register::<Text>("Text");
+    #[cfg(feature = "png")]
+    let inflater = inflater.register::<Resource<PixelImage>>("Image");
+    #[cfg(feature = "widgets")]
+    let inflater = inflater
+      .register::<Row>("Row")
+      .register::<Column>("Column")
+      .register::<Flex>("Flex")
+      .register::<Stack>("Stack")
+      .register::<SizedBox>("SizedBox")
+      .register::<Expanded>("Expanded");
+    inflater
+  }
+}
+
+impl fmt::Display for InflateError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: ", self.path)?;
+    match &self.kind {
+      InflateErrorKind::Syntax(e) => write!(f, "invalid description, {e}"),
+      InflateErrorKind::NotObject => write!(f, "expected a widget object"),
+      InflateErrorKind::MissingType => write!(f, "missing the `type` field"),
+      InflateErrorKind::UnknownType(ty) => write!(f, "unknown widget type `{ty}`"),
+      InflateErrorKind::UnknownField => write!(f, "unknown field"),
+      InflateErrorKind::MissingField => write!(f, "missing field"),
+      InflateErrorKind::BadField { expected } => write!(f, "expected {expected}"),
+      InflateErrorKind::UnknownHandler(name) => write!(f, "unknown handler `{name}`"),
+    }
+  }
+}
+
+impl std::error::Error for InflateError {}
+
+// The built-in widgets.
+
+/// Build a single child widget, the child is optional.
+fn with_optional_child<'w, P, const M: usize>(parent: P, child: Option<Widget<'w>>) -> Widget<'w>
+where
+  P: SingleChild + IntoWidget<'w, M>,
+{
+  match child {
+    Some(child) => parent.with_child(child).into_widget(),
+    None => IntoWidget::<'w, M>::into_widget(parent),
+  }
+}
+
+impl DynBuild for Container {
+  fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+    let container = Container::declarer()
+      .size(node.required::<Size>("size")?)
+      .finish();
+    Ok(with_optional_child(container, node.child()?))
+  }
+}
+
+impl DynBuild for Text {
+  fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+    let mut text = Text::declarer().text(node.required::<String>("text")?);
+    if let Some(align) = node.attr::<TextAlign>("text_align")? {
+      text = text.text_align(align);
+    }
+    Ok(text.build())
+  }
+}
+
+#[cfg(feature = "png")]
+impl DynBuild for Resource<PixelImage> {
+  fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+    let src = node.required::<String>("src")?;
+    let bytes = std::fs::read(&src)
+      .map_err(|_| node.error_at("src", InflateErrorKind::BadField { expected: "a png file" }))?;
+    Ok(Resource::new(PixelImage::from_png(&bytes)).into_widget())
+  }
+}
+
+// The values of the fields.
+
+impl FromValue for f32 {
+  const EXPECTED: &'static str = "a number";
+  fn from_value(value: &Value) -> Option<Self> { value.as_f64().map(|v| v as f32) }
+}
+
+impl FromValue for bool {
+  const EXPECTED: &'static str = "a boolean";
+  fn from_value(value: &Value) -> Option<Self> { value.as_bool() }
+}
+
+impl FromValue for String {
+  const EXPECTED: &'static str = "a string";
+  fn from_value(value: &Value) -> Option<Self> { value.as_str().map(String::from) }
+}
+
+impl FromValue for Size {
+  const EXPECTED: &'static str = "a size as `[width, height]`";
+  fn from_value(value: &Value) -> Option<Self> {
+    match value.as_array()?.as_slice() {
+      [w, h] => Some(Size::new(f32::from_value(w)?, f32::from_value(h)?)),
+      _ => None,
+    }
+  }
+}
+
+impl FromValue for EdgeInsets {
+  const EXPECTED: &'static str = "a number or `[top, right, bottom, left]`";
+  fn from_value(value: &Value) -> Option<Self> {
+    if let Some(v) = f32::from_value(value) {
+      return Some(EdgeInsets::all(v));
+    }
+    match value.as_array()?.as_slice() {
+      [t, r, b, l] => Some(EdgeInsets::new(
+        f32::from_value(t)?,
+        f32::from_value(r)?,
+        f32::from_value(b)?,
+        f32::from_value(l)?,
+      )),
+      _ => None,
+    }
+  }
+}
+
+impl FromValue for Color {
+  const EXPECTED: &'static str = "a color as `#RRGGBB` or `#RRGGBBAA`";
+  fn from_value(value: &Value) -> Option<Self> {
+    let hex = value.as_str()?.strip_prefix('#')?;
+    let rgba = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+      6 => Some(Color::from_u32(rgba << 8 | 0xFF)),
+      8 => Some(Color::from_u32(rgba)),
+      _ => None,
+    }
+  }
+}
+
+macro_rules! impl_enum_from_value {
+  ($ty:ident { $($v:ident),* }) => {
+    impl FromValue for $ty {
+      const EXPECTED: &'static str =
+        concat!("one of", $(" `", stringify!($v), "`"),*);
+      fn from_value(value: &Value) -> Option<Self> {
+        match value.as_str()? {
+          $(stringify!($v) => Some($ty::$v),)*
+          _ => None,
+        }
+      }
+    }
+  };
+}
+
+impl_enum_from_value!(Align { Start, Center, End, Stretch });
+impl_enum_from_value!(HAlign { Left, Center, Right, Stretch });
+impl_enum_from_value!(VAlign { Top, Center, Bottom, Stretch });
+impl_enum_from_value!(TextAlign { Start, Center, End, Left, Right, Justify });
+
+#[cfg(feature = "widgets")]
+mod widgets_impl {
+  use super::*;
+
+  fn build_flex(
+    mut flex: FatObj<FlexDeclarer>, node: &mut Node,
+  ) -> Result<Widget<'static>, InflateError> {
+    if let Some(v) = node.attr::<bool>("reverse")? {
+      flex = flex.reverse(v);
+    }
+    if let Some(v) = node.attr::<bool>("wrap")? {
+      flex = flex.wrap(v);
+    }
+    if let Some(v) = node.attr::<Align>("align_items")? {
+      flex = flex.align_items(v);
+    }
+    if let Some(v) = node.attr::<JustifyContent>("justify_content")? {
+      flex = flex.justify_content(v);
+    }
+    if let Some(v) = node.attr::<f32>("item_gap")? {
+      flex = flex.item_gap(v);
+    }
+    if let Some(v) = node.attr::<f32>("line_gap")? {
+      flex = flex.line_gap(v);
+    }
+    let children = node.children()?;
+    Ok(flex.finish().with_child(children).into_widget())
+  }
+
+  impl DynBuild for Row {
+    fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+      build_flex(Row::declarer(), node)
+    }
+  }
+
+  impl DynBuild for Column {
+    fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+      build_flex(Column::declarer(), node)
+    }
+  }
+
+  impl DynBuild for Flex {
+    fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+      let mut flex = Flex::declarer();
+      if let Some(v) = node.attr::<Direction>("direction")? {
+        flex = flex.direction(v);
+      }
+      build_flex(flex, node)
+    }
+  }
+
+  impl DynBuild for Stack {
+    fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+      let mut stack = Stack::declarer();
+      if let Some(v) = node.attr::<StackFit>("fit")? {
+        stack = stack.fit(v);
+      }
+      let children = node.children()?;
+      Ok(stack.finish().with_child(children).into_widget())
+    }
+  }
+
+  impl DynBuild for SizedBox {
+    fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+      let sized_box = SizedBox::declarer()
+        .size(node.required::<Size>("size")?)
+        .finish();
+      Ok(with_optional_child(sized_box, node.child()?))
+    }
+  }
+
+  impl DynBuild for Expanded {
+    fn dyn_build(node: &mut Node) -> Result<Widget<'static>, InflateError> {
+      let mut expanded = Expanded::declarer();
+      if let Some(v) = node.attr::<f32>("flex")? {
+        expanded = expanded.flex(v);
+      }
+      let child = node
+        .child()?
+        .ok_or_else(|| node.error_at("child", InflateErrorKind::MissingField))?;
+      Ok(expanded.finish().with_child(child).into_widget())
+    }
+  }
+
+  impl_enum_from_value!(Direction { Horizontal, Vertical });
+  impl_enum_from_value!(StackFit { Loose, Expand, Passthrough });
+  impl_enum_from_value!(JustifyContent {
+    Start,
+    Center,
+    End,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly
+  });
+}
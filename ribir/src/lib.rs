@@ -3,9 +3,15 @@ pub use ribir_core as core;
 pub use ribir_widgets as widgets;
 pub mod app;
 mod backends;
+#[cfg(feature = "inflate")]
+pub mod inflate;
+#[cfg(feature = "inflate")]
+pub use inflate::inflate_from_str;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod clipboard;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod opener;
 mod winit_shell_wnd;
 #[cfg(feature = "material")]
 pub use ribir_material as material;
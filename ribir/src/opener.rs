@@ -0,0 +1,79 @@
+use std::{
+  ffi::OsStr,
+  io::{Error, ErrorKind},
+  path::Path,
+  process::Command,
+};
+
+use ribir_core::opener::Opener;
+
+/// Open the urls and reveal the files by the tools of the system, `xdg-open`
+/// on Linux and BSD, `open` on macOS and `start` or `explorer` on Windows.
+pub struct SystemOpener;
+
+impl Opener for SystemOpener {
+  fn open_url(&mut self, url: &str) -> Result<(), Error> {
+    if url.is_empty() {
+      return Err(Error::new(ErrorKind::InvalidInput, "open an empty url"));
+    }
+    launch(open_command(url.as_ref()))
+  }
+
+  fn reveal_path(&mut self, path: &Path) -> Result<(), Error> {
+    let path = path.canonicalize()?;
+    launch(reveal_command(&path))
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn open_command(target: &OsStr) -> Command {
+  let mut cmd = Command::new("open");
+  cmd.arg(target);
+  cmd
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_command(path: &Path) -> Command {
+  let mut cmd = Command::new("open");
+  cmd.arg("-R").arg(path);
+  cmd
+}
+
+#[cfg(target_os = "windows")]
+fn open_command(target: &OsStr) -> Command {
+  let mut cmd = Command::new("cmd");
+  // The empty title keeps `start` from taking a quoted target as the title.
+  cmd.args(["/C", "start", ""]).arg(target);
+  cmd
+}
+
+#[cfg(target_os = "windows")]
+fn reveal_command(path: &Path) -> Command {
+  let mut select = std::ffi::OsString::from("/select,");
+  select.push(path);
+  let mut cmd = Command::new("explorer");
+  cmd.arg(select);
+  cmd
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn open_command(target: &OsStr) -> Command {
+  let mut cmd = Command::new("xdg-open");
+  cmd.arg(target);
+  cmd
+}
+
+/// `xdg-open` can't select a file, so the folder of the file is opened.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn reveal_command(path: &Path) -> Command {
+  let dir = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+  open_command(dir.as_os_str())
+}
+
+/// Launch the tool without waiting for it, it fails if the tool can't start.
+fn launch(mut cmd: Command) -> Result<(), Error> {
+  let mut child = cmd.spawn()?;
+  // Reap the tool when it exits.
+  std::thread::spawn(move || child.wait());
+  Ok(())
+}
@@ -11,20 +11,26 @@ use winit::{
 
 use crate::{
   backends::*,
-  prelude::{WindowAttributes, request_redraw},
+  prelude::{App, AppEvent, WindowAttributes, request_redraw},
 };
 pub trait WinitBackend<'a>: Sized {
   fn new(window: &'a winit::window::Window) -> impl Future<Output = Self>;
 
   fn on_resize(&mut self, size: DeviceSize);
 
+  /// Set whether the window composites with what's behind it by the alpha of
+  /// the frames.
+  fn set_transparent(&mut self, transparent: bool);
+
   fn begin_frame(&mut self, surface_color: Color);
 
   fn draw_commands(
     &mut self, viewport: DeviceRect, global_matrix: &Transform, commands: &[PaintCommand],
   );
 
-  fn end_frame(&mut self);
+  /// End the frame and present it, return `false` if the frame is skipped,
+  /// e.g. the GPU is recovering from an error.
+  fn end_frame(&mut self) -> bool;
 }
 
 pub struct WinitShellWnd {
@@ -89,6 +95,12 @@ impl ShellWindow for WinitShellWnd {
   #[inline]
   fn cursor(&self) -> CursorIcon { self.cursor }
 
+  // winit can't use an image as the cursor until 0.30, let the window paint it.
+  fn set_image_cursor(&mut self, _: &ImageCursor) -> bool { false }
+
+  #[inline]
+  fn set_cursor_visible(&mut self, visible: bool) { self.winit_wnd.set_cursor_visible(visible) }
+
   #[inline]
   fn set_title(&mut self, title: &str) { self.winit_wnd.set_title(title) }
 
@@ -130,12 +142,66 @@ impl ShellWindow for WinitShellWnd {
     }
   }
 
+  #[inline]
+  fn is_maximized(&self) -> bool { self.winit_wnd.is_maximized() }
+
+  #[inline]
+  fn set_maximized(&mut self, maximized: bool) { self.winit_wnd.set_maximized(maximized) }
+
+  fn request_close(&mut self) { App::event_sender().send(AppEvent::CloseWindow(self.id())); }
+
   #[inline]
   fn focus_window(&mut self) { self.winit_wnd.focus_window() }
 
   #[inline]
   fn set_decorations(&mut self, decorations: bool) { self.winit_wnd.set_decorations(decorations) }
 
+  fn begin_move_drag(&mut self) {
+    if let Err(err) = self.winit_wnd.drag_window() {
+      log::warn!("Failed to move the window by dragging: {err}");
+    }
+  }
+
+  fn begin_resize_drag(&mut self, edge: ResizeEdge) {
+    use winit::window::ResizeDirection;
+    let direction = match edge {
+      ResizeEdge::Top => ResizeDirection::North,
+      ResizeEdge::Bottom => ResizeDirection::South,
+      ResizeEdge::Left => ResizeDirection::West,
+      ResizeEdge::Right => ResizeDirection::East,
+      ResizeEdge::TopLeft => ResizeDirection::NorthWest,
+      ResizeEdge::TopRight => ResizeDirection::NorthEast,
+      ResizeEdge::BottomLeft => ResizeDirection::SouthWest,
+      ResizeEdge::BottomRight => ResizeDirection::SouthEast,
+    };
+    if let Err(err) = self.winit_wnd.drag_resize_window(direction) {
+      log::warn!("Failed to resize the window by dragging: {err}");
+    }
+  }
+
+  fn set_pointer_locked(&mut self, locked: bool) {
+    use winit::window::CursorGrabMode;
+    let res = if locked {
+      // Not every platform can lock the cursor, confine it in the window instead.
+      self
+        .winit_wnd
+        .set_cursor_grab(CursorGrabMode::Locked)
+        .or_else(|_| {
+          self
+            .winit_wnd
+            .set_cursor_grab(CursorGrabMode::Confined)
+        })
+    } else {
+      self
+        .winit_wnd
+        .set_cursor_grab(CursorGrabMode::None)
+    };
+    if let Err(err) = res {
+      log::warn!("Failed to lock the pointer: {err}");
+    }
+    self.winit_wnd.set_cursor_visible(!locked);
+  }
+
   #[inline]
   fn set_ime_allowed(&mut self, allowed: bool) { self.winit_wnd.set_ime_allowed(allowed); }
 
@@ -163,8 +229,15 @@ impl ShellWindow for WinitShellWnd {
       .draw_commands(viewport, &Transform::scale(scale, scale), commands);
   }
 
-  #[inline]
-  fn end_frame(&mut self) { self.backend.end_frame() }
+  fn end_frame(&mut self) {
+    if !self.backend.end_frame() {
+      // Draw the skipped frame again in the next frame.
+      if let Some(wnd) = AppCtx::get_window(self.id()) {
+        wnd.request_repaint();
+      }
+      self.winit_wnd.request_redraw();
+    }
+  }
 }
 
 pub(crate) fn new_id(id: winit::window::WindowId) -> WindowId {
@@ -240,7 +313,8 @@ impl WinitShellWnd {
       .with_resizable(attrs.resizable)
       // hide the window until the render backend is ready
       .with_visible(false)
-      .with_decorations(attrs.decorations);
+      .with_decorations(attrs.decorations)
+      .with_transparent(attrs.transparent);
 
     if let Some(size) = attrs.size {
       builder = builder.with_inner_size(LogicalSize::new(size.width, size.height));
@@ -262,7 +336,8 @@ impl WinitShellWnd {
     let ptr = &winit_wnd as *const winit::window::Window;
     // Safety: a reference to winit_wnd is valid as long as the WinitShellWnd is
     // alive.
-    let backend = Backend::new(unsafe { &*ptr }).await;
+    let mut backend = Backend::new(unsafe { &*ptr }).await;
+    backend.set_transparent(attrs.transparent);
 
     // show the window after the render backend is ready
     if attrs.visible {
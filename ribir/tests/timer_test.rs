@@ -2,8 +2,6 @@ use ribir::core::timer::Timer;
 use rxrust::scheduler::NEW_TIMER_FN;
 
 mod test_single_thread {
-  use std::{cell::RefCell, rc::Rc, thread::sleep};
-
   use ribir_core::{prelude::*, reset_test_env, test_helper::*};
   use winit::event::{DeviceId, ElementState, MouseButton};
 
@@ -26,111 +24,104 @@ mod test_single_thread {
     wnd.draw_frame();
     wnd.assert_root_size((20., 20.).into());
 
-    sleep(Duration::from_millis(10));
-
     // trigger timeout
-    super::Timer::wake_timeout_futures();
-    AppCtx::run_until_stalled();
-    wnd.draw_frame();
+    wnd.advance_time(Duration::from_millis(10));
     wnd.assert_root_size((10., 10.).into());
   }
 
   fn env(times: usize) -> (TestWindow, Watcher<Reader<i32>>) {
+    env_with(move |w_count| {
+      fn_widget! {
+        @MockBox {
+          size: Size::new(400., 400.),
+          on_x_times_tap: (times, move |_| *$w_count.write() +=1 )
+        }
+      }
+    })
+  }
+
+  fn env_with<W: Into<GenWidget>>(
+    w: impl FnOnce(Stateful<i32>) -> W,
+  ) -> (TestWindow, Watcher<Reader<i32>>) {
     let size = Size::new(400., 400.);
     let (count, w_count) = split_value(0);
-
-    let w = fn_widget! {
-      @MockBox {
-        size,
-        on_x_times_tap: (times, move |_| *$w_count.write() +=1 )
-      }
-    };
-    let mut wnd = TestWindow::new_with_size(w, size);
+    let mut wnd = TestWindow::new_with_size(w(w_count), size);
     wnd.draw_frame();
 
     (wnd, count)
   }
 
-  fn run_until(wnd: &TestWindow, cond: impl Fn() -> bool) {
-    loop {
-      Timer::wake_timeout_futures();
-      AppCtx::run_until_stalled();
-      wnd.run_frame_tasks();
-
-      if (cond)() {
-        break;
-      }
-      sleep(Duration::from_millis(1));
+  fn tap_times(wnd: &mut TestWindow, times: usize, interval: Duration) {
+    let device_id = unsafe { DeviceId::dummy() };
+    for _ in 0..times {
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.advance_time(interval);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.advance_time(interval);
     }
   }
 
   pub fn test_double_tap() {
     reset_test_env!();
-    let (wnd, count) = env(2);
-    let c_wnd = wnd.clone();
-
-    let device_id = unsafe { DeviceId::dummy() };
-    let is_complete = Rc::new(RefCell::new(false));
-    let is_complete2 = is_complete.clone();
-    observable::interval(Duration::from_millis(10), AppCtx::scheduler())
-      .take(8)
-      .on_complete(move || {
-        *is_complete.borrow_mut() = true;
-      })
-      .subscribe(move |i| {
-        let state = if i % 2 == 0 { ElementState::Pressed } else { ElementState::Released };
-        c_wnd.process_mouse_input(device_id, state, MouseButton::Left);
-      });
-
-    run_until(&wnd, || *is_complete2.borrow());
+    let (mut wnd, count) = env(2);
+    tap_times(&mut wnd, 4, Duration::from_millis(10));
     assert_eq!(*count.read(), 2);
 
-    let (wnd, count) = env(2);
-    let c_wnd = wnd.clone();
-
-    let is_complete = Rc::new(RefCell::new(false));
-    let is_complete2 = is_complete.clone();
-    observable::interval(Duration::from_millis(251), AppCtx::scheduler())
-      .take(8)
-      .on_complete(move || {
-        *is_complete.borrow_mut() = true;
-      })
-      .subscribe(move |i| {
-        c_wnd.process_mouse_input(
-          device_id,
-          if i % 2 == 0 { ElementState::Pressed } else { ElementState::Released },
-          MouseButton::Left,
-        );
-      });
-
-    run_until(&wnd, || *is_complete2.borrow());
+    let (mut wnd, count) = env(2);
+    tap_times(&mut wnd, 4, Duration::from_millis(251));
     assert_eq!(*count.read(), 0);
+
+    // The interval is between two taps, not from the first tap.
+    let interval = Duration::from_millis(400);
+    let (mut wnd, count) = env_with(|w_count| {
+      fn_widget! {
+        @MockBox {
+          size: Size::new(400., 400.),
+          on_double_tap_within: (interval, move |_| *$w_count.write() +=1 )
+        }
+      }
+    });
+    tap_times(&mut wnd, 4, Duration::from_millis(150));
+    assert_eq!(*count.read(), 2);
+    tap_times(&mut wnd, 4, Duration::from_millis(201));
+    assert_eq!(*count.read(), 2);
   }
 
   pub fn test_tripe_tap() {
     reset_test_env!();
-    let (wnd, count) = env(3);
-    let c_wnd = wnd.clone();
-
-    let device_id = unsafe { DeviceId::dummy() };
-    let is_complete = Rc::new(RefCell::new(false));
-    let is_complete2 = is_complete.clone();
-    observable::interval(Duration::from_millis(10), AppCtx::scheduler())
-      .take(12)
-      .on_complete(move || {
-        *is_complete.borrow_mut() = true;
-      })
-      .subscribe(move |i| {
-        c_wnd.process_mouse_input(
-          device_id,
-          if i % 2 == 0 { ElementState::Pressed } else { ElementState::Released },
-          MouseButton::Left,
-        );
-      });
-
-    run_until(&wnd, || *is_complete2.borrow());
-
+    let (mut wnd, count) = env(3);
+    tap_times(&mut wnd, 6, Duration::from_millis(10));
     assert_eq!(*count.read(), 2);
+
+    // Three slow but steady taps, the whole sequence is longer than the
+    // interval, but every tap follows the previous one within it.
+    let interval = Duration::from_millis(400);
+    let (mut wnd, count) = env_with(|w_count| {
+      fn_widget! {
+        @MockBox {
+          size: Size::new(400., 400.),
+          on_triple_tap_within: (interval, move |_| *$w_count.write() +=1 )
+        }
+      }
+    });
+    tap_times(&mut wnd, 3, Duration::from_millis(150));
+    assert_eq!(*count.read(), 1);
+
+    // The interval of the theme is the default one.
+    let (mut wnd, count) = env_with(|w_count| {
+      fn_widget! {
+        let theme = Theme { multi_tap_interval: interval, ..Default::default() };
+        let w_count = w_count.clone_writer();
+        theme.with_child(fn_widget! {
+          @MockBox {
+            size: Size::new(400., 400.),
+            on_triple_tap: move |_| *$w_count.write() +=1,
+          }
+        })
+      }
+    });
+    tap_times(&mut wnd, 3, Duration::from_millis(150));
+    assert_eq!(*count.read(), 1);
   }
 }
 
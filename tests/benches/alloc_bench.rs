@@ -0,0 +1,96 @@
+//! Benchmarks of the allocations of the hot paths: building a typical form
+//! and dispatching the pointer moves across a deep chain.
+//!
+//! Run it with the `alloc_track` feature to print the allocations of every
+//! scenario besides the timings:
+//!
+//! ```sh
+//! cargo bench -p tests --bench alloc_bench --features alloc_track
+//! ```
+//!
+//! The counts are the average of an iteration after the warm-up, quote them
+//! with the timings when optimizing these paths.
+
+mod bench_helper;
+
+use bench_helper::*;
+use criterion::{Criterion, criterion_group, criterion_main};
+use ribir::{
+  core::{
+    alloc_track::{AllocCount, count_allocs},
+    reset_test_env,
+    test_helper::*,
+  },
+  prelude::*,
+};
+
+#[cfg(feature = "alloc_track")]
+#[global_allocator]
+static GLOBAL: ribir::core::alloc_track::TrackingAllocator =
+  ribir::core::alloc_track::TrackingAllocator;
+
+const WND_SIZE: Size = Size::new(1024., 1024.);
+const WARM_UP: usize = 10;
+const SAMPLES: usize = 100;
+
+/// Run `f` after the warm-up, and print the average allocations of it.
+fn report_allocs(name: &str, mut f: impl FnMut()) {
+  (0..WARM_UP).for_each(|_| f());
+  let (_, AllocCount { count, bytes }) = count_allocs(|| (0..SAMPLES).for_each(|_| f()));
+  if cfg!(feature = "alloc_track") {
+    println!("{name}: {} allocs, {} bytes per iteration", count / SAMPLES, bytes / SAMPLES);
+  }
+}
+
+/// A form of the labeled inputs, an agreement checkbox and a submit button.
+fn form() -> GenWidget {
+  GenWidget::new(|| {
+    fn_widget! {
+      @Column {
+        @ {
+          ["Name", "Email", "Phone", "Address"].map(|label| @Row {
+            @Text { text: label }
+            @Input { }
+          })
+        }
+        @Row {
+          @Checkbox { checked: true }
+          @Text { text: "Agree the terms" }
+        }
+        @FilledButton { @ { "Submit" } }
+      }
+    }
+    .into_widget()
+  })
+}
+
+fn form_build(c: &mut Criterion) {
+  reset_test_env!();
+
+  let mut wnd = TestWindow::new_with_size(fn_widget!(Void), WND_SIZE);
+  let mut build = || {
+    wnd.0.init(form());
+    wnd.draw_frame();
+  };
+  report_allocs("alloc/form_build", &mut build);
+  c.bench_function("alloc/form_build", |b| b.iter(&mut build));
+  AppCtx::remove_wnd(wnd.id());
+}
+
+fn pointer_move_deep_chain(c: &mut Criterion) {
+  reset_test_env!();
+
+  let w = GenWidget::new(|| tree_with_leaf(10, 1, leaf(Size::new(100., 100.))));
+  let wnd = bench_wnd(w, WND_SIZE);
+  let mut x = 10.;
+  let mut move_in_leaf = || {
+    x = if x == 10. { 90. } else { 10. };
+    move_cursor(&wnd, Point::new(x, 50.));
+  };
+  report_allocs("alloc/pointer_move_10_deep", &mut move_in_leaf);
+  c.bench_function("alloc/pointer_move_10_deep", |b| b.iter(&mut move_in_leaf));
+  AppCtx::remove_wnd(wnd.id());
+}
+
+criterion_group!(alloc, form_build, pointer_move_deep_chain);
+criterion_main!(alloc);
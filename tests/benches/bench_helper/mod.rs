@@ -0,0 +1,91 @@
+//! Shared scenarios for the benchmark suite.
+//!
+//! All the scenarios build their widgets with the public APIs and run in a
+//! headless `TestWindow`, so the numbers only reflect the framework cost of
+//! build, layout and dispatch.
+//!
+//! Optimizations of these paths (layout caching, keyed diff, hit-test
+//! acceleration, ...) should quote the benchmark groups they affect, for
+//! example `tree_inflate/6x4` or `hit_test/10k_nodes`, with the numbers before
+//! and after the change.
+#![allow(dead_code)]
+
+use ribir::{core::test_helper::*, prelude::*};
+use winit::event::{DeviceId, WindowEvent};
+
+/// The `(depth, breadth)` shapes used by the tree benchmarks.
+pub const TREE_SHAPES: [(usize, usize); 5] = [(2, 100), (3, 20), (6, 4), (12, 2), (500, 1)];
+
+/// A tree with `breadth` children in every level and `depth` levels, the
+/// leaves are fixed size `MockBox`.
+#[derive(Clone, Debug)]
+pub struct DeepTree {
+  pub depth: usize,
+  pub breadth: usize,
+}
+
+impl Compose for DeepTree {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    fn_widget! {
+      let DeepTree { depth, breadth } = $this.clone();
+      @MockMulti {
+        @ { (0..breadth).map(move |_| sub_tree(depth - 1, breadth)) }
+      }
+    }
+    .into_widget()
+  }
+}
+
+fn sub_tree(depth: usize, breadth: usize) -> Widget<'static> {
+  if depth > 0 { DeepTree { depth, breadth }.into_widget() } else { leaf(Size::new(10., 10.)) }
+}
+
+/// A fixed size leaf widget.
+pub fn leaf(size: Size) -> Widget<'static> { MockBox { size }.into_widget() }
+
+/// Same as `DeepTree`, but the last leaf of the deepest path is replaced by
+/// the `leaf` widget, so the benchmark can modify only one leaf of a big tree.
+pub fn tree_with_leaf(depth: usize, breadth: usize, leaf: Widget<'static>) -> Widget<'static> {
+  if depth == 0 {
+    return leaf;
+  }
+  let siblings = (1..breadth).map(move |_| sub_tree(depth - 1, breadth));
+  MockMulti
+    .with_child(siblings)
+    .with_child(tree_with_leaf(depth - 1, breadth, leaf))
+    .into_widget()
+}
+
+/// A grid of `rows` x `columns` leaves, every leaf is a `cell` x `cell` box.
+pub fn grid(rows: usize, columns: usize, cell: f32) -> GenWidget {
+  GenWidget::new(move || {
+    fn_widget! {
+      @Column {
+        @ {
+          (0..rows).map(move |_| @Row {
+            @ { (0..columns).map(move |_| leaf(Size::new(cell, cell))) }
+          })
+        }
+      }
+    }
+    .into_widget()
+  })
+}
+
+/// Create a headless window to run the benchmark.
+pub fn bench_wnd(w: impl Into<GenWidget>, size: Size) -> TestWindow {
+  let mut wnd = TestWindow::new_with_size(w, size);
+  wnd.draw_frame();
+  wnd
+}
+
+/// Move the cursor to `pos` and dispatch all the events it triggered.
+pub fn move_cursor(wnd: &TestWindow, pos: Point) {
+  let device_id = unsafe { DeviceId::dummy() };
+  #[allow(deprecated)]
+  wnd.processes_native_event(WindowEvent::CursorMoved {
+    device_id,
+    position: (pos.x as f64, pos.y as f64).into(),
+  });
+  wnd.run_frame_tasks();
+}
@@ -0,0 +1,162 @@
+//! Benchmarks of the framework pipeline: build, layout and event dispatch.
+//!
+//! See `bench_helper` for the scenarios and the naming convention to quote
+//! the results.
+
+mod bench_helper;
+
+use bench_helper::*;
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use ribir::{
+  core::{reset_test_env, test_helper::*},
+  prelude::*,
+};
+
+const WND_SIZE: Size = Size::new(1024., 1024.);
+
+fn tree_inflate(c: &mut Criterion) {
+  reset_test_env!();
+
+  let mut group = c.benchmark_group("tree_inflate");
+  let mut wnd = TestWindow::new_with_size(fn_widget!(Void), WND_SIZE);
+  for (depth, breadth) in TREE_SHAPES {
+    let id = BenchmarkId::from_parameter(format!("{depth}x{breadth}"));
+    group.bench_function(id, |b| {
+      b.iter(|| {
+        wnd
+          .0
+          .init(GenWidget::new(move || DeepTree { depth, breadth }.into_widget()));
+        wnd.draw_frame();
+      })
+    });
+  }
+  group.finish();
+  AppCtx::remove_wnd(wnd.id());
+}
+
+fn relayout(c: &mut Criterion) {
+  reset_test_env!();
+
+  let mut group = c.benchmark_group("relayout");
+  for (depth, breadth) in TREE_SHAPES {
+    let size = Stateful::new(Size::new(10., 10.));
+    let c_size = size.clone_writer();
+    let w = GenWidget::new(move || {
+      let size = c_size.clone_writer();
+      let leaf = fn_widget! { @MockBox { size: pipe!(*$size) } };
+      tree_with_leaf(depth, breadth, leaf.into_widget())
+    });
+    let mut wnd = bench_wnd(w, WND_SIZE);
+
+    let id = BenchmarkId::from_parameter(format!("{depth}x{breadth}"));
+    group.bench_function(id, |b| {
+      b.iter(|| {
+        {
+          let mut size = size.write();
+          size.width = if size.width == 10. { 20. } else { 10. };
+        }
+        wnd.draw_frame();
+      })
+    });
+    AppCtx::remove_wnd(wnd.id());
+  }
+  group.finish();
+}
+
+fn pipe_list(c: &mut Criterion) {
+  reset_test_env!();
+
+  const BASE: usize = 1000;
+  let list = Stateful::new((0..BASE).collect::<Vec<_>>());
+  let c_list = list.clone_writer();
+  let w = fn_widget! {
+    @MockMulti {
+      @ {
+        pipe!($c_list.clone()).map(|items| {
+          items.into_iter().map(|i| leaf(Size::new(1. + (i % 10) as f32, 10.)))
+        })
+      }
+    }
+  };
+  let mut wnd = bench_wnd(w, WND_SIZE);
+
+  let mut group = c.benchmark_group("pipe_list");
+  group.bench_function("append_10", |b| {
+    let mut setup_wnd = wnd.clone();
+    b.iter_batched(
+      || {
+        list.write().truncate(BASE);
+        setup_wnd.draw_frame();
+      },
+      |_| {
+        list.write().extend(BASE..BASE + 10);
+        wnd.draw_frame();
+      },
+      BatchSize::PerIteration,
+    )
+  });
+  group.bench_function("reorder", |b| {
+    list.write().truncate(BASE);
+    wnd.draw_frame();
+    b.iter(|| {
+      list.write().reverse();
+      wnd.draw_frame();
+    })
+  });
+  group.finish();
+  AppCtx::remove_wnd(wnd.id());
+}
+
+fn pipe_in_wide_tree(c: &mut Criterion) {
+  reset_test_env!();
+
+  const SIBLINGS: usize = 1000;
+  let width = Stateful::new(10.);
+  let c_width = width.clone_writer();
+  let w = fn_widget! {
+    @MockMulti {
+      @ { (0..SIBLINGS).map(|_| leaf(Size::new(10., 10.))) }
+      @ { pipe!(*$c_width).map(|w| leaf(Size::new(w, 10.))) }
+    }
+  };
+  let mut wnd = bench_wnd(w, WND_SIZE);
+
+  let mut group = c.benchmark_group("pipe_in_wide_tree");
+  // Only the piped child rebuilds, its siblings are kept as they are.
+  group.bench_function(format!("{SIBLINGS}_siblings"), |b| {
+    b.iter(|| {
+      let mut width = width.write();
+      *width = if *width == 10. { 20. } else { 10. };
+      drop(width);
+      wnd.draw_frame();
+    })
+  });
+  group.finish();
+  AppCtx::remove_wnd(wnd.id());
+}
+
+fn hit_test(c: &mut Criterion) {
+  reset_test_env!();
+
+  let mut group = c.benchmark_group("hit_test");
+  // The cost of a move should grow slower than the number of nodes, the
+  // subtrees away from the cursor are never visited.
+  for (side, name) in [(10, "100_nodes"), (32, "1k_nodes"), (100, "10k_nodes")] {
+    let wnd = bench_wnd(grid(side, side, 8.), WND_SIZE);
+    let end = side as f32 * 8. - 4.;
+    group.bench_function(name, |b| {
+      let mut toggle = false;
+      b.iter(|| {
+        toggle = !toggle;
+        // Move between two cells to make sure every iteration hits a new widget.
+        let pos = if toggle { Point::new(end, end) } else { Point::new(end - 8., end) };
+        move_cursor(&wnd, pos);
+      })
+    });
+    AppCtx::remove_wnd(wnd.id());
+  }
+  group.finish();
+}
+
+criterion_group!(pipeline, tree_inflate, relayout, pipe_list, pipe_in_wide_tree, hit_test);
+criterion_main!(pipeline);
@@ -14,16 +14,20 @@ fn shape_1k(c: &mut Criterion) {
       ..<_>::default()
     });
 
+  let str: Substr = include_str!("../../LICENSE").into();
   c.bench_function("shape_1k", |b| {
     b.iter(|| {
       // clean cache
       shaper.end_frame();
       shaper.end_frame();
 
-      let str = include_str!("../../LICENSE").into();
       shaper.shape_text(&str, &ids, TextDirection::LeftToRight, GlyphBaseline::Alphabetic)
     })
   });
+
+  c.bench_function("shape_1k_cached", |b| {
+    b.iter(|| shaper.shape_text(&str, &ids, TextDirection::LeftToRight, GlyphBaseline::Alphabetic))
+  });
 }
 
 criterion_group!(text_benches, shape_1k);
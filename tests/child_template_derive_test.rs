@@ -102,3 +102,17 @@ fn enum_template() {
     @P { @{ self::ChildC } }
   };
 }
+
+#[test]
+#[should_panic = "The child `ChildA` of the template `ChildTemplateOfP` is filled twice"]
+fn panic_if_fill_same_field_twice() { let _ = P.with_child(ChildA).with_child(ChildA); }
+
+#[test]
+#[should_panic = "The required child `ChildA` of the template `ChildTemplateOfP` is not given."]
+fn panic_if_miss_required_child() { let _ = P.with_child(ChildB).into_widget(); }
+
+#[test]
+fn template_builder_not_conflict_with_declare() {
+  let _ = ChildTemplateOfP::builder().with_child(ChildA);
+  let _ = ChildTemplateOfP::widget_builder().with_child(ChildA);
+}
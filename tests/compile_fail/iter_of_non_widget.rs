@@ -0,0 +1,7 @@
+use ribir::prelude::*;
+
+fn main() {
+  let _ = fn_widget! {
+    @Column { @ { vec![1, 2, 3] } }
+  };
+}
@@ -0,0 +1,35 @@
+use ribir::prelude::*;
+
+#[derive(Declare)]
+struct Card;
+
+#[derive(ChildOfCompose)]
+struct Title;
+
+#[derive(ChildOfCompose)]
+struct Footer;
+
+#[derive(Template)]
+struct CardChild {
+  title: Title,
+  footer: Option<Footer>,
+}
+
+impl ComposeChild<'static> for Card {
+  type Child = CardChild;
+
+  fn compose_child(_: impl StateWriter<Value = Self>, _: Self::Child) -> Widget<'static> {
+    Void.into_widget()
+  }
+}
+
+struct NotSlot;
+
+fn main() {
+  let _ = fn_widget! {
+    @Card {
+      @ { Title }
+      @ { NotSlot }
+    }
+  };
+}
@@ -0,0 +1,11 @@
+use ribir::prelude::*;
+
+fn main() {
+  let _ = fn_widget! {
+    @SizedBox {
+      size: Size::new(100., 100.),
+      @Text { text: "first" }
+      @Text { text: "second" }
+    }
+  };
+}
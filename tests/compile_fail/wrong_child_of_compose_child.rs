@@ -0,0 +1,18 @@
+use ribir::prelude::*;
+
+#[derive(Declare)]
+struct Frame;
+
+impl<'c> ComposeChild<'c> for Frame {
+  type Child = Widget<'c>;
+
+  fn compose_child(_: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> { child }
+}
+
+struct NotWidget;
+
+fn main() {
+  let _ = fn_widget! {
+    @Frame { @ { NotWidget } }
+  };
+}
@@ -0,0 +1,9 @@
+use ribir::prelude::*;
+
+struct NotWidget;
+
+fn main() {
+  let _ = fn_widget! {
+    @Row { @ { NotWidget } }
+  };
+}
@@ -0,0 +1,5 @@
+#[test]
+fn child_misuse() {
+  let t = trybuild::TestCases::new();
+  t.compile_fail("compile_fail/*.rs");
+}
@@ -68,3 +68,46 @@ fn declarer_simple_attr() {
   assert_eq!(s.read().a, 1.);
   assert_eq!(s.read().b, 1);
 }
+
+#[test]
+fn builder_same_as_macro() {
+  use ribir::core::{reset_test_env, test_helper::*};
+  reset_test_env!();
+
+  let by_macro = fn_widget! {
+    @MockMulti {
+      margin: EdgeInsets::all(2.),
+      @Text { text: "Hi!", on_tap: |_| {} }
+      @MockBox {
+        size: Size::new(10., 10.),
+        background: Color::RED,
+        padding: EdgeInsets::all(1.),
+        @Text { text: "Ribir" }
+      }
+    }
+  };
+
+  let by_builder = fn_widget! {
+    MockMulti::widget_builder()
+      .margin(EdgeInsets::all(2.))
+      .finish()
+      .with_child(Text::widget_builder().text("Hi!").on_tap(|_| {}).build())
+      .with_child(
+        MockBox::widget_builder()
+          .size(Size::new(10., 10.))
+          .background(Color::RED)
+          .padding(EdgeInsets::all(1.))
+          .finish()
+          .with_child(Text::widget_builder().text("Ribir").build()),
+      )
+  };
+
+  let mut macro_wnd = TestWindow::new(by_macro);
+  macro_wnd.draw_frame();
+  let mut builder_wnd = TestWindow::new(by_builder);
+  builder_wnd.draw_frame();
+
+  let dump = macro_wnd.dump_tree();
+  assert!(dump.contains("Text"));
+  assert_eq!(dump, builder_wnd.dump_tree());
+}
@@ -0,0 +1,54 @@
+use ribir::{
+  core::{reset_test_env, test_helper::*},
+  prelude::*,
+};
+
+#[test]
+fn embed_assets() {
+  reset_test_env!();
+
+  let png = include_asset!("./assets/red_4x4.png");
+  let svg = include_asset!("./assets/test1.svg");
+  assert_eq!(png.kind(), AssetKind::Image);
+  assert_eq!(svg.kind(), AssetKind::Svg);
+  assert_eq!(&*png.bytes(), include_bytes!("./assets/red_4x4.png"));
+  // The identical files share one asset.
+  assert_eq!(include_asset!("assets/red_4x4.png", Image), png);
+
+  let mut wnd = TestWindow::new(fn_widget! {
+    @MockMulti {
+      @ { Image::from_asset(png) }
+      @ { Svg::from_asset(svg, true, false).unwrap() }
+    }
+  });
+  wnd.draw_frame();
+  wnd.draw_frame();
+  wnd.assert_root_size(Size::new(36., 32.));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn override_assets() {
+  reset_test_env!();
+
+  let dir = std::env::temp_dir().join(format!("ribir_asset_override_{}", std::process::id()));
+  std::fs::create_dir_all(dir.join("assets")).unwrap();
+  let svg = r#"<svg width="16" height="8" xmlns="http://www.w3.org/2000/svg">
+    <rect width="16" height="8" />
+  </svg>"#;
+  std::fs::write(dir.join("assets/test1.svg"), svg).unwrap();
+
+  let asset = include_asset!("./assets/test1.svg");
+  AssetOverride::set_dir(Some(dir.clone()));
+  let overridden = Svg::from_asset(asset, true, false).unwrap();
+  AssetOverride::set_dir(None);
+  let embedded = Svg::from_asset(asset, true, false).unwrap();
+  let _ = std::fs::remove_dir_all(&dir);
+
+  assert_eq!(overridden.size(), Size::new(16., 8.));
+  assert_eq!(embedded.size(), Size::new(32., 32.));
+
+  let mut wnd = TestWindow::new(fn_widget! { overridden.clone() });
+  wnd.draw_frame();
+  wnd.assert_root_size(Size::new(16., 8.));
+}
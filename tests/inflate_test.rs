@@ -0,0 +1,148 @@
+use std::{cell::Cell, rc::Rc};
+
+use ribir::{
+  core::{reset_test_env, test_helper::*},
+  inflate::*,
+  prelude::*,
+};
+use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+const DASHBOARD: &str = r##"{
+  "type": "Column",
+  "item_gap": 8,
+  "margin": [4, 8, 4, 8],
+  "children": [
+    {
+      "type": "Row",
+      "justify_content": "SpaceBetween",
+      "children": [
+        { "type": "Text", "text": "Visitors" },
+        { "type": "Text", "text": "1024", "text_align": "End" }
+      ]
+    },
+    {
+      "type": "SizedBox",
+      "size": [200, 40],
+      "background": "#6750A4",
+      "on_tap": "refresh",
+      "child": { "type": "Text", "text": "Refresh", "h_align": "Center" }
+    },
+    {
+      "type": "Stack",
+      "children": [
+        { "type": "Container", "size": [100, 20] },
+        { "type": "Container", "size": [50, 10], "padding": 2 }
+      ]
+    }
+  ]
+}"##;
+
+fn dashboard_by_macro() -> Widget<'static> {
+  fn_widget! {
+    @Column {
+      item_gap: 8.,
+      margin: EdgeInsets::new(4., 8., 4., 8.),
+      @Row {
+        justify_content: JustifyContent::SpaceBetween,
+        @Text { text: "Visitors" }
+        @Text { text: "1024", text_align: TextAlign::End }
+      }
+      @SizedBox {
+        size: Size::new(200., 40.),
+        background: Color::from_u32(0x6750A4FF),
+        on_tap: |_| {},
+        @Text { text: "Refresh", h_align: HAlign::Center }
+      }
+      @Stack {
+        @Container { size: Size::new(100., 20.) }
+        @Container { size: Size::new(50., 10.), padding: EdgeInsets::all(2.) }
+      }
+    }
+  }
+  .into_widget()
+}
+
+fn tap_at(wnd: &TestWindow, pos: (i32, i32)) {
+  let device_id = unsafe { DeviceId::dummy() };
+
+  #[allow(deprecated)]
+  wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: pos.into() });
+  wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+  wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+}
+
+#[test]
+fn inflate_same_as_macro() {
+  reset_test_env!();
+
+  let by_data = fn_widget! {
+    Inflater::default()
+      .handler("refresh", |_| {})
+      .inflate_from_str(DASHBOARD)
+      .unwrap()
+  };
+
+  let mut macro_wnd = TestWindow::new(dashboard_by_macro);
+  macro_wnd.draw_frame();
+  let mut data_wnd = TestWindow::new(by_data);
+  data_wnd.draw_frame();
+
+  let dump = macro_wnd.dump_tree();
+  assert!(dump.contains("Text"));
+  assert_eq!(dump, data_wnd.dump_tree());
+}
+
+#[test]
+fn named_handler_fires_on_tap() {
+  reset_test_env!();
+
+  let taps = Rc::new(Cell::new(0));
+  let c_taps = taps.clone();
+  let w = fn_widget! {
+    let c_taps = c_taps.clone();
+    Inflater::default()
+      .handler("refresh", move |_| c_taps.set(c_taps.get() + 1))
+      .inflate_from_str(DASHBOARD)
+      .unwrap()
+  };
+  let mut wnd = TestWindow::new(w);
+  wnd.draw_frame();
+
+  // The refresh box is below the first row, and the column has a margin.
+  tap_at(&wnd, (20, 50));
+  wnd.draw_frame();
+  assert_eq!(taps.get(), 1);
+
+  // Tap outside of the box.
+  tap_at(&wnd, (500, 500));
+  wnd.draw_frame();
+  assert_eq!(taps.get(), 1);
+}
+
+#[test]
+fn error_with_path() {
+  reset_test_env!();
+
+  let err = |src: &str| inflate_from_str(src).err().unwrap().to_string();
+
+  assert_eq!(
+    err(
+      r#"{ "type": "Column", "children": [{ "type": "Text", "text": "a" }, { "type": "Chart" }] }"#
+    ),
+    "$.children[1]: unknown widget type `Chart`"
+  );
+  assert_eq!(
+    err(r#"{ "type": "Row", "children": [{ "type": "Row", "item_gap": "wide" }] }"#),
+    "$.children[0].item_gap: expected a number"
+  );
+  assert_eq!(
+    err(r#"{ "type": "SizedBox", "size": [1, 1], "child": { "type": "Text", "txt": "a" } }"#),
+    "$.child.text: missing field"
+  );
+  assert_eq!(err(r#"{ "type": "Text", "text": "a", "color": "red" }"#), "$.color: unknown field");
+  assert_eq!(
+    err(r#"{ "type": "Text", "text": "a", "on_tap": "refresh" }"#),
+    "$.on_tap: unknown handler `refresh`"
+  );
+  assert_eq!(err(r#"{ "text": "a" }"#), "$: missing the `type` field");
+}
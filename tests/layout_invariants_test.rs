@@ -0,0 +1,177 @@
+//! Check the invariants of the layout on the random trees of the layout
+//! widgets:
+//!
+//! - every size is finite and within the clamp that its parent gives,
+//! - laying out again gives the same result,
+//! - a zero padding changes nothing.
+//!
+//! A few cases from a fixed seed run in the default suite, the
+//! `layout_fuzz` feature runs many random cases:
+//!
+//! ```sh
+//! cargo test -p tests --features layout_fuzz --test layout_invariants
+//! ```
+//!
+//! A failure is shrunk to a minimal tree, it's reported with the dump of the
+//! tree.
+
+use proptest::{
+  prelude::*,
+  test_runner::{Config, RngAlgorithm, TestRng, TestRunner},
+};
+use ribir::{
+  core::{reset_test_env, test_helper::*},
+  prelude::*,
+};
+
+#[derive(Debug, Clone)]
+enum Node {
+  Sized(Size),
+  Padding(f32, Box<Node>),
+  Constrained(BoxClamp, Box<Node>),
+  Expanded(f32, Box<Node>),
+  Flex { direction: Direction, wrap: bool, children: Vec<Node> },
+}
+
+fn length(max: u16) -> impl Strategy<Value = f32> { (0..=max).prop_map(f32::from) }
+
+fn clamp() -> impl Strategy<Value = BoxClamp> {
+  (length(200), length(200), length(200), length(200)).prop_map(|(w1, h1, w2, h2)| BoxClamp {
+    min: Size::new(w1.min(w2), h1.min(h2)),
+    max: Size::new(w1.max(w2), h1.max(h2)),
+  })
+}
+
+fn node() -> impl Strategy<Value = Node> {
+  let leaf = (length(200), length(200)).prop_map(|(w, h)| Node::Sized(Size::new(w, h)));
+  leaf.prop_recursive(4, 32, 4, |inner| {
+    prop_oneof![
+      (length(20), inner.clone()).prop_map(|(p, n)| Node::Padding(p, Box::new(n))),
+      (clamp(), inner.clone()).prop_map(|(c, n)| Node::Constrained(c, Box::new(n))),
+      ((1..=3u8).prop_map(f32::from), inner.clone())
+        .prop_map(|(f, n)| Node::Expanded(f, Box::new(n))),
+      (any::<bool>(), any::<bool>(), prop::collection::vec(inner, 0..4)).prop_map(
+        |(horizontal, wrap, children)| {
+          let direction = if horizontal { Direction::Horizontal } else { Direction::Vertical };
+          Node::Flex { direction, wrap, children }
+        }
+      ),
+    ]
+  })
+}
+
+fn build(node: &Node) -> Widget<'static> {
+  match node {
+    Node::Sized(size) => {
+      let size = *size;
+      rdl! { @SizedBox { size } }.into_widget()
+    }
+    Node::Padding(p, child) => FatObj::new(build(child))
+      .padding(EdgeInsets::all(*p))
+      .into_widget(),
+    Node::Constrained(clamp, child) => FatObj::new(build(child))
+      .clamp(*clamp)
+      .into_widget(),
+    Node::Expanded(flex, child) => {
+      let flex = *flex;
+      let child = build(child);
+      rdl! { @Expanded { flex, @ { child } } }.into_widget()
+    }
+    Node::Flex { direction, wrap, children } => {
+      let (direction, wrap) = (*direction, *wrap);
+      let children: Vec<_> = children.iter().map(build).collect();
+      rdl! { @Flex { direction, wrap, @ { children } } }.into_widget()
+    }
+  }
+}
+
+/// Wrap every `SizedBox` of the tree with a zero padding.
+fn pad_leaves(node: &Node) -> Node {
+  match node {
+    Node::Sized(_) => Node::Padding(0., Box::new(node.clone())),
+    Node::Padding(p, child) => Node::Padding(*p, Box::new(pad_leaves(child))),
+    Node::Constrained(c, child) => Node::Constrained(*c, Box::new(pad_leaves(child))),
+    Node::Expanded(f, child) => Node::Expanded(*f, Box::new(pad_leaves(child))),
+    Node::Flex { direction, wrap, children } => Node::Flex {
+      direction: *direction,
+      wrap: *wrap,
+      children: children.iter().map(pad_leaves).collect(),
+    },
+  }
+}
+
+fn layout(node: &Node, wnd_size: Size) -> TestWindow {
+  let node = node.clone();
+  let mut wnd = TestWindow::new_with_size(move || build(&node), wnd_size);
+  wnd.draw_frame();
+  wnd
+}
+
+fn sized_box_rects(wnd: &TestWindow) -> Vec<Rect> {
+  let mut rects = vec![];
+  wnd.walk_layout(|name, info, pos| {
+    if name.ends_with("SizedBox") {
+      rects.push(Rect::new(pos, info.size.unwrap()));
+    }
+  });
+  rects
+}
+
+fn check(node: &Node, wnd_size: Size) -> Result<(), TestCaseError> {
+  let mut wnd = layout(node, wnd_size);
+  let dump = wnd.dump_tree();
+
+  let mut bad = None;
+  wnd.walk_layout(|name, info, _| {
+    let Some(size) = info.size else { return };
+    let clamped = info.clamp.clamp(size);
+    let within =
+      (clamped.width - size.width).abs() < 0.01 && (clamped.height - size.height).abs() < 0.01;
+    if bad.is_none() && !(size.width.is_finite() && size.height.is_finite() && within) {
+      bad = Some(format!("{name} has size {size:?} out of {:?}", info.clamp));
+    }
+  });
+  if let Some(bad) = bad {
+    return Err(TestCaseError::fail(format!("{bad}\n{dump}")));
+  }
+
+  wnd.request_resize(Size::new(wnd_size.width + 37., wnd_size.height + 23.));
+  wnd.draw_frame();
+  wnd.request_resize(wnd_size);
+  wnd.draw_frame();
+  prop_assert_eq!(&wnd.dump_tree(), &dump, "laid out again differently");
+
+  let padded = layout(&pad_leaves(node), wnd_size);
+  prop_assert_eq!(
+    sized_box_rects(&padded),
+    sized_box_rects(&wnd),
+    "a zero padding changes the layout\n{}\n{}",
+    padded.dump_tree(),
+    dump
+  );
+  Ok(())
+}
+
+fn run(mut runner: TestRunner) {
+  reset_test_env!();
+
+  // A zero sized window is minimized, it's not laid out.
+  let wnd_size = (1..=400u16, 1..=400u16).prop_map(|(w, h)| Size::new(w.into(), h.into()));
+  if let Err(e) = runner.run(&(node(), wnd_size), |(node, size)| check(&node, size)) {
+    panic!("{e}");
+  }
+}
+
+#[test]
+fn seeded_layout_invariants() {
+  let config = Config { cases: 64, failure_persistence: None, ..Config::default() };
+  let rng = TestRng::from_seed(RngAlgorithm::ChaCha, &[7; 32]);
+  run(TestRunner::new_with_rng(config, rng));
+}
+
+#[cfg(feature = "layout_fuzz")]
+#[test]
+fn fuzz_layout_invariants() {
+  let config = Config { cases: 4096, failure_persistence: None, ..Config::default() };
+  run(TestRunner::new(config));
+}
@@ -605,7 +605,7 @@ widget_layout_test!(
 fn builtin_ref() {
   reset_test_env!();
 
-  let (icon, w_icon) = split_value(CursorIcon::default());
+  let (icon, w_icon) = split_value(PointerCursor::default());
 
   let w = fn_widget! {
     let mut tap_box = @SizedBox {
@@ -613,11 +613,11 @@ fn builtin_ref() {
       cursor: CursorIcon::Pointer,
     };
     @Flex {
-      cursor: pipe!($tap_box.cursor),
+      cursor: pipe!($tap_box.cursor.clone()),
       @$tap_box {
         on_tap: move |_| {
-          $tap_box.write().cursor = CursorIcon::AllScroll;
-          *$w_icon.write() = $tap_box.cursor;
+          $tap_box.write().cursor = CursorIcon::AllScroll.into();
+          *$w_icon.write() = $tap_box.cursor.clone();
         }
       }
     }
@@ -628,7 +628,7 @@ fn builtin_ref() {
 
   tap_at(&wnd, (1, 1));
   wnd.draw_frame();
-  assert_eq!(*icon.read(), CursorIcon::AllScroll);
+  assert_eq!(*icon.read(), PointerCursor::Icon(CursorIcon::AllScroll));
 }
 
 #[test]
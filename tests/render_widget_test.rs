@@ -0,0 +1,143 @@
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
+};
+
+use gauge::*;
+use ribir::{
+  core::{reset_test_env, test_helper::*},
+  prelude::*,
+};
+
+/// A render widget that records the constraints it receives and how many times
+/// it's painted. It places its child after a gap of `width`, so its size
+/// follows its child.
+#[derive(Default, Clone, SingleChild)]
+struct Probe {
+  width: f32,
+  clamps: Rc<RefCell<Vec<BoxClamp>>>,
+  paints: Rc<Cell<usize>>,
+}
+
+impl Render for Probe {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    self.clamps.borrow_mut().push(clamp);
+    let child = match ctx.single_child() {
+      Some(child) => {
+        let size = ctx.perform_child_layout(child, clamp.loose());
+        ctx.update_position(child, Point::new(self.width, 0.));
+        size
+      }
+      None => Size::zero(),
+    };
+    clamp.clamp(Size::new(self.width + child.width, child.height.max(10.)))
+  }
+
+  fn paint(&self, _: &mut PaintingCtx) { self.paints.set(self.paints.get() + 1); }
+}
+
+#[test]
+fn custom_render_in_row() {
+  reset_test_env!();
+
+  let probe = Probe { width: 40., ..Default::default() };
+  let clamps = probe.clamps.clone();
+  let w = fn_widget! {
+    let probe = probe.clone();
+    @Row {
+      @Container { size: Size::new(30., 20.) }
+      @ { probe }
+      @Container { size: Size::new(30., 20.) }
+    }
+  };
+  let mut wnd = TestWindow::new_with_size(w, Size::new(200., 100.));
+  wnd.draw_frame();
+
+  let clamps = clamps.borrow();
+  assert_eq!(clamps.len(), 1);
+  assert_eq!(clamps[0].min, Size::zero());
+  assert_eq!(clamps[0].max.height, 100.);
+  LayoutCase::expect_rect(&wnd, &[0, 1], Rect::new(Point::new(30., 0.), Size::new(40., 10.)));
+  LayoutCase::expect_x(&wnd, &[0, 2], 70.);
+}
+
+#[test]
+fn repaint_when_state_changes() {
+  reset_test_env!();
+
+  let probe = Stateful::new(Probe { width: 40., ..Default::default() });
+  let paints = probe.read().paints.clone();
+  let clamps = probe.read().clamps.clone();
+  let c_probe = probe.clone_writer();
+  let w = fn_widget! {
+    @Row { @ { c_probe.clone_writer() } }
+  };
+  let mut wnd = TestWindow::new_with_size(w, Size::new(200., 100.));
+  wnd.draw_frame();
+  assert_eq!(paints.get(), 1);
+
+  // Nothing changed, nothing to lay out or paint.
+  wnd.draw_frame();
+  assert_eq!(paints.get(), 1);
+  assert_eq!(clamps.borrow().len(), 1);
+
+  probe.write().width = 60.;
+  wnd.draw_frame();
+  assert_eq!(paints.get(), 2);
+  assert_eq!(clamps.borrow().len(), 2);
+  LayoutCase::expect_size(&wnd, &[0, 0], Size::new(60., 10.));
+}
+
+#[test]
+fn relayout_when_child_changes() {
+  reset_test_env!();
+
+  let probe = Probe { width: 40., ..Default::default() };
+  let clamps = probe.clamps.clone();
+  let child_size = Stateful::new(Size::new(20., 20.));
+  let c_child_size = child_size.clone_watcher();
+  let w = fn_widget! {
+    let probe = probe.clone();
+    @Row {
+      @ $probe { @Container { size: pipe!(*$c_child_size) } }
+      @Container { size: Size::new(30., 20.) }
+    }
+  };
+  let mut wnd = TestWindow::new_with_size(w, Size::new(200., 100.));
+  wnd.draw_frame();
+  LayoutCase::expect_size(&wnd, &[0, 0], Size::new(60., 20.));
+  LayoutCase::expect_x(&wnd, &[0, 0, 0], 40.);
+  LayoutCase::expect_x(&wnd, &[0, 1], 60.);
+
+  // The child grows, the probe and the row are laid out again.
+  *child_size.write() = Size::new(50., 30.);
+  wnd.draw_frame();
+  assert_eq!(clamps.borrow().len(), 2);
+  LayoutCase::expect_size(&wnd, &[0, 0], Size::new(90., 30.));
+  LayoutCase::expect_x(&wnd, &[0, 1], 90.);
+}
+
+#[test]
+fn gauge_from_another_crate() {
+  reset_test_env!();
+
+  let gauge = Stateful::new(Gauge { value: 0.5 });
+  let c_gauge = gauge.clone_writer();
+  let w = fn_widget! {
+    @Row {
+      @ { c_gauge.clone_writer() }
+      @SizedBox {
+        size: Size::new(60., 60.),
+        @Gauge { value: 1. }
+      }
+    }
+  };
+  let mut wnd = TestWindow::new_with_size(w, Size::new(400., 200.));
+  wnd.draw_frame();
+  LayoutCase::expect_size(&wnd, &[0, 0], Size::new(120., 120.));
+  LayoutCase::expect_size(&wnd, &[0, 1, 0], Size::new(60., 60.));
+
+  gauge.write().value = 0.8;
+  wnd.draw_frame();
+  LayoutCase::expect_size(&wnd, &[0, 0], Size::new(120., 120.));
+}
@@ -2,10 +2,13 @@ use ribir_core::prelude::Classes;
 
 mod buttons_cls;
 mod checkbox_cls;
+mod dock_cls;
 mod progress_cls;
 mod radio_cls;
 mod scrollbar_cls;
 mod slider_cls;
+mod split_pane_cls;
+mod title_bar_cls;
 mod tooltips_cls;
 
 pub fn initd_classes() -> Classes {
@@ -18,6 +21,9 @@ pub fn initd_classes() -> Classes {
   checkbox_cls::init(&mut classes);
   tooltips_cls::init(&mut classes);
   slider_cls::init(&mut classes);
+  dock_cls::init(&mut classes);
+  split_pane_cls::init(&mut classes);
+  title_bar_cls::init(&mut classes);
 
   classes
 }
@@ -191,8 +191,8 @@ fn btn_label_style(line_height: f32) -> TextStyle {
 
 fn base_interactive(w: Widget, radius: Radius) -> Widget {
   let hover_layer = HoverLayer::tracked(LayerArea::WidgetCover(radius));
-  ripple! {
-    bounded: RippleBound::Radius(radius),
+  press_feedback! {
+    clip: radius,
     cursor: CursorIcon::Pointer,
     @ $hover_layer { @ { w } }
   }
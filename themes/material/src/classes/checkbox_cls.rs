@@ -7,7 +7,7 @@ use crate::*;
 pub(super) fn init(classes: &mut Classes) {
   classes.insert(CHECKBOX, |w| {
     let hover_layer = HoverLayer::tracked(LayerArea::WidgetCover(md::RADIUS_20));
-    ripple! {
+    press_feedback! {
       cursor: CursorIcon::Pointer,
       radius: 20.,
       center: true,
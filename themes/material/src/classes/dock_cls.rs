@@ -0,0 +1,41 @@
+use ribir_core::prelude::*;
+use ribir_widgets::prelude::*;
+
+use crate::md;
+
+pub(super) fn init(classes: &mut Classes) {
+  classes.insert(
+    DOCK_TAB_BAR,
+    style_class! {
+      background: Palette::of(BuildCtx::get()).surface_container(),
+    },
+  );
+  classes.insert(
+    DOCK_TAB,
+    style_class! {
+      cursor: CursorIcon::Pointer,
+      padding: EdgeInsets::new(4., 8., 4., 12.),
+    },
+  );
+  classes.insert(
+    DOCK_TAB_ACTIVE,
+    style_class! {
+      cursor: CursorIcon::Pointer,
+      padding: EdgeInsets::new(4., 8., 4., 12.),
+      background: Palette::of(BuildCtx::get()).surface(),
+    },
+  );
+  classes.insert(
+    DOCK_TAB_CLOSE,
+    style_class! {
+      margin: EdgeInsets::only_left(8.),
+    },
+  );
+  classes.insert(
+    DOCK_HINT,
+    style_class! {
+      background: Palette::of(BuildCtx::get()).primary().with_alpha(0.2),
+      border_radius: md::RADIUS_4,
+    },
+  );
+}
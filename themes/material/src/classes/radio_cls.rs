@@ -6,7 +6,7 @@ use crate::*;
 pub(super) fn init(classes: &mut Classes) {
   classes.insert(RADIO, |w| {
     let hover_layer = HoverLayer::tracked(LayerArea::WidgetCover(md::RADIUS_20));
-    ripple! {
+    press_feedback! {
       radius: 20.,
       center: true,
       cursor: CursorIcon::Pointer,
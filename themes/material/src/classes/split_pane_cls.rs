@@ -0,0 +1,11 @@
+use ribir_core::prelude::*;
+use ribir_widgets::prelude::*;
+
+pub(super) fn init(classes: &mut Classes) {
+  classes.insert(
+    SPLIT_PANE_DIVIDER,
+    style_class! {
+      background: Palette::of(BuildCtx::get()).outline_variant(),
+    },
+  );
+}
@@ -0,0 +1,24 @@
+use ribir_core::{named_style_class, prelude::*};
+use ribir_widgets::prelude::*;
+
+const TITLE_BAR_HEIGHT: f32 = 40.;
+const CAPTION_ICON_SIZE: f32 = 18.;
+
+named_style_class!(caption_button => {
+  text_line_height: CAPTION_ICON_SIZE,
+  padding: EdgeInsets::symmetrical(0., 14.),
+  cursor: CursorIcon::Pointer
+});
+
+pub(super) fn init(classes: &mut Classes) {
+  classes.insert(
+    TITLE_BAR,
+    style_class! {
+      clamp: BoxClamp::fixed_height(TITLE_BAR_HEIGHT),
+      padding: EdgeInsets::only_left(16.),
+      background: Palette::of(BuildCtx::get()).surface_container(),
+    },
+  );
+  classes.insert(TITLE_BAR_BUTTON, caption_button);
+  classes.insert(TITLE_BAR_CLOSE, caption_button);
+}
@@ -23,6 +23,7 @@ fn new(palette: Palette) -> Theme {
     ],
     ..Default::default()
   };
+  theme.focus_ring.color = theme.palette.primary();
 
   fill_svgs! { theme.icon_theme,
     svgs::ADD: "./icons/add_FILL0_wght400_GRAD0_opsz48.svg",
@@ -39,8 +40,11 @@ fn new(palette: Palette) -> Theme {
     svgs::HOME: "./icons/home_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::LOGIN: "./icons/login_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::LOGOUT: "./icons/logout_FILL0_wght400_GRAD0_opsz48.svg",
+    svgs::MAXIMIZE: "./icons/crop_square_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::MENU: "./icons/menu_FILL0_wght400_GRAD0_opsz48.svg",
+    svgs::MINIMIZE: "./icons/minimize_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::MORE_VERT: "./icons/more_vert_FILL0_wght400_GRAD0_opsz48.svg",
+    svgs::RESTORE: "./icons/filter_none_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::SEARCH: "./icons/search_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::SETTINGS: "./icons/settings_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::STAR: "./icons/star_FILL0_wght400_GRAD0_opsz48.svg",
@@ -103,7 +107,7 @@ fn init_custom_style(theme: &mut Theme) {
   theme
     .custom_styles
     .set_custom_style(ListItemStyle {
-      padding_style: Some(EdgeInsets { left: 0., right: 24., bottom: 8., top: 8. }),
+      padding_style: Some(EdgeInsets::new(8., 24., 8., 0.)),
       item_align: |num| {
         if num >= 2 { Align::Start } else { Align::Center }
       },
@@ -193,6 +197,12 @@ fn override_compose_decorator(theme: &mut Theme) {
     .into_widget()
   });
 
+  styles.override_compose_decorator::<PressFeedback>(|this, host, _| {
+    let PressFeedback { radius, center, clip } = this.read().clone();
+    let bounded = clip.map_or(RippleBound::Unbounded, RippleBound::Radius);
+    ripple! { radius, center, bounded, @ { host } }.into_widget()
+  });
+
   let textfield = TextFieldThemeSuit::from_theme(&theme.palette, &theme.typography_theme);
   theme.custom_styles.set_custom_style(textfield);
 }
@@ -263,6 +273,7 @@ pub fn typography_theme() -> TypographyTheme {
         letter_space,
         font_face,
         overflow: TextOverflow::Clip,
+        ..<_>::default()
       },
       decoration: TextDecorationStyle {
         decoration: TextDecoration::NONE,
@@ -1,9 +1,20 @@
-use ribir_core::prelude::*;
+use std::cell::Cell;
 
-use crate::{LayerArea, PressedLayer, md};
+use ribir_core::{prelude::*, ticker::FrameMsg, wrap_render::WrapRender};
+
+use crate::{PressedLayer, md};
 
 /// Widget use to do ripple animate as a visual feedback to user interactive.
 /// Usually for touch and mouse.
+///
+/// Every press launches a new ripple that expands from the pressed position,
+/// and fades out after the pointer is released or leaves the host. So the
+/// ripples of rapid taps overlap and animate independently.
+///
+/// The ripples can be disabled for the whole application by the
+/// [`RippleStyle`] of the theme. The material theme installs it as the
+/// [`PressFeedback`](ribir_widgets::prelude::PressFeedback) of the built-in
+/// controls.
 #[derive(Declare)]
 pub struct Ripple {
   /// The radius in pixels of foreground ripples when fully expanded. The
@@ -17,7 +28,15 @@ pub struct Ripple {
   #[declare(default=RippleBound::Unbounded)]
   /// How ripples show outside of the host widget box.
   pub bounded: RippleBound,
-  #[declare(default)]
+  #[declare(skip)]
+  waves: Vec<RippleWave>,
+  /// The time of the frame that the ripples are painted at.
+  #[declare(skip, default = clock::now())]
+  now: Instant,
+  /// The size of the host in the last layout.
+  #[declare(skip)]
+  host_size: Cell<Size>,
+  #[declare(skip)]
   launcher: Option<Box<dyn Fn(Option<Point>)>>,
 }
 
@@ -32,21 +51,112 @@ pub enum RippleBound {
   Radius(Radius),
 }
 
+/// A ripple launched by a press.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RippleWave {
+  /// The center of the ripple in the coordinate of the host.
+  pub center: Point,
+  /// The radius of the ripple when it's fully expanded.
+  pub radius: f32,
+  /// The radius of the host box that the ripple is clipped by, `None` if it's
+  /// unbounded.
+  pub clip: Option<Radius>,
+  /// When the ripple is launched.
+  pub launch_at: Instant,
+  /// When the pointer that launched the ripple is released, `None` if it's
+  /// still pressed.
+  pub release_at: Option<Instant>,
+}
+
+/// The style of the ripples, set it to the `custom_styles` of the theme to
+/// config the ripples of all the widgets, such as the buttons.
+///
+/// # Example
+///
+/// Disable the ripples of the whole application.
+///
+/// ```no_run
+/// use ribir_core::prelude::*;
+/// use ribir_material::RippleStyle;
+///
+/// let mut theme = AppCtx::app_theme().write();
+/// theme
+///   .custom_styles
+///   .set_custom_style(RippleStyle { enabled: false });
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RippleStyle {
+  /// Whether to show the ripples. If it's false, the `Ripple` composes its
+  /// child as it is.
+  pub enabled: bool,
+}
+
+impl CustomStyle for RippleStyle {
+  fn default_style(_: &impl ProviderCtx) -> Self { RippleStyle { enabled: true } }
+}
+
 impl<'c> ComposeChild<'c> for Ripple {
   type Child = Widget<'c>;
 
   fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    if !RippleStyle::of(BuildCtx::get()).enabled {
+      return child;
+    }
+
     fn_widget! {
-      let mut ripple_layer = PressedLayer::new(LayerArea::WidgetCover(Radius::all(0.)));
-      init_ripple_launcher(&this, &mut ripple_layer);
+      let wnd = BuildCtx::get().window();
+      let c_wnd = wnd.clone();
+      // Keep the frames coming until all the ripples fade out.
+      let running = watch!($this.waves.is_empty())
+        .distinct_until_changed()
+        .skip(1)
+        .subscribe(move |empty| if empty {
+          c_wnd.dec_running_animate();
+        } else {
+          c_wnd.inc_running_animate();
+        });
+      let ticks = wnd
+        .frame_tick_stream()
+        .subscribe(move |msg| if let FrameMsg::NewFrame(time) = msg {
+          if !$this.waves.is_empty() {
+            $this.write().advance(time);
+          }
+        });
+
+      let writer = this.clone_writer();
+      $this.silent().launcher = Some(Box::new(move |pos| {
+        let writer = writer.clone_writer();
+        // The caller may be reading the ripple, so launch it in a task.
+        let _ = AppCtx::spawn_local(async move {
+          let size = writer.read().host_size.get();
+          writer.write().launch_wave(pos, size);
+        });
+      }));
 
-      @ $ripple_layer {
+      let host = FatObj::new(WrapRender::combine_child(this.clone_writer(), child));
+      @ $host {
         on_pointer_down: move |e| {
-          let pos = (!$this.center).then(||e.position());
-          $this.launch(pos);
+          let pos = (!$this.center).then(|| e.position());
+          let size = e.box_size().unwrap_or_default();
+          $this.write().launch_wave(pos, size);
+        },
+        on_pointer_up: move |_| if $this.is_pressed() {
+          $this.write().release();
+        },
+        on_pointer_cancel: move |_| if $this.is_pressed() {
+          $this.write().release();
+        },
+        on_pointer_leave: move |_| if $this.is_pressed() {
+          $this.write().release();
+        },
+        on_disposed: move |_| {
+          $this.silent().launcher = None;
+          if !$this.waves.is_empty() {
+            wnd.dec_running_animate();
+          }
+          running.unsubscribe();
+          ticks.unsubscribe();
         },
-        on_disposed: move |_| $this.write().launcher = None,
-        @ { child }
       }
     }
     .into_widget()
@@ -54,70 +164,281 @@ impl<'c> ComposeChild<'c> for Ripple {
 }
 
 impl Ripple {
-  /// Manual launch a ripple animate at `pos`.
-  pub fn launch(&self, pos: Option<Point>) { self.launcher.as_ref().inspect(|l| l(pos)); }
-}
+  /// The time a ripple takes to expand fully.
+  const GROW: Duration = md::easing::duration::SHORT3;
+  /// The time a ripple takes to fade out.
+  const FADE: Duration = md::easing::duration::MEDIUM3;
+
+  /// Manual launch a ripple at `pos`, the ripple starts from the center of the
+  /// host if `pos` is `None`. It's launched in the next frame, and stays until
+  /// [`Ripple::release`] is called.
+  pub fn launch(&self, pos: Option<Point>) {
+    if let Some(launcher) = &self.launcher {
+      launcher(pos);
+    }
+  }
 
-fn init_ripple_launcher(
-  this: &impl StateWriter<Value = Ripple>, layer: &mut FatObj<Stateful<PressedLayer>>,
-) {
-  rdl! {
-    let ripple_grow = @Animate {
-      state: LerpFnState::new(
-        part_writer!(&mut layer.area),
-        move |_, to, factor| {
-          let LayerArea::Circle { center, radius, clip } = *to else { unreachable!() };
-          LayerArea::Circle { center, radius: f32::lerp(&0., &radius, factor), clip }
-        }
-      ),
-      transition: EasingTransition {
-        easing: md::easing::EMPHASIZED_DECELERATE,
-        duration: md::easing::duration::SHORT3,
-      }.box_it(),
-      from: LayerArea::WidgetCover(Radius::all(0.)),
+  /// Launch a ripple at `pos` in a host of `size`.
+  fn launch_wave(&mut self, pos: Option<Point>, size: Size) {
+    let center = pos.unwrap_or_else(|| (size / 2.).to_vector().to_point());
+    let radius = self.radius.unwrap_or_else(|| {
+      let distance_x = f32::max(center.x, size.width - center.x);
+      let distance_y = f32::max(center.y, size.height - center.y);
+      (distance_x.powf(2.) + distance_y.powf(2.)).sqrt()
+    });
+    let clip = match self.bounded {
+      RippleBound::Unbounded => None,
+      RippleBound::Bounded => Some(Radius::all(0.)),
+      RippleBound::Radius(radius) => Some(radius),
     };
+    self.now = clock::now();
+    self
+      .waves
+      .push(RippleWave { center, radius, clip, launch_at: self.now, release_at: None });
+  }
+
+  /// Release the pressed ripples, they fade out once they fully expand.
+  pub fn release(&mut self) {
+    let now = clock::now();
+    self
+      .waves
+      .iter_mut()
+      .filter(|w| w.release_at.is_none())
+      .for_each(|w| w.release_at = Some(now));
+  }
+
+  /// The ripples that are showing.
+  pub fn waves(&self) -> &[RippleWave] { &self.waves }
+
+  /// Return if any ripple is still pressed.
+  pub fn is_pressed(&self) -> bool { self.waves.iter().any(|w| w.release_at.is_none()) }
+
+  /// Move the ripples to the time `now`, and remove the ones that faded out.
+  fn advance(&mut self, now: Instant) {
+    self.now = now;
+    self.waves.retain(|w| !w.is_finished(now));
+  }
+}
+
+impl RippleWave {
+  /// The radius of the ripple at the time `now`.
+  pub fn radius_at(&self, now: Instant) -> f32 {
+    let rate = time_rate(now.duration_since(self.launch_at), Ripple::GROW);
+    self.radius * md::easing::EMPHASIZED_DECELERATE.easing(rate)
+  }
+
+  /// The opacity of the ripple at the time `now`.
+  pub fn opacity_at(&self, now: Instant) -> f32 {
+    let show = PressedLayer::show_opacity();
+    match self.fade_start() {
+      Some(start) if now > start => {
+        let rate = time_rate(now.duration_since(start), Ripple::FADE);
+        show * (1. - md::easing::STANDARD_ACCELERATE.easing(rate))
+      }
+      _ => show,
+    }
+  }
+
+  /// The ripple starts to fade out after it's released and fully expanded.
+  fn fade_start(&self) -> Option<Instant> {
+    self
+      .release_at
+      .map(|at| at.max(self.launch_at + Ripple::GROW))
+  }
+
+  fn is_finished(&self, now: Instant) -> bool {
+    self
+      .fade_start()
+      .is_some_and(|start| now >= start + Ripple::FADE)
+  }
+}
 
-    let fade_out = @Animate {
-      state: part_writer!(&mut layer.draw_opacity),
-      transition: EasingTransition{
-        easing: md::easing::STANDARD_ACCELERATE,
-        duration: md::easing::duration::MEDIUM3
-      }.box_it(),
-      from: PressedLayer::show_opacity(),
+fn time_rate(elapsed: Duration, total: Duration) -> f32 {
+  (elapsed.as_secs_f32() / total.as_secs_f32()).min(1.)
+}
+
+impl WrapRender for Ripple {
+  fn perform_layout(&self, clamp: BoxClamp, host: &dyn Render, ctx: &mut LayoutCtx) -> Size {
+    let size = host.perform_layout(clamp, ctx);
+    self.host_size.set(size);
+    size
+  }
+
+  fn paint(&self, host: &dyn Render, ctx: &mut PaintingCtx) {
+    if self.waves.is_empty() {
+      host.paint(ctx);
+      return;
+    }
+
+    // record transform and fill brush for draw ripples used, because the host
+    // widget may change them.
+    let (matrix, fill_brush) = {
+      let painter = ctx.painter();
+      (*painter.transform(), painter.fill_brush().clone())
     };
+    host.paint(ctx);
 
-    watch!(!$ripple_grow.is_running() && !$layer.is_pointer_pressed())
-      .skip(1)
-      .distinct_until_changed()
-      .filter(|fade| *fade)
-      .subscribe(move |_| {
-        $layer.write().hide();
-        fade_out.run();
-      });
-
-    let launcher = move |pos: Option<Point>| {
-      let size = $layer.layout_size();
-      let center = pos.unwrap_or_else(|| {
-        (size / 2.).to_vector().to_point()
-      });
-      let radius = $this.radius.unwrap_or_else(|| {
-        let distance_x = f32::max(center.x , size.width - center.x);
-        let distance_y = f32::max(center.y, size.height - center.y);
-        (distance_x.powf(2.) + distance_y.powf(2.)).sqrt()
-      });
-      let clip = match $this.bounded {
-        RippleBound::Unbounded => None,
-        RippleBound::Bounded => Some(Radius::all(0.)),
-        RippleBound::Radius(radius) => Some(radius),
-      };
-      {
-        let mut layer = $layer.write();
-        layer.area = LayerArea::Circle { center, radius, clip };
-        layer.show();
+    let rect = Rect::from_size(ctx.box_size().unwrap());
+    let painter = ctx.painter();
+    for wave in self.waves.iter() {
+      painter
+        .save()
+        .set_transform(matrix)
+        .set_fill_brush(fill_brush.clone())
+        .apply_alpha(wave.opacity_at(self.now));
+      if let Some(clip) = &wave.clip {
+        painter.clip(Path::rect_round(&rect, clip).into());
       }
-      ripple_grow.run()
+      painter
+        .circle(wave.center, wave.radius_at(self.now))
+        .fill()
+        .restore();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+  use ribir_widgets::prelude::*;
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, ElementState, MouseButton, WindowEvent},
+  };
+
+  use super::*;
+
+  fn ripple_wnd() -> (TestWindow, Stateful<Ripple>) {
+    let ripple = Stateful::new(None);
+    let c_ripple = ripple.clone_writer();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let r = @Ripple { bounded: RippleBound::Bounded };
+        *$c_ripple.write() = Some(r.clone_writer());
+        @ $r { @MockBox { size: Size::new(100., 100.) } }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    let ripple = ripple.write().take().unwrap();
+    (wnd, ripple)
+  }
+
+  fn press(wnd: &mut TestWindow, state: ElementState) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: LogicalPosition::new(20., 20.).to_physical(1.),
+    });
+    wnd.process_mouse_input(device_id, state, MouseButton::Left);
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn ripples_expand_and_fade_out() {
+    reset_test_env!();
+
+    let (mut wnd, ripple) = ripple_wnd();
+    press(&mut wnd, ElementState::Pressed);
+    let radius = || {
+      let ripple = ripple.read();
+      ripple
+        .waves()
+        .iter()
+        .map(|w| w.radius_at(ripple.now))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(ripple.read().waves()[0].center, Point::new(20., 20.));
+    assert_eq!(radius(), [0.]);
+
+    wnd.advance_time(Duration::from_millis(50));
+    wnd.draw_frame();
+    let first = radius()[0];
+    assert!(first > 0.);
+    // The ripple is painted over the host.
+    assert!(!wnd.take_last_frame().unwrap().commands.is_empty());
+
+    // A rapid second tap launches another ripple, the first one keeps growing.
+    press(&mut wnd, ElementState::Released);
+    press(&mut wnd, ElementState::Pressed);
+    press(&mut wnd, ElementState::Released);
+    wnd.advance_time(Duration::from_millis(50));
+    wnd.draw_frame();
+    let radius2 = radius();
+    assert_eq!(radius2.len(), 2);
+    assert!(radius2[0] > first);
+    assert!(radius2[1] > 0. && radius2[1] < radius2[0]);
+
+    // Both ripples are removed after they fade out.
+    for _ in 0..10 {
+      wnd.advance_time(Duration::from_millis(100));
+      wnd.draw_frame();
+    }
+    assert!(ripple.read().waves().is_empty());
+    assert!(wnd.take_last_frame().unwrap().commands.is_empty());
+  }
+
+  #[test]
+  fn launch_manually() {
+    reset_test_env!();
+
+    let (mut wnd, ripple) = ripple_wnd();
+    ripple.read().launch(None);
+    wnd.draw_frame();
+    assert_eq!(ripple.read().waves()[0].center, Point::new(50., 50.));
+    assert!(ripple.read().is_pressed());
+
+    ripple.write().release();
+    for _ in 0..10 {
+      wnd.advance_time(Duration::from_millis(100));
+      wnd.draw_frame();
+    }
+    assert!(ripple.read().waves().is_empty());
+  }
+
+  #[test]
+  fn installed_as_press_feedback() {
+    reset_test_env!();
+
+    AppCtx::set_app_theme(crate::purple::light());
+    let feedback_wnd = || {
+      let mut wnd = TestWindow::new_with_size(
+        fn_widget! {
+          @PressFeedback {
+            clip: Radius::all(0.),
+            @MockBox { size: Size::new(100., 100.) }
+          }
+        },
+        Size::new(100., 100.),
+      );
+      wnd.draw_frame();
+      press(&mut wnd, ElementState::Pressed);
+      wnd.advance_time(Duration::from_millis(50));
+      wnd.draw_frame();
+      wnd.take_last_frame().unwrap()
     };
+    // The ripple is painted over the host.
+    assert!(!feedback_wnd().commands.is_empty());
+
+    // Disabled by replacing the decorator.
+    AppCtx::app_theme()
+      .write()
+      .compose_decorators
+      .override_compose_decorator::<PressFeedback>(|_, host, _| host);
+    assert!(feedback_wnd().commands.is_empty());
+  }
+
+  #[test]
+  fn disable_ripples() {
+    reset_test_env!();
 
-    $this.write().launcher = Some(Box::new(launcher));
+    AppCtx::app_theme()
+      .write()
+      .custom_styles
+      .set_custom_style(RippleStyle { enabled: false });
+    let (mut wnd, ripple) = ripple_wnd();
+    press(&mut wnd, ElementState::Pressed);
+    assert!(ripple.read().waves().is_empty());
   }
 }
@@ -50,17 +50,28 @@ pub fn with_palette(palette: Palette) -> Theme {
     svgs::INDETERMINATE_CHECK_BOX: "./icons/indeterminate_check_box_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::LOGIN: "./icons/login_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::LOGOUT: "./icons/logout_FILL0_wght400_GRAD0_opsz48.svg",
+    svgs::MAXIMIZE: "./icons/crop_square_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::MENU: "./icons/menu_FILL0_wght400_GRAD0_opsz48.svg",
+    svgs::MINIMIZE: "./icons/minimize_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::MORE_HORIZ: "./icons/more_horiz_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::MORE_VERT: "./icons/more_vert_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::OPEN_IN_NEW: "./icons/open_in_new_FILL0_wght400_GRAD0_opsz48.svg",
+    svgs::RESTORE: "./icons/filter_none_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::SEARCH: "./icons/search_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::SETTINGS: "./icons/settings_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::STAR: "./icons/star_FILL0_wght400_GRAD0_opsz48.svg",
     svgs::TEXT_CARET: "./icons/text_caret.svg"
   };
 
-  Theme { palette, classes, typography_theme: typography_theme(), icon_theme, ..Default::default() }
+  let focus_ring = FocusRing { color: palette.primary(), ..Default::default() };
+  Theme {
+    palette,
+    classes,
+    typography_theme: typography_theme(),
+    icon_theme,
+    focus_ring,
+    ..Default::default()
+  }
 }
 
 fn typography_theme() -> TypographyTheme {
@@ -82,6 +93,7 @@ fn typography_theme() -> TypographyTheme {
         letter_space,
         font_face,
         overflow: TextOverflow::Clip,
+        ..<_>::default()
       },
       decoration: TextDecorationStyle {
         decoration: TextDecoration::NONE,
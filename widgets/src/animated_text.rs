@@ -0,0 +1,667 @@
+//! Texts that animate their changes.
+//!
+//! An [`AnimatedText`] fades and slides in the graphemes inserted into its
+//! text and fades out the removed ones, and an [`AnimatedNumber`] counts to
+//! its new value, formatting every frame by the [`NumberFormat`] of its
+//! ancestors.
+//!
+//! ```
+//! # use ribir_core::prelude::*;
+//! # use ribir_widgets::prelude::*;
+//! let visits = Stateful::new(100.);
+//! let _w = fn_widget! {
+//!   @Column {
+//!     @AnimatedText { text: "Visits today" }
+//!     @AnimatedNumber { value: pipe!(*$visits) }
+//!     @FilledButton {
+//!       on_tap: move |_| *$visits.write() += 150.,
+//!       @{ "Visit" }
+//!     }
+//!   }
+//! };
+//! ```
+use std::{cell::Cell, convert::Infallible, rc::Rc, sync::OnceLock};
+
+use ribir_core::{prelude::*, rxrust::ops::box_it::BoxOp};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::layout::*;
+
+/// A text that animates the graphemes that its changes insert and remove, the
+/// inserted graphemes fade and slide in and the removed ones fade out.
+///
+/// The graphemes that both the old and the new text start or end with are
+/// kept, so a text typed in by appending only animates the appended graphemes.
+/// A change in the middle of an animation retargets the graphemes, they don't
+/// wait for the running animation.
+#[derive(Declare)]
+pub struct AnimatedText {
+  pub text: CowArc<str>,
+  /// The transition of the graphemes entering and leaving, the
+  /// `transitions::EASE_OUT` of the theme if it's `None`.
+  #[declare(strict, default)]
+  pub transition: Option<Box<dyn Transition>>,
+}
+
+/// A number that counts to its new value when it changes, the interpolated
+/// value is formatted by the [`NumberFormat`] of its ancestors every frame.
+///
+/// A change in the middle of the counting continues from the number that's
+/// shown, it doesn't jump to the previous target.
+#[derive(Declare)]
+pub struct AnimatedNumber {
+  pub value: f64,
+  /// The count of the digits after the decimal separator, the shown number is
+  /// formatted again when it changes.
+  #[declare(default)]
+  pub decimals: usize,
+  /// The transition of the counting, the `transitions::EASE_OUT` of the theme
+  /// if it's `None`.
+  #[declare(strict, default)]
+  pub transition: Option<Box<dyn Transition>>,
+}
+
+/// How to format a number for a locale, a subtree can use its own format by
+/// providing a `NumberFormat` to it:
+///
+/// ```
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let german = NumberFormat::for_locale("de-DE");
+/// providers![Queryable(german)].with_child(fn_widget! {
+///   @AnimatedNumber { value: 1234.5, decimals: 2_usize }
+/// });
+/// ```
+///
+/// The widgets without a provided format use the format of the system locale,
+/// see [`NumberFormat::system`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+  /// The separator between the groups of the digits of the integer part, no
+  /// grouping if it's `None`.
+  pub group_separator: Option<char>,
+  pub decimal_separator: char,
+  /// The count of the digits in the groups before the last group of three
+  /// digits, it's 2 in the Indian numbering, like `12,34,567`.
+  pub secondary_group_size: usize,
+}
+
+impl Default for NumberFormat {
+  fn default() -> Self { Self::system() }
+}
+
+impl NumberFormat {
+  /// The format that the widget of `ctx` uses, it's provided by its nearest
+  /// ancestor, or the format of the system locale.
+  pub fn of(ctx: &impl ProviderCtx) -> Self {
+    ctx
+      .of::<NumberFormat>()
+      .map_or_else(NumberFormat::system, |f| f.clone())
+  }
+
+  /// The format of the system locale, the locale is read from the `LC_ALL`,
+  /// `LC_NUMERIC` or `LANG` environment variable, and it's `en-US` if none
+  /// of them is set.
+  pub fn system() -> Self {
+    static SYSTEM: OnceLock<NumberFormat> = OnceLock::new();
+    SYSTEM
+      .get_or_init(|| {
+        let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+          .iter()
+          .filter_map(|key| std::env::var(key).ok())
+          .find(|v| !v.is_empty());
+        Self::for_locale(locale.as_deref().unwrap_or("en-US"))
+      })
+      .clone()
+  }
+
+  /// The format of a locale, the `locale` is a language tag like `de-DE`, or
+  /// a POSIX locale like `de_DE.UTF-8`. The locales not known use the format
+  /// of `en-US`.
+  pub fn for_locale(locale: &str) -> Self {
+    let tag = locale
+      .split(['.', '@'])
+      .next()
+      .unwrap_or_default();
+    let mut subtags = tag.split(['-', '_']);
+    let lang = subtags
+      .next()
+      .unwrap_or_default()
+      .to_ascii_lowercase();
+    // Skip the script, such as the `Latn` of `sr-Latn-RS`.
+    let region = subtags
+      .find(|s| s.len() != 4)
+      .map(|s| s.to_ascii_uppercase());
+
+    let (group, decimal, secondary) = match (lang.as_str(), region.as_deref()) {
+      ("de" | "it", Some("CH")) | ("rm", _) => ('\u{2019}', '.', 3),
+      ("de", Some("AT")) | ("pt", Some("PT")) => ('\u{a0}', ',', 3),
+      ("es", Some("MX" | "US")) => (',', '.', 3),
+      ("en", Some("IN")) | ("hi" | "bn" | "mr" | "gu" | "pa" | "te" | "kn" | "ml", _) => {
+        (',', '.', 2)
+      }
+      (
+        "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" | "ro" | "hr" | "sl" | "sr"
+        | "vi" | "ca" | "is",
+        _,
+      ) => ('.', ',', 3),
+      ("fr", _) => ('\u{202f}', ',', 3),
+      (
+        "ru" | "uk" | "be" | "pl" | "cs" | "sk" | "fi" | "sv" | "nb" | "nn" | "no" | "hu" | "bg"
+        | "lt" | "lv" | "et" | "kk",
+        _,
+      ) => ('\u{a0}', ',', 3),
+      _ => (',', '.', 3),
+    };
+    Self {
+      group_separator: Some(group),
+      decimal_separator: decimal,
+      secondary_group_size: secondary,
+    }
+  }
+
+  /// Format the `value` with `decimals` digits after the decimal separator.
+  pub fn format(&self, value: f64, decimals: usize) -> String {
+    let digits = format!("{:.decimals$}", value.abs());
+    let (int, frac) = digits
+      .split_once('.')
+      .map_or((digits.as_str(), None), |(int, frac)| (int, Some(frac)));
+
+    let mut label = String::with_capacity(digits.len() + int.len() / 2 + 1);
+    // Avoid a label of `-0` for a value like `-0.0001`.
+    if value.is_sign_negative()
+      && digits
+        .chars()
+        .any(|c| c.is_ascii_digit() && c != '0')
+    {
+      label.push('-');
+    }
+    let secondary = self.secondary_group_size.max(1);
+    for (i, c) in int.chars().enumerate() {
+      // The count of the digits after the separator.
+      let after = int.len() - i;
+      if i > 0 && (after == 3 || (after > 3 && (after - 3) % secondary == 0)) {
+        if let Some(sep) = self.group_separator {
+          label.push(sep);
+        }
+      }
+      label.push(c);
+    }
+    if let Some(frac) = frac {
+      label.push(self.decimal_separator);
+      label.push_str(frac);
+    }
+    label
+  }
+}
+
+impl Compose for AnimatedText {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    fn_widget! {
+      let transition = $this.silent().transition.take();
+      let transition =
+        Sc::new(transition.unwrap_or_else(|| transitions::EASE_OUT.of(BuildCtx::get())));
+      let duration = transition.duration();
+
+      // Every grapheme starts in a segment of its own, and the graphemes
+      // inserted later join the segment where they're inserted, so a change only
+      // rebuilds the segments that it inserts to or removes from.
+      let mut segments: Vec<_> = $this
+        .text
+        .graphemes(true)
+        .enumerate()
+        .map(|(key, g)| Stateful::new(vec![Glyph::new(key, g, 1.)]))
+        .collect();
+      if segments.is_empty() {
+        segments.push(Stateful::new(vec![]));
+      }
+      let segments = Rc::new(segments);
+      let mut next_key = segments.len();
+      let c_segments = segments.clone();
+      let u = watch!($this.text.clone()).subscribe(move |text| {
+        let shown: Vec<_> = c_segments
+          .iter()
+          .flat_map(|seg| {
+            seg
+              .read()
+              .iter()
+              .filter(|g| g.target > 0.)
+              .map(|g| g.grapheme.clone())
+              .collect::<Vec<_>>()
+          })
+          .collect();
+        let unchanged = shown.iter().map(|g| &**g).eq(text.graphemes(true));
+        if unchanged {
+          return;
+        }
+        let leaving = retarget_glyphs(&c_segments, &text, &mut next_key);
+        if !leaving.is_empty() {
+          let c_segments = c_segments.clone();
+          observable::timer((), duration, AppCtx::scheduler()).subscribe(move |_| {
+            for seg in c_segments.iter() {
+              if seg.read().iter().any(|g| leaving.contains(&g.key)) {
+                seg.write().retain(|g| !leaving.contains(&g.key));
+              }
+            }
+          });
+        }
+      });
+
+      let mut row = @Row {
+        semantics_label: pipe!($this.text.clone()),
+        on_disposed: move |_| u.unsubscribe(),
+      }
+      .with_child(Vec::<Widget>::new());
+      for seg in segments.iter() {
+        let seg = seg.clone_writer();
+        let transition = transition.clone();
+        row = row.with_child(pipe!($seg;).map(move |_| {
+          seg.read().iter().map(|g| g.widget(&transition)).collect::<Vec<_>>()
+        }));
+      }
+      row
+    }
+    .into_widget()
+  }
+}
+
+impl Compose for AnimatedNumber {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    fn_widget! {
+      let transition = $this.silent().transition.take();
+      let transition = transition.unwrap_or_else(|| transitions::EASE_OUT.of(BuildCtx::get()));
+      let format = NumberFormat::of(BuildCtx::get());
+
+      let value = $this.value;
+      let text = @Text { text: format.format(value, $this.decimals) };
+      let reader = this.clone_reader();
+      let state = NumberTextState {
+        number: Stateful::new(value),
+        text: text.clone_writer(),
+        label: Rc::new(move |v| format.format(v, reader.read().decimals)),
+      };
+      let c_state = state.clone_setter();
+      let decimals_state = state.clone_setter();
+      let animate = @Animate { transition, state, from: value };
+
+      let u = watch!($this.value).subscribe(move |v| {
+        let from = c_state.get();
+        if from == v {
+          return;
+        }
+        // The animate reads the target from the state, it continues from the
+        // shown number if it's running.
+        c_state.revert_value(v);
+        $animate.write().from = from;
+        animate.run();
+        if !animate.is_running() {
+          c_state.show(v);
+        }
+      });
+      let u2 = watch!($this.decimals)
+        .distinct_until_changed()
+        .subscribe(move |_| decimals_state.show(decimals_state.get()));
+      @$text {
+        on_disposed: move |_| {
+          u.unsubscribe();
+          u2.unsubscribe();
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+/// A grapheme of an [`AnimatedText`], it's leaving if its target presence is
+/// zero.
+struct Glyph {
+  key: usize,
+  grapheme: CowArc<str>,
+  presence: Stateful<GlyphPresence>,
+  target: f32,
+  /// If the transition of the presence is set up, it's set up when the glyph
+  /// is built the first time.
+  animated: Cell<bool>,
+}
+
+impl Glyph {
+  fn new(key: usize, grapheme: &str, presence: f32) -> Self {
+    Self {
+      key,
+      grapheme: grapheme.to_string().into(),
+      presence: Stateful::new(GlyphPresence { presence }),
+      target: 1.,
+      animated: Cell::new(false),
+    }
+  }
+
+  fn widget(&self, transition: &Sc<Box<dyn Transition>>) -> Widget<'static> {
+    if !self.animated.replace(true) {
+      self
+        .presence
+        .clone_writer()
+        .transition(transition.clone());
+    }
+    if self.presence.read().presence != self.target {
+      self.presence.write().presence = self.target;
+    }
+    let presence = self.presence.clone_writer();
+    let grapheme = self.grapheme.clone();
+    fn_widget! {
+      @$presence { @Text { text: grapheme } }
+    }
+    .into_widget()
+  }
+}
+
+/// Retarget the glyphs in the `segments` to the `text`, the glyphs between the
+/// common prefix and suffix start to leave, and the new graphemes are inserted
+/// after them. Return the keys of the glyphs that start to leave.
+fn retarget_glyphs(
+  segments: &[Stateful<Vec<Glyph>>], text: &str, next_key: &mut usize,
+) -> Vec<usize> {
+  // The positions of the shown glyphs, by the segment and the index in it.
+  let mut shown = vec![];
+  let mut graphemes = vec![];
+  for (s, seg) in segments.iter().enumerate() {
+    for (i, g) in seg.read().iter().enumerate() {
+      if g.target > 0. {
+        shown.push((s, i));
+        graphemes.push(g.grapheme.clone());
+      }
+    }
+  }
+  let new: Vec<&str> = text.graphemes(true).collect();
+  let same = |a: usize, b: &str| &*graphemes[a] == b;
+
+  let prefix = (0..shown.len().min(new.len()))
+    .take_while(|i| same(*i, new[*i]))
+    .count();
+  let suffix = (0..(shown.len() - prefix).min(new.len() - prefix))
+    .take_while(|i| same(shown.len() - 1 - i, new[new.len() - 1 - i]))
+    .count();
+
+  let leaving = &shown[prefix..shown.len() - suffix];
+  let leaving_keys = leaving
+    .iter()
+    .map(|(s, i)| {
+      // Only the presence changes, the segment doesn't need to rebuild.
+      let mut seg = segments[*s].silent();
+      let g = &mut seg[*i];
+      g.target = 0.;
+      if g.animated.get() {
+        g.presence.write().presence = 0.;
+      }
+      g.key
+    })
+    .collect();
+
+  // Insert after the leaving glyphs, or where the kept prefix ends.
+  let (seg, at) = leaving
+    .last()
+    .or(prefix.checked_sub(1).map(|i| &shown[i]))
+    .map_or((0, 0), |(s, i)| (*s, i + 1));
+  let inserted: Vec<_> = new[prefix..new.len() - suffix]
+    .iter()
+    .map(|g| {
+      *next_key += 1;
+      Glyph::new(*next_key, g, 0.)
+    })
+    .collect();
+  if !inserted.is_empty() {
+    segments[seg].write().splice(at..at, inserted);
+  }
+
+  leaving_keys
+}
+
+/// The distance that a glyph slides when it enters or leaves.
+const GLYPH_SLIDE: f32 = 8.;
+
+/// Fades, slides and narrows its child by how much the glyph is present.
+#[derive(SingleChild, Clone, PartialEq)]
+struct GlyphPresence {
+  presence: f32,
+}
+
+impl GlyphPresence {
+  fn slide(&self) -> Transform { Transform::translation(0., (1. - self.presence) * GLYPH_SLIDE) }
+}
+
+impl Lerp for GlyphPresence {
+  fn lerp(&self, to: &Self, factor: f32) -> Self {
+    Self { presence: self.presence.lerp(&to.presence, factor) }
+  }
+}
+
+impl Render for GlyphPresence {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let size = ctx.assert_perform_single_child_layout(clamp);
+    Size::new(size.width * self.presence, size.height)
+  }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    ctx
+      .painter()
+      .apply_alpha(self.presence)
+      .apply_transform(&self.slide());
+  }
+
+  fn get_transform(&self) -> Option<Transform> { Some(self.slide()) }
+
+  fn get_opacity(&self) -> f32 { self.presence }
+}
+
+/// The animate state of an [`AnimatedNumber`], it formats the number to the
+/// text whenever the number is set, so every frame shows the interpolated
+/// number.
+struct NumberTextState {
+  number: Stateful<f64>,
+  text: Stateful<Text>,
+  label: Rc<dyn Fn(f64) -> String>,
+}
+
+impl NumberTextState {
+  fn show(&self, v: f64) { self.text.write().text = (self.label)(v).into(); }
+}
+
+impl AnimateStateSetter for NumberTextState {
+  type C = Self;
+  type Value = f64;
+
+  fn get(&self) -> f64 { *self.number.read() }
+
+  fn set(&self, v: f64) {
+    *self.number.shallow() = v;
+    self.show(v);
+  }
+
+  fn revert_value(&self, v: f64) {
+    // The text keeps the last shown number.
+    let mut w = self.number.write();
+    *w = v;
+    w.forget_modifies();
+  }
+
+  fn animate_state_modifies(&self) -> BoxOp<'static, ModifyScope, Infallible> {
+    self.number.animate_state_modifies()
+  }
+
+  fn clone_setter(&self) -> Self {
+    Self {
+      number: self.number.clone_writer(),
+      text: self.text.clone_writer(),
+      label: self.label.clone(),
+    }
+  }
+}
+
+impl AnimateState for NumberTextState {
+  fn calc_lerp_value(&mut self, from: &f64, to: &f64, rate: f32) -> f64 { from.lerp(to, rate) }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*, window::WindowFlags};
+
+  use super::*;
+
+  fn linear() -> Option<Box<dyn Transition>> {
+    Some(Box::new(EasingTransition {
+      duration: Duration::from_millis(100),
+      easing: easing::LINEAR,
+    }))
+  }
+
+  /// The texts that the window shows.
+  fn texts(wnd: &TestWindow) -> Vec<String> {
+    let root = wnd.tree_dump().nodes[0].id;
+    let texts = wnd.accessible_name(root).unwrap_or_default();
+    texts.split(' ').map(str::to_string).collect()
+  }
+
+  #[test]
+  fn format_number() {
+    let en = NumberFormat::for_locale("en-US");
+    assert_eq!(en.format(1234567.891, 2), "1,234,567.89");
+    assert_eq!(en.format(999., 0), "999");
+    assert_eq!(en.format(-1000., 0), "-1,000");
+    assert_eq!(en.format(-0.0001, 1), "0.0");
+
+    let german = NumberFormat::for_locale("de_DE.UTF-8");
+    assert_eq!(german.format(1234.5, 2), "1.234,50");
+    let swiss = NumberFormat::for_locale("de-CH");
+    assert_eq!(swiss.format(1234.5, 1), "1\u{2019}234.5");
+    let french = NumberFormat::for_locale("fr-FR");
+    assert_eq!(french.format(1234.5, 1), "1\u{202f}234,5");
+    let indian = NumberFormat::for_locale("en-IN");
+    assert_eq!(indian.format(1234567., 0), "12,34,567");
+    assert_eq!(indian.format(123., 0), "123");
+    assert_eq!(NumberFormat::for_locale("C"), en);
+
+    let plain =
+      NumberFormat { group_separator: None, decimal_separator: '.', secondary_group_size: 3 };
+    assert_eq!(plain.format(1234.5, 1), "1234.5");
+  }
+
+  #[test]
+  fn reformat_when_decimals_change() {
+    reset_test_env!();
+
+    let (decimals, w_decimals) = split_value(0_usize);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let decimals = decimals.clone_watcher();
+      providers![Queryable(NumberFormat::for_locale("en-US"))].with_child(fn_widget! {
+        @MockBox {
+          size: Size::new(100., 20.),
+          @AnimatedNumber { value: 1234.5678, decimals: pipe!(*$decimals) }
+        }
+      })
+    });
+    wnd.draw_frame();
+    assert_eq!(texts(&wnd), ["1,235"]);
+
+    *w_decimals.write() = 2;
+    wnd.draw_frame();
+    assert_eq!(texts(&wnd), ["1,234.57"]);
+  }
+
+  #[test]
+  fn count_up_and_retarget() {
+    reset_test_env!();
+
+    let (value, w_value) = split_value(1000.);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let value = value.clone_watcher();
+      providers![Queryable(NumberFormat::for_locale("en-US"))].with_child(fn_widget! {
+        @MockBox {
+          size: Size::new(100., 20.),
+          @AnimatedNumber { value: pipe!(*$value), transition: linear() }
+        }
+      })
+    });
+    wnd.set_flags(WindowFlags::ANIMATIONS);
+    wnd.draw_frame();
+    assert_eq!(texts(&wnd), ["1,000"]);
+
+    *w_value.write() = 2000.;
+    wnd.draw_frame();
+    assert_eq!(texts(&wnd), ["1,000"]);
+    wnd.advance_time(Duration::from_millis(50));
+    assert_eq!(texts(&wnd), ["1,500"]);
+
+    // Retarget in the middle, it continues from the shown number.
+    *w_value.write() = 5000.;
+    wnd.draw_frame();
+    assert_eq!(texts(&wnd), ["1,500"]);
+    wnd.advance_time(Duration::from_millis(50));
+    assert_eq!(texts(&wnd), ["3,250"]);
+    wnd.advance_time(Duration::from_millis(60));
+    assert_eq!(texts(&wnd), ["5,000"]);
+  }
+
+  #[test]
+  fn graphemes_enter_and_leave() {
+    reset_test_env!();
+
+    let (text, w_text) = split_value("abc");
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 20.),
+        @AnimatedText { text: pipe!(*$text), transition: linear() }
+      }
+    });
+    wnd.set_flags(WindowFlags::ANIMATIONS);
+    wnd.draw_frame();
+    assert_eq!(texts(&wnd), ["a", "b", "c"]);
+
+    // The removed grapheme leaves before the inserted one enters.
+    *w_text.write() = "abd";
+    wnd.draw_frame();
+    assert_eq!(texts(&wnd), ["a", "b", "c", "d"]);
+
+    // Retarget the entering grapheme to leave.
+    wnd.advance_time(Duration::from_millis(50));
+    *w_text.write() = "ab";
+    wnd.draw_frame();
+    assert_eq!(texts(&wnd), ["a", "b", "c", "d"]);
+
+    wnd.advance_time(Duration::from_millis(60));
+    assert_eq!(texts(&wnd), ["a", "b", "d"]);
+    wnd.advance_time(Duration::from_millis(60));
+    assert_eq!(texts(&wnd), ["a", "b"]);
+  }
+
+  #[test]
+  fn keep_unchanged_glyphs() {
+    reset_test_env!();
+
+    let (text, w_text) = split_value("abc");
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 20.),
+        @AnimatedText { text: pipe!(*$text), transition: linear() }
+      }
+    });
+    wnd.set_flags(WindowFlags::ANIMATIONS);
+    wnd.draw_frame();
+    let glyphs = |wnd: &TestWindow| {
+      wnd
+        .tree_dump()
+        .nodes
+        .into_iter()
+        .filter(|n| n.name.ends_with("Text"))
+        .map(|n| n.id)
+        .collect::<Vec<_>>()
+    };
+    let before = glyphs(&wnd);
+    assert_eq!(before.len(), 3);
+
+    // Only the glyph that changes is built again.
+    *w_text.write() = "abd";
+    wnd.draw_frame();
+    let after = glyphs(&wnd);
+    assert_eq!(after.len(), 4);
+    assert_eq!(after[..2], before[..2]);
+  }
+}
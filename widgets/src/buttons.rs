@@ -289,7 +289,7 @@ impl<'c> ButtonChild<'c> {
     [btn, btn_leading_icon, btn_trialing_icon, btn_label, icon_only, label_only]: [ClassName; 6],
   ) -> Widget<'c> {
     let Self { label, icon } = self;
-    match (label, icon) {
+    let button = match (label, icon) {
       (None, None) => void!( class: btn ).into_widget(),
       (None, Some(icon)) => fat_obj! {
         class: icon_only,
@@ -314,14 +314,22 @@ impl<'c> ButtonChild<'c> {
         }
       }
       .into_widget(),
+    };
+    rdl! {
+      @TouchTarget {
+        on_tap: move |e| SoundTheme::play(e, SoundEvent::Press),
+        @ { button }
+      }
     }
+    .into_widget()
   }
 }
 
 #[cfg(test)]
 mod tests {
-  use ribir_core::test_helper::*;
+  use ribir_core::{reset_test_env, sound::MockSoundPlayer, test_helper::*};
   use ribir_dev_helper::*;
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 
   use super::*;
   use crate::prelude::*;
@@ -489,4 +497,36 @@ mod tests {
     })
     .with_wnd_size(Size::new(640., 256.)),
   );
+
+  fn tap_twice(enabled: bool) -> Vec<SoundId> {
+    reset_test_env!();
+
+    let player = MockSoundPlayer::default();
+    AppCtx::set_sound_player(Box::new(player.clone()));
+    let click = AppCtx::load_sound(vec![0; 4]);
+    let mut theme = AppCtx::app_theme().write();
+    theme.sound_theme.set(SoundEvent::Press, click);
+    theme.sound_theme.enabled = enabled;
+    drop(theme);
+
+    let w = fn_widget! { @FilledButton { @{ "Press me" } } };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (10., 10.).into() });
+    for _ in 0..2 {
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.draw_frame();
+    }
+    player.played()
+  }
+
+  #[test]
+  fn press_sound() {
+    assert_eq!(tap_twice(true), [SoundId(0), SoundId(0)]);
+    assert!(tap_twice(false).is_empty());
+  }
 }
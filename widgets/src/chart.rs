@@ -0,0 +1,368 @@
+//! Minimal charting widgets.
+//!
+//! A [`ChartFrame`] lays out the axes and the plot area, and the series
+//! composed inside it paint their data in the plot area.
+//!
+//! ```
+//! # use ribir_core::prelude::*;
+//! # use ribir_widgets::prelude::*;
+//! let visits = Stateful::new(vec![(0., 3.), (1., 5.), (2., 4.), (3., 7.)]);
+//! let _chart = fn_widget! {
+//!   @ChartFrame {
+//!     x_axis: Axis::new(0., 3.),
+//!     y_axis: Axis::new(0., 8.),
+//!     @BarSeries { data: visits.clone_writer() }
+//!     @LineSeries {
+//!       data: visits.clone_writer(),
+//!       transition: Some(Box::new(EasingTransition {
+//!         easing: easing::EASE_IN_OUT,
+//!         duration: Duration::from_millis(300),
+//!       }) as Box<dyn Transition>),
+//!     }
+//!   }
+//! };
+//! ```
+use ribir_core::prelude::*;
+
+mod axis;
+mod series;
+pub use axis::*;
+pub use series::*;
+use series::{SeriesKind, SeriesPaint, lerp_points, map_point};
+
+/// A frame of a chart, it draws the axes with the tick labels around the plot
+/// area, and places the series in the plot area. Hovering the plot area shows
+/// a tooltip of the nearest data point.
+///
+/// The frame fills the space its parent gives.
+#[simple_declare]
+pub struct ChartFrame {
+  pub x_axis: Axis,
+  pub y_axis: Axis,
+  /// The space between the plot area and the bounds of the frame, the tick
+  /// labels are placed in it.
+  #[declare(default = EdgeInsets::new(8., 16., 24., 40.))]
+  pub plot_padding: EdgeInsets,
+  #[declare(default = Palette::of(BuildCtx::get()).outline())]
+  pub axis_color: Color,
+}
+
+impl ChartFrame {
+  /// The plot area in a frame of `size`.
+  pub fn plot_rect(&self, size: Size) -> Rect { plot_rect(&self.plot_padding, size) }
+
+  /// Map a data point to the position in the plot area of `plot_size`.
+  pub fn map_point(&self, point: (f64, f64), plot_size: Size) -> Point {
+    map_point(&self.x_axis, &self.y_axis, point, plot_size)
+  }
+
+  /// The data point nearest to the position in the plot area of `plot_size`.
+  pub fn nearest_point<'a>(
+    &self, data: impl IntoIterator<Item = &'a (f64, f64)>, pos: Point, plot_size: Size,
+  ) -> Option<(f64, f64)> {
+    data
+      .into_iter()
+      .map(|p| (*p, self.map_point(*p, plot_size).distance_to(pos)))
+      .min_by(|a, b| a.1.total_cmp(&b.1))
+      .map(|(p, _)| p)
+  }
+
+  fn tooltip(&self, (x, y): (f64, f64)) -> String {
+    format!("{}, {}", self.x_axis.format(x), self.y_axis.format(y))
+  }
+}
+
+impl ComposeChild<'static> for ChartFrame {
+  type Child = Vec<ChartSeries>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'static> {
+    fn_widget! {
+      let mut all_data = vec![];
+      let series = child.into_iter().map(|s| {
+        let (data, kind, transition) = s.into_parts();
+        all_data.push(data.clone_watcher());
+        series_widget(&this, data, kind, transition)
+      }).collect::<Vec<_>>();
+
+      let hovered = Stateful::new(None);
+      let mut plot = @PlotArea {};
+      let tooltip = Overlay::new(
+        move || {
+          let hovered = *$hovered;
+          let text = hovered.map_or_else(String::new, |p| $this.tooltip(p));
+          @Text { text, class: TOOLTIPS }.into_widget()
+        },
+        OverlayStyle { auto_close_policy: AutoClosePolicy::NOT_AUTO_CLOSE, mask: None }
+      ).with_host($plot.track_id());
+      let c_tooltip = tooltip.clone();
+
+      @ChartLayout {
+        x_axis: pipe!($this.x_axis.clone()),
+        y_axis: pipe!($this.y_axis.clone()),
+        plot_padding: pipe!($this.plot_padding),
+        axis_color: pipe!($this.axis_color),
+        @Clip {
+          @$plot {
+            on_pointer_move: move |e| {
+              let size = $plot.layout_size();
+              let pos = e.position();
+              let nearest = all_data
+                .iter()
+                .filter_map(|d| $this.nearest_point(d.read().iter(), pos, size))
+                .min_by(|a, b| {
+                  let a = $this.map_point(*a, size).distance_to(pos);
+                  let b = $this.map_point(*b, size).distance_to(pos);
+                  a.total_cmp(&b)
+                });
+              if *$hovered != nearest {
+                *$hovered.write() = nearest;
+                tooltip.close();
+                if let Some(p) = nearest {
+                  let at = e.map_to_global($this.map_point(p, size)) + Vector::new(8., 8.);
+                  tooltip.show_at(at, e.window());
+                }
+              }
+            },
+            on_pointer_leave: move |_| {
+              *$hovered.write() = None;
+              c_tooltip.close();
+            },
+            @ { series }
+          }
+        }
+        @ {
+          pipe!($this.x_axis.clone()).map(move |axis| {
+            axis.ticks().into_iter().map(|t| @Text { text: axis.format(t) }).collect::<Vec<_>>()
+          })
+        }
+        @ {
+          pipe!($this.y_axis.clone()).map(move |axis| {
+            axis.ticks().into_iter().map(|t| @Text { text: axis.format(t) }).collect::<Vec<_>>()
+          })
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+fn series_widget(
+  frame: &impl StateWriter<Value = ChartFrame>, data: SeriesData, kind: SeriesKind,
+  transition: Option<Box<dyn Transition>>,
+) -> Widget<'static> {
+  let this = frame.clone_watcher();
+  fn_widget! {
+    let paint = @SeriesPaint {
+      kind,
+      x_axis: pipe!($this.x_axis.clone()),
+      y_axis: pipe!($this.y_axis.clone()),
+      points: $data.clone(),
+    };
+    if let Some(transition) = transition {
+      let points = paint.clone_writer().map_writer(|w| PartData::from_ref(&w.points));
+      LerpFnState::new(points, |from: &Vec<_>, to: &Vec<_>, rate| lerp_points(from, to, rate))
+        .transition(transition);
+    }
+    let u = watch!($data.clone()).subscribe(move |v| $paint.write().points = v);
+    @$paint { on_disposed: move |_| u.unsubscribe() }
+  }
+  .into_widget()
+}
+
+/// Lay out the plot area and the tick labels of a chart, the first child is
+/// the plot area, then the labels of the x axis and the labels of the y axis.
+#[derive(Declare, MultiChild)]
+struct ChartLayout {
+  x_axis: Axis,
+  y_axis: Axis,
+  plot_padding: EdgeInsets,
+  axis_color: Color,
+}
+
+/// The plot area of a chart, all the series fill it.
+#[derive(Declare, MultiChild)]
+struct PlotArea {}
+
+impl Render for PlotArea {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let (ctx, children) = ctx.split_children();
+    for c in children {
+      ctx.perform_child_layout(c, BoxClamp { min: clamp.max, max: clamp.max });
+    }
+    clamp.max
+  }
+
+  fn only_sized_by_parent(&self) -> bool { true }
+}
+
+/// The size of the chart if its parent doesn't limit it.
+const DEFAULT_CHART_SIZE: Size = Size::new(320., 240.);
+/// The length of the tick marks and the gap between them and the labels.
+const TICK_SIZE: f32 = 4.;
+
+fn plot_rect(padding: &EdgeInsets, size: Size) -> Rect {
+  let EdgeInsets { left, right, bottom, top, .. } = *padding;
+  let size = Size::new(size.width - left - right, size.height - top - bottom);
+  Rect::new(Point::new(left, top), size.max(Size::zero()))
+}
+
+impl Render for ChartLayout {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let size = Size::new(
+      if clamp.max.width.is_finite() { clamp.max.width } else { DEFAULT_CHART_SIZE.width },
+      if clamp.max.height.is_finite() { clamp.max.height } else { DEFAULT_CHART_SIZE.height },
+    );
+    let size = clamp.clamp(size);
+    let plot = plot_rect(&self.plot_padding, size);
+
+    let (ctx, mut children) = ctx.split_children();
+    if let Some(area) = children.next() {
+      ctx.perform_child_layout(area, BoxClamp { min: plot.size, max: plot.size });
+      ctx.update_position(area, plot.origin);
+    }
+
+    let label_clamp = BoxClamp { min: Size::zero(), max: size };
+    let x_ticks = self.x_axis.ticks();
+    let y_ticks = self.y_axis.ticks();
+    let x_labels = x_ticks.iter().map(|t| (true, *t));
+    let y_labels = y_ticks.iter().map(|t| (false, *t));
+    for ((is_x, t), label) in x_labels.chain(y_labels).zip(children) {
+      let label_size = ctx.perform_child_layout(label, label_clamp);
+      let pos = if is_x {
+        let x = plot.min_x() + self.x_axis.map(t, plot.width());
+        Point::new(x - label_size.width / 2., plot.max_y() + TICK_SIZE * 2.)
+      } else {
+        let y = plot.max_y() - self.y_axis.map(t, plot.height());
+        Point::new(plot.min_x() - TICK_SIZE * 2. - label_size.width, y - label_size.height / 2.)
+      };
+      ctx.update_position(label, pos);
+    }
+
+    size
+  }
+
+  fn only_sized_by_parent(&self) -> bool { true }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let plot = plot_rect(&self.plot_padding, ctx.box_size().unwrap());
+    let painter = ctx.painter();
+    painter
+      .set_stroke_brush(self.axis_color)
+      .set_line_width(1.)
+      .begin_path(Point::new(plot.min_x(), plot.min_y()))
+      .line_to(Point::new(plot.min_x(), plot.max_y()))
+      .line_to(Point::new(plot.max_x(), plot.max_y()))
+      .end_path(false);
+    for t in self.x_axis.ticks() {
+      let x = plot.min_x() + self.x_axis.map(t, plot.width());
+      painter
+        .begin_path(Point::new(x, plot.max_y()))
+        .line_to(Point::new(x, plot.max_y() + TICK_SIZE))
+        .end_path(false);
+    }
+    for t in self.y_axis.ticks() {
+      let y = plot.max_y() - self.y_axis.map(t, plot.height());
+      painter
+        .begin_path(Point::new(plot.min_x() - TICK_SIZE, y))
+        .line_to(Point::new(plot.min_x(), y))
+        .end_path(false);
+    }
+    painter.stroke();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+
+  use super::*;
+  use crate::prelude::*;
+
+  fn frame() -> ChartFrame {
+    ChartFrame {
+      x_axis: Axis::new(0., 10.),
+      y_axis: Axis::new(-50., 50.),
+      plot_padding: EdgeInsets::new(10., 10., 20., 40.),
+      axis_color: Color::BLACK,
+    }
+  }
+
+  #[test]
+  fn map_known_points() {
+    let frame = frame();
+    let plot = frame.plot_rect(Size::new(250., 130.));
+    assert_eq!(plot, Rect::new(Point::new(40., 10.), Size::new(200., 100.)));
+
+    assert_eq!(frame.map_point((0., -50.), plot.size), Point::new(0., 100.));
+    assert_eq!(frame.map_point((10., 50.), plot.size), Point::new(200., 0.));
+    assert_eq!(frame.map_point((2.5, 0.), plot.size), Point::new(50., 50.));
+    assert_eq!(frame.map_point((5., 25.), plot.size), Point::new(100., 25.));
+
+    let data = [(0., 0.), (5., 25.), (10., -50.)];
+    let nearest = frame.nearest_point(&data, Point::new(90., 30.), plot.size);
+    assert_eq!(nearest, Some((5., 25.)));
+  }
+
+  #[test]
+  fn plot_area_and_labels() {
+    reset_test_env!();
+
+    let data = Stateful::new(vec![(0., 0.), (5., 25.)]);
+    let c_data = data.clone_writer();
+    let w = fn_widget! {
+      let linear: Box<dyn Transition> = Box::new(transitions::LINEAR.of(BuildCtx::get()));
+      @SizedBox {
+        size: Size::new(250., 130.),
+        @ChartFrame {
+          x_axis: Axis::new(0., 10.),
+          y_axis: Axis::new(-50., 50.),
+          plot_padding: EdgeInsets::new(10., 10., 20., 40.),
+          @LineSeries {
+            data: c_data.clone_writer(),
+            transition: Some(linear),
+          }
+          @BarSeries { data: c_data.clone_writer() }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    // The plot area.
+    LayoutCase::expect_rect(
+      &wnd,
+      &[0, 0, 0],
+      Rect::new(Point::new(40., 10.), Size::new(200., 100.)),
+    );
+    // The series fill the plot area.
+    LayoutCase::expect_size(&wnd, &[0, 0, 0, 0, 0], Size::new(200., 100.));
+    LayoutCase::expect_size(&wnd, &[0, 0, 0, 0, 1], Size::new(200., 100.));
+
+    // The label of `5` on the x axis is centered below the tick.
+    let x_ticks = Axis::new(0., 10.).ticks();
+    assert_eq!(x_ticks, vec![0., 2., 4., 6., 8., 10.]);
+    let label = wnd.layout_info_by_path(&[0, 0, 3]).unwrap();
+    let size = label.size.unwrap();
+    assert_eq!(label.pos.x + size.width / 2., 40. + 80.);
+    assert_eq!(label.pos.y, 110. + TICK_SIZE * 2.);
+
+    // The label of `0` on the y axis is at the middle of the plot area.
+    let y_ticks = Axis::new(-50., 50.).ticks();
+    let zero = y_ticks.iter().position(|t| *t == 0.).unwrap();
+    let label = wnd
+      .layout_info_by_path(&[0, 0, 1 + x_ticks.len() + zero])
+      .unwrap();
+    let size = label.size.unwrap();
+    assert_eq!(label.pos.y + size.height / 2., 60.);
+    assert_eq!(label.pos.x + size.width, 40. - TICK_SIZE * 2.);
+
+    // Update the data, the line animates to the new points.
+    data.write()[1] = (5., -25.);
+    wnd.draw_frame();
+    wnd.advance_time(Duration::from_secs(1));
+    wnd.draw_frame();
+    data.write().push((10., 50.));
+    wnd.draw_frame();
+    LayoutCase::expect_size(&wnd, &[0, 0, 0, 0, 0], Size::new(200., 100.));
+  }
+}
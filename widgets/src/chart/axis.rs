@@ -0,0 +1,160 @@
+/// A linear scale that maps the data values in the range of `min..=max` to
+/// pixels, and generates the ticks at "nice" values, the steps between the
+/// ticks are always 1, 2 or 5 times a power of ten.
+///
+/// ```
+/// use ribir_widgets::prelude::*;
+///
+/// let axis = Axis::new(0., 7.).with_tick_count(3);
+/// assert_eq!(axis.ticks(), vec![0., 2., 4., 6.]);
+/// assert_eq!(axis.map(3.5, 100.), 50.);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+  /// The data value at the start of the axis.
+  pub min: f64,
+  /// The data value at the end of the axis.
+  pub max: f64,
+  /// The desired count of the ticks, the real count may be a little different
+  /// to make the ticks land on nice values.
+  pub tick_count: usize,
+}
+
+impl Axis {
+  pub fn new(min: f64, max: f64) -> Self { Self { min, max, tick_count: 5 } }
+
+  pub fn with_tick_count(mut self, tick_count: usize) -> Self {
+    self.tick_count = tick_count;
+    self
+  }
+
+  /// Map a data value to the offset from the start of the axis, the axis is
+  /// `len` pixels long.
+  pub fn map(&self, value: f64, len: f32) -> f32 {
+    let range = self.max - self.min;
+    if range == 0. { 0. } else { ((value - self.min) / range * len as f64) as f32 }
+  }
+
+  /// Map the offset from the start of the axis back to the data value, the
+  /// opposite of [`Axis::map`].
+  pub fn invert(&self, offset: f32, len: f32) -> f64 {
+    if len == 0. { self.min } else { self.min + offset as f64 / len as f64 * (self.max - self.min) }
+  }
+
+  /// The values of the ticks in the range of the axis, in ascending order.
+  pub fn ticks(&self) -> Vec<f64> {
+    let (lo, hi) = self.bounds();
+    let Some(step) = self.step() else { return vec![lo] };
+    let first = (lo / step.value()).ceil() as i64;
+    let last = (hi / step.value()).floor() as i64;
+    (first..=last).map(|i| step.at(i)).collect()
+  }
+
+  /// Format a value as a tick label, with as many decimals as the step of the
+  /// ticks needs.
+  pub fn format(&self, value: f64) -> String {
+    let decimals = self
+      .step()
+      .map_or(0, |s| (-s.exp).max(0) as usize);
+    let label = format!("{value:.decimals$}");
+    // Avoid a label of `-0` for a value like `-0.0001`.
+    if label
+      .trim_start_matches('-')
+      .chars()
+      .all(|c| c == '0' || c == '.')
+    {
+      label.trim_start_matches('-').to_string()
+    } else {
+      label
+    }
+  }
+
+  fn bounds(&self) -> (f64, f64) { (self.min.min(self.max), self.min.max(self.max)) }
+
+  fn step(&self) -> Option<TickStep> {
+    let (lo, hi) = self.bounds();
+    let range = hi - lo;
+    if range <= 0. || !range.is_finite() {
+      return None;
+    }
+    let raw = range / self.tick_count.max(1) as f64;
+    let mut exp = raw.log10().floor() as i32;
+    let norm = raw / 10f64.powi(exp);
+    // Pick the nice factor that is closest to the raw step.
+    let factor = if norm < 2f64.sqrt() {
+      1
+    } else if norm < 10f64.sqrt() {
+      2
+    } else if norm < 50f64.sqrt() {
+      5
+    } else {
+      exp += 1;
+      1
+    };
+    Some(TickStep { factor, exp })
+  }
+}
+
+/// The step between two ticks, `factor * 10^exp`.
+#[derive(Clone, Copy)]
+struct TickStep {
+  factor: i64,
+  exp: i32,
+}
+
+impl TickStep {
+  fn value(&self) -> f64 { self.at(1) }
+
+  /// The value of the `i`-th tick from zero. Divide by the power of ten for a
+  /// negative exponent, so `3 * 0.2` is `0.6` and not `0.6000000000000001`.
+  fn at(&self, i: i64) -> f64 {
+    let n = (i * self.factor) as f64;
+    if self.exp < 0 { n / 10f64.powi(-self.exp) } else { n * 10f64.powi(self.exp) }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ticks_of_awkward_ranges() {
+    assert_eq!(Axis::new(0., 7.).ticks(), vec![0., 1., 2., 3., 4., 5., 6., 7.]);
+    assert_eq!(Axis::new(0., 7.).with_tick_count(3).ticks(), vec![0., 2., 4., 6.]);
+    assert_eq!(Axis::new(0., 100.).ticks(), vec![0., 20., 40., 60., 80., 100.]);
+    assert_eq!(Axis::new(-3.2, 4.7).ticks(), vec![-2., 0., 2., 4.]);
+    assert_eq!(Axis::new(-10., -2.).ticks(), vec![-10., -8., -6., -4., -2.]);
+    assert_eq!(Axis::new(0., 1.).ticks(), vec![0., 0.2, 0.4, 0.6, 0.8, 1.]);
+    assert_eq!(Axis::new(0.03, 0.07).ticks(), vec![0.03, 0.04, 0.05, 0.06, 0.07]);
+    assert_eq!(Axis::new(0., 2.).with_tick_count(3).ticks(), vec![0., 0.5, 1., 1.5, 2.]);
+    // A reversed axis has the same ticks.
+    assert_eq!(Axis::new(7., 0.).with_tick_count(3).ticks(), vec![0., 2., 4., 6.]);
+    // An empty range only has one tick.
+    assert_eq!(Axis::new(3., 3.).ticks(), vec![3.]);
+  }
+
+  #[test]
+  fn tick_labels() {
+    let axis = Axis::new(0., 1.);
+    assert_eq!(axis.format(0.6), "0.6");
+    assert_eq!(axis.format(1.), "1.0");
+    assert_eq!(axis.format(-0.01), "0.0");
+
+    let axis = Axis::new(-500., 2500.);
+    assert_eq!(axis.ticks(), vec![-500., 0., 500., 1000., 1500., 2000., 2500.]);
+    assert_eq!(axis.format(1500.), "1500");
+  }
+
+  #[test]
+  fn map_and_invert() {
+    let axis = Axis::new(-10., 10.);
+    assert_eq!(axis.map(-10., 200.), 0.);
+    assert_eq!(axis.map(0., 200.), 100.);
+    assert_eq!(axis.map(5., 200.), 150.);
+    assert_eq!(axis.invert(150., 200.), 5.);
+
+    let reversed = Axis::new(10., 0.);
+    assert_eq!(reversed.map(10., 100.), 0.);
+    assert_eq!(reversed.map(2.5, 100.), 75.);
+  }
+}
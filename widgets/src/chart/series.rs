@@ -0,0 +1,149 @@
+use ribir_core::prelude::*;
+
+use super::Axis;
+
+/// The data points of a series, in `(x, y)` pairs.
+pub type SeriesData = Stateful<Vec<(f64, f64)>>;
+
+/// A series that connects its data points with a polyline, it should be a
+/// child of a [`ChartFrame`](super::ChartFrame).
+#[simple_declare]
+pub struct LineSeries {
+  #[declare(strict)]
+  pub data: SeriesData,
+  #[declare(default = Palette::of(BuildCtx::get()).primary())]
+  pub color: Color,
+  #[declare(default = 2.)]
+  pub line_width: f32,
+  /// Animate the line from the old data to the new data with the transition
+  /// when the data changes, if the count of the points is not changed.
+  #[declare(strict, default)]
+  pub transition: Option<Box<dyn Transition>>,
+}
+
+/// A series that draws a bar from zero to the value of every data point, it
+/// should be a child of a [`ChartFrame`](super::ChartFrame).
+#[simple_declare]
+pub struct BarSeries {
+  #[declare(strict)]
+  pub data: SeriesData,
+  #[declare(default = Palette::of(BuildCtx::get()).secondary())]
+  pub color: Color,
+  /// The width of a bar in pixels.
+  #[declare(default = 12.)]
+  pub bar_width: f32,
+  /// Animate the bars from the old data to the new data with the transition
+  /// when the data changes, if the count of the points is not changed.
+  #[declare(strict, default)]
+  pub transition: Option<Box<dyn Transition>>,
+}
+
+/// The series a [`ChartFrame`](super::ChartFrame) accepts as its children.
+pub enum ChartSeries {
+  Line(State<LineSeries>),
+  Bar(State<BarSeries>),
+}
+
+impl ComposeChildFrom<State<LineSeries>, 0> for ChartSeries {
+  fn compose_child_from(from: State<LineSeries>) -> Self { ChartSeries::Line(from) }
+}
+
+impl ComposeChildFrom<State<BarSeries>, 0> for ChartSeries {
+  fn compose_child_from(from: State<BarSeries>) -> Self { ChartSeries::Bar(from) }
+}
+
+#[derive(Clone, Copy)]
+pub(super) enum SeriesKind {
+  Line { color: Color, line_width: f32 },
+  Bar { color: Color, bar_width: f32 },
+}
+
+impl ChartSeries {
+  pub(super) fn into_parts(self) -> (SeriesData, SeriesKind, Option<Box<dyn Transition>>) {
+    match self {
+      ChartSeries::Line(s) => {
+        let LineSeries { data, color, line_width, transition } = unwrap_series(s);
+        (data, SeriesKind::Line { color, line_width }, transition)
+      }
+      ChartSeries::Bar(s) => {
+        let BarSeries { data, color, bar_width, transition } = unwrap_series(s);
+        (data, SeriesKind::Bar { color, bar_width }, transition)
+      }
+    }
+  }
+}
+
+fn unwrap_series<T: 'static>(series: State<T>) -> T {
+  series.try_into_value().unwrap_or_else(|_| {
+    panic!("A series should not be shared, share its data if you want to update the chart.")
+  })
+}
+
+/// The widget that paints a series in the plot area of the chart.
+#[derive(Declare)]
+pub(super) struct SeriesPaint {
+  pub(super) kind: SeriesKind,
+  pub(super) x_axis: Axis,
+  pub(super) y_axis: Axis,
+  pub(super) points: Vec<(f64, f64)>,
+}
+
+/// Map a data point to the position in a plot area of `size`, the y axis
+/// grows upwards.
+pub(super) fn map_point(x_axis: &Axis, y_axis: &Axis, (x, y): (f64, f64), size: Size) -> Point {
+  Point::new(x_axis.map(x, size.width), size.height - y_axis.map(y, size.height))
+}
+
+/// Lerp the points one by one, jump to the new points if the count is changed.
+pub(super) fn lerp_points(from: &[(f64, f64)], to: &[(f64, f64)], rate: f32) -> Vec<(f64, f64)> {
+  if from.len() != to.len() {
+    return to.to_vec();
+  }
+  from
+    .iter()
+    .zip(to.iter())
+    .map(|(f, t)| f.lerp(t, rate))
+    .collect()
+}
+
+impl Render for SeriesPaint {
+  fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size { clamp.max }
+
+  fn only_sized_by_parent(&self) -> bool { true }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let size = ctx.box_size().unwrap();
+    let Self { kind, x_axis, y_axis, points } = self;
+    let mut pts = points
+      .iter()
+      .map(|p| map_point(x_axis, y_axis, *p, size));
+    let painter = ctx.painter();
+    match *kind {
+      SeriesKind::Line { color, line_width } => {
+        let Some(first) = pts.next() else { return };
+        painter
+          .set_stroke_brush(color)
+          .set_line_width(line_width)
+          .begin_path(first);
+        for p in pts {
+          painter.line_to(p);
+        }
+        painter.end_path(false).stroke();
+      }
+      SeriesKind::Bar { color, bar_width } => {
+        let zero = 0f64.clamp(y_axis.min.min(y_axis.max), y_axis.min.max(y_axis.max));
+        let base = size.height - y_axis.map(zero, size.height);
+        painter.set_fill_brush(color);
+        for p in pts {
+          let top = p.y.min(base);
+          let rect = Rect::new(
+            Point::new(p.x - bar_width / 2., top),
+            Size::new(bar_width, (p.y - base).abs()),
+          );
+          painter.rect(&rect);
+        }
+        painter.fill();
+      }
+    }
+  }
+}
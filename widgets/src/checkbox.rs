@@ -88,12 +88,14 @@ impl ComposeChild<'static> for Checkbox {
         class: distinct_pipe!($this.state_class_name()),
         @Void { class: CHECKBOX }
       };
-      @FatObj {
-        on_tap: move |_| $this.write().switch_check(),
-        on_key_up: move |k| if *k.key() == VirtualKey::Named(NamedKey::Space) {
-          $this.write().switch_check()
-        },
-        @ icon_with_label(icon.into_widget(), child)
+      @TouchTarget {
+        @FatObj {
+          on_tap: move |_| $this.write().switch_check(),
+          on_key_up: move |k| if *k.key() == VirtualKey::Named(NamedKey::Space) {
+            $this.write().switch_check()
+          },
+          @ icon_with_label(icon.into_widget(), child)
+        }
       }
     }
     .into_widget()
@@ -0,0 +1,430 @@
+//! A dock area arranges panels in a tree of splits and tabbed groups. Drag a
+//! panel by its tab and drop it on a side of another group to re-split the
+//! layout, or on the center of a group to add it as a tab.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use ribir_core::prelude::*;
+//! # use ribir_widgets::prelude::*;
+//! let _w = fn_widget! {
+//!   let layout = DockLayout::new(DockNode::split(
+//!     Direction::Horizontal,
+//!     0.3,
+//!     DockNode::group(["files", "search"]),
+//!     DockNode::group(["editor"]),
+//!   ));
+//!   let dock = @DockArea { layout };
+//!   // Persist the layout when it changes.
+//!   watch!($dock.layout.clone()).subscribe(|layout| {
+//!     let _json = serde_json::to_string(&layout).unwrap();
+//!   });
+//!   @ $dock {
+//!     @DockPanel::new("files", "Files", fn_widget! { @Text { text: "files" } })
+//!     @DockPanel::new("search", "Search", fn_widget! { @Text { text: "search" } })
+//!     @DockPanel::new("editor", "Editor", fn_widget! { @Text { text: "editor" } })
+//!   }
+//! };
+//! ```
+
+use std::{
+  cell::{Cell, RefCell},
+  collections::HashMap,
+  rc::Rc,
+};
+
+use ribir_core::prelude::*;
+
+use crate::prelude::*;
+
+mod dock_layout;
+pub use dock_layout::*;
+
+class_names! {
+  #[doc = "Class name for the tab bar of a group in a dock area"]
+  DOCK_TAB_BAR,
+  #[doc = "Class name for a tab in a dock area"]
+  DOCK_TAB,
+  #[doc = "Class name for the tab of the active panel in a dock area"]
+  DOCK_TAB_ACTIVE,
+  #[doc = "Class name for the close button of a tab in a dock area"]
+  DOCK_TAB_CLOSE,
+  #[doc = "Class name for the hint of where a dragging panel will dock"]
+  DOCK_HINT,
+}
+
+/// How far the pointer moves before a tab starts dragging.
+const DRAG_THRESHOLD: f32 = 4.;
+
+/// A widget that shows its [`DockPanel`] children in the arrangement of a
+/// [`DockLayout`], see the [module-level documentation](self) for more.
+#[derive(Declare)]
+pub struct DockArea {
+  /// The layout of the panels, it changes when the user resizes, drags or
+  /// closes the panels.
+  pub layout: DockLayout,
+}
+
+/// A panel of a [`DockArea`], the `id` is how the [`DockLayout`] refers to
+/// it.
+#[derive(ChildOfCompose)]
+pub struct DockPanel {
+  id: String,
+  title: CowArc<str>,
+  content: GenWidget,
+}
+
+impl DockPanel {
+  pub fn new(
+    id: impl Into<String>, title: impl Into<CowArc<str>>, content: impl Into<GenWidget>,
+  ) -> Self {
+    Self { id: id.into(), title: title.into(), content: content.into() }
+  }
+}
+
+impl DockSide {
+  /// The side of the `rect` that the `pos` points to, `None` if the `pos` is
+  /// outside of the `rect`. The middle half of the `rect` is its center.
+  pub fn hit(rect: Rect, pos: Point) -> Option<Self> {
+    if !rect.contains(pos) {
+      return None;
+    }
+    let x = (pos.x - rect.min_x()) / rect.width();
+    let y = (pos.y - rect.min_y()) / rect.height();
+    let sides = [
+      (x, DockSide::Left),
+      (1. - x, DockSide::Right),
+      (y, DockSide::Top),
+      (1. - y, DockSide::Bottom),
+    ];
+    let (dist, side) = sides
+      .into_iter()
+      .min_by(|a, b| a.0.total_cmp(&b.0))
+      .unwrap();
+    Some(if dist > 0.25 { DockSide::Center } else { side })
+  }
+
+  /// The area of the `rect` that a panel docked to this side will take.
+  pub fn hint_rect(self, rect: Rect) -> Rect {
+    let Size { width, height, .. } = rect.size;
+    match self {
+      DockSide::Left => Rect::new(rect.origin, Size::new(width / 2., height)),
+      DockSide::Right => {
+        Rect::new(rect.origin + Vector::new(width / 2., 0.), Size::new(width / 2., height))
+      }
+      DockSide::Top => Rect::new(rect.origin, Size::new(width, height / 2.)),
+      DockSide::Bottom => {
+        Rect::new(rect.origin + Vector::new(0., height / 2.), Size::new(width, height / 2.))
+      }
+      DockSide::Center => rect,
+    }
+  }
+}
+
+struct TabDrag {
+  _handle: GrabPointer,
+  panel: String,
+  start: Point,
+  /// The panel of the target group and the side to dock.
+  target: Option<(String, DockSide)>,
+}
+
+type MountedGroup = (WidgetId, Vec<String>);
+
+/// The state of a dock area that lives across the rebuilds of its layout.
+#[derive(Clone)]
+struct DockCtx {
+  panels: Rc<HashMap<String, DockPanel>>,
+  /// The mounted groups and their panels.
+  groups: Rc<RefCell<Vec<MountedGroup>>>,
+  drag: Rc<RefCell<Option<TabDrag>>>,
+  hint: Overlay,
+  hint_rect: Rc<Cell<Rect>>,
+}
+
+impl DockCtx {
+  fn drag_to(&self, e: &PointerEvent) {
+    let mut drag = self.drag.borrow_mut();
+    let Some(drag) = drag.as_mut() else { return };
+    let pos = e.global_pos();
+    if drag.target.is_none() && (pos - drag.start).length() < DRAG_THRESHOLD {
+      return;
+    }
+
+    let wnd = e.window();
+    let target = self
+      .groups
+      .borrow()
+      .iter()
+      .find_map(|(id, panels)| {
+        let rect = Rect::new(wnd.map_to_global(Point::zero(), *id), wnd.widget_size(*id)?);
+        let side = DockSide::hit(rect, pos)?;
+        // A panel can dock to a side of its own group only if it has other
+        // panels to leave behind.
+        let target = panels.iter().find(|p| **p != drag.panel)?;
+        let own_group = panels.contains(&drag.panel);
+        (!own_group || side != DockSide::Center).then(|| (target.clone(), side, rect))
+      });
+
+    let new_target = target
+      .as_ref()
+      .map(|(panel, side, _)| (panel.clone(), *side));
+    if drag.target == new_target {
+      return;
+    }
+    drag.target = new_target;
+    self.hint.close();
+    if let Some((_, side, rect)) = target {
+      let rect = side.hint_rect(rect);
+      self.hint_rect.set(rect);
+      self.hint.show_at(rect.origin, wnd);
+    }
+  }
+
+  /// Finish the dragging, return the panel to dock, the target panel and the
+  /// side.
+  fn drop(&self) -> Option<(String, String, DockSide)> {
+    self.hint.close();
+    let drag = self.drag.borrow_mut().take()?;
+    let (target, side) = drag.target?;
+    Some((drag.panel, target, side))
+  }
+}
+
+impl ComposeChild<'static> for DockArea {
+  type Child = Vec<DockPanel>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'static> {
+    let panels = child
+      .into_iter()
+      .map(|p| (p.id.clone(), p))
+      .collect();
+    let hint_rect = Rc::new(Cell::new(Rect::zero()));
+    let c_hint_rect = hint_rect.clone();
+    let hint = Overlay::new(
+      move || {
+        let size = c_hint_rect.get().size;
+        fn_widget! { @Container { size, class: DOCK_HINT } }.into_widget()
+      },
+      OverlayStyle { auto_close_policy: AutoClosePolicy::NOT_AUTO_CLOSE, mask: None },
+    );
+    let ctx = DockCtx {
+      panels: Rc::new(panels),
+      groups: <_>::default(),
+      drag: <_>::default(),
+      hint,
+      hint_rect,
+    };
+
+    let area = this.clone_writer();
+    fn_widget! {
+      pipe!($this.layout.root.clone()).map(move |root| match root {
+        Some(root) => dock_node(&area, &root, vec![], &ctx),
+        None => Void.into_widget(),
+      })
+    }
+    .into_widget()
+  }
+}
+
+fn dock_node(
+  this: &impl StateWriter<Value = DockArea>, node: &DockNode, path: Vec<usize>, ctx: &DockCtx,
+) -> Widget<'static> {
+  let split = match node {
+    DockNode::Group(group) => return dock_group(this, group, ctx),
+    DockNode::Split(split) => split,
+  };
+
+  let child_path = |idx| path.iter().copied().chain([idx]).collect();
+  let first = dock_node(this, &split.first, child_path(0), ctx);
+  let second = dock_node(this, &split.second, child_path(1), ctx);
+  let (direction, ratio) = (split.direction, split.ratio);
+  let this = this.clone_writer();
+  fn_widget! {
+    let pane = @SplitPane { direction, position: ratio };
+    // Keep the ratio without rebuilding the layout.
+    let u = watch!($pane.position)
+      .subscribe(move |ratio| this.silent().layout.set_ratio(&path, ratio));
+    @ $pane {
+      on_disposed: move |_| u.unsubscribe(),
+      @Leading::new(first)
+      @Trailing::new(second)
+    }
+  }
+  .into_widget()
+}
+
+fn dock_group(
+  this: &impl StateWriter<Value = DockArea>, group: &DockGroup, ctx: &DockCtx,
+) -> Widget<'static> {
+  let tabs = group
+    .panels
+    .iter()
+    .enumerate()
+    .map(|(idx, id)| dock_tab(this, id, idx == group.active, ctx))
+    .collect::<Vec<_>>();
+  let content = group
+    .panels
+    .get(group.active)
+    .and_then(|id| ctx.panels.get(id))
+    .map_or_else(|| Void.into_widget(), |p| p.content.gen_widget());
+  let panels = group.panels.clone();
+  let groups = ctx.groups.clone();
+  fn_widget! {
+    let c_groups = groups.clone();
+    @Column {
+      align_items: Align::Stretch,
+      on_mounted: move |e| groups.borrow_mut().push((e.current_target(), panels)),
+      on_disposed: move |e| {
+        let id = e.current_target();
+        c_groups.borrow_mut().retain(|(w, _)| *w != id);
+      },
+      @Row { class: DOCK_TAB_BAR, @ { tabs } }
+      @Expanded { @ { content } }
+    }
+  }
+  .into_widget()
+}
+
+fn dock_tab(
+  this: &impl StateWriter<Value = DockArea>, id: &str, active: bool, ctx: &DockCtx,
+) -> Widget<'static> {
+  let title = ctx
+    .panels
+    .get(id)
+    .map_or_else(|| id.to_string().into(), |p| p.title.clone());
+  let id = id.to_string();
+  let this = this.clone_writer();
+  let ctx = ctx.clone();
+  fn_widget! {
+    let (c_id, close_id) = (id.clone(), id.clone());
+    let (drag, drop) = (ctx.clone(), ctx.clone());
+    @Row {
+      class: if active { DOCK_TAB_ACTIVE } else { DOCK_TAB },
+      align_items: Align::Center,
+      on_tap: move |_| if !active {
+        $this.write().layout.activate(&c_id);
+      },
+      on_pointer_down: move |e| {
+        if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
+          let panel = id.clone();
+          let start = e.global_pos();
+          *ctx.drag.borrow_mut() = Some(TabDrag { _handle: handle, panel, start, target: None });
+        }
+      },
+      on_pointer_move: move |e| drag.drag_to(e),
+      on_pointer_up: move |_| if let Some((panel, target, side)) = drop.drop() {
+        $this.write().layout.dock(&panel, &target, side);
+      },
+      @Text { text: title }
+      @Text {
+        class: DOCK_TAB_CLOSE,
+        text: "×",
+        // Not start dragging the tab.
+        on_pointer_down: move |e| e.stop_propagation(),
+        on_tap: move |e| {
+          e.stop_propagation();
+          $this.write().layout.close(&close_id);
+        },
+      }
+    }
+  }
+  .into_widget()
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+
+  #[test]
+  fn hit_sides() {
+    let rect = Rect::new(Point::new(100., 100.), Size::new(200., 100.));
+    assert_eq!(DockSide::hit(rect, Point::new(110., 150.)), Some(DockSide::Left));
+    assert_eq!(DockSide::hit(rect, Point::new(290., 150.)), Some(DockSide::Right));
+    assert_eq!(DockSide::hit(rect, Point::new(200., 105.)), Some(DockSide::Top));
+    assert_eq!(DockSide::hit(rect, Point::new(200., 195.)), Some(DockSide::Bottom));
+    assert_eq!(DockSide::hit(rect, Point::new(200., 150.)), Some(DockSide::Center));
+    assert_eq!(DockSide::hit(rect, Point::new(50., 150.)), None);
+
+    assert_eq!(
+      DockSide::Right.hint_rect(rect),
+      Rect::new(Point::new(200., 100.), Size::new(100., 100.))
+    );
+  }
+
+  fn move_to(wnd: &TestWindow, pos: (f64, f64)) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: pos.into() });
+    wnd.run_frame_tasks();
+  }
+
+  fn press(wnd: &TestWindow, state: ElementState) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.process_mouse_input(device_id, state, MouseButton::Left);
+    wnd.run_frame_tasks();
+  }
+
+  #[test]
+  fn drag_tab_to_dock() {
+    reset_test_env!();
+
+    let layout = DockLayout::new(DockNode::split(
+      Direction::Horizontal,
+      0.5,
+      DockNode::group(["a", "b"]),
+      DockNode::group(["c"]),
+    ));
+    let (layout, w_layout) = split_value(layout);
+    let w = fn_widget! {
+      let dock = @DockArea { layout: w_layout.read().clone() };
+      let w_layout = w_layout.clone_writer();
+      watch!($dock.layout.clone()).subscribe(move |l| *w_layout.write() = l);
+      @ $dock {
+        @DockPanel::new("a", "A", fn_widget! { @Void {} })
+        @DockPanel::new("b", "B", fn_widget! { @Void {} })
+        @DockPanel::new("c", "C", fn_widget! { @Void {} })
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(404., 300.));
+    wnd.draw_frame();
+
+    // Drag the tab `a` to the bottom of the group of `c`.
+    move_to(&wnd, (4., 4.));
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, (300., 150.));
+    move_to(&wnd, (300., 290.));
+    press(&wnd, ElementState::Released);
+    wnd.draw_frame();
+
+    assert_eq!(
+      *layout.read(),
+      DockLayout::new(DockNode::split(
+        Direction::Horizontal,
+        0.5,
+        DockNode::group(["b"]),
+        DockNode::split(Direction::Vertical, 0.5, DockNode::group(["c"]), DockNode::group(["a"])),
+      ))
+    );
+
+    // Drag the tab `c` to the center of the group of `b`.
+    move_to(&wnd, (206., 4.));
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, (100., 150.));
+    press(&wnd, ElementState::Released);
+    wnd.draw_frame();
+
+    assert_eq!(
+      *layout.read(),
+      DockLayout::new(DockNode::split(
+        Direction::Horizontal,
+        0.5,
+        DockNode::Group(DockGroup { panels: vec!["b".into(), "c".into()], active: 1 }),
+        DockNode::group(["a"]),
+      ))
+    );
+  }
+}
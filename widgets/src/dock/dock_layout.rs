@@ -0,0 +1,316 @@
+use std::mem;
+
+use serde::{Deserialize, Serialize};
+
+use crate::layout::Direction;
+
+/// The layout of the panels in a [`DockArea`](super::DockArea), a tree of
+/// splits whose leaves are groups of tabbed panels.
+///
+/// It is serializable, so an application can persist the layout the user
+/// arranged and restore it in the next session.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DockLayout {
+  /// The root of the tree, `None` if all the panels are closed.
+  pub root: Option<DockNode>,
+}
+
+/// A node of the [`DockLayout`] tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DockNode {
+  Split(DockSplit),
+  Group(DockGroup),
+}
+
+/// Two nodes that share the space of their parent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockSplit {
+  pub direction: Direction,
+  /// The ratio of the space that the first node takes.
+  pub ratio: f32,
+  pub first: Box<DockNode>,
+  pub second: Box<DockNode>,
+}
+
+/// A group of panels, shown as tabs and only the active panel is visible.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DockGroup {
+  /// The ids of the panels in the order of the tabs.
+  pub panels: Vec<String>,
+  /// The index of the active panel.
+  pub active: usize,
+}
+
+/// Where to dock a panel relative to a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockSide {
+  Left,
+  Right,
+  Top,
+  Bottom,
+  /// Add the panel as a tab of the group.
+  Center,
+}
+
+impl DockNode {
+  /// Create a group of the `panels`, the first panel is active.
+  pub fn group<S: Into<String>>(panels: impl IntoIterator<Item = S>) -> Self {
+    let panels = panels.into_iter().map(Into::into).collect();
+    DockNode::Group(DockGroup { panels, active: 0 })
+  }
+
+  /// Split the space between the `first` and the `second` node.
+  pub fn split(direction: Direction, ratio: f32, first: DockNode, second: DockNode) -> Self {
+    let (first, second) = (Box::new(first), Box::new(second));
+    DockNode::Split(DockSplit { direction, ratio, first, second })
+  }
+
+  fn find(&self, panel: &str, path: &mut Vec<usize>) -> bool {
+    match self {
+      DockNode::Group(g) => g.panels.iter().any(|p| p == panel),
+      DockNode::Split(s) => {
+        for (idx, child) in [&s.first, &s.second].into_iter().enumerate() {
+          path.push(idx);
+          if child.find(panel, path) {
+            return true;
+          }
+          path.pop();
+        }
+        false
+      }
+    }
+  }
+}
+
+impl DockLayout {
+  pub fn new(root: DockNode) -> Self { Self { root: Some(root) } }
+
+  /// The path of the node that contains the `panel`, every step of the path
+  /// is `0` for the first child of a split and `1` for the second child.
+  pub fn find(&self, panel: &str) -> Option<Vec<usize>> {
+    let mut path = vec![];
+    self
+      .root
+      .as_ref()?
+      .find(panel, &mut path)
+      .then_some(path)
+  }
+
+  /// The node at the `path`.
+  pub fn node(&self, path: &[usize]) -> Option<&DockNode> {
+    path
+      .iter()
+      .try_fold(self.root.as_ref()?, |node, idx| match node {
+        DockNode::Split(s) => Some(if *idx == 0 { &*s.first } else { &*s.second }),
+        DockNode::Group(_) => None,
+      })
+  }
+
+  /// The group that contains the `panel`.
+  pub fn group_of(&self, panel: &str) -> Option<&DockGroup> {
+    match self.node(&self.find(panel)?)? {
+      DockNode::Group(g) => Some(g),
+      DockNode::Split(_) => None,
+    }
+  }
+
+  /// Make the `panel` the active tab of its group, return `false` if the panel
+  /// is not in the layout.
+  pub fn activate(&mut self, panel: &str) -> bool {
+    let Some(group) = self.group_mut(panel) else { return false };
+    group.active = group
+      .panels
+      .iter()
+      .position(|p| p == panel)
+      .unwrap();
+    true
+  }
+
+  /// Close the `panel`, the split that holds its group collapses if it was the
+  /// last panel of the group. Return `false` if the panel is not in the layout.
+  pub fn close(&mut self, panel: &str) -> bool {
+    let Some(path) = self.find(panel) else { return false };
+    let Some(DockNode::Group(group)) = self.node_mut(&path) else { return false };
+    let idx = group
+      .panels
+      .iter()
+      .position(|p| p == panel)
+      .unwrap();
+    group.panels.remove(idx);
+    if group.active > idx || group.active >= group.panels.len() {
+      group.active = group.active.saturating_sub(1);
+    }
+    if group.panels.is_empty() {
+      self.remove_node(&path);
+    }
+    true
+  }
+
+  /// Move the `panel` to the `side` of the group that contains the `target`
+  /// panel, return `false` if nothing changes.
+  pub fn dock(&mut self, panel: &str, target: &str, side: DockSide) -> bool {
+    let (Some(from), Some(to)) = (self.find(panel), self.find(target)) else { return false };
+    if panel == target {
+      return false;
+    }
+    if from == to {
+      let len = self.group_of(panel).map_or(0, |g| g.panels.len());
+      if side == DockSide::Center || len == 1 {
+        return false;
+      }
+    }
+
+    self.close(panel);
+    let path = self.find(target).unwrap();
+    let node = self.node_mut(&path).unwrap();
+    match side {
+      DockSide::Center => {
+        let DockNode::Group(group) = node else { unreachable!() };
+        group.panels.push(panel.to_string());
+        group.active = group.panels.len() - 1;
+      }
+      side => {
+        let target = mem::replace(node, DockNode::group::<String>([]));
+        let new = DockNode::group([panel]);
+        let direction = match side {
+          DockSide::Left | DockSide::Right => Direction::Horizontal,
+          _ => Direction::Vertical,
+        };
+        *node = match side {
+          DockSide::Left | DockSide::Top => DockNode::split(direction, 0.5, new, target),
+          _ => DockNode::split(direction, 0.5, target, new),
+        };
+      }
+    }
+    true
+  }
+
+  /// Set the ratio of the split at the `path`.
+  pub fn set_ratio(&mut self, path: &[usize], ratio: f32) {
+    if let Some(DockNode::Split(s)) = self.node_mut(path) {
+      s.ratio = ratio;
+    }
+  }
+
+  fn group_mut(&mut self, panel: &str) -> Option<&mut DockGroup> {
+    let path = self.find(panel)?;
+    match self.node_mut(&path)? {
+      DockNode::Group(g) => Some(g),
+      DockNode::Split(_) => None,
+    }
+  }
+
+  fn node_mut(&mut self, path: &[usize]) -> Option<&mut DockNode> {
+    path
+      .iter()
+      .try_fold(self.root.as_mut()?, |node, idx| match node {
+        DockNode::Split(s) => Some(if *idx == 0 { &mut *s.first } else { &mut *s.second }),
+        DockNode::Group(_) => None,
+      })
+  }
+
+  /// Remove the node at the `path`, its sibling takes the place of their
+  /// parent split.
+  fn remove_node(&mut self, path: &[usize]) {
+    let Some((idx, parent)) = path.split_last() else {
+      self.root = None;
+      return;
+    };
+    let Some(node) = self.node_mut(parent) else { return };
+    if let DockNode::Split(s) = node {
+      let sibling = if *idx == 0 { &mut s.second } else { &mut s.first };
+      let sibling = mem::replace(&mut **sibling, DockNode::group::<String>([]));
+      *node = sibling;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn two_groups() -> DockLayout {
+    DockLayout::new(DockNode::split(
+      Direction::Horizontal,
+      0.3,
+      DockNode::group(["files", "search"]),
+      DockNode::group(["editor"]),
+    ))
+  }
+
+  #[test]
+  fn dock_to_sides() {
+    let mut layout = two_groups();
+    assert!(layout.dock("search", "editor", DockSide::Bottom));
+    assert_eq!(
+      layout,
+      DockLayout::new(DockNode::split(
+        Direction::Horizontal,
+        0.3,
+        DockNode::group(["files"]),
+        DockNode::split(
+          Direction::Vertical,
+          0.5,
+          DockNode::group(["editor"]),
+          DockNode::group(["search"])
+        ),
+      ))
+    );
+    assert_eq!(layout.find("search"), Some(vec![1, 1]));
+
+    // The group of `files` collapses after the panel is docked away.
+    assert!(layout.dock("files", "editor", DockSide::Center));
+    assert_eq!(
+      layout,
+      DockLayout::new(DockNode::split(
+        Direction::Vertical,
+        0.5,
+        DockNode::Group(DockGroup { panels: vec!["editor".into(), "files".into()], active: 1 }),
+        DockNode::group(["search"])
+      ))
+    );
+
+    assert!(layout.dock("search", "files", DockSide::Left));
+    assert_eq!(
+      layout,
+      DockLayout::new(DockNode::split(
+        Direction::Horizontal,
+        0.5,
+        DockNode::group(["search"]),
+        DockNode::Group(DockGroup { panels: vec!["editor".into(), "files".into()], active: 1 }),
+      ))
+    );
+
+    // Docking a panel to its own group.
+    assert!(!layout.dock("search", "search", DockSide::Right));
+    assert!(!layout.dock("files", "editor", DockSide::Center));
+    assert!(layout.dock("files", "editor", DockSide::Top));
+    assert_eq!(layout.find("files"), Some(vec![1, 0]));
+  }
+
+  #[test]
+  fn close_last_tab_collapses() {
+    let mut layout = two_groups();
+    assert!(layout.activate("search"));
+    assert!(layout.close("search"));
+    assert_eq!(layout.group_of("files").unwrap().active, 0);
+
+    assert!(layout.close("editor"));
+    assert_eq!(layout, DockLayout::new(DockNode::group(["files"])));
+    assert!(layout.close("files"));
+    assert_eq!(layout.root, None);
+    assert!(!layout.close("files"));
+  }
+
+  #[test]
+  fn serialize_round_trip() {
+    let mut layout = two_groups();
+    layout.dock("search", "editor", DockSide::Top);
+    layout.set_ratio(&[1], 0.25);
+
+    let json = serde_json::to_string(&layout).unwrap();
+    let restored: DockLayout = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, layout);
+    assert!(json.contains(r#""ratio":0.25"#));
+  }
+}
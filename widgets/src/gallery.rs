@@ -0,0 +1,34 @@
+//! Register the states of the widgets to the
+//! [gallery](ribir_core::gallery) of the application.
+
+use ribir_core::{gallery, prelude::*};
+
+use crate::prelude::*;
+
+/// Register the states of the widgets of this crate to the gallery, their
+/// names start with the module of the widget, such as `buttons/filled`.
+pub fn register() {
+  gallery::register("buttons/button", fn_widget! { @Button { @{ "Button" } } });
+  gallery::register("buttons/filled", fn_widget! { @FilledButton { @{ "Filled" } } });
+  gallery::register("buttons/text", fn_widget! { @TextButton { @{ "Text" } } });
+  gallery::register("buttons/fab", fn_widget! { @Fab { @{ "Fab" } } });
+  gallery::register("buttons/mini_fab", fn_widget! { @MiniFab { @{ "Mini" } } });
+  gallery::register("buttons/large_fab", fn_widget! { @LargeFab { @{ "Large" } } });
+
+  gallery::register("checkbox/unchecked", fn_widget! { @Checkbox { @{ "Unchecked" } } });
+  gallery::register(
+    "checkbox/checked",
+    fn_widget! {
+      @Checkbox { checked: true, @{ "Checked" } }
+    },
+  );
+  gallery::register(
+    "checkbox/indeterminate",
+    fn_widget! {
+      @Checkbox { indeterminate: true, @{ "Indeterminate" } }
+    },
+  );
+
+  gallery::register("radio/unselected", fn_widget! { @Radio {} });
+  gallery::register("radio/selected", fn_widget! { @Radio { selected: true } });
+}
@@ -45,7 +45,8 @@ use ribir_core::{impl_compose_child_for_wrap_render, prelude::*, wrap_render::Wr
 /// };
 /// ```
 ///
-/// To specify the icon size, you can use the `text_line_height` property.
+/// To specify the icon size, you can use the `text_line_height` property, or
+/// pick a level of the icon size of the theme by the `size` property.
 ///
 /// ```
 /// use ribir_core::prelude::*;
@@ -55,9 +56,35 @@ use ribir_core::{impl_compose_child_for_wrap_render, prelude::*, wrap_render::Wr
 ///   text_line_height: 64.,
 ///   @ { named_svgs::get_or_default("search") }
 /// };
+/// let _icon = icon! {
+///   size: IconScale::Small,
+///   @ { svgs::SEARCH }
+/// };
+/// ```
+///
+/// The directional icons, such as the back and forward arrows, should point
+/// to the other side in a right-to-left text direction. Set `mirror_in_rtl` to
+/// flip them horizontally in that case.
+///
+/// ```
+/// use ribir_core::prelude::*;
+/// use ribir_widgets::prelude::*;
+///
+/// let _icon = icon! {
+///   mirror_in_rtl: true,
+///   @ { svgs::ARROW_BACK }
+/// };
 /// ```
 #[derive(Declare, Default, Clone, Copy)]
-pub struct Icon;
+pub struct Icon {
+  /// The level of the icon size of the theme, the icon is sized by the text
+  /// line height if it's `None`.
+  #[declare(default)]
+  pub size: Option<IconScale>,
+  /// Whether to flip the icon horizontally in a right-to-left text direction.
+  #[declare(default)]
+  pub mirror_in_rtl: bool,
+}
 
 #[derive(Template)]
 pub enum IconChild<'c> {
@@ -70,15 +97,20 @@ pub enum IconChild<'c> {
 
 impl<'c> ComposeChild<'c> for Icon {
   type Child = IconChild<'c>;
-  fn compose_child(_: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
     let child = match child {
       IconChild::FontIcon(text) => IconText.with_child(text! { text }).into_widget(),
       IconChild::Widget(child) => child,
     };
 
-    IconRender { scale: Cell::new(0.) }
-      .with_child(child)
-      .into_widget()
+    fn_widget! {
+      @IconRender {
+        size: pipe!($this.size),
+        mirror_in_rtl: pipe!($this.mirror_in_rtl),
+        @ { child }
+      }
+    }
+    .into_widget()
   }
 }
 
@@ -98,20 +130,37 @@ impl WrapRender for IconText {
   }
 }
 
-#[derive(SingleChild)]
+#[derive(SingleChild, Declare)]
 struct IconRender {
+  #[declare(default)]
+  size: Option<IconScale>,
+  #[declare(default)]
+  mirror_in_rtl: bool,
+  #[declare(skip)]
   scale: Cell<f32>,
+  /// The width of the icon if it's mirrored in the last layout.
+  #[declare(skip)]
+  mirrored: Cell<Option<f32>>,
 }
 
 impl Render for IconRender {
   fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
-    let icon_size = ctx.text_style().line_height;
+    let icon_size = match self.size {
+      Some(scale) => IconSize::of(&ctx).size_of(scale),
+      None => Size::splat(ctx.text_style().line_height),
+    };
     let child_size = ctx
       .perform_single_child_layout(BoxClamp::default())
       .unwrap_or_default();
-    let scale = icon_size / child_size.width.max(child_size.height);
+    let scale = (icon_size.width / child_size.width).min(icon_size.height / child_size.height);
     self.scale.set(scale);
-    clamp.clamp(Size::splat(icon_size))
+
+    let size = clamp.clamp(icon_size);
+    let rtl = ctx.text_direction() == TextDirection::RightToLeft;
+    self
+      .mirrored
+      .set((self.mirror_in_rtl && rtl).then_some(size.width));
+    size
   }
 
   fn paint(&self, ctx: &mut PaintingCtx) {
@@ -124,6 +173,9 @@ impl Render for IconRender {
       if real_size.greater_than(size).any() {
         painter.clip(Path::rect(&Rect::from_size(size)).into());
       }
+      if let Some(width) = self.mirrored.get() {
+        painter.translate(width, 0.).scale(-1., 1.);
+      }
       let offset = (size - real_size) / 2.0;
       painter
         .translate(offset.width, offset.height)
@@ -133,7 +185,14 @@ impl Render for IconRender {
 
   fn get_transform(&self) -> Option<Transform> {
     let scale = self.scale.get();
-    Some(Transform::scale(scale, scale))
+    let transform = Transform::scale(scale, scale);
+    let transform = match self.mirrored.get() {
+      Some(width) => transform
+        .then_scale(-1., 1.)
+        .then_translate(Vector::new(width, 0.)),
+      None => transform,
+    };
+    Some(transform)
   }
 
   fn only_sized_by_parent(&self) -> bool { true }
@@ -141,12 +200,73 @@ impl Render for IconRender {
 
 #[cfg(test)]
 mod tests {
-  use ribir_core::test_helper::*;
+  use ribir_core::{reset_test_env, test_helper::*};
   use ribir_dev_helper::*;
 
   use super::*;
   use crate::prelude::*;
 
+  #[test]
+  fn shadowed_icon_theme() {
+    reset_test_env!();
+
+    let done = Resource::new(named_svgs::get_or_default(""));
+    let c_done = done.clone();
+    let (resolved, w_resolved) = split_value(vec![]);
+    let mut wnd = TestWindow::new(fn_widget! {
+      let mut icon_theme = IconTheme::of(BuildCtx::get()).clone();
+      icon_theme.icon_size.small = Size::splat(40.);
+      icon_theme.set_svg(svgs::DONE, c_done.clone());
+      let w_resolved = w_resolved.clone_writer();
+      @Column {
+        @Icon { size: IconScale::Small, @ { svgs::DONE } }
+        @ {
+          Provider::new(Box::new(Queryable(icon_theme))).with_child(fn_widget! {
+            let icon = svgs::DONE.of_or_miss(BuildCtx::get());
+            $w_resolved.write().push(Resource::as_ptr(&icon));
+            @Icon { size: IconScale::Small, @ { svgs::DONE } }
+          })
+        }
+      }
+    });
+    wnd.draw_frame();
+
+    assert_eq!(*resolved.read(), [Resource::as_ptr(&done)]);
+    wnd.assert_root_size(Size::new(40., 64.));
+    assert_eq!(wnd.layout_info_by_path(&[0, 0]).unwrap().size, Some(Size::splat(24.)));
+    assert_eq!(wnd.layout_info_by_path(&[0, 1]).unwrap().size, Some(Size::splat(40.)));
+  }
+
+  fn painted_icon_transform(dir: TextDirection, mirror_in_rtl: bool) -> Transform {
+    let mut wnd = TestWindow::new(fn_widget! {
+      Provider::new(Box::new(Queryable(dir))).with_child(fn_widget! {
+        @Icon { size: IconScale::Small, mirror_in_rtl, @ { svgs::DONE } }
+      })
+    });
+    wnd.draw_frame();
+    let frame = wnd.take_last_frame().unwrap();
+    match &frame.commands[0] {
+      PaintCommand::Path(path) => path.transform,
+      PaintCommand::Bundle { transform, .. } => *transform,
+      PaintCommand::PopClip => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn mirror_in_rtl() {
+    reset_test_env!();
+
+    let ltr = painted_icon_transform(TextDirection::LeftToRight, true);
+    assert!(ltr.m11 > 0.);
+
+    // Only the directional icon is mirrored in the right-to-left direction.
+    let rtl = painted_icon_transform(TextDirection::RightToLeft, false);
+    assert_eq!(rtl, ltr);
+    let mirrored = painted_icon_transform(TextDirection::RightToLeft, true);
+    assert_eq!(mirrored.m11, -ltr.m11);
+    assert_eq!(mirrored.m31, ltr.m31 + 24.);
+  }
+
   widget_image_tests!(
     icons,
     WidgetTester::new(row! {
@@ -0,0 +1,424 @@
+use std::{
+  cell::{Cell, RefCell},
+  future::Future,
+  pin::Pin,
+  rc::Rc,
+};
+
+use ribir_core::prelude::*;
+
+use crate::prelude::*;
+
+class_names! {
+  #[doc = "Class name for the trailing row of the infinite list while loading"]
+  INFINITE_LIST_LOADING,
+  #[doc = "Class name for the trailing row of the infinite list after a load failed"]
+  INFINITE_LIST_RETRY,
+}
+
+/// A vertical scrollable list that loads more items when it's scrolled near
+/// its end.
+///
+/// The list shows the `items` by the `item_builder`. Once the rest of the list
+/// to scroll is shorter than the `threshold`, it calls `load_more` and appends
+/// what the future returns to the `items`. Only one load runs at a time, and
+/// the list stops loading after a load returns no item.
+///
+/// A loading row is shown at the end of the list while loading. If the load
+/// fails, it's replaced by a row with the error and a button to retry.
+///
+/// With an `item_restoration_id`, the list saves its first visible item when
+/// it's disposed, and scrolls back to that item when a list of the same id is
+/// built again in the window, see [`ScrollRestoration`]. It restores by the
+/// index of the item, so the items before it can resize in between.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let items = Stateful::new(vec![]);
+/// let _w = fn_widget! {
+///   let mut next = 0;
+///   @InfiniteList {
+///     items: items.clone_writer(),
+///     load_more: move || {
+///       let from = next;
+///       next += 20;
+///       async move { Ok((from..from + 20).collect()) }
+///     },
+///     item_builder: |i: &i32| @Text { text: i.to_string() }.into_widget(),
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct InfiniteList<T>
+where
+  T: 'static,
+{
+  /// The items of the list, the loaded items are appended to it.
+  pub items: Stateful<Vec<T>>,
+  /// Load the items after the last one, an empty result means all the items
+  /// are loaded.
+  pub load_more: LoadMoreHandler<T>,
+  /// Build the widget of an item.
+  pub item_builder: ItemBuilder<T>,
+  /// Load more items when the rest of the list to scroll is shorter than it in
+  /// logical pixels.
+  #[declare(default = 200.)]
+  pub threshold: f32,
+  /// The id to save and restore the first visible item of the list by. Use it
+  /// instead of the builtin `restoration_id`, which restores the list by the
+  /// scroll position.
+  #[declare(default)]
+  pub item_restoration_id: Option<CowArc<str>>,
+  #[declare(skip)]
+  state: LoadState,
+}
+
+/// The result of loading more items, the error message is shown in the list.
+pub type LoadResult<T> = Result<Vec<T>, CowArc<str>>;
+
+/// The future that loads the next items of the list.
+pub type LoadMoreFuture<T> = Pin<Box<dyn Future<Output = LoadResult<T>>>>;
+
+/// The callback of [`InfiniteList::load_more`], it returns the future that
+/// loads the next items.
+pub struct LoadMoreHandler<T>(Box<dyn FnMut() -> LoadMoreFuture<T>>);
+
+impl<T, F, Fut> From<F> for LoadMoreHandler<T>
+where
+  F: FnMut() -> Fut + 'static,
+  Fut: Future<Output = LoadResult<T>> + 'static,
+{
+  fn from(mut f: F) -> Self { Self(Box::new(move || Box::pin(f()))) }
+}
+
+/// The builder of [`InfiniteList::item_builder`].
+pub struct ItemBuilder<T>(Rc<dyn Fn(&T) -> Widget<'static>>);
+
+impl<T, F> From<F> for ItemBuilder<T>
+where
+  F: Fn(&T) -> Widget<'static> + 'static,
+{
+  fn from(f: F) -> Self { Self(Rc::new(f)) }
+}
+
+/// The loading states of an [`InfiniteList`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum LoadState {
+  /// Waiting to be scrolled near the end.
+  #[default]
+  Idle,
+  /// Waiting for the future of `load_more`.
+  Loading,
+  /// The last load failed with the message, waiting to retry.
+  Failed(CowArc<str>),
+  /// All the items are loaded.
+  Finished,
+}
+
+impl<T: 'static> InfiniteList<T> {
+  /// The loading state of the list.
+  pub fn load_state(&self) -> &LoadState { &self.state }
+}
+
+/// Start to load more items, unless a load is running or all the items are
+/// loaded.
+fn load<T: 'static>(this: &impl StateWriter<Value = InfiniteList<T>>) {
+  let (fut, items) = {
+    let mut this = this.write();
+    if matches!(this.state, LoadState::Loading | LoadState::Finished) {
+      return;
+    }
+    this.state = LoadState::Loading;
+    ((this.load_more.0)(), this.items.clone_writer())
+  };
+  let this = this.clone_writer();
+  let _ = AppCtx::spawn_local(async move {
+    let state = match fut.await {
+      Ok(new) if new.is_empty() => LoadState::Finished,
+      Ok(new) => {
+        items.write().extend(new);
+        LoadState::Idle
+      }
+      Err(err) => LoadState::Failed(err),
+    };
+    this.write().state = state;
+  });
+}
+
+impl<T: 'static> Compose for InfiniteList<T> {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    let (items, builder) = {
+      let this = this.read();
+      (this.items.clone_writer(), this.item_builder.0.clone())
+    };
+    let (c_this, retry_this) = (this.clone_writer(), this.clone_writer());
+    let restoration_id = this.read().item_restoration_id.clone();
+    // The rects of the items in the list, to find the first visible item.
+    let item_rects: Rc<RefCell<Vec<Rect>>> = <_>::default();
+    let pending = restoration_id.as_ref().and_then(|id| {
+      let wnd = BuildCtx::get().window();
+      let saved = wnd.scroll_restoration().borrow().get(id);
+      match saved {
+        Some(ScrollAnchor::Item { index, offset }) => Some((index, offset)),
+        _ => None,
+      }
+    });
+    let pending = Rc::new(Cell::new(pending));
+    fn_widget! {
+      let mut list = @Column { scrollable: Scrollable::Y };
+      let scroll = list.get_scrollable_widget().clone_writer();
+      let (c_scroll, c_rects) = (scroll.clone_writer(), item_rects.clone());
+      let saving_rects = item_rects.clone();
+      @ $list {
+        on_performed_layout: move |_| {
+          let rects = c_rects.borrow();
+          if let Some((index, offset)) = pending.get().filter(|_| !rects.is_empty()) {
+            pending.set(None);
+            let top = rects[index.min(rects.len() - 1)].min_y();
+            c_scroll.write().restore_to(Point::new(0., top + offset));
+          }
+        },
+        on_disposed: move |e| {
+          let Some(id) = restoration_id.clone() else { return };
+          let y = scroll.read().get_scroll_pos().y;
+          let rects = saving_rects.borrow();
+          if let Some(index) = rects.iter().position(|r| r.max_y() > y) {
+            let anchor = ScrollAnchor::Item { index, offset: y - rects[index].min_y() };
+            e.window().scroll_restoration().borrow_mut().save(id, anchor);
+          }
+        },
+        on_scroll: move |e| {
+          let near_end = {
+            let this = c_this.read();
+            e.remaining().y < this.threshold && this.state == LoadState::Idle
+          };
+          // A failed load waits for the retry instead.
+          if near_end {
+            load(&c_this);
+          }
+        },
+        @ {
+          pipe!($items.len()).map(move |len| {
+            let builder = builder.clone();
+            let items = items.read();
+            item_rects.borrow_mut().resize(len, Rect::zero());
+            items
+              .iter()
+              .enumerate()
+              .map(|(idx, item)| {
+                let rects = item_rects.clone();
+                FatObj::new(builder(item))
+                  .on_performed_layout(move |e| {
+                    let mut rects = rects.borrow_mut();
+                    if let (Some(rect), Some(slot)) = (e.box_rect(), rects.get_mut(idx)) {
+                      *slot = rect;
+                    }
+                  })
+                  .into_widget()
+              })
+              .collect::<Vec<_>>()
+          })
+        }
+        @ {
+          let retry_this = retry_this.clone_writer();
+          pipe!($this.state.clone()).map(move |state| {
+            let retry_this = retry_this.clone_writer();
+            match state {
+              LoadState::Loading => @Row {
+                class: INFINITE_LIST_LOADING,
+                justify_content: JustifyContent::Center,
+                @SpinnerProgress { value: None }
+              }
+              .into_widget(),
+              LoadState::Failed(err) => @Row {
+                class: INFINITE_LIST_RETRY,
+                align_items: Align::Center,
+                justify_content: JustifyContent::SpaceBetween,
+                @Text { text: err }
+                @TextButton {
+                  on_tap: move |_| load(&retry_this),
+                  @ { "Retry" }
+                }
+              }
+              .into_widget(),
+              LoadState::Idle | LoadState::Finished => Void.into_widget(),
+            }
+          })
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+
+  use futures::channel::oneshot;
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent},
+  };
+
+  use super::*;
+
+  type Loads = Rc<RefCell<Vec<oneshot::Sender<LoadResult<i32>>>>>;
+
+  /// A list that loads when the test sends the result to the channel of each
+  /// load.
+  fn list_wnd() -> (TestWindow, Stateful<Vec<i32>>, Loads) {
+    let items = Stateful::new(vec![]);
+    let loads: Loads = <_>::default();
+    let (c_items, c_loads) = (items.clone_writer(), loads.clone());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let loads = c_loads.clone();
+        @InfiniteList {
+          items: c_items.clone_writer(),
+          load_more: move || {
+            let (tx, rx) = oneshot::channel();
+            loads.borrow_mut().push(tx);
+            async move { rx.await.unwrap_or(Ok(vec![])) }
+          },
+          item_builder: |_: &i32| @MockBox { size: Size::new(100., 30.) }.into_widget(),
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+    (wnd, items, loads)
+  }
+
+  fn finish_load(wnd: &mut TestWindow, loads: &Loads, res: LoadResult<i32>) {
+    let tx = loads.borrow_mut().pop().unwrap();
+    let _ = tx.send(res);
+    wnd.draw_frame();
+    wnd.draw_frame();
+  }
+
+  fn wheel(wnd: &mut TestWindow, delta: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: LogicalPosition::new(50., 50.).to_physical(1.),
+    });
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::PixelDelta((0., -delta as f64).into()),
+      phase: TouchPhase::Started,
+    });
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn load_near_end_once_at_a_time() {
+    reset_test_env!();
+
+    let (mut wnd, items, loads) = list_wnd();
+    // The empty list is at its end.
+    assert_eq!(loads.borrow().len(), 1);
+    wheel(&mut wnd, 10.);
+    wnd.draw_frame();
+    assert_eq!(loads.borrow().len(), 1);
+
+    finish_load(&mut wnd, &loads, Ok((0..10).collect()));
+    assert_eq!(*items.read(), (0..10).collect::<Vec<_>>());
+    // 200 pixels left to scroll, not near the end yet.
+    assert!(loads.borrow().is_empty());
+
+    wheel(&mut wnd, 150.);
+    assert_eq!(loads.borrow().len(), 1);
+    wheel(&mut wnd, 50.);
+    assert_eq!(loads.borrow().len(), 1);
+
+    finish_load(&mut wnd, &loads, Ok((10..20).collect()));
+    assert_eq!(items.read().len(), 20);
+  }
+
+  #[test]
+  fn retry_failed_load() {
+    reset_test_env!();
+
+    let (mut wnd, items, loads) = list_wnd();
+    finish_load(&mut wnd, &loads, Err("offline".into()));
+    // The failed load is not retried by scrolling.
+    wheel(&mut wnd, 10.);
+    assert!(loads.borrow().is_empty());
+
+    // Tap the retry button at the end of the row.
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: LogicalPosition::new(95., 5.).to_physical(1.),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+    assert_eq!(loads.borrow().len(), 1);
+
+    finish_load(&mut wnd, &loads, Ok(vec![1, 2]));
+    assert_eq!(*items.read(), [1, 2]);
+    // Still near the end, it loads until nothing left.
+    assert_eq!(loads.borrow().len(), 1);
+    finish_load(&mut wnd, &loads, Ok(vec![]));
+    wheel(&mut wnd, 10.);
+    assert!(loads.borrow().is_empty());
+  }
+
+  #[test]
+  fn restore_first_visible_item() {
+    reset_test_env!();
+
+    let show = Stateful::new(true);
+    let items = Stateful::new(vec![30; 10]);
+    let offset = Stateful::new(Point::zero());
+    let (c_show, c_items, c_offset) =
+      (show.clone_watcher(), items.clone_writer(), offset.clone_writer());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let (c_items, c_offset) = (c_items.clone_writer(), c_offset.clone_writer());
+        @ {
+          pipe!(*$c_show).map(move |show| {
+            let c_offset = c_offset.clone_writer();
+            show.then(|| @InfiniteList {
+              items: c_items.clone_writer(),
+              load_more: || async { Ok(vec![]) },
+              item_builder: |h: &i32| @MockBox { size: Size::new(100., *h as f32) }.into_widget(),
+              item_restoration_id: Some("feed".into()),
+              on_scroll: move |e| *$c_offset.write() = e.offset,
+            })
+          })
+        }
+      },
+      Size::new(100., 100.),
+    );
+    wnd.draw_frame();
+
+    // The fourth item is the first visible, scrolled 10 pixels past its top.
+    wheel(&mut wnd, 100.);
+    *show.write() = false;
+    wnd.draw_frame();
+    let saved = wnd.scroll_restoration().borrow().get("feed");
+    assert_eq!(saved, Some(ScrollAnchor::Item { index: 3, offset: 10. }));
+
+    // Two items above are removed, and the rest ones before it resize.
+    *items.write() = vec![50, 50, 50, 30, 30, 30, 30, 30];
+    *show.write() = true;
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert_eq!(*offset.read(), Point::new(0., 160.));
+
+    *show.write() = false;
+    wnd.draw_frame();
+    let saved = wnd.scroll_restoration().borrow().get("feed");
+    assert_eq!(saved, Some(ScrollAnchor::Item { index: 3, offset: 10. }));
+  }
+}
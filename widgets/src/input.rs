@@ -4,18 +4,25 @@ mod caret_state;
 mod glyphs_helper;
 mod handle;
 mod selected_text;
+mod spell_check;
 mod text_selectable;
-use std::ops::Range;
+use std::{ops::Range, sync::Arc};
 
+pub use caret::{CaretShape, CaretStyle};
 pub use caret_state::{CaretPosition, CaretState};
 pub use selected_text::SelectedHighLightStyle;
+pub use spell_check::{
+  DictionarySpellChecker, Misspelling, SpellCheckDeclarerExtend, SpellCheckStyle, SpellChecker,
+};
 pub use text_selectable::TextSelectable;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
   input::{
     caret::*,
     handle::{TextCaretWriter, edit_handle, edit_key_handle},
     selected_text::*,
+    spell_check::*,
     text_selectable::{SelectableText, bind_point_listener, select_key_handle},
   },
   layout::*,
@@ -87,6 +94,27 @@ pub struct Input {
   caret: CaretState,
   #[declare(default = InputStyle::of(BuildCtx::get()).size)]
   size: Option<f32>,
+  #[declare(default = CaretStyle::of(BuildCtx::get()))]
+  pub caret_style: CaretStyle,
+  #[declare(custom, default)]
+  spell_check: Option<Arc<dyn SpellChecker>>,
+  #[declare(skip)]
+  misspellings: Vec<Misspelling>,
+  /// Paint every character of the text as the `obscuring_char`, for a
+  /// password. An obscured text can't be copied or spell checked, and the
+  /// assistive technologies only know that it's a password field.
+  #[declare(default)]
+  pub obscure_text: bool,
+  /// The character that an obscured text is painted with.
+  #[declare(default = '•')]
+  pub obscuring_char: char,
+  /// How long the last typed character of an obscured text is shown before
+  /// it's obscured, it's obscured at once if `None`.
+  #[declare(default)]
+  pub reveal_last_char: Option<Duration>,
+  /// The byte offset of the typed character that is not obscured yet.
+  #[declare(skip)]
+  revealed: Option<usize>,
 }
 
 #[derive(Declare)]
@@ -103,16 +131,49 @@ pub struct TextArea {
   rows: Option<f32>,
   #[declare(default = TextAreaStyle::of(BuildCtx::get()).cols)]
   cols: Option<f32>,
+  #[declare(default = CaretStyle::of(BuildCtx::get()))]
+  pub caret_style: CaretStyle,
+  #[declare(custom, default)]
+  spell_check: Option<Arc<dyn SpellChecker>>,
+  #[declare(skip)]
+  misspellings: Vec<Misspelling>,
+}
+
+impl SpellCheckDeclarerExtend for FatObj<InputDeclarer> {
+  fn spell_check(mut self, checker: Option<Arc<dyn SpellChecker>>) -> Self {
+    self.spell_check = Some(DeclareInit::Value(checker));
+    self
+  }
+}
+
+impl SpellCheckDeclarerExtend for FatObj<TextAreaDeclarer> {
+  fn spell_check(mut self, checker: Option<Arc<dyn SpellChecker>>) -> Self {
+    self.spell_check = Some(DeclareInit::Value(checker));
+    self
+  }
 }
 
 impl Input {
   /// set the text and the caret selection will be reset to the start.
   pub fn set_text(&mut self, text: &str) { self.set_text_with_caret(text, CaretState::default()); }
+
+  /// The misspelled words of the text that have been found by its
+  /// [`SpellChecker`].
+  pub fn misspellings(&self) -> &[Misspelling] { &self.misspellings }
+
+  /// The byte length of the grapheme at `idx` in the painted text.
+  fn painted_len(&self, idx: usize, grapheme: &str) -> usize {
+    if self.revealed == Some(idx) { grapheme.len() } else { self.obscuring_char.len_utf8() }
+  }
 }
 
 impl TextArea {
   /// set the text and the caret selection will be reset to the start.
   pub fn set_text(&mut self, text: &str) { self.set_text_with_caret(text, CaretState::default()); }
+
+  /// The misspelled words of the text that have been found by its
+  /// [`SpellChecker`].
+  pub fn misspellings(&self) -> &[Misspelling] { &self.misspellings }
 }
 
 impl SelectableText for Input {
@@ -123,6 +184,48 @@ impl SelectableText for Input {
   fn caret(&self) -> CaretState { self.caret }
 
   fn set_caret(&mut self, caret: CaretState) { self.caret = caret; }
+
+  fn is_obscured(&self) -> bool { self.obscure_text }
+
+  fn painted_text(&self) -> CowArc<str> {
+    if !self.obscure_text {
+      return self.text.clone();
+    }
+    let mut buf = [0; 4];
+    let obscuring: &str = self.obscuring_char.encode_utf8(&mut buf);
+    self
+      .text
+      .grapheme_indices(true)
+      .map(|(idx, g)| if self.revealed == Some(idx) { g } else { obscuring })
+      .collect::<String>()
+      .into()
+  }
+
+  fn painted_cluster(&self, cluster: usize) -> usize {
+    if !self.obscure_text {
+      return cluster;
+    }
+    self
+      .text
+      .grapheme_indices(true)
+      .take_while(|(idx, _)| *idx < cluster)
+      .map(|(idx, g)| self.painted_len(idx, g))
+      .sum()
+  }
+
+  fn text_cluster(&self, cluster: usize) -> usize {
+    if !self.obscure_text {
+      return cluster;
+    }
+    let mut painted = 0;
+    for (idx, g) in self.text.grapheme_indices(true) {
+      if painted >= cluster {
+        return idx;
+      }
+      painted += self.painted_len(idx, g);
+    }
+    self.text.len()
+  }
 }
 
 impl EditableText for Input {
@@ -130,11 +233,26 @@ impl EditableText for Input {
   fn caret(&self) -> CaretState { self.caret }
   fn set_text_with_caret(&mut self, text: &str, caret: CaretState) {
     let new_text = text.replace(['\r', '\n'], " ");
+    self.revealed = if self.obscure_text && self.reveal_last_char.is_some() {
+      typed_grapheme(&self.text, &new_text, caret.cluster())
+    } else {
+      None
+    };
     self.text = new_text.into();
     self.caret = caret;
   }
 }
 
+/// The byte offset of the grapheme that is typed before the `caret` if the
+/// `new` text is the `old` text with only it inserted.
+fn typed_grapheme(old: &str, new: &str, caret: usize) -> Option<usize> {
+  let start = caret.checked_sub(new.len().checked_sub(old.len())?)?;
+  let typed = new.get(start..caret)?;
+  let only_typed =
+    new.get(..start)? == old.get(..start)? && new.get(caret..)? == old.get(start..)?;
+  (only_typed && typed.graphemes(true).count() == 1).then_some(start)
+}
+
 impl SelectableText for TextArea {
   fn select_range(&self) -> Range<usize> { self.caret.select_range() }
 
@@ -156,6 +274,31 @@ impl EditableText for TextArea {
   }
 }
 
+impl SpellCheckHost for Input {
+  fn spell_checker(&self) -> Option<Arc<dyn SpellChecker>> {
+    self
+      .spell_check
+      .clone()
+      .filter(|_| !self.obscure_text)
+  }
+
+  fn misspellings(&self) -> &[Misspelling] { &self.misspellings }
+
+  fn set_misspellings(&mut self, misspellings: Vec<Misspelling>) {
+    self.misspellings = misspellings;
+  }
+}
+
+impl SpellCheckHost for TextArea {
+  fn spell_checker(&self) -> Option<Arc<dyn SpellChecker>> { self.spell_check.clone() }
+
+  fn misspellings(&self) -> &[Misspelling] { &self.misspellings }
+
+  fn set_misspellings(&mut self, misspellings: Vec<Misspelling>) {
+    self.misspellings = misspellings;
+  }
+}
+
 #[derive(Debug)]
 struct PreEditState {
   position: usize,
@@ -259,11 +402,31 @@ impl ComposeChild<'static> for Input {
   ) -> Widget<'static> {
     fn_widget! {
       let text = @Text {
-        text: pipe!($this.text.clone()),
+        text: pipe!(SelectableText::painted_text(&*$this)),
         text_style: pipe!($this.style.clone()),
       };
+
+      // Obscure the revealed character after its delay.
+      let c_this = this.clone_writer();
+      let mut _guard = None;
+      let u = watch!($this.revealed)
+        .distinct_until_changed()
+        .subscribe(move |revealed| {
+          _guard = None;
+          let delay = c_this.read().reveal_last_char.filter(|_| revealed.is_some());
+          if let Some(delay) = delay {
+            let this = c_this.clone_writer();
+            let unsub = observable::timer((), delay, AppCtx::scheduler())
+              .subscribe(move |_| $this.write().revealed = None)
+              .unsubscribe_when_dropped();
+            _guard = Some(unsub);
+          }
+        });
+
       @FocusScope {
         can_focus: true,
+        semantics_label: pipe!(if $this.obscure_text { "password field" } else { "" }),
+        on_disposed: move |_| u.unsubscribe(),
         @ConstrainedBox {
           clamp: pipe!(size_clamp(&$this.style, Some(1.), $this.size)),
           @ {
@@ -324,10 +487,9 @@ impl ComposeChild<'static> for TextArea {
   }
 }
 
-trait EditableTextExtraWidget: EditableText + SelectableText
-where
-  Self: 'static,
-{
+trait EditableTextExtraWidget: SpellCheckHost {
+  fn caret_style(&self) -> &CaretStyle;
+
   fn edit_area(
     this: impl StateWriter<Value = Self>, text: FatObj<State<Text>>,
     scroll_dir: impl Pipe<Value = Scrollable>, placeholder: Option<Placeholder>,
@@ -342,6 +504,13 @@ where
 
       let mut caret_box = @Caret {
         focused: pipe!($stack.has_focus()),
+        style: pipe!($this.caret_style().clone()),
+        // The block caret at the end of a line covers a half of the line height.
+        cell_width: pipe!(
+          let line_height = $this.current_line_height(&$text).unwrap_or(0.);
+          $this.caret_cell_width(&$text).unwrap_or(line_height / 2.)
+        ),
+        text_caret: pipe!(SelectableText::caret(&*$this)),
         clamp: pipe!(
             $this.current_line_height(&$text).unwrap_or(0.)
           ).map(BoxClamp::fixed_height),
@@ -380,22 +549,39 @@ where
       let ime_handle = Stateful::new(
         ImeHandle::new(wnd, this.clone_writer(), caret_box_id)
       );
+      // The editing is the default action of the events, so an ancestor can
+      // still prevent it in the bubble phase.
+      let chars_this = this.clone_writer();
+      let key_this = this.clone_writer();
+      let key_text = only_text.clone_reader();
       let mut stack = @ $stack {
-        on_focus: move |_| $ime_handle.write().ime_allowed(),
+        // The IME is disabled for an obscured text, its composition would
+        // show the typed characters.
+        on_focus: move |_| if !$this.is_obscured() { $ime_handle.write().ime_allowed() },
         on_blur: move |_| $ime_handle.write().ime_disallowed(),
         on_chars: move |c| {
-          let _hint_capture_writer = || $this.write();
-          edit_handle(&this, c);
+          let this = chars_this.clone_writer();
+          c.add_default_action(move |c| edit_handle(&this, c));
         },
         on_key_down: move |k| {
-          let _hint_capture_writer = || $this.write();
-          select_key_handle(&this, &$only_text, k);
-          edit_key_handle(&this, k);
+          let this = key_this.clone_writer();
+          let text = key_text.clone_reader();
+          k.add_default_action(move |k| {
+            select_key_handle(&this, &text.read(), k);
+            edit_key_handle(&this, k);
+          });
         },
         on_ime_pre_edit: move |e| {
           $ime_handle.write().update_pre_edit(e);
         },
       };
+      watch!($this.is_obscured())
+        .distinct_until_changed()
+        .filter(move |_| $stack.has_focus())
+        .subscribe(move |obscured| {
+          let mut ime_handle = $ime_handle.write();
+          if obscured { ime_handle.ime_disallowed() } else { ime_handle.ime_allowed() }
+        });
 
       let high_light_rect = @UnconstrainedBox {
         clamp_dim: ClampDim::MIN_SIZE,
@@ -412,7 +598,25 @@ where
         @OnlySizedByParent { @ {caret_box } }
       };
 
+      let spell_underlines = $this.spell_checker().is_some().then(|| {
+        bind_spell_check(this.clone_writer());
+        @UnconstrainedBox {
+          clamp_dim: ClampDim::MIN_SIZE,
+          @OnlySizedByParent {
+            @SpellUnderlines {
+              rects: pipe!(misspelled_rects(&*$this, &$text)),
+              style: SpellCheckStyle::of(BuildCtx::get()),
+            }
+          }
+        }
+      });
+
       let text_widget = text.into_widget();
+      let text_widget = bind_suggestion_menu(
+        this.clone_writer(),
+        text_widget,
+        only_text.clone_reader()
+      );
       let text_widget = bind_point_listener(
         this.clone_writer(),
         text_widget,
@@ -425,15 +629,20 @@ where
         @ { high_light_rect }
         @ { caret }
         @ { text_widget }
+        @ { spell_underlines }
       }
     }
     .into_widget()
   }
 }
 
-impl EditableTextExtraWidget for TextArea {}
+impl EditableTextExtraWidget for TextArea {
+  fn caret_style(&self) -> &CaretStyle { &self.caret_style }
+}
 
-impl EditableTextExtraWidget for Input {}
+impl EditableTextExtraWidget for Input {
+  fn caret_style(&self) -> &CaretStyle { &self.caret_style }
+}
 
 fn size_clamp(style: &TextStyle, rows: Option<f32>, cols: Option<f32>) -> BoxClamp {
   let mut clamp: BoxClamp =
@@ -478,6 +687,8 @@ fn auto_scroll_pos(container: &ScrollableWidget, before: Point, after: Point, si
 
 #[cfg(test)]
 mod tests {
+  use std::{cell::Cell, rc::Rc};
+
   use ribir_core::{prelude::*, reset_test_env, test_helper::*};
   use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 
@@ -534,4 +745,256 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(*value.read(), "hello");
   }
+
+  #[test]
+  fn ancestor_prevents_key_editing() {
+    reset_test_env!();
+    let (value, w_value) = split_value(String::default());
+    let w = fn_widget! {
+      let input = @Input { auto_focus: true };
+      watch!($input.text.clone())
+        .subscribe(move |text| *$w_value.write() = text.to_string());
+      @SizedBox {
+        size: Size::new(200., 24.),
+        on_key_down: |e| {
+          if e.key() == &VirtualKey::Named(NamedKey::Backspace) {
+            e.prevent_default();
+          }
+        },
+        @ { input }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+    wnd.processes_receive_chars("hello".into());
+    wnd.draw_frame();
+    assert_eq!(*value.read(), "hello");
+
+    let press = |wnd: &mut TestWindow, code: KeyCode, key: NamedKey| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(code),
+        VirtualKey::Named(key),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.draw_frame();
+    };
+    press(&mut wnd, KeyCode::Backspace, NamedKey::Backspace);
+    assert_eq!(*value.read(), "hello");
+
+    // The keys that the ancestor doesn't prevent still edit the text.
+    press(&mut wnd, KeyCode::ArrowLeft, NamedKey::ArrowLeft);
+    press(&mut wnd, KeyCode::Delete, NamedKey::Delete);
+    assert_eq!(*value.read(), "hell");
+  }
+
+  fn caret_test_input(
+    shape: CaretShape, blink_interval: Option<Duration>, painted: &Rc<Cell<Option<Rect>>>,
+  ) -> (TestWindow, Stateful<Option<Stateful<Input>>>) {
+    let input_slot = Stateful::new(None);
+    let (c_painted, c_slot) = (painted.clone(), input_slot.clone_writer());
+    let w = fn_widget! {
+      let painted = c_painted.clone();
+      let custom_caret = move |_: &mut Painter, rect: Rect| painted.set(Some(rect));
+      let caret_style = CaretStyle {
+        shape,
+        blink_interval,
+        custom_caret: Some(Rc::new(custom_caret)),
+        ..CaretStyle::of(BuildCtx::get())
+      };
+      let input = @Input { auto_focus: true, caret_style };
+      *c_slot.write() = Some(input.clone_writer());
+      input
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+    wnd.draw_frame();
+    (wnd, input_slot)
+  }
+
+  #[test]
+  fn caret_blink() {
+    reset_test_env!();
+    let painted = Rc::new(Cell::new(None));
+    let interval = Duration::from_millis(500);
+    let (mut wnd, _input) = caret_test_input(CaretShape::Bar, Some(interval), &painted);
+    assert!(painted.take().is_some());
+
+    // Hidden in a blink interval, and shown again in the next one.
+    wnd.advance_time(interval);
+    assert!(wnd.take_last_frame().is_some());
+    assert!(painted.take().is_none());
+    wnd.advance_time(interval);
+    assert!(painted.take().is_some());
+
+    // Keep shown while typing.
+    wnd.advance_time(Duration::from_millis(300));
+    wnd.processes_receive_chars("a".into());
+    wnd.draw_frame();
+    assert!(painted.take().is_some());
+    wnd.advance_time(Duration::from_millis(300));
+    wnd.processes_receive_chars("b".into());
+    wnd.draw_frame();
+    assert!(painted.take().is_some());
+    wnd.take_last_frame();
+    wnd.advance_time(Duration::from_millis(300));
+    assert!(wnd.take_last_frame().is_none());
+
+    // Blink again after the quiet period.
+    wnd.advance_time(Duration::from_millis(200));
+    assert!(wnd.take_last_frame().is_some());
+    assert!(painted.take().is_none());
+  }
+
+  #[test]
+  fn caret_always_on() {
+    reset_test_env!();
+    let painted = Rc::new(Cell::new(None));
+    let (mut wnd, _input) = caret_test_input(CaretShape::Bar, None, &painted);
+    assert!(painted.take().is_some());
+    wnd.take_last_frame();
+
+    wnd.advance_time(Duration::from_secs(2));
+    assert!(wnd.take_last_frame().is_none());
+  }
+
+  #[test]
+  fn block_caret_covers_glyph() {
+    reset_test_env!();
+    let painted = Rc::new(Cell::new(None));
+    let (mut wnd, input) = caret_test_input(CaretShape::Block, None, &painted);
+    let input = input.read().as_ref().unwrap().clone_writer();
+
+    let mut cell_width = |cluster| {
+      let caret = CaretPosition { cluster, position: None };
+      input
+        .write()
+        .set_text_with_caret("iW", caret.into());
+      wnd.draw_frame();
+      painted.take().unwrap().width()
+    };
+    let i = cell_width(0);
+    let w = cell_width(1);
+    assert!(0. < i && i < w);
+    // At the end of the line, it still has a width.
+    assert!(cell_width(2) > 0.);
+  }
+
+  #[test]
+  fn copy_html_and_paste_text() {
+    use ribir_core::clipboard::{ClipboardData, ClipboardFormat};
+
+    reset_test_env!();
+    let (mut wnd, input) = caret_test_input(CaretShape::Bar, None, &Rc::new(Cell::new(None)));
+    let input = input.read().as_ref().unwrap().clone_writer();
+    let press = |wnd: &mut TestWindow, code: KeyCode, c: &str| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(code),
+        VirtualKey::Character(c.into()),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.draw_frame();
+    };
+    #[cfg(target_os = "macos")]
+    let command = ModifiersState::SUPER;
+    #[cfg(not(target_os = "macos"))]
+    let command = ModifiersState::CONTROL;
+
+    wnd.processes_receive_chars("a<b".into());
+    wnd.draw_frame();
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::ModifiersChanged(command.into()));
+    press(&mut wnd, KeyCode::KeyA, "a");
+    press(&mut wnd, KeyCode::KeyC, "c");
+    {
+      let mut clipboard = AppCtx::clipboard().borrow_mut();
+      assert_eq!(clipboard.read_formats(), [ClipboardFormat::Text, ClipboardFormat::Html]);
+      assert_eq!(clipboard.read_text().unwrap(), "a<b");
+      assert_eq!(clipboard.read_html().unwrap(), "a&lt;b");
+    }
+
+    // The plain text is preferred to the HTML.
+    let data = [ClipboardData::Text("plain".into()), ClipboardData::Html("<b>rich</b>".into())];
+    AppCtx::clipboard()
+      .borrow_mut()
+      .write_data(&data)
+      .unwrap();
+    press(&mut wnd, KeyCode::KeyV, "v");
+    assert_eq!(&**SelectableText::text(&*input.read()), "plain");
+
+    // The HTML is pasted as its text if there is no plain text.
+    let data = [ClipboardData::Html("<b>rich</b>".into())];
+    AppCtx::clipboard()
+      .borrow_mut()
+      .write_data(&data)
+      .unwrap();
+    press(&mut wnd, KeyCode::KeyA, "a");
+    press(&mut wnd, KeyCode::KeyV, "v");
+    assert_eq!(&**SelectableText::text(&*input.read()), "rich");
+  }
+
+  #[test]
+  fn obscured_input() {
+    reset_test_env!();
+    let input_slot = Stateful::new(None);
+    let c_slot = input_slot.clone_writer();
+    let w = fn_widget! {
+      let input = @Input {
+        auto_focus: true,
+        obscure_text: true,
+        reveal_last_char: Some(Duration::from_millis(500)),
+      };
+      *c_slot.write() = Some(input.clone_writer());
+      input
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+    let input = input_slot.read().as_ref().unwrap().clone_writer();
+    let painted = || SelectableText::painted_text(&*input.read()).to_string();
+
+    wnd.processes_receive_chars("p".into());
+    wnd.draw_frame();
+    assert_eq!(painted(), "p");
+    wnd.processes_receive_chars("w".into());
+    wnd.draw_frame();
+    assert_eq!(painted(), "•w");
+    wnd.advance_time(Duration::from_millis(500));
+    assert_eq!(painted(), "••");
+    assert_eq!(&**SelectableText::text(&*input.read()), "pw");
+
+    // The content can't be copied.
+    AppCtx::clipboard().borrow_mut().clear().unwrap();
+    #[cfg(target_os = "macos")]
+    let command = ModifiersState::SUPER;
+    #[cfg(not(target_os = "macos"))]
+    let command = ModifiersState::CONTROL;
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::ModifiersChanged(command.into()));
+    for (code, c) in [(KeyCode::KeyA, "a"), (KeyCode::KeyC, "c")] {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(code),
+        VirtualKey::Character(c.into()),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.draw_frame();
+    }
+    assert_eq!(SelectableText::select_range(&*input.read()), 0..2);
+    assert!(
+      AppCtx::clipboard()
+        .borrow_mut()
+        .read_text()
+        .is_err()
+    );
+
+    // Revealed as the plain text.
+    input.write().obscure_text = false;
+    wnd.draw_frame();
+    assert_eq!(painted(), "pw");
+  }
 }
@@ -1,32 +1,125 @@
+use std::rc::Rc;
+
 use ribir_core::prelude::*;
+
+use super::CaretState;
+
+/// The shape of the text caret.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CaretShape {
+  /// A vertical bar before the glyph at the caret.
+  #[default]
+  Bar,
+  /// A block covers the glyph at the caret, like in a terminal.
+  Block,
+  /// A line under the glyph at the caret.
+  Underline,
+}
+
+/// Paint a caret into the rect that its shape fills.
+pub type CaretPainter = Rc<dyn Fn(&mut Painter, Rect)>;
+
+/// The style of the text caret of [`Input`](super::Input) and
+/// [`TextArea`](super::TextArea), provide it to style the carets of a
+/// subtree, or override it by the `caret_style` of a widget.
+#[derive(Clone)]
+pub struct CaretStyle {
+  /// The width of the bar caret, or the thickness of the underline caret.
+  pub width: f32,
+  pub color: Color,
+  /// The time the caret shows or hides in a blink, the caret is always shown
+  /// if it's `None`.
+  pub blink_interval: Option<Duration>,
+  pub shape: CaretShape,
+  /// Paint the caret instead of the `shape`, it gets the rect that the shape
+  /// fills.
+  pub custom_caret: Option<CaretPainter>,
+}
+
+impl CustomStyle for CaretStyle {
+  fn default_style(ctx: &impl ProviderCtx) -> Self {
+    Self {
+      width: 2.,
+      color: Palette::of(ctx).primary(),
+      blink_interval: Some(Duration::from_millis(500)),
+      shape: CaretShape::Bar,
+      custom_caret: None,
+    }
+  }
+}
+
 #[derive(Declare)]
 pub struct Caret {
   pub focused: bool,
-  #[declare(default = svgs::TEXT_CARET)]
-  pub icon: NamedSvg,
+  pub style: CaretStyle,
+  /// The width of the glyph at the caret, the block and the underline caret
+  /// cover it.
+  #[declare(default)]
+  pub cell_width: f32,
+  /// The caret of the text, the caret stops blinking while it moves, such as
+  /// when typing, and blinks again after a blink interval.
+  #[declare(default)]
+  pub text_caret: CaretState,
+}
+
+#[derive(Declare)]
+struct CaretPaint {
+  style: CaretStyle,
+  cell_width: f32,
+}
+
+impl Render for CaretPaint {
+  fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size {
+    let width = match self.style.shape {
+      CaretShape::Bar => self.style.width,
+      CaretShape::Block | CaretShape::Underline => self.cell_width,
+    };
+    clamp.clamp(Size::new(width, 0.))
+  }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let size = ctx.box_size().unwrap();
+    let CaretStyle { width, color, shape, custom_caret, .. } = &self.style;
+    let rect = match shape {
+      CaretShape::Bar | CaretShape::Block => Rect::from_size(size),
+      CaretShape::Underline => {
+        let thickness = width.min(size.height);
+        Rect::new(Point::new(0., size.height - thickness), Size::new(size.width, thickness))
+      }
+    };
+    let painter = ctx.painter();
+    if let Some(custom) = custom_caret {
+      custom(painter, rect);
+    } else {
+      painter.set_fill_brush(*color).rect(&rect).fill();
+    }
+  }
+
+  fn hit_test(&self, _: &HitTestCtx, _: Point) -> HitTest {
+    HitTest { hit: false, can_hit_child: false }
+  }
 }
 
 impl Compose for Caret {
   fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
-    let blink_interval = Duration::from_millis(500);
     fn_widget! {
-      let icon = FatObj::new($this.icon);
-      let mut caret = @ $icon {
+      let mut caret = @CaretPaint {
+        style: pipe!($this.style.clone()),
+        cell_width: pipe!($this.cell_width),
         opacity: 0.,
-        box_fit: BoxFit::CoverY,
       };
       let mut _guard = None;
-      let u = watch!($this.focused)
-        .subscribe(move |focused| {
-          if focused {
-            $caret.write().opacity = 1.;
+      let u = watch!(($this.focused, $this.text_caret))
+        .distinct_until_changed()
+        .subscribe(move |(focused, _)| {
+          _guard = None;
+          $caret.write().opacity = if focused { 1. } else { 0. };
+          let blink_interval = $this.style.blink_interval;
+          if let Some(blink_interval) = blink_interval.filter(|_| focused) {
             let unsub = interval(blink_interval, AppCtx::scheduler())
               .subscribe(move |idx| $caret.write().opacity = (idx % 2) as f32)
               .unsubscribe_when_dropped();
             _guard = Some(unsub);
-          } else {
-            $caret.write().opacity = 0.;
-            _guard = None;
           }
         });
       @ $caret { on_disposed: move |_| u.unsubscribe() }
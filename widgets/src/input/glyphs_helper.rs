@@ -69,6 +69,10 @@ impl TextGlyphsHelper {
     this.line_height_by_caret(caret).into()
   }
 
+  pub(crate) fn cell_width(&self, text: &CowArc<str>, caret: CaretPosition) -> Option<f32> {
+    self.helper.get(text)?.cell_width(caret)
+  }
+
   pub(crate) fn selection(&self, text: &CowArc<str>, rg: &Range<usize>) -> Option<Vec<Rect>> {
     self.helper.get(text)?.selection(rg).into()
   }
@@ -95,18 +99,25 @@ pub(crate) trait GlyphsHelper {
 
   fn line_height_by_caret(&self, caret: CaretPosition) -> f32;
 
+  /// The width of the glyphs of the cluster after the caret, `None` at the
+  /// end of a line.
+  fn cell_width(&self, caret: CaretPosition) -> Option<f32>;
+
   fn selection(&self, rg: &Range<usize>) -> Vec<Rect>;
 
   fn caret_position(&self, caret: CaretPosition) -> (usize, usize);
+
+  fn cluster_glyphs(&self, row: usize, col: usize) -> Range<usize>;
 }
 
 impl GlyphsHelper for VisualGlyphs {
   fn caret_position_from_pos(&self, x: f32, y: f32) -> CaretPosition {
-    let (para, mut offset) = self.nearest_glyph(x, y);
-    let rc = self.glyph_rect(para, offset);
-    if (rc.min_x() - x).abs() > (rc.max_x() - x).abs() {
-      offset += 1;
-    }
+    let (para, offset) = self.nearest_glyph(x, y);
+    // The caret can't be placed inside a cluster, like an emoji sequence.
+    let glyphs = self.cluster_glyphs(para, offset);
+    let left = self.glyph_rect(para, glyphs.start).min_x();
+    let right = self.glyph_rect(para, glyphs.end - 1).max_x();
+    let offset = if (left - x).abs() > (right - x).abs() { glyphs.end } else { glyphs.start };
     let cluster = self.position_to_cluster(para, offset);
     CaretPosition { cluster, position: Some((para, offset)) }
   }
@@ -132,7 +143,7 @@ impl GlyphsHelper for VisualGlyphs {
     let (mut row, mut col) = self.caret_position(caret);
 
     (row, col) = match (row > 0, col > 0) {
-      (_, true) => (row, col - 1),
+      (_, true) => (row, self.cluster_glyphs(row, col - 1).start),
       (true, false) => (row - 1, self.glyph_count(row - 1, true)),
       (false, false) => (0, 0),
     };
@@ -144,7 +155,7 @@ impl GlyphsHelper for VisualGlyphs {
   fn next(&self, caret: CaretPosition) -> CaretPosition {
     let (mut row, mut col) = self.caret_position(caret);
     (row, col) = match (row + 1 < self.glyph_row_count(), col < self.glyph_count(row, true)) {
-      (_, true) => (row, col + 1),
+      (_, true) => (row, self.cluster_glyphs(row, col).end),
       (true, false) => (row + 1, 0),
       (false, false) => (row, self.glyph_count(row, true)),
     };
@@ -190,6 +201,17 @@ impl GlyphsHelper for VisualGlyphs {
     self.line_height(row)
   }
 
+  fn cell_width(&self, caret: CaretPosition) -> Option<f32> {
+    let (row, col) = self.caret_position(caret);
+    if col >= self.glyph_count(row, true) {
+      return None;
+    }
+    let glyphs = self.cluster_glyphs(row, col);
+    let left = self.glyph_rect(row, glyphs.start).min_x();
+    let right = self.glyph_rect(row, glyphs.end - 1).max_x();
+    Some(right - left)
+  }
+
   fn selection(&self, rg: &Range<usize>) -> Vec<Rect> {
     if rg.is_empty() {
       return vec![];
@@ -202,6 +224,26 @@ impl GlyphsHelper for VisualGlyphs {
       .position
       .unwrap_or_else(|| self.position_by_cluster(caret.cluster))
   }
+
+  /// The glyphs in the `row` of the same cluster as the glyph at `col`, an
+  /// emoji sequence may be shaped to several glyphs of one cluster.
+  fn cluster_glyphs(&self, row: usize, col: usize) -> Range<usize> {
+    let count = self.glyph_count(row, false);
+    if col >= count {
+      return col..col + 1;
+    }
+    let cluster = self.position_to_cluster(row, col);
+    let same = |c: &usize| self.position_to_cluster(row, *c) == cluster;
+    let start = (0..col)
+      .rev()
+      .take_while(same)
+      .last()
+      .unwrap_or(col);
+    let end = (col + 1..count)
+      .find(|c| !same(c))
+      .unwrap_or(count);
+    start..end
+  }
 }
 
 #[cfg(test)]
@@ -220,11 +262,9 @@ mod tests {
     let _ = font_db.borrow_mut().load_font_file(path);
     TypographyStore::new(font_db)
   }
-  #[test]
-  fn glyph_move() {
-    let mut store = test_store();
 
-    let style = TextStyle {
+  fn dejavu_style() -> TextStyle {
+    TextStyle {
       font_size: 16.,
       font_face: FontFace {
         families: Box::new([FontFamily::Name("DejaVu Sans".into())]),
@@ -233,7 +273,13 @@ mod tests {
       letter_space: 0.,
       line_height: 16.,
       overflow: TextOverflow::AutoWrap,
-    };
+      ..<_>::default()
+    }
+  }
+  #[test]
+  fn glyph_move() {
+    let mut store = test_store();
+    let style = dejavu_style();
     let glyphs = store.typography(
       "1 23 456 7890\n12345".into(),
       &style,
@@ -264,4 +310,44 @@ mod tests {
     caret = helper.up(caret);
     assert!(caret == CaretPosition { cluster: 0, position: Some((0, 0)) });
   }
+
+  #[test]
+  fn emoji_sequence_is_one_caret_step() {
+    let mut store = test_store();
+    let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../fonts/RibirTestEmoji.ttf";
+    let _ = store.font_db().borrow_mut().load_font_file(path);
+    store
+      .font_db()
+      .borrow_mut()
+      .set_emoji_fonts(&FontFace {
+        families: Box::new([FontFamily::Name("Ribir Test Emoji".into())]),
+        ..<_>::default()
+      });
+
+    // A family emoji joined by ZWJ, 18 bytes.
+    let text = "a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}b";
+    let glyphs = store.typography(
+      text.into(),
+      &dejavu_style(),
+      Size::new(1000., 100.),
+      TextAlign::Start,
+      font_db::GlyphBaseline::Alphabetic,
+      PlaceLineDirection::TopToBottom,
+    );
+    // The emoji is shaped to several glyphs of one cluster.
+    assert!(glyphs.glyph_count(0, true) > 3);
+
+    let mut caret = CaretPosition { cluster: 1, position: None };
+    caret = glyphs.next(caret);
+    assert_eq!(caret.cluster, 19);
+    assert_eq!(caret.position, Some((0, glyphs.glyph_count(0, true) - 1)));
+    caret = glyphs.prev(caret);
+    assert_eq!(caret, CaretPosition { cluster: 1, position: Some((0, 1)) });
+
+    // A click inside the emoji places the caret at one of its sides.
+    let rc = glyphs.glyph_rect(0, 2);
+    let caret = glyphs.caret_position_from_pos(rc.center().x, rc.center().y);
+    assert!(caret.cluster == 1 || caret.cluster == 19);
+    assert_eq!(glyphs.selection(&(1..19)).len(), 1);
+  }
 }
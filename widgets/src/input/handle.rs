@@ -1,12 +1,18 @@
 #![allow(clippy::needless_lifetimes)]
 use std::ops::{Deref, DerefMut};
 
-use ribir_core::prelude::{
-  AppCtx, CharsEvent, GraphemeCursor, KeyCode, KeyboardEvent, NamedKey, PhysicalKey, StateWriter,
-  TextWriter, VirtualKey,
+use ribir_core::{
+  clipboard::html_to_text,
+  prelude::{
+    AppCtx, CharsEvent, GraphemeCursor, KeyCode, KeyboardEvent, NamedKey, PhysicalKey, StateWriter,
+    TextWriter, VirtualKey,
+  },
 };
 
-use super::EditableText;
+use super::{
+  EditableText,
+  text_selectable::{SelectableText, copy_text},
+};
 
 pub struct TextCaretWriter<'a, H>
 where
@@ -56,6 +62,8 @@ where
   fn deref_mut(&mut self) -> &mut Self::Target { &mut self.writer }
 }
 
+/// Type the received characters, it's the default action of the `Chars`
+/// event, a listener can prevent it.
 pub(crate) fn edit_handle<F: EditableText>(this: &impl StateWriter<Value = F>, event: &CharsEvent) {
   if event.common.with_command_key() {
     return;
@@ -74,7 +82,9 @@ pub(crate) fn edit_handle<F: EditableText>(this: &impl StateWriter<Value = F>, e
   }
 }
 
-pub(crate) fn edit_key_handle<F: EditableText>(
+/// Edit the text by the key, it's the default action of the `KeyDown` event, a
+/// listener can prevent it.
+pub(crate) fn edit_key_handle<F: EditableText + SelectableText>(
   this: &impl StateWriter<Value = F>, event: &KeyboardEvent,
 ) {
   let mut deal = false;
@@ -86,7 +96,7 @@ pub(crate) fn edit_key_handle<F: EditableText>(
   }
 }
 
-fn key_with_command<F: EditableText>(
+fn key_with_command<F: EditableText + SelectableText>(
   this: &impl StateWriter<Value = F>, event: &KeyboardEvent,
 ) -> bool {
   if !event.with_command_key() {
@@ -97,11 +107,17 @@ fn key_with_command<F: EditableText>(
   // layout use the same key as shortcut.
   match event.key_code() {
     PhysicalKey::Code(KeyCode::KeyV) => {
-      let clipboard = AppCtx::clipboard();
-      let txt = clipboard.borrow_mut().read_text();
+      // Prefer the plain text, the HTML is stripped if there is no text.
+      let mut clipboard = AppCtx::clipboard().borrow_mut();
+      let txt = clipboard.read_text().or_else(|_| {
+        clipboard
+          .read_html()
+          .map(|html| html_to_text(&html))
+      });
+      drop(clipboard);
       if let Ok(txt) = txt {
         let mut this = this.write();
-        let rg = this.caret().select_range();
+        let rg = EditableText::caret(&*this).select_range();
         let mut writer = TextCaretWriter::new(&mut *this);
         if !rg.is_empty() {
           writer.delete_byte_range(&rg);
@@ -111,14 +127,15 @@ fn key_with_command<F: EditableText>(
       true
     }
     PhysicalKey::Code(KeyCode::KeyX) => {
-      let rg = this.read().caret().select_range();
-      if !rg.is_empty() {
+      // An obscured text can't be cut, just like it can't be copied.
+      let rg = EditableText::caret(&*this.read()).select_range();
+      if !rg.is_empty() && !this.read().is_obscured() {
         let mut this = this.write();
-        let txt = this.text().substr(rg.clone()).to_string();
+        let txt = EditableText::text(&*this)
+          .substr(rg.clone())
+          .to_string();
         TextCaretWriter::new(&mut *this).delete_byte_range(&rg);
-        let clipboard = AppCtx::clipboard();
-        let _ = clipboard.borrow_mut().clear();
-        let _ = clipboard.borrow_mut().write_text(&txt);
+        copy_text(&txt);
       }
       true
     }
@@ -0,0 +1,550 @@
+use std::{
+  cell::RefCell,
+  collections::{HashMap, HashSet},
+  ops::Range,
+  rc::Rc,
+  sync::Arc,
+};
+
+use ribir_core::{local_sender::LocalSender, prelude::*};
+
+use super::{
+  EditableText, SelectableText,
+  caret_state::CaretPosition,
+  glyphs_helper::{GlyphsHelper, TextGlyphsHelper},
+};
+use crate::layout::Column;
+
+/// Check the spelling of the text of an [`Input`](super::Input) or a
+/// [`TextArea`](super::TextArea).
+///
+/// The text is checked by paragraphs off the UI thread when the user stops
+/// typing, and only the edited paragraphs are checked again.
+pub trait SpellChecker: Send + Sync {
+  /// Return the misspelled words of the `paragraph`, their ranges are the byte
+  /// ranges in the `paragraph`.
+  fn check(&self, paragraph: &str) -> Vec<Misspelling>;
+}
+
+/// A misspelled word found by a [`SpellChecker`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+  /// The byte range of the word.
+  pub range: Range<usize>,
+  /// The words to replace it with, the better ones first.
+  pub suggestions: Vec<String>,
+}
+
+/// Set the spell checker of a text input, the text isn't checked by default.
+pub trait SpellCheckDeclarerExtend {
+  fn spell_check(self, checker: Option<Arc<dyn SpellChecker>>) -> Self;
+}
+
+/// A [`SpellChecker`] that knows only the words of its dictionary, the words
+/// are compared case-insensitively. It suggests the words that are one or two
+/// edits away.
+pub struct DictionarySpellChecker {
+  words: HashSet<String>,
+}
+
+impl DictionarySpellChecker {
+  pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+    let words = words
+      .into_iter()
+      .map(|w| w.as_ref().to_lowercase())
+      .collect();
+    Self { words }
+  }
+}
+
+impl SpellChecker for DictionarySpellChecker {
+  fn check(&self, paragraph: &str) -> Vec<Misspelling> {
+    words_of(paragraph)
+      .filter_map(|range| {
+        let word = paragraph[range.clone()].to_lowercase();
+        if self.words.contains(&word) {
+          return None;
+        }
+        let mut suggestions: Vec<_> = self
+          .words
+          .iter()
+          .map(|w| (edit_distance(&word, w), w))
+          .filter(|(d, _)| *d <= 2)
+          .collect();
+        suggestions.sort();
+        let suggestions = suggestions
+          .into_iter()
+          .map(|(_, w)| w.clone())
+          .collect();
+        Some(Misspelling { range, suggestions })
+      })
+      .collect()
+  }
+}
+
+fn words_of(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+  let is_word_char = |c: char| c.is_alphabetic() || c == '\'';
+  let mut start = None;
+  text
+    .char_indices()
+    .chain(std::iter::once((text.len(), ' ')))
+    .filter_map(move |(idx, c)| match (start, is_word_char(c)) {
+      (None, true) => {
+        start = Some(idx);
+        None
+      }
+      (Some(s), false) => {
+        start = None;
+        Some(s..idx)
+      }
+      _ => None,
+    })
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut row: Vec<usize> = (0..=b.len()).collect();
+  for (i, ca) in a.chars().enumerate() {
+    let mut diagonal = row[0];
+    row[0] = i + 1;
+    for (j, cb) in b.iter().enumerate() {
+      let above = row[j + 1];
+      row[j + 1] = (above + 1)
+        .min(row[j] + 1)
+        .min(diagonal + (ca != *cb) as usize);
+      diagonal = above;
+    }
+  }
+  row[b.len()]
+}
+
+/// The style of the underlines of the misspelled words.
+#[derive(Clone, PartialEq)]
+pub struct SpellCheckStyle {
+  pub color: Color,
+  pub thickness: f32,
+  /// The height from the crest to the trough of the wave.
+  pub wave_height: f32,
+  /// The length of a full wave.
+  pub wave_length: f32,
+}
+
+impl CustomStyle for SpellCheckStyle {
+  fn default_style(ctx: &impl ProviderCtx) -> Self {
+    Self { color: Palette::of(ctx).error(), thickness: 1., wave_height: 2., wave_length: 4. }
+  }
+}
+
+/// The paragraphs that have been checked, so only the edited paragraphs are
+/// checked again.
+pub(crate) struct SpellCheck {
+  checker: Arc<dyn SpellChecker>,
+  checked: HashMap<String, Vec<Misspelling>>,
+  checking: HashSet<String>,
+}
+
+fn paragraphs(text: &str) -> impl Iterator<Item = (usize, &str)> {
+  text.split('\n').scan(0, |offset, p| {
+    let start = *offset;
+    *offset += p.len() + 1;
+    Some((start, p))
+  })
+}
+
+impl SpellCheck {
+  fn new(checker: Arc<dyn SpellChecker>) -> Self {
+    Self { checker, checked: <_>::default(), checking: <_>::default() }
+  }
+
+  /// The known misspellings of the `text`, the paragraphs that are not checked
+  /// yet have none.
+  fn misspellings(&self, text: &str) -> Vec<Misspelling> {
+    paragraphs(text)
+      .filter_map(|(offset, p)| Some((offset, self.checked.get(p)?)))
+      .flat_map(|(offset, misspellings)| {
+        misspellings.iter().map(move |m| Misspelling {
+          range: m.range.start + offset..m.range.end + offset,
+          suggestions: m.suggestions.clone(),
+        })
+      })
+      .collect()
+  }
+
+  /// Forget the paragraphs that are not in the `text` anymore, and return the
+  /// paragraphs to check.
+  fn take_unchecked(&mut self, text: &str) -> Vec<String> {
+    let current: HashSet<_> = paragraphs(text).map(|(_, p)| p).collect();
+    self
+      .checked
+      .retain(|p, _| current.contains(p.as_str()));
+    let to_check: Vec<_> = current
+      .into_iter()
+      .filter(|p| !self.checked.contains_key(*p) && !self.checking.contains(*p))
+      .map(str::to_string)
+      .collect();
+    self.checking.extend(to_check.iter().cloned());
+    to_check
+  }
+
+  fn finish(&mut self, results: Vec<(String, Vec<Misspelling>)>) {
+    for (p, misspellings) in results {
+      self.checking.remove(&p);
+      self.checked.insert(p, misspellings);
+    }
+  }
+}
+
+/// How long the text is unchanged before it's checked.
+const IDLE_DURATION: Duration = Duration::from_millis(300);
+
+pub(crate) trait SpellCheckHost: EditableText + SelectableText + 'static {
+  fn spell_checker(&self) -> Option<Arc<dyn SpellChecker>>;
+
+  fn misspellings(&self) -> &[Misspelling];
+
+  fn set_misspellings(&mut self, misspellings: Vec<Misspelling>);
+}
+
+fn update_misspellings<H: SpellCheckHost>(host: &impl StateWriter<Value = H>, spell: &SpellCheck) {
+  let misspellings = spell.misspellings(EditableText::text(&*host.read()));
+  if host.read().misspellings() != misspellings {
+    host.write().set_misspellings(misspellings);
+  }
+}
+
+/// Check the text of the `host` when it's edited, the misspellings are kept in
+/// the `host`.
+pub(crate) fn bind_spell_check<H: SpellCheckHost>(host: impl StateWriter<Value = H> + 'static) {
+  let Some(checker) = host.read().spell_checker() else { return };
+  let spell = Rc::new(RefCell::new(SpellCheck::new(checker)));
+
+  // The checked paragraphs are underlined at once, even they are moved.
+  let (c_host, c_spell) = (host.clone_writer(), spell.clone());
+  host
+    .modifies()
+    .subscribe(move |_| update_misspellings(&c_host, &c_spell.borrow()));
+
+  let (c_host, init) = (host.clone_writer(), EditableText::text(&*host.read()).clone());
+  host
+    .modifies()
+    .map(move |_| EditableText::text(&*c_host.read()).clone())
+    .distinct_until_changed()
+    .merge(observable::of(init))
+    .debounce(IDLE_DURATION, AppCtx::scheduler())
+    .subscribe(move |text| {
+      let paragraphs = spell.borrow_mut().take_unchecked(&text);
+      if paragraphs.is_empty() {
+        return;
+      }
+      let checker = spell.borrow().checker.clone();
+      let proxy = AppCtx::proxy();
+      let back = LocalSender::new((host.clone_writer(), spell.clone()));
+      std::thread::spawn(move || {
+        let results: Vec<_> = paragraphs
+          .into_iter()
+          .map(|p| {
+            let misspellings = checker.check(&p);
+            (p, misspellings)
+          })
+          .collect();
+        proxy.run_on_ui(move || {
+          let (host, spell) = &*back;
+          spell.borrow_mut().finish(results);
+          update_misspellings(host, &spell.borrow());
+        });
+      });
+    });
+}
+
+/// The rects of the misspelled words in the `text`.
+pub(crate) fn misspelled_rects(host: &impl SpellCheckHost, text: &Text) -> Vec<Rect> {
+  let Some(glyphs) = text.glyphs() else { return vec![] };
+  let helper = TextGlyphsHelper::new(text.text.clone(), glyphs.clone());
+  host
+    .misspellings()
+    .iter()
+    .filter_map(|m| helper.selection(EditableText::text(host), &m.range))
+    .flatten()
+    .collect()
+}
+
+/// Paint the wavy underlines at the bottom of the `rects`.
+#[derive(Declare)]
+pub(crate) struct SpellUnderlines {
+  pub(crate) rects: Vec<Rect>,
+  pub(crate) style: SpellCheckStyle,
+}
+
+impl Render for SpellUnderlines {
+  fn perform_layout(&self, _: BoxClamp, _: &mut LayoutCtx) -> Size { Size::zero() }
+
+  fn only_sized_by_parent(&self) -> bool { true }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let SpellCheckStyle { color, thickness, wave_height, wave_length } = self.style;
+    let half = wave_length / 2.;
+    if self.rects.is_empty() || half <= 0. {
+      return;
+    }
+
+    let painter = ctx.painter();
+    painter
+      .set_stroke_brush(color)
+      .set_line_width(thickness);
+    for rc in self.rects.iter() {
+      let bottom = rc.max_y() - thickness / 2.;
+      let top = bottom - wave_height;
+      painter.begin_path(Point::new(rc.min_x(), bottom));
+      let mut x = rc.min_x();
+      let mut up = true;
+      while x < rc.max_x() {
+        x = (x + half).min(rc.max_x());
+        painter.line_to(Point::new(x, if up { top } else { bottom }));
+        up = !up;
+      }
+      painter.end_path(false).stroke();
+    }
+  }
+
+  fn hit_test(&self, _: &HitTestCtx, _: Point) -> HitTest {
+    HitTest { hit: false, can_hit_child: false }
+  }
+}
+
+/// Replace the misspelled `word` in the `range` with the `suggestion`. The
+/// caret in the word goes to the end of the suggestion, and the caret after the
+/// word keeps its place in the text. Nothing changes if the word has been
+/// edited.
+pub(crate) fn replace_misspelled(
+  host: &mut impl EditableText, range: Range<usize>, word: &str, suggestion: &str,
+) {
+  if host.text().get(range.clone()) != Some(word) {
+    return;
+  }
+  let caret = host.caret().cluster();
+  let cluster = if caret >= range.end {
+    caret - range.len() + suggestion.len()
+  } else if caret > range.start {
+    range.start + suggestion.len()
+  } else {
+    caret
+  };
+  let mut text = host.text().to_string();
+  text.replace_range(range, suggestion);
+  host.set_text_with_caret(&text, CaretPosition { cluster, position: None }.into());
+}
+
+/// Show the suggestions of the misspelled word under the pointer when the
+/// secondary button is pressed on the `text_widget`, select a suggestion to
+/// replace the word.
+pub(crate) fn bind_suggestion_menu<H: SpellCheckHost>(
+  host: impl StateWriter<Value = H> + 'static, text_widget: Widget<'static>, text: Reader<Text>,
+) -> Widget<'static> {
+  let writer = host.clone_writer();
+  let w = fn_widget! {
+    let text_widget = FatObj::new(text_widget);
+    @ $text_widget {
+      on_pointer_down: move |e| {
+        if e.mouse_buttons() != MouseButtons::SECONDARY {
+          return;
+        }
+        let pos = e.position();
+        let Some(cluster) = $text
+          .glyphs()
+          .map(|glyphs| glyphs.caret_position_from_pos(pos.x, pos.y).cluster)
+        else {
+          return;
+        };
+        let (range, word, suggestions) = {
+          let host = $host;
+          let host_text = EditableText::text(&*host);
+          let hit = host
+            .misspellings()
+            .iter()
+            .filter(|_| *host_text == $text.text)
+            .filter(|m| !m.suggestions.is_empty())
+            .find(|m| m.range.start <= cluster && cluster <= m.range.end);
+          let Some(m) = hit else { return };
+          (m.range.clone(), host_text[m.range.clone()].to_string(), m.suggestions.clone())
+        };
+
+        let host = writer.clone_writer();
+        let menu = Overlay::new(
+          move || {
+            let (host, range, word) = (host.clone_writer(), range.clone(), word.clone());
+            let items = suggestions.clone().into_iter().map(move |suggestion| {
+              let (host, range, word) = (host.clone_writer(), range.clone(), word.clone());
+              @Text {
+                text: suggestion.clone(),
+                padding: EdgeInsets::symmetrical(4., 8.),
+                on_tap: move |e| {
+                  replace_misspelled(&mut *$host.write(), range.clone(), &word, &suggestion);
+                  if let Some(menu) = Overlay::of(&**e) {
+                    menu.close();
+                  }
+                },
+              }
+            });
+            let w = fn_widget! {
+              @Column {
+                background: Palette::of(BuildCtx::get()).surface_container(),
+                @ { items }
+              }
+            };
+            w.into_widget()
+          },
+          OverlayStyle {
+            auto_close_policy: AutoClosePolicy::ESC | AutoClosePolicy::TAP_OUTSIDE,
+            mask: None,
+          },
+        );
+        menu.show_at(e.global_pos(), e.window());
+      },
+    }
+  };
+  w.into_widget()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Mutex;
+
+  use futures::{StreamExt, channel::mpsc};
+  use ribir_core::{reset_test_env, test_helper::*};
+
+  use super::*;
+  use crate::input::{CaretShape, CaretState, CaretStyle, TextArea};
+
+  /// Record the paragraphs that it checks.
+  struct Recorder {
+    dict: DictionarySpellChecker,
+    checked: Arc<Mutex<Vec<String>>>,
+  }
+
+  impl SpellChecker for Recorder {
+    fn check(&self, paragraph: &str) -> Vec<Misspelling> {
+      self
+        .checked
+        .lock()
+        .unwrap()
+        .push(paragraph.to_string());
+      self.dict.check(paragraph)
+    }
+  }
+
+  /// Pass the idle time to check the text, and wait for the misspellings in the
+  /// `expected` ranges to be applied in the UI thread.
+  fn wait_checked(wnd: &mut TestWindow, area: &Stateful<TextArea>, expected: &[Range<usize>]) {
+    let (applied, mut notified) = mpsc::unbounded();
+    let (c_area, expected) = (area.clone_watcher(), expected.to_vec());
+    let u = area
+      .modifies()
+      .filter(move |_| ranges(&c_area) == expected)
+      .subscribe(move |_| {
+        let _ = applied.unbounded_send(());
+      });
+    wnd.advance_time(IDLE_DURATION);
+    AppCtx::run_until(notified.next());
+    u.unsubscribe();
+    wnd.draw_frame();
+  }
+
+  fn ranges(area: &impl StateReader<Value = TextArea>) -> Vec<Range<usize>> {
+    area
+      .read()
+      .misspellings()
+      .iter()
+      .map(|m| m.range.clone())
+      .collect()
+  }
+
+  #[test]
+  #[allow(clippy::single_range_in_vec_init)]
+  fn check_edited_paragraphs() {
+    reset_test_env!();
+
+    let checked = Arc::new(Mutex::new(vec![]));
+    let dict = DictionarySpellChecker::new(["hello", "world", "spell", "check"]);
+    let checker = Recorder { dict, checked: checked.clone() };
+    let area = Stateful::new(TextArea {
+      style: TextStyle::default(),
+      auto_wrap: true,
+      text: "helo world\nspel check".into(),
+      caret: CaretState::default(),
+      rows: Some(2.),
+      cols: Some(20.),
+      caret_style: CaretStyle {
+        width: 2.,
+        color: Color::BLACK,
+        blink_interval: None,
+        shape: CaretShape::Bar,
+        custom_caret: None,
+      },
+      spell_check: Some(Arc::new(checker)),
+      misspellings: vec![],
+    });
+    let c_area = area.clone_writer();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let area = c_area.clone_writer();
+        @ $area {}
+      },
+      Size::new(300., 300.),
+    );
+    wnd.draw_frame();
+    wait_checked(&mut wnd, &area, &[0..4, 11..15]);
+    assert_eq!(area.read().misspellings()[0].suggestions, ["hello"]);
+
+    // The checked paragraph keeps its underline, the edited one is checked
+    // again.
+    area
+      .write()
+      .set_text_with_caret("helo world\nspell chek", CaretState::default());
+    wnd.draw_frame();
+    assert_eq!(ranges(&area), [0..4]);
+    wait_checked(&mut wnd, &area, &[0..4, 17..21]);
+    assert_eq!(checked.lock().unwrap()[2..], ["spell chek"]);
+
+    area
+      .write()
+      .set_text_with_caret("say helo world\nspell chek", CaretState::default());
+    wnd.draw_frame();
+    assert_eq!(ranges(&area), [21..25]);
+    wait_checked(&mut wnd, &area, &[0..3, 4..8, 21..25]);
+    assert_eq!(checked.lock().unwrap()[3..], ["say helo world"]);
+  }
+
+  #[test]
+  fn replace_by_suggestion() {
+    let mut area = TextArea {
+      style: TextStyle::default(),
+      auto_wrap: true,
+      text: "helo world".into(),
+      caret: CaretPosition { cluster: 10, position: None }.into(),
+      rows: None,
+      cols: None,
+      caret_style: CaretStyle {
+        width: 2.,
+        color: Color::BLACK,
+        blink_interval: None,
+        shape: CaretShape::Bar,
+        custom_caret: None,
+      },
+      spell_check: None,
+      misspellings: vec![],
+    };
+
+    replace_misspelled(&mut area, 0..4, "helo", "hello");
+    assert_eq!(&**EditableText::text(&area), "hello world");
+    assert_eq!(EditableText::caret(&area).cluster(), 11);
+
+    area.set_text_with_caret("helo", CaretPosition { cluster: 2, position: None }.into());
+    replace_misspelled(&mut area, 0..4, "helo", "hello");
+    assert_eq!(&**EditableText::text(&area), "hello");
+    assert_eq!(EditableText::caret(&area).cluster(), 5);
+
+    // The word has been edited.
+    replace_misspelled(&mut area, 0..4, "helo", "hello");
+    assert_eq!(&**EditableText::text(&area), "hello");
+  }
+}
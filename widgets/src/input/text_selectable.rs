@@ -1,6 +1,9 @@
 use std::ops::Range;
 
-use ribir_core::prelude::*;
+use ribir_core::{
+  clipboard::{ClipboardData, text_to_html},
+  prelude::*,
+};
 
 use super::glyphs_helper::TextGlyphsHelper;
 use crate::{
@@ -31,12 +34,29 @@ pub trait SelectableText {
 
   fn set_caret(&mut self, caret: CaretState);
 
+  /// Whether the text is painted obscured, like a password. An obscured text
+  /// can't be copied.
+  fn is_obscured(&self) -> bool { false }
+
+  /// The text that is painted, it differs from the text if it's obscured.
+  fn painted_text(&self) -> CowArc<str> { self.text().clone() }
+
+  /// The byte offset in the painted text of the byte offset `cluster` of the
+  /// text.
+  fn painted_cluster(&self, cluster: usize) -> usize { cluster }
+
+  /// The byte offset in the text of the byte offset `cluster` of the painted
+  /// text.
+  fn text_cluster(&self, cluster: usize) -> usize { cluster }
+
   fn select_text_rect(&self, text: &Text) -> Vec<Rect> {
     text
       .glyphs()
       .and_then(|glyphs| {
         let helper = TextGlyphsHelper::new(text.text.clone(), glyphs.clone());
-        helper.selection(self.text(), &self.select_range())
+        let rg = self.select_range();
+        let rg = self.painted_cluster(rg.start)..self.painted_cluster(rg.end);
+        helper.selection(&self.painted_text(), &rg)
       })
       .unwrap_or_default()
   }
@@ -44,16 +64,28 @@ pub trait SelectableText {
   fn caret_position(&self, text: &Text) -> Option<Point> {
     text.glyphs().and_then(|glyphs| {
       let helper = TextGlyphsHelper::new(text.text.clone(), glyphs.clone());
-      helper.cursor(self.text(), self.caret().caret_position())
+      helper.cursor(&self.painted_text(), self.painted_caret())
+    })
+  }
+
+  fn caret_cell_width(&self, text: &Text) -> Option<f32> {
+    text.glyphs().and_then(|glyphs| {
+      let helper = TextGlyphsHelper::new(text.text.clone(), glyphs.clone());
+      helper.cell_width(&self.painted_text(), self.painted_caret())
     })
   }
 
   fn current_line_height(&self, text: &Text) -> Option<f32> {
     text.glyphs().and_then(|glyphs| {
       let helper = TextGlyphsHelper::new(text.text.clone(), glyphs.clone());
-      helper.line_height(self.text(), self.caret().caret_position())
+      helper.line_height(&self.painted_text(), self.painted_caret())
     })
   }
+
+  fn painted_caret(&self) -> CaretPosition {
+    let caret = self.caret().caret_position();
+    CaretPosition { cluster: self.painted_cluster(caret.cluster), ..caret }
+  }
 }
 
 impl SelectableText for TextSelectable {
@@ -81,6 +113,7 @@ pub(crate) fn bind_point_listener<T: SelectableText>(
         let position = e.position();
         if let Some(helper) = $text.glyphs() {
           let end = helper.caret_position_from_pos(position.x, position.y);
+          let end = CaretPosition { cluster: this.text_cluster(end.cluster), ..end };
           let begin = if e.with_shift_key() {
             match this.caret() {
               CaretState::Caret(begin) |
@@ -101,6 +134,7 @@ pub(crate) fn bind_point_listener<T: SelectableText>(
               if let Some(glyphs) = $text.glyphs() {
                 let position = e.position();
                 let end = glyphs.caret_position_from_pos(position.x, position.y);
+                let end = CaretPosition { cluster: this.text_cluster(end.cluster), ..end };
                 this.set_caret(CaretState::Selecting(begin, end));
               }
           }
@@ -122,8 +156,14 @@ pub(crate) fn bind_point_listener<T: SelectableText>(
         if let Some(glyphs) = $text.glyphs() {
           let position = e.position();
           let caret = glyphs.caret_position_from_pos(position.x, position.y);
-          let rg = select_word(&$text.text, caret.cluster);
-          $this.write().set_caret(CaretState::Select(
+          let mut this = $this.write();
+          let rg = if this.is_obscured() {
+            // The words of an obscured text are not revealed.
+            0..this.text().len()
+          } else {
+            select_word(&$text.text, this.text_cluster(caret.cluster))
+          };
+          this.set_caret(CaretState::Select(
             CaretPosition { cluster: rg.start, position: None },
             CaretPosition { cluster: rg.end, position: None }
           ));
@@ -167,11 +207,15 @@ impl ComposeChild<'static> for TextSelectable {
         only_text.clone_reader(),
       );
 
+      let key_this = this.clone_writer();
+      let key_text = only_text.clone_reader();
       @ $stack {
         tab_index: -1_i16,
         on_blur: move |_| { $this.write().set_caret(CaretState::default()); },
         on_key_down: move |k| {
-          select_key_handle(&this, &$only_text,  k);
+          let this = key_this.clone_writer();
+          let text = key_text.clone_reader();
+          k.add_default_action(move |k| select_key_handle(&this, &text.read(), k));
         },
         @ $high_light_rect { }
         @ $text_widget {}
@@ -181,6 +225,8 @@ impl ComposeChild<'static> for TextSelectable {
   }
 }
 
+/// Move the caret or copy the selection by the key, it's the default action
+/// of the `KeyDown` event, a listener can prevent it.
 pub(crate) fn select_key_handle<F: SelectableText>(
   this: &impl StateWriter<Value = F>, text: &Text, event: &KeyboardEvent,
 ) {
@@ -201,11 +247,10 @@ fn deal_with_command<F: SelectableText>(
   // layout use the same key as shortcut.
   match event.key_code() {
     PhysicalKey::Code(KeyCode::KeyC) => {
-      let text = this.read().selected_text();
-      if !text.is_empty() {
-        let clipboard = AppCtx::clipboard();
-        let _ = clipboard.borrow_mut().clear();
-        let _ = clipboard.borrow_mut().write_text(&text);
+      let this = this.read();
+      let text = this.selected_text();
+      if !text.is_empty() && !this.is_obscured() {
+        copy_text(&text);
       }
     }
     PhysicalKey::Code(KeyCode::KeyA) => {
@@ -220,6 +265,12 @@ fn deal_with_command<F: SelectableText>(
   true
 }
 
+/// Copy the `text` to the clipboard as both the plain text and the HTML.
+pub(crate) fn copy_text(text: &str) {
+  let data = [ClipboardData::Text(text.to_string()), ClipboardData::Html(text_to_html(text))];
+  let _ = AppCtx::clipboard().borrow_mut().write_data(&data);
+}
+
 fn is_move_by_word(event: &KeyboardEvent) -> bool {
   #[cfg(target_os = "macos")]
   return event.with_alt_key();
@@ -233,35 +284,45 @@ fn deal_with_selection<F: SelectableText>(
   let Some(glyphs) = text.glyphs() else { return };
   let helper = TextGlyphsHelper::new(text.text.clone(), glyphs.clone());
 
-  let old_caret = this.read().caret();
-  let text = this.read().text().clone();
+  let this_ref = this.read();
+  let old_caret = this_ref.caret();
+  let text = this_ref.text().clone();
+  let painted = this_ref.painted_text();
+  let caret = this_ref.painted_caret();
+  // The words of an obscured text are not revealed, moving by word moves to the
+  // begin or end of the line.
+  let obscured = this_ref.is_obscured();
+  let by_word = is_move_by_word(event) && !obscured;
+  let to_line_edge = event.with_command_key() || (is_move_by_word(event) && obscured);
+  let to_text = |c: CaretPosition| CaretPosition { cluster: this_ref.text_cluster(c.cluster), ..c };
   let new_caret_position = match event.key() {
     VirtualKey::Named(NamedKey::ArrowLeft) => {
-      if is_move_by_word(event) {
+      if by_word {
         let cluster = select_prev_word(&text, old_caret.cluster(), false).start;
         Some(CaretPosition { cluster, position: None })
-      } else if event.with_command_key() {
-        helper.line_begin(&text, old_caret.caret_position())
+      } else if to_line_edge {
+        helper.line_begin(&painted, caret).map(to_text)
       } else {
-        helper.prev(&text, old_caret.caret_position())
+        helper.prev(&painted, caret).map(to_text)
       }
     }
     VirtualKey::Named(NamedKey::ArrowRight) => {
-      if is_move_by_word(event) {
+      if by_word {
         let cluster = select_next_word(&text, old_caret.cluster(), true).end;
         Some(CaretPosition { cluster, position: None })
-      } else if event.with_command_key() {
-        helper.line_end(&text, old_caret.caret_position())
+      } else if to_line_edge {
+        helper.line_end(&painted, caret).map(to_text)
       } else {
-        helper.next(&text, old_caret.caret_position())
+        helper.next(&painted, caret).map(to_text)
       }
     }
-    VirtualKey::Named(NamedKey::ArrowUp) => helper.up(&text, old_caret.caret_position()),
-    VirtualKey::Named(NamedKey::ArrowDown) => helper.down(&text, old_caret.caret_position()),
-    VirtualKey::Named(NamedKey::Home) => helper.line_begin(&text, old_caret.caret_position()),
-    VirtualKey::Named(NamedKey::End) => helper.line_end(&text, old_caret.caret_position()),
+    VirtualKey::Named(NamedKey::ArrowUp) => helper.up(&painted, caret).map(to_text),
+    VirtualKey::Named(NamedKey::ArrowDown) => helper.down(&painted, caret).map(to_text),
+    VirtualKey::Named(NamedKey::Home) => helper.line_begin(&painted, caret).map(to_text),
+    VirtualKey::Named(NamedKey::End) => helper.line_end(&painted, caret).map(to_text),
     _ => None,
   };
+  drop(this_ref);
 
   if new_caret_position.is_some() {
     if event.with_shift_key() {
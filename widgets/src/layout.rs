@@ -1,4 +1,6 @@
-#[derive(Debug, Copy, Clone, PartialEq, Default)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum Direction {
   #[default]
   /// Left and right.
@@ -96,10 +96,14 @@ impl Render for Flex {
     if Align::Stretch == self.align_items {
       min_size.cross = max_size.cross;
     }
+    // A horizontal flex starts from the right in a right-to-left layout.
+    let rtl =
+      direction == Direction::Horizontal && ctx.text_direction() == TextDirection::RightToLeft;
     let mut layouter = FlexLayouter {
       max: max_size,
       min: min_size,
       reverse: self.reverse,
+      rtl,
       dir: direction,
       align_items: self.align_items,
       justify_content: self.justify_content,
@@ -151,6 +155,8 @@ struct FlexLayouter {
   max: FlexSize,
   min: FlexSize,
   reverse: bool,
+  /// Mirror the main axis, the start of the main axis is at the right.
+  rtl: bool,
   dir: Direction,
   align_items: Align,
   justify_content: JustifyContent,
@@ -313,6 +319,12 @@ impl FlexLayouter {
     } else {
       update_position!();
     }
+    if self.rtl {
+      lines
+        .iter_mut()
+        .flat_map(|l| l.items_info.iter_mut())
+        .for_each(|item| item.pos.main = bound.main - item.pos.main - item.size.main);
+    }
 
     let (ctx, mut children) = ctx.split_children();
 
@@ -590,6 +602,19 @@ mod tests {
     .with_wnd_size(Size::new(500., 500.))
   }
 
+  widget_layout_test!(
+    rtl_start_main_align,
+    WidgetTester::new(fn_widget! {
+      let row = main_align(JustifyContent::Start).widget;
+      Provider::new(Box::new(Queryable(TextDirection::RightToLeft)))
+        .with_child(fn_widget! { row.gen_widget() })
+    })
+    .with_wnd_size(Size::new(500., 500.)),
+    LayoutCase::new(&[0, 0, 0]).with_x(400.),
+    LayoutCase::new(&[0, 0, 1]).with_x(300.),
+    LayoutCase::new(&[0, 0, 2]).with_x(200.)
+  );
+
   widget_layout_test!(
     start_main_align,
     main_align(JustifyContent::Start),
@@ -24,8 +24,12 @@ impl Render for SizedBox {
 
 #[cfg(test)]
 mod tests {
-  use ribir_core::test_helper::*;
+  use ribir_core::{reset_test_env, test_helper::*};
   use ribir_dev_helper::*;
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, ElementState, MouseButton, WindowEvent},
+  };
 
   use super::*;
 
@@ -71,4 +75,59 @@ mod tests {
     WidgetTester::new(fn_widget!(SizedBox { size: Size::new(10., 10.) })),
     LayoutCase::default().with_size(Size::new(10., 10.))
   );
+
+  #[test]
+  fn drag_by_anchor() {
+    reset_test_env!();
+
+    let (events, w_events) = split_value(vec![]);
+    let w = fn_widget! {
+      let (w_events2, w_events3) = (w_events.clone_writer(), w_events.clone_writer());
+      let pos = Stateful::new(Point::zero());
+      @SizedBox {
+        size: Size::new(20., 20.),
+        anchor: pipe!(Anchor::from_point(*$pos)),
+        on_tap: move |_| $w_events.write().push("tap"),
+        on_drag_start: move |_| $w_events2.write().push("start"),
+        on_drag_update: move |e| *$pos.write() += e.delta,
+        on_drag_end: move |_| $w_events3.write().push("end"),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let move_to = |wnd: &TestWindow, x: f32, y: f32| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id: unsafe { DeviceId::dummy() },
+        position: LogicalPosition::new(x, y).to_physical(1.),
+      });
+    };
+    let press = |wnd: &TestWindow, state| {
+      wnd.process_mouse_input(unsafe { DeviceId::dummy() }, state, MouseButton::Left);
+    };
+
+    // A tap moves less than the slop.
+    move_to(&wnd, 10., 10.);
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, 12., 10.);
+    press(&wnd, ElementState::Released);
+    wnd.draw_frame();
+    assert_eq!(*events.read(), ["tap"]);
+    LayoutCase::expect_pos(&wnd, &[0], Point::zero());
+
+    // The drag follows the pointer out of the box.
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, 20., 15.);
+    wnd.draw_frame();
+    LayoutCase::expect_pos(&wnd, &[0], Point::new(8., 5.));
+    move_to(&wnd, 70., 60.);
+    wnd.draw_frame();
+    LayoutCase::expect_pos(&wnd, &[0], Point::new(58., 50.));
+    move_to(&wnd, 90., 90.);
+    press(&wnd, ElementState::Released);
+    wnd.draw_frame();
+    LayoutCase::expect_pos(&wnd, &[0], Point::new(78., 80.));
+    assert_eq!(*events.read(), ["tap", "start", "end"]);
+  }
 }
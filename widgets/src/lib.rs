@@ -1,28 +1,45 @@
+pub mod animated_text;
 pub mod avatar;
 pub mod buttons;
+pub mod chart;
 pub mod checkbox;
 pub mod common_widget;
 pub mod divider;
+pub mod dock;
+#[cfg(feature = "gallery")]
+pub mod gallery;
 pub mod grid_view;
 pub mod icon;
+pub mod infinite_list;
 pub mod input;
 pub mod label;
 pub mod layout;
 pub mod link;
 pub mod lists;
+pub mod menu;
+pub mod page_view;
 pub mod path;
+pub mod press_feedback;
 pub mod progress;
+pub mod pull_to_refresh;
 pub mod radio;
+pub mod responsive;
 pub mod scrollbar;
+pub mod selection;
+pub mod skeleton;
 pub mod slider;
+pub mod split_pane;
 pub mod tabs;
 pub mod text_field;
+pub mod title_bar;
 
 pub mod transform_box;
 pub mod prelude {
   pub use super::{
-    avatar::*, buttons::*, checkbox::*, common_widget::*, divider::*, grid_view::*, icon::*,
-    input::*, label::*, layout::*, link::*, lists::*, path::*, progress::*, radio::*, scrollbar::*,
-    slider::*, tabs::*, text_field::*, transform_box::*,
+    animated_text::*, avatar::*, buttons::*, chart::*, checkbox::*, common_widget::*, divider::*,
+    dock::*, grid_view::*, icon::*, infinite_list::*, input::*, label::*, layout::*, link::*,
+    lists::*, menu::*, page_view::*, path::*, press_feedback::*, progress::*, pull_to_refresh::*,
+    radio::*, responsive::*, scrollbar::*, selection::*, skeleton::*, slider::*, split_pane::*,
+    tabs::*, text_field::*, title_bar::*, transform_box::*,
   };
 }
@@ -6,7 +6,8 @@ use webbrowser::{Browser, open_browser as open};
 pub struct UrlLink {
   /// Want to open url
   url: CowArc<str>,
-  /// Select the browser software you expect to open
+  /// Select the browser software you expect to open, the default one opens
+  /// the url by [`AppCtx::open_url`].
   #[declare(default=Browser::Default)]
   browser: Browser,
 }
@@ -17,10 +18,49 @@ impl<'c> ComposeChild<'c> for UrlLink {
     FatObj::new(child)
       .on_tap(move |_| {
         let this = this.read();
-        if open(this.browser, &this.url).is_err() {
-          warn!("Open link fail");
+        let res = if this.browser == Browser::Default {
+          AppCtx::open_url(&this.url)
+        } else {
+          open(this.browser, &this.url)
+        };
+        if let Err(err) = res {
+          warn!("Open link {} fail: {err}", &*this.url);
         }
       })
       .into_widget()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{opener::MockOpener, reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::prelude::*;
+
+  #[test]
+  fn open_by_app_opener() {
+    reset_test_env!();
+
+    let opener = MockOpener::default();
+    AppCtx::set_opener(Box::new(opener.clone()));
+    let w = fn_widget! {
+      @UrlLink {
+        url: "https://github.com/RibirX/Ribir",
+        @SizedBox { size: Size::new(100., 20.) }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (10., 10.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+
+    assert_eq!(opener.opened_urls(), ["https://github.com/RibirX/Ribir"]);
+  }
+}
@@ -342,7 +342,7 @@ impl CustomStyle for ListItemStyle {
     let typography = TypographyTheme::of(ctx);
     let palette = Palette::of(ctx);
     ListItemStyle {
-      padding_style: Some(EdgeInsets { left: 0., right: 24., bottom: 8., top: 8. }),
+      padding_style: Some(EdgeInsets::new(8., 24., 8., 0.)),
       item_align: |num| {
         if num >= 2 { Align::Start } else { Align::Center }
       },
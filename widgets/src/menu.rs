@@ -0,0 +1,488 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ribir_core::prelude::*;
+
+use crate::prelude::*;
+
+class_names! {
+  #[doc = "Class name for the menu"]
+  MENU,
+  #[doc = "Class name for the item of the menu"]
+  MENU_ITEM,
+  #[doc = "Class name for the icon of the menu item"]
+  MENU_ITEM_ICON,
+  #[doc = "Class name for the label of the menu item"]
+  MENU_ITEM_LABEL,
+}
+
+/// A vertical list of the [`MenuItem`]s, show it by an overlay, such as a
+/// context menu.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// AppCtx::register_command("app::save", Command::new("save", || {}));
+/// AppCtx::register_command("app::open", Command::new("open", || {}));
+///
+/// let _w = fn_widget! {
+///   @Menu {
+///     @MenuItem { command: "app::open" }
+///     @MenuItem { command: "app::save" }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct Menu;
+
+/// An item of the [`Menu`] that shows the icon and the title of a registered
+/// [`Command`], and executes the command when it's tapped. It's dimmed while
+/// the command is disabled, see [`AppCtx::register_command`].
+#[derive(Declare)]
+pub struct MenuItem {
+  pub command: &'static str,
+}
+
+impl<'c> ComposeChild<'c> for Menu {
+  type Child = Vec<Widget<'c>>;
+
+  fn compose_child(_: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    fn_widget! {
+      @Column { class: MENU, @ { child } }
+    }
+    .into_widget()
+  }
+}
+
+impl Compose for MenuItem {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    let command = this.read().command;
+    let Some(info) = AppCtx::command_info(command) else {
+      log::warn!("The command `{command}` of the menu item isn't registered.");
+      return Void.into_widget();
+    };
+    fn_widget! {
+      let icon = info.icon.map(|name| @Icon {
+        class: MENU_ITEM_ICON,
+        @ { named_svgs::get_or_default(name) }
+      });
+      @CommandBinding {
+        command,
+        @HorizontalLine {
+          class: MENU_ITEM,
+          @ { icon }
+          @Text { class: MENU_ITEM_LABEL, text: info.title.clone() }
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+/// A menu that opens at the pointer when the secondary button is pressed on
+/// its child, such as the right-click menu of a list.
+///
+/// The `menu_builder` builds the entries of the menu for the widget under the
+/// pointer, the target of the pointer event, so the menu can depend on what's
+/// clicked. The menu flips or slides to stay inside the window, see
+/// [`Overlay::show_at`], and a submenu opens beside its entry when the entry is
+/// hovered or tapped.
+///
+/// The menus are closed by tapping outside them or by the `Esc` key, or after
+/// an item is chosen, then `on_select` is called with the value of the item.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let _w = fn_widget! {
+///   @ContextMenu {
+///     menu_builder: |_: WidgetId| vec![
+///       ContextMenuEntry::item("Copy", "copy"),
+///       ContextMenuEntry::submenu("Share", vec![ContextMenuEntry::item("Mail", "mail")]),
+///     ],
+///     on_select: |action: &'static str| log::info!("{action} is chosen"),
+///     @Text { text: "Right click me" }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct ContextMenu<T>
+where
+  T: 'static,
+{
+  /// Build the entries of the menu for the widget that's clicked.
+  pub menu_builder: ContextMenuBuilder<T>,
+  /// Called with the value of the chosen item.
+  pub on_select: ContextMenuSelect<T>,
+}
+
+/// An entry of a [`ContextMenu`].
+#[derive(Clone)]
+pub enum ContextMenuEntry<T> {
+  /// An item that can be chosen.
+  Item { label: CowArc<str>, value: T },
+  /// An entry that opens a submenu of the `entries`.
+  Submenu { label: CowArc<str>, entries: Vec<ContextMenuEntry<T>> },
+}
+
+/// The builder of [`ContextMenu::menu_builder`].
+pub struct ContextMenuBuilder<T>(Box<dyn Fn(WidgetId) -> Vec<ContextMenuEntry<T>>>);
+
+impl<T, F> From<F> for ContextMenuBuilder<T>
+where
+  F: Fn(WidgetId) -> Vec<ContextMenuEntry<T>> + 'static,
+{
+  fn from(f: F) -> Self { Self(Box::new(f)) }
+}
+
+/// The callback of [`ContextMenu::on_select`].
+pub struct ContextMenuSelect<T>(Rc<RefCell<dyn FnMut(T)>>);
+
+impl<T, F> From<F> for ContextMenuSelect<T>
+where
+  F: FnMut(T) + 'static,
+{
+  fn from(f: F) -> Self { Self(Rc::new(RefCell::new(f))) }
+}
+
+impl<T> ContextMenuEntry<T> {
+  /// An item of the `label` that's chosen as the `value`.
+  pub fn item(label: impl Into<CowArc<str>>, value: T) -> Self {
+    Self::Item { label: label.into(), value }
+  }
+
+  /// An entry of the `label` that opens a submenu of the `entries`.
+  pub fn submenu(label: impl Into<CowArc<str>>, entries: Vec<ContextMenuEntry<T>>) -> Self {
+    Self::Submenu { label: label.into(), entries }
+  }
+}
+
+impl<'c, T: Clone + 'static> ComposeChild<'c> for ContextMenu<T> {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    fn_widget! {
+      let child = FatObj::new(child);
+      @ $child {
+        on_pointer_down: move |e| {
+          if e.mouse_buttons() != MouseButtons::SECONDARY {
+            return;
+          }
+          let (entries, on_select) = {
+            let this = $this;
+            ((this.menu_builder.0)(e.target()), this.on_select.0.clone())
+          };
+          if entries.is_empty() {
+            return;
+          }
+          let menus = Rc::new(OpenMenus { menus: <_>::default(), on_select });
+          menus.open(entries, e.global_pos(), e.window());
+          e.stop_propagation();
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+/// The menus opened by a [`ContextMenu`], the root menu first.
+struct OpenMenus<T> {
+  menus: RefCell<Vec<Overlay>>,
+  on_select: Rc<RefCell<dyn FnMut(T)>>,
+}
+
+impl<T: Clone + 'static> OpenMenus<T> {
+  /// Open a menu of the `entries` after the last open menu.
+  fn open(self: &Rc<Self>, entries: Vec<ContextMenuEntry<T>>, pos: Point, wnd: Sc<Window>) {
+    let depth = self.menus.borrow().len();
+    let this = self.clone();
+    let entries = Rc::new(entries);
+    let auto_close_policy = if depth == 0 {
+      AutoClosePolicy::ESC | AutoClosePolicy::TAP_OUTSIDE
+    } else {
+      AutoClosePolicy::ESC
+    };
+    let menu = Overlay::new(
+      move || this.menu_widget(&entries, depth),
+      OverlayStyle { auto_close_policy, mask: None },
+    );
+    self.menus.borrow_mut().push(menu.clone());
+    menu.show_at(pos, wnd);
+  }
+
+  fn menu_widget(
+    self: &Rc<Self>, entries: &[ContextMenuEntry<T>], depth: usize,
+  ) -> Widget<'static> {
+    let this = self.clone();
+    let items = entries
+      .iter()
+      .cloned()
+      .map(|entry| self.entry_widget(entry, depth))
+      .collect::<Vec<_>>();
+    fn_widget! {
+      @Column {
+        class: MENU,
+        auto_focus: true,
+        background: Palette::of(BuildCtx::get()).surface_container(),
+        on_disposed: move |_| {
+          // The root menu is closed by tapping outside, close its submenus.
+          let root = this.menus.borrow().first().cloned();
+          if depth == 0 && root.is_some_and(|root| !root.is_showing()) {
+            this.close_from(0);
+          }
+        },
+        @ { items }
+      }
+    }
+    .into_widget()
+  }
+
+  fn entry_widget(self: &Rc<Self>, entry: ContextMenuEntry<T>, depth: usize) -> Widget<'static> {
+    let this = self.clone();
+    match entry {
+      ContextMenuEntry::Item { label, value } => {
+        let hover = self.clone();
+        fn_widget! {
+          @HorizontalLine {
+            class: MENU_ITEM,
+            on_pointer_enter: move |_| hover.close_from(depth + 1),
+            on_tap: move |_| this.select(value.clone()),
+            @Text { class: MENU_ITEM_LABEL, text: label.clone() }
+          }
+        }
+        .into_widget()
+      }
+      ContextMenuEntry::Submenu { label, entries } => {
+        // Hovering the entry reopens its submenu in case another one is open,
+        // tapping it only opens the submenu if it's closed.
+        let open_submenu = move |e: &mut PointerEvent, reopen: bool| {
+          let is_open = this
+            .menus
+            .borrow()
+            .get(depth + 1)
+            .is_some_and(Overlay::is_showing);
+          if reopen || !is_open {
+            this.close_from(depth + 1);
+            let wnd = e.window();
+            let id = e.current_target();
+            let width = wnd.widget_size(id).unwrap_or_default().width;
+            let pos = wnd.map_to_global(Point::new(width, 0.), id);
+            this.open(entries.clone(), pos, wnd);
+          }
+        };
+        let hover = open_submenu.clone();
+        fn_widget! {
+          @HorizontalLine {
+            class: MENU_ITEM,
+            on_pointer_enter: move |e| hover(e, true),
+            on_tap: move |e| open_submenu(e, false),
+            @Text { class: MENU_ITEM_LABEL, text: label.clone() }
+            @Text { text: "›" }
+          }
+        }
+        .into_widget()
+      }
+    }
+  }
+
+  /// Close the menus from the `depth`.
+  fn close_from(&self, depth: usize) {
+    let closed = {
+      let mut menus = self.menus.borrow_mut();
+      let depth = depth.min(menus.len());
+      menus.split_off(depth)
+    };
+    closed.iter().for_each(Overlay::close);
+  }
+
+  fn select(&self, value: T) {
+    self.close_from(0);
+    (self.on_select.borrow_mut())(value);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, rc::Rc};
+
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+
+  #[test]
+  fn menu_item_and_shortcut_share_command() {
+    reset_test_env!();
+
+    let executed = Rc::new(Cell::new(0));
+    let c_executed = executed.clone();
+    let enabled = Stateful::new(false);
+    AppCtx::register_command(
+      "test::save",
+      Command::new("Save", move || c_executed.set(c_executed.get() + 1))
+        .with_enabled(enabled.clone_watcher()),
+    );
+    let ctrl_s = Shortcut::new(ModifiersState::CONTROL, VirtualKey::Character("s".into()));
+    AppCtx::bind_shortcut(ctrl_s, "test::save");
+
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @Menu {
+          @MenuItem { command: "test::save" }
+        }
+      },
+      Size::new(200., 200.),
+    );
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let tap_item = |wnd: &TestWindow| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (2., 2.).into() });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+    let press_shortcut = |wnd: &TestWindow| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(KeyCode::KeyS),
+        VirtualKey::Character("s".into()),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.run_frame_tasks();
+    };
+    let info = AppCtx::command_info("test::save").unwrap();
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::ModifiersChanged(ModifiersState::CONTROL.into()));
+
+    // Both respect the disabled command.
+    assert!(!*info.enabled.read());
+    tap_item(&wnd);
+    press_shortcut(&wnd);
+    assert_eq!(executed.get(), 0);
+
+    *enabled.write() = true;
+    wnd.draw_frame();
+    assert!(*info.enabled.read());
+    tap_item(&wnd);
+    assert_eq!(executed.get(), 1);
+    press_shortcut(&wnd);
+    assert_eq!(executed.get(), 2);
+
+    *enabled.write() = false;
+    wnd.draw_frame();
+    assert!(!*info.enabled.read());
+    tap_item(&wnd);
+    press_shortcut(&wnd);
+    assert_eq!(executed.get(), 2);
+  }
+
+  #[test]
+  fn context_menu_at_corner() {
+    reset_test_env!();
+
+    let targets = Rc::new(RefCell::new(vec![]));
+    let selected = Rc::new(RefCell::new(vec![]));
+    let (c_targets, c_selected) = (targets.clone(), selected.clone());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let (targets, selected) = (c_targets.clone(), c_selected.clone());
+        @ContextMenu {
+          menu_builder: move |target: WidgetId| {
+            targets.borrow_mut().push(target);
+            vec![
+              ContextMenuEntry::item("Copy", 1),
+              ContextMenuEntry::submenu("More", vec![ContextMenuEntry::item("Paste", 2)]),
+            ]
+          },
+          on_select: move |v: i32| selected.borrow_mut().push(v),
+          @MockBox { size: Size::new(200., 200.) }
+        }
+      },
+      Size::new(200., 200.),
+    );
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let click = |wnd: &mut TestWindow, pos: Point, btn: MouseButton| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: (pos.x, pos.y).into(),
+      });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, btn);
+      wnd.run_frame_tasks();
+      wnd.process_mouse_input(device_id, ElementState::Released, btn);
+      wnd.draw_frame();
+    };
+    // The global rect of the widget at the `path` of the dump.
+    let rect_of = |wnd: &TestWindow, path: &[usize]| {
+      let nodes = wnd.tree_dump().nodes;
+      let mut origin = Point::zero();
+      let mut rect = Rect::zero();
+      for i in 1..=path.len() {
+        let node = nodes
+          .iter()
+          .find(|n| n.path == path[..i])
+          .unwrap();
+        rect = node.rect.unwrap().translate(origin.to_vector());
+        origin = rect.origin;
+      }
+      rect
+    };
+    let menus = |wnd: &TestWindow| {
+      let nodes = wnd.tree_dump().nodes;
+      nodes.iter().filter(|n| n.path.len() == 1).count() - 1
+    };
+    let wnd_rect = Rect::from_size(Size::new(200., 200.));
+
+    // The menu near the bottom-right corner flips to end at the pointer.
+    click(&mut wnd, Point::new(190., 190.), MouseButton::Right);
+    let content = wnd.tree_dump().nodes[1].id;
+    assert_eq!(*targets.borrow(), [content]);
+    assert_eq!(menus(&wnd), 1);
+    let menu = rect_of(&wnd, &[1, 0]);
+    assert!(wnd_rect.contains_rect(&menu));
+    assert_eq!(menu.max(), Point::new(190., 190.));
+
+    // The submenu stays inside the window too.
+    let more = rect_of(&wnd, &[1, 0, 1]);
+    click(&mut wnd, more.center(), MouseButton::Left);
+    assert_eq!(menus(&wnd), 2);
+    let submenu = rect_of(&wnd, &[2]);
+    assert!(wnd_rect.contains_rect(&submenu));
+
+    let paste = rect_of(&wnd, &[2, 0]);
+    click(&mut wnd, paste.center(), MouseButton::Left);
+    wnd.draw_frame();
+    assert_eq!(*selected.borrow(), [2]);
+    assert_eq!(menus(&wnd), 0);
+
+    // Tapping outside closes the menu without choosing.
+    click(&mut wnd, Point::new(20., 20.), MouseButton::Right);
+    assert_eq!(menus(&wnd), 1);
+    click(&mut wnd, Point::new(150., 20.), MouseButton::Left);
+    wnd.draw_frame();
+    assert_eq!(menus(&wnd), 0);
+    assert_eq!(*selected.borrow(), [2]);
+
+    // So does the `Esc` key.
+    click(&mut wnd, Point::new(20., 20.), MouseButton::Right);
+    assert_eq!(menus(&wnd), 1);
+    wnd.processes_keyboard_event(
+      PhysicalKey::Code(KeyCode::Escape),
+      VirtualKey::Named(NamedKey::Escape),
+      false,
+      KeyLocation::Standard,
+      ElementState::Pressed,
+    );
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert_eq!(menus(&wnd), 0);
+  }
+}
@@ -0,0 +1,596 @@
+use ribir_core::{prelude::*, ticker::FrameMsg};
+
+use crate::prelude::*;
+
+class_names! {
+  #[doc = "Class name for the dots of the page indicator"]
+  PAGE_INDICATOR_DOT,
+  #[doc = "Class name for the dot of the current page, instead of `PAGE_INDICATOR_DOT`"]
+  PAGE_INDICATOR_ACTIVE_DOT,
+}
+
+/// A widget that shows one of its children as a page at a time, and swipes to
+/// the next or the previous page by dragging.
+///
+/// A fast fling turns to the next page in its direction, otherwise the pages
+/// settle at the page nearest to where the pointer lifts. Only the current
+/// page and the pages beside it are laid out.
+///
+/// Share the [`PageController`] of the widget with a [`PageIndicator`], or use
+/// it to change the page by code.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let controller = Stateful::new(PageController::default());
+/// let _w = fn_widget! {
+///   @Column {
+///     @Expanded {
+///       @PageView {
+///         controller: controller.clone_writer(),
+///         looping: true,
+///         on_page_changed: |page: usize| println!("page {page}"),
+///         @Text { text: "First" }
+///         @Text { text: "Second" }
+///         @Text { text: "Third" }
+///       }
+///     }
+///     @PageIndicator { controller: controller.clone_writer() }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct PageView {
+  /// The state of the pages.
+  #[declare(default = Stateful::new(PageController::default()))]
+  pub controller: Stateful<PageController>,
+  /// The direction that the pages are laid out and swiped along.
+  #[declare(default)]
+  pub direction: Direction,
+  /// Whether the last page is followed by the first one, and the first one is
+  /// preceded by the last one.
+  #[declare(default)]
+  pub looping: bool,
+  /// Called when the page is changed by dragging or by the controller.
+  #[declare(default)]
+  pub on_page_changed: PageChangedHandler,
+}
+
+/// The callback of [`PageView::on_page_changed`], it receives the index of the
+/// new page.
+pub struct PageChangedHandler(Box<dyn FnMut(usize)>);
+
+impl Default for PageChangedHandler {
+  fn default() -> Self { Self(Box::new(|_| {})) }
+}
+
+impl<F: FnMut(usize) + 'static> From<F> for PageChangedHandler {
+  fn from(f: F) -> Self { Self(Box::new(f)) }
+}
+
+/// A row of dots that shows the current page of a [`PageView`] sharing the
+/// same controller, tap a dot to go to its page.
+#[derive(Declare)]
+pub struct PageIndicator {
+  /// The controller of the page view that the indicator is for.
+  pub controller: Stateful<PageController>,
+}
+
+/// The state of a [`PageView`].
+pub struct PageController {
+  page: usize,
+  page_count: usize,
+  /// The position in pages, it's not wrapped in a looping page view.
+  position: f32,
+  /// The size of a page along the direction of the page view.
+  extent: f32,
+  looping: bool,
+  motion: Option<ScrollMotion>,
+  /// The physics that the pages settle by.
+  pub physics: ScrollPhysics,
+}
+
+/// The speed of a fling in logical pixels per second, a faster one turns to
+/// the next page in its direction.
+const FLING_VELOCITY: f32 = 300.;
+
+impl Default for PageController {
+  fn default() -> Self { Self::new(0) }
+}
+
+impl PageController {
+  /// A controller that starts at the `page`.
+  pub fn new(page: usize) -> Self {
+    Self {
+      page,
+      page_count: 0,
+      position: page as f32,
+      extent: 0.,
+      looping: false,
+      motion: None,
+      physics: ScrollPhysics::default(),
+    }
+  }
+
+  /// The index of the current page, it's the page that the pages are settling
+  /// at if they're moving.
+  pub fn page(&self) -> usize { self.page }
+
+  /// The number of the pages.
+  pub fn page_count(&self) -> usize { self.page_count }
+
+  /// The position of the pages, `1.5` means the middle between the second and
+  /// the third page.
+  pub fn position(&self) -> f32 {
+    if self.looping && self.page_count > 0 {
+      self.position.rem_euclid(self.page_count as f32)
+    } else {
+      self.position
+    }
+  }
+
+  /// Whether the pages are settling at a page.
+  pub fn is_moving(&self) -> bool { self.motion.is_some() }
+
+  /// Go to the `page` without an animation.
+  pub fn jump_to_page(&mut self, page: usize) {
+    self.motion = None;
+    self.page = self.clamp_page(page);
+    self.position = self.page as f32;
+  }
+
+  /// Move to the `page` by the spring of the [`ScrollPhysics`]. A looping page
+  /// view moves the shortest way to it.
+  pub fn animate_to_page(&mut self, page: usize) {
+    let page = self.clamp_page(page);
+    if self.extent <= 0. {
+      self.jump_to_page(page);
+      return;
+    }
+    let mut target = page as f32;
+    if self.looping {
+      let count = self.page_count as f32;
+      let base = self.position.round();
+      let delta = (target - base.rem_euclid(count) + count / 2.).rem_euclid(count) - count / 2.;
+      target = base + delta;
+    }
+    let velocity = self.velocity(clock::now());
+    self.settle_to(target, velocity);
+  }
+
+  fn clamp_page(&self, page: usize) -> usize { page.min(self.page_count.saturating_sub(1)) }
+
+  fn max_position(&self) -> f32 { self.page_count.saturating_sub(1) as f32 }
+
+  /// The velocity of the running motion in logical pixels per second.
+  fn velocity(&self, now: Instant) -> f32 {
+    match self.motion.map(|m| m.step(&self.physics, now)) {
+      Some(MotionStep::Running(_, v)) => v.x,
+      _ => 0.,
+    }
+  }
+
+  /// Move by a dragging pointer, `delta` is in logical pixels.
+  fn drag_by(&mut self, delta: f32) {
+    if self.extent <= 0. {
+      return;
+    }
+    self.motion = None;
+    self.position += delta / self.extent;
+    if !self.looping {
+      self.position = self.position.clamp(0., self.max_position());
+    }
+  }
+
+  /// Settle after the pointer lifts with the `velocity` in logical pixels per
+  /// second.
+  fn release(&mut self, velocity: f32) {
+    let mut target = if velocity.abs() >= FLING_VELOCITY {
+      (self.position + velocity.signum() * 0.5).round()
+    } else {
+      self.position.round()
+    };
+    if !self.looping {
+      target = target.clamp(0., self.max_position());
+    }
+    self.settle_to(target, velocity);
+  }
+
+  fn settle_to(&mut self, target: f32, velocity: f32) {
+    self.page = if self.looping && self.page_count > 0 {
+      target.rem_euclid(self.page_count as f32) as usize
+    } else {
+      target as usize
+    };
+    let (from, to) = (self.position * self.extent, target * self.extent);
+    self.motion = (from != to).then(|| ScrollMotion::Settle {
+      start: clock::now(),
+      from: Point::new(from, 0.),
+      to: Point::new(to, 0.),
+      velocity: Vector::new(velocity, 0.),
+    });
+    if self.motion.is_none() {
+      self.position = target;
+    }
+  }
+
+  fn advance(&mut self, now: Instant) {
+    let Some(motion) = self.motion else { return };
+    match motion.step(&self.physics, now) {
+      MotionStep::Running(pos, _) => self.position = pos.x / self.extent,
+      MotionStep::Stopped(pos, _) | MotionStep::Settled(pos) => {
+        self.position = pos.x / self.extent;
+        self.motion = None;
+      }
+    }
+  }
+
+  fn set_extent(&mut self, extent: f32) {
+    // The position is in pages, only the running motion is in pixels.
+    if let Some(ScrollMotion::Settle { to, .. }) = self.motion {
+      let target = to.x / self.extent;
+      self.extent = extent;
+      self.motion = None;
+      self.settle_to(target, 0.);
+    } else {
+      self.extent = extent;
+    }
+  }
+
+  fn set_page_count(&mut self, count: usize) {
+    self.page_count = count;
+    if self.page >= count {
+      self.jump_to_page(count.saturating_sub(1));
+    }
+  }
+}
+
+impl<'c> ComposeChild<'c> for PageView {
+  type Child = Vec<Widget<'c>>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    let controller = this.read().controller.clone_writer();
+    controller.write().set_page_count(child.len());
+
+    fn_widget! {
+      let looping = watch!($this.looping)
+        .subscribe(move |looping| $controller.write().looping = looping);
+      let changed = watch!($controller.page())
+        .distinct_until_changed()
+        .skip(1)
+        .subscribe(move |page| ($this.silent().on_page_changed.0)(page));
+
+      // Keep the window drawing while the pages settle.
+      let wnd = BuildCtx::get().window();
+      let moving = watch!($controller.is_moving())
+        .distinct_until_changed()
+        .skip(1)
+        .subscribe(move |moving| if moving {
+          wnd.inc_running_animate();
+        } else {
+          wnd.dec_running_animate();
+        });
+      let ticks = BuildCtx::get()
+        .window()
+        .frame_tick_stream()
+        .subscribe(move |msg| if let FrameMsg::NewFrame(time) = msg {
+          if $controller.is_moving() {
+            $controller.write().advance(time);
+          }
+        });
+
+      let mut pages = @Pages {
+        direction: pipe!($this.direction),
+        position: pipe!($controller.position()),
+        looping: pipe!($this.looping),
+      };
+      watch!(($this.direction, $pages.layout_size()))
+        .map(|(dir, size)| if dir.is_horizontal() { size.width } else { size.height })
+        .distinct_until_changed()
+        .subscribe(move |extent| $controller.write().set_extent(extent));
+
+      let drag: Stateful<Option<PageDrag>> = Stateful::new(None);
+      @Clip {
+        on_disposed: move |_| {
+          if $controller.is_moving() {
+            BuildCtx::get().window().dec_running_animate();
+          }
+          looping.unsubscribe();
+          changed.unsubscribe();
+          moving.unsubscribe();
+          ticks.unsubscribe();
+        },
+        @ $pages {
+          on_pointer_down: move |e| {
+            if let Some(grab) = GrabPointer::grab(e.current_target(), &e.window()) {
+              let mut tracker = VelocityTracker::default();
+              tracker.add_sample(clock::now(), e.global_pos());
+              *$drag.write() = Some(PageDrag {
+                _grab: grab,
+                last: e.global_pos(),
+                tracker,
+                started: false,
+              });
+            }
+          },
+          on_pointer_move: move |e| {
+            let mut drag = $drag.write();
+            if let Some(drag) = drag.as_mut() {
+              let pos = e.global_pos();
+              drag.tracker.add_sample(clock::now(), pos);
+              let delta = drag.last - pos;
+              if !drag.started {
+                if !GestureSettings::of(e).is_drag(&e.point_type, delta) {
+                  return;
+                }
+                drag.started = true;
+              }
+              drag.last = pos;
+              let delta = along($this.direction, delta);
+              $controller.write().drag_by(delta);
+            }
+          },
+          on_pointer_up: move |_| {
+            let drag = $drag.write().take();
+            if let Some(drag) = drag.filter(|d| d.started) {
+              let velocity = along($this.direction, -drag.tracker.velocity());
+              $controller.write().release(velocity);
+            }
+          },
+          @ { child }
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+/// The state of a pointer that drags the pages.
+struct PageDrag {
+  _grab: GrabPointer,
+  last: Point,
+  tracker: VelocityTracker,
+  /// Whether the pointer has moved farther than the slop, see
+  /// [`GestureSettings`].
+  started: bool,
+}
+
+fn along(direction: Direction, v: Vector) -> f32 {
+  if direction.is_horizontal() { v.x } else { v.y }
+}
+
+/// Lays out the pages at the `position`, every page is as large as the widget.
+#[derive(MultiChild, Declare)]
+struct Pages {
+  direction: Direction,
+  position: f32,
+  looping: bool,
+}
+
+impl Render for Pages {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let size = clamp.max;
+    let size = Size::new(
+      if size.width.is_finite() { size.width } else { clamp.min.width },
+      if size.height.is_finite() { size.height } else { clamp.min.height },
+    );
+    let extent = along(self.direction, size.to_vector());
+    let current = self.position.round();
+
+    let (ctx, children) = ctx.split_children();
+    let children = children.collect::<Vec<_>>();
+    let count = children.len() as f32;
+    for (i, c) in children.into_iter().enumerate() {
+      let mut offset = i as f32 - current;
+      if self.looping {
+        offset = (offset + count / 2.).rem_euclid(count) - count / 2.;
+      }
+      // Only the current page and the pages beside it are laid out.
+      if offset.abs() > 1. {
+        ctx.force_child_relayout(c);
+        continue;
+      }
+      ctx.perform_child_layout(c, BoxClamp::fixed_size(size));
+      let pos = (offset + current - self.position) * extent;
+      let pos =
+        if self.direction.is_horizontal() { Point::new(pos, 0.) } else { Point::new(0., pos) };
+      ctx.update_position(c, pos);
+    }
+    size
+  }
+}
+
+impl Compose for PageIndicator {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    let controller = this.read().controller.clone_writer();
+    fn_widget! {
+      let palette = Palette::of(BuildCtx::get());
+      let (active_color, color) = (palette.primary(), palette.outline_variant());
+      @Row {
+        align_items: Align::Center,
+        item_gap: 8.,
+        @ {
+          pipe!($controller.page_count()).map(move |count| {
+            (0..count)
+              .map(|i| {
+                @Container {
+                  class: distinct_pipe!($controller.page() == i).map(|active| {
+                    if active { PAGE_INDICATOR_ACTIVE_DOT } else { PAGE_INDICATOR_DOT }
+                  }),
+                  size: distinct_pipe!($controller.page() == i).map(|active| {
+                    if active { Size::new(16., 8.) } else { Size::splat(8.) }
+                  }),
+                  border_radius: Radius::all(4.),
+                  background: distinct_pipe!($controller.page() == i).map(move |active| {
+                    Brush::from(if active { active_color } else { color })
+                  }),
+                  on_tap: move |_| $controller.write().animate_to_page(i),
+                }
+              })
+              .collect::<Vec<_>>()
+          })
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+
+  use super::*;
+
+  fn page_window(looping: bool) -> (TestWindow, Stateful<PageController>, Stateful<Vec<usize>>) {
+    let controller = Stateful::new(PageController::default());
+    let changes = Stateful::new(vec![]);
+    let (c_controller, c_changes) = (controller.clone_writer(), changes.clone_writer());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let c_changes = c_changes.clone_writer();
+        @Column {
+          @Expanded {
+            @PageView {
+              controller: c_controller.clone_writer(),
+              looping,
+              on_page_changed: move |page: usize| c_changes.write().push(page),
+              @MockBox { size: Size::zero() }
+              @MockBox { size: Size::zero() }
+              @MockBox { size: Size::zero() }
+              @MockBox { size: Size::zero() }
+            }
+          }
+          @PageIndicator { controller: c_controller.clone_writer() }
+        }
+      },
+      Size::new(200., 100.),
+    );
+    wnd.draw_frame();
+    (wnd, controller, changes)
+  }
+
+  fn run_until_rest(wnd: &mut TestWindow, controller: &Stateful<PageController>) {
+    let mut frames = 0;
+    while controller.read().is_moving() {
+      wnd.advance_time(Duration::from_millis(16));
+      wnd.draw_frame();
+      frames += 1;
+      assert!(frames < 1000, "the pages never rest");
+    }
+  }
+
+  fn pen(x: f32, state: PenState) -> PenPacket {
+    PenPacket {
+      position: Point::new(x, 50.),
+      state,
+      pressure: 0.5,
+      tilt_x: 0.,
+      tilt_y: 0.,
+      twist: 0.,
+      is_eraser: false,
+    }
+  }
+
+  /// Drag the pen from `from` by the `steps` every `interval`, and lift it.
+  fn swipe(wnd: &mut TestWindow, from: f32, steps: &[f32], interval: Duration) {
+    wnd.process_pen_input(pen(from, PenState::Contact));
+    wnd.draw_frame();
+    let mut x = from;
+    for step in steps {
+      x += step;
+      wnd.advance_time(interval);
+      wnd.process_pen_input(pen(x, PenState::Contact));
+      wnd.draw_frame();
+    }
+    wnd.process_pen_input(pen(x, PenState::Hover));
+    wnd.draw_frame();
+  }
+
+  /// The sizes of the dots of the page indicator.
+  fn dots(wnd: &TestWindow) -> Vec<f32> {
+    (0..4)
+      .map(|i| {
+        wnd
+          .layout_info_by_path(&[0, 1, i])
+          .unwrap()
+          .size
+          .unwrap()
+          .width
+      })
+      .collect()
+  }
+
+  #[test]
+  fn fast_fling_turns_page() {
+    reset_test_env!();
+
+    let (mut wnd, controller, changes) = page_window(false);
+    // Only 50px, but at 1000px/s.
+    swipe(&mut wnd, 150., &[-10.; 5], Duration::from_millis(10));
+    assert_eq!(controller.read().page(), 1);
+    run_until_rest(&mut wnd, &controller);
+    assert_eq!(controller.read().position(), 1.);
+    assert_eq!(*changes.read(), [1]);
+  }
+
+  #[test]
+  fn slow_drag_settles_nearest() {
+    reset_test_env!();
+
+    let (mut wnd, controller, changes) = page_window(false);
+    // Less than a half page goes back.
+    swipe(&mut wnd, 150., &[-20.; 4], Duration::from_millis(100));
+    run_until_rest(&mut wnd, &controller);
+    assert_eq!(controller.read().page(), 0);
+    assert_eq!(controller.read().position(), 0.);
+
+    // More than a half page goes on.
+    swipe(&mut wnd, 150., &[-30.; 4], Duration::from_millis(100));
+    run_until_rest(&mut wnd, &controller);
+    assert_eq!(controller.read().page(), 1);
+    assert_eq!(controller.read().position(), 1.);
+    assert_eq!(*changes.read(), [1]);
+  }
+
+  #[test]
+  fn only_near_pages_laid_out() {
+    reset_test_env!();
+
+    let (mut wnd, controller, _) = page_window(false);
+    let laid_out = |wnd: &TestWindow| {
+      (0..4)
+        .map(|i| wnd.layout_info_by_path(&[0, 0, 0, i]).is_some())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(laid_out(&wnd), [true, true, false, false]);
+
+    controller.write().jump_to_page(3);
+    wnd.draw_frame();
+    assert_eq!(laid_out(&wnd), [false, false, true, true]);
+  }
+
+  #[test]
+  fn looping_and_indicator_sync() {
+    reset_test_env!();
+
+    let (mut wnd, controller, changes) = page_window(true);
+    assert_eq!(dots(&wnd), [16., 8., 8., 8.]);
+
+    // Fling back from the first page to the last one.
+    swipe(&mut wnd, 50., &[10.; 5], Duration::from_millis(10));
+    run_until_rest(&mut wnd, &controller);
+    assert_eq!(controller.read().page(), 3);
+    assert_eq!(controller.read().position(), 3.);
+    assert_eq!(dots(&wnd), [8., 8., 8., 16.]);
+
+    // Move to the second page by the controller, the shortest way is forward.
+    controller.write().animate_to_page(1);
+    assert!(controller.read().is_moving());
+    run_until_rest(&mut wnd, &controller);
+    assert_eq!(dots(&wnd), [8., 16., 8., 8.]);
+    assert_eq!(*changes.read(), [3, 1]);
+  }
+}
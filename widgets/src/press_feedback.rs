@@ -0,0 +1,42 @@
+use ribir_core::prelude::*;
+
+/// A decorator that shows the visual feedback of pressing its host, such as
+/// the ripple of the material theme.
+///
+/// It composes the host as it is, the theme installs the feedback by
+/// registering a compose decorator to it, see [`ComposeDecorators`]. So an
+/// application can replace or disable the feedback of all the built-in
+/// controls by its theme.
+///
+/// # Example
+///
+/// Disable the press feedback of the whole application.
+///
+/// ```no_run
+/// use ribir_core::prelude::*;
+/// use ribir_widgets::prelude::*;
+///
+/// let mut theme = AppCtx::app_theme().write();
+/// theme
+///   .compose_decorators
+///   .override_compose_decorator::<PressFeedback>(|_, host, _| host);
+/// ```
+#[derive(Declare, Debug, Clone, PartialEq)]
+pub struct PressFeedback {
+  /// The radius of the feedback when it's fully expanded, it covers the whole
+  /// host if it's `None`.
+  #[declare(default)]
+  pub radius: Option<f32>,
+  /// Whether the feedback always starts from the center of the host instead
+  /// of the pressed position.
+  #[declare(default)]
+  pub center: bool,
+  /// The border radius of the host box that the feedback is clipped by, the
+  /// feedback can show outside of the host if it's `None`.
+  #[declare(default)]
+  pub clip: Option<Radius>,
+}
+
+impl ComposeDecorator for PressFeedback {
+  fn compose_decorator(_: State<Self>, host: Widget) -> Widget { host }
+}
@@ -0,0 +1,333 @@
+use std::{future::Future, pin::Pin, rc::Rc};
+
+use ribir_core::prelude::*;
+
+use crate::prelude::*;
+
+class_names! {
+  #[doc = "Class name for the indicator of the pull to refresh"]
+  PULL_TO_REFRESH_INDICATOR,
+}
+
+/// A widget that scrolls its child vertically, and refreshes it by pulling it
+/// down from the top.
+///
+/// Pull the child past the `threshold` and release it to call `on_refresh`.
+/// The indicator stays until the future returned by `on_refresh` resolves,
+/// and the child can't be pulled to refresh again in the meantime.
+///
+/// The pull is done by the overscroll of the dragging pointer, see
+/// [`ScrollPhysics::overscroll`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let _w = fn_widget! {
+///   @PullToRefresh {
+///     on_refresh: || async {
+///       // Load the new data.
+///     },
+///     @Column {
+///       @Text { text: "Item 1" }
+///       @Text { text: "Item 2" }
+///     }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct PullToRefresh {
+  /// How far in logical pixels the child should be pulled to refresh.
+  #[declare(default = 64.)]
+  pub threshold: f32,
+  /// Called when the child is released past the `threshold`.
+  pub on_refresh: RefreshHandler,
+  #[declare(skip)]
+  state: RefreshState,
+  /// How far the child is pulled down.
+  #[declare(skip)]
+  pull: f32,
+}
+
+/// The callback of [`PullToRefresh::on_refresh`], it returns the future that
+/// refreshes the content.
+pub struct RefreshHandler(Box<dyn FnMut() -> Pin<Box<dyn Future<Output = ()>>>>);
+
+impl<F, Fut> From<F> for RefreshHandler
+where
+  F: FnMut() -> Fut + 'static,
+  Fut: Future<Output = ()> + 'static,
+{
+  fn from(mut f: F) -> Self { Self(Box::new(move || Box::pin(f()))) }
+}
+
+/// The states of a [`PullToRefresh`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshState {
+  /// Not pulled past the threshold.
+  #[default]
+  Idle,
+  /// Pulled past the threshold, releasing it starts to refresh.
+  Armed,
+  /// Waiting for the future of the refresh.
+  Refreshing,
+  /// The refresh finished, the indicator is going away.
+  Settling,
+}
+
+/// The size of the indicator.
+const INDICATOR_SIZE: f32 = 40.;
+
+impl PullToRefresh {
+  /// The state of the refresh.
+  pub fn state(&self) -> RefreshState { self.state }
+
+  /// How far the child is pulled down in logical pixels.
+  pub fn pull_distance(&self) -> f32 { self.pull }
+
+  fn set_pull(&mut self, pull: f32, dragging: bool) {
+    self.pull = pull;
+    match self.state {
+      RefreshState::Idle if dragging && pull >= self.threshold => self.state = RefreshState::Armed,
+      RefreshState::Armed if pull < self.threshold => self.state = RefreshState::Idle,
+      _ => {}
+    }
+  }
+
+  /// The distance from the top to the bottom of the indicator.
+  fn indicator_offset(&self) -> f32 {
+    match self.state {
+      RefreshState::Idle | RefreshState::Armed => self.pull.min(self.threshold * 1.5),
+      RefreshState::Refreshing | RefreshState::Settling => self.threshold,
+    }
+  }
+}
+
+impl<'c> ComposeChild<'c> for PullToRefresh {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    fn_widget! {
+      let mut child = FatObj::new(child).scrollable(Scrollable::Y);
+      let scrollable = child.get_scrollable_widget().clone_writer();
+      scrollable.write().physics.overscroll = true;
+      // The child is dragged if it's overscrolled but not moving by itself,
+      // and it starts to bounce back once the pointer is released.
+      let c_this = this.clone_writer();
+      let u = watch!(($child.get_scroll_pos().y, $scrollable.is_moving()))
+        .distinct_until_changed()
+        .subscribe(move |(y, moving)| {
+          let pull = (-y).max(0.);
+          if moving && $this.state == RefreshState::Armed {
+            let mut w = $this.write();
+            w.pull = pull;
+            w.state = RefreshState::Refreshing;
+            // Keep the indicator until the refresh finishes, meanwhile the
+            // child can't be pulled again.
+            $scrollable.write().physics.overscroll = false;
+            let refresh = (w.on_refresh.0)();
+            let this = c_this.clone_writer();
+            let _ = AppCtx::spawn_local(async move {
+              refresh.await;
+              this.write().state = RefreshState::Settling;
+            });
+          } else {
+            $this.write().set_pull(pull, !moving);
+          }
+        });
+
+      let mut indicator = @Container {
+        class: PULL_TO_REFRESH_INDICATOR,
+        size: Size::splat(INDICATOR_SIZE),
+        h_align: HAlign::Center,
+        anchor: pipe!(Anchor::top($this.indicator_offset() - INDICATOR_SIZE)),
+        opacity: pipe!(if $this.state == RefreshState::Settling { 0. } else { 1. }),
+      };
+      let fade = indicator
+        .get_opacity_widget()
+        .map_writer(|w| PartData::from_ref_mut(&mut w.opacity))
+        .transition(transitions::LINEAR.of(BuildCtx::get()));
+      let indicator = @ $indicator {
+        @SpinnerProgress {
+          value: pipe! {
+            let this = $this;
+            match this.state {
+              RefreshState::Idle | RefreshState::Armed => {
+                Some((this.pull / this.threshold).min(1.))
+              }
+              RefreshState::Refreshing | RefreshState::Settling => None,
+            }
+          },
+          transform: pipe! {
+            let this = $this;
+            Transform::rotation(Angle::radians(this.pull / this.threshold * std::f32::consts::PI))
+          },
+        }
+      };
+
+      // Back to idle after the indicator fades out, and pull to refresh again.
+      let c_scrollable = scrollable.clone_writer();
+      let finish = move || if $this.state == RefreshState::Settling && !$fade.is_running() {
+        $this.write().state = RefreshState::Idle;
+        c_scrollable.write().physics.overscroll = true;
+      };
+      let finish = Rc::new(finish);
+      let c_finish = finish.clone();
+      let faded = watch!($fade.is_running())
+        .distinct_until_changed()
+        .filter(|running| !running)
+        .subscribe(move |_| c_finish());
+      let wnd = BuildCtx::get().window();
+      let settling = watch!($this.state)
+        .distinct_until_changed()
+        .filter(|state| *state == RefreshState::Settling)
+        .subscribe(move |_| {
+          // The indicator does not fade if the animations are disabled.
+          let finish = finish.clone();
+          wnd.once_frame_finished(move || finish());
+        });
+
+      @Clip {
+        on_disposed: move |_| {
+          u.unsubscribe();
+          faded.unsubscribe();
+          settling.unsubscribe();
+        },
+        @Stack {
+          @ { child }
+          @ { indicator }
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+
+  use futures::channel::oneshot;
+  use ribir_core::{reset_test_env, test_helper::*, window::WindowFlags};
+
+  use super::*;
+
+  type Refresh = (TestWindow, Stateful<PullToRefresh>, Rc<RefCell<Vec<oneshot::Sender<()>>>>);
+
+  fn refresh_window() -> Refresh {
+    let pull = Stateful::new(None);
+    let requests: Rc<RefCell<Vec<oneshot::Sender<()>>>> = <_>::default();
+    let (c_pull, c_requests) = (pull.clone_writer(), requests.clone());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let requests = c_requests.clone();
+        let p = @PullToRefresh {
+          on_refresh: move || {
+            // The refresh finishes when the test sends to the channel.
+            let (tx, rx) = oneshot::channel();
+            requests.borrow_mut().push(tx);
+            async move {
+              let _ = rx.await;
+            }
+          },
+        };
+        *$c_pull.write() = Some(p.clone_writer());
+        @ $p { @MockBox { size: Size::new(200., 1000.) } }
+      },
+      Size::new(200., 100.),
+    );
+    wnd.draw_frame();
+    let pull = pull.write().take().unwrap();
+    (wnd, pull, requests)
+  }
+
+  fn pen(y: f32, state: PenState) -> PenPacket {
+    PenPacket {
+      position: Point::new(100., y),
+      state,
+      pressure: 0.5,
+      tilt_x: 0.,
+      tilt_y: 0.,
+      twist: 0.,
+      is_eraser: false,
+    }
+  }
+
+  /// Pull down by `distance` slowly, without lifting the pen.
+  fn pull_down(wnd: &mut TestWindow, distance: f32) {
+    wnd.process_pen_input(pen(0., PenState::Contact));
+    wnd.draw_frame();
+    for i in 1..=10 {
+      wnd.advance_time(Duration::from_millis(50));
+      wnd.process_pen_input(pen(distance * i as f32 / 10., PenState::Contact));
+      wnd.draw_frame();
+    }
+  }
+
+  fn lift(wnd: &mut TestWindow, y: f32) {
+    wnd.process_pen_input(pen(y, PenState::Hover));
+    wnd.draw_frame();
+  }
+
+  fn run_frames(wnd: &mut TestWindow) {
+    for _ in 0..60 {
+      wnd.advance_time(Duration::from_millis(16));
+      wnd.draw_frame();
+    }
+  }
+
+  #[test]
+  fn refresh_state_machine() {
+    reset_test_env!();
+
+    let (mut wnd, pull, requests) = refresh_window();
+    // The indicator fades out before it's back to idle.
+    wnd.set_flags(WindowFlags::ANIMATIONS);
+    // The pull is half of the overscroll of the pen.
+    pull_down(&mut wnd, 100.);
+    assert_eq!(pull.read().state(), RefreshState::Idle);
+    assert!(pull.read().pull_distance() > 0.);
+
+    pull_down(&mut wnd, 160.);
+    assert_eq!(pull.read().state(), RefreshState::Armed);
+
+    lift(&mut wnd, 160.);
+    assert_eq!(pull.read().state(), RefreshState::Refreshing);
+    assert_eq!(requests.borrow().len(), 1);
+
+    // Held until the refresh finishes.
+    run_frames(&mut wnd);
+    assert_eq!(pull.read().state(), RefreshState::Refreshing);
+    assert_eq!(pull.read().pull_distance(), 0.);
+
+    let tx = requests.borrow_mut().pop().unwrap();
+    let _ = tx.send(());
+    wnd.draw_frame();
+    assert_eq!(pull.read().state(), RefreshState::Settling);
+
+    run_frames(&mut wnd);
+    assert_eq!(pull.read().state(), RefreshState::Idle);
+
+    // Pull to refresh again.
+    pull_down(&mut wnd, 160.);
+    assert_eq!(pull.read().state(), RefreshState::Armed);
+  }
+
+  #[test]
+  fn no_retrigger_while_refreshing() {
+    reset_test_env!();
+
+    let (mut wnd, pull, requests) = refresh_window();
+    pull_down(&mut wnd, 160.);
+    lift(&mut wnd, 160.);
+    run_frames(&mut wnd);
+    assert_eq!(pull.read().state(), RefreshState::Refreshing);
+
+    pull_down(&mut wnd, 160.);
+    assert_eq!(pull.read().pull_distance(), 0.);
+    lift(&mut wnd, 160.);
+    assert_eq!(pull.read().state(), RefreshState::Refreshing);
+    assert_eq!(requests.borrow().len(), 1);
+  }
+}
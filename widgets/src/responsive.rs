@@ -0,0 +1,125 @@
+use std::rc::Rc;
+
+use ribir_core::prelude::*;
+
+/// A widget that builds its content from the [`Breakpoint`] that matches the
+/// width of the window.
+///
+/// It only rebuilds when the window resizes across a breakpoint, not on every
+/// change of the window size.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let _w = fn_widget! {
+///   @Responsive {
+///     @Breakpoint::new(0., fn_widget! { @Text { text: "compact" } })
+///     @Breakpoint::new(600., fn_widget! { @Text { text: "medium" } })
+///     @Breakpoint::new(840., fn_widget! { @Text { text: "expanded" } })
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct Responsive;
+
+/// A child of [`Responsive`], its `builder` is used when the width of the
+/// window is not less than `min_width` and less than the `min_width` of the
+/// next breakpoint.
+#[derive(ChildOfCompose)]
+pub struct Breakpoint {
+  min_width: f32,
+  builder: GenWidget,
+}
+
+impl Breakpoint {
+  pub fn new(min_width: f32, builder: impl Into<GenWidget>) -> Self {
+    Self { min_width, builder: builder.into() }
+  }
+}
+
+/// The index of the breakpoint that `width` falls in, the breakpoints must be
+/// sorted by their `min_width`. The first breakpoint is used if the `width` is
+/// less than all of them.
+fn bucket(breakpoints: &[Breakpoint], width: f32) -> usize {
+  breakpoints
+    .iter()
+    .rposition(|b| b.min_width <= width)
+    .unwrap_or(0)
+}
+
+impl ComposeChild<'static> for Responsive {
+  type Child = Vec<Breakpoint>;
+
+  fn compose_child(_: impl StateWriter<Value = Self>, mut child: Self::Child) -> Widget<'static> {
+    if child.is_empty() {
+      return Void.into_widget();
+    }
+    child.sort_by(|a, b| a.min_width.total_cmp(&b.min_width));
+    let breakpoints = Rc::new(child);
+    let c_breakpoints = breakpoints.clone();
+    let media = BuildCtx::get().media();
+    pipe!($media.size.width)
+      .map(move |width| bucket(&c_breakpoints, width))
+      .distinct()
+      .map(move |idx| breakpoints[idx].builder.gen_widget())
+      .into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::Cell;
+
+  use ribir_core::{reset_test_env, test_helper::*};
+
+  use super::*;
+
+  fn counter_builder(counter: &Rc<Cell<usize>>) -> GenWidget {
+    let counter = counter.clone();
+    GenWidget::new(move || {
+      counter.set(counter.get() + 1);
+      Void.into_widget()
+    })
+  }
+
+  #[test]
+  fn rebuild_only_across_breakpoints() {
+    reset_test_env!();
+
+    let compact = Rc::new(Cell::new(0));
+    let expanded = Rc::new(Cell::new(0));
+    let (c_compact, c_expanded) = (counter_builder(&compact), counter_builder(&expanded));
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @Responsive {
+          @Breakpoint::new(600., c_expanded.clone())
+          @Breakpoint::new(0., c_compact.clone())
+        }
+      },
+      Size::new(300., 100.),
+    );
+    wnd.draw_frame();
+    assert_eq!((compact.get(), expanded.get()), (1, 0));
+
+    // Resizes within a breakpoint do not rebuild.
+    wnd.request_resize(Size::new(400., 100.));
+    wnd.draw_frame();
+    wnd.request_resize(Size::new(599., 300.));
+    wnd.draw_frame();
+    assert_eq!((compact.get(), expanded.get()), (1, 0));
+
+    // Crossing a breakpoint rebuilds exactly once.
+    wnd.request_resize(Size::new(700., 300.));
+    wnd.draw_frame();
+    assert_eq!((compact.get(), expanded.get()), (1, 1));
+    wnd.request_resize(Size::new(900., 300.));
+    wnd.draw_frame();
+    assert_eq!((compact.get(), expanded.get()), (1, 1));
+
+    wnd.request_resize(Size::new(200., 300.));
+    wnd.draw_frame();
+    assert_eq!((compact.get(), expanded.get()), (2, 1));
+  }
+}
@@ -0,0 +1,392 @@
+use std::{collections::HashSet, hash::Hash};
+
+use ribir_core::prelude::*;
+
+/// The multi-selection of the items of a list, with the rules of the desktop
+/// file managers. Share it by a [`Stateful`] between the [`SelectionScope`] of
+/// the list and the [`Selectable`] of its items, and watch it to style the
+/// selected items or to act on them.
+///
+/// The anchor is the item that a range selection starts from, it's set by a
+/// plain click, a toggle or a move without extending, and is kept while the
+/// range is extended.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let files = ["a.txt", "b.txt", "c.txt"];
+/// let selection = Stateful::new(SelectionController::new(files));
+/// let _w = fn_widget! {
+///   @SelectionScope {
+///     controller: selection.clone_writer(),
+///     @Column {
+///       @ {
+///         files.map(|file| @Selectable {
+///           controller: selection.clone_writer(),
+///           item: file,
+///           @Text {
+///             text: file,
+///             opacity: pipe!(if $selection.is_selected(&file) { 1. } else { 0.6 }),
+///           }
+///         })
+///       }
+///     }
+///   }
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct SelectionController<T> {
+  items: Vec<T>,
+  selected: HashSet<T>,
+  anchor: Option<T>,
+  active: Option<T>,
+}
+
+/// The scope of a [`SelectionController`] that handles its keyboard
+/// shortcuts when the focus is within it:
+///
+/// - `Ctrl/Cmd + A` selects all the items.
+/// - The arrow keys move the active item and select only it, with `Shift` they
+///   extend the selection from the anchor to it, and with `Ctrl/Cmd` they move
+///   it without changing the selection.
+/// - `Ctrl/Cmd + Space` toggles the active item.
+///
+/// The scope is focusable, so it's focused when one of its items is clicked.
+#[derive(Declare)]
+pub struct SelectionScope<T>
+where
+  T: 'static,
+{
+  pub controller: Stateful<SelectionController<T>>,
+}
+
+/// Make the child an item of a [`SelectionController`]: a click selects only
+/// the item, `Ctrl/Cmd` + click toggles it, and `Shift` + click selects the
+/// range from the anchor to it, add `Ctrl/Cmd` to keep the items selected
+/// before.
+#[derive(Declare)]
+pub struct Selectable<T>
+where
+  T: 'static,
+{
+  pub controller: Stateful<SelectionController<T>>,
+  #[declare(strict)]
+  pub item: T,
+}
+
+impl<T: Eq + Hash + Clone> SelectionController<T> {
+  /// Create a controller of the `items` in their order, nothing is selected.
+  pub fn new(items: impl IntoIterator<Item = T>) -> Self {
+    Self {
+      items: items.into_iter().collect(),
+      selected: HashSet::new(),
+      anchor: None,
+      active: None,
+    }
+  }
+
+  /// Replace the items, the selected items not in the new items are dropped.
+  pub fn set_items(&mut self, items: impl IntoIterator<Item = T>) {
+    self.items = items.into_iter().collect();
+    let items = &self.items;
+    self.selected.retain(|s| items.contains(s));
+    let keep = |item: &mut Option<T>| {
+      if item.as_ref().is_some_and(|i| !items.contains(i)) {
+        *item = None;
+      }
+    };
+    keep(&mut self.anchor);
+    keep(&mut self.active);
+  }
+
+  pub fn items(&self) -> &[T] { &self.items }
+
+  pub fn selected(&self) -> &HashSet<T> { &self.selected }
+
+  /// The selected items in the order of the items.
+  pub fn selected_in_order(&self) -> Vec<&T> {
+    self
+      .items
+      .iter()
+      .filter(|i| self.selected.contains(i))
+      .collect()
+  }
+
+  pub fn is_selected(&self, item: &T) -> bool { self.selected.contains(item) }
+
+  /// The item that a range selection starts from.
+  pub fn anchor(&self) -> Option<&T> { self.anchor.as_ref() }
+
+  /// The item the keyboard moves from, it's the last clicked or moved to.
+  pub fn active(&self) -> Option<&T> { self.active.as_ref() }
+
+  /// Select only the `item`, and make it the anchor.
+  pub fn select_only(&mut self, item: T) {
+    self.selected.clear();
+    self.selected.insert(item.clone());
+    self.anchor = Some(item.clone());
+    self.active = Some(item);
+  }
+
+  /// Toggle the selection of the `item`, and make it the anchor.
+  pub fn toggle(&mut self, item: T) {
+    if !self.selected.remove(&item) {
+      self.selected.insert(item.clone());
+    }
+    self.anchor = Some(item.clone());
+    self.active = Some(item);
+  }
+
+  /// Select the items from the anchor to the `item`, the items selected before
+  /// are kept if `keep` is true. The anchor is kept, it's the `item` if there
+  /// was no anchor.
+  pub fn select_range_to(&mut self, item: T, keep: bool) {
+    let anchor = self
+      .anchor
+      .get_or_insert_with(|| item.clone())
+      .clone();
+    let (Some(from), Some(to)) = (self.index_of(&anchor), self.index_of(&item)) else {
+      return;
+    };
+    if !keep {
+      self.selected.clear();
+    }
+    let range = from.min(to)..=from.max(to);
+    self
+      .selected
+      .extend(self.items[range].iter().cloned());
+    self.active = Some(item);
+  }
+
+  /// Select all the items, the anchor and the active item are kept.
+  pub fn select_all(&mut self) { self.selected = self.items.iter().cloned().collect(); }
+
+  pub fn clear(&mut self) { self.selected.clear(); }
+
+  /// Move the active item by `step` in the items, it's clamped to the first
+  /// and the last item. The selection is extended from the anchor to it if
+  /// `extend` is true, otherwise only it's selected.
+  pub fn move_active(&mut self, step: isize, extend: bool) {
+    let Some(item) = self.item_by_step(step) else { return };
+    if extend {
+      self.select_range_to(item, false);
+    } else {
+      self.select_only(item);
+    }
+  }
+
+  /// Move the active item by `step` without changing the selection.
+  pub fn move_active_only(&mut self, step: isize) {
+    if let Some(item) = self.item_by_step(step) {
+      self.active = Some(item);
+    }
+  }
+
+  fn item_by_step(&self, step: isize) -> Option<T> {
+    let last = self.items.len().checked_sub(1)?;
+    let idx = match self
+      .active
+      .as_ref()
+      .and_then(|a| self.index_of(a))
+    {
+      Some(idx) => idx.saturating_add_signed(step).min(last),
+      // Start from the edge that the step moves from.
+      None if step < 0 => last,
+      None => 0,
+    };
+    Some(self.items[idx].clone())
+  }
+
+  fn index_of(&self, item: &T) -> Option<usize> { self.items.iter().position(|i| i == item) }
+}
+
+impl<'c, T: Eq + Hash + Clone + 'static> ComposeChild<'c> for SelectionScope<T> {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    let controller = this.read().controller.clone_writer();
+    FatObj::new(child)
+      .tab_index(0i16)
+      .on_key_down(move |e| {
+        let command = e.with_command_key();
+        let mut ctrl = controller.write();
+        match e.key() {
+          VirtualKey::Character(c) if command && c.eq_ignore_ascii_case("a") => ctrl.select_all(),
+          VirtualKey::Named(NamedKey::Space) if command => {
+            if let Some(active) = ctrl.active().cloned() {
+              ctrl.toggle(active);
+            }
+          }
+          VirtualKey::Named(
+            key @ (NamedKey::ArrowUp
+            | NamedKey::ArrowLeft
+            | NamedKey::ArrowDown
+            | NamedKey::ArrowRight),
+          ) => {
+            let step = if matches!(key, NamedKey::ArrowUp | NamedKey::ArrowLeft) { -1 } else { 1 };
+            if command {
+              ctrl.move_active_only(step);
+            } else {
+              ctrl.move_active(step, e.with_shift_key());
+            }
+          }
+          _ => {
+            ctrl.forget_modifies();
+            return;
+          }
+        }
+        e.stop_propagation();
+      })
+      .into_widget()
+  }
+}
+
+impl<'c, T: Eq + Hash + Clone + 'static> ComposeChild<'c> for Selectable<T> {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    let (controller, item) = {
+      let this = this.read();
+      (this.controller.clone_writer(), this.item.clone())
+    };
+    FatObj::new(child)
+      .on_tap(move |e| {
+        let mut ctrl = controller.write();
+        let item = item.clone();
+        if e.with_shift_key() {
+          ctrl.select_range_to(item, e.with_command_key());
+        } else if e.with_command_key() {
+          ctrl.toggle(item);
+        } else {
+          ctrl.select_only(item);
+        }
+      })
+      .into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::{
+    event::{DeviceId, ElementState, MouseButton, WindowEvent},
+    keyboard::NativeKeyCode,
+  };
+
+  use super::*;
+
+  fn list(selection: Stateful<SelectionController<usize>>) -> impl Fn() -> Widget<'static> {
+    move || {
+      let selection = selection.clone_writer();
+      fn_widget! {
+        @SelectionScope {
+          controller: selection.clone_writer(),
+          @MockStack {
+            @ {
+              (0..5).map(move |i| @Selectable {
+                controller: selection.clone_writer(),
+                item: i,
+                @MockBox {
+                  size: Size::new(100., 20.),
+                  anchor: Anchor::top(i as f32 * 20.),
+                }
+              })
+            }
+          }
+        }
+      }
+      .into_widget()
+    }
+  }
+
+  #[test]
+  fn click_and_keyboard_selection() {
+    reset_test_env!();
+
+    let selection = Stateful::new(SelectionController::new(0..5));
+    let mut wnd = TestWindow::new_with_size(list(selection.clone_writer()), Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let modifiers = |wnd: &TestWindow, modifiers: ModifiersState| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::ModifiersChanged(modifiers.into()));
+    };
+    let click = |wnd: &mut TestWindow, item: usize| {
+      let position = (50., item as f64 * 20. + 10.).into();
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.draw_frame();
+    };
+    let press = |wnd: &mut TestWindow, key: VirtualKey| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+        key,
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.draw_frame();
+    };
+    let assert_selection = |selected: &[usize], anchor: Option<usize>| {
+      let selection = selection.read();
+      let mut actual: Vec<_> = selection.selected().iter().copied().collect();
+      actual.sort();
+      assert_eq!(actual, selected);
+      assert_eq!(selection.anchor().copied(), anchor);
+    };
+
+    click(&mut wnd, 1);
+    assert_selection(&[1], Some(1));
+
+    modifiers(&wnd, ModifiersState::SHIFT);
+    click(&mut wnd, 3);
+    assert_selection(&[1, 2, 3], Some(1));
+    // The range shrinks from the same anchor.
+    click(&mut wnd, 0);
+    assert_selection(&[0, 1], Some(1));
+
+    // Toggling moves the anchor.
+    modifiers(&wnd, ModifiersState::CONTROL);
+    click(&mut wnd, 4);
+    assert_selection(&[0, 1, 4], Some(4));
+    click(&mut wnd, 0);
+    assert_selection(&[1, 4], Some(0));
+
+    // Ctrl + Shift adds the range to the selection.
+    modifiers(&wnd, ModifiersState::CONTROL | ModifiersState::SHIFT);
+    click(&mut wnd, 2);
+    assert_selection(&[0, 1, 2, 4], Some(0));
+
+    modifiers(&wnd, ModifiersState::CONTROL);
+    press(&mut wnd, VirtualKey::Character("a".into()));
+    assert_selection(&[0, 1, 2, 3, 4], Some(0));
+
+    modifiers(&wnd, ModifiersState::empty());
+    press(&mut wnd, VirtualKey::Named(NamedKey::ArrowDown));
+    assert_selection(&[3], Some(3));
+
+    modifiers(&wnd, ModifiersState::SHIFT);
+    press(&mut wnd, VirtualKey::Named(NamedKey::ArrowUp));
+    press(&mut wnd, VirtualKey::Named(NamedKey::ArrowUp));
+    assert_selection(&[1, 2, 3], Some(3));
+    assert_eq!(selection.read().active(), Some(&1));
+
+    // Ctrl moves the active item only, and Ctrl + Space toggles it.
+    modifiers(&wnd, ModifiersState::CONTROL);
+    press(&mut wnd, VirtualKey::Named(NamedKey::ArrowUp));
+    assert_selection(&[1, 2, 3], Some(3));
+    press(&mut wnd, VirtualKey::Named(NamedKey::Space));
+    assert_selection(&[0, 1, 2, 3], Some(0));
+
+    // Clamped at the last item.
+    modifiers(&wnd, ModifiersState::empty());
+    click(&mut wnd, 4);
+    press(&mut wnd, VirtualKey::Named(NamedKey::ArrowDown));
+    assert_selection(&[4], Some(4));
+  }
+}
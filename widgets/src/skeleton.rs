@@ -0,0 +1,402 @@
+use std::{cell::Cell, rc::Rc};
+
+use ribir_core::{prelude::*, ticker::FrameMsg};
+
+use crate::prelude::*;
+
+/// A placeholder shows the shape of the content that is loading, with a
+/// shimmer sweeping across it.
+///
+/// All the skeletons share the same phase of the shimmer, so they pulse
+/// together wherever and whenever they are built. The shimmer only animates
+/// when the skeleton is painted, it stops when the skeleton is offstage.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let _w = fn_widget! {
+///   @Column {
+///     @Skeleton { clamp: BoxClamp::fixed_size(Size::splat(48.)) }
+///     @Skeleton { shape: SkeletonShape::TextLine }
+///     @Skeleton { shape: SkeletonShape::TextLine }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct Skeleton {
+  /// The shape of the placeholder.
+  #[declare(default)]
+  pub shape: SkeletonShape,
+  /// The phase of the shimmer in the range of `0.` to `1.`.
+  #[declare(skip, default = shimmer_phase(clock::now()))]
+  phase: f32,
+}
+
+/// The shape of a [`Skeleton`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SkeletonShape {
+  /// A rounded block that fills the constraints, size it by `clamp`
+  /// as the content it stands for.
+  #[default]
+  Block,
+  /// A line of text, it's as high as a line of the inherited text style and
+  /// as wide as the constraints allow.
+  TextLine,
+}
+
+/// A widget shows a skeleton placeholder until its content is loaded, and
+/// swaps to the content with a crossfade.
+///
+/// The content is built only once and kept while the placeholder is showing,
+/// so its state survives the swap.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let loaded = Stateful::new(false);
+/// let _w = fn_widget! {
+///   @SkeletonGroup {
+///     loaded: pipe!(*$loaded),
+///     placeholder: || skeleton! { shape: SkeletonShape::TextLine }.into_widget(),
+///     @Text { text: "Loaded content" }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct SkeletonGroup {
+  /// Whether the content is loaded, the placeholder shows if it's false.
+  pub loaded: bool,
+  /// The placeholder to show while the content is loading, it's built when
+  /// the content becomes unloaded and disposed after it fades out.
+  pub placeholder: GenWidget,
+}
+
+/// The time the shimmer takes to sweep across a skeleton.
+const SHIMMER_PERIOD: Duration = Duration::from_millis(1500);
+
+thread_local! {
+  static SHIMMER_EPOCH: Instant = clock::now();
+}
+
+/// The phase of the shimmer at the time `now`, it's same for every skeleton
+/// painted at the same time.
+fn shimmer_phase(now: Instant) -> f32 {
+  let elapsed = SHIMMER_EPOCH.with(|epoch| now.saturating_duration_since(*epoch));
+  (elapsed.as_secs_f32() / SHIMMER_PERIOD.as_secs_f32()).fract()
+}
+
+impl Skeleton {
+  /// The phase of the shimmer in the range of `0.` to `1.`.
+  pub fn phase(&self) -> f32 { self.phase }
+}
+
+impl Compose for Skeleton {
+  fn compose(this: impl StateWriter<Value = Self>) -> Widget<'static> {
+    fn_widget! {
+      let palette = Palette::of(BuildCtx::get());
+      let render = @SkeletonRender {
+        shape: distinct_pipe!($this.shape),
+        phase: distinct_pipe!($this.phase),
+        color: palette.surface_container_highest(),
+        highlight: palette.surface_container_low(),
+      };
+      let wnd = BuildCtx::get().window();
+      let ticks = wnd
+        .frame_tick_stream()
+        .subscribe(move |msg| if let FrameMsg::NewFrame(time) = msg {
+          // Keep the shimmer going only if the skeleton was painted in the
+          // last frame.
+          let render = $render;
+          if render.painted.replace(false) {
+            let phase = shimmer_phase(time);
+            if $this.phase != phase {
+              $this.write().phase = phase;
+            }
+          } else if render.running.replace(false) {
+            wnd.dec_running_animate();
+          }
+        });
+
+      @ $render {
+        on_disposed: move |e| {
+          if $render.running.replace(false) {
+            e.window().dec_running_animate();
+          }
+          ticks.unsubscribe();
+        },
+      }
+    }
+    .into_widget()
+  }
+}
+
+#[derive(Declare)]
+struct SkeletonRender {
+  shape: SkeletonShape,
+  phase: f32,
+  color: Color,
+  highlight: Color,
+  /// Whether the skeleton is painted since the last frame.
+  #[declare(skip)]
+  painted: Cell<bool>,
+  /// Whether the skeleton keeps the window drawing frames.
+  #[declare(skip)]
+  running: Cell<bool>,
+}
+
+impl Render for SkeletonRender {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let fill = |max: f32, min: f32| if max.is_finite() { max } else { min };
+    let width = fill(clamp.max.width, clamp.min.width);
+    let height = match self.shape {
+      SkeletonShape::Block => fill(clamp.max.height, clamp.min.height),
+      SkeletonShape::TextLine => ctx.text_style().line_height,
+    };
+    clamp.clamp(Size::new(width, height))
+  }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    self.painted.set(true);
+    if !self.running.replace(true) {
+      ctx.window().inc_running_animate();
+    }
+
+    let size = ctx.box_size().unwrap();
+    let (rect, radius) = match self.shape {
+      SkeletonShape::Block => (Rect::from_size(size), Radius::all(4.)),
+      SkeletonShape::TextLine => {
+        // Leave the gap between lines as the text does.
+        let height = size.height * 0.6;
+        let rect =
+          Rect::new(Point::new(0., (size.height - height) / 2.), Size::new(size.width, height));
+        (rect, Radius::all(height / 2.))
+      }
+    };
+    if rect.is_empty() {
+      return;
+    }
+
+    // The highlight band sweeps from the left outside to the right outside, and
+    // leans by the height to be diagonal.
+    let band = size.width.max(size.height);
+    let x = -band + self.phase * (size.width + band * 2.);
+    let brush = color::LinearGradient {
+      start: Point::new(x - band / 2., 0.),
+      end: Point::new(x + band / 2., size.height),
+      stops: vec![
+        GradientStop::new(self.color, 0.),
+        GradientStop::new(self.highlight, 0.5),
+        GradientStop::new(self.color, 1.),
+      ],
+      spread_method: SpreadMethod::Pad,
+    };
+    ctx
+      .painter()
+      .set_fill_brush(Brush::LinearGradient(brush))
+      .rect_round(&rect, &radius)
+      .fill();
+  }
+}
+
+impl<'c> ComposeChild<'c> for SkeletonGroup {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    fn_widget! {
+      let showing = Stateful::new(!$this.loaded);
+      let mut content = @IgnorePointer {
+        ignore: pipe!(!$this.loaded),
+        opacity: pipe!(if $this.loaded { 1. } else { 0. }),
+      };
+      let mut placeholder = @IgnorePointer {
+        opacity: pipe!(if $this.loaded { 0. } else { 1. }),
+      };
+      content
+        .get_opacity_widget()
+        .map_writer(|w| PartData::from_ref_mut(&mut w.opacity))
+        .transition(transitions::LINEAR.of(BuildCtx::get()));
+      let fade = placeholder
+        .get_opacity_widget()
+        .map_writer(|w| PartData::from_ref_mut(&mut w.opacity))
+        .transition(transitions::LINEAR.of(BuildCtx::get()));
+
+      // Dispose the placeholder after it fades out.
+      let wnd = BuildCtx::get().window();
+      let hide = Rc::new(move || if $this.loaded && !$fade.is_running() && *$showing {
+        *$showing.write() = false;
+      });
+      let c_hide = hide.clone();
+      let u1 = watch!($this.loaded)
+        .distinct_until_changed()
+        .subscribe(move |loaded| if !loaded {
+          *$showing.write() = true;
+        } else {
+          // The placeholder does not fade if the animations are disabled.
+          let hide = hide.clone();
+          wnd.once_frame_finished(move || hide());
+        });
+      let u2 = watch!($fade.is_running())
+        .distinct_until_changed()
+        .filter(|running| !running)
+        .subscribe(move |_| c_hide());
+
+      @Stack {
+        on_disposed: move |_| {
+          u1.unsubscribe();
+          u2.unsubscribe();
+        },
+        @ $content { @ { child } }
+        @ $placeholder {
+          @ {
+            pipe!(*$showing).map(move |showing| {
+              showing.then(|| $this.placeholder.gen_widget())
+            })
+          }
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+
+  use super::*;
+
+  #[test]
+  fn shared_shimmer_phase() {
+    reset_test_env!();
+
+    let skeletons = Stateful::new(vec![]);
+    let show_second = Stateful::new(false);
+    let (c_skeletons, c_show) = (skeletons.clone_writer(), show_second.clone_writer());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let first = @Skeleton { clamp: BoxClamp::fixed_size(Size::new(100., 20.)) };
+        $c_skeletons.write().push(first.clone_writer());
+        @Column {
+          @ { first }
+          @ {
+            pipe!(*$c_show).map(move |show| show.then(|| {
+              let second = @Skeleton { shape: SkeletonShape::TextLine };
+              $c_skeletons.write().push(second.clone_writer());
+              second
+            }))
+          }
+        }
+      },
+      Size::new(200., 100.),
+    );
+    wnd.draw_frame();
+    wnd.advance_time(Duration::from_millis(300));
+    wnd.draw_frame();
+    let phase = skeletons.read()[0].read().phase();
+    assert!(phase > 0.);
+
+    // The second skeleton is built later, but it pulses with the first one.
+    *show_second.write() = true;
+    wnd.draw_frame();
+    for _ in 0..3 {
+      wnd.advance_time(Duration::from_millis(100));
+      wnd.draw_frame();
+      let skeletons = skeletons.read();
+      assert_eq!(skeletons.len(), 2);
+      assert_eq!(skeletons[0].read().phase(), skeletons[1].read().phase());
+    }
+    assert_ne!(skeletons.read()[0].read().phase(), phase);
+  }
+
+  #[test]
+  fn stop_shimmer_offstage() {
+    reset_test_env!();
+
+    let show = Stateful::new(true);
+    let c_show = show.clone_writer();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @Skeleton { clamp: BoxClamp::fixed_size(Size::new(100., 20.)), visible: pipe!(*$c_show) }
+      },
+      Size::new(200., 100.),
+    );
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert!(wnd.need_draw());
+
+    *show.write() = false;
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert!(!wnd.need_draw());
+  }
+
+  fn counted_skeleton(counter: Stateful<i32>) -> Widget<'static> {
+    fn_widget! {
+      @Skeleton {
+        clamp: BoxClamp::fixed_size(Size::new(100., 20.)),
+        on_mounted: move |_| *$counter.write() += 1,
+        on_disposed: move |_| *$counter.write() -= 1,
+      }
+    }
+    .into_widget()
+  }
+
+  #[test]
+  fn crossfade_keeps_content_state() {
+    reset_test_env!();
+
+    let loaded = Stateful::new(false);
+    let content = Stateful::new(None);
+    let (mounted, placeholders) = (Stateful::new(0), Stateful::new(0));
+    let (c_loaded, c_content) = (loaded.clone_writer(), content.clone_writer());
+    let (c_mounted, c_placeholders) = (mounted.clone_writer(), placeholders.clone_writer());
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        let c_placeholders = c_placeholders.clone_writer();
+        @SkeletonGroup {
+          loaded: pipe!(*$c_loaded),
+          placeholder: move || counted_skeleton(c_placeholders.clone_writer()),
+          @ {
+            // The state of the content.
+            let count = Stateful::new(0);
+            *$c_content.write() = Some(count.clone_writer());
+            @Text {
+              text: pipe!($count.to_string()),
+              on_mounted: move |_| *$c_mounted.write() += 1,
+            }
+          }
+        }
+      },
+      Size::new(200., 100.),
+    );
+    wnd.draw_frame();
+    assert_eq!(*placeholders.read(), 1);
+    let count = content.write().take().unwrap();
+    *count.write() = 1;
+    wnd.draw_frame();
+
+    *loaded.write() = true;
+    wnd.draw_frame();
+    // The placeholder fades out with the content fading in.
+    assert_eq!(*placeholders.read(), 1);
+    for _ in 0..10 {
+      wnd.advance_time(Duration::from_millis(100));
+      wnd.draw_frame();
+    }
+    assert_eq!(*placeholders.read(), 0);
+
+    // The content is not rebuilt, and keeps its state.
+    assert_eq!(*mounted.read(), 1);
+    assert!(content.read().is_none());
+    assert_eq!(*count.read(), 1);
+
+    *loaded.write() = false;
+    wnd.draw_frame();
+    assert_eq!(*placeholders.read(), 1);
+    assert_eq!(*mounted.read(), 1);
+  }
+}
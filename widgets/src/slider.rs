@@ -126,26 +126,28 @@ impl Compose for Slider {
             flex: pipe!($this.ratio()),
             @Void { class: SLIDER_ACTIVE_TRACK }
           }
-          @Void {
-            class: SLIDER_INDICATOR ,
-            on_tap: move |e| e.stop_propagation(),
-            on_pointer_down: move |e| {
-              if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
-                *$drag_info.write() = Some((handle, e.global_pos().x, $this.ratio()));
-              }
-            },
-            on_pointer_move: move|e| if let Some((_, pos, ratio)) = $drag_info.as_ref() {
-              let width = $row.layout_size().width;
-              let val = ratio + (e.global_pos().x - pos) / width;
-              $this.write().set_to(val);
-            },
-            on_pointer_up: move |_| {
-              $drag_info.write().take();
-            },
-            tooltips: pipe!($this.value).map(move |v| {
-              let precision = precision($this.min, $this.max);
-              format!("{:.1$}", v, precision)
-            }),
+          @TouchTarget {
+            @Void {
+              class: SLIDER_INDICATOR ,
+              on_tap: move |e| e.stop_propagation(),
+              on_pointer_down: move |e| {
+                if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
+                  *$drag_info.write() = Some((handle, e.global_pos().x, $this.ratio()));
+                }
+              },
+              on_pointer_move: move|e| if let Some((_, pos, ratio)) = $drag_info.as_ref() {
+                let width = $row.layout_size().width;
+                let val = ratio + (e.global_pos().x - pos) / width;
+                $this.write().set_to(val);
+              },
+              on_pointer_up: move |_| {
+                $drag_info.write().take();
+              },
+              tooltips: pipe!($this.value).map(move |v| {
+                let precision = precision($this.min, $this.max);
+                format!("{:.1$}", v, precision)
+              }),
+            }
           }
           @Expanded {
             flex: pipe!($this.ratio()).map(|v| 1. - v),
@@ -292,51 +294,55 @@ impl Compose for RangeSlider {
             flex: pipe!($this.start_ratio()),
             @Void { class: RANGE_SLIDER_INACTIVE_TRACK_LEFT }
           }
-          @Void {
-            class: SLIDER_INDICATOR,
-            tooltips: pipe!($this.start).map(move |v| {
-              let precision = precision($this.min, $this.max);
-              format!("{:.1$}", v, precision)
-            }),
-            on_tap: move |e| e.stop_propagation(),
-            on_pointer_down: move |e| {
-              if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
-                *$drag_info1.write() = Some((handle, e.global_pos().x, $this.start_ratio()));
-              }
-            },
-            on_pointer_move: move |e| {
-              if let Some((_, pos, ratio)) = $drag_info1.as_ref() {
-                let width = $row.layout_size().width;
-                let val = ratio + (e.global_pos().x - pos) / width;
-                $this.write().set_start_ratio(val);
-              }
-            },
-            on_pointer_up: move |_| { $drag_info1.write().take(); }
+          @TouchTarget {
+            @Void {
+              class: SLIDER_INDICATOR,
+              tooltips: pipe!($this.start).map(move |v| {
+                let precision = precision($this.min, $this.max);
+                format!("{:.1$}", v, precision)
+              }),
+              on_tap: move |e| e.stop_propagation(),
+              on_pointer_down: move |e| {
+                if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
+                  *$drag_info1.write() = Some((handle, e.global_pos().x, $this.start_ratio()));
+                }
+              },
+              on_pointer_move: move |e| {
+                if let Some((_, pos, ratio)) = $drag_info1.as_ref() {
+                  let width = $row.layout_size().width;
+                  let val = ratio + (e.global_pos().x - pos) / width;
+                  $this.write().set_start_ratio(val);
+                }
+              },
+              on_pointer_up: move |_| { $drag_info1.write().take(); }
+            }
           }
           @Expanded {
             flex: pipe!($this.end_ratio() - $this.start_ratio()),
             @Void { class: RANGE_SLIDER_ACTIVE_TRACK }
           }
-          @Void {
-            class: SLIDER_INDICATOR,
-            tooltips: pipe!($this.end).map(move |v| {
-              let precision = precision($this.min, $this.max);
-              format!("{:.1$}", v, precision)
-            }),
-            on_tap: move |e| e.stop_propagation(),
-            on_pointer_down: move |e| {
-              if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
-                *$drag_info2.write() = Some((handle, e.global_pos().x, $this.end_ratio()));
-              }
-            },
-            on_pointer_move: move |e| {
-              if let Some((_, pos, ratio)) = $drag_info2.as_ref() {
-                let width = $row.layout_size().width;
-                let val = ratio + (e.global_pos().x - pos) / width;
-                $this.write().set_end_ratio(val);
-              }
-            },
-            on_pointer_up: move |_| { $drag_info2.write().take(); }
+          @TouchTarget {
+            @Void {
+              class: SLIDER_INDICATOR,
+              tooltips: pipe!($this.end).map(move |v| {
+                let precision = precision($this.min, $this.max);
+                format!("{:.1$}", v, precision)
+              }),
+              on_tap: move |e| e.stop_propagation(),
+              on_pointer_down: move |e| {
+                if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
+                  *$drag_info2.write() = Some((handle, e.global_pos().x, $this.end_ratio()));
+                }
+              },
+              on_pointer_move: move |e| {
+                if let Some((_, pos, ratio)) = $drag_info2.as_ref() {
+                  let width = $row.layout_size().width;
+                  let val = ratio + (e.global_pos().x - pos) / width;
+                  $this.write().set_end_ratio(val);
+                }
+              },
+              on_pointer_up: move |_| { $drag_info2.write().take(); }
+            }
           }
           @Expanded {
             flex: pipe!($this.end_ratio()).map(|v| 1. - v),
@@ -0,0 +1,408 @@
+use ribir_core::prelude::*;
+
+use crate::prelude::*;
+
+class_names! {
+  #[doc = "Class name for the divider between the two panes of a split pane"]
+  SPLIT_PANE_DIVIDER,
+}
+
+/// A widget that splits its space into two resizable panes, the first pane is
+/// the `Leading` child and the second pane is the `Trailing` child.
+///
+/// Drag the divider between the panes to resize them, or focus the divider and
+/// use the arrow keys. Double-click the divider to reset it to the initial
+/// position.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let _w = fn_widget! {
+///   @SplitPane {
+///     position: 0.3,
+///     first_min: 100.,
+///     collapse_threshold: 40.,
+///     @Leading::new(@Text { text: "Explorer" })
+///     @Trailing::new(@Text { text: "Editor" })
+///   }
+/// };
+/// ```
+#[derive(Declare, Clone, Copy, PartialEq, Debug)]
+pub struct SplitPane {
+  /// Place the panes side by side if horizontal, or one above the other if
+  /// vertical.
+  #[declare(default)]
+  pub direction: Direction,
+  /// The size of the first pane, as a ratio of the space of the two panes or
+  /// as pixels, depending on the `unit`. It is updated when the divider is
+  /// moved.
+  ///
+  /// A position of zero or of the whole space collapses a pane, and the size
+  /// limits of the panes are ignored.
+  pub position: f32,
+  /// The unit of the `position`.
+  #[declare(default)]
+  pub unit: SplitUnit,
+  /// The minimum size of the first pane in pixels.
+  #[declare(default)]
+  pub first_min: f32,
+  /// The maximum size of the first pane in pixels.
+  #[declare(default = f32::INFINITY)]
+  pub first_max: f32,
+  /// The minimum size of the second pane in pixels.
+  #[declare(default)]
+  pub second_min: f32,
+  /// The maximum size of the second pane in pixels.
+  #[declare(default = f32::INFINITY)]
+  pub second_max: f32,
+  /// A pane snaps closed when the divider is moved to make it smaller than
+  /// this size in pixels, zero means never collapse.
+  #[declare(default)]
+  pub collapse_threshold: f32,
+  /// The thickness of the divider in pixels.
+  #[declare(default = 4.)]
+  pub divider_thickness: f32,
+  /// How many pixels an arrow key moves the focused divider.
+  #[declare(default = 10.)]
+  pub keyboard_step: f32,
+}
+
+/// The unit of [`SplitPane::position`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SplitUnit {
+  /// The first pane keeps a ratio of the space when the split pane resizes.
+  #[default]
+  Ratio,
+  /// The first pane keeps its size in pixels when the split pane resizes.
+  Pixel,
+}
+
+/// The children of a [`SplitPane`].
+#[derive(Template)]
+pub struct SplitPaneChild<'w> {
+  first: Leading<Widget<'w>>,
+  second: Trailing<Widget<'w>>,
+}
+
+impl SplitPane {
+  /// The size of the first pane in pixels, when the two panes share `space`
+  /// pixels.
+  pub fn first_extent(&self, space: f32) -> f32 {
+    let extent = match self.unit {
+      SplitUnit::Ratio => self.position * space,
+      SplitUnit::Pixel => self.position,
+    };
+    if extent <= 0. {
+      0.
+    } else if extent >= space {
+      space
+    } else {
+      self.clamp_extent(extent, space)
+    }
+  }
+
+  /// Move the divider to make the first pane `extent` pixels, the extent is
+  /// limited by the size limits of the panes, and a pane collapses if it
+  /// becomes smaller than the collapse threshold.
+  pub fn resize_first(&mut self, extent: f32, space: f32) {
+    let threshold = self.collapse_threshold;
+    let extent = if extent < threshold {
+      0.
+    } else if space - extent < threshold {
+      space
+    } else {
+      self.clamp_extent(extent, space)
+    };
+    self.position = match self.unit {
+      SplitUnit::Ratio if space > 0. => extent / space,
+      SplitUnit::Ratio => 0.,
+      SplitUnit::Pixel => extent,
+    };
+  }
+
+  fn clamp_extent(&self, extent: f32, space: f32) -> f32 {
+    let min = self.first_min.max(space - self.second_max);
+    let max = self.first_max.min(space - self.second_min);
+    extent.min(max).max(min).clamp(0., space)
+  }
+
+  /// The space of the two panes when the split pane is `size`.
+  fn space(&self, size: Size) -> f32 {
+    let main = self.main(size.to_tuple());
+    main - self.divider_thickness.min(main)
+  }
+
+  fn main(&self, (x, y): (f32, f32)) -> f32 {
+    match self.direction {
+      Direction::Horizontal => x,
+      Direction::Vertical => y,
+    }
+  }
+
+  fn step_key(&self, key: &VirtualKey) -> Option<f32> {
+    let (back, forward) = match self.direction {
+      Direction::Horizontal => (NamedKey::ArrowLeft, NamedKey::ArrowRight),
+      Direction::Vertical => (NamedKey::ArrowUp, NamedKey::ArrowDown),
+    };
+    match key {
+      VirtualKey::Named(k) if *k == back => Some(-self.keyboard_step),
+      VirtualKey::Named(k) if *k == forward => Some(self.keyboard_step),
+      _ => None,
+    }
+  }
+}
+
+impl<'c> ComposeChild<'c> for SplitPane {
+  type Child = SplitPaneChild<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    let SplitPaneChild { first, second } = child;
+    let init_position = this.read().position;
+    fn_widget! {
+      let mut layout = @SplitLayout { pane: pipe!(*$this) };
+      let drag: Stateful<Option<(GrabPointer, f32, f32)>> = Stateful::new(None);
+      let divider = @Void {
+        class: SPLIT_PANE_DIVIDER,
+        tab_index: 0_i16,
+        cursor: pipe!($this.direction).map(|d| match d {
+          Direction::Horizontal => CursorIcon::ColResize,
+          Direction::Vertical => CursorIcon::RowResize,
+        }),
+        on_pointer_down: move |e| {
+          if let Some(handle) = GrabPointer::grab(e.current_target(), &e.window()) {
+            let pane = $this;
+            let start = pane.main(e.global_pos().to_tuple());
+            let extent = pane.first_extent(pane.space($layout.layout_size()));
+            *$drag.write() = Some((handle, start, extent));
+          }
+        },
+        on_pointer_move: move |e| if let Some((_, start, extent)) = $drag.as_ref() {
+          let pos = $this.main(e.global_pos().to_tuple());
+          let space = $this.space($layout.layout_size());
+          $this.write().resize_first(extent + pos - start, space);
+        },
+        on_pointer_up: move |_| {
+          $drag.write().take();
+        },
+        on_double_tap: move |_| $this.write().position = init_position,
+        on_key_down: move |e| {
+          let Some(step) = $this.step_key(e.key()) else { return };
+          let space = $this.space($layout.layout_size());
+          let extent = $this.first_extent(space);
+          $this.write().resize_first(extent + step, space);
+        },
+      };
+
+      @ $layout {
+        @ { first.unwrap() }
+        @ { divider }
+        @ { second.unwrap() }
+      }
+    }
+    .into_widget()
+  }
+}
+
+/// Lays out its three children, the first pane, the divider and the second
+/// pane, along the direction of the split pane.
+#[derive(Declare, MultiChild)]
+struct SplitLayout {
+  pane: SplitPane,
+}
+
+impl Render for SplitLayout {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let pane = &self.pane;
+    let horizontal = pane.direction == Direction::Horizontal;
+    let size = if clamp.max.is_finite() { clamp.max } else { clamp.min };
+    let cross = if horizontal { size.height } else { size.width };
+    let space = pane.space(size);
+    let thickness = pane.main(size.to_tuple()) - space;
+    let first = pane.first_extent(space);
+
+    let (ctx, children) = ctx.split_children();
+    let mut offset = 0.;
+    for (child, extent) in children.zip([first, thickness, space - first]) {
+      let (child_size, pos) = if horizontal {
+        (Size::new(extent, cross), Point::new(offset, 0.))
+      } else {
+        (Size::new(cross, extent), Point::new(0., offset))
+      };
+      ctx.perform_child_layout(child, BoxClamp { min: child_size, max: child_size });
+      ctx.update_position(child, pos);
+      offset += extent;
+    }
+
+    size
+  }
+
+  fn only_sized_by_parent(&self) -> bool { true }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+
+  fn half() -> SplitPane {
+    SplitPane {
+      direction: Direction::Horizontal,
+      position: 0.5,
+      unit: SplitUnit::Ratio,
+      first_min: 0.,
+      first_max: f32::INFINITY,
+      second_min: 0.,
+      second_max: f32::INFINITY,
+      collapse_threshold: 0.,
+      divider_thickness: 4.,
+      keyboard_step: 10.,
+    }
+  }
+
+  fn split_window(pane: SplitPane) -> TestWindow {
+    let w = fn_widget! {
+      let pane = Stateful::new(pane);
+      @ $pane {
+        @Leading::new(@Void {})
+        @Trailing::new(@Void {})
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(404., 100.));
+    wnd.draw_frame();
+    wnd
+  }
+
+  fn first_width(wnd: &TestWindow) -> f32 {
+    wnd
+      .layout_info_by_path(&[0, 0])
+      .unwrap()
+      .size
+      .unwrap()
+      .width
+  }
+
+  fn second_width(wnd: &TestWindow) -> f32 {
+    wnd
+      .layout_info_by_path(&[0, 2])
+      .unwrap()
+      .size
+      .unwrap()
+      .width
+  }
+
+  fn drag(wnd: &mut TestWindow, from: f32, to: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: (from as f64, 50.).into(),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    // Let the divider grab the pointer before it moves.
+    wnd.run_frame_tasks();
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: (to as f64, 50.).into(),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn drag_divider() {
+    reset_test_env!();
+
+    let mut wnd = split_window(half());
+    assert_eq!(first_width(&wnd), 200.);
+    assert_eq!(second_width(&wnd), 200.);
+
+    drag(&mut wnd, 202., 102.);
+    assert_eq!(first_width(&wnd), 100.);
+    assert_eq!(second_width(&wnd), 300.);
+
+    let mut wnd = split_window(SplitPane { position: 120., unit: SplitUnit::Pixel, ..half() });
+    assert_eq!(first_width(&wnd), 120.);
+    drag(&mut wnd, 122., 152.);
+    assert_eq!(first_width(&wnd), 150.);
+  }
+
+  #[test]
+  fn clamp_by_size_limits() {
+    reset_test_env!();
+
+    let mut wnd =
+      split_window(SplitPane { first_min: 80., first_max: 300., second_min: 150., ..half() });
+    drag(&mut wnd, 202., 12.);
+    assert_eq!(first_width(&wnd), 80.);
+
+    drag(&mut wnd, 82., 392.);
+    assert_eq!(first_width(&wnd), 250.);
+    assert_eq!(second_width(&wnd), 150.);
+  }
+
+  #[test]
+  fn snap_closed() {
+    reset_test_env!();
+
+    let mut wnd = split_window(SplitPane {
+      first_min: 100.,
+      second_min: 100.,
+      collapse_threshold: 50.,
+      ..half()
+    });
+    // Between the threshold and the min size, the pane keeps its min size.
+    drag(&mut wnd, 202., 62.);
+    assert_eq!(first_width(&wnd), 100.);
+
+    drag(&mut wnd, 102., 42.);
+    assert_eq!(first_width(&wnd), 0.);
+    assert_eq!(second_width(&wnd), 400.);
+
+    // Collapse the second pane.
+    drag(&mut wnd, 2., 370.);
+    assert_eq!(first_width(&wnd), 400.);
+    assert_eq!(second_width(&wnd), 0.);
+  }
+
+  #[test]
+  fn keyboard_and_reset() {
+    reset_test_env!();
+
+    let mut wnd = split_window(SplitPane { position: 200., unit: SplitUnit::Pixel, ..half() });
+    wnd.request_next_focus();
+    let key = |wnd: &mut TestWindow, code: KeyCode, key: NamedKey| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(code),
+        VirtualKey::Named(key),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.draw_frame();
+    };
+    key(&mut wnd, KeyCode::ArrowRight, NamedKey::ArrowRight);
+    assert_eq!(first_width(&wnd), 210.);
+    key(&mut wnd, KeyCode::ArrowLeft, NamedKey::ArrowLeft);
+    key(&mut wnd, KeyCode::ArrowLeft, NamedKey::ArrowLeft);
+    assert_eq!(first_width(&wnd), 190.);
+    // Keys of the other direction are ignored.
+    key(&mut wnd, KeyCode::ArrowDown, NamedKey::ArrowDown);
+    assert_eq!(first_width(&wnd), 190.);
+
+    drag(&mut wnd, 192., 302.);
+    assert_eq!(first_width(&wnd), 300.);
+
+    let device_id = unsafe { DeviceId::dummy() };
+    for _ in 0..2 {
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    }
+    wnd.draw_frame();
+    assert_eq!(first_width(&wnd), 200.);
+  }
+}
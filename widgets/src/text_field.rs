@@ -1,14 +1,46 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{collections::HashMap, hash::Hash, sync::Arc};
 
 use ribir_core::prelude::*;
 
 use crate::prelude::*;
 
-#[derive(Declare, Default)]
+#[derive(Declare, Clone)]
 pub struct TextField {
   /// textfield's input value
   #[declare(skip)]
   text: CowArc<str>,
+  /// Check the spelling of the input value, see [`SpellChecker`].
+  #[declare(custom, default)]
+  spell_check: Option<Arc<dyn SpellChecker>>,
+  /// Obscure the input value, for a password. See [`Input::obscure_text`].
+  #[declare(default)]
+  pub obscure_text: bool,
+  /// The character that the obscured input value is painted with.
+  #[declare(default = '•')]
+  pub obscuring_char: char,
+  /// How long the last typed character is shown before it's obscured, see
+  /// [`Input::reveal_last_char`].
+  #[declare(default)]
+  pub reveal_last_char: Option<Duration>,
+}
+
+impl Default for TextField {
+  fn default() -> Self {
+    Self {
+      text: CowArc::default(),
+      spell_check: None,
+      obscure_text: false,
+      obscuring_char: '•',
+      reveal_last_char: None,
+    }
+  }
+}
+
+impl SpellCheckDeclarerExtend for FatObj<TextFieldDeclarer> {
+  fn spell_check(mut self, checker: Option<Arc<dyn SpellChecker>>) -> Self {
+    self.spell_check = Some(DeclareInit::Value(checker));
+    self
+  }
 }
 
 impl TextField {
@@ -41,6 +73,23 @@ pub struct TextFieldTml<'w> {
 
   /// An icon that appears after the editable part of the text field
   trailing_icon: Option<Trailing<Widget<'w>>>,
+
+  /// A button after the editable part of the text field that reveals or
+  /// obscures the input value when it's tapped.
+  reveal_toggle: Option<RevealToggle<Widget<'w>>>,
+}
+
+/// The child of a [`TextField`] that toggles its `obscure_text` when it's
+/// tapped, such as an eye icon.
+#[derive(ChildOfCompose)]
+pub struct RevealToggle<T>(T);
+
+impl<T> RevealToggle<T> {
+  pub fn new<const M: usize>(child: impl IntoChildCompose<T, M>) -> Self {
+    RevealToggle(child.into_child_compose())
+  }
+
+  pub fn unwrap(self) -> T { self.0 }
 }
 
 #[derive(Clone)]
@@ -189,9 +238,9 @@ impl TextFieldThemeSuit {
 
     let mut themes = HashMap::new();
 
-    let input_expand_padding = EdgeInsets { left: 16., right: 16., bottom: 16., top: 16. };
+    let input_expand_padding = EdgeInsets::all(16.);
 
-    let input_collapse_padding = EdgeInsets { left: 16., right: 16., bottom: 8., top: 8. };
+    let input_collapse_padding = EdgeInsets::symmetrical(8., 16.);
 
     themes.insert(TextFieldState::Enabled, TextFieldTheme {
       text_brush: palette.on_surface().into(),
@@ -269,7 +318,21 @@ impl<'c> ComposeChild<'c> for TextField {
   fn compose_child(this: impl StateWriter<Value = Self>, config: Self::Child) -> Widget<'c> {
     fn_widget! {
       let mut config = config.unwrap_or_default();
-      take_option_field!({leading_icon, trailing_icon}, config);
+      take_option_field!({leading_icon, trailing_icon, reveal_toggle}, config);
+      let reveal_toggle = reveal_toggle.map(|t| {
+        let this = this.clone_writer();
+        @Icon {
+          cursor: CursorIcon::Pointer,
+          semantics_label: pipe!(
+            if $this.obscure_text { "show password" } else { "hide password" }
+          ),
+          on_tap: move |_| {
+            let mut this = $this.write();
+            this.obscure_text = !this.obscure_text;
+          },
+          @{ t.unwrap() }
+        }
+      });
 
       let ctx = BuildCtx::get();
       let theme_suit = TextFieldThemeSuit::of(ctx);
@@ -299,6 +362,7 @@ impl<'c> ComposeChild<'c> for TextField {
           @{
             trailing_icon.map(|t| @Icon { @{ t.unwrap() } })
           }
+          @{ reveal_toggle }
         }
         @Container {
           v_align: VAlign::Bottom,
@@ -324,7 +388,13 @@ fn build_input_area(
       .map_writer(|w| PartData::from_ref(&w.visible))
       .transition(transitions::LINEAR.of(BuildCtx::get()));
 
-    let mut input = @Input{ style: pipe!($theme.text.clone()) };
+    let mut input = @Input {
+      style: pipe!($theme.text.clone()),
+      spell_check: $this.spell_check.clone(),
+      obscure_text: pipe!($this.obscure_text),
+      obscuring_char: pipe!($this.obscuring_char),
+      reveal_last_char: pipe!($this.reveal_last_char),
+    };
     $input.write().set_text(&$this.text);
 
     watch!($input.text().clone())
@@ -422,3 +492,108 @@ fn build_content_area(
   }
   .into_widget()
 }
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, rc::Rc};
+
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+
+  #[test]
+  fn edit_in_transaction() {
+    reset_test_env!();
+
+    let field = Stateful::new(TextField::default());
+    field.write().set_text("origin".into());
+    AppCtx::run_until_stalled();
+    let notified = Rc::new(Cell::new(0));
+    let c_notified = notified.clone();
+    field
+      .modifies()
+      .subscribe(move |_| c_notified.set(c_notified.get() + 1));
+
+    let edit = |tx: &Transaction<TextField>, chars: &str| {
+      let editing = tx.clone_writer();
+      let mut wnd =
+        TestWindow::new_with_size(fn_widget! { editing.clone_writer() }, Size::new(200., 100.));
+      wnd.draw_frame();
+      let device_id = unsafe { DeviceId::dummy() };
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: (18., 20.).into(),
+      });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.draw_frame();
+      wnd.processes_receive_chars(chars.to_string());
+      wnd.draw_frame();
+    };
+
+    let tx = field.begin_transaction();
+    // The caret is at the start, focused by tapping the start of the field.
+    edit(&tx, "edited ");
+    assert_eq!(&*tx.read().text(), "edited origin");
+    tx.rollback();
+    AppCtx::run_until_stalled();
+    assert_eq!(&*field.read().text(), "origin");
+    assert_eq!(notified.get(), 0);
+
+    let tx = field.begin_transaction();
+    edit(&tx, "committed ");
+    tx.commit();
+    AppCtx::run_until_stalled();
+    assert_eq!(&*field.read().text(), "committed origin");
+    assert_eq!(notified.get(), 1);
+  }
+
+  #[test]
+  fn prevent_typing_by_key_down() {
+    reset_test_env!();
+
+    let field = Stateful::new(TextField::default());
+    let c_field = field.clone_writer();
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @MockMulti {
+          on_key_down: move |e| if e.key() == &VirtualKey::Character("x".into()) {
+            e.prevent_default();
+          },
+          @ { c_field.clone_writer() }
+        }
+      },
+      Size::new(200., 100.),
+    );
+    wnd.draw_frame();
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (18., 20.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+
+    let type_key = |wnd: &mut TestWindow, code: KeyCode, c: &str| {
+      let key = |state| {
+        let key = VirtualKey::Character(c.into());
+        wnd.processes_keyboard_event(
+          PhysicalKey::Code(code),
+          key,
+          false,
+          KeyLocation::Standard,
+          state,
+        );
+      };
+      key(ElementState::Pressed);
+      wnd.processes_receive_chars(c.to_string());
+      key(ElementState::Released);
+      wnd.draw_frame();
+    };
+    type_key(&mut wnd, KeyCode::KeyX, "x");
+    assert_eq!(&*field.read().text(), "");
+    type_key(&mut wnd, KeyCode::KeyA, "a");
+    assert_eq!(&*field.read().text(), "a");
+  }
+}
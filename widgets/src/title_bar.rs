@@ -0,0 +1,233 @@
+use ribir_core::prelude::*;
+
+use crate::prelude::*;
+
+class_names! {
+  #[doc = "Class name for the title bar"]
+  TITLE_BAR,
+  #[doc = "Class name for the caption buttons of the title bar"]
+  TITLE_BAR_BUTTON,
+  #[doc = "Class name for the close button of the title bar, instead of `TITLE_BAR_BUTTON`"]
+  TITLE_BAR_CLOSE,
+}
+
+/// A title bar for a window without decorations, see
+/// `WindowAttributes::with_decorations`.
+///
+/// Its child is the content of the title bar, such as the title or a search
+/// box, and it's followed by the minimize, maximize and close buttons.
+///
+/// Drag the title bar to move the window, and double-click it to maximize or
+/// restore the window. The interactive children, the ones that listen to the
+/// pointer events or can be focused, take the pointer before the title bar, so
+/// pressing them does not move the window. The window cannot be moved by the
+/// title bar when it's maximized.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// let _w = fn_widget! {
+///   @TitleBar {
+///     maximizable: false,
+///     @Text { text: "My App" }
+///   }
+/// };
+/// ```
+#[derive(Declare, Clone, Copy, PartialEq, Debug)]
+pub struct TitleBar {
+  /// Whether to show the minimize button.
+  #[declare(default = true)]
+  pub minimizable: bool,
+  /// Whether to show the maximize button, and to maximize the window by
+  /// double-clicking the title bar.
+  #[declare(default = true)]
+  pub maximizable: bool,
+  /// Whether to show the close button.
+  #[declare(default = true)]
+  pub closable: bool,
+}
+
+impl<'c> ComposeChild<'c> for TitleBar {
+  type Child = Widget<'c>;
+
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> Widget<'c> {
+    fn_widget! {
+      let wnd = BuildCtx::get().window();
+      let media = BuildCtx::get().media();
+      let maximized = Stateful::new(wnd.is_maximized());
+      // The window resizes when it's maximized or restored by the system.
+      let u = watch!($media.size).subscribe(move |_| {
+        let is_maximized = wnd.is_maximized();
+        if *$maximized != is_maximized {
+          *$maximized.write() = is_maximized;
+        }
+      });
+      let (tap_max, double_tap_max) = (maximized.clone_writer(), maximized.clone_writer());
+
+      @Row {
+        class: TITLE_BAR,
+        align_items: Align::Center,
+        on_pointer_down: move |e| {
+          if !*$maximized && on_drag_area(e) {
+            e.window().begin_move_drag();
+          }
+        },
+        on_double_tap: move |e| {
+          if $this.maximizable && on_drag_area(e) {
+            toggle_maximize(&double_tap_max, &e.window());
+          }
+        },
+        on_disposed: move |_| u.unsubscribe(),
+        @Expanded { @ { child } }
+        @Icon {
+          class: TITLE_BAR_BUTTON,
+          visible: pipe!($this.minimizable),
+          on_tap: move |e| { e.window().set_minimized(true); },
+          @ { svgs::MINIMIZE }
+        }
+        @Icon {
+          class: TITLE_BAR_BUTTON,
+          visible: pipe!($this.maximizable),
+          on_tap: move |e| toggle_maximize(&tap_max, &e.window()),
+          @ {
+            pipe!(*$maximized).map(|maximized| {
+              if maximized { svgs::RESTORE } else { svgs::MAXIMIZE }
+            })
+          }
+        }
+        @Icon {
+          class: TITLE_BAR_CLOSE,
+          visible: pipe!($this.closable),
+          on_tap: move |e| e.window().request_close(),
+          @ { svgs::CLOSE }
+        }
+      }
+    }
+    .into_widget()
+  }
+}
+
+fn toggle_maximize(maximized: &Stateful<bool>, wnd: &Window) {
+  let is_maximized = !*maximized.read();
+  wnd.set_maximized(is_maximized);
+  *maximized.write() = is_maximized;
+}
+
+/// Whether the pointer of the event is pressed on the title bar itself, not on
+/// an interactive child of it.
+fn on_drag_area(e: &CommonEvent) -> bool {
+  let bar = e.current_target();
+  let mut id = e.target();
+  while id != bar {
+    let interactive = e.query_all_of_widget::<MixBuiltin>(id).any(|m| {
+      m.mix_flags()
+        .read()
+        .intersects(MixFlags::Pointer | MixFlags::Focus)
+    });
+    // The target may be dropped by a handler before the event bubbles to the
+    // title bar, it's not in the drag area then.
+    match e.widget_parent(id) {
+      Some(parent) if !interactive => id = parent,
+      _ => return false,
+    }
+  }
+  true
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, ElementState, MouseButton, WindowEvent},
+  };
+
+  use super::*;
+
+  fn shell(wnd: &TestWindow) -> std::cell::RefMut<'_, TestShellWindow> {
+    std::cell::RefMut::map(wnd.shell_wnd().borrow_mut(), |w| {
+      w.as_any_mut()
+        .downcast_mut::<TestShellWindow>()
+        .unwrap()
+    })
+  }
+
+  fn click_at(wnd: &mut TestWindow, x: f32, y: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved {
+      device_id,
+      position: LogicalPosition::new(x, y).to_physical(1.),
+    });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+  }
+
+  fn title_bar_wnd() -> TestWindow {
+    let mut wnd = TestWindow::new_with_size(
+      fn_widget! {
+        @TitleBar {
+          @Row {
+            @Text { text: "Title" }
+            @MockBox {
+              size: Size::new(40., 20.),
+              on_tap: move |_| {},
+            }
+          }
+        }
+      },
+      Size::new(400., 100.),
+    );
+    wnd.draw_frame();
+    wnd
+  }
+
+  #[test]
+  fn caption_buttons() {
+    reset_test_env!();
+
+    let mut wnd = title_bar_wnd();
+    // The minimize, maximize and close buttons are at the end of the bar.
+    click_at(&mut wnd, 350., 10.);
+    assert!(shell(&wnd).minimized);
+
+    click_at(&mut wnd, 370., 10.);
+    assert!(shell(&wnd).maximized);
+    // The buttons do not move the window.
+    assert!(shell(&wnd).drags.is_empty());
+
+    // A maximized window cannot be moved.
+    click_at(&mut wnd, 200., 10.);
+    assert!(shell(&wnd).drags.is_empty());
+
+    click_at(&mut wnd, 370., 10.);
+    assert!(!shell(&wnd).maximized);
+
+    click_at(&mut wnd, 390., 10.);
+    assert!(shell(&wnd).close_requested);
+  }
+
+  #[test]
+  fn drag_area() {
+    reset_test_env!();
+
+    let mut wnd = title_bar_wnd();
+    // The title text is a part of the drag area.
+    click_at(&mut wnd, 10., 10.);
+    assert_eq!(shell(&wnd).drags, [WindowDrag::Move]);
+
+    // The interactive child takes the pointer.
+    click_at(&mut wnd, 50., 10.);
+    click_at(&mut wnd, 50., 10.);
+    assert_eq!(shell(&wnd).drags, [WindowDrag::Move]);
+    assert!(!shell(&wnd).maximized);
+
+    // Double-click the drag area to maximize the window.
+    click_at(&mut wnd, 200., 10.);
+    click_at(&mut wnd, 200., 10.);
+    assert!(shell(&wnd).maximized);
+  }
+}